@@ -0,0 +1,8 @@
+-- Tracks uncommitted edits made in an attempt worktree while no agent process was running
+CREATE TABLE attempt_manual_changes (
+    task_attempt_id BLOB PRIMARY KEY,
+    changed_paths   TEXT NOT NULL DEFAULT '[]', -- JSON array of worktree-relative paths
+    detected_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
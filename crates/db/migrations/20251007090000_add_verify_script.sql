@@ -0,0 +1,40 @@
+-- Optional per-project script run after the coding agent (and cleanup script); a task only
+-- moves to review when it exits 0.
+ALTER TABLE projects ADD COLUMN verify_script TEXT;
+
+-- Widen the run_reason CHECK to allow 'verifyscript'
+ALTER TABLE execution_processes
+  ADD COLUMN run_reason_new TEXT NOT NULL DEFAULT 'setupscript'
+    CHECK (run_reason_new IN ('setupscript',
+                              'cleanupscript',
+                              'codingagent',
+                              'devserver',
+                              'verifyscript'));
+
+UPDATE execution_processes
+  SET run_reason_new = run_reason;
+
+DROP INDEX IF EXISTS idx_execution_processes_type;
+
+ALTER TABLE execution_processes DROP COLUMN run_reason;
+
+ALTER TABLE execution_processes
+  RENAME COLUMN run_reason_new TO run_reason;
+
+CREATE INDEX idx_execution_processes_type
+        ON execution_processes(run_reason);
+
+-- Outcome of each verify script run
+CREATE TABLE attempt_verifications (
+    id                   BLOB PRIMARY KEY,
+    task_attempt_id      BLOB NOT NULL,
+    execution_process_id BLOB NOT NULL,
+    passed               BOOLEAN NOT NULL,
+    exit_code            INTEGER,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_attempt_verifications_task_attempt_created_at
+    ON attempt_verifications (task_attempt_id, created_at DESC);
@@ -0,0 +1,4 @@
+-- Per-project tuning for the worktree diff watcher. NULL keeps the built-in defaults;
+-- exclude patterns are stored one per line.
+ALTER TABLE projects ADD COLUMN watcher_exclude_patterns TEXT;
+ALTER TABLE projects ADD COLUMN watcher_max_events_per_second INTEGER;
@@ -0,0 +1,5 @@
+-- Strategy used for direct merges; NULL for PR merges. Earlier direct merges were all squashes.
+ALTER TABLE merges ADD COLUMN merge_strategy TEXT
+    CHECK (merge_strategy IN ('squash', 'merge_commit', 'rebase_fast_forward'));
+
+UPDATE merges SET merge_strategy = 'squash' WHERE merge_type = 'direct';
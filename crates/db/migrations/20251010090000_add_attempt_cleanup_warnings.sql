@@ -0,0 +1,12 @@
+-- Warnings issued before an inactive attempt's worktree is cleaned up. The cleanup job only
+-- deletes a worktree once its latest warning's cleanup_at has passed.
+CREATE TABLE attempt_cleanup_warnings (
+    id              BLOB PRIMARY KEY,
+    task_attempt_id BLOB NOT NULL,
+    cleanup_at      TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_attempt_cleanup_warnings_task_attempt_created_at
+    ON attempt_cleanup_warnings (task_attempt_id, created_at DESC);
@@ -0,0 +1,2 @@
+-- Initialize and update git submodules (recursively) when creating attempt worktrees
+ALTER TABLE projects ADD COLUMN init_submodules BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,5 @@
+-- Stacked attempts: the attempt whose branch this attempt was based on
+ALTER TABLE task_attempts ADD COLUMN parent_attempt_id BLOB
+    REFERENCES task_attempts(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_task_attempts_parent_attempt_id ON task_attempts(parent_attempt_id);
@@ -0,0 +1,4 @@
+-- Scheduled `git maintenance` for repositories that accumulate worktrees and refs from attempts
+ALTER TABLE projects ADD COLUMN git_maintenance_enabled BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE projects ADD COLUMN git_maintenance_interval_hours INTEGER NOT NULL DEFAULT 24;
+ALTER TABLE projects ADD COLUMN git_maintenance_last_run_at TEXT;
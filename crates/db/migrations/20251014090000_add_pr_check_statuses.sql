@@ -0,0 +1,12 @@
+-- Latest review decision and CI check results of an attempt's open pull request, refreshed by
+-- the PR monitor. One row per attempt; replaced on every change.
+CREATE TABLE pr_check_statuses (
+    task_attempt_id BLOB PRIMARY KEY,
+    pr_number       INTEGER NOT NULL,
+    pr_url          TEXT NOT NULL,
+    review_state    TEXT NOT NULL,
+    checks_state    TEXT NOT NULL,
+    checks          TEXT NOT NULL DEFAULT '[]',
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
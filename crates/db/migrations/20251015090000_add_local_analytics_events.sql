@@ -0,0 +1,11 @@
+-- Analytics events kept on this machine instead of being sent to the analytics service, used
+-- for the local insights endpoints.
+CREATE TABLE local_analytics_events (
+    id          BLOB PRIMARY KEY,
+    event_name  TEXT NOT NULL,
+    properties  TEXT NOT NULL DEFAULT '{}',
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_local_analytics_events_name_created_at
+    ON local_analytics_events (event_name, created_at);
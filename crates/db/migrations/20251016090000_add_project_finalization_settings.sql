@@ -0,0 +1,3 @@
+-- Per-run-reason finalization rules as JSON; NULL keeps the default behaviour of moving the task
+-- to review, notifying and starting a queued follow-up.
+ALTER TABLE projects ADD COLUMN finalization_settings TEXT;
@@ -0,0 +1,3 @@
+-- Repository-relative PR template used when creating pull requests; NULL checks the default
+-- template locations.
+ALTER TABLE projects ADD COLUMN pr_template_path TEXT;
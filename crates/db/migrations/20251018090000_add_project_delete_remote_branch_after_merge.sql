@@ -0,0 +1,2 @@
+-- Delete the remote copy of an attempt branch once its PR is merged or the attempt is merged locally
+ALTER TABLE projects ADD COLUMN delete_remote_branch_after_merge BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,4 @@
+-- Run the project's execution processes in a Docker/Podman container built from `sandbox_image`
+ALTER TABLE projects ADD COLUMN sandbox_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE projects ADD COLUMN sandbox_runtime TEXT NOT NULL DEFAULT 'docker';
+ALTER TABLE projects ADD COLUMN sandbox_image TEXT;
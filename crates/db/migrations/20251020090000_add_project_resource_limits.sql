@@ -0,0 +1,3 @@
+-- CPU (percent of one core) and memory (MB) caps for the project's execution processes
+ALTER TABLE projects ADD COLUMN resource_cpu_percent INTEGER;
+ALTER TABLE projects ADD COLUMN resource_memory_mb INTEGER;
@@ -0,0 +1,4 @@
+-- Newline-separated repository-relative directories shared from the main checkout into new
+-- attempt worktrees, and whether they are symlinked or cloned
+ALTER TABLE projects ADD COLUMN shared_dependency_dirs TEXT;
+ALTER TABLE projects ADD COLUMN dependency_share_mode TEXT NOT NULL DEFAULT 'symlink';
@@ -0,0 +1,10 @@
+-- Port assigned to a dev server process. A port counts as taken while its process is running.
+CREATE TABLE dev_server_ports (
+    execution_process_id BLOB PRIMARY KEY,
+    port                 INTEGER NOT NULL,
+    url                  TEXT NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_dev_server_ports_port ON dev_server_ports(port);
@@ -0,0 +1,20 @@
+-- Number of pre-created worktrees kept ready for new attempts; 0 disables the pool
+ALTER TABLE projects ADD COLUMN warm_worktree_pool_size INTEGER NOT NULL DEFAULT 0;
+
+-- Worktrees created ahead of time, with the setup script already run. A claimed worktree keeps
+-- its row until the attempt has started, so the setup script is not run a second time.
+CREATE TABLE warm_worktrees (
+    id                    BLOB PRIMARY KEY,
+    project_id            BLOB NOT NULL,
+    worktree_path         TEXT NOT NULL,
+    branch                TEXT NOT NULL,
+    base_branch           TEXT NOT NULL,
+    base_commit           TEXT NOT NULL,
+    ready                 BOOLEAN NOT NULL DEFAULT FALSE,
+    claimed_by_attempt_id BLOB,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (claimed_by_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_warm_worktrees_project_id ON warm_worktrees(project_id);
@@ -0,0 +1,4 @@
+-- Optional deadline for a task; open tasks past it are reported as overdue
+ALTER TABLE tasks ADD COLUMN due_at TEXT;
+
+CREATE INDEX idx_tasks_project_id_due_at ON tasks(project_id, due_at) WHERE due_at IS NOT NULL;
@@ -0,0 +1,22 @@
+-- Recurring task creation from templates on a cron schedule, optionally starting an attempt
+CREATE TABLE task_schedules (
+    id                  BLOB PRIMARY KEY,
+    project_id          BLOB NOT NULL,
+    template_id         BLOB NOT NULL,
+    cron_expression     TEXT NOT NULL,
+    enabled             BOOLEAN NOT NULL DEFAULT TRUE,
+    -- JSON ExecutorProfileId; NULL only creates the task
+    executor_profile_id TEXT,
+    base_branch         TEXT,
+    next_run_at         TEXT NOT NULL,
+    last_run_at         TEXT,
+    last_task_id        BLOB,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (template_id) REFERENCES task_templates(id) ON DELETE CASCADE,
+    FOREIGN KEY (last_task_id) REFERENCES tasks(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_schedules_project_id ON task_schedules(project_id);
+CREATE INDEX idx_task_schedules_next_run_at ON task_schedules(next_run_at) WHERE enabled = TRUE;
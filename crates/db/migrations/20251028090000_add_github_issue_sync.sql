@@ -0,0 +1,26 @@
+-- Projects whose tasks are kept in sync with the issues of a GitHub repository
+CREATE TABLE github_issue_syncs (
+    project_id     BLOB PRIMARY KEY,
+    owner          TEXT NOT NULL,
+    repo           TEXT NOT NULL,
+    last_synced_at TEXT,
+    last_error     TEXT,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- The issue a task was imported from; issue_state is the state last seen on or pushed to GitHub
+CREATE TABLE task_github_issues (
+    task_id      BLOB PRIMARY KEY,
+    project_id   BLOB NOT NULL,
+    issue_number INTEGER NOT NULL,
+    issue_url    TEXT NOT NULL,
+    issue_state  TEXT NOT NULL DEFAULT 'open'
+                    CHECK (issue_state IN ('open', 'closed')),
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, issue_number)
+);
@@ -0,0 +1,27 @@
+-- Projects that pull the Jira issues matching a JQL query in as tasks
+CREATE TABLE jira_syncs (
+    project_id       BLOB PRIMARY KEY,
+    jql              TEXT NOT NULL,
+    -- Jira statuses to transition to when the task reaches InReview / Done; NULL uses the defaults
+    in_review_status TEXT,
+    done_status      TEXT,
+    last_synced_at   TEXT,
+    last_error       TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- The Jira issue a task was imported from; pushed_status is the last task status sent to Jira
+CREATE TABLE task_jira_issues (
+    task_id       BLOB PRIMARY KEY,
+    project_id    BLOB NOT NULL,
+    issue_key     TEXT NOT NULL,
+    issue_url     TEXT NOT NULL,
+    pushed_status TEXT,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, issue_key)
+);
@@ -0,0 +1,7 @@
+-- Issue sync covers GitLab projects as well as GitHub repositories. For GitLab, owner is the
+-- namespace path (which may contain slashes) and repo the project name.
+ALTER TABLE github_issue_syncs RENAME TO issue_syncs;
+ALTER TABLE issue_syncs ADD COLUMN tracker TEXT NOT NULL DEFAULT 'github'
+    CHECK (tracker IN ('github', 'gitlab'));
+
+ALTER TABLE task_github_issues RENAME TO task_issues;
@@ -0,0 +1,20 @@
+-- Outbound webhooks: lifecycle events are POSTed as JSON signed with the webhook's secret
+CREATE TABLE webhooks (
+    id                   BLOB PRIMARY KEY,
+    -- NULL receives events from every project
+    project_id           BLOB,
+    url                  TEXT NOT NULL,
+    secret               TEXT NOT NULL,
+    -- JSON array of subscribed event names
+    events               TEXT NOT NULL DEFAULT '[]',
+    enabled              BOOLEAN NOT NULL DEFAULT TRUE,
+    last_delivery_at     TEXT,
+    -- HTTP status of the last delivery attempt; NULL when the request never got a response
+    last_response_status INTEGER,
+    last_error           TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_webhooks_project_id ON webhooks(project_id);
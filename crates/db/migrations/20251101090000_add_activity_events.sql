@@ -0,0 +1,73 @@
+-- Activity feed history, recorded as the rows it is derived from change instead of being
+-- recomputed from them on every read
+CREATE TABLE activity_events (
+    id           BLOB PRIMARY KEY,
+    project_id   BLOB NOT NULL,
+    entity_type  TEXT NOT NULL
+                 CHECK (entity_type IN ('task', 'attempt', 'comment', 'deployment')),
+    entity_id    BLOB NOT NULL,
+    -- Row the event was recorded from: task, execution process, verification or cleanup warning
+    source_id    BLOB NOT NULL,
+    -- The task itself, or the parent task of an attempt; its events go when it is deleted
+    task_id      BLOB,
+    headline     TEXT,
+    body         TEXT,
+    -- Task status, or the state of the attempt
+    status       TEXT,
+    executor     TEXT,
+    urgency_hint TEXT
+                 CHECK (urgency_hint IN ('low', 'normal', 'elevated', 'high', 'critical')),
+    -- Normalised to 'YYYY-MM-DD HH:MM:SS.SSS' so ranges can use the index
+    created_at   TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    -- Recording the same change twice is a no-op
+    UNIQUE (source_id, status, created_at)
+);
+
+CREATE INDEX idx_activity_events_project_created_at
+    ON activity_events (project_id, created_at DESC);
+
+-- Start from what the feed showed so far
+INSERT OR IGNORE INTO activity_events
+    (id, project_id, entity_type, entity_id, source_id, task_id, headline, body, status,
+     created_at)
+SELECT id, project_id, 'task', id, id, id,
+       CASE WHEN created_at = updated_at THEN 'Task created: ' ELSE 'Task updated: ' END || title,
+       description, status, strftime('%Y-%m-%d %H:%M:%f', updated_at)
+  FROM tasks;
+
+INSERT OR IGNORE INTO activity_events
+    (id, project_id, entity_type, entity_id, source_id, task_id, headline, status, executor,
+     created_at)
+SELECT ep.id, t.project_id, 'attempt', ta.id, ep.id, ta.task_id, 'Attempt updated',
+       lower(ep.status), ta.executor,
+       strftime('%Y-%m-%d %H:%M:%f', COALESCE(ep.completed_at, ep.started_at))
+  FROM execution_processes ep
+  JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+  JOIN tasks t ON t.id = ta.task_id;
+
+INSERT OR IGNORE INTO activity_events
+    (id, project_id, entity_type, entity_id, source_id, task_id, headline, body, status,
+     executor, created_at)
+SELECT av.id, t.project_id, 'attempt', ta.id, av.id, ta.task_id,
+       'Verification failed: ' || t.title,
+       CASE WHEN av.exit_code IS NOT NULL
+            THEN 'Verify script exited with code ' || av.exit_code END,
+       'verificationfailed', ta.executor, strftime('%Y-%m-%d %H:%M:%f', av.created_at)
+  FROM attempt_verifications av
+  JOIN task_attempts ta ON ta.id = av.task_attempt_id
+  JOIN tasks t ON t.id = ta.task_id
+ WHERE av.passed = FALSE;
+
+INSERT OR IGNORE INTO activity_events
+    (id, project_id, entity_type, entity_id, source_id, task_id, headline, body, status,
+     executor, urgency_hint, created_at)
+SELECT w.id, t.project_id, 'attempt', ta.id, w.id, ta.task_id,
+       'Workspace will be cleaned up: ' || t.title,
+       'The worktree will be deleted after ' || strftime('%Y-%m-%d %H:%M', w.cleanup_at)
+           || ' UTC unless the attempt is kept',
+       'cleanupscheduled', ta.executor, 'elevated', strftime('%Y-%m-%d %H:%M:%f', w.created_at)
+  FROM attempt_cleanup_warnings w
+  JOIN task_attempts ta ON ta.id = w.task_attempt_id
+  JOIN tasks t ON t.id = ta.task_id;
@@ -0,0 +1,13 @@
+-- Activity events each user has acknowledged. Overdue alerts are derived when the feed is read
+-- and never stored in activity_events, so event_id is not a foreign key.
+CREATE TABLE activity_event_reads (
+    user_id    TEXT NOT NULL,
+    event_id   BLOB NOT NULL,
+    project_id BLOB NOT NULL,
+    read_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (user_id, event_id),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_activity_event_reads_user_project
+    ON activity_event_reads(user_id, project_id);
@@ -0,0 +1,82 @@
+-- Merges, pull requests and dev servers get events of their own. SQLite cannot change a CHECK
+-- constraint in place, so the table is rebuilt.
+CREATE TABLE activity_events_new (
+    id           BLOB PRIMARY KEY,
+    project_id   BLOB NOT NULL,
+    entity_type  TEXT NOT NULL
+                 CHECK (entity_type IN ('task', 'attempt', 'comment', 'deployment', 'merge',
+                                        'pullrequest', 'devserver')),
+    entity_id    BLOB NOT NULL,
+    -- Row the event was recorded from: task, execution process, verification, cleanup warning
+    -- or merge
+    source_id    BLOB NOT NULL,
+    -- The task itself, or the parent task of an attempt; its events go when it is deleted
+    task_id      BLOB,
+    -- Attempt a merge, pull request or dev server belongs to
+    attempt_id   BLOB,
+    headline     TEXT,
+    body         TEXT,
+    -- Task status, the state of the attempt, pull request or dev server
+    status       TEXT,
+    executor     TEXT,
+    url          TEXT,
+    urgency_hint TEXT
+                 CHECK (urgency_hint IN ('low', 'normal', 'elevated', 'high', 'critical')),
+    -- Normalised to 'YYYY-MM-DD HH:MM:SS.SSS' so ranges can use the index
+    created_at   TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    -- Recording the same change twice is a no-op
+    UNIQUE (source_id, status, created_at)
+);
+
+INSERT INTO activity_events_new
+    (id, project_id, entity_type, entity_id, source_id, task_id, headline, body, status,
+     executor, urgency_hint, created_at)
+SELECT id, project_id, entity_type, entity_id, source_id, task_id, headline, body, status,
+       executor, urgency_hint, created_at
+  FROM activity_events;
+
+DROP TABLE activity_events;
+ALTER TABLE activity_events_new RENAME TO activity_events;
+
+CREATE INDEX idx_activity_events_project_created_at
+    ON activity_events (project_id, created_at DESC);
+
+-- Dev server runs were recorded as attempt updates
+UPDATE activity_events
+   SET entity_type = 'devserver',
+       attempt_id = entity_id,
+       entity_id = source_id,
+       headline = CASE status
+                      WHEN 'running' THEN 'Dev server started'
+                      WHEN 'failed' THEN 'Dev server failed'
+                      ELSE 'Dev server stopped'
+                  END
+ WHERE source_id IN (SELECT id FROM execution_processes WHERE run_reason = 'devserver');
+
+INSERT OR IGNORE INTO activity_events
+    (id, project_id, entity_type, entity_id, source_id, task_id, attempt_id, headline, body,
+     status, executor, created_at)
+SELECT m.id, t.project_id, 'merge', m.id, m.id, ta.task_id, ta.id,
+       'Merged into ' || m.target_branch_name || ': ' || t.title,
+       'Merge commit ' || substr(m.merge_commit, 1, 12),
+       'merged', ta.executor, strftime('%Y-%m-%d %H:%M:%f', m.created_at)
+  FROM merges m
+  JOIN task_attempts ta ON ta.id = m.task_attempt_id
+  JOIN tasks t ON t.id = ta.task_id
+ WHERE m.merge_type = 'direct';
+
+INSERT OR IGNORE INTO activity_events
+    (id, project_id, entity_type, entity_id, source_id, task_id, attempt_id, headline, body,
+     status, executor, url, created_at)
+SELECT m.id, t.project_id, 'pullrequest', m.id, m.id, ta.task_id, ta.id,
+       'Pull request ' || CASE m.pr_status WHEN 'open' THEN 'opened' ELSE m.pr_status END
+           || ': ' || t.title,
+       'Pull request #' || m.pr_number || ' into ' || m.target_branch_name,
+       m.pr_status, ta.executor, m.pr_url,
+       strftime('%Y-%m-%d %H:%M:%f', COALESCE(m.pr_merged_at, m.created_at))
+  FROM merges m
+  JOIN task_attempts ta ON ta.id = m.task_attempt_id
+  JOIN tasks t ON t.id = ta.task_id
+ WHERE m.merge_type = 'pr';
@@ -0,0 +1,8 @@
+-- Urgent activity events that were sent as notifications, so each goes out once. Overdue alerts
+-- are not stored in activity_events, so event_id is not a foreign key.
+CREATE TABLE activity_event_notifications (
+    event_id    BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    notified_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
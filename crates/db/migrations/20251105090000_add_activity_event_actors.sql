@@ -0,0 +1,3 @@
+-- JSON array of {id, display_name}: who made the change, e.g. the authors of an attempt's
+-- commits. NULL until known.
+ALTER TABLE activity_events ADD COLUMN actors TEXT;
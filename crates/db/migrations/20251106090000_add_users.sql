@@ -0,0 +1,29 @@
+-- Accounts for sharing one instance. While the table is empty the app is single-user and every
+-- request is allowed, as before.
+CREATE TABLE users (
+    id            BLOB PRIMARY KEY,
+    username      TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    display_name  TEXT NOT NULL,
+    -- Argon2id PHC string
+    password_hash TEXT NOT NULL,
+    role          TEXT NOT NULL DEFAULT 'contributor'
+                  CHECK (role IN ('admin', 'contributor', 'viewer')),
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Login sessions. Only a hash of the token is stored.
+CREATE TABLE user_sessions (
+    id           BLOB PRIMARY KEY,
+    user_id      BLOB NOT NULL,
+    token_hash   TEXT NOT NULL UNIQUE,
+    expires_at   TEXT NOT NULL,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_user_sessions_user_id ON user_sessions(user_id);
+
+-- Who started an attempt and who last saved a draft; NULL in single-user mode
+ALTER TABLE task_attempts ADD COLUMN created_by_user_id BLOB REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE drafts ADD COLUMN updated_by_user_id BLOB REFERENCES users(id) ON DELETE SET NULL;
@@ -0,0 +1,22 @@
+-- State-changing API calls, for finding out who changed what. The actor is not a foreign key so
+-- entries outlive the accounts they name.
+CREATE TABLE audit_log (
+    id             BLOB PRIMARY KEY,
+    -- NULL in single-user mode
+    actor_user_id  BLOB,
+    actor_name     TEXT,
+    method         TEXT NOT NULL,
+    path           TEXT NOT NULL,
+    -- Last collection and id in the path, e.g. task-attempts and the attempt id
+    entity_type    TEXT,
+    entity_id      BLOB,
+    status_code    INTEGER NOT NULL,
+    -- JSON summaries of the entity around the call, for the kinds of entity that have one
+    before_summary TEXT,
+    after_summary  TEXT,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_audit_log_created_at ON audit_log(created_at);
+CREATE INDEX idx_audit_log_entity_id ON audit_log(entity_id);
+CREATE INDEX idx_audit_log_actor_user_id ON audit_log(actor_user_id);
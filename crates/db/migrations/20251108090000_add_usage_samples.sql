@@ -0,0 +1,13 @@
+-- Coding agent usage sampled periodically, so usage can be charted over time
+CREATE TABLE usage_samples (
+    id                     BLOB PRIMARY KEY,
+    agent                  TEXT NOT NULL CHECK (agent IN ('codex', 'claude-code')),
+    -- Share of the agent's current rate limit window used, 0-100
+    used_percent           REAL,
+    -- Codex's longer, weekly window
+    secondary_used_percent REAL,
+    total_tokens           INTEGER,
+    sampled_at             TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_usage_samples_agent_sampled_at ON usage_samples(agent, sampled_at);
@@ -0,0 +1,12 @@
+-- Tokens a coding agent process used, per model, read from its output when it exits
+CREATE TABLE execution_process_token_usage (
+    execution_process_id        BLOB NOT NULL,
+    model                       TEXT NOT NULL,
+    -- Input not served from the prompt cache
+    input_tokens                INTEGER NOT NULL DEFAULT 0,
+    cached_input_tokens         INTEGER NOT NULL DEFAULT 0,
+    cache_creation_input_tokens INTEGER NOT NULL DEFAULT 0,
+    output_tokens               INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (execution_process_id, model),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,9 @@
+-- Budget notifications already sent, so each is sent once per agent, month and level
+CREATE TABLE budget_alerts (
+    agent   TEXT NOT NULL,
+    -- First day of the budget month, YYYY-MM-DD
+    month   TEXT NOT NULL,
+    level   TEXT NOT NULL CHECK (level IN ('warning', 'exceeded')),
+    sent_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (agent, month, level)
+);
@@ -0,0 +1,2 @@
+-- When the sampled rate limit window resets, so queued work can wait for it
+ALTER TABLE usage_samples ADD COLUMN resets_at TEXT;
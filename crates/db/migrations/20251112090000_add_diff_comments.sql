@@ -0,0 +1,21 @@
+-- Comments left on line ranges of an attempt's diff, waiting to be sent to the agent
+CREATE TABLE diff_comments (
+    id              BLOB PRIMARY KEY,
+    task_attempt_id BLOB NOT NULL,
+    -- Set for multi-repository attempts; paths are relative to that repository
+    repository_id   BLOB,
+    file_path       TEXT NOT NULL,
+    side            TEXT NOT NULL CHECK (side IN ('old', 'new')),
+    start_line      INTEGER NOT NULL,
+    end_line        INTEGER NOT NULL,
+    -- The commented lines as they were when the comment was written
+    code            TEXT,
+    body            TEXT NOT NULL,
+    -- Set once the comment has been included in a follow-up
+    sent_at         TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_diff_comments_task_attempt_id ON diff_comments(task_attempt_id);
@@ -0,0 +1,3 @@
+-- Files whose diffs start collapsed, one gitignore-style pattern per line. NULL keeps the
+-- built-in lockfile patterns.
+ALTER TABLE projects ADD COLUMN diff_ignore_patterns TEXT;
@@ -0,0 +1,10 @@
+-- Logs of long-finished processes, moved out of execution_process_logs so the table read on
+-- every view stays small. Rows are only read when a process has no hot logs.
+CREATE TABLE execution_process_logs_archive (
+    execution_id      BLOB PRIMARY KEY,
+    logs              TEXT NOT NULL,
+    byte_size         INTEGER NOT NULL,
+    inserted_at       TEXT NOT NULL,
+    archived_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+-- When a queued follow-up should start. NULL starts it as soon as nothing is running.
+ALTER TABLE drafts ADD COLUMN run_at TEXT;
+
+CREATE INDEX idx_drafts_scheduled ON drafts(run_at) WHERE queued = 1 AND run_at IS NOT NULL;
@@ -0,0 +1,21 @@
+-- Named prompt snippets referenced as {{snippet:name}} in task descriptions and follow-ups
+CREATE TABLE prompt_snippets (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB,  -- NULL for global snippets
+    name        TEXT NOT NULL,
+    content     TEXT NOT NULL,
+    description TEXT,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_prompt_snippets_project_id ON prompt_snippets(project_id);
+
+CREATE UNIQUE INDEX idx_prompt_snippets_unique_name_project
+ON prompt_snippets(project_id, name)
+WHERE project_id IS NOT NULL;
+
+CREATE UNIQUE INDEX idx_prompt_snippets_unique_name_global
+ON prompt_snippets(name)
+WHERE project_id IS NULL;
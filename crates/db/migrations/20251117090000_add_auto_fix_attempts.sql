@@ -0,0 +1,6 @@
+-- How many follow-ups to send automatically when the cleanup or verify script fails after a
+-- coding agent run. 0 turns it off.
+ALTER TABLE projects ADD COLUMN auto_fix_attempts INTEGER NOT NULL DEFAULT 0;
+
+-- Automatic follow-ups sent since the attempt's scripts last passed
+ALTER TABLE task_attempts ADD COLUMN auto_fix_count INTEGER NOT NULL DEFAULT 0;
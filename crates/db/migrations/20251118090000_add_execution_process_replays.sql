@@ -0,0 +1,11 @@
+-- Execution processes started by re-running another process's executor action
+CREATE TABLE execution_process_replays (
+    execution_process_id BLOB PRIMARY KEY,
+    replayed_from_id     BLOB NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (replayed_from_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_process_replays_replayed_from_id
+    ON execution_process_replays(replayed_from_id);
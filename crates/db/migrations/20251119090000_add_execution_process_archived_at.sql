@@ -0,0 +1,4 @@
+-- Set when a long-finished process is archived along with its logs. Archived processes are left
+-- out of attempt timelines; the row stays, since sessions, token usage and restore points
+-- reference it.
+ALTER TABLE execution_processes ADD COLUMN archived_at TEXT;
@@ -1,13 +1,143 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
 use sqlx::{
     Error, Pool, Sqlite,
-    sqlite::{SqliteConnectOptions, SqliteConnection, SqlitePoolOptions},
+    sqlite::{SqliteAutoVacuum, SqliteConnectOptions, SqliteConnection, SqlitePoolOptions},
 };
 use utils::assets::asset_dir;
 
 pub mod activity_feed_queries;
+pub mod maintenance;
 pub mod models;
+pub mod pagination;
+pub mod stats;
+
+/// The SQLite file in the app data directory
+fn database_url() -> String {
+    format!(
+        "sqlite://{}",
+        asset_dir().join("db.sqlite").to_string_lossy()
+    )
+}
+
+fn database_path(database_url: &str) -> Option<PathBuf> {
+    let rest = database_url
+        .strip_prefix("sqlite://")
+        .or_else(|| database_url.strip_prefix("sqlite:"))?;
+    Some(PathBuf::from(rest.split('?').next().unwrap_or(rest)))
+}
+
+/// `VIBE_DATABASE_KEY` encrypts the database with SQLCipher, in builds with the `sqlcipher`
+/// feature. Whether a database is encrypted is settled when it is created, so a key given for an
+/// existing plaintext database is refused instead of surfacing as "file is not a database".
+fn database_key(database_url: &str) -> Result<Option<String>, Error> {
+    let Some(key) = std::env::var("VIBE_DATABASE_KEY")
+        .ok()
+        .filter(|key| !key.is_empty())
+    else {
+        return Ok(None);
+    };
+    if !cfg!(feature = "sqlcipher") {
+        return Err(Error::Configuration(
+            "VIBE_DATABASE_KEY is set, but this build does not include SQLCipher".into(),
+        ));
+    }
+    if let Some(database) = database_path(database_url)
+        && is_plaintext_database(&database)?
+    {
+        return Err(Error::Configuration(
+            format!(
+                "{} is not encrypted; unset VIBE_DATABASE_KEY or move the database aside to start an encrypted one",
+                database.display()
+            )
+            .into(),
+        ));
+    }
+    Ok(Some(key))
+}
+
+/// Unencrypted SQLite files start with a fixed header; SQLCipher files start with random salt
+fn is_plaintext_database(path: &Path) -> Result<bool, Error> {
+    use std::io::Read;
+
+    let mut header = [0u8; 16];
+    match std::fs::File::open(path).and_then(|mut file| file.read_exact(&mut header)) {
+        Ok(()) => Ok(&header == b"SQLite format 3\0"),
+        Err(e)
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::NotFound | std::io::ErrorKind::UnexpectedEof
+            ) =>
+        {
+            Ok(false)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn connect_options(database_url: &str) -> Result<SqliteConnectOptions, Error> {
+    let mut options = SqliteConnectOptions::from_str(database_url)?
+        .create_if_missing(true)
+        .busy_timeout(std::time::Duration::from_secs(10))
+        .auto_vacuum(SqliteAutoVacuum::Incremental)
+        .pragma("journal_mode", "WAL")
+        .pragma("synchronous", "NORMAL")
+        .pragma("cache_size", "-64000");
+    if let Some(key) = database_key(database_url)? {
+        // sqlx sends `key` before every other pragma, as SQLCipher requires
+        options = options.pragma("key", format!("'{}'", key.replace('\'', "''")));
+    }
+    Ok(options)
+}
+
+/// A backup copied to `<database>.restore` replaces the database on the next start, before any
+/// connection is opened. The replaced database is moved to `<database>.pre-restore`, together with
+/// its write-ahead log, so a mistaken restore can be undone.
+fn restore_pending_backup(database_url: &str) -> Result<(), Error> {
+    let Some(database) = database_path(database_url) else {
+        return Ok(());
+    };
+    let with_suffix = |suffix: &str| {
+        let mut path = database.clone().into_os_string();
+        path.push(suffix);
+        PathBuf::from(path)
+    };
+
+    let remove_if_present = |path: &Path| match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    };
+
+    let pending = with_suffix(".restore");
+    if !pending.is_file() {
+        return Ok(());
+    }
+    let previous = with_suffix(".pre-restore");
+    let previous_wal = with_suffix(".pre-restore-wal");
+    // A log left from an earlier restore must not be paired with the database moved aside now
+    remove_if_present(&previous_wal)?;
+    if database.is_file() {
+        std::fs::rename(&database, &previous)?;
+        let wal = with_suffix("-wal");
+        if wal.is_file() {
+            std::fs::rename(&wal, &previous_wal)?;
+        }
+    }
+    for stale in [with_suffix("-wal"), with_suffix("-shm")] {
+        remove_if_present(&stale)?;
+    }
+    std::fs::rename(&pending, &database)?;
+    tracing::info!(
+        "Restored database from {}; the previous one was kept as {}",
+        pending.display(),
+        previous.display()
+    );
+    Ok(())
+}
 
 #[derive(Clone)]
 pub struct DBService {
@@ -16,16 +146,9 @@ pub struct DBService {
 
 impl DBService {
     pub async fn new() -> Result<DBService, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .busy_timeout(std::time::Duration::from_secs(10))
-            .pragma("journal_mode", "WAL")
-            .pragma("synchronous", "NORMAL")
-            .pragma("cache_size", "-64000");
+        let database_url = database_url();
+        restore_pending_backup(&database_url)?;
+        let options = connect_options(&database_url)?;
         let pool = SqlitePoolOptions::new()
             .max_connections(10)
             .acquire_timeout(std::time::Duration::from_secs(10))
@@ -35,6 +158,16 @@ impl DBService {
         Ok(DBService { pool })
     }
 
+    /// Write a consistent copy of the database to `path`, which must not exist yet, without
+    /// blocking writers for longer than the copy takes
+    pub async fn backup_to(&self, path: &Path) -> Result<(), Error> {
+        sqlx::query("VACUUM INTO ?")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     pub async fn new_with_after_connect<F>(after_connect: F) -> Result<DBService, Error>
     where
         F: for<'a> Fn(
@@ -59,16 +192,9 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .busy_timeout(std::time::Duration::from_secs(10))
-            .pragma("journal_mode", "WAL")
-            .pragma("synchronous", "NORMAL")
-            .pragma("cache_size", "-64000");
+        let database_url = database_url();
+        restore_pending_backup(&database_url)?;
+        let options = connect_options(&database_url)?;
 
         let pool = if let Some(hook) = after_connect {
             SqlitePoolOptions::new()
@@ -95,3 +221,42 @@ impl DBService {
         Ok(pool)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restoring_keeps_the_replaced_database_and_its_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = dir.path().join("db.sqlite");
+        let url = format!("sqlite://{}", database.display());
+        let file = |name: &str| dir.path().join(name);
+        let read = |name: &str| std::fs::read_to_string(file(name)).unwrap();
+
+        // Nothing happens without a pending backup
+        std::fs::write(&database, "current").unwrap();
+        restore_pending_backup(&url).unwrap();
+        assert_eq!(read("db.sqlite"), "current");
+        assert!(!file("db.sqlite.pre-restore").exists());
+
+        std::fs::write(file("db.sqlite-wal"), "current log").unwrap();
+        std::fs::write(file("db.sqlite-shm"), "index").unwrap();
+        std::fs::write(file("db.sqlite.restore"), "backup").unwrap();
+        restore_pending_backup(&url).unwrap();
+
+        assert_eq!(read("db.sqlite"), "backup");
+        assert_eq!(read("db.sqlite.pre-restore"), "current");
+        assert_eq!(read("db.sqlite.pre-restore-wal"), "current log");
+        assert!(!file("db.sqlite.restore").exists());
+        assert!(!file("db.sqlite-wal").exists());
+        assert!(!file("db.sqlite-shm").exists());
+
+        // A second restore replaces the kept copy, and drops the log that belonged to it
+        std::fs::write(file("db.sqlite.restore"), "older backup").unwrap();
+        restore_pending_backup(&url).unwrap();
+        assert_eq!(read("db.sqlite"), "older backup");
+        assert_eq!(read("db.sqlite.pre-restore"), "backup");
+        assert!(!file("db.sqlite.pre-restore-wal").exists());
+    }
+}
@@ -0,0 +1,84 @@
+//! Housekeeping for the long-lived SQLite file: folding the WAL back into the database,
+//! refreshing the planner's statistics and returning free pages to the filesystem.
+
+use std::time::Instant;
+
+use serde::Serialize;
+use sqlx::{Error, Row};
+use ts_rs::TS;
+
+use crate::DBService;
+
+/// `PRAGMA auto_vacuum` value for incremental mode
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DatabaseMaintenance {
+    /// Whether a reader kept the checkpoint from reaching the end of the WAL
+    pub checkpoint_busy: bool,
+    #[ts(type = "number")]
+    pub checkpointed_frames: i64,
+    /// Free pages returned to the filesystem
+    #[ts(type = "number")]
+    pub freed_pages: i64,
+    /// Whether free pages can be returned without a full `VACUUM`. Databases created before
+    /// incremental vacuum was turned on stay without it until they are converted.
+    pub incremental_vacuum: bool,
+    /// Set when this run converted the database, which takes one full `VACUUM`
+    pub converted_to_incremental: bool,
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+}
+
+impl DBService {
+    /// Checkpoint and truncate the WAL, run `ANALYZE` and release free pages with an incremental
+    /// vacuum. A database without incremental vacuum is only converted when
+    /// `convert_to_incremental` is set, since the `VACUUM` that takes rewrites the whole file and
+    /// blocks writers until it is done.
+    pub async fn run_maintenance(
+        &self,
+        convert_to_incremental: bool,
+    ) -> Result<DatabaseMaintenance, Error> {
+        let started = Instant::now();
+        let mut conn = self.pool.acquire().await?;
+
+        let freelist_before: i64 = sqlx::query_scalar("PRAGMA freelist_count")
+            .fetch_one(&mut *conn)
+            .await?;
+        let auto_vacuum: i64 = sqlx::query_scalar("PRAGMA auto_vacuum")
+            .fetch_one(&mut *conn)
+            .await?;
+        let incremental_vacuum = auto_vacuum == AUTO_VACUUM_INCREMENTAL;
+        let converted_to_incremental = !incremental_vacuum && convert_to_incremental;
+        if incremental_vacuum {
+            sqlx::query("PRAGMA incremental_vacuum")
+                .execute(&mut *conn)
+                .await?;
+        } else if converted_to_incremental {
+            // The mode of an existing database only changes when it is rebuilt
+            sqlx::query("PRAGMA auto_vacuum = INCREMENTAL")
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query("VACUUM").execute(&mut *conn).await?;
+        }
+        let freelist_after: i64 = sqlx::query_scalar("PRAGMA freelist_count")
+            .fetch_one(&mut *conn)
+            .await?;
+
+        sqlx::query("ANALYZE").execute(&mut *conn).await?;
+
+        // Last, so the WAL written by the steps above is folded in as well
+        let checkpoint = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(DatabaseMaintenance {
+            checkpoint_busy: checkpoint.try_get::<i64, _>(0)? != 0,
+            checkpointed_frames: checkpoint.try_get::<i64, _>(2)?.max(0),
+            freed_pages: (freelist_before - freelist_after).max(0),
+            incremental_vacuum: incremental_vacuum || converted_to_incremental,
+            converted_to_incremental,
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+}
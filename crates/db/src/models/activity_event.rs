@@ -0,0 +1,379 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use uuid::Uuid;
+
+use crate::activity_feed_queries::{ActivityActorRow, UrgencyHint};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum ActivityEventEntity {
+    Task,
+    Attempt,
+    Comment,
+    Deployment,
+    Merge,
+    PullRequest,
+    DevServer,
+}
+
+/// A recorded activity feed event
+#[derive(Debug, Clone, FromRow)]
+pub struct ActivityEventRecord {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub entity_type: ActivityEventEntity,
+    pub entity_id: Uuid,
+    pub source_id: Uuid,
+    pub task_id: Option<Uuid>,
+    /// Set for merges, pull requests and dev servers
+    pub attempt_id: Option<Uuid>,
+    pub headline: Option<String>,
+    pub body: Option<String>,
+    pub status: Option<String>,
+    pub executor: Option<String>,
+    pub url: Option<String>,
+    pub urgency_hint: Option<UrgencyHint>,
+    /// Authors of the commits behind the event, once attributed
+    pub actors: Option<Json<Vec<ActivityActorRow>>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Commits an event is about: those reachable from `head` but not from `base`, or only `head`
+/// when there is no `base`
+#[derive(Debug, Clone, FromRow)]
+pub struct ActivityCommitRange {
+    pub repo_path: String,
+    pub base: Option<String>,
+    pub head: String,
+}
+
+impl ActivityEventRecord {
+    /// Record the task at `rowid` as created or updated. Nothing is recorded when this version
+    /// of the task already was.
+    pub async fn record_task(pool: &SqlitePool, rowid: i64) -> Result<bool, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO activity_events
+                   (id, project_id, entity_type, entity_id, source_id, task_id, headline, body,
+                    status, created_at)
+               SELECT $1, project_id, 'task', id, id, id,
+                      CASE WHEN created_at = updated_at THEN 'Task created: '
+                           ELSE 'Task updated: ' END || title,
+                      description, status, strftime('%Y-%m-%d %H:%M:%f', updated_at)
+                 FROM tasks
+                WHERE rowid = $2"#,
+            id,
+            rowid
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the state of the execution process at `rowid` as an event of its attempt. Each
+    /// status a process goes through is recorded once. Dev servers have events of their own, see
+    /// [`Self::record_dev_server`].
+    pub async fn record_execution_process(
+        pool: &SqlitePool,
+        rowid: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO activity_events
+                   (id, project_id, entity_type, entity_id, source_id, task_id, headline, status,
+                    executor, created_at)
+               SELECT $1, t.project_id, 'attempt', ta.id, ep.id, ta.task_id, 'Attempt updated',
+                      lower(ep.status), ta.executor,
+                      strftime('%Y-%m-%d %H:%M:%f', COALESCE(ep.completed_at, ep.started_at))
+                 FROM execution_processes ep
+                 JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+                 JOIN tasks t ON t.id = ta.task_id
+                WHERE ep.rowid = $2 AND ep.run_reason != 'devserver'"#,
+            id,
+            rowid
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the verify script run at `rowid` if it failed
+    pub async fn record_verification(pool: &SqlitePool, rowid: i64) -> Result<bool, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO activity_events
+                   (id, project_id, entity_type, entity_id, source_id, task_id, headline, body,
+                    status, executor, created_at)
+               SELECT $1, t.project_id, 'attempt', ta.id, av.id, ta.task_id,
+                      'Verification failed: ' || t.title,
+                      CASE WHEN av.exit_code IS NOT NULL
+                           THEN 'Verify script exited with code ' || av.exit_code END,
+                      'verificationfailed', ta.executor,
+                      strftime('%Y-%m-%d %H:%M:%f', av.created_at)
+                 FROM attempt_verifications av
+                 JOIN task_attempts ta ON ta.id = av.task_attempt_id
+                 JOIN tasks t ON t.id = ta.task_id
+                WHERE av.rowid = $2 AND av.passed = FALSE"#,
+            id,
+            rowid
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the cleanup warning at `rowid`
+    pub async fn record_cleanup_warning(
+        pool: &SqlitePool,
+        rowid: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO activity_events
+                   (id, project_id, entity_type, entity_id, source_id, task_id, headline, body,
+                    status, executor, urgency_hint, created_at)
+               SELECT $1, t.project_id, 'attempt', ta.id, w.id, ta.task_id,
+                      'Workspace will be cleaned up: ' || t.title,
+                      'The worktree will be deleted after '
+                          || strftime('%Y-%m-%d %H:%M', w.cleanup_at)
+                          || ' UTC unless the attempt is kept',
+                      'cleanupscheduled', ta.executor, 'elevated',
+                      strftime('%Y-%m-%d %H:%M:%f', w.created_at)
+                 FROM attempt_cleanup_warnings w
+                 JOIN task_attempts ta ON ta.id = w.task_attempt_id
+                 JOIN tasks t ON t.id = ta.task_id
+                WHERE w.rowid = $2"#,
+            id,
+            rowid
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a direct merge of an attempt
+    pub async fn record_merge(pool: &SqlitePool, merge_id: Uuid) -> Result<bool, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO activity_events
+                   (id, project_id, entity_type, entity_id, source_id, task_id, attempt_id,
+                    headline, body, status, executor, created_at)
+               SELECT $1, t.project_id, 'merge', m.id, m.id, ta.task_id, ta.id,
+                      'Merged into ' || m.target_branch_name || ': ' || t.title,
+                      'Merge commit ' || substr(m.merge_commit, 1, 12),
+                      'merged', ta.executor, strftime('%Y-%m-%d %H:%M:%f', m.created_at)
+                 FROM merges m
+                 JOIN task_attempts ta ON ta.id = m.task_attempt_id
+                 JOIN tasks t ON t.id = ta.task_id
+                WHERE m.id = $2 AND m.merge_type = 'direct'"#,
+            id,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the current state of a pull request: opened, merged or closed
+    pub async fn record_pull_request(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO activity_events
+                   (id, project_id, entity_type, entity_id, source_id, task_id, attempt_id,
+                    headline, body, status, executor, url, created_at)
+               SELECT $1, t.project_id, 'pullrequest', m.id, m.id, ta.task_id, ta.id,
+                      'Pull request '
+                          || CASE m.pr_status WHEN 'open' THEN 'opened' ELSE m.pr_status END
+                          || ': ' || t.title,
+                      'Pull request #' || m.pr_number || ' into ' || m.target_branch_name,
+                      m.pr_status, ta.executor, m.pr_url,
+                      strftime('%Y-%m-%d %H:%M:%f',
+                               CASE WHEN m.pr_status = 'open' THEN m.created_at
+                                    ELSE COALESCE(m.pr_merged_at, $2) END)
+                 FROM merges m
+                 JOIN task_attempts ta ON ta.id = m.task_attempt_id
+                 JOIN tasks t ON t.id = ta.task_id
+                WHERE m.id = $3 AND m.merge_type = 'pr'"#,
+            id,
+            now,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the state of a dev server run
+    pub async fn record_dev_server(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO activity_events
+                   (id, project_id, entity_type, entity_id, source_id, task_id, attempt_id,
+                    headline, status, executor, created_at)
+               SELECT $1, t.project_id, 'devserver', ep.id, ep.id, ta.task_id, ta.id,
+                      CASE lower(ep.status)
+                          WHEN 'running' THEN 'Dev server started'
+                          WHEN 'failed' THEN 'Dev server failed'
+                          ELSE 'Dev server stopped'
+                      END,
+                      lower(ep.status), ta.executor,
+                      strftime('%Y-%m-%d %H:%M:%f', COALESCE(ep.completed_at, ep.started_at))
+                 FROM execution_processes ep
+                 JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+                 JOIN tasks t ON t.id = ta.task_id
+                WHERE ep.id = $2 AND ep.run_reason = 'devserver'"#,
+            id,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Commits behind the events of `source_id`: the commits a coding agent run made, or the
+    /// merge commit of a direct merge. `None` when the source made no commits.
+    pub async fn find_commit_range(
+        pool: &SqlitePool,
+        source_id: Uuid,
+    ) -> Result<Option<ActivityCommitRange>, sqlx::Error> {
+        sqlx::query_as!(
+            ActivityCommitRange,
+            r#"SELECT p.git_repo_path as "repo_path!",
+                      ep.before_head_commit as "base?",
+                      ep.after_head_commit as "head!"
+                 FROM execution_processes ep
+                 JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+                 JOIN tasks t ON t.id = ta.task_id
+                 JOIN projects p ON p.id = t.project_id
+                WHERE ep.id = $1 AND ep.run_reason = 'codingagent'
+                  AND ep.after_head_commit IS NOT NULL
+                  AND ep.after_head_commit IS NOT ep.before_head_commit
+               UNION ALL
+               SELECT p.git_repo_path, NULL, m.merge_commit
+                 FROM merges m
+                 JOIN task_attempts ta ON ta.id = m.task_attempt_id
+                 JOIN tasks t ON t.id = ta.task_id
+                 JOIN projects p ON p.id = t.project_id
+                WHERE m.id = $1 AND m.merge_type = 'direct' AND m.merge_commit IS NOT NULL"#,
+            source_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Attribute the events of `source_id` that have no actors yet to `actors`
+    pub async fn set_actors(
+        pool: &SqlitePool,
+        source_id: Uuid,
+        actors: &[ActivityActorRow],
+    ) -> Result<u64, sqlx::Error> {
+        let actors = Json(actors);
+        let result = sqlx::query!(
+            "UPDATE activity_events SET actors = $1 WHERE source_id = $2 AND actors IS NULL",
+            actors,
+            source_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Delete events created before `before`, along with the read and notification marks made
+    /// before it, then all but the newest `max_events` events. Returns the number of events
+    /// deleted.
+    pub async fn prune(
+        pool: &SqlitePool,
+        before: Option<DateTime<Utc>>,
+        max_events: Option<u32>,
+    ) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let mut deleted = 0;
+        if let Some(before) = before {
+            deleted += sqlx::query!(
+                r#"DELETE FROM activity_events
+                    WHERE created_at < strftime('%Y-%m-%d %H:%M:%f', $1)"#,
+                before
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+            // Marks of overdue alerts have no stored event, so they expire by age as well
+            sqlx::query!(
+                r#"DELETE FROM activity_event_reads
+                    WHERE read_at < strftime('%Y-%m-%d %H:%M:%f', $1)"#,
+                before
+            )
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query!(
+                r#"DELETE FROM activity_event_notifications
+                    WHERE notified_at < strftime('%Y-%m-%d %H:%M:%f', $1)"#,
+                before
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        if let Some(max_events) = max_events {
+            let max_events = max_events as i64;
+            deleted += sqlx::query!(
+                r#"DELETE FROM activity_events
+                    WHERE id IN (SELECT id FROM activity_events
+                                  ORDER BY created_at DESC
+                                  LIMIT -1 OFFSET $1)"#,
+                max_events
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    /// Events of a project at or after `since`, newest first. Cleanup warnings are left out once
+    /// the attempt was kept or its worktree deleted.
+    pub async fn find_since(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ActivityEventRecord,
+            r#"SELECT ae.id as "id!: Uuid",
+                      ae.project_id as "project_id!: Uuid",
+                      ae.entity_type as "entity_type!: ActivityEventEntity",
+                      ae.entity_id as "entity_id!: Uuid",
+                      ae.source_id as "source_id!: Uuid",
+                      ae.task_id as "task_id: Uuid",
+                      ae.attempt_id as "attempt_id: Uuid",
+                      ae.headline,
+                      ae.body,
+                      ae.status,
+                      ae.executor,
+                      ae.url,
+                      ae.urgency_hint as "urgency_hint: UrgencyHint",
+                      ae.actors as "actors: Json<Vec<ActivityActorRow>>",
+                      ae.created_at as "created_at!: DateTime<Utc>"
+                 FROM activity_events ae
+                WHERE ae.project_id = $1
+                  AND ae.created_at >= strftime('%Y-%m-%d %H:%M:%f', $2)
+                  AND (ae.status IS NOT 'cleanupscheduled' OR EXISTS (
+                        SELECT 1
+                          FROM attempt_cleanup_warnings w
+                          JOIN task_attempts ta ON ta.id = w.task_attempt_id
+                         WHERE w.id = ae.source_id AND ta.worktree_deleted = FALSE))
+                ORDER BY ae.created_at DESC"#,
+            project_id,
+            since
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,25 @@
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Activity events that were sent out as notifications
+pub struct ActivityEventNotification;
+
+impl ActivityEventNotification {
+    /// Claim the event for notifying. Returns false when it was already sent, so concurrent or
+    /// repeated passes notify once.
+    pub async fn claim(
+        pool: &SqlitePool,
+        event_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO activity_event_notifications (event_id, project_id)
+               VALUES ($1, $2)"#,
+            event_id,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashSet;
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Which activity events a user has acknowledged
+pub struct ActivityEventRead;
+
+impl ActivityEventRead {
+    /// Ids of the project's events `user_id` has read
+    pub async fn find_read_ids(
+        pool: &SqlitePool,
+        user_id: &str,
+        project_id: Uuid,
+    ) -> Result<HashSet<Uuid>, sqlx::Error> {
+        let ids = sqlx::query_scalar!(
+            r#"SELECT event_id as "event_id!: Uuid"
+                 FROM activity_event_reads
+                WHERE user_id = $1 AND project_id = $2"#,
+            user_id,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(ids.into_iter().collect())
+    }
+
+    /// Mark `event_ids` read for `user_id`, returning how many were unread
+    pub async fn mark_read(
+        pool: &SqlitePool,
+        user_id: &str,
+        project_id: Uuid,
+        event_ids: &[Uuid],
+    ) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let mut marked = 0;
+        for event_id in event_ids {
+            marked += sqlx::query!(
+                r#"INSERT OR IGNORE INTO activity_event_reads (user_id, event_id, project_id)
+                   VALUES ($1, $2, $3)"#,
+                user_id,
+                event_id,
+                project_id
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(marked)
+    }
+}
@@ -0,0 +1,150 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Hours without activity after which an attempt is warned about the upcoming cleanup
+pub const CLEANUP_WARNING_AFTER_HOURS: i64 = 48;
+/// Minimum time between the warning and the worktree being deleted
+pub const CLEANUP_WARNING_LEAD_HOURS: i64 = 24;
+
+/// Notice that an inactive attempt's worktree will be deleted at `cleanup_at`.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptCleanupWarning {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    #[ts(type = "Date")]
+    pub cleanup_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Attempt that has been inactive long enough to be warned and has no warning newer than its
+/// last activity.
+#[derive(Debug, Clone, FromRow)]
+pub struct AttemptNearingCleanup {
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub task_title: String,
+    pub last_activity_at: DateTime<Utc>,
+}
+
+impl AttemptCleanupWarning {
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        cleanup_at: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            AttemptCleanupWarning,
+            r#"INSERT INTO attempt_cleanup_warnings (id, task_attempt_id, cleanup_at)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid",
+                      task_attempt_id as "task_attempt_id!: Uuid",
+                      cleanup_at as "cleanup_at!: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            cleanup_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_latest_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptCleanupWarning,
+            r#"SELECT id as "id!: Uuid",
+                      task_attempt_id as "task_attempt_id!: Uuid",
+                      cleanup_at as "cleanup_at!: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_cleanup_warnings
+               WHERE task_attempt_id = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM attempt_cleanup_warnings WHERE task_attempt_id = $1",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Whether the attempt was warned and the warning's grace period has passed
+    pub async fn cleanup_due(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        Ok(Self::find_latest_by_task_attempt_id(pool, task_attempt_id)
+            .await?
+            .is_some_and(|warning| warning.cleanup_at <= now))
+    }
+
+    /// Attempts idle for `CLEANUP_WARNING_AFTER_HOURS` that still need a warning. Activity is the
+    /// later of the attempt's `updated_at` and its last completed execution, so touching the
+    /// attempt postpones the warning.
+    pub async fn find_attempts_to_warn(
+        pool: &SqlitePool,
+    ) -> Result<Vec<AttemptNearingCleanup>, sqlx::Error> {
+        let threshold = Utc::now() - Duration::hours(CLEANUP_WARNING_AFTER_HOURS);
+        sqlx::query_as!(
+            AttemptNearingCleanup,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      task_title as "task_title!",
+                      last_activity_at as "last_activity_at!: DateTime<Utc>"
+               FROM (
+                   SELECT ta.id AS task_attempt_id,
+                          t.id AS task_id,
+                          t.project_id AS project_id,
+                          t.title AS task_title,
+                          MAX(
+                              datetime(ta.updated_at),
+                              COALESCE(
+                                  (SELECT MAX(datetime(ep.completed_at))
+                                   FROM execution_processes ep
+                                   WHERE ep.task_attempt_id = ta.id),
+                                  datetime(ta.updated_at)
+                              )
+                          ) AS last_activity_at
+                   FROM task_attempts ta
+                   JOIN tasks t ON t.id = ta.task_id
+                   WHERE ta.worktree_deleted = FALSE
+                     AND ta.container_ref IS NOT NULL
+                     AND NOT EXISTS (
+                         SELECT 1 FROM execution_processes running
+                         WHERE running.task_attempt_id = ta.id AND running.completed_at IS NULL
+                     )
+               ) idle
+               WHERE last_activity_at < datetime($1)
+                 AND NOT EXISTS (
+                     SELECT 1 FROM attempt_cleanup_warnings w
+                     WHERE w.task_attempt_id = idle.task_attempt_id
+                       AND datetime(w.created_at) >= idle.last_activity_at
+                 )
+               ORDER BY last_activity_at ASC"#,
+            threshold
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
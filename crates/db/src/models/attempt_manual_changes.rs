@@ -0,0 +1,238 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Uncommitted edits found in an attempt worktree while no agent process was running.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AttemptManualChanges {
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub changed_paths: Vec<String>,
+    #[ts(type = "Date")]
+    pub detected_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct AttemptManualChangesRow {
+    task_attempt_id: Uuid,
+    task_id: Uuid,
+    changed_paths: String,
+    detected_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<AttemptManualChangesRow> for AttemptManualChanges {
+    fn from(row: AttemptManualChangesRow) -> Self {
+        AttemptManualChanges {
+            task_attempt_id: row.task_attempt_id,
+            task_id: row.task_id,
+            changed_paths: serde_json::from_str(&row.changed_paths).unwrap_or_default(),
+            detected_at: row.detected_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl AttemptManualChanges {
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptManualChangesRow,
+            r#"SELECT amc.task_attempt_id as "task_attempt_id!: Uuid",
+                      ta.task_id as "task_id!: Uuid",
+                      amc.changed_paths,
+                      amc.detected_at as "detected_at!: DateTime<Utc>",
+                      amc.updated_at as "updated_at!: DateTime<Utc>"
+               FROM attempt_manual_changes amc
+               JOIN task_attempts ta ON ta.id = amc.task_attempt_id
+               WHERE amc.task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.map(Into::into))
+    }
+
+    /// All attempts in a project that currently have manual changes, newest first.
+    pub async fn list_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            AttemptManualChangesRow,
+            r#"SELECT amc.task_attempt_id as "task_attempt_id!: Uuid",
+                      ta.task_id as "task_id!: Uuid",
+                      amc.changed_paths,
+                      amc.detected_at as "detected_at!: DateTime<Utc>",
+                      amc.updated_at as "updated_at!: DateTime<Utc>"
+               FROM attempt_manual_changes amc
+               JOIN task_attempts ta ON ta.id = amc.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1
+               ORDER BY amc.updated_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Attempt ids that currently carry a manual-changes record.
+    pub async fn list_attempt_ids(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid" FROM attempt_manual_changes"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Store the attempt's current set of manually changed paths, creating the record if needed.
+    /// Returns true when the record did not exist before this call.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        paths: &[String],
+    ) -> Result<bool, sqlx::Error> {
+        let paths: BTreeSet<&String> = paths.iter().collect();
+        let paths_json = serde_json::to_string(&paths).unwrap_or_else(|_| "[]".to_string());
+
+        let mut tx = pool.begin().await?;
+
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM attempt_manual_changes WHERE task_attempt_id = $1) as "exists!: bool""#,
+            task_attempt_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"INSERT INTO attempt_manual_changes (task_attempt_id, changed_paths)
+               VALUES ($1, $2)
+               ON CONFLICT(task_attempt_id) DO UPDATE SET
+                   changed_paths = excluded.changed_paths,
+                   updated_at = datetime('now', 'subsec')"#,
+            task_attempt_id,
+            paths_json
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(!exists)
+    }
+
+    pub async fn clear(pool: &SqlitePool, task_attempt_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM attempt_manual_changes WHERE task_attempt_id = $1",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::models::{
+        project::{CreateProject, Project},
+        task::{CreateTask, Task},
+        task_attempt::{CreateTaskAttempt, TaskAttempt},
+    };
+
+    async fn setup_attempt() -> (SqlitePool, Project, TaskAttempt) {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let project_id = Uuid::new_v4();
+        let project = Project::create(
+            &pool,
+            &CreateProject {
+                name: "Manual".to_string(),
+                git_repo_path: format!("/tmp/{}", project_id),
+                use_existing_repo: false,
+                setup_script: None,
+                dev_script: None,
+                cleanup_script: None,
+                copy_files: None,
+            },
+            project_id,
+        )
+        .await
+        .unwrap();
+        let task = Task::create(
+            &pool,
+            &CreateTask::from_title_description(project.id, "Task".to_string(), None),
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let attempt = TaskAttempt::create(
+            &pool,
+            &CreateTaskAttempt {
+                executor: BaseCodingAgent::ClaudeCode,
+                base_branch: "main".to_string(),
+                branch: "feature/manual".to_string(),
+                repositories: None,
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+
+        (pool, project, attempt)
+    }
+
+    #[tokio::test]
+    async fn upsert_replaces_paths_and_clear_removes_record() {
+        let (pool, project, attempt) = setup_attempt().await;
+
+        let first = AttemptManualChanges::upsert(&pool, attempt.id, &["src/a.rs".to_string()])
+            .await
+            .unwrap();
+        let second = AttemptManualChanges::upsert(
+            &pool,
+            attempt.id,
+            &["src/c.rs".to_string(), "src/b.rs".to_string()],
+        )
+        .await
+        .unwrap();
+        assert!(first);
+        assert!(!second);
+
+        let listed = AttemptManualChanges::list_for_project(&pool, project.id)
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].changed_paths, vec!["src/b.rs", "src/c.rs"]);
+
+        AttemptManualChanges::clear(&pool, attempt.id)
+            .await
+            .unwrap();
+        assert!(
+            AttemptManualChanges::find_by_task_attempt_id(&pool, attempt.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Result of running the project's verify script against an attempt.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct AttemptVerification {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub passed: bool,
+    pub exit_code: Option<i64>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl AttemptVerification {
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        execution_process_id: Uuid,
+        passed: bool,
+        exit_code: Option<i64>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            AttemptVerification,
+            r#"INSERT INTO attempt_verifications (id, task_attempt_id, execution_process_id, passed, exit_code)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         task_attempt_id as "task_attempt_id!: Uuid",
+                         execution_process_id as "execution_process_id!: Uuid",
+                         passed as "passed!: bool",
+                         exit_code,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            execution_process_id,
+            passed,
+            exit_code
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_latest_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AttemptVerification,
+            r#"SELECT id as "id!: Uuid",
+                      task_attempt_id as "task_attempt_id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      passed as "passed!: bool",
+                      exit_code,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_verifications
+               WHERE task_attempt_id = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::pagination::Page;
+
+/// One state-changing API call
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    /// `None` when the server runs without user accounts
+    pub actor_user_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub method: String,
+    pub path: String,
+    /// Collection the call acted on, e.g. `tasks` or `task-attempts`
+    pub entity_type: Option<String>,
+    pub entity_id: Option<Uuid>,
+    #[ts(type = "number")]
+    pub status_code: i64,
+    #[ts(type = "JsonValue | null")]
+    pub before_summary: Option<Json<Value>>,
+    #[ts(type = "JsonValue | null")]
+    pub after_summary: Option<Json<Value>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateAuditLogEntry {
+    pub actor_user_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<Uuid>,
+    pub status_code: u16,
+    pub before_summary: Option<Value>,
+    pub after_summary: Option<Value>,
+}
+
+/// Conditions left out match every entry
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub actor_user_id: Option<Uuid>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<Uuid>,
+}
+
+impl AuditLogEntry {
+    pub async fn create(pool: &SqlitePool, data: CreateAuditLogEntry) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let status_code = i64::from(data.status_code);
+        let before_summary = data.before_summary.map(Json);
+        let after_summary = data.after_summary.map(Json);
+        sqlx::query_as!(
+            AuditLogEntry,
+            r#"INSERT INTO audit_log (
+                   id, actor_user_id, actor_name, method, path, entity_type, entity_id,
+                   status_code, before_summary, after_summary
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid",
+                         actor_user_id as "actor_user_id: Uuid",
+                         actor_name,
+                         method,
+                         path,
+                         entity_type,
+                         entity_id as "entity_id: Uuid",
+                         status_code,
+                         before_summary as "before_summary: Json<Value>",
+                         after_summary as "after_summary: Json<Value>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.actor_user_id,
+            data.actor_name,
+            data.method,
+            data.path,
+            data.entity_type,
+            data.entity_id,
+            status_code,
+            before_summary,
+            after_summary
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Newest first, with the number of entries matching `filter` across all pages
+    pub async fn find(
+        pool: &SqlitePool,
+        filter: &AuditLogFilter,
+        page: Page,
+    ) -> Result<(Vec<Self>, i64), sqlx::Error> {
+        let limit = page.sql_limit();
+        let offset = page.sql_offset();
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM audit_log
+               WHERE ($1 IS NULL OR actor_user_id = $1)
+                 AND ($2 IS NULL OR entity_type = $2)
+                 AND ($3 IS NULL OR entity_id = $3)"#,
+            filter.actor_user_id,
+            filter.entity_type,
+            filter.entity_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let entries = sqlx::query_as!(
+            AuditLogEntry,
+            r#"SELECT id as "id!: Uuid",
+                      actor_user_id as "actor_user_id: Uuid",
+                      actor_name,
+                      method,
+                      path,
+                      entity_type,
+                      entity_id as "entity_id: Uuid",
+                      status_code,
+                      before_summary as "before_summary: Json<Value>",
+                      after_summary as "after_summary: Json<Value>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM audit_log
+               WHERE ($1 IS NULL OR actor_user_id = $1)
+                 AND ($2 IS NULL OR entity_type = $2)
+                 AND ($3 IS NULL OR entity_id = $3)
+               ORDER BY created_at DESC, rowid DESC
+               LIMIT $4 OFFSET $5"#,
+            filter.actor_user_id,
+            filter.entity_type,
+            filter.entity_id,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok((entries, total))
+    }
+
+    /// Delete entries recorded before `before`. Returns the number deleted.
+    pub async fn prune(pool: &SqlitePool, before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM audit_log
+                WHERE created_at < strftime('%Y-%m-%d %H:%M:%f', $1)"#,
+            before
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// The fields worth comparing before and after a call on the entity, or `None` for entity
+    /// types without a summary and entities that don't exist
+    pub async fn entity_summary(
+        pool: &SqlitePool,
+        entity_type: &str,
+        id: Uuid,
+    ) -> Result<Option<Value>, sqlx::Error> {
+        let summary = match entity_type {
+            "tasks" => {
+                sqlx::query_scalar!(
+                    r#"SELECT json_object('title', title, 'status', status) as "summary!: Json<Value>"
+                       FROM tasks WHERE id = $1"#,
+                    id
+                )
+                .fetch_optional(pool)
+                .await?
+            }
+            "projects" => {
+                sqlx::query_scalar!(
+                    r#"SELECT json_object('name', name, 'git_repo_path', git_repo_path) as "summary!: Json<Value>"
+                       FROM projects WHERE id = $1"#,
+                    id
+                )
+                .fetch_optional(pool)
+                .await?
+            }
+            "task-attempts" => {
+                sqlx::query_scalar!(
+                    r#"SELECT json_object(
+                           'branch', branch,
+                           'target_branch', target_branch,
+                           'worktree_deleted', worktree_deleted,
+                           'merged', EXISTS (
+                               SELECT 1 FROM merges m WHERE m.task_attempt_id = task_attempts.id
+                           )
+                       ) as "summary!: Json<Value>"
+                       FROM task_attempts WHERE id = $1"#,
+                    id
+                )
+                .fetch_optional(pool)
+                .await?
+            }
+            "users" => {
+                sqlx::query_scalar!(
+                    r#"SELECT json_object('username', username, 'role', role) as "summary!: Json<Value>"
+                       FROM users WHERE id = $1"#,
+                    id
+                )
+                .fetch_optional(pool)
+                .await?
+            }
+            "labels" => {
+                sqlx::query_scalar!(
+                    r#"SELECT json_object('name', name, 'color', color) as "summary!: Json<Value>"
+                       FROM labels WHERE id = $1"#,
+                    id
+                )
+                .fetch_optional(pool)
+                .await?
+            }
+            _ => return Ok(None),
+        };
+        Ok(summary.map(|Json(value)| value))
+    }
+}
@@ -0,0 +1,38 @@
+use chrono::NaiveDate;
+use executors::executors::BaseCodingAgent;
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+
+/// How far into its monthly budget an agent is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetLevel {
+    Warning,
+    Exceeded,
+}
+
+pub struct BudgetAlert;
+
+impl BudgetAlert {
+    /// Note that the alert for `level` was sent for the month starting on `month`. Returns `false`
+    /// when it had been sent already.
+    pub async fn record(
+        pool: &SqlitePool,
+        agent: BaseCodingAgent,
+        month: NaiveDate,
+        level: BudgetLevel,
+    ) -> Result<bool, sqlx::Error> {
+        let agent = agent.to_string();
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO budget_alerts (agent, month, level) VALUES ($1, $2, $3)"#,
+            agent,
+            month,
+            level
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
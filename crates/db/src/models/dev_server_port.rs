@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Port handed to a dev server process through `VIBE_DEV_SERVER_PORT`
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DevServerPort {
+    pub execution_process_id: Uuid,
+    #[ts(type = "number")]
+    pub port: i64,
+    /// Where the dev server is expected to listen
+    pub url: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl DevServerPort {
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        port: u16,
+        url: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let port = i64::from(port);
+        sqlx::query_as!(
+            DevServerPort,
+            r#"INSERT INTO dev_server_ports (execution_process_id, port, url)
+               VALUES ($1, $2, $3)
+               RETURNING execution_process_id as "execution_process_id!: Uuid",
+                         port,
+                         url,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            execution_process_id,
+            port,
+            url
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServerPort,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid",
+                      port,
+                      url,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM dev_server_ports
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Ports held by dev servers that are still running
+    pub async fn ports_in_use(pool: &SqlitePool) -> Result<Vec<u16>, sqlx::Error> {
+        let ports = sqlx::query_scalar!(
+            r#"SELECT dsp.port
+               FROM dev_server_ports dsp
+               JOIN execution_processes ep ON ep.id = dsp.execution_process_id
+               WHERE ep.status = 'running'"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(ports
+            .into_iter()
+            .filter_map(|port| u16::try_from(port).ok())
+            .collect())
+    }
+}
@@ -0,0 +1,202 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Which version of the file a comment's line numbers refer to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum DiffCommentSide {
+    Old,
+    New,
+}
+
+/// Comment on a range of lines in an attempt's diff
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DiffComment {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub repository_id: Option<Uuid>,
+    pub file_path: String,
+    pub side: DiffCommentSide,
+    /// 1-based, inclusive
+    #[ts(type = "number")]
+    pub start_line: i64,
+    /// 1-based, inclusive
+    #[ts(type = "number")]
+    pub end_line: i64,
+    /// The commented lines as they were when the comment was written
+    pub code: Option<String>,
+    pub body: String,
+    /// When the comment was included in a follow-up; unsent comments are still pending
+    #[ts(type = "Date | null")]
+    pub sent_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateDiffComment {
+    pub repository_id: Option<Uuid>,
+    pub file_path: String,
+    pub side: DiffCommentSide,
+    #[ts(type = "number")]
+    pub start_line: i64,
+    /// Defaults to `start_line` for single-line comments
+    #[ts(type = "number | null")]
+    pub end_line: Option<i64>,
+    pub code: Option<String>,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateDiffComment {
+    pub body: String,
+}
+
+impl DiffComment {
+    /// Comments on the attempt in file and line order. Sent comments are left out unless
+    /// `include_sent` is set.
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        include_sent: bool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DiffComment,
+            r#"SELECT id as "id!: Uuid",
+                      task_attempt_id as "task_attempt_id!: Uuid",
+                      repository_id as "repository_id: Uuid",
+                      file_path,
+                      side as "side!: DiffCommentSide",
+                      start_line,
+                      end_line,
+                      code,
+                      body,
+                      sent_at as "sent_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM diff_comments
+               WHERE task_attempt_id = $1 AND ($2 OR sent_at IS NULL)
+               ORDER BY file_path ASC, start_line ASC, created_at ASC"#,
+            task_attempt_id,
+            include_sent
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DiffComment,
+            r#"SELECT id as "id!: Uuid",
+                      task_attempt_id as "task_attempt_id!: Uuid",
+                      repository_id as "repository_id: Uuid",
+                      file_path,
+                      side as "side!: DiffCommentSide",
+                      start_line,
+                      end_line,
+                      code,
+                      body,
+                      sent_at as "sent_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM diff_comments
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        data: &CreateDiffComment,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let end_line = data.end_line.unwrap_or(data.start_line);
+        let body = data.body.trim();
+        sqlx::query_as!(
+            DiffComment,
+            r#"INSERT INTO diff_comments
+                   (id, task_attempt_id, repository_id, file_path, side, start_line, end_line,
+                    code, body)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid",
+                         task_attempt_id as "task_attempt_id!: Uuid",
+                         repository_id as "repository_id: Uuid",
+                         file_path,
+                         side as "side!: DiffCommentSide",
+                         start_line,
+                         end_line,
+                         code,
+                         body,
+                         sent_at as "sent_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            data.repository_id,
+            data.file_path,
+            data.side,
+            data.start_line,
+            end_line,
+            data.code,
+            body
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update_body(pool: &SqlitePool, id: Uuid, body: &str) -> Result<Self, sqlx::Error> {
+        let body = body.trim();
+        sqlx::query_as!(
+            DiffComment,
+            r#"UPDATE diff_comments
+               SET body = $1, updated_at = datetime('now', 'subsec')
+               WHERE id = $2
+               RETURNING id as "id!: Uuid",
+                         task_attempt_id as "task_attempt_id!: Uuid",
+                         repository_id as "repository_id: Uuid",
+                         file_path,
+                         side as "side!: DiffCommentSide",
+                         start_line,
+                         end_line,
+                         code,
+                         body,
+                         sent_at as "sent_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            body,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM diff_comments WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record that the comments were included in a follow-up
+    pub async fn mark_sent(pool: &SqlitePool, ids: &[Uuid]) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for id in ids {
+            sqlx::query!(
+                "UPDATE diff_comments SET sent_at = datetime('now', 'subsec') WHERE id = $1",
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+}
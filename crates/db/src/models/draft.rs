@@ -48,6 +48,8 @@ pub struct Draft {
     pub variant: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_ids: Option<Vec<Uuid>>,
+    /// When a queued follow-up should start; `None` starts it as soon as nothing is running
+    pub run_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub version: i64,
@@ -64,6 +66,7 @@ struct DraftRow {
     pub sending: bool,
     pub variant: Option<String>,
     pub image_ids: Option<String>,
+    pub run_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub version: i64,
@@ -85,6 +88,7 @@ impl From<DraftRow> for Draft {
             sending: r.sending,
             variant: r.variant,
             image_ids,
+            run_at: r.run_at,
             created_at: r.created_at,
             updated_at: r.updated_at,
             version: r.version,
@@ -117,6 +121,7 @@ impl Draft {
                 sending                  as "sending!: bool",
                 variant,
                 image_ids,
+                run_at                   as "run_at?: DateTime<Utc>",
                 created_at               as "created_at!: DateTime<Utc>",
                 updated_at               as "updated_at!: DateTime<Utc>",
                 version                  as "version!: i64"
@@ -147,6 +152,7 @@ impl Draft {
                 sending                  as "sending!: bool",
                 variant,
                 image_ids,
+                run_at                   as "run_at?: DateTime<Utc>",
                 created_at               as "created_at!: DateTime<Utc>",
                 updated_at               as "updated_at!: DateTime<Utc>",
                 version                  as "version!: i64"
@@ -160,6 +166,87 @@ impl Draft {
         .map(|opt| opt.map(Draft::from))
     }
 
+    /// Queued follow-ups whose scheduled start time has passed and that are not already being
+    /// sent, oldest schedule first
+    pub async fn find_due_scheduled(
+        pool: &SqlitePool,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DraftRow,
+            r#"SELECT
+                id                       as "id!: Uuid",
+                task_attempt_id          as "task_attempt_id!: Uuid",
+                draft_type,
+                retry_process_id         as "retry_process_id?: Uuid",
+                prompt,
+                queued                   as "queued!: bool",
+                sending                  as "sending!: bool",
+                variant,
+                image_ids,
+                run_at                   as "run_at?: DateTime<Utc>",
+                created_at               as "created_at!: DateTime<Utc>",
+                updated_at               as "updated_at!: DateTime<Utc>",
+                version                  as "version!: i64"
+              FROM drafts
+             WHERE draft_type = 'follow_up'
+               AND queued = 1
+               AND sending = 0
+               AND run_at IS NOT NULL
+               AND datetime(run_at) <= datetime($1)
+               AND TRIM(prompt) != ''
+             ORDER BY datetime(run_at)"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| rows.into_iter().map(Draft::from).collect())
+    }
+
+    /// Move a queued follow-up, with its images and schedule, from one attempt to another. Nothing
+    /// moves if the follow-up is not queued, is already being sent, or the target attempt has a
+    /// follow-up draft of its own.
+    pub async fn transfer_queued_follow_up(
+        pool: &SqlitePool,
+        from_attempt_id: Uuid,
+        to_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DraftRow,
+            r#"UPDATE drafts
+                SET task_attempt_id = $2,
+                    updated_at = CURRENT_TIMESTAMP,
+                    version = version + 1
+                WHERE task_attempt_id = $1
+                  AND draft_type = 'follow_up'
+                  AND queued = 1
+                  AND sending = 0
+                  AND NOT EXISTS (
+                      SELECT 1 FROM drafts
+                      WHERE task_attempt_id = $2 AND draft_type = 'follow_up'
+                  )
+                RETURNING
+                  id                       as "id!: Uuid",
+                  task_attempt_id          as "task_attempt_id!: Uuid",
+                  draft_type,
+                  retry_process_id         as "retry_process_id?: Uuid",
+                  prompt,
+                  queued                   as "queued!: bool",
+                  sending                  as "sending!: bool",
+                  variant,
+                  image_ids,
+                  run_at                   as "run_at?: DateTime<Utc>",
+                  created_at               as "created_at!: DateTime<Utc>",
+                  updated_at               as "updated_at!: DateTime<Utc>",
+                  version                  as "version!: i64""#,
+            from_attempt_id,
+            to_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|opt| opt.map(Draft::from))
+    }
+
     pub async fn upsert(pool: &SqlitePool, data: &UpsertDraft) -> Result<Self, sqlx::Error> {
         // Validate retry_process_id requirement
         if data.draft_type == DraftType::Retry && data.retry_process_id.is_none() {
@@ -197,6 +284,7 @@ impl Draft {
                  sending                  as "sending!: bool",
                  variant,
                  image_ids,
+                 run_at                   as "run_at?: DateTime<Utc>",
                  created_at               as "created_at!: DateTime<Utc>",
                  updated_at               as "updated_at!: DateTime<Utc>",
                  version                  as "version!: i64""#,
@@ -214,6 +302,26 @@ impl Draft {
         .map(Draft::from)
     }
 
+    /// Record the signed-in user who last saved the draft
+    pub async fn set_updated_by(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        draft_type: DraftType,
+        user_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let draft_type_str = draft_type.as_str();
+        sqlx::query!(
+            r#"UPDATE drafts SET updated_by_user_id = $1
+                WHERE task_attempt_id = $2 AND draft_type = $3"#,
+            user_id,
+            task_attempt_id,
+            draft_type_str
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn clear_after_send(
         pool: &SqlitePool,
         task_attempt_id: Uuid,
@@ -226,7 +334,7 @@ impl Draft {
                 // Follow-up drafts: update to empty
                 sqlx::query(
                     r#"UPDATE drafts
-                       SET prompt = '', queued = 0, sending = 0, image_ids = NULL, updated_at = CURRENT_TIMESTAMP, version = version + 1
+                       SET prompt = '', queued = 0, sending = 0, image_ids = NULL, run_at = NULL, updated_at = CURRENT_TIMESTAMP, version = version + 1
                      WHERE task_attempt_id = ? AND draft_type = ?"#,
                 )
                 .bind(task_attempt_id)
@@ -333,7 +441,8 @@ impl Draft {
         Ok(())
     }
 
-    /// Set queued flag (and bump metadata) for a draft by attempt and type.
+    /// Set queued flag (and bump metadata) for a draft by attempt and type. `run_at` schedules a
+    /// queued draft; unqueueing clears it.
     pub async fn set_queued(
         pool: &SqlitePool,
         task_attempt_id: Uuid,
@@ -341,6 +450,7 @@ impl Draft {
         queued: bool,
         expected_queued: Option<bool>,
         expected_version: Option<i64>,
+        run_at: Option<DateTime<Utc>>,
     ) -> Result<u64, sqlx::Error> {
         let result = sqlx::query(
             r#"UPDATE drafts
@@ -348,6 +458,7 @@ impl Draft {
                                    WHEN ?1 THEN (TRIM(prompt) <> '')
                                    ELSE 0
                                  END,
+                       run_at     = CASE WHEN ?1 THEN ?6 ELSE NULL END,
                        updated_at = CURRENT_TIMESTAMP,
                        version    = version + 1
                  WHERE task_attempt_id = ?2
@@ -360,6 +471,7 @@ impl Draft {
         .bind(draft_type.as_str())
         .bind(expected_queued.map(|value| value as i64))
         .bind(expected_version)
+        .bind(run_at)
         .execute(pool)
         .await?;
 
@@ -46,6 +46,7 @@ pub enum ExecutionProcessRunReason {
     CleanupScript,
     CodingAgent,
     DevServer,
+    VerifyScript,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -200,7 +201,8 @@ impl ExecutionProcess {
         .await
     }
 
-    /// Find all execution processes for a task attempt (optionally include soft-deleted)
+    /// Find all execution processes for a task attempt (optionally include soft-deleted).
+    /// Archived processes are never included.
     pub async fn find_by_task_attempt_id(
         pool: &SqlitePool,
         task_attempt_id: Uuid,
@@ -224,6 +226,7 @@ impl ExecutionProcess {
                FROM execution_processes
                WHERE task_attempt_id = ?
                  AND (? OR dropped = FALSE)
+                 AND archived_at IS NULL
                ORDER BY created_at ASC"#,
             task_attempt_id,
             show_soft_deleted
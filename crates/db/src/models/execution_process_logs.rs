@@ -41,6 +41,69 @@ impl ExecutionProcessLogs {
         .await
     }
 
+    /// Find logs by execution process ID, falling back to the archive when the process has no
+    /// logs in the main table
+    pub async fn find_including_archived(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        if let Some(logs) = Self::find_by_execution_id(pool, execution_id).await? {
+            return Ok(Some(logs));
+        }
+        sqlx::query_as!(
+            ExecutionProcessLogs,
+            r#"SELECT execution_id as "execution_id!: Uuid",
+                      logs,
+                      byte_size,
+                      inserted_at as "inserted_at!: DateTime<Utc>"
+               FROM execution_process_logs_archive
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Archive processes that finished before `before`: their logs move into the archive table
+    /// and the processes are marked archived, which leaves them out of attempt listings. Running
+    /// processes are never archived. Returns the number of processes archived.
+    pub async fn archive_finished_before(
+        pool: &SqlitePool,
+        before: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let archived = sqlx::query!(
+            r#"UPDATE execution_processes
+                  SET archived_at = datetime('now', 'subsec')
+                WHERE archived_at IS NULL
+                  AND status != 'running'
+                  AND completed_at IS NOT NULL
+                  AND datetime(completed_at) < datetime($1)"#,
+            before
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        sqlx::query!(
+            r#"INSERT OR REPLACE INTO execution_process_logs_archive
+                   (execution_id, logs, byte_size, inserted_at)
+               SELECT epl.execution_id, epl.logs, epl.byte_size, epl.inserted_at
+                 FROM execution_process_logs epl
+                 JOIN execution_processes ep ON ep.id = epl.execution_id
+                WHERE ep.archived_at IS NOT NULL"#
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"DELETE FROM execution_process_logs
+                WHERE execution_id IN (SELECT execution_id FROM execution_process_logs_archive)"#
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(archived)
+    }
+
     /// Create or update execution process logs
     pub async fn upsert(
         pool: &SqlitePool,
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Links an execution process to the one whose executor action it re-ran
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ExecutionProcessReplay {
+    pub execution_process_id: Uuid,
+    pub replayed_from_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExecutionProcessReplay {
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        replayed_from_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessReplay,
+            r#"INSERT INTO execution_process_replays (execution_process_id, replayed_from_id)
+               VALUES ($1, $2)
+               RETURNING execution_process_id as "execution_process_id!: Uuid",
+                         replayed_from_id as "replayed_from_id!: Uuid",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            execution_process_id,
+            replayed_from_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Replays among the attempt's processes, oldest first
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessReplay,
+            r#"SELECT r.execution_process_id as "execution_process_id!: Uuid",
+                      r.replayed_from_id as "replayed_from_id!: Uuid",
+                      r.created_at as "created_at!: DateTime<Utc>"
+               FROM execution_process_replays r
+               JOIN execution_processes ep ON ep.id = r.execution_process_id
+               WHERE ep.task_attempt_id = $1
+               ORDER BY r.created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
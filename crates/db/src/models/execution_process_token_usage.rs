@@ -0,0 +1,272 @@
+use chrono::{DateTime, Utc};
+use executors::executors::BaseCodingAgent;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Tokens used with one model. Input counts exclude the cached input counted separately.
+#[derive(Debug, Clone, Default, PartialEq, Eq, FromRow, Serialize, Deserialize, TS)]
+pub struct ModelTokenUsage {
+    pub model: String,
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    /// Input read from the prompt cache
+    #[ts(type = "number")]
+    pub cached_input_tokens: i64,
+    /// Input written to the prompt cache
+    #[ts(type = "number")]
+    pub cache_creation_input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+}
+
+impl ModelTokenUsage {
+    pub fn add(&mut self, other: &ModelTokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.output_tokens += other.output_tokens;
+    }
+
+    /// Every input and output token, cached or not
+    pub fn total_tokens(&self) -> i64 {
+        self.input_tokens
+            + self.cached_input_tokens
+            + self.cache_creation_input_tokens
+            + self.output_tokens
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessTokenUsage {
+    pub execution_process_id: Uuid,
+    #[sqlx(flatten)]
+    pub usage: ModelTokenUsage,
+}
+
+/// One model's usage in one execution process, with the task and project the process ran for
+#[derive(Debug, Clone, FromRow)]
+pub struct TokenUsageRecord {
+    pub execution_process_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    /// `null` for processes that were not started by a coding agent
+    pub executor: Option<String>,
+    pub task_title: String,
+    pub project_name: String,
+    #[sqlx(flatten)]
+    pub usage: ModelTokenUsage,
+}
+
+impl ExecutionProcessTokenUsage {
+    /// Replace what is recorded for the process, which is read again whenever it exits
+    pub async fn replace(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        usage: &[ModelTokenUsage],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM execution_process_token_usage WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for model in usage {
+            sqlx::query!(
+                r#"INSERT INTO execution_process_token_usage (
+                       execution_process_id, model, input_tokens, cached_input_tokens,
+                       cache_creation_input_tokens, output_tokens
+                   )
+                   VALUES ($1, $2, $3, $4, $5, $6)"#,
+                execution_process_id,
+                model.model,
+                model.input_tokens,
+                model.cached_input_tokens,
+                model.cache_creation_input_tokens,
+                model.output_tokens
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<ModelTokenUsage>, sqlx::Error> {
+        sqlx::query_as!(
+            ModelTokenUsage,
+            r#"SELECT model, input_tokens, cached_input_tokens, cache_creation_input_tokens,
+                      output_tokens
+               FROM execution_process_token_usage
+               WHERE execution_process_id = $1
+               ORDER BY model ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Usage of every process in the attempt, oldest process first
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT u.execution_process_id as "execution_process_id!: Uuid", u.model,
+                      u.input_tokens, u.cached_input_tokens, u.cache_creation_input_tokens,
+                      u.output_tokens
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               WHERE ep.task_attempt_id = $1
+               ORDER BY ep.created_at ASC, u.model ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| ExecutionProcessTokenUsage {
+                execution_process_id: row.execution_process_id,
+                usage: ModelTokenUsage {
+                    model: row.model,
+                    input_tokens: row.input_tokens,
+                    cached_input_tokens: row.cached_input_tokens,
+                    cache_creation_input_tokens: row.cache_creation_input_tokens,
+                    output_tokens: row.output_tokens,
+                },
+            })
+            .collect())
+    }
+
+    /// Usage summed per model across every attempt of the task
+    pub async fn sum_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<ModelTokenUsage>, sqlx::Error> {
+        sqlx::query_as!(
+            ModelTokenUsage,
+            r#"SELECT u.model,
+                      SUM(u.input_tokens) as "input_tokens!: i64",
+                      SUM(u.cached_input_tokens) as "cached_input_tokens!: i64",
+                      SUM(u.cache_creation_input_tokens) as "cache_creation_input_tokens!: i64",
+                      SUM(u.output_tokens) as "output_tokens!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               WHERE ta.task_id = $1
+               GROUP BY u.model
+               ORDER BY u.model ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Usage summed per model across every task in the project
+    pub async fn sum_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<ModelTokenUsage>, sqlx::Error> {
+        sqlx::query_as!(
+            ModelTokenUsage,
+            r#"SELECT u.model,
+                      SUM(u.input_tokens) as "input_tokens!: i64",
+                      SUM(u.cached_input_tokens) as "cached_input_tokens!: i64",
+                      SUM(u.cache_creation_input_tokens) as "cache_creation_input_tokens!: i64",
+                      SUM(u.output_tokens) as "output_tokens!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1
+               GROUP BY u.model
+               ORDER BY u.model ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Usage summed per model across the processes started with `executor` since `since`
+    pub async fn sum_by_executor_since(
+        pool: &SqlitePool,
+        executor: BaseCodingAgent,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ModelTokenUsage>, sqlx::Error> {
+        let executor = executor.to_string();
+        sqlx::query_as!(
+            ModelTokenUsage,
+            r#"SELECT u.model,
+                      SUM(u.input_tokens) as "input_tokens!: i64",
+                      SUM(u.cached_input_tokens) as "cached_input_tokens!: i64",
+                      SUM(u.cache_creation_input_tokens) as "cache_creation_input_tokens!: i64",
+                      SUM(u.output_tokens) as "output_tokens!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               WHERE json_extract(ep.executor_action, '$.typ.executor_profile_id.executor') = $1
+                 AND ep.created_at >= datetime($2)
+               GROUP BY u.model
+               ORDER BY u.model ASC"#,
+            executor,
+            since
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Usage of the processes started from `from` up to `to`, optionally only those started with
+    /// `executor`, oldest process first
+    pub async fn find_records_between(
+        pool: &SqlitePool,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        executor: Option<BaseCodingAgent>,
+    ) -> Result<Vec<TokenUsageRecord>, sqlx::Error> {
+        let executor = executor.map(|executor| executor.to_string());
+        let rows = sqlx::query!(
+            r#"SELECT u.execution_process_id as "execution_process_id!: Uuid",
+                      ep.created_at as "started_at!: DateTime<Utc>",
+                      json_extract(ep.executor_action, '$.typ.executor_profile_id.executor')
+                          as "executor?: String",
+                      t.title as task_title,
+                      p.name as project_name,
+                      u.model, u.input_tokens, u.cached_input_tokens,
+                      u.cache_creation_input_tokens, u.output_tokens
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               JOIN projects p ON p.id = t.project_id
+               WHERE datetime(ep.created_at) >= datetime($1)
+                 AND datetime(ep.created_at) < datetime($2)
+                 AND ($3 IS NULL
+                      OR json_extract(ep.executor_action, '$.typ.executor_profile_id.executor') = $3)
+               ORDER BY ep.created_at ASC, u.model ASC"#,
+            from,
+            to,
+            executor
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| TokenUsageRecord {
+                execution_process_id: row.execution_process_id,
+                started_at: row.started_at,
+                executor: row.executor,
+                task_title: row.task_title,
+                project_name: row.project_name,
+                usage: ModelTokenUsage {
+                    model: row.model,
+                    input_tokens: row.input_tokens,
+                    cached_input_tokens: row.cached_input_tokens,
+                    cache_creation_input_tokens: row.cache_creation_input_tokens,
+                    output_tokens: row.output_tokens,
+                },
+            })
+            .collect())
+    }
+}
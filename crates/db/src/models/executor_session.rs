@@ -31,6 +31,18 @@ pub struct UpdateExecutorSession {
     pub summary: Option<String>,
 }
 
+/// A coding agent's session id and the task and project it worked on
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutorSessionOwner {
+    pub session_id: String,
+    /// Executor of the process that started the session, e.g. `CLAUDE_CODE`
+    pub executor: Option<String>,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub project_id: Uuid,
+    pub project_name: String,
+}
+
 impl ExecutorSession {
     /// Find executor session by ID
     #[allow(dead_code)]
@@ -129,6 +141,31 @@ impl ExecutorSession {
         .await
     }
 
+    /// Coding agent sessions of an attempt that were not dropped by a retry, oldest first
+    pub async fn find_active_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutorSession,
+            r#"SELECT es.id as "id!: Uuid",
+                      es.task_attempt_id as "task_attempt_id!: Uuid",
+                      es.execution_process_id as "execution_process_id!: Uuid",
+                      es.session_id,
+                      es.prompt,
+                      es.summary,
+                      es.created_at as "created_at!: DateTime<Utc>",
+                      es.updated_at as "updated_at!: DateTime<Utc>"
+               FROM executor_sessions es
+               JOIN execution_processes ep ON ep.id = es.execution_process_id
+               WHERE es.task_attempt_id = $1 AND ep.dropped = FALSE
+               ORDER BY es.created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Create a new executor session
     pub async fn create(
         pool: &SqlitePool,
@@ -251,4 +288,39 @@ impl ExecutorSession {
 
         Ok(())
     }
+
+    /// Sessions that may have been active between `from` and `to`: started before `to` and last
+    /// updated after `from`. Oldest first.
+    pub async fn find_owners_active_between(
+        pool: &SqlitePool,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<ExecutorSessionOwner>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutorSessionOwner,
+            r#"SELECT es.session_id as "session_id!",
+                      json_extract(ep.executor_action, '$.typ.executor_profile_id.executor')
+                          as "executor?: String",
+                      t.id as "task_id!: Uuid",
+                      t.title as task_title,
+                      p.id as "project_id!: Uuid",
+                      p.name as project_name
+               FROM executor_sessions es
+               JOIN execution_processes ep ON ep.id = es.execution_process_id
+               JOIN task_attempts ta ON ta.id = es.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               JOIN projects p ON p.id = t.project_id
+               WHERE es.session_id IS NOT NULL
+                 AND datetime(ep.created_at) <= datetime($1)
+                 AND datetime(es.updated_at) >= datetime($2)
+                 AND ($3 IS NULL OR p.id = $3)
+               ORDER BY es.created_at ASC"#,
+            to,
+            from,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
 }
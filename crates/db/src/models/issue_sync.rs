@@ -0,0 +1,329 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Where a project's issues are tracked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum IssueTracker {
+    GitHub,
+    GitLab,
+}
+
+/// The GitHub repository or GitLab project whose issues a project mirrors as tasks
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct IssueSync {
+    pub project_id: Uuid,
+    pub tracker: IssueTracker,
+    /// GitHub owner, or GitLab namespace path such as `group/subgroup`
+    pub owner: String,
+    pub repo: String,
+    /// Issues updated before this were already imported; None until the first sync succeeds
+    #[ts(type = "Date | null")]
+    pub last_synced_at: Option<DateTime<Utc>>,
+    /// Why the latest sync failed; cleared by the next successful one
+    pub last_error: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// `repo` is the repository path (`owner/name`, `group/subgroup/project`) or its URL
+#[derive(Debug, Deserialize, TS)]
+pub struct LinkIssueSync {
+    pub tracker: IssueTracker,
+    pub repo: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum IssueState {
+    Open,
+    Closed,
+}
+
+/// The issue a task was imported from
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskIssue {
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    /// GitHub issue number or GitLab issue iid
+    #[ts(type = "number")]
+    pub issue_number: i64,
+    pub issue_url: String,
+    /// Last state seen on the tracker or pushed to it
+    pub issue_state: IssueState,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IssueSync {
+    /// `owner/repo`, the full path of the repository on its host
+    pub fn repo_path(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            IssueSync,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      tracker as "tracker!: IssueTracker",
+                      owner,
+                      repo,
+                      last_synced_at as "last_synced_at: DateTime<Utc>",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM issue_syncs
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            IssueSync,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      tracker as "tracker!: IssueTracker",
+                      owner,
+                      repo,
+                      last_synced_at as "last_synced_at: DateTime<Utc>",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM issue_syncs
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Link the project to `owner/repo` on `tracker`. Relinking starts over with a full import;
+    /// switching to another repository also forgets the issues of the previous one.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        tracker: IssueTracker,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"DELETE FROM task_issues
+               WHERE project_id = $1
+                 AND EXISTS (SELECT 1 FROM issue_syncs
+                             WHERE project_id = $1
+                               AND (tracker <> $2 OR owner <> $3 COLLATE NOCASE
+                                    OR repo <> $4 COLLATE NOCASE))"#,
+            project_id,
+            tracker,
+            owner,
+            repo
+        )
+        .execute(&mut *tx)
+        .await?;
+        let sync = sqlx::query_as!(
+            IssueSync,
+            r#"INSERT INTO issue_syncs (project_id, tracker, owner, repo)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(project_id) DO UPDATE
+               SET tracker = excluded.tracker, owner = excluded.owner, repo = excluded.repo,
+                   last_synced_at = NULL, last_error = NULL,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid",
+                      tracker as "tracker!: IssueTracker",
+                      owner,
+                      repo,
+                      last_synced_at as "last_synced_at: DateTime<Utc>",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            tracker,
+            owner,
+            repo
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(sync)
+    }
+
+    pub async fn record_success(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        synced_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE issue_syncs
+               SET last_synced_at = $1, last_error = NULL, updated_at = datetime('now', 'subsec')
+               WHERE project_id = $2"#,
+            synced_at,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_error(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        error: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE issue_syncs
+               SET last_error = $1, updated_at = datetime('now', 'subsec')
+               WHERE project_id = $2"#,
+            error,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Unlink the project. Its tasks stay, but forget which issues they came from.
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!("DELETE FROM task_issues WHERE project_id = $1", project_id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query!("DELETE FROM issue_syncs WHERE project_id = $1", project_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl TaskIssue {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssue,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      issue_number,
+                      issue_url,
+                      issue_state as "issue_state!: IssueState",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_issues
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_issue_number(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        issue_number: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssue,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      issue_number,
+                      issue_url,
+                      issue_state as "issue_state!: IssueState",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_issues
+               WHERE project_id = $1 AND issue_number = $2"#,
+            project_id,
+            issue_number
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Links whose issue state no longer matches the task: open issues of done or cancelled
+    /// tasks, and closed issues of tasks that were picked up again
+    pub async fn find_out_of_sync(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssue,
+            r#"SELECT ti.task_id as "task_id!: Uuid",
+                      ti.project_id as "project_id!: Uuid",
+                      ti.issue_number,
+                      ti.issue_url,
+                      ti.issue_state as "issue_state!: IssueState",
+                      ti.created_at as "created_at!: DateTime<Utc>",
+                      ti.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_issues ti
+               JOIN tasks t ON t.id = ti.task_id
+               WHERE ti.project_id = $1
+                 AND ((ti.issue_state = 'open' AND t.status IN ('done', 'cancelled'))
+                   OR (ti.issue_state = 'closed' AND t.status NOT IN ('done', 'cancelled')))
+               ORDER BY ti.issue_number ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        project_id: Uuid,
+        issue_number: i64,
+        issue_url: &str,
+        issue_state: IssueState,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssue,
+            r#"INSERT INTO task_issues (task_id, project_id, issue_number, issue_url, issue_state)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING task_id as "task_id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      issue_number,
+                      issue_url,
+                      issue_state as "issue_state!: IssueState",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            task_id,
+            project_id,
+            issue_number,
+            issue_url,
+            issue_state
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn set_state(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        issue_state: IssueState,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_issues
+               SET issue_state = $1, updated_at = datetime('now', 'subsec')
+               WHERE task_id = $2"#,
+            issue_state,
+            task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
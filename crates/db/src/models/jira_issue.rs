@@ -0,0 +1,290 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+/// The Jira query whose issues a project pulls in as tasks
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct JiraSync {
+    pub project_id: Uuid,
+    pub jql: String,
+    /// Jira status to move the issue to when its task goes to review; None means "In Review"
+    pub in_review_status: Option<String>,
+    /// Jira status to move the issue to when its task is done; None means "Done"
+    pub done_status: Option<String>,
+    #[ts(type = "Date | null")]
+    pub last_synced_at: Option<DateTime<Utc>>,
+    /// Why the latest sync failed; cleared by the next successful one
+    pub last_error: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertJiraSync {
+    pub jql: String,
+    pub in_review_status: Option<String>,
+    pub done_status: Option<String>,
+}
+
+/// The Jira issue a task was imported from
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskJiraIssue {
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    /// Such as `PROJ-123`
+    pub issue_key: String,
+    pub issue_url: String,
+    /// Task status the issue was last transitioned for
+    pub pushed_status: Option<TaskStatus>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A linked task that reached InReview or Done since its issue was last transitioned
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingJiraTransition {
+    pub task_id: Uuid,
+    pub issue_key: String,
+    pub status: TaskStatus,
+}
+
+impl JiraSync {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            JiraSync,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      jql,
+                      in_review_status,
+                      done_status,
+                      last_synced_at as "last_synced_at: DateTime<Utc>",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM jira_syncs
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            JiraSync,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      jql,
+                      in_review_status,
+                      done_status,
+                      last_synced_at as "last_synced_at: DateTime<Utc>",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM jira_syncs
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertJiraSync,
+    ) -> Result<Self, sqlx::Error> {
+        let status = |status: &Option<String>| {
+            status
+                .as_deref()
+                .map(str::trim)
+                .filter(|status| !status.is_empty())
+                .map(str::to_string)
+        };
+        let jql = data.jql.trim();
+        let in_review_status = status(&data.in_review_status);
+        let done_status = status(&data.done_status);
+        sqlx::query_as!(
+            JiraSync,
+            r#"INSERT INTO jira_syncs (project_id, jql, in_review_status, done_status)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(project_id) DO UPDATE
+               SET jql = excluded.jql, in_review_status = excluded.in_review_status,
+                   done_status = excluded.done_status, last_error = NULL,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid",
+                      jql,
+                      in_review_status,
+                      done_status,
+                      last_synced_at as "last_synced_at: DateTime<Utc>",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            jql,
+            in_review_status,
+            done_status
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn record_success(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        synced_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE jira_syncs
+               SET last_synced_at = $1, last_error = NULL, updated_at = datetime('now', 'subsec')
+               WHERE project_id = $2"#,
+            synced_at,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_error(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        error: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE jira_syncs
+               SET last_error = $1, updated_at = datetime('now', 'subsec')
+               WHERE project_id = $2"#,
+            error,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Stop syncing. Imported tasks stay, but forget which issues they came from.
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM task_jira_issues WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        let result = sqlx::query!("DELETE FROM jira_syncs WHERE project_id = $1", project_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl TaskJiraIssue {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskJiraIssue,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      issue_key,
+                      issue_url,
+                      pushed_status as "pushed_status: TaskStatus",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_jira_issues
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Keys of the issues already imported into the project
+    pub async fn find_keys_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            "SELECT issue_key FROM task_jira_issues WHERE project_id = $1",
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_pending_transitions(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<PendingJiraTransition>, sqlx::Error> {
+        sqlx::query_as!(
+            PendingJiraTransition,
+            r#"SELECT tji.task_id as "task_id!: Uuid",
+                      tji.issue_key,
+                      t.status as "status!: TaskStatus"
+               FROM task_jira_issues tji
+               JOIN tasks t ON t.id = tji.task_id
+               WHERE tji.project_id = $1
+                 AND t.status IN ('inreview', 'done')
+                 AND (tji.pushed_status IS NULL OR tji.pushed_status <> t.status)
+               ORDER BY tji.issue_key ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        project_id: Uuid,
+        issue_key: &str,
+        issue_url: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskJiraIssue,
+            r#"INSERT INTO task_jira_issues (task_id, project_id, issue_key, issue_url)
+               VALUES ($1, $2, $3, $4)
+               RETURNING task_id as "task_id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      issue_key,
+                      issue_url,
+                      pushed_status as "pushed_status: TaskStatus",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            task_id,
+            project_id,
+            issue_key,
+            issue_url
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn set_pushed_status(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        status: TaskStatus,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_jira_issues
+               SET pushed_status = $1, updated_at = datetime('now', 'subsec')
+               WHERE task_id = $2"#,
+            status,
+            task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Acquire, Executor, FromRow, Sqlite, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Label {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    /// Hex color such as `#3b82f6`
+    pub color: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateLabel {
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateLabel {
+    pub name: Option<String>,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetTaskLabels {
+    pub label_ids: Vec<Uuid>,
+}
+
+/// Whether `color` is a `#rrggbb` hex color
+pub fn is_valid_label_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+impl Label {
+    pub async fn find_by_project_id<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Label,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      color,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM labels
+               WHERE project_id = $1
+               ORDER BY name COLLATE NOCASE ASC"#,
+            project_id
+        )
+        .fetch_all(executor)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Label,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      color,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM labels
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_name(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        name: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Label,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      color,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM labels
+               WHERE project_id = $1 AND name = $2"#,
+            project_id,
+            name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
+        project_id: Uuid,
+        data: &CreateLabel,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let name = data.name.trim();
+        sqlx::query_as!(
+            Label,
+            r#"INSERT INTO labels (id, project_id, name, color)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      color,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            name,
+            data.color
+        )
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        name: &str,
+        color: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let name = name.trim();
+        let label = sqlx::query_as!(
+            Label,
+            r#"UPDATE labels
+               SET name = $1, color = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $3
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      color,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            name,
+            color,
+            id
+        )
+        .fetch_one(pool)
+        .await?;
+        Self::touch_labelled_tasks(pool, id).await?;
+        Ok(label)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        let task_ids = Self::labelled_task_ids(pool, id).await?;
+        sqlx::query!("DELETE FROM labels WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        for task_id in task_ids {
+            touch_task(pool, task_id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Label,
+            r#"SELECT l.id as "id!: Uuid",
+                      l.project_id as "project_id!: Uuid",
+                      l.name,
+                      l.color,
+                      l.created_at as "created_at!: DateTime<Utc>",
+                      l.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_labels tl
+               JOIN labels l ON l.id = tl.label_id
+               WHERE tl.task_id = $1
+               ORDER BY l.name COLLATE NOCASE ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Labels of every task in the project, keyed by task id
+    pub async fn find_by_task_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<HashMap<Uuid, Vec<Label>>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT tl.task_id as "task_id!: Uuid",
+                      l.id as "id!: Uuid",
+                      l.project_id as "project_id!: Uuid",
+                      l.name,
+                      l.color,
+                      l.created_at as "created_at!: DateTime<Utc>",
+                      l.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_labels tl
+               JOIN labels l ON l.id = tl.label_id
+               WHERE l.project_id = $1
+               ORDER BY l.name COLLATE NOCASE ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        let mut by_task: HashMap<Uuid, Vec<Label>> = HashMap::new();
+        for row in rows {
+            by_task.entry(row.task_id).or_default().push(Label {
+                id: row.id,
+                project_id: row.project_id,
+                name: row.name,
+                color: row.color,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            });
+        }
+        Ok(by_task)
+    }
+
+    /// Replace the task's labels. Ids of labels from other projects are ignored.
+    pub async fn set_for_task<'a, A: Acquire<'a, Database = Sqlite>>(
+        conn: A,
+        task_id: Uuid,
+        project_id: Uuid,
+        label_ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = conn.begin().await?;
+        sqlx::query!("DELETE FROM task_labels WHERE task_id = $1", task_id)
+            .execute(&mut *tx)
+            .await?;
+        for label_id in label_ids {
+            sqlx::query!(
+                r#"INSERT OR IGNORE INTO task_labels (task_id, label_id)
+                   SELECT $1, id FROM labels WHERE id = $2 AND project_id = $3"#,
+                task_id,
+                label_id,
+                project_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        touch_task(&mut *tx, task_id).await?;
+        tx.commit().await
+    }
+
+    async fn touch_labelled_tasks(pool: &SqlitePool, label_id: Uuid) -> Result<(), sqlx::Error> {
+        for task_id in Self::labelled_task_ids(pool, label_id).await? {
+            touch_task(pool, task_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn labelled_task_ids(
+        pool: &SqlitePool,
+        label_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT task_id as "task_id!: Uuid" FROM task_labels WHERE label_id = $1"#,
+            label_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+/// Bump the task's `updated_at` so task streams send it again with its new labels
+async fn touch_task<'e, E: Executor<'e, Database = Sqlite>>(
+    executor: E,
+    task_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE tasks SET updated_at = datetime('now', 'subsec') WHERE id = $1",
+        task_id
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
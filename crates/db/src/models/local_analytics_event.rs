@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Coding agent runs started and finished on one day (UTC)
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct DailyRuns {
+    /// `YYYY-MM-DD`
+    pub day: String,
+    #[ts(type = "number")]
+    pub started: i64,
+    #[ts(type = "number")]
+    pub finished: i64,
+    #[ts(type = "number")]
+    pub succeeded: i64,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct ExecutorUsage {
+    pub executor: String,
+    #[ts(type = "number")]
+    pub runs: i64,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct EventCount {
+    pub event_name: String,
+    #[ts(type = "number")]
+    pub count: i64,
+}
+
+/// Analytics event stored locally because external analytics is disabled
+pub struct LocalAnalyticsEvent;
+
+impl LocalAnalyticsEvent {
+    pub async fn record(
+        pool: &SqlitePool,
+        event_name: &str,
+        properties: &Value,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        let properties = properties.to_string();
+        let created_at = Utc::now();
+        sqlx::query!(
+            r#"INSERT INTO local_analytics_events (id, event_name, properties, created_at)
+               VALUES ($1, $2, $3, $4)"#,
+            id,
+            event_name,
+            properties,
+            created_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn runs_per_day(
+        pool: &SqlitePool,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<DailyRuns>, sqlx::Error> {
+        sqlx::query_as!(
+            DailyRuns,
+            r#"SELECT date(created_at) as "day!: String",
+                      SUM(event_name = 'task_attempt_started') as "started!: i64",
+                      SUM(event_name = 'task_attempt_finished') as "finished!: i64",
+                      SUM(event_name = 'task_attempt_finished'
+                          AND json_extract(properties, '$.execution_success') = 1) as "succeeded!: i64"
+               FROM local_analytics_events
+               WHERE event_name IN ('task_attempt_started', 'task_attempt_finished')
+                 AND created_at >= $1
+               GROUP BY date(created_at)
+               ORDER BY date(created_at) ASC"#,
+            since
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Attempts started per executor, most used first
+    pub async fn executor_mix(
+        pool: &SqlitePool,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ExecutorUsage>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutorUsage,
+            r#"SELECT COALESCE(json_extract(properties, '$.executor'), 'UNKNOWN') as "executor!: String",
+                      COUNT(*) as "runs!: i64"
+               FROM local_analytics_events
+               WHERE event_name = 'task_attempt_started' AND created_at >= $1
+               GROUP BY COALESCE(json_extract(properties, '$.executor'), 'UNKNOWN')
+               ORDER BY COUNT(*) DESC"#,
+            since
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn event_counts(
+        pool: &SqlitePool,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<EventCount>, sqlx::Error> {
+        sqlx::query_as!(
+            EventCount,
+            r#"SELECT event_name, COUNT(*) as "count!: i64"
+               FROM local_analytics_events
+               WHERE created_at >= $1
+               GROUP BY event_name
+               ORDER BY COUNT(*) DESC"#,
+            since
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -14,6 +14,20 @@ pub enum MergeStatus {
     Unknown,
 }
 
+/// How a direct merge lands the attempt branch on its target
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// One new commit on the target containing all attempt changes
+    #[default]
+    Squash,
+    /// A two-parent merge commit, keeping the attempt history
+    MergeCommit,
+    /// Rebase the attempt branch onto the target if needed, then fast-forward the target
+    RebaseFastForward,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Merge {
@@ -27,6 +41,7 @@ pub struct DirectMerge {
     pub task_attempt_id: Uuid,
     pub merge_commit: String,
     pub target_branch_name: String,
+    pub merge_strategy: MergeStrategy,
     pub created_at: DateTime<Utc>,
 }
 
@@ -68,10 +83,19 @@ struct MergeRow {
     pr_status: Option<MergeStatus>,
     pr_merged_at: Option<DateTime<Utc>>,
     pr_merge_commit_sha: Option<String>,
+    merge_strategy: Option<MergeStrategy>,
     created_at: DateTime<Utc>,
 }
 
 impl Merge {
+    /// Whether the changes have landed: any direct merge, or a PR that was merged
+    pub fn is_merged(&self) -> bool {
+        match self {
+            Merge::Direct(_) => true,
+            Merge::Pr(pr) => matches!(pr.pr_info.status, MergeStatus::Merged),
+        }
+    }
+
     pub fn merge_commit(&self) -> Option<String> {
         match self {
             Merge::Direct(direct) => Some(direct.merge_commit.clone()),
@@ -80,11 +104,12 @@ impl Merge {
     }
 
     /// Create a direct merge record
-    pub async fn create_direct(
-        pool: &SqlitePool,
+    pub async fn create_direct<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
         task_attempt_id: Uuid,
         target_branch_name: &str,
         merge_commit: &str,
+        merge_strategy: MergeStrategy,
     ) -> Result<DirectMerge, sqlx::Error> {
         let id = Uuid::new_v4();
         let now = Utc::now();
@@ -92,8 +117,9 @@ impl Merge {
         sqlx::query_as!(
             MergeRow,
             r#"INSERT INTO merges (
-                id, task_attempt_id, merge_type, merge_commit, created_at, target_branch_name
-            ) VALUES ($1, $2, 'direct', $3, $4, $5)
+                id, task_attempt_id, merge_type, merge_commit, created_at, target_branch_name,
+                merge_strategy
+            ) VALUES ($1, $2, 'direct', $3, $4, $5, $6)
             RETURNING 
                 id as "id!: Uuid",
                 task_attempt_id as "task_attempt_id!: Uuid",
@@ -104,6 +130,7 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -111,15 +138,16 @@ impl Merge {
             task_attempt_id,
             merge_commit,
             now,
-            target_branch_name
+            target_branch_name,
+            merge_strategy
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
         .map(Into::into)
     }
     /// Create a new PR record (when PR is opened)
-    pub async fn create_pr(
-        pool: &SqlitePool,
+    pub async fn create_pr<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
         task_attempt_id: Uuid,
         target_branch_name: &str,
         pr_number: i64,
@@ -143,6 +171,7 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
             "#,
@@ -153,7 +182,7 @@ impl Merge {
             now,
             target_branch_name
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
         .map(Into::into)
     }
@@ -172,6 +201,7 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 created_at as "created_at!: DateTime<Utc>",
                 target_branch_name as "target_branch_name!: String"
                FROM merges 
@@ -185,8 +215,8 @@ impl Merge {
     }
 
     /// Update PR status for a task attempt
-    pub async fn update_status(
-        pool: &SqlitePool,
+    pub async fn update_status<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
         merge_id: Uuid,
         pr_status: MergeStatus,
         merge_commit_sha: Option<String>,
@@ -208,7 +238,7 @@ impl Merge {
             merged_at,
             merge_id
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
 
         Ok(())
@@ -231,6 +261,7 @@ impl Merge {
                 pr_status as "pr_status?: MergeStatus",
                 pr_merged_at as "pr_merged_at?: DateTime<Utc>",
                 pr_merge_commit_sha,
+                merge_strategy as "merge_strategy?: MergeStrategy",
                 target_branch_name as "target_branch_name!: String",
                 created_at as "created_at!: DateTime<Utc>"
             FROM merges 
@@ -266,6 +297,8 @@ impl From<MergeRow> for DirectMerge {
                 .merge_commit
                 .expect("direct merge must have merge_commit"),
             target_branch_name: row.target_branch_name,
+            // Always set on direct merges; the migration recorded older ones as squashes
+            merge_strategy: row.merge_strategy.unwrap_or_default(),
             created_at: row.created_at,
         }
     }
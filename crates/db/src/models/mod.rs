@@ -1,12 +1,37 @@
+pub mod activity_event;
+pub mod activity_event_notification;
+pub mod activity_event_read;
+pub mod attempt_cleanup_warning;
+pub mod attempt_manual_changes;
+pub mod attempt_verification;
+pub mod audit_log;
+pub mod budget_alert;
+pub mod dev_server_port;
+pub mod diff_comment;
 pub mod draft;
 pub mod execution_process;
 pub mod execution_process_logs;
+pub mod execution_process_replay;
+pub mod execution_process_token_usage;
 pub mod executor_session;
 pub mod image;
+pub mod issue_sync;
+pub mod jira_issue;
+pub mod label;
+pub mod local_analytics_event;
 pub mod merge;
+pub mod pr_check_status;
 pub mod project;
 pub mod project_repository;
+pub mod prompt_snippet;
 pub mod task;
 pub mod task_attempt;
 pub mod task_attempt_repository;
+pub mod task_dependency;
+pub mod task_schedule;
 pub mod task_template;
+pub mod usage_sample;
+pub mod user;
+pub mod user_session;
+pub mod warm_worktree;
+pub mod webhook;
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Overall review decision on a pull request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PrReviewState {
+    ReviewRequired,
+    Commented,
+    Approved,
+    ChangesRequested,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PrCheckState {
+    Pending,
+    Success,
+    Failure,
+    /// Neutral or skipped
+    Neutral,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct PrCheckRun {
+    pub name: String,
+    pub state: PrCheckState,
+    pub details_url: Option<String>,
+}
+
+impl PrCheckState {
+    /// Combine individual check results: any failure fails the whole set, then anything still
+    /// running keeps it pending. No checks at all counts as success.
+    pub fn summarize(checks: &[PrCheckRun]) -> Self {
+        if checks.iter().any(|c| c.state == PrCheckState::Failure) {
+            PrCheckState::Failure
+        } else if checks.iter().any(|c| c.state == PrCheckState::Pending) {
+            PrCheckState::Pending
+        } else {
+            PrCheckState::Success
+        }
+    }
+}
+
+/// Review and CI status of the attempt's open pull request
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PrCheckStatus {
+    pub task_attempt_id: Uuid,
+    pub pr_number: i64,
+    pub pr_url: String,
+    pub review_state: PrReviewState,
+    pub checks_state: PrCheckState,
+    #[ts(type = "Array<PrCheckRun>")]
+    pub checks: Json<Vec<PrCheckRun>>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PrCheckStatus {
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PrCheckStatus,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid",
+                      pr_number,
+                      pr_url,
+                      review_state as "review_state!: PrReviewState",
+                      checks_state as "checks_state!: PrCheckState",
+                      checks as "checks!: Json<Vec<PrCheckRun>>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM pr_check_statuses
+               WHERE task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PrCheckStatus,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid",
+                      pr_number,
+                      pr_url,
+                      review_state as "review_state!: PrReviewState",
+                      checks_state as "checks_state!: PrCheckState",
+                      checks as "checks!: Json<Vec<PrCheckRun>>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM pr_check_statuses
+               WHERE rowid = $1"#,
+            rowid
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store the latest status. Returns false without writing when nothing changed, so unchanged
+    /// polls do not emit events.
+    pub async fn upsert_if_changed(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        pr_number: i64,
+        pr_url: &str,
+        review_state: PrReviewState,
+        checks: &[PrCheckRun],
+    ) -> Result<bool, sqlx::Error> {
+        let checks_state = PrCheckState::summarize(checks);
+        if let Some(existing) = Self::find_by_task_attempt_id(pool, task_attempt_id).await?
+            && existing.pr_number == pr_number
+            && existing.review_state == review_state
+            && existing.checks_state == checks_state
+            && existing.checks.0 == checks
+        {
+            return Ok(false);
+        }
+
+        let checks = Json(checks);
+        sqlx::query!(
+            r#"INSERT INTO pr_check_statuses
+                   (task_attempt_id, pr_number, pr_url, review_state, checks_state, checks,
+                    updated_at)
+               VALUES ($1, $2, $3, $4, $5, $6, datetime('now', 'subsec'))
+               ON CONFLICT(task_attempt_id) DO UPDATE SET
+                   pr_number = excluded.pr_number,
+                   pr_url = excluded.pr_url,
+                   review_state = excluded.review_state,
+                   checks_state = excluded.checks_state,
+                   checks = excluded.checks,
+                   updated_at = excluded.updated_at"#,
+            task_attempt_id,
+            pr_number,
+            pr_url,
+            review_state,
+            checks_state,
+            checks
+        )
+        .execute(pool)
+        .await?;
+        Ok(true)
+    }
+}
@@ -1,13 +1,16 @@
 use std::path::PathBuf;
 
 use chrono::{DateTime, Utc};
+use executors::sandbox::{ContainerRuntime, ResourceLimits};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Acquire, FromRow, Sqlite, SqlitePool};
 
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::{execution_process::ExecutionProcessRunReason, task::TaskStatus};
+
 #[derive(Debug, Error)]
 pub enum ProjectError {
     #[error(transparent)]
@@ -59,6 +62,154 @@ pub struct UpdateProject {
     pub copy_files: Option<String>,
 }
 
+/// Overrides for the worktree diff watcher; `None` fields fall back to the watcher defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct ProjectWatcherSettings {
+    /// Gitignore-style patterns whose changes are not streamed; an empty list excludes nothing
+    pub exclude_patterns: Option<Vec<String>>,
+    /// Soft cap on filesystem events per second per diff stream; 0 disables throttling
+    pub max_events_per_second: Option<u32>,
+    /// Gitignore-style patterns for files such as lockfiles whose diffs start collapsed, with
+    /// contents omitted; an empty list collapses nothing
+    pub diff_ignore_patterns: Option<Vec<String>>,
+}
+
+/// How dependency directories from the main checkout end up in a new worktree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum DependencyShareMode {
+    /// Link to the main checkout's directory; installs in one worktree are visible in all
+    #[default]
+    Symlink,
+    /// Copy-on-write clone where the filesystem supports it, a plain copy otherwise
+    Clone,
+}
+
+impl DependencyShareMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DependencyShareMode::Symlink => "symlink",
+            DependencyShareMode::Clone => "clone",
+        }
+    }
+
+    pub fn from_db_value(value: &str) -> Self {
+        match value {
+            "clone" => DependencyShareMode::Clone,
+            _ => DependencyShareMode::Symlink,
+        }
+    }
+}
+
+/// How attempt worktrees are prepared for the project
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct ProjectWorktreeSettings {
+    /// Run `git submodule update --init --recursive` after creating a worktree
+    pub init_submodules: bool,
+    /// Git-ignored directories such as `node_modules`, `target` or `.venv` taken from the main
+    /// checkout instead of being installed again in every worktree
+    #[serde(default)]
+    pub shared_dirs: Vec<String>,
+    #[serde(default)]
+    pub share_mode: DependencyShareMode,
+    /// Worktrees kept ready, with the setup script already run, for new attempts to claim
+    #[serde(default)]
+    pub warm_pool_size: u32,
+}
+
+/// How pull requests are opened for the project's attempts
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct ProjectPrSettings {
+    /// Repository-relative PR template; when unset the usual template locations are checked
+    pub template_path: Option<String>,
+    /// Delete the attempt branch on its remote once the PR is merged or the attempt is merged
+    /// locally
+    #[serde(default)]
+    pub delete_remote_branch_after_merge: bool,
+}
+
+/// Isolation of the project's execution processes in a container. The worktree and the
+/// repository are mounted at their host paths; dev servers keep running on the host so their
+/// ports stay reachable.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct ProjectSandboxSettings {
+    pub enabled: bool,
+    #[serde(default)]
+    pub runtime: ContainerRuntime,
+    /// Image providing the shell and the coding agent CLIs
+    pub image: Option<String>,
+}
+
+/// Hours between scheduled `git maintenance` runs unless the project overrides it
+pub const DEFAULT_GIT_MAINTENANCE_INTERVAL_HOURS: u32 = 24;
+
+/// Scheduled `git maintenance` for the project's repositories
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectGitMaintenanceSettings {
+    pub enabled: bool,
+    /// Hours between runs
+    pub interval_hours: u32,
+    /// When maintenance last ran; ignored on update
+    #[serde(default)]
+    #[ts(type = "Date | null")]
+    pub last_run_at: Option<DateTime<Utc>>,
+}
+
+impl Default for ProjectGitMaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: DEFAULT_GIT_MAINTENANCE_INTERVAL_HOURS,
+            last_run_at: None,
+        }
+    }
+}
+
+/// What happens when the last process of a run finishes
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct FinalizationRule {
+    /// Status the task moves to; `None` leaves it unchanged
+    pub task_status: Option<TaskStatus>,
+    pub notify: bool,
+    /// Start the attempt's queued follow-up, if any
+    pub consume_queued_follow_up: bool,
+}
+
+impl Default for FinalizationRule {
+    fn default() -> Self {
+        Self {
+            task_status: Some(TaskStatus::InReview),
+            notify: true,
+            consume_queued_follow_up: true,
+        }
+    }
+}
+
+/// Finalization rules keyed by the run reason of the process that finished last. Dev servers are
+/// never finalized.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default)]
+pub struct ProjectFinalizationSettings {
+    pub coding_agent: FinalizationRule,
+    pub setup_script: FinalizationRule,
+    pub cleanup_script: FinalizationRule,
+    /// Only applied when the verify script passed
+    pub verify_script: FinalizationRule,
+}
+
+impl ProjectFinalizationSettings {
+    pub fn rule_for(&self, run_reason: &ExecutionProcessRunReason) -> Option<&FinalizationRule> {
+        match run_reason {
+            ExecutionProcessRunReason::CodingAgent => Some(&self.coding_agent),
+            ExecutionProcessRunReason::SetupScript => Some(&self.setup_script),
+            ExecutionProcessRunReason::CleanupScript => Some(&self.cleanup_script),
+            ExecutionProcessRunReason::VerifyScript => Some(&self.verify_script),
+            ExecutionProcessRunReason::DevServer => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct SearchResult {
     pub path: String,
@@ -79,6 +230,25 @@ pub enum SearchMatchType {
     FullPath,
 }
 
+/// Patterns stored one per line, skipping blank lines
+fn pattern_lines(stored: &str) -> Vec<String> {
+    stored
+        .lines()
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn join_patterns(patterns: &[String]) -> String {
+    patterns
+        .iter()
+        .map(|pattern| pattern.trim())
+        .filter(|pattern| !pattern.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 impl Project {
     pub async fn count(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
         sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!: i64" FROM projects"#)
@@ -155,12 +325,12 @@ impl Project {
         .await
     }
 
-    pub async fn create(
-        pool: &SqlitePool,
+    pub async fn create<'a, A: Acquire<'a, Database = Sqlite>>(
+        conn: A,
         data: &CreateProject,
         project_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
-        let mut tx = pool.begin().await?;
+        let mut tx = conn.begin().await?;
         let project = sqlx::query_as!(
             Project,
             r#"INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
@@ -271,4 +441,377 @@ impl Project {
 
         Ok(result.count > 0)
     }
+
+    /// Script that must pass before an attempt's task moves to review, if configured.
+    pub async fn find_verify_script(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let script = sqlx::query_scalar!("SELECT verify_script FROM projects WHERE id = $1", id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(script.flatten().filter(|script| !script.trim().is_empty()))
+    }
+
+    pub async fn set_verify_script(
+        pool: &SqlitePool,
+        id: Uuid,
+        verify_script: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let verify_script = verify_script.filter(|script| !script.trim().is_empty());
+        sqlx::query!(
+            "UPDATE projects SET verify_script = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            verify_script,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Follow-ups sent automatically when the cleanup or verify script fails; 0 when disabled
+    pub async fn find_auto_fix_attempts(pool: &SqlitePool, id: Uuid) -> Result<u32, sqlx::Error> {
+        let attempts =
+            sqlx::query_scalar!("SELECT auto_fix_attempts FROM projects WHERE id = $1", id)
+                .fetch_optional(pool)
+                .await?;
+        Ok(attempts.and_then(|n| u32::try_from(n).ok()).unwrap_or(0))
+    }
+
+    pub async fn set_auto_fix_attempts(
+        pool: &SqlitePool,
+        id: Uuid,
+        attempts: u32,
+    ) -> Result<(), sqlx::Error> {
+        let attempts = i64::from(attempts);
+        sqlx::query!(
+            "UPDATE projects SET auto_fix_attempts = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            attempts,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_watcher_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<ProjectWatcherSettings, sqlx::Error> {
+        let row = sqlx::query!(
+            "SELECT watcher_exclude_patterns, watcher_max_events_per_second, diff_ignore_patterns FROM projects WHERE id = $1",
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(ProjectWatcherSettings::default());
+        };
+        Ok(ProjectWatcherSettings {
+            exclude_patterns: row.watcher_exclude_patterns.as_deref().map(pattern_lines),
+            max_events_per_second: row
+                .watcher_max_events_per_second
+                .and_then(|max| u32::try_from(max).ok()),
+            diff_ignore_patterns: row.diff_ignore_patterns.as_deref().map(pattern_lines),
+        })
+    }
+
+    pub async fn set_watcher_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+        settings: &ProjectWatcherSettings,
+    ) -> Result<(), sqlx::Error> {
+        let exclude_patterns = settings.exclude_patterns.as_deref().map(join_patterns);
+        let diff_ignore_patterns = settings.diff_ignore_patterns.as_deref().map(join_patterns);
+        let max_events_per_second = settings.max_events_per_second.map(i64::from);
+        sqlx::query!(
+            "UPDATE projects SET watcher_exclude_patterns = $1, watcher_max_events_per_second = $2, diff_ignore_patterns = $3, updated_at = datetime('now', 'subsec') WHERE id = $4",
+            exclude_patterns,
+            max_events_per_second,
+            diff_ignore_patterns,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_worktree_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<ProjectWorktreeSettings, sqlx::Error> {
+        let row = sqlx::query!(
+            "SELECT init_submodules, shared_dependency_dirs, dependency_share_mode, warm_worktree_pool_size FROM projects WHERE id = $1",
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        let Some(row) = row else {
+            return Ok(ProjectWorktreeSettings::default());
+        };
+        Ok(ProjectWorktreeSettings {
+            init_submodules: row.init_submodules,
+            shared_dirs: row
+                .shared_dependency_dirs
+                .unwrap_or_default()
+                .lines()
+                .map(str::trim)
+                .filter(|dir| !dir.is_empty())
+                .map(str::to_string)
+                .collect(),
+            share_mode: DependencyShareMode::from_db_value(&row.dependency_share_mode),
+            warm_pool_size: u32::try_from(row.warm_worktree_pool_size).unwrap_or(0),
+        })
+    }
+
+    pub async fn set_worktree_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+        settings: &ProjectWorktreeSettings,
+    ) -> Result<(), sqlx::Error> {
+        let shared_dirs = settings
+            .shared_dirs
+            .iter()
+            .map(|dir| dir.trim())
+            .filter(|dir| !dir.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let shared_dirs = (!shared_dirs.is_empty()).then_some(shared_dirs);
+        let share_mode = settings.share_mode.as_str();
+        let warm_pool_size = i64::from(settings.warm_pool_size);
+        sqlx::query!(
+            "UPDATE projects SET init_submodules = $1, shared_dependency_dirs = $2, dependency_share_mode = $3, warm_worktree_pool_size = $4, updated_at = datetime('now', 'subsec') WHERE id = $5",
+            settings.init_submodules,
+            shared_dirs,
+            share_mode,
+            warm_pool_size,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_git_maintenance_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<ProjectGitMaintenanceSettings, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT git_maintenance_enabled,
+                      git_maintenance_interval_hours,
+                      git_maintenance_last_run_at as "git_maintenance_last_run_at: DateTime<Utc>"
+               FROM projects
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(match row {
+            Some(row) => ProjectGitMaintenanceSettings {
+                enabled: row.git_maintenance_enabled,
+                interval_hours: u32::try_from(row.git_maintenance_interval_hours)
+                    .unwrap_or(DEFAULT_GIT_MAINTENANCE_INTERVAL_HOURS),
+                last_run_at: row.git_maintenance_last_run_at,
+            },
+            None => ProjectGitMaintenanceSettings::default(),
+        })
+    }
+
+    pub async fn set_git_maintenance_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+        settings: &ProjectGitMaintenanceSettings,
+    ) -> Result<(), sqlx::Error> {
+        let interval_hours = i64::from(settings.interval_hours);
+        sqlx::query!(
+            "UPDATE projects SET git_maintenance_enabled = $1, git_maintenance_interval_hours = $2, updated_at = datetime('now', 'subsec') WHERE id = $3",
+            settings.enabled,
+            interval_hours,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_pr_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<ProjectPrSettings, sqlx::Error> {
+        let row = sqlx::query!(
+            "SELECT pr_template_path, delete_remote_branch_after_merge FROM projects WHERE id = $1",
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(match row {
+            Some(row) => ProjectPrSettings {
+                template_path: row.pr_template_path,
+                delete_remote_branch_after_merge: row.delete_remote_branch_after_merge,
+            },
+            None => ProjectPrSettings::default(),
+        })
+    }
+
+    pub async fn set_pr_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+        settings: &ProjectPrSettings,
+    ) -> Result<(), sqlx::Error> {
+        let template_path = settings
+            .template_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|path| !path.is_empty());
+        sqlx::query!(
+            "UPDATE projects SET pr_template_path = $1, delete_remote_branch_after_merge = $2, updated_at = datetime('now', 'subsec') WHERE id = $3",
+            template_path,
+            settings.delete_remote_branch_after_merge,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_sandbox_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<ProjectSandboxSettings, sqlx::Error> {
+        let row = sqlx::query!(
+            "SELECT sandbox_enabled, sandbox_runtime, sandbox_image FROM projects WHERE id = $1",
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(match row {
+            Some(row) => ProjectSandboxSettings {
+                enabled: row.sandbox_enabled,
+                runtime: ContainerRuntime::from_program(&row.sandbox_runtime),
+                image: row.sandbox_image,
+            },
+            None => ProjectSandboxSettings::default(),
+        })
+    }
+
+    pub async fn set_sandbox_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+        settings: &ProjectSandboxSettings,
+    ) -> Result<(), sqlx::Error> {
+        let image = settings
+            .image
+            .as_deref()
+            .map(str::trim)
+            .filter(|image| !image.is_empty());
+        let runtime = settings.runtime.program();
+        sqlx::query!(
+            "UPDATE projects SET sandbox_enabled = $1, sandbox_runtime = $2, sandbox_image = $3, updated_at = datetime('now', 'subsec') WHERE id = $4",
+            settings.enabled,
+            runtime,
+            image,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_resource_limits(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<ResourceLimits, sqlx::Error> {
+        let row = sqlx::query!(
+            "SELECT resource_cpu_percent, resource_memory_mb FROM projects WHERE id = $1",
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(match row {
+            Some(row) => ResourceLimits {
+                cpu_percent: row
+                    .resource_cpu_percent
+                    .and_then(|value| u32::try_from(value).ok()),
+                memory_mb: row
+                    .resource_memory_mb
+                    .and_then(|value| u32::try_from(value).ok()),
+            },
+            None => ResourceLimits::default(),
+        })
+    }
+
+    pub async fn set_resource_limits(
+        pool: &SqlitePool,
+        id: Uuid,
+        limits: &ResourceLimits,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET resource_cpu_percent = $1, resource_memory_mb = $2, updated_at = datetime('now', 'subsec') WHERE id = $3",
+            limits.cpu_percent,
+            limits.memory_mb,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Unparseable stored rules fall back to the defaults rather than blocking finalization
+    pub async fn find_finalization_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<ProjectFinalizationSettings, sqlx::Error> {
+        let raw = sqlx::query_scalar!(
+            "SELECT finalization_settings FROM projects WHERE id = $1",
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+        Ok(raw
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default())
+    }
+
+    pub async fn set_finalization_settings(
+        pool: &SqlitePool,
+        id: Uuid,
+        settings: &ProjectFinalizationSettings,
+    ) -> Result<(), sqlx::Error> {
+        let raw = serde_json::to_string(settings).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+        sqlx::query!(
+            "UPDATE projects SET finalization_settings = $1, updated_at = datetime('now', 'subsec') WHERE id = $2",
+            raw,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Projects with maintenance enabled whose interval has elapsed since the last run
+    pub async fn find_due_for_git_maintenance(pool: &SqlitePool) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT id as "id!: Uuid"
+               FROM projects
+               WHERE git_maintenance_enabled = TRUE
+                 AND (git_maintenance_last_run_at IS NULL
+                      OR datetime(git_maintenance_last_run_at, '+' || git_maintenance_interval_hours || ' hours')
+                         <= datetime('now'))"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_git_maintenance_run(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET git_maintenance_last_run_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
 }
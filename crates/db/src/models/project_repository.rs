@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool, Transaction};
+use sqlx::{Acquire, Executor, FromRow, QueryBuilder, Sqlite, SqlitePool, Transaction};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -103,8 +103,8 @@ impl ProjectRepository {
         .await
     }
 
-    pub async fn find_primary(
-        pool: &SqlitePool,
+    pub async fn find_primary<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
         project_id: Uuid,
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
@@ -121,12 +121,12 @@ impl ProjectRepository {
                WHERE project_id = $1 AND is_primary = 1"#,
             project_id
         )
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
     }
 
-    pub async fn create(
-        pool: &SqlitePool,
+    pub async fn create<'a, A: Acquire<'a, Database = Sqlite>>(
+        conn: A,
         project_id: Uuid,
         data: &CreateProjectRepository,
     ) -> Result<Self, ProjectRepositoryError> {
@@ -144,7 +144,7 @@ impl ProjectRepository {
 
         let normalized_root = normalize_root_path(data.root_path.as_deref());
 
-        let mut tx = pool.begin().await?;
+        let mut tx = conn.begin().await?;
 
         let name_exists = sqlx::query_scalar!(
             r#"SELECT EXISTS(
@@ -236,13 +236,13 @@ impl ProjectRepository {
         Ok(repository)
     }
 
-    pub async fn update(
-        pool: &SqlitePool,
+    pub async fn update<'a, A: Acquire<'a, Database = Sqlite>>(
+        conn: A,
         project_id: Uuid,
         repository_id: Uuid,
         data: &UpdateProjectRepository,
     ) -> Result<Self, ProjectRepositoryError> {
-        let mut tx = pool.begin().await?;
+        let mut tx = conn.begin().await?;
         let existing = sqlx::query_as!(
             ProjectRepository,
             r#"SELECT id as "id!: Uuid",
@@ -0,0 +1,212 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Named block of text that prompts pull in with `{{snippet:name}}`
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PromptSnippet {
+    pub id: Uuid,
+    pub project_id: Option<Uuid>, // None for global snippets
+    pub name: String,
+    pub content: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreatePromptSnippet {
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub content: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdatePromptSnippet {
+    pub name: Option<String>,
+    pub content: Option<String>,
+    pub description: Option<String>,
+}
+
+impl PromptSnippet {
+    /// Names may only use letters, digits, `-`, `_` and `.` so they can be written inside a
+    /// placeholder without escaping
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PromptSnippet,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      content,
+                      description,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM prompt_snippets
+               ORDER BY project_id IS NULL DESC, name ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        if let Some(pid) = project_id {
+            sqlx::query_as!(
+                PromptSnippet,
+                r#"SELECT id as "id!: Uuid",
+                          project_id as "project_id: Uuid",
+                          name,
+                          content,
+                          description,
+                          created_at as "created_at!: DateTime<Utc>",
+                          updated_at as "updated_at!: DateTime<Utc>"
+                   FROM prompt_snippets
+                   WHERE project_id = $1
+                   ORDER BY name ASC"#,
+                pid
+            )
+            .fetch_all(pool)
+            .await
+        } else {
+            sqlx::query_as!(
+                PromptSnippet,
+                r#"SELECT id as "id!: Uuid",
+                          project_id as "project_id: Uuid",
+                          name,
+                          content,
+                          description,
+                          created_at as "created_at!: DateTime<Utc>",
+                          updated_at as "updated_at!: DateTime<Utc>"
+                   FROM prompt_snippets
+                   WHERE project_id IS NULL
+                   ORDER BY name ASC"#
+            )
+            .fetch_all(pool)
+            .await
+        }
+    }
+
+    /// Snippets visible to a project: its own plus the global ones it does not override
+    pub async fn find_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PromptSnippet,
+            r#"SELECT s.id as "id!: Uuid",
+                      s.project_id as "project_id: Uuid",
+                      s.name,
+                      s.content,
+                      s.description,
+                      s.created_at as "created_at!: DateTime<Utc>",
+                      s.updated_at as "updated_at!: DateTime<Utc>"
+               FROM prompt_snippets s
+               WHERE s.project_id = $1
+                  OR (s.project_id IS NULL AND NOT EXISTS (
+                      SELECT 1 FROM prompt_snippets p
+                      WHERE p.project_id = $1 AND p.name = s.name
+                  ))
+               ORDER BY s.name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PromptSnippet,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      content,
+                      description,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM prompt_snippets
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreatePromptSnippet,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            PromptSnippet,
+            r#"INSERT INTO prompt_snippets (id, project_id, name, content, description)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      content,
+                      description,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.content,
+            data.description
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdatePromptSnippet,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let content = data.content.as_ref().unwrap_or(&existing.content);
+        let description = data.description.as_ref().or(existing.description.as_ref());
+
+        sqlx::query_as!(
+            PromptSnippet,
+            r#"UPDATE prompt_snippets
+               SET name = $2, content = $3, description = $4, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      content,
+                      description,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            content,
+            description
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM prompt_snippets WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
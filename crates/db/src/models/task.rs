@@ -1,11 +1,14 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, task_attempt::TaskAttempt};
+use super::{label::Label, project::Project, task_attempt::TaskAttempt};
+use crate::pagination::{Page, SortDirection};
 
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display)]
 #[sqlx(type_name = "task_status", rename_all = "lowercase")]
@@ -42,6 +45,10 @@ pub struct TaskWithAttemptStatus {
     pub has_merged_attempt: bool,
     pub last_attempt_failed: bool,
     pub executor: String,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    #[serde(default)]
+    pub due_at: Option<DateTime<Utc>>,
 }
 
 impl std::ops::Deref for TaskWithAttemptStatus {
@@ -57,6 +64,43 @@ impl std::ops::DerefMut for TaskWithAttemptStatus {
     }
 }
 
+/// What a task list is ordered by; ties are broken by id
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Title,
+    /// Board order: todo first, cancelled last
+    Status,
+    /// Tasks without a due date come last either way
+    DueAt,
+}
+
+impl TaskSortField {
+    /// Key the paged task query picks its sort expression by
+    fn as_sql(self) -> &'static str {
+        match self {
+            TaskSortField::CreatedAt => "created_at",
+            TaskSortField::UpdatedAt => "updated_at",
+            TaskSortField::Title => "title",
+            TaskSortField::Status => "status",
+            TaskSortField::DueAt => "due_at",
+        }
+    }
+}
+
+/// Filters, order and window for a project's task list
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TaskListOptions {
+    /// Only tasks carrying this label
+    pub label_id: Option<Uuid>,
+    pub sort: TaskSortField,
+    pub direction: SortDirection,
+    pub page: Page,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct TaskRelationships {
     pub parent_task: Option<Task>,    // The task that owns this attempt
@@ -102,6 +146,44 @@ pub struct UpdateTask {
     pub image_ids: Option<Vec<Uuid>>,
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct SetTaskDueDate {
+    /// None clears the due date
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+/// An open task whose due date has passed
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct OverdueTask {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    #[sqlx(flatten)]
+    pub task: Task,
+    pub due_at: DateTime<Utc>,
+}
+
+/// A task with a due date, whether or not it has passed
+#[derive(Debug, Clone, FromRow)]
+pub struct DueTask {
+    #[sqlx(flatten)]
+    pub task: Task,
+    pub due_at: DateTime<Utc>,
+}
+
+/// A task that reached Done, with the details release notes are built from.
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct CompletedTask {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    /// Latest summary reported by a coding agent for any of the task's attempts
+    pub summary: Option<String>,
+    pub pr_number: Option<i64>,
+    pub pr_url: Option<String>,
+    #[ts(type = "Date")]
+    pub completed_at: DateTime<Utc>,
+}
+
 impl Task {
     pub fn to_prompt(&self) -> String {
         if let Some(description) = self.description.as_ref().filter(|d| !d.trim().is_empty()) {
@@ -180,6 +262,8 @@ ORDER BY t.created_at DESC"#,
         .fetch_all(pool)
         .await?;
 
+        let mut labels = Label::find_by_task_for_project(pool, project_id).await?;
+        let due_dates = Self::find_due_dates_for_project(pool, project_id).await?;
         let tasks = records
             .into_iter()
             .map(|rec| TaskWithAttemptStatus {
@@ -199,12 +283,241 @@ ORDER BY t.created_at DESC"#,
                 has_merged_attempt: false, // TODO use merges table
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 executor: rec.executor,
+                labels: labels.remove(&rec.id).unwrap_or_default(),
+                due_at: due_dates.get(&rec.id).copied(),
             })
             .collect();
 
         Ok(tasks)
     }
 
+    /// A single task with its attempt status, labels and due date
+    pub async fn find_by_id_with_attempt_status(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<TaskWithAttemptStatus>, sqlx::Error> {
+        let Some(rec) = sqlx::query!(
+            r#"SELECT
+  t.id                            AS "id!: Uuid",
+  t.project_id                    AS "project_id!: Uuid",
+  t.title,
+  t.description,
+  t.status                        AS "status!: TaskStatus",
+  t.parent_task_attempt           AS "parent_task_attempt: Uuid",
+  t.parent_task_id                AS "parent_task_id: Uuid",
+  t.due_at                        AS "due_at: DateTime<Utc>",
+  t.created_at                    AS "created_at!: DateTime<Utc>",
+  t.updated_at                    AS "updated_at!: DateTime<Utc>",
+
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM task_attempts ta
+      JOIN execution_processes ep
+        ON ep.task_attempt_id = ta.id
+     WHERE ta.task_id       = t.id
+       AND ep.status        = 'running'
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+     LIMIT 1
+  ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
+
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM task_attempts ta
+      JOIN execution_processes ep
+        ON ep.task_attempt_id = ta.id
+     WHERE ta.task_id       = t.id
+       AND ep.status        = 'running'
+       AND ep.run_reason    = 'devserver'
+     LIMIT 1
+  ) THEN 1 ELSE 0 END            AS "has_running_dev_server!: i64",
+
+  CASE WHEN (
+    SELECT ep.status
+      FROM task_attempts ta
+      JOIN execution_processes ep
+        ON ep.task_attempt_id = ta.id
+     WHERE ta.task_id       = t.id
+     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+     ORDER BY ep.created_at DESC
+     LIMIT 1
+  ) IN ('failed','killed') THEN 1 ELSE 0 END
+                                 AS "last_attempt_failed!: i64",
+
+  ( SELECT ta.executor
+      FROM task_attempts ta
+      WHERE ta.task_id = t.id
+     ORDER BY ta.created_at DESC
+      LIMIT 1
+    )                               AS "executor!: String"
+
+FROM tasks t
+WHERE t.id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let labels = Label::find_by_task_id(pool, rec.id).await?;
+        Ok(Some(TaskWithAttemptStatus {
+            task: Task {
+                id: rec.id,
+                project_id: rec.project_id,
+                title: rec.title,
+                description: rec.description,
+                status: rec.status,
+                parent_task_attempt: rec.parent_task_attempt,
+                parent_task_id: rec.parent_task_id,
+                created_at: rec.created_at,
+                updated_at: rec.updated_at,
+            },
+            has_in_progress_attempt: rec.has_in_progress_attempt != 0,
+            has_running_dev_server: rec.has_running_dev_server != 0,
+            has_merged_attempt: false, // TODO use merges table
+            last_attempt_failed: rec.last_attempt_failed != 0,
+            executor: rec.executor,
+            labels,
+            due_at: rec.due_at,
+        }))
+    }
+
+    /// One page of the project's tasks with attempt status, and how many tasks match in all
+    pub async fn find_page_by_project_id_with_attempt_status(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        options: &TaskListOptions,
+    ) -> Result<(Vec<TaskWithAttemptStatus>, i64), sqlx::Error> {
+        let sort = options.sort.as_sql();
+        let direction = options.direction.as_sql();
+        let limit = options.page.sql_limit();
+        let offset = options.page.sql_offset();
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM tasks t
+               WHERE t.project_id = $1
+                 AND ($2 IS NULL OR EXISTS (
+                     SELECT 1 FROM task_labels tl WHERE tl.task_id = t.id AND tl.label_id = $2
+                 ))"#,
+            project_id,
+            options.label_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        // The sort key and direction are parameters so the query can be checked at compile time;
+        // only one of the two ORDER BY CASE terms is non-NULL for a given direction.
+        let records = sqlx::query!(
+            r#"SELECT
+  t.id                            AS "id!: Uuid",
+  t.project_id                    AS "project_id!: Uuid",
+  t.title,
+  t.description,
+  t.status                        AS "status!: TaskStatus",
+  t.parent_task_attempt           AS "parent_task_attempt: Uuid",
+  t.parent_task_id                AS "parent_task_id: Uuid",
+  t.created_at                    AS "created_at!: DateTime<Utc>",
+  t.updated_at                    AS "updated_at!: DateTime<Utc>",
+  EXISTS (
+    SELECT 1
+      FROM task_attempts ta
+      JOIN execution_processes ep ON ep.task_attempt_id = ta.id
+     WHERE ta.task_id = t.id
+       AND ep.status = 'running'
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+  )                               AS "has_in_progress_attempt!: bool",
+  EXISTS (
+    SELECT 1
+      FROM task_attempts ta
+      JOIN execution_processes ep ON ep.task_attempt_id = ta.id
+     WHERE ta.task_id = t.id
+       AND ep.status = 'running'
+       AND ep.run_reason = 'devserver'
+  )                               AS "has_running_dev_server!: bool",
+  COALESCE((
+    SELECT ep.status
+      FROM task_attempts ta
+      JOIN execution_processes ep ON ep.task_attempt_id = ta.id
+     WHERE ta.task_id = t.id
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+     ORDER BY ep.created_at DESC
+     LIMIT 1
+  ) IN ('failed','killed'), 0)    AS "last_attempt_failed!: bool",
+  ( SELECT ta.executor
+      FROM task_attempts ta
+     WHERE ta.task_id = t.id
+     ORDER BY ta.created_at DESC
+     LIMIT 1
+  )                               AS "executor?: String"
+FROM tasks t
+WHERE t.project_id = $1
+  AND ($2 IS NULL OR EXISTS (
+      SELECT 1 FROM task_labels tl WHERE tl.task_id = t.id AND tl.label_id = $2
+  ))
+ORDER BY
+  CASE WHEN $3 = 'due_at' THEN t.due_at IS NULL END,
+  CASE WHEN $4 = 'ASC' THEN
+    CASE $3
+      WHEN 'created_at' THEN t.created_at
+      WHEN 'updated_at' THEN t.updated_at
+      WHEN 'title' THEN lower(t.title)
+      WHEN 'status' THEN CASE t.status WHEN 'todo' THEN 0 WHEN 'inprogress' THEN 1
+                                       WHEN 'inreview' THEN 2 WHEN 'done' THEN 3 ELSE 4 END
+      WHEN 'due_at' THEN t.due_at
+    END
+  END ASC,
+  CASE WHEN $4 = 'DESC' THEN
+    CASE $3
+      WHEN 'created_at' THEN t.created_at
+      WHEN 'updated_at' THEN t.updated_at
+      WHEN 'title' THEN lower(t.title)
+      WHEN 'status' THEN CASE t.status WHEN 'todo' THEN 0 WHEN 'inprogress' THEN 1
+                                       WHEN 'inreview' THEN 2 WHEN 'done' THEN 3 ELSE 4 END
+      WHEN 'due_at' THEN t.due_at
+    END
+  END DESC,
+  CASE WHEN $4 = 'ASC' THEN t.id END ASC,
+  t.id DESC
+LIMIT $5 OFFSET $6"#,
+            project_id,
+            options.label_id,
+            sort,
+            direction,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut labels = Label::find_by_task_for_project(pool, project_id).await?;
+        let due_dates = Self::find_due_dates_for_project(pool, project_id).await?;
+        let tasks = records
+            .into_iter()
+            .map(|rec| TaskWithAttemptStatus {
+                task: Task {
+                    id: rec.id,
+                    project_id: rec.project_id,
+                    title: rec.title,
+                    description: rec.description,
+                    status: rec.status,
+                    parent_task_attempt: rec.parent_task_attempt,
+                    parent_task_id: rec.parent_task_id,
+                    created_at: rec.created_at,
+                    updated_at: rec.updated_at,
+                },
+                has_in_progress_attempt: rec.has_in_progress_attempt,
+                has_running_dev_server: rec.has_running_dev_server,
+                has_merged_attempt: false, // TODO use merges table
+                last_attempt_failed: rec.last_attempt_failed,
+                executor: rec.executor.unwrap_or_default(),
+                labels: labels.remove(&rec.id).unwrap_or_default(),
+                due_at: due_dates.get(&rec.id).copied(),
+            })
+            .collect();
+        Ok((tasks, total))
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
@@ -246,8 +559,8 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
-    pub async fn create(
-        pool: &SqlitePool,
+    pub async fn create<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
         data: &CreateTask,
         task_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
@@ -264,7 +577,7 @@ ORDER BY t.created_at DESC"#,
             data.parent_task_attempt,
             data.parent_task_id
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
     }
 
@@ -298,8 +611,8 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
-    pub async fn update_status(
-        pool: &SqlitePool,
+    pub async fn update_status<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
         id: Uuid,
         status: TaskStatus,
     ) -> Result<(), sqlx::Error> {
@@ -308,7 +621,7 @@ ORDER BY t.created_at DESC"#,
             id,
             status
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
         Ok(())
     }
@@ -408,4 +721,189 @@ ORDER BY t.created_at DESC"#,
             subtasks,
         })
     }
+
+    pub async fn find_due_at(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT due_at as "due_at: DateTime<Utc>" FROM tasks WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+    }
+
+    /// Due dates of the project's tasks that have one, keyed by task id
+    pub async fn find_due_dates_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<HashMap<Uuid, DateTime<Utc>>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT id as "id!: Uuid", due_at as "due_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1 AND due_at IS NOT NULL"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(|row| (row.id, row.due_at)).collect())
+    }
+
+    /// Set or clear the due date. Bumps `updated_at` so task streams pick up the change.
+    pub async fn set_due_at<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
+        id: Uuid,
+        due_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET due_at = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+            due_at,
+            id
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Tasks of a project that are neither Done nor Cancelled and were due before `now`, most
+    /// overdue first
+    pub async fn find_overdue(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<OverdueTask>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      status as "status!: TaskStatus",
+                      parent_task_attempt as "parent_task_attempt: Uuid",
+                      parent_task_id as "parent_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>",
+                      due_at as "due_at!: DateTime<Utc>"
+                 FROM tasks
+                WHERE project_id = $1 AND due_at IS NOT NULL AND datetime(due_at) < datetime($2)
+                  AND status NOT IN ('done', 'cancelled')
+                ORDER BY datetime(due_at) ASC"#,
+            project_id,
+            now
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| OverdueTask {
+                task: Task {
+                    id: row.id,
+                    project_id: row.project_id,
+                    title: row.title,
+                    description: row.description,
+                    status: row.status,
+                    parent_task_attempt: row.parent_task_attempt,
+                    parent_task_id: row.parent_task_id,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                },
+                due_at: row.due_at,
+            })
+            .collect())
+    }
+
+    /// Tasks of a project that are neither Done nor Cancelled and have a due date, soonest first
+    pub async fn find_open_with_due_date(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<DueTask>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      title,
+                      description,
+                      status as "status!: TaskStatus",
+                      parent_task_attempt as "parent_task_attempt: Uuid",
+                      parent_task_id as "parent_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>",
+                      due_at as "due_at!: DateTime<Utc>"
+                 FROM tasks
+                WHERE project_id = $1 AND due_at IS NOT NULL
+                  AND status NOT IN ('done', 'cancelled')
+                ORDER BY datetime(due_at) ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| DueTask {
+                task: Task {
+                    id: row.id,
+                    project_id: row.project_id,
+                    title: row.title,
+                    description: row.description,
+                    status: row.status,
+                    parent_task_attempt: row.parent_task_attempt,
+                    parent_task_id: row.parent_task_id,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                },
+                due_at: row.due_at,
+            })
+            .collect())
+    }
+
+    /// Tasks of a project that are Done and were last updated in `[from, to)`, oldest first,
+    /// leaving out tasks labelled `excluded_label`.
+    /// Tasks have no dedicated completion timestamp, so `updated_at` stands in for it.
+    pub async fn find_completed_between(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        excluded_label: &str,
+    ) -> Result<Vec<CompletedTask>, sqlx::Error> {
+        let done = TaskStatus::Done;
+        sqlx::query_as!(
+            CompletedTask,
+            r#"SELECT t.id as "id!: Uuid",
+                      t.title,
+                      t.description,
+                      t.updated_at as "completed_at!: DateTime<Utc>",
+                      (SELECT es.summary
+                         FROM executor_sessions es
+                         JOIN task_attempts ta ON ta.id = es.task_attempt_id
+                        WHERE ta.task_id = t.id AND es.summary IS NOT NULL
+                        ORDER BY es.created_at DESC
+                        LIMIT 1) as "summary?: String",
+                      pr.pr_number as "pr_number?: i64",
+                      pr.pr_url as "pr_url?: String"
+                 FROM tasks t
+                 LEFT JOIN (
+                      SELECT ta.task_id, m.pr_number, m.pr_url,
+                             ROW_NUMBER() OVER (PARTITION BY ta.task_id ORDER BY m.created_at DESC) AS rn
+                        FROM merges m
+                        JOIN task_attempts ta ON ta.id = m.task_attempt_id
+                       WHERE m.merge_type = 'pr'
+                 ) pr ON pr.task_id = t.id AND pr.rn = 1
+                WHERE t.project_id = $1 AND t.status = $2 AND t.updated_at >= datetime($3) AND t.updated_at < datetime($4)
+                  AND NOT EXISTS (
+                      SELECT 1
+                        FROM task_labels tl
+                        JOIN labels l ON l.id = tl.label_id
+                       WHERE tl.task_id = t.id AND l.name = $5
+                  )
+                ORDER BY t.updated_at ASC"#,
+            project_id,
+            done,
+            from,
+            to,
+            excluded_label
+        )
+        .fetch_all(pool)
+        .await
+    }
 }
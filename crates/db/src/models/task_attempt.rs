@@ -3,12 +3,16 @@ use std::collections::HashSet;
 use chrono::{DateTime, Utc};
 use executors::executors::BaseCodingAgent;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Sqlite, SqlitePool, Transaction, Type};
+use sqlx::{Acquire, Executor, FromRow, Sqlite, SqlitePool, Transaction, Type};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, task::Task};
+use super::{
+    project::Project,
+    task::{Task, TaskStatus},
+};
+use crate::pagination::{Page, SortDirection};
 
 #[derive(Debug, Error)]
 pub enum TaskAttemptError {
@@ -51,6 +55,24 @@ pub struct TaskAttempt {
     pub updated_at: DateTime<Utc>,
 }
 
+/// What an attempt list is ordered by; ties are broken by id
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskAttemptSortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl TaskAttemptSortField {
+    fn as_sql(self) -> &'static str {
+        match self {
+            TaskAttemptSortField::CreatedAt => "created_at",
+            TaskAttemptSortField::UpdatedAt => "updated_at",
+        }
+    }
+}
+
 /// GitHub PR creation parameters
 pub struct CreatePrParams<'a> {
     pub attempt_id: Uuid,
@@ -74,6 +96,22 @@ pub struct AttemptResumeContext {
     pub cumulative_diffs: String,
 }
 
+/// Attempt branch that may be ready for deletion, with what is needed to decide whether it is stale
+#[derive(Debug, Clone, FromRow)]
+pub struct BranchCleanupCandidate {
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub task_status: TaskStatus,
+    pub branch: String,
+    pub target_branch: String,
+    pub container_ref: Option<String>,
+    pub worktree_deleted: bool,
+    /// A direct merge or a merged PR was recorded for the attempt
+    pub has_recorded_merge: bool,
+    pub has_running_process: bool,
+}
+
 #[derive(Debug)]
 pub struct TaskAttemptContext {
     pub task_attempt: TaskAttempt,
@@ -153,6 +191,61 @@ impl TaskAttempt {
         Ok(attempts)
     }
 
+    /// One page of attempts, optionally only those of `task_id`, and how many match in all
+    pub async fn fetch_page(
+        pool: &SqlitePool,
+        task_id: Option<Uuid>,
+        sort: TaskAttemptSortField,
+        direction: SortDirection,
+        page: Page,
+    ) -> Result<(Vec<Self>, i64), TaskAttemptError> {
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM task_attempts
+               WHERE $1 IS NULL OR task_id = $1"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        let sort = sort.as_sql();
+        let direction = direction.as_sql();
+        let limit = page.sql_limit();
+        let offset = page.sql_offset();
+        let attempts = sqlx::query_as!(
+            TaskAttempt,
+            r#"SELECT id AS "id!: Uuid",
+                      task_id AS "task_id!: Uuid",
+                      container_ref,
+                      branch,
+                      target_branch,
+                      executor AS "executor!",
+                      worktree_deleted AS "worktree_deleted!: bool",
+                      setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM task_attempts
+               WHERE $1 IS NULL OR task_id = $1
+               ORDER BY
+                 CASE WHEN $3 = 'ASC' THEN
+                   CASE $2 WHEN 'updated_at' THEN updated_at ELSE created_at END
+                 END ASC,
+                 CASE WHEN $3 = 'DESC' THEN
+                   CASE $2 WHEN 'updated_at' THEN updated_at ELSE created_at END
+                 END DESC,
+                 CASE WHEN $3 = 'ASC' THEN id END ASC,
+                 id DESC
+               LIMIT $4 OFFSET $5"#,
+            task_id,
+            sort,
+            direction,
+            limit,
+            offset
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok((attempts, total))
+    }
+
     /// Load task attempt with full validation - ensures task_attempt belongs to task and task belongs to project
     pub async fn load_context(
         pool: &SqlitePool,
@@ -220,15 +313,15 @@ impl TaskAttempt {
     }
 
     /// Helper function to mark a worktree as deleted in the database
-    pub async fn mark_worktree_deleted(
-        pool: &SqlitePool,
+    pub async fn mark_worktree_deleted<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
         attempt_id: Uuid,
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
             "UPDATE task_attempts SET worktree_deleted = TRUE, updated_at = datetime('now') WHERE id = ?",
             attempt_id
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
         Ok(())
     }
@@ -254,6 +347,33 @@ impl TaskAttempt {
         .await
     }
 
+    /// The task's most recently created attempt
+    pub async fn find_latest_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttempt,
+            r#"SELECT  id                AS "id!: Uuid",
+                       task_id           AS "task_id!: Uuid",
+                       container_ref,
+                       branch,
+                       target_branch,
+                       executor AS "executor!",
+                       worktree_deleted  AS "worktree_deleted!: bool",
+                       setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       created_at        AS "created_at!: DateTime<Utc>",
+                       updated_at        AS "updated_at!: DateTime<Utc>"
+               FROM    task_attempts
+               WHERE   task_id = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             TaskAttempt,
@@ -312,6 +432,35 @@ impl TaskAttempt {
             .collect())
     }
 
+    /// Attempts in a project that still have a live worktree, newest first.
+    pub async fn find_active_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttempt,
+            r#"SELECT ta.id AS "id!: Uuid",
+                      ta.task_id AS "task_id!: Uuid",
+                      ta.container_ref,
+                      ta.branch,
+                      ta.target_branch,
+                      ta.executor AS "executor!",
+                      ta.worktree_deleted AS "worktree_deleted!: bool",
+                      ta.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                      ta.created_at AS "created_at!: DateTime<Utc>",
+                      ta.updated_at AS "updated_at!: DateTime<Utc>"
+               FROM task_attempts ta
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1
+                 AND ta.worktree_deleted = FALSE
+                 AND ta.container_ref IS NOT NULL
+               ORDER BY ta.created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn container_ref_exists(
         pool: &SqlitePool,
         container_ref: &str,
@@ -375,13 +524,13 @@ impl TaskAttempt {
             .collect())
     }
 
-    pub async fn create(
-        pool: &SqlitePool,
+    pub async fn create<'a, A: Acquire<'a, Database = Sqlite>>(
+        conn: A,
         data: &CreateTaskAttempt,
         id: Uuid,
         task_id: Uuid,
     ) -> Result<Self, TaskAttemptError> {
-        let mut tx: Transaction<'_, Sqlite> = pool.begin().await?;
+        let mut tx: Transaction<'_, Sqlite> = conn.begin().await?;
 
         let project_row = sqlx::query!(
             r#"SELECT project_id as "project_id!: Uuid" FROM tasks WHERE id = $1"#,
@@ -570,6 +719,194 @@ impl TaskAttempt {
         Ok(())
     }
 
+    /// Record that the attempt was branched off `parent_attempt_id` (stacked attempts)
+    pub async fn set_parent_attempt(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+        parent_attempt_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE task_attempts SET parent_attempt_id = $1 WHERE id = $2",
+            parent_attempt_id,
+            attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the signed-in user who started the attempt
+    pub async fn set_created_by(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE task_attempts SET created_by_user_id = $1 WHERE id = $2",
+            user_id,
+            attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the executor now driving the attempt, e.g. after a handoff
+    pub async fn update_executor(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+        executor: &BaseCodingAgent,
+    ) -> Result<(), sqlx::Error> {
+        let executor = executor.to_string();
+        sqlx::query!(
+            "UPDATE task_attempts SET executor = $1, updated_at = datetime('now', 'subsec')
+             WHERE id = $2",
+            executor,
+            attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Claim one automatic fix follow-up for the attempt and return how many have now been sent.
+    /// Returns `None` once `limit` follow-ups were sent since the attempt's scripts last passed.
+    pub async fn try_claim_auto_fix(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+        limit: u32,
+    ) -> Result<Option<u32>, sqlx::Error> {
+        let limit = i64::from(limit);
+        let count = sqlx::query_scalar!(
+            r#"UPDATE task_attempts SET auto_fix_count = auto_fix_count + 1
+               WHERE id = $1 AND auto_fix_count < $2
+               RETURNING auto_fix_count as "auto_fix_count!: i64""#,
+            attempt_id,
+            limit
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(count.and_then(|count| u32::try_from(count).ok()))
+    }
+
+    pub async fn reset_auto_fix_count(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE task_attempts SET auto_fix_count = 0 WHERE id = $1 AND auto_fix_count > 0",
+            attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_parent_attempt_id(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT parent_attempt_id as "parent_attempt_id: Uuid" FROM task_attempts WHERE id = $1"#,
+            attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+    }
+
+    /// Attempts stacked directly on `parent_attempt_id`, oldest first
+    pub async fn find_child_attempts(
+        pool: &SqlitePool,
+        parent_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttempt,
+            r#"SELECT id AS "id!: Uuid",
+                      task_id AS "task_id!: Uuid",
+                      container_ref,
+                      branch,
+                      target_branch,
+                      executor AS "executor!",
+                      worktree_deleted AS "worktree_deleted!: bool",
+                      setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM task_attempts
+               WHERE parent_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            parent_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Point children that still target the parent's branch at `new_target_branch`, once the
+    /// parent has landed there. Returns the number of attempts updated.
+    pub async fn retarget_child_attempts(
+        pool: &SqlitePool,
+        parent_attempt_id: Uuid,
+        parent_branch: &str,
+        new_target_branch: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE task_attempts
+               SET target_branch = $1, updated_at = datetime('now', 'subsec')
+               WHERE parent_attempt_id = $2 AND target_branch = $3"#,
+            new_target_branch,
+            parent_attempt_id,
+            parent_branch
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Record activity on the attempt without changing it, pushing back worktree cleanup
+    pub async fn touch(pool: &SqlitePool, attempt_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE task_attempts SET updated_at = datetime('now', 'subsec') WHERE id = $1",
+            attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Every attempt of the project with its task state and merge history, newest first.
+    pub async fn find_branch_cleanup_candidates(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<BranchCleanupCandidate>, sqlx::Error> {
+        sqlx::query_as!(
+            BranchCleanupCandidate,
+            r#"SELECT ta.id AS "task_attempt_id!: Uuid",
+                      t.id AS "task_id!: Uuid",
+                      t.title AS task_title,
+                      t.status AS "task_status!: TaskStatus",
+                      ta.branch AS branch,
+                      ta.target_branch AS target_branch,
+                      ta.container_ref AS container_ref,
+                      ta.worktree_deleted AS "worktree_deleted!: bool",
+                      EXISTS (
+                          SELECT 1 FROM merges m
+                          WHERE m.task_attempt_id = ta.id
+                            AND (m.merge_type = 'direct' OR m.pr_status = 'merged')
+                      ) AS "has_recorded_merge!: bool",
+                      EXISTS (
+                          SELECT 1 FROM execution_processes ep
+                          WHERE ep.task_attempt_id = ta.id AND ep.status = 'running'
+                      ) AS "has_running_process!: bool"
+               FROM task_attempts ta
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1
+               ORDER BY ta.created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn resolve_container_ref(
         pool: &SqlitePool,
         container_ref: &str,
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Acquire, Executor, FromRow, Sqlite, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -63,8 +63,8 @@ impl TaskAttemptRepository {
         .await
     }
 
-    pub async fn find_for_attempt(
-        pool: &SqlitePool,
+    pub async fn find_for_attempt<'e, E: Executor<'e, Database = Sqlite>>(
+        executor: E,
         attempt_id: Uuid,
         project_repository_id: Uuid,
     ) -> Result<Option<Self>, sqlx::Error> {
@@ -84,7 +84,7 @@ impl TaskAttemptRepository {
             attempt_id,
             project_repository_id
         )
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await
     }
 
@@ -137,16 +137,19 @@ impl TaskAttemptRepository {
         Ok(())
     }
 
-    pub async fn upsert_branch(
-        pool: &SqlitePool,
+    pub async fn upsert_branch<'a, A: Acquire<'a, Database = Sqlite>>(
+        conn: A,
         attempt_id: Uuid,
         project_repository_id: Uuid,
         is_primary: bool,
         branch: Option<&str>,
         base_branch: Option<&str>,
     ) -> Result<(), sqlx::Error> {
+        let mut conn = conn.acquire().await?;
+
         // First, try to find existing record
-        let existing = Self::find_for_attempt(pool, attempt_id, project_repository_id).await?;
+        let existing =
+            Self::find_for_attempt(&mut *conn, attempt_id, project_repository_id).await?;
 
         if let Some(_existing_record) = existing {
             // Update existing record
@@ -163,7 +166,7 @@ impl TaskAttemptRepository {
                 attempt_id,
                 project_repository_id
             )
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         } else {
             // Insert new record
@@ -185,7 +188,7 @@ impl TaskAttemptRepository {
                 branch,
                 base_branch
             )
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         }
         Ok(())
@@ -0,0 +1,205 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::Task;
+
+#[derive(Debug, Error)]
+pub enum TaskDependencyError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("A task cannot depend on itself")]
+    SelfDependency,
+    #[error("Task not found")]
+    TaskNotFound,
+    #[error("Tasks can only depend on tasks of the same project")]
+    CrossProject,
+    #[error("'{0}' already depends on this task")]
+    Cycle(String),
+}
+
+/// `task_id` is blocked until `depends_on_task_id` is done
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskDependency {
+    pub task_id: Uuid,
+    pub depends_on_task_id: Uuid,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskDependency {
+    /// Make `task` depend on `depends_on_task_id`, rejecting links to itself, to other projects'
+    /// tasks and links that would close a cycle
+    pub async fn add(
+        pool: &SqlitePool,
+        task: &Task,
+        depends_on_task_id: Uuid,
+    ) -> Result<Self, TaskDependencyError> {
+        if depends_on_task_id == task.id {
+            return Err(TaskDependencyError::SelfDependency);
+        }
+        let other = Task::find_by_id(pool, depends_on_task_id)
+            .await?
+            .ok_or(TaskDependencyError::TaskNotFound)?;
+        if other.project_id != task.project_id {
+            return Err(TaskDependencyError::CrossProject);
+        }
+        if Self::depends_transitively(pool, other.id, task.id).await? {
+            return Err(TaskDependencyError::Cycle(other.title));
+        }
+        Ok(Self::create(pool, task.id, other.id).await?)
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query!(
+            "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_task_id) VALUES ($1, $2)",
+            task_id,
+            depends_on_task_id
+        )
+        .execute(pool)
+        .await?;
+        sqlx::query_as!(
+            TaskDependency,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      depends_on_task_id as "depends_on_task_id!: Uuid",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_dependencies
+               WHERE task_id = $1 AND depends_on_task_id = $2"#,
+            task_id,
+            depends_on_task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Returns whether the dependency existed
+    pub async fn delete(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_task_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM task_dependencies WHERE task_id = $1 AND depends_on_task_id = $2",
+            task_id,
+            depends_on_task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Tasks that `task_id` depends on
+    pub async fn find_dependency_ids(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT depends_on_task_id as "depends_on_task_id!: Uuid"
+               FROM task_dependencies
+               WHERE task_id = $1
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tasks that depend on `task_id`
+    pub async fn find_dependent_ids(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT task_id as "task_id!: Uuid"
+               FROM task_dependencies
+               WHERE depends_on_task_id = $1
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskDependency,
+            r#"SELECT td.task_id as "task_id!: Uuid",
+                      td.depends_on_task_id as "depends_on_task_id!: Uuid",
+                      td.created_at as "created_at!: DateTime<Utc>"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.task_id
+               WHERE t.project_id = $1
+               ORDER BY td.created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Whether `task_id` already depends on `other_task_id`, directly or through other tasks
+    pub async fn depends_transitively(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        other_task_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"WITH RECURSIVE reachable(id) AS (
+                   SELECT depends_on_task_id FROM task_dependencies WHERE task_id = $1
+                   UNION
+                   SELECT td.depends_on_task_id
+                   FROM task_dependencies td
+                   JOIN reachable r ON td.task_id = r.id
+               )
+               SELECT EXISTS(SELECT 1 FROM reachable WHERE id = $2) as "exists!: bool""#,
+            task_id,
+            other_task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Id and title of every task `task_id` depends on that is not done yet
+    pub async fn find_unfinished_dependencies(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT t.id as "id!: Uuid", t.title
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.depends_on_task_id
+               WHERE td.task_id = $1 AND t.status != 'done'
+               ORDER BY td.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(|row| (row.id, row.title)).collect())
+    }
+
+    /// Tasks of the project with at least one dependency that is not done yet
+    pub async fn find_blocked_task_ids(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT DISTINCT td.task_id as "task_id!: Uuid"
+               FROM task_dependencies td
+               JOIN tasks t ON t.id = td.task_id
+               JOIN tasks dep ON dep.id = td.depends_on_task_id
+               WHERE t.project_id = $1 AND dep.status != 'done'"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
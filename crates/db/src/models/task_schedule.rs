@@ -0,0 +1,230 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Creates a task from a template every time its cron expression fires
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskSchedule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub template_id: Uuid,
+    pub cron_expression: String,
+    pub enabled: bool,
+    /// When set, an attempt is started with this executor right after the task is created
+    #[ts(type = "ExecutorProfileId | null")]
+    pub executor_profile_id: Option<Json<ExecutorProfileId>>,
+    /// Base branch of the started attempt; defaults to the branch checked out in the project
+    pub base_branch: Option<String>,
+    #[ts(type = "Date")]
+    pub next_run_at: DateTime<Utc>,
+    #[ts(type = "Date | null")]
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_task_id: Option<Uuid>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTaskSchedule {
+    pub template_id: Uuid,
+    pub cron_expression: String,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub base_branch: Option<String>,
+}
+
+/// Replaces the schedule's settings; the template stays the same
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateTaskSchedule {
+    pub cron_expression: String,
+    pub enabled: bool,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub base_branch: Option<String>,
+}
+
+impl TaskSchedule {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      template_id as "template_id!: Uuid",
+                      cron_expression,
+                      enabled as "enabled!: bool",
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      base_branch,
+                      next_run_at as "next_run_at!: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      template_id as "template_id!: Uuid",
+                      cron_expression,
+                      enabled as "enabled!: bool",
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      base_branch,
+                      next_run_at as "next_run_at!: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled schedules whose next run is at or before `now`
+    pub async fn find_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      template_id as "template_id!: Uuid",
+                      cron_expression,
+                      enabled as "enabled!: bool",
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      base_branch,
+                      next_run_at as "next_run_at!: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE enabled = TRUE AND datetime(next_run_at) <= datetime($1)
+               ORDER BY datetime(next_run_at) ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateTaskSchedule,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let cron_expression = data.cron_expression.trim();
+        let executor_profile_id = data.executor_profile_id.clone().map(Json);
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"INSERT INTO task_schedules (id, project_id, template_id, cron_expression,
+                                           executor_profile_id, base_branch, next_run_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      template_id as "template_id!: Uuid",
+                      cron_expression,
+                      enabled as "enabled!: bool",
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      base_branch,
+                      next_run_at as "next_run_at!: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.template_id,
+            cron_expression,
+            executor_profile_id,
+            data.base_branch,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateTaskSchedule,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        let cron_expression = data.cron_expression.trim();
+        let executor_profile_id = data.executor_profile_id.clone().map(Json);
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"UPDATE task_schedules
+               SET cron_expression = $1, enabled = $2, executor_profile_id = $3, base_branch = $4,
+                   next_run_at = $5, updated_at = datetime('now', 'subsec')
+               WHERE id = $6
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      template_id as "template_id!: Uuid",
+                      cron_expression,
+                      enabled as "enabled!: bool",
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      base_branch,
+                      next_run_at as "next_run_at!: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            cron_expression,
+            data.enabled,
+            executor_profile_id,
+            data.base_branch,
+            next_run_at,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record that the schedule created `task_id` and move it on to its next run. A schedule
+    /// that will never fire again is disabled.
+    pub async fn record_run(
+        pool: &SqlitePool,
+        id: Uuid,
+        ran_at: DateTime<Utc>,
+        task_id: Uuid,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_schedules
+               SET last_run_at = $1, last_task_id = $2, next_run_at = COALESCE($3, next_run_at),
+                   enabled = enabled AND $3 IS NOT NULL, updated_at = datetime('now', 'subsec')
+               WHERE id = $4"#,
+            ran_at,
+            task_id,
+            next_run_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_schedules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::Display;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A coding agent whose usage is sampled, named as in `/usage/{agent}` paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Type, Serialize, Deserialize, TS, Display)]
+#[sqlx(type_name = "TEXT", rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum UsageAgent {
+    Codex,
+    ClaudeCode,
+}
+
+/// An agent's usage at one point in time
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct UsageSample {
+    pub id: Uuid,
+    pub agent: UsageAgent,
+    /// Share of the current rate limit window used, 0-100
+    pub used_percent: Option<f64>,
+    /// Share of Codex's weekly window used
+    pub secondary_used_percent: Option<f64>,
+    #[ts(type = "number | null")]
+    pub total_tokens: Option<i64>,
+    #[ts(type = "Date")]
+    pub sampled_at: DateTime<Utc>,
+    /// When the current rate limit window resets, if the agent reports it
+    #[ts(type = "Date | null")]
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateUsageSample {
+    pub agent: UsageAgent,
+    pub used_percent: Option<f64>,
+    pub secondary_used_percent: Option<f64>,
+    pub total_tokens: Option<i64>,
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+impl UsageSample {
+    pub async fn create(pool: &SqlitePool, data: &CreateUsageSample) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            UsageSample,
+            r#"INSERT INTO usage_samples (
+                   id, agent, used_percent, secondary_used_percent, total_tokens, resets_at
+               )
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         agent as "agent!: UsageAgent",
+                         used_percent,
+                         secondary_used_percent,
+                         total_tokens,
+                         sampled_at as "sampled_at!: DateTime<Utc>",
+                         resets_at as "resets_at: DateTime<Utc>""#,
+            id,
+            data.agent,
+            data.used_percent,
+            data.secondary_used_percent,
+            data.total_tokens,
+            data.resets_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Samples for `agent` taken from `from` up to `to`, oldest first
+    pub async fn find_range(
+        pool: &SqlitePool,
+        agent: UsageAgent,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            UsageSample,
+            r#"SELECT id as "id!: Uuid",
+                      agent as "agent!: UsageAgent",
+                      used_percent,
+                      secondary_used_percent,
+                      total_tokens,
+                      sampled_at as "sampled_at!: DateTime<Utc>",
+                      resets_at as "resets_at: DateTime<Utc>"
+               FROM usage_samples
+               WHERE agent = $1 AND sampled_at >= datetime($2) AND sampled_at <= datetime($3)
+               ORDER BY sampled_at ASC"#,
+            agent,
+            from,
+            to
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The most recent sample for `agent`
+    pub async fn find_latest(
+        pool: &SqlitePool,
+        agent: UsageAgent,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            UsageSample,
+            r#"SELECT id as "id!: Uuid",
+                      agent as "agent!: UsageAgent",
+                      used_percent,
+                      secondary_used_percent,
+                      total_tokens,
+                      sampled_at as "sampled_at!: DateTime<Utc>",
+                      resets_at as "resets_at: DateTime<Utc>"
+               FROM usage_samples
+               WHERE agent = $1
+               ORDER BY sampled_at DESC
+               LIMIT 1"#,
+            agent
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete_before(
+        pool: &SqlitePool,
+        before: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM usage_samples WHERE sampled_at < datetime($1)"#,
+            before
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
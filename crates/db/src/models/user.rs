@@ -0,0 +1,213 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::Display;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What a user may do. Each role can do everything the roles after it can.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Type, Serialize, Deserialize, TS, Display,
+)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum UserRole {
+    /// Manages accounts and app settings
+    Admin,
+    /// Creates and changes projects, tasks and attempts
+    Contributor,
+    /// Read-only access
+    Viewer,
+}
+
+impl UserRole {
+    /// Whether this role has at least the permissions of `required`
+    pub fn allows(self, required: UserRole) -> bool {
+        self <= required
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct User {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: String,
+    pub role: UserRole,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateUser {
+    pub username: String,
+    /// Defaults to the username
+    pub display_name: Option<String>,
+    pub password: String,
+    pub role: UserRole,
+}
+
+/// Fields left out are not changed
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateUser {
+    pub display_name: Option<String>,
+    pub password: Option<String>,
+    pub role: Option<UserRole>,
+}
+
+impl User {
+    pub async fn count(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!: i64" FROM users"#)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn count_admins(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!: i64" FROM users WHERE role = 'admin'"#)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"SELECT id as "id!: Uuid",
+                      username,
+                      display_name,
+                      role as "role!: UserRole",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM users
+               ORDER BY username ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"SELECT id as "id!: Uuid",
+                      username,
+                      display_name,
+                      role as "role!: UserRole",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM users
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The user with `username`, ignoring case, and their password hash
+    pub async fn find_credentials(
+        pool: &SqlitePool,
+        username: &str,
+    ) -> Result<Option<(Self, String)>, sqlx::Error> {
+        let username = username.trim();
+        let row = sqlx::query!(
+            r#"SELECT id as "id!: Uuid",
+                      username,
+                      display_name,
+                      role as "role!: UserRole",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>",
+                      password_hash
+               FROM users
+               WHERE username = $1"#,
+            username
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(|row| {
+            let user = User {
+                id: row.id,
+                username: row.username,
+                display_name: row.display_name,
+                role: row.role,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            };
+            (user, row.password_hash)
+        }))
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateUser,
+        password_hash: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let username = data.username.trim();
+        let display_name = data
+            .display_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(username);
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            User,
+            r#"INSERT INTO users (id, username, display_name, password_hash, role)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                      username,
+                      display_name,
+                      role as "role!: UserRole",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            username,
+            display_name,
+            password_hash,
+            data.role
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Apply `data`, with the password already hashed
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateUser,
+        password_hash: Option<&str>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let display_name = data
+            .display_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty());
+        sqlx::query_as!(
+            User,
+            r#"UPDATE users
+               SET display_name = COALESCE($1, display_name),
+                   password_hash = COALESCE($2, password_hash),
+                   role = COALESCE($3, role),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $4
+               RETURNING id as "id!: Uuid",
+                      username,
+                      display_name,
+                      role as "role!: UserRole",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            display_name,
+            password_hash,
+            data.role,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM users WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use super::user::{User, UserRole};
+
+/// A login. Sessions are looked up by a hash of their token, the token itself is only known to
+/// the client.
+pub struct UserSession;
+
+impl UserSession {
+    pub async fn create(
+        pool: &SqlitePool,
+        user_id: Uuid,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO user_sessions (id, user_id, token_hash, expires_at)
+               VALUES ($1, $2, $3, $4)"#,
+            id,
+            user_id,
+            token_hash,
+            expires_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The user signed in with the session whose token hashes to `token_hash`, unless the
+    /// session expired before `now`
+    pub async fn find_user(
+        pool: &SqlitePool,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<User>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"SELECT u.id as "id!: Uuid",
+                      u.username,
+                      u.display_name,
+                      u.role as "role!: UserRole",
+                      u.created_at as "created_at!: DateTime<Utc>",
+                      u.updated_at as "updated_at!: DateTime<Utc>"
+               FROM user_sessions s
+               JOIN users u ON u.id = s.user_id
+               WHERE s.token_hash = $1 AND s.expires_at > $2"#,
+            token_hash,
+            now
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, token_hash: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM user_sessions WHERE token_hash = $1",
+            token_hash
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Sign the user out everywhere, e.g. after their password or role changed
+    pub async fn delete_for_user(pool: &SqlitePool, user_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM user_sessions WHERE user_id = $1", user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_expired(pool: &SqlitePool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM user_sessions WHERE expires_at <= $1", now)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
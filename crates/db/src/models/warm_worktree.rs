@@ -0,0 +1,169 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// A worktree created ahead of time on a placeholder branch, waiting to be claimed by a new
+/// attempt of its project
+#[derive(Debug, Clone, FromRow)]
+pub struct WarmWorktree {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub worktree_path: String,
+    pub branch: String,
+    pub base_branch: String,
+    pub base_commit: String,
+    pub ready: bool,
+    pub claimed_by_attempt_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WarmWorktree {
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        worktree_path: &str,
+        branch: &str,
+        base_branch: &str,
+        base_commit: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            WarmWorktree,
+            r#"INSERT INTO warm_worktrees (id, project_id, worktree_path, branch, base_branch, base_commit)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      worktree_path,
+                      branch,
+                      base_branch,
+                      base_commit,
+                      ready as "ready!: bool",
+                      claimed_by_attempt_id as "claimed_by_attempt_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            worktree_path,
+            branch,
+            base_branch,
+            base_commit
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn mark_ready(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("UPDATE warm_worktrees SET ready = TRUE WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Unclaimed worktrees of the project, ready or still being prepared
+    pub async fn find_unclaimed_by_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorktree,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      worktree_path,
+                      branch,
+                      base_branch,
+                      base_commit,
+                      ready as "ready!: bool",
+                      claimed_by_attempt_id as "claimed_by_attempt_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM warm_worktrees
+               WHERE project_id = $1 AND claimed_by_attempt_id IS NULL
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Worktrees whose preparation never finished, e.g. because the server stopped midway
+    pub async fn find_unfinished(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorktree,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      worktree_path,
+                      branch,
+                      base_branch,
+                      base_commit,
+                      ready as "ready!: bool",
+                      claimed_by_attempt_id as "claimed_by_attempt_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM warm_worktrees
+               WHERE ready = FALSE"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Hand the oldest ready worktree built from `base_commit` of `base_branch` to the attempt
+    pub async fn claim(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        base_branch: &str,
+        base_commit: &str,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorktree,
+            r#"UPDATE warm_worktrees SET claimed_by_attempt_id = $1
+               WHERE id = (
+                   SELECT id FROM warm_worktrees
+                   WHERE project_id = $2 AND base_branch = $3 AND base_commit = $4
+                     AND ready = TRUE AND claimed_by_attempt_id IS NULL
+                   ORDER BY created_at ASC
+                   LIMIT 1
+               )
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      worktree_path,
+                      branch,
+                      base_branch,
+                      base_commit,
+                      ready as "ready!: bool",
+                      claimed_by_attempt_id as "claimed_by_attempt_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>""#,
+            task_attempt_id,
+            project_id,
+            base_branch,
+            base_commit
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Forget the worktree claimed by the attempt. Returns whether there was one, i.e. whether
+    /// the attempt's setup script has already run.
+    pub async fn take_claim(pool: &SqlitePool, task_attempt_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM warm_worktrees WHERE claimed_by_attempt_id = $1",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn path_exists(pool: &SqlitePool, worktree_path: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM warm_worktrees WHERE worktree_path = $1) as "exists!: bool""#,
+            worktree_path
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM warm_worktrees WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use strum_macros::Display;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Lifecycle events a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WebhookEvent {
+    TaskCreated,
+    AttemptStarted,
+    AttemptFinished,
+    MergeCompleted,
+}
+
+/// A URL that lifecycle events are POSTed to
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Webhook {
+    pub id: Uuid,
+    /// Only events from this project are delivered; None delivers events from every project
+    pub project_id: Option<Uuid>,
+    pub url: String,
+    /// Key for the HMAC-SHA256 signature sent with every delivery
+    pub secret: String,
+    #[ts(type = "WebhookEvent[]")]
+    pub events: Json<Vec<WebhookEvent>>,
+    pub enabled: bool,
+    #[ts(type = "Date | null")]
+    pub last_delivery_at: Option<DateTime<Utc>>,
+    /// HTTP status of the last delivery; None when no response was received
+    #[ts(type = "number | null")]
+    pub last_response_status: Option<i64>,
+    /// Why the last delivery failed after all retries; cleared by the next successful one
+    pub last_error: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A secret is generated when none is given
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateWebhook {
+    pub project_id: Option<Uuid>,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateWebhook {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+}
+
+impl Webhook {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEvent>>",
+                      enabled as "enabled!: bool",
+                      last_delivery_at as "last_delivery_at: DateTime<Utc>",
+                      last_response_status,
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEvent>>",
+                      enabled as "enabled!: bool",
+                      last_delivery_at as "last_delivery_at: DateTime<Utc>",
+                      last_response_status,
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled webhooks that receive `event` when it happens in `project_id`
+    pub async fn find_subscribed(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        event: WebhookEvent,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let event = event.to_string();
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEvent>>",
+                      enabled as "enabled!: bool",
+                      last_delivery_at as "last_delivery_at: DateTime<Utc>",
+                      last_response_status,
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE enabled = TRUE
+                 AND (project_id IS NULL OR project_id = $1)
+                 AND EXISTS (SELECT 1 FROM json_each(webhooks.events) WHERE value = $2)"#,
+            project_id,
+            event
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateWebhook,
+        secret: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let url = data.url.trim();
+        let events = Json(&data.events);
+        sqlx::query_as!(
+            Webhook,
+            r#"INSERT INTO webhooks (id, project_id, url, secret, events)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEvent>>",
+                      enabled as "enabled!: bool",
+                      last_delivery_at as "last_delivery_at: DateTime<Utc>",
+                      last_response_status,
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            url,
+            secret,
+            events
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateWebhook,
+    ) -> Result<Self, sqlx::Error> {
+        let url = data.url.trim();
+        let events = Json(&data.events);
+        sqlx::query_as!(
+            Webhook,
+            r#"UPDATE webhooks
+               SET url = $1, events = $2, enabled = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $4
+               RETURNING id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEvent>>",
+                      enabled as "enabled!: bool",
+                      last_delivery_at as "last_delivery_at: DateTime<Utc>",
+                      last_response_status,
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>""#,
+            url,
+            events,
+            data.enabled,
+            id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record the outcome of a delivery, including its retries
+    pub async fn record_delivery(
+        pool: &SqlitePool,
+        id: Uuid,
+        response_status: Option<i64>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE webhooks
+               SET last_delivery_at = datetime('now', 'subsec'), last_response_status = $1,
+                   last_error = $2
+               WHERE id = $3"#,
+            response_status,
+            error,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM webhooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
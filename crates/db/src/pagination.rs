@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Largest page a list endpoint hands out, whatever the client asks for
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortDirection {
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        }
+    }
+}
+
+/// A window into a list. Without a limit everything from `offset` on is returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Page {
+    pub limit: Option<u32>,
+    pub offset: u32,
+}
+
+impl Page {
+    pub fn new(limit: Option<u32>, offset: Option<u32>) -> Self {
+        Self {
+            limit: limit.map(|limit| limit.min(MAX_PAGE_SIZE)),
+            offset: offset.unwrap_or(0),
+        }
+    }
+
+    /// Bound to `LIMIT ?`, where a negative limit means no limit
+    pub fn sql_limit(&self) -> i64 {
+        self.limit.map_or(-1, i64::from)
+    }
+
+    pub fn sql_offset(&self) -> i64 {
+        i64::from(self.offset)
+    }
+}
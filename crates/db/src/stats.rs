@@ -0,0 +1,107 @@
+//! Where the database's disk space goes, to judge whether archiving or pruning is worth it.
+
+use serde::Serialize;
+use sqlx::{Error, Row};
+use ts_rs::TS;
+
+use crate::DBService;
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DatabaseStats {
+    pub path: String,
+    #[ts(type = "number")]
+    pub file_bytes: u64,
+    /// Write-ahead log not yet checkpointed into the database file
+    #[ts(type = "number")]
+    pub wal_bytes: u64,
+    /// Space inside the file that is allocated but unused
+    #[ts(type = "number")]
+    pub free_bytes: i64,
+    /// Largest first
+    pub tables: Vec<TableStats>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TableStats {
+    pub name: String,
+    #[ts(type = "number")]
+    pub rows: i64,
+    /// Space used by the table and its indexes; unset when SQLite was built without the `dbstat`
+    /// table
+    #[ts(type = "number | null")]
+    pub bytes: Option<i64>,
+}
+
+fn file_len(path: &str) -> u64 {
+    std::fs::metadata(path).map(|meta| meta.len()).unwrap_or(0)
+}
+
+impl DBService {
+    pub async fn stats(&self) -> Result<DatabaseStats, Error> {
+        let mut conn = self.pool.acquire().await?;
+
+        let path: String = sqlx::query("PRAGMA database_list")
+            .fetch_all(&mut *conn)
+            .await?
+            .into_iter()
+            .find(|row| {
+                row.try_get::<String, _>("name")
+                    .is_ok_and(|name| name == "main")
+            })
+            .and_then(|row| row.try_get("file").ok())
+            .unwrap_or_default();
+        let page_size: i64 = sqlx::query_scalar("PRAGMA page_size")
+            .fetch_one(&mut *conn)
+            .await?;
+        let freelist_count: i64 = sqlx::query_scalar("PRAGMA freelist_count")
+            .fetch_one(&mut *conn)
+            .await?;
+
+        let names = sqlx::query_scalar!(
+            r#"SELECT name as "name!: String" FROM sqlite_schema
+                WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+                ORDER BY name"#
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+        let sizes: Option<Vec<(String, i64)>> = sqlx::query_as(
+            r#"SELECT s.tbl_name, SUM(d.pgsize)
+                 FROM dbstat d
+                 JOIN sqlite_schema s ON s.name = d.name
+                GROUP BY s.tbl_name"#,
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .ok();
+
+        let mut tables = Vec::with_capacity(names.len());
+        for name in names {
+            let rows: i64 = sqlx::query_scalar(&format!(
+                "SELECT COUNT(*) FROM \"{}\"",
+                name.replace('"', "\"\"")
+            ))
+            .fetch_one(&mut *conn)
+            .await?;
+            let bytes = sizes.as_ref().map(|sizes| {
+                sizes
+                    .iter()
+                    .find(|(table, _)| *table == name)
+                    .map_or(0, |(_, bytes)| *bytes)
+            });
+            tables.push(TableStats { name, rows, bytes });
+        }
+        tables.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(b.rows.cmp(&a.rows)));
+
+        Ok(DatabaseStats {
+            file_bytes: file_len(&path),
+            wal_bytes: if path.is_empty() {
+                0
+            } else {
+                file_len(&format!("{path}-wal"))
+            },
+            free_bytes: freelist_count * page_size,
+            path,
+            tables,
+        })
+    }
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use db::models::{
+    activity_event::{ActivityEventEntity, ActivityEventRecord},
+    activity_event_read::ActivityEventRead,
+    task::Task,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::common::{create_project, create_task, setup_test_db};
+
+async fn task_rowid(pool: &SqlitePool, task_id: Uuid) -> i64 {
+    sqlx::query_scalar("SELECT rowid FROM tasks WHERE id = ?")
+        .bind(task_id)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to look up task rowid")
+}
+
+#[tokio::test]
+async fn task_changes_are_recorded_once() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "activity").await;
+    let task = create_task(&pool, project.id, "Write docs").await;
+    let rowid = task_rowid(&pool, task.id).await;
+
+    assert!(
+        ActivityEventRecord::record_task(&pool, rowid)
+            .await
+            .unwrap()
+    );
+    // The update hook can fire again for the same state of the row
+    assert!(
+        !ActivityEventRecord::record_task(&pool, rowid)
+            .await
+            .unwrap()
+    );
+
+    let events = ActivityEventRecord::find_since(&pool, project.id, Utc::now() - Duration::days(1))
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].entity_type, ActivityEventEntity::Task);
+    assert_eq!(events[0].entity_id, task.id);
+    assert_eq!(
+        events[0].headline.as_deref(),
+        Some("Task created: Write docs")
+    );
+    assert_eq!(events[0].status.as_deref(), Some("todo"));
+
+    let later = ActivityEventRecord::find_since(&pool, project.id, Utc::now() + Duration::hours(1))
+        .await
+        .unwrap();
+    assert!(later.is_empty());
+
+    Task::delete(&pool, task.id).await.unwrap();
+    let events = ActivityEventRecord::find_since(&pool, project.id, Utc::now() - Duration::days(1))
+        .await
+        .unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn read_state_is_kept_per_user() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "activity").await;
+    let first = Uuid::new_v4();
+    let second = Uuid::new_v4();
+
+    assert_eq!(
+        ActivityEventRead::mark_read(&pool, "alice", project.id, &[first, second])
+            .await
+            .unwrap(),
+        2
+    );
+    // Marking everything read again only counts what was still unread
+    assert_eq!(
+        ActivityEventRead::mark_read(&pool, "alice", project.id, &[first])
+            .await
+            .unwrap(),
+        0
+    );
+
+    let read = ActivityEventRead::find_read_ids(&pool, "alice", project.id)
+        .await
+        .unwrap();
+    assert_eq!(read.len(), 2);
+    assert!(read.contains(&first) && read.contains(&second));
+    assert!(
+        ActivityEventRead::find_read_ids(&pool, "bob", project.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn pruning_removes_expired_then_oldest_events() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "activity").await;
+    let mut task_ids = Vec::new();
+    for title in ["Oldest", "Middle", "Newest"] {
+        let task = create_task(&pool, project.id, title).await;
+        let rowid = task_rowid(&pool, task.id).await;
+        ActivityEventRecord::record_task(&pool, rowid)
+            .await
+            .unwrap();
+        task_ids.push(task.id);
+    }
+    sqlx::query(
+        "UPDATE activity_events SET created_at = strftime('%Y-%m-%d %H:%M:%f', 'now', '-30 days')
+          WHERE entity_id = ?",
+    )
+    .bind(task_ids[0])
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let since = Utc::now() - Duration::days(60);
+    let deleted = ActivityEventRecord::prune(&pool, Some(Utc::now() - Duration::days(7)), None)
+        .await
+        .unwrap();
+    assert_eq!(deleted, 1);
+    assert_eq!(
+        ActivityEventRecord::find_since(&pool, project.id, since)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let deleted = ActivityEventRecord::prune(&pool, None, Some(1))
+        .await
+        .unwrap();
+    assert_eq!(deleted, 1);
+    let events = ActivityEventRecord::find_since(&pool, project.id, since)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].entity_id, task_ids[2]);
+}
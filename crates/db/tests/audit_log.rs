@@ -0,0 +1,139 @@
+mod common;
+
+use db::{
+    models::{
+        audit_log::{AuditLogEntry, AuditLogFilter, CreateAuditLogEntry},
+        task::{Task, TaskStatus},
+    },
+    pagination::Page,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::common::{create_project, create_task, setup_test_db};
+
+fn entry(actor_user_id: Option<Uuid>, entity_id: Uuid) -> CreateAuditLogEntry {
+    CreateAuditLogEntry {
+        actor_user_id,
+        actor_name: actor_user_id.map(|_| "alice".to_string()),
+        method: "PUT".to_string(),
+        path: format!("/tasks/{entity_id}"),
+        entity_type: Some("tasks".to_string()),
+        entity_id: Some(entity_id),
+        status_code: 200,
+        before_summary: None,
+        after_summary: None,
+    }
+}
+
+#[tokio::test]
+async fn task_summaries_capture_title_and_status() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "board").await;
+    let task = create_task(&pool, project.id, "Fix login").await;
+
+    let before = AuditLogEntry::entity_summary(&pool, "tasks", task.id)
+        .await
+        .unwrap();
+    Task::update_status(&pool, task.id, TaskStatus::Done)
+        .await
+        .unwrap();
+    let after = AuditLogEntry::entity_summary(&pool, "tasks", task.id)
+        .await
+        .unwrap();
+    assert_eq!(
+        before,
+        Some(json!({"title": "Fix login", "status": "todo"}))
+    );
+    assert_eq!(after, Some(json!({"title": "Fix login", "status": "done"})));
+
+    assert_eq!(
+        AuditLogEntry::entity_summary(&pool, "tasks", Uuid::new_v4())
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        AuditLogEntry::entity_summary(&pool, "webhooks", task.id)
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn entries_are_filtered_and_newest_first() {
+    let pool = setup_test_db().await;
+    let actor = Uuid::new_v4();
+    let first = Uuid::new_v4();
+    let second = Uuid::new_v4();
+
+    let mut created = entry(Some(actor), first);
+    created.after_summary = Some(json!({"status": "done"}));
+    AuditLogEntry::create(&pool, created).await.unwrap();
+    AuditLogEntry::create(&pool, entry(Some(actor), second))
+        .await
+        .unwrap();
+    AuditLogEntry::create(&pool, entry(None, second))
+        .await
+        .unwrap();
+
+    let (entries, total) = AuditLogEntry::find(
+        &pool,
+        &AuditLogFilter {
+            actor_user_id: Some(actor),
+            ..Default::default()
+        },
+        Page::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(entries[0].entity_id, Some(second));
+    assert_eq!(entries[1].entity_id, Some(first));
+    assert_eq!(
+        entries[1].after_summary.as_ref().map(|summary| &summary.0),
+        Some(&json!({"status": "done"}))
+    );
+
+    let (entries, total) = AuditLogEntry::find(
+        &pool,
+        &AuditLogFilter {
+            entity_id: Some(second),
+            ..Default::default()
+        },
+        Page::new(Some(1), None),
+    )
+    .await
+    .unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].actor_user_id, None);
+}
+
+#[tokio::test]
+async fn pruning_drops_entries_older_than_the_cutoff() {
+    let pool = setup_test_db().await;
+    let old = AuditLogEntry::create(&pool, entry(None, Uuid::new_v4()))
+        .await
+        .unwrap();
+    sqlx::query("UPDATE audit_log SET created_at = datetime('now', '-100 days') WHERE id = ?")
+        .bind(old.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let recent = AuditLogEntry::create(&pool, entry(None, Uuid::new_v4()))
+        .await
+        .unwrap();
+
+    let deleted = AuditLogEntry::prune(&pool, chrono::Utc::now() - chrono::Duration::days(90))
+        .await
+        .unwrap();
+    assert_eq!(deleted, 1);
+
+    let (entries, total) = AuditLogEntry::find(&pool, &AuditLogFilter::default(), Page::default())
+        .await
+        .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(entries[0].id, recent.id);
+}
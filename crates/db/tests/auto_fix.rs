@@ -0,0 +1,64 @@
+mod common;
+
+use db::models::{project::Project, task_attempt::TaskAttempt};
+
+use crate::common::{create_attempt, create_project, create_task, setup_test_db};
+
+#[tokio::test]
+async fn auto_fixes_stop_at_the_limit_until_reset() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "auto-fix").await;
+    let task = create_task(&pool, project.id, "Fix the build").await;
+    let attempt = create_attempt(&pool, &task, "feature/auto-fix").await;
+
+    assert_eq!(
+        TaskAttempt::try_claim_auto_fix(&pool, attempt.id, 2)
+            .await
+            .unwrap(),
+        Some(1)
+    );
+    assert_eq!(
+        TaskAttempt::try_claim_auto_fix(&pool, attempt.id, 2)
+            .await
+            .unwrap(),
+        Some(2)
+    );
+    assert_eq!(
+        TaskAttempt::try_claim_auto_fix(&pool, attempt.id, 2)
+            .await
+            .unwrap(),
+        None
+    );
+
+    TaskAttempt::reset_auto_fix_count(&pool, attempt.id)
+        .await
+        .unwrap();
+    assert_eq!(
+        TaskAttempt::try_claim_auto_fix(&pool, attempt.id, 2)
+            .await
+            .unwrap(),
+        Some(1)
+    );
+}
+
+#[tokio::test]
+async fn projects_default_to_no_auto_fixes() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "auto-fix").await;
+
+    assert_eq!(
+        Project::find_auto_fix_attempts(&pool, project.id)
+            .await
+            .unwrap(),
+        0
+    );
+    Project::set_auto_fix_attempts(&pool, project.id, 3)
+        .await
+        .unwrap();
+    assert_eq!(
+        Project::find_auto_fix_attempts(&pool, project.id)
+            .await
+            .unwrap(),
+        3
+    );
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use chrono::NaiveDate;
+use db::models::budget_alert::{BudgetAlert, BudgetLevel};
+use executors::executors::BaseCodingAgent;
+
+use crate::common::setup_test_db;
+
+#[tokio::test]
+async fn alerts_are_recorded_once_per_agent_month_and_level() {
+    let pool = setup_test_db().await;
+    let november = NaiveDate::from_ymd_opt(2025, 11, 1).unwrap();
+    let december = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap();
+    let record = |agent, month, level| BudgetAlert::record(&pool, agent, month, level);
+
+    assert!(
+        record(BaseCodingAgent::ClaudeCode, november, BudgetLevel::Warning)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !record(BaseCodingAgent::ClaudeCode, november, BudgetLevel::Warning)
+            .await
+            .unwrap()
+    );
+    assert!(
+        record(BaseCodingAgent::ClaudeCode, november, BudgetLevel::Exceeded)
+            .await
+            .unwrap()
+    );
+    assert!(
+        record(BaseCodingAgent::Codex, november, BudgetLevel::Warning)
+            .await
+            .unwrap()
+    );
+    assert!(
+        record(BaseCodingAgent::ClaudeCode, december, BudgetLevel::Warning)
+            .await
+            .unwrap()
+    );
+}
@@ -0,0 +1,69 @@
+//! Fixtures shared by the database integration tests. Each test binary uses a different subset.
+#![allow(dead_code)]
+
+use db::models::{
+    project::{CreateProject, Project},
+    task::{CreateTask, Task},
+    task_attempt::{CreateTaskAttempt, TaskAttempt},
+};
+use executors::executors::BaseCodingAgent;
+use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use uuid::Uuid;
+
+/// Fresh in-memory database with all migrations applied
+pub async fn setup_test_db() -> SqlitePool {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("Failed to create test database");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("Failed to run migrations");
+    pool
+}
+
+pub async fn create_project(pool: &SqlitePool, name: &str) -> Project {
+    Project::create(
+        pool,
+        &CreateProject {
+            name: name.to_string(),
+            git_repo_path: format!("/tmp/{name}"),
+            use_existing_repo: false,
+            setup_script: None,
+            dev_script: None,
+            cleanup_script: None,
+            copy_files: None,
+        },
+        Uuid::new_v4(),
+    )
+    .await
+    .expect("Failed to create project")
+}
+
+pub async fn create_task(pool: &SqlitePool, project_id: Uuid, title: &str) -> Task {
+    Task::create(
+        pool,
+        &CreateTask::from_title_description(project_id, title.to_string(), None),
+        Uuid::new_v4(),
+    )
+    .await
+    .expect("Failed to create task")
+}
+
+pub async fn create_attempt(pool: &SqlitePool, task: &Task, branch: &str) -> TaskAttempt {
+    TaskAttempt::create(
+        pool,
+        &CreateTaskAttempt {
+            executor: BaseCodingAgent::ClaudeCode,
+            base_branch: "main".to_string(),
+            branch: branch.to_string(),
+            repositories: None,
+        },
+        Uuid::new_v4(),
+        task.id,
+    )
+    .await
+    .expect("Failed to create attempt")
+}
@@ -0,0 +1,46 @@
+use std::str::FromStr;
+
+use db::DBService;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use tempfile::TempDir;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+#[tokio::test]
+async fn stats_report_file_sizes_and_largest_tables_first() -> TestResult<()> {
+    let temp = TempDir::new()?;
+    let db_path = temp.path().join("stats.sqlite");
+    let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", db_path.display()))?
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await?;
+    sqlx::migrate!("./migrations").run(&pool).await?;
+    let db = DBService { pool };
+
+    sqlx::query("CREATE TABLE bulky (data BLOB)")
+        .execute(&db.pool)
+        .await?;
+    for _ in 0..32 {
+        sqlx::query("INSERT INTO bulky VALUES (zeroblob(8192))")
+            .execute(&db.pool)
+            .await?;
+    }
+
+    let stats = db.stats().await?;
+    assert_eq!(stats.path, db_path.to_string_lossy());
+    assert!(stats.file_bytes > 0 || stats.wal_bytes > 0);
+    let bulky = stats
+        .tables
+        .iter()
+        .find(|table| table.name == "bulky")
+        .expect("every table is listed");
+    assert_eq!(bulky.rows, 32);
+    assert!(stats.tables.iter().any(|table| table.name == "tasks"));
+    if bulky.bytes.is_some() {
+        assert_eq!(stats.tables[0].name, "bulky");
+    }
+    Ok(())
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use db::models::diff_comment::{CreateDiffComment, DiffComment, DiffCommentSide};
+use uuid::Uuid;
+
+use crate::common::{create_attempt, create_project, create_task, setup_test_db};
+
+fn comment(file_path: &str, start_line: i64, body: &str) -> CreateDiffComment {
+    CreateDiffComment {
+        repository_id: None,
+        file_path: file_path.to_string(),
+        side: DiffCommentSide::New,
+        start_line,
+        end_line: None,
+        code: None,
+        body: body.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn sent_comments_are_no_longer_pending() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "review").await;
+    let task = create_task(&pool, project.id, "Refactor").await;
+    let attempt = create_attempt(&pool, &task, "feature/review").await;
+
+    let later = DiffComment::create(&pool, attempt.id, &comment("src/b.rs", 3, "Rename"))
+        .await
+        .unwrap();
+    let earlier = DiffComment::create(&pool, attempt.id, &comment("src/a.rs", 9, " Split up "))
+        .await
+        .unwrap();
+    assert_eq!(earlier.end_line, 9);
+    assert_eq!(earlier.body, "Split up");
+
+    let pending = DiffComment::find_by_task_attempt_id(&pool, attempt.id, false)
+        .await
+        .unwrap();
+    let ids: Vec<Uuid> = pending.iter().map(|comment| comment.id).collect();
+    assert_eq!(ids, vec![earlier.id, later.id]);
+
+    DiffComment::mark_sent(&pool, &[earlier.id]).await.unwrap();
+    let pending = DiffComment::find_by_task_attempt_id(&pool, attempt.id, false)
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, later.id);
+
+    let all = DiffComment::find_by_task_attempt_id(&pool, attempt.id, true)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 2);
+    assert!(all[0].sent_at.is_some());
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use db::{
+    activity_feed_queries::{UrgencyHint, fetch_overdue_task_activity},
+    models::task::{Task, TaskStatus},
+};
+
+use crate::common::{create_project, create_task, setup_test_db};
+
+#[tokio::test]
+async fn only_open_tasks_past_their_due_date_are_overdue() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "due-dates").await;
+    let now = Utc::now();
+
+    let late = create_task(&pool, project.id, "Late").await;
+    let later = create_task(&pool, project.id, "Very late").await;
+    let upcoming = create_task(&pool, project.id, "Upcoming").await;
+    let finished = create_task(&pool, project.id, "Finished late").await;
+    let undated = create_task(&pool, project.id, "No deadline").await;
+
+    Task::set_due_at(&pool, late.id, Some(now - Duration::hours(2)))
+        .await
+        .unwrap();
+    Task::set_due_at(&pool, later.id, Some(now - Duration::days(3)))
+        .await
+        .unwrap();
+    Task::set_due_at(&pool, upcoming.id, Some(now + Duration::days(1)))
+        .await
+        .unwrap();
+    Task::set_due_at(&pool, finished.id, Some(now - Duration::days(1)))
+        .await
+        .unwrap();
+    Task::update_status(&pool, finished.id, TaskStatus::Done)
+        .await
+        .unwrap();
+
+    let overdue = Task::find_overdue(&pool, project.id, now).await.unwrap();
+    assert_eq!(
+        overdue.iter().map(|t| t.task.id).collect::<Vec<_>>(),
+        [later.id, late.id]
+    );
+
+    let tasks = Task::find_by_project_id_with_attempt_status(&pool, project.id)
+        .await
+        .unwrap();
+    let listed = tasks.iter().find(|t| t.id == upcoming.id).unwrap();
+    assert!(listed.due_at.is_some());
+    assert!(
+        tasks
+            .iter()
+            .find(|t| t.id == undated.id)
+            .unwrap()
+            .due_at
+            .is_none()
+    );
+
+    Task::set_due_at(&pool, late.id, None).await.unwrap();
+    assert_eq!(Task::find_due_at(&pool, late.id).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn overdue_tasks_raise_elevated_activity() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "due-dates").await;
+    let now = Utc::now();
+    let late = create_task(&pool, project.id, "Late").await;
+    let stale = create_task(&pool, project.id, "Overdue before the window").await;
+    Task::set_due_at(&pool, late.id, Some(now - Duration::hours(1)))
+        .await
+        .unwrap();
+    Task::set_due_at(&pool, stale.id, Some(now - Duration::days(30)))
+        .await
+        .unwrap();
+
+    let rows = fetch_overdue_task_activity(&pool, project.id, now - Duration::days(7))
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    let row = &rows[0];
+    assert_eq!(row.entity_id, late.id);
+    assert_ne!(row.event_id, Some(late.id));
+    assert!(matches!(row.urgency_hint, Some(UrgencyHint::Elevated)));
+    assert_eq!(row.headline.as_deref(), Some("Task overdue: Late"));
+}
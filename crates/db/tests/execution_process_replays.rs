@@ -0,0 +1,68 @@
+mod common;
+
+use db::models::{
+    execution_process::{CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason},
+    execution_process_replay::ExecutionProcessReplay,
+    task_attempt::TaskAttempt,
+};
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::common::{create_attempt, create_project, create_task, setup_test_db};
+
+async fn create_process(pool: &SqlitePool, attempt: &TaskAttempt) -> ExecutionProcess {
+    let action = ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            script: "npm run build".to_string(),
+            language: ScriptRequestLanguage::Bash,
+            context: ScriptContext::SetupScript,
+        }),
+        None,
+    );
+    ExecutionProcess::create(
+        pool,
+        &CreateExecutionProcess {
+            task_attempt_id: attempt.id,
+            executor_action: action,
+            run_reason: ExecutionProcessRunReason::SetupScript,
+        },
+        Uuid::new_v4(),
+        None,
+    )
+    .await
+    .expect("Failed to create execution process")
+}
+
+#[tokio::test]
+async fn replays_are_listed_per_attempt() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "replays").await;
+    let task = create_task(&pool, project.id, "Flaky build").await;
+    let attempt = create_attempt(&pool, &task, "feature/first").await;
+    let other_attempt = create_attempt(&pool, &task, "feature/second").await;
+
+    let original = create_process(&pool, &attempt).await;
+    let replay = create_process(&pool, &attempt).await;
+    let other_original = create_process(&pool, &other_attempt).await;
+    let other_replay = create_process(&pool, &other_attempt).await;
+
+    let link = ExecutionProcessReplay::create(&pool, replay.id, original.id)
+        .await
+        .unwrap();
+    assert_eq!(link.execution_process_id, replay.id);
+    assert_eq!(link.replayed_from_id, original.id);
+    ExecutionProcessReplay::create(&pool, other_replay.id, other_original.id)
+        .await
+        .unwrap();
+
+    let replays = ExecutionProcessReplay::find_by_task_attempt_id(&pool, attempt.id)
+        .await
+        .unwrap();
+    assert_eq!(replays.len(), 1);
+    assert_eq!(replays[0].execution_process_id, replay.id);
+    assert_eq!(replays[0].replayed_from_id, original.id);
+}
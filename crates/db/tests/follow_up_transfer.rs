@@ -0,0 +1,76 @@
+mod common;
+
+use db::models::{
+    draft::{Draft, DraftType, UpsertDraft},
+    task_attempt::TaskAttempt,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::common::{create_attempt, create_project, create_task, setup_test_db};
+
+async fn save_follow_up(pool: &SqlitePool, attempt: &TaskAttempt, queued: bool) {
+    Draft::upsert(
+        pool,
+        &UpsertDraft {
+            task_attempt_id: attempt.id,
+            draft_type: DraftType::FollowUp,
+            retry_process_id: None,
+            prompt: "Also update the changelog".to_string(),
+            queued,
+            variant: None,
+            image_ids: Some(vec![Uuid::new_v4()]),
+        },
+    )
+    .await
+    .expect("Failed to save draft");
+}
+
+#[tokio::test]
+async fn queued_follow_up_moves_to_the_new_attempt() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "transfer").await;
+    let task = create_task(&pool, project.id, "Retry me").await;
+    let previous = create_attempt(&pool, &task, "feature/first").await;
+    let next = create_attempt(&pool, &task, "feature/second").await;
+    save_follow_up(&pool, &previous, true).await;
+
+    let moved = Draft::transfer_queued_follow_up(&pool, previous.id, next.id)
+        .await
+        .unwrap()
+        .expect("queued follow-up should move");
+    assert_eq!(moved.task_attempt_id, next.id);
+    assert!(moved.queued);
+    assert_eq!(moved.prompt, "Also update the changelog");
+    assert_eq!(moved.image_ids.map(|ids| ids.len()), Some(1));
+
+    assert!(
+        Draft::find_by_task_attempt_and_type(&pool, previous.id, DraftType::FollowUp)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn unqueued_drafts_stay_with_their_attempt() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "transfer").await;
+    let task = create_task(&pool, project.id, "Retry me").await;
+    let previous = create_attempt(&pool, &task, "feature/first").await;
+    let next = create_attempt(&pool, &task, "feature/second").await;
+    save_follow_up(&pool, &previous, false).await;
+
+    assert!(
+        Draft::transfer_queued_follow_up(&pool, previous.id, next.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        Draft::find_by_task_attempt_and_type(&pool, previous.id, DraftType::FollowUp)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use db::models::{
+    label::{CreateLabel, Label, is_valid_label_color},
+    task::Task,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::common::{create_project, create_task, setup_test_db};
+
+async fn create_label(pool: &SqlitePool, project_id: Uuid, name: &str) -> Label {
+    Label::create(
+        pool,
+        project_id,
+        &CreateLabel {
+            name: name.to_string(),
+            color: "#3b82f6".to_string(),
+        },
+    )
+    .await
+    .expect("Failed to create label")
+}
+
+#[test]
+fn label_colors_must_be_hex() {
+    assert!(is_valid_label_color("#3b82F6"));
+    assert!(!is_valid_label_color("3b82f6"));
+    assert!(!is_valid_label_color("#3b82f"));
+    assert!(!is_valid_label_color("#zzzzzz"));
+}
+
+#[tokio::test]
+async fn task_labels_are_listed_with_tasks() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "labels").await;
+    let other_project = create_project(&pool, "other").await;
+    let task = create_task(&pool, project.id, "Labelled").await;
+    let plain = create_task(&pool, project.id, "Plain").await;
+    let bug = create_label(&pool, project.id, "bug").await;
+    let ui = create_label(&pool, project.id, "ui").await;
+    let foreign = create_label(&pool, other_project.id, "bug").await;
+
+    Label::set_for_task(&pool, task.id, project.id, &[ui.id, bug.id, foreign.id])
+        .await
+        .unwrap();
+
+    let tasks = Task::find_by_project_id_with_attempt_status(&pool, project.id)
+        .await
+        .unwrap();
+    let labelled = tasks.iter().find(|t| t.id == task.id).unwrap();
+    assert_eq!(
+        labelled
+            .labels
+            .iter()
+            .map(|l| l.name.as_str())
+            .collect::<Vec<_>>(),
+        ["bug", "ui"]
+    );
+    assert!(
+        tasks
+            .iter()
+            .find(|t| t.id == plain.id)
+            .unwrap()
+            .labels
+            .is_empty()
+    );
+
+    Label::delete(&pool, bug.id).await.unwrap();
+    let tasks = Task::find_by_project_id_with_attempt_status(&pool, project.id)
+        .await
+        .unwrap();
+    let labelled = tasks.iter().find(|t| t.id == task.id).unwrap();
+    assert_eq!(labelled.labels.len(), 1);
+    assert_eq!(labelled.labels[0].id, ui.id);
+}
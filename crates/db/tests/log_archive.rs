@@ -0,0 +1,108 @@
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use db::models::{
+    execution_process::ExecutionProcess, execution_process_logs::ExecutionProcessLogs,
+    task_attempt::TaskAttempt,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::common::{create_attempt, create_project, create_task, setup_test_db};
+
+/// Insert a process directly; archiving only looks at its status and completion time
+async fn create_process(
+    pool: &SqlitePool,
+    attempt: &TaskAttempt,
+    status: &str,
+    completed_at: Option<DateTime<Utc>>,
+) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO execution_processes
+               (id, task_attempt_id, run_reason, executor_action, status, completed_at)
+           VALUES (?, ?, 'codingagent', '{}', ?, ?)"#,
+    )
+    .bind(id)
+    .bind(attempt.id)
+    .bind(status)
+    .bind(completed_at)
+    .execute(pool)
+    .await
+    .expect("Failed to create execution process");
+    ExecutionProcessLogs::append_log_line(pool, id, "{\"Stdout\":\"hello\"}\n")
+        .await
+        .expect("Failed to write logs");
+    id
+}
+
+async fn hot_log_count(pool: &SqlitePool) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM execution_process_logs")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn old_finished_processes_and_their_logs_are_archived() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "archive").await;
+    let task = create_task(&pool, project.id, "Old work").await;
+    let attempt = create_attempt(&pool, &task, "feature/archive").await;
+    let now = Utc::now();
+    let old = create_process(&pool, &attempt, "completed", Some(now - Duration::days(40))).await;
+    let recent = create_process(&pool, &attempt, "completed", Some(now - Duration::days(2))).await;
+    let running = create_process(&pool, &attempt, "running", None).await;
+
+    let archived = ExecutionProcessLogs::archive_finished_before(&pool, now - Duration::days(30))
+        .await
+        .unwrap();
+    assert_eq!(archived, 1);
+    assert_eq!(hot_log_count(&pool).await, 2);
+
+    assert!(
+        ExecutionProcessLogs::find_by_execution_id(&pool, old)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let restored = ExecutionProcessLogs::find_including_archived(&pool, old)
+        .await
+        .unwrap()
+        .expect("archived logs are still readable");
+    assert_eq!(restored.parse_logs().unwrap().len(), 1);
+
+    for id in [recent, running] {
+        assert!(
+            ExecutionProcessLogs::find_by_execution_id(&pool, id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    // The archived process is left out of the attempt's timeline, dropped processes or not
+    for show_soft_deleted in [false, true] {
+        let listed: Vec<Uuid> =
+            ExecutionProcess::find_by_task_attempt_id(&pool, attempt.id, show_soft_deleted)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|process| process.id)
+                .collect();
+        assert_eq!(listed.len(), 2);
+        assert!(!listed.contains(&old));
+    }
+    assert!(
+        ExecutionProcess::find_by_id(&pool, old)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    // Running again finds nothing new to move
+    let archived = ExecutionProcessLogs::archive_finished_before(&pool, now - Duration::days(30))
+        .await
+        .unwrap();
+    assert_eq!(archived, 0);
+}
@@ -0,0 +1,60 @@
+use std::str::FromStr;
+
+use db::DBService;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use tempfile::TempDir;
+
+type TestResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+#[tokio::test]
+async fn maintenance_converts_to_incremental_vacuum_on_request_and_frees_pages() -> TestResult<()> {
+    let temp = TempDir::new()?;
+    let db_path = temp.path().join("maintenance.sqlite");
+    // Created like a database from before incremental vacuum was turned on
+    let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", db_path.display()))?
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await?;
+    sqlx::migrate!("./migrations").run(&pool).await?;
+    let db = DBService { pool };
+
+    // Scheduled runs leave the mode alone
+    let scheduled = db.run_maintenance(false).await?;
+    assert!(!scheduled.converted_to_incremental);
+    assert!(!scheduled.incremental_vacuum);
+    let auto_vacuum: i64 = sqlx::query_scalar("PRAGMA auto_vacuum")
+        .fetch_one(&db.pool)
+        .await?;
+    assert_eq!(auto_vacuum, 0);
+
+    let first = db.run_maintenance(true).await?;
+    assert!(first.converted_to_incremental);
+    assert!(first.incremental_vacuum);
+    let auto_vacuum: i64 = sqlx::query_scalar("PRAGMA auto_vacuum")
+        .fetch_one(&db.pool)
+        .await?;
+    assert_eq!(auto_vacuum, 2);
+
+    sqlx::query("CREATE TABLE filler (data BLOB)")
+        .execute(&db.pool)
+        .await?;
+    for _ in 0..64 {
+        sqlx::query("INSERT INTO filler VALUES (zeroblob(8192))")
+            .execute(&db.pool)
+            .await?;
+    }
+    sqlx::query("DROP TABLE filler").execute(&db.pool).await?;
+
+    let second = db.run_maintenance(false).await?;
+    assert!(!second.converted_to_incremental);
+    assert!(second.freed_pages > 0);
+    assert!(!second.checkpoint_busy);
+    let free_pages: i64 = sqlx::query_scalar("PRAGMA freelist_count")
+        .fetch_one(&db.pool)
+        .await?;
+    assert_eq!(free_pages, 0);
+    Ok(())
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use db::models::prompt_snippet::{CreatePromptSnippet, PromptSnippet};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::common::{create_project, setup_test_db};
+
+async fn create_snippet(
+    pool: &SqlitePool,
+    project_id: Option<Uuid>,
+    name: &str,
+    content: &str,
+) -> Result<PromptSnippet, sqlx::Error> {
+    PromptSnippet::create(
+        pool,
+        &CreatePromptSnippet {
+            project_id,
+            name: name.to_string(),
+            content: content.to_string(),
+            description: None,
+        },
+    )
+    .await
+}
+
+#[tokio::test]
+async fn project_snippets_override_global_ones_with_the_same_name() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "snippets").await;
+    let other = create_project(&pool, "other").await;
+
+    create_snippet(&pool, None, "coding-standards", "global standards")
+        .await
+        .unwrap();
+    create_snippet(&pool, None, "tone", "be brief")
+        .await
+        .unwrap();
+    create_snippet(
+        &pool,
+        Some(project.id),
+        "coding-standards",
+        "project standards",
+    )
+    .await
+    .unwrap();
+
+    let visible = PromptSnippet::find_for_project(&pool, project.id)
+        .await
+        .unwrap();
+    let visible: Vec<(&str, &str)> = visible
+        .iter()
+        .map(|s| (s.name.as_str(), s.content.as_str()))
+        .collect();
+    assert_eq!(
+        visible,
+        vec![
+            ("coding-standards", "project standards"),
+            ("tone", "be brief")
+        ]
+    );
+
+    let other_visible = PromptSnippet::find_for_project(&pool, other.id)
+        .await
+        .unwrap();
+    assert_eq!(other_visible.len(), 2);
+    assert!(
+        other_visible
+            .iter()
+            .any(|s| s.content == "global standards")
+    );
+}
+
+#[tokio::test]
+async fn names_are_unique_within_a_scope() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "unique").await;
+
+    create_snippet(&pool, None, "tone", "a").await.unwrap();
+    assert!(create_snippet(&pool, None, "tone", "b").await.is_err());
+
+    create_snippet(&pool, Some(project.id), "tone", "c")
+        .await
+        .unwrap();
+    assert!(
+        create_snippet(&pool, Some(project.id), "tone", "d")
+            .await
+            .is_err()
+    );
+}
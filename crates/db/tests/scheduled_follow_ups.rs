@@ -0,0 +1,145 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use db::models::{
+    draft::{Draft, DraftType, UpsertDraft},
+    task_attempt::TaskAttempt,
+};
+use sqlx::SqlitePool;
+
+use crate::common::{create_attempt, create_project, create_task, setup_test_db};
+
+async fn save_follow_up(pool: &SqlitePool, attempt: &TaskAttempt, prompt: &str) {
+    Draft::upsert(
+        pool,
+        &UpsertDraft {
+            task_attempt_id: attempt.id,
+            draft_type: DraftType::FollowUp,
+            retry_process_id: None,
+            prompt: prompt.to_string(),
+            queued: false,
+            variant: None,
+            image_ids: None,
+        },
+    )
+    .await
+    .expect("Failed to save draft");
+}
+
+#[tokio::test]
+async fn scheduled_follow_up_is_due_once_its_time_passes() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "scheduled").await;
+    let task = create_task(&pool, project.id, "Nightly").await;
+    let attempt = create_attempt(&pool, &task, "feature/scheduled").await;
+    save_follow_up(&pool, &attempt, "Run the full test suite").await;
+
+    let run_at = Utc::now() + Duration::hours(8);
+    let updated = Draft::set_queued(
+        &pool,
+        attempt.id,
+        DraftType::FollowUp,
+        true,
+        None,
+        None,
+        Some(run_at),
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated, 1);
+
+    let draft = Draft::find_by_task_attempt_and_type(&pool, attempt.id, DraftType::FollowUp)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(draft.queued);
+    assert_eq!(
+        draft.run_at.map(|at| at.timestamp()),
+        Some(run_at.timestamp())
+    );
+
+    assert!(
+        Draft::find_due_scheduled(&pool, Utc::now())
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let due = Draft::find_due_scheduled(&pool, run_at + Duration::minutes(1))
+        .await
+        .unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].task_attempt_id, attempt.id);
+
+    // A follow-up that is already being sent is not picked up again
+    assert!(
+        Draft::try_mark_sending(&pool, attempt.id, DraftType::FollowUp)
+            .await
+            .unwrap()
+    );
+    assert!(
+        Draft::find_due_scheduled(&pool, run_at + Duration::minutes(1))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn unqueueing_or_sending_clears_the_schedule() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "scheduled").await;
+    let task = create_task(&pool, project.id, "Nightly").await;
+    let attempt = create_attempt(&pool, &task, "feature/scheduled").await;
+    save_follow_up(&pool, &attempt, "Refactor tonight").await;
+    let run_at = Some(Utc::now() + Duration::hours(2));
+
+    Draft::set_queued(
+        &pool,
+        attempt.id,
+        DraftType::FollowUp,
+        true,
+        None,
+        None,
+        run_at,
+    )
+    .await
+    .unwrap();
+    Draft::set_queued(
+        &pool,
+        attempt.id,
+        DraftType::FollowUp,
+        false,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let draft = Draft::find_by_task_attempt_and_type(&pool, attempt.id, DraftType::FollowUp)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!draft.queued);
+    assert!(draft.run_at.is_none());
+
+    Draft::set_queued(
+        &pool,
+        attempt.id,
+        DraftType::FollowUp,
+        true,
+        None,
+        None,
+        run_at,
+    )
+    .await
+    .unwrap();
+    Draft::clear_after_send(&pool, attempt.id, DraftType::FollowUp)
+        .await
+        .unwrap();
+    let draft = Draft::find_by_task_attempt_and_type(&pool, attempt.id, DraftType::FollowUp)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(draft.run_at.is_none());
+    assert!(draft.prompt.is_empty());
+}
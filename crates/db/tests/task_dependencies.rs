@@ -0,0 +1,97 @@
+mod common;
+
+use db::models::{
+    task::{Task, TaskStatus},
+    task_dependency::{TaskDependency, TaskDependencyError},
+};
+
+use crate::common::{create_project, create_task, setup_test_db};
+
+#[tokio::test]
+async fn dependencies_that_close_a_cycle_are_rejected() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "dependencies").await;
+    let a = create_task(&pool, project.id, "A").await;
+    let b = create_task(&pool, project.id, "B").await;
+    let c = create_task(&pool, project.id, "C").await;
+
+    TaskDependency::add(&pool, &a, b.id).await.unwrap();
+    TaskDependency::add(&pool, &b, c.id).await.unwrap();
+
+    assert!(matches!(
+        TaskDependency::add(&pool, &c, a.id).await,
+        Err(TaskDependencyError::Cycle(title)) if title == "A"
+    ));
+    assert!(matches!(
+        TaskDependency::add(&pool, &a, a.id).await,
+        Err(TaskDependencyError::SelfDependency)
+    ));
+    assert!(
+        TaskDependency::find_dependency_ids(&pool, c.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn dependencies_on_other_projects_are_rejected() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "dependencies").await;
+    let other_project = create_project(&pool, "other").await;
+    let task = create_task(&pool, project.id, "Ship").await;
+    let foreign = create_task(&pool, other_project.id, "Elsewhere").await;
+
+    assert!(matches!(
+        TaskDependency::add(&pool, &task, foreign.id).await,
+        Err(TaskDependencyError::CrossProject)
+    ));
+    assert!(
+        TaskDependency::find_by_project_id(&pool, project.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn tasks_stay_blocked_until_their_dependencies_are_done() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "dependencies").await;
+    let release = create_task(&pool, project.id, "Release").await;
+    let tests = create_task(&pool, project.id, "Write tests").await;
+    TaskDependency::add(&pool, &release, tests.id)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        TaskDependency::find_blocked_task_ids(&pool, project.id)
+            .await
+            .unwrap(),
+        vec![release.id]
+    );
+    // Starting an attempt is refused while this is non-empty
+    assert_eq!(
+        TaskDependency::find_unfinished_dependencies(&pool, release.id)
+            .await
+            .unwrap(),
+        vec![(tests.id, "Write tests".to_string())]
+    );
+
+    Task::update_status(&pool, tests.id, TaskStatus::Done)
+        .await
+        .unwrap();
+
+    assert!(
+        TaskDependency::find_blocked_task_ids(&pool, project.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        TaskDependency::find_unfinished_dependencies(&pool, release.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
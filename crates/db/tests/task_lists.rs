@@ -0,0 +1,114 @@
+mod common;
+
+use db::{
+    models::{
+        label::{CreateLabel, Label},
+        task::{Task, TaskListOptions, TaskSortField, TaskStatus},
+    },
+    pagination::{MAX_PAGE_SIZE, Page, SortDirection},
+};
+
+use crate::common::{create_project, create_task, setup_test_db};
+
+fn titles(tasks: &[db::models::task::TaskWithAttemptStatus]) -> Vec<&str> {
+    tasks.iter().map(|task| task.title.as_str()).collect()
+}
+
+#[test]
+fn pages_are_capped() {
+    let page = Page::new(Some(10_000), None);
+    assert_eq!(page.limit, Some(MAX_PAGE_SIZE));
+    assert_eq!(page.sql_offset(), 0);
+    assert_eq!(Page::default().sql_limit(), -1);
+}
+
+#[tokio::test]
+async fn task_lists_are_sorted_and_paged() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "board").await;
+    for title in ["banana", "Cherry", "apple", "date"] {
+        create_task(&pool, project.id, title).await;
+    }
+
+    let by_title = |direction, page| TaskListOptions {
+        sort: TaskSortField::Title,
+        direction,
+        page,
+        ..Default::default()
+    };
+    let (tasks, total) = Task::find_page_by_project_id_with_attempt_status(
+        &pool,
+        project.id,
+        &by_title(SortDirection::Asc, Page::default()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(total, 4);
+    assert_eq!(titles(&tasks), ["apple", "banana", "Cherry", "date"]);
+
+    let (tasks, total) = Task::find_page_by_project_id_with_attempt_status(
+        &pool,
+        project.id,
+        &by_title(SortDirection::Desc, Page::new(Some(2), Some(1))),
+    )
+    .await
+    .unwrap();
+    // The total counts every page
+    assert_eq!(total, 4);
+    assert_eq!(titles(&tasks), ["Cherry", "banana"]);
+}
+
+#[tokio::test]
+async fn task_lists_sort_by_board_column_and_filter_by_label() {
+    let pool = setup_test_db().await;
+    let project = create_project(&pool, "board").await;
+    let done = create_task(&pool, project.id, "done").await;
+    let todo = create_task(&pool, project.id, "todo").await;
+    let review = create_task(&pool, project.id, "review").await;
+    Task::update_status(&pool, done.id, TaskStatus::Done)
+        .await
+        .unwrap();
+    Task::update_status(&pool, review.id, TaskStatus::InReview)
+        .await
+        .unwrap();
+
+    let (tasks, _) = Task::find_page_by_project_id_with_attempt_status(
+        &pool,
+        project.id,
+        &TaskListOptions {
+            sort: TaskSortField::Status,
+            direction: SortDirection::Asc,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(titles(&tasks), ["todo", "review", "done"]);
+
+    let label = Label::create(
+        &pool,
+        project.id,
+        &CreateLabel {
+            name: "bug".to_string(),
+            color: "#ef4444".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    Label::set_for_task(&pool, todo.id, project.id, &[label.id])
+        .await
+        .unwrap();
+    let (tasks, total) = Task::find_page_by_project_id_with_attempt_status(
+        &pool,
+        project.id,
+        &TaskListOptions {
+            label_id: Some(label.id),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(titles(&tasks), ["todo"]);
+    assert_eq!(tasks[0].labels.len(), 1);
+}
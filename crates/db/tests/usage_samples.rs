@@ -0,0 +1,59 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use db::models::usage_sample::{CreateUsageSample, UsageAgent, UsageSample};
+
+use crate::common::setup_test_db;
+
+fn sample(agent: UsageAgent, used_percent: f64) -> CreateUsageSample {
+    CreateUsageSample {
+        agent,
+        used_percent: Some(used_percent),
+        secondary_used_percent: None,
+        total_tokens: Some(1_000),
+        resets_at: None,
+    }
+}
+
+#[tokio::test]
+async fn history_is_per_agent_and_within_the_range() {
+    let pool = setup_test_db().await;
+    let older = UsageSample::create(&pool, &sample(UsageAgent::Codex, 10.0))
+        .await
+        .unwrap();
+    sqlx::query("UPDATE usage_samples SET sampled_at = datetime('now', '-2 days') WHERE id = ?")
+        .bind(older.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    UsageSample::create(&pool, &sample(UsageAgent::Codex, 20.0))
+        .await
+        .unwrap();
+    UsageSample::create(&pool, &sample(UsageAgent::ClaudeCode, 30.0))
+        .await
+        .unwrap();
+
+    let now = Utc::now() + Duration::seconds(1);
+    let day = UsageSample::find_range(&pool, UsageAgent::Codex, now - Duration::days(1), now)
+        .await
+        .unwrap();
+    assert_eq!(day.len(), 1);
+    assert_eq!(day[0].used_percent, Some(20.0));
+
+    let week = UsageSample::find_range(&pool, UsageAgent::Codex, now - Duration::days(7), now)
+        .await
+        .unwrap();
+    let percents: Vec<_> = week.iter().map(|s| s.used_percent).collect();
+    assert_eq!(percents, [Some(10.0), Some(20.0)]);
+
+    let latest = UsageSample::find_latest(&pool, UsageAgent::Codex)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest.used_percent, Some(20.0));
+
+    let pruned = UsageSample::delete_before(&pool, Utc::now() - Duration::days(1))
+        .await
+        .unwrap();
+    assert_eq!(pruned, 1);
+}
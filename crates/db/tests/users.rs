@@ -0,0 +1,58 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use db::models::{
+    user::{CreateUser, User, UserRole},
+    user_session::UserSession,
+};
+
+use crate::common::setup_test_db;
+
+#[tokio::test]
+async fn sessions_resolve_to_their_user_until_they_expire() {
+    let pool = setup_test_db().await;
+    let user = User::create(
+        &pool,
+        &CreateUser {
+            username: "Ada".to_string(),
+            display_name: None,
+            password: "unused".to_string(),
+            role: UserRole::Viewer,
+        },
+        "hash",
+    )
+    .await
+    .unwrap();
+    assert_eq!(user.display_name, "Ada");
+    assert_eq!(User::count(&pool).await.unwrap(), 1);
+
+    // Usernames are matched without regard to case
+    let (found, hash) = User::find_credentials(&pool, "ada").await.unwrap().unwrap();
+    assert_eq!(found.id, user.id);
+    assert_eq!(hash, "hash");
+
+    let now = Utc::now();
+    UserSession::create(&pool, user.id, "live", now + Duration::days(1))
+        .await
+        .unwrap();
+    UserSession::create(&pool, user.id, "stale", now - Duration::days(1))
+        .await
+        .unwrap();
+    let session_user = UserSession::find_user(&pool, "live", now).await.unwrap();
+    assert_eq!(session_user.map(|u| u.role), Some(UserRole::Viewer));
+    assert!(
+        UserSession::find_user(&pool, "stale", now)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Deleting the account ends its sessions
+    User::delete(&pool, user.id).await.unwrap();
+    assert!(
+        UserSession::find_user(&pool, "live", now)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
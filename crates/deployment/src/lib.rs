@@ -7,6 +7,7 @@ use db::{
     DBService,
     models::{
         execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+        local_analytics_event::LocalAnalyticsEvent,
         project::{CreateProject, Project},
         task::{Task, TaskStatus},
         task_attempt::{TaskAttempt, TaskAttemptError},
@@ -29,7 +30,10 @@ use services::services::{
     filesystem_watcher::FilesystemWatcherError,
     git::{GitService, GitServiceError},
     image::{ImageError, ImageService},
+    issue_sync::IssueSyncService,
+    jira_sync::JiraSyncService,
     pr_monitor::PrMonitorService,
+    project_stats::ProjectStatsService,
     sentry::SentryService,
     worktree_manager::WorktreeError,
 };
@@ -108,6 +112,8 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn drafts(&self) -> &DraftsService;
 
+    fn project_stats(&self) -> &ProjectStatsService;
+
     async fn update_sentry_scope(&self) -> Result<(), DeploymentError> {
         let user_id = self.user_id();
         let config = self.config().read().await;
@@ -125,6 +131,18 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         PrMonitorService::spawn(db, config).await
     }
 
+    async fn spawn_issue_sync_service(&self) -> tokio::task::JoinHandle<()> {
+        let db = self.db().clone();
+        let config = self.config().clone();
+        IssueSyncService::spawn(db, config).await
+    }
+
+    async fn spawn_jira_sync_service(&self) -> tokio::task::JoinHandle<()> {
+        let db = self.db().clone();
+        let config = self.config().clone();
+        JiraSyncService::spawn(db, config).await
+    }
+
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
         let analytics_enabled = self.config().read().await.analytics_enabled;
         // Only skip tracking if user explicitly opted out (Some(false))
@@ -133,6 +151,15 @@ pub trait Deployment: Clone + Send + Sync + 'static {
             && let Some(analytics) = self.analytics()
         {
             analytics.track_event(self.user_id(), event_name, Some(properties.clone()));
+        } else if let Err(e) =
+            LocalAnalyticsEvent::record(&self.db().pool, event_name, &properties).await
+        {
+            // Events that are not sent off-machine are kept locally for the insights endpoints
+            tracing::warn!(
+                "Failed to record local analytics event {}: {}",
+                event_name,
+                e
+            );
         }
     }
 
@@ -21,6 +21,7 @@ pub enum ScriptContext {
     SetupScript,
     CleanupScript,
     DevServer,
+    VerifyScript,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -2,10 +2,17 @@ use std::collections::HashMap;
 
 use tokio::process::Command;
 
+use crate::sandbox::ContainerSandbox;
+
+/// Apply the execution environment to a prepared command. This is the last step before spawning,
+/// so inside a [`ContainerSandbox::scope`] the command is also moved into the container.
 pub fn apply_env(command: &mut Command, env: Option<&HashMap<String, String>>) {
     if let Some(entries) = env {
         for (key, value) in entries {
             command.env(key, value);
         }
     }
+    if let Some(sandbox) = ContainerSandbox::current() {
+        *command = sandbox.wrap(command);
+    }
 }
@@ -28,6 +28,7 @@ use crate::{
         stderr_processor::normalize_stderr_logs,
         utils::{EntryIndexProvider, patch::ConversationPatch},
     },
+    tool_policy::ToolPolicy,
 };
 
 static BACKEND_PORT: OnceCell<u16> = OnceCell::const_new();
@@ -62,11 +63,29 @@ pub struct ClaudeCode {
     pub model: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dangerously_skip_permissions: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_policy: Option<ToolPolicy>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
 }
 
 impl ClaudeCode {
+    fn tool_policy(&self) -> Option<&ToolPolicy> {
+        self.tool_policy
+            .as_ref()
+            .filter(|policy| !policy.is_empty())
+    }
+
+    /// Whether tool calls are routed through the approval hook
+    fn uses_approval_hook(&self) -> bool {
+        self.approvals.unwrap_or(false)
+            || self.plan.unwrap_or(false)
+            || self
+                .tool_policy()
+                .and_then(ToolPolicy::ask_matcher)
+                .is_some()
+    }
+
     async fn build_command_builder(&self) -> CommandBuilder {
         // If base_command_override is provided and claude_code_router is also set, log a warning
         if self.cmd.base_command_override.is_some() && self.claude_code_router.is_some() {
@@ -89,8 +108,9 @@ impl ClaudeCode {
             builder = builder.extend_params(["--permission-mode=plan"]);
         }
 
-        if plan || approvals {
-            match settings_json(plan).await {
+        let tool_policy = self.tool_policy();
+        if plan || approvals || tool_policy.is_some() {
+            match settings_json(plan, approvals, tool_policy).await {
                 // TODO: Avoid quoting
                 Ok(settings) => match shlex::try_quote(&settings) {
                     Ok(quoted) => {
@@ -136,7 +156,7 @@ impl StandardCodingAgentExecutor for ClaudeCode {
             base_command = create_watchkill_script(&base_command);
         }
 
-        if self.approvals.unwrap_or(false) || self.plan.unwrap_or(false) {
+        if self.uses_approval_hook() {
             write_python_hook(current_dir).await?
         }
 
@@ -185,7 +205,7 @@ impl StandardCodingAgentExecutor for ClaudeCode {
             base_command = create_watchkill_script(&base_command);
         }
 
-        if self.approvals.unwrap_or(false) || self.plan.unwrap_or(false) {
+        if self.uses_approval_hook() {
             write_python_hook(current_dir).await?
         }
 
@@ -269,33 +289,53 @@ async fn write_python_hook(current_dir: &Path) -> Result<(), ExecutorError> {
 }
 
 // Configure settings json
-async fn settings_json(plan: bool) -> Result<String, std::io::Error> {
-    let backend_port = get_backend_port().await?;
-    let backend_timeout = APPROVAL_TIMEOUT_SECONDS + 5; // add buffer
-
-    let matcher = if plan {
-        "^ExitPlanMode$"
-    } else {
-        "^(?!(Glob|Grep|NotebookRead|Read|Task|TodoWrite)$).*"
-    };
+async fn settings_json(
+    plan: bool,
+    approvals: bool,
+    tool_policy: Option<&ToolPolicy>,
+) -> Result<String, std::io::Error> {
+    let mut matchers = Vec::new();
+    if plan {
+        matchers.push("^ExitPlanMode$".to_string());
+    } else if approvals {
+        matchers.push("^(?!(Glob|Grep|NotebookRead|Read|Task|TodoWrite)$).*".to_string());
+    }
+    // The catch-all approvals matcher already covers the policy's ask list
+    if (plan || !approvals)
+        && let Some(matcher) = tool_policy.and_then(ToolPolicy::ask_matcher)
+    {
+        matchers.push(matcher);
+    }
 
-    Ok(serde_json::json!({
-        "hooks": {
-            "PreToolUse": [
-                {
+    let mut settings = serde_json::Map::new();
+    if !matchers.is_empty() {
+        let backend_port = get_backend_port().await?;
+        let backend_timeout = APPROVAL_TIMEOUT_SECONDS + 5; // add buffer
+        let pre_tool_use: Vec<serde_json::Value> = matchers
+            .into_iter()
+            .map(|matcher| {
+                serde_json::json!({
                     "matcher": matcher,
                     "hooks": [
                         {
                             "type": "command",
                             "command": format!("$CLAUDE_PROJECT_DIR/.claude/hooks/confirm.py --timeout-seconds {backend_timeout} --poll-interval 5 --backend-port {backend_port}"),
                             "timeout": backend_timeout + 10
-                       }
+                        }
                     ]
-                }
-            ]
-        }
-    })
-    .to_string())
+                })
+            })
+            .collect();
+        settings.insert(
+            "hooks".to_string(),
+            serde_json::json!({ "PreToolUse": pre_tool_use }),
+        );
+    }
+    if let Some(permissions) = tool_policy.and_then(ToolPolicy::claude_permissions) {
+        settings.insert("permissions".to_string(), permissions);
+    }
+
+    Ok(serde_json::Value::Object(settings).to_string())
 }
 
 fn create_watchkill_script(command: &str) -> String {
@@ -1737,6 +1777,7 @@ mod tests {
             model: None,
             append_prompt: AppendPrompt::default(),
             dangerously_skip_permissions: None,
+            tool_policy: None,
             cmd: crate::command::CmdOverrides {
                 base_command_override: None,
                 additional_params: None,
@@ -3,6 +3,7 @@ use std::{collections::HashMap, path::Path, sync::Arc};
 use async_trait::async_trait;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use strum_macros::AsRefStr;
 use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
@@ -35,23 +36,96 @@ impl GeminiModel {
     }
 }
 
+/// Tool call approval mode for Gemini CLI
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum GeminiApprovalMode {
+    Default,
+    AutoEdit,
+    Yolo,
+}
+
+/// Flags owned by the structured options, which must not also be passed as additional params
+const STRUCTURED_FLAGS: &[&str] = &[
+    "--yolo",
+    "-y",
+    "--approval-mode",
+    "--sandbox",
+    "-s",
+    "--checkpointing",
+    "-c",
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
 pub struct Gemini {
     #[serde(default)]
     pub append_prompt: AppendPrompt,
     pub model: GeminiModel,
+    /// Legacy shorthand for `approval_mode: yolo`
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub yolo: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_mode: Option<GeminiApprovalMode>,
+    /// Run tools inside Gemini CLI's sandbox
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<bool>,
+    /// Snapshot the project before file edits so they can be restored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpointing: Option<bool>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
 }
 
 impl Gemini {
+    /// Approval mode after folding in the legacy `yolo` flag
+    pub fn effective_approval_mode(&self) -> Option<GeminiApprovalMode> {
+        match (&self.approval_mode, self.yolo) {
+            (Some(mode), _) => Some(mode.clone()),
+            (None, Some(true)) => Some(GeminiApprovalMode::Yolo),
+            (None, _) => None,
+        }
+    }
+
+    /// Reject option combinations Gemini CLI would refuse or silently resolve differently
+    pub fn validate(&self) -> Result<(), String> {
+        if self.yolo == Some(true)
+            && let Some(mode) = &self.approval_mode
+            && mode != &GeminiApprovalMode::Yolo
+        {
+            return Err(format!(
+                "'yolo' conflicts with approval_mode '{}'",
+                mode.as_ref()
+            ));
+        }
+
+        if let Some(params) = &self.cmd.additional_params {
+            for param in params {
+                let flag = param.split('=').next().unwrap_or(param);
+                if STRUCTURED_FLAGS.contains(&flag) {
+                    return Err(format!(
+                        "'{flag}' is set through the structured Gemini options, not additional params"
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn build_command_builder(&self) -> CommandBuilder {
         let mut builder = self.model.build_command_builder();
 
-        if self.yolo.unwrap_or(false) {
-            builder = builder.extend_params(["--yolo"]);
+        if let Some(mode) = self.effective_approval_mode() {
+            builder = builder.extend_params(["--approval-mode", mode.as_ref()]);
+        }
+
+        if self.sandbox.unwrap_or(false) {
+            builder = builder.extend_params(["--sandbox"]);
+        }
+
+        if self.checkpointing.unwrap_or(false) {
+            builder = builder.extend_params(["--checkpointing"]);
         }
 
         builder = builder.extend_params(["--experimental-acp"]);
@@ -105,3 +179,61 @@ impl StandardCodingAgentExecutor for Gemini {
         dirs::home_dir().map(|home| home.join(".gemini").join("settings.json"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gemini(json: serde_json::Value) -> Gemini {
+        serde_json::from_value(json).expect("valid gemini config")
+    }
+
+    #[test]
+    fn structured_options_become_flags() {
+        let config = gemini(serde_json::json!({
+            "model": "flash",
+            "approval_mode": "auto_edit",
+            "sandbox": true,
+            "checkpointing": true,
+        }));
+
+        let command = config.build_command_builder().build_initial();
+        assert!(command.contains("--approval-mode auto_edit"));
+        assert!(command.contains("--sandbox"));
+        assert!(command.contains("--checkpointing"));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn legacy_yolo_maps_to_approval_mode() {
+        let config = gemini(serde_json::json!({ "model": "default", "yolo": true }));
+
+        assert_eq!(
+            config.effective_approval_mode(),
+            Some(GeminiApprovalMode::Yolo)
+        );
+        assert!(
+            config
+                .build_command_builder()
+                .build_initial()
+                .contains("--approval-mode yolo")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_conflicting_options() {
+        let conflicting = gemini(serde_json::json!({
+            "model": "default",
+            "yolo": true,
+            "approval_mode": "default",
+        }));
+        assert!(conflicting.validate().is_err());
+
+        let duplicated = gemini(serde_json::json!({
+            "model": "default",
+            "sandbox": true,
+            "additional_params": ["--sandbox=false"],
+        }));
+        assert!(duplicated.validate().is_err());
+    }
+}
@@ -1,6 +1,7 @@
 #!/usr/bin/env python3
 import argparse
 import json
+import os
 import sys
 import time
 import urllib.error
@@ -33,10 +34,19 @@ def json_success() -> None:
     sys.exit(0)
 
 
+def auth_headers() -> dict:
+    """Bearer token the server hands its execution processes, once accounts exist."""
+    token = os.environ.get("VIBE_SESSION_TOKEN")
+    return {"Authorization": f"Bearer {token}"} if token else {}
+
+
 def http_post_json(url: str, body: dict) -> dict:
     data = json.dumps(body).encode("utf-8")
     req = urllib.request.Request(
-        url, data=data, headers={"Content-Type": "application/json"}, method="POST"
+        url,
+        data=data,
+        headers={"Content-Type": "application/json", **auth_headers()},
+        method="POST",
     )
     try:
         with urllib.request.urlopen(req, timeout=10) as resp:
@@ -54,7 +64,7 @@ def http_post_json(url: str, body: dict) -> dict:
 
 
 def http_get_json(url: str) -> dict:
-    req = urllib.request.Request(url, method="GET")
+    req = urllib.request.Request(url, headers=auth_headers(), method="GET")
     try:
         with urllib.request.urlopen(req, timeout=10) as resp:
             return json.loads(resp.read().decode("utf-8") or "{}")
@@ -18,6 +18,7 @@ use crate::{
         gemini::Gemini, opencode::Opencode, qwen::QwenCode,
     },
     mcp_config::McpConfig,
+    tool_policy::ToolPolicy,
 };
 
 pub mod acp;
@@ -123,6 +124,18 @@ impl CodingAgent {
         self.default_mcp_config_path().is_some()
     }
 
+    /// Check executor-specific option combinations before a profile is saved
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Gemini(gemini) => gemini.validate(),
+            Self::ClaudeCode(claude) => claude
+                .tool_policy
+                .as_ref()
+                .map_or(Ok(()), ToolPolicy::validate),
+            _ => Ok(()),
+        }
+    }
+
     pub fn capabilities(&self) -> Vec<BaseAgentCapability> {
         match self {
             Self::ClaudeCode(_) => vec![BaseAgentCapability::SessionFork],
@@ -1,10 +1,13 @@
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
-use serde_json::{from_value, json, to_value};
+use serde_json::{Value, from_value, json, to_value};
 use ts_rs::TS;
 use workspace_utils::diff::Diff;
 
-use crate::logs::NormalizedEntry;
+use crate::{logs::NormalizedEntry, sandbox::ResourceUsage};
+
+/// Where the latest resource usage sample of a running process lives in its stream
+const RESOURCE_USAGE_PATH: &str = "/resource_usage";
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, TS)]
 #[serde(rename_all = "lowercase")]
@@ -22,6 +25,7 @@ pub enum PatchType {
     Stdout(String),
     Stderr(String),
     Diff(Diff),
+    ResourceUsage(ResourceUsage),
 }
 
 #[derive(Serialize)]
@@ -94,6 +98,26 @@ impl ConversationPatch {
         from_value(json!([patch_entry])).unwrap()
     }
 
+    /// Create a patch that turns the diff previously sent at the given index into `diff`, only
+    /// touching the fields that changed, so unchanged file contents are not sent again
+    pub fn update_diff(entry_index: String, previous: &Diff, diff: &Diff) -> Patch {
+        let prefix = format!("/entries/{entry_index}/content");
+        let mut ops = to_value(json_patch::diff(
+            &to_value(previous).unwrap(),
+            &to_value(diff).unwrap(),
+        ))
+        .unwrap();
+        for op in ops.as_array_mut().into_iter().flatten() {
+            for key in ["path", "from"] {
+                if let Some(Value::String(pointer)) = op.get_mut(key) {
+                    *pointer = format!("{prefix}{pointer}");
+                }
+            }
+        }
+
+        from_value(ops).unwrap()
+    }
+
     /// Create a REMOVE patch for removing a diff
     pub fn remove_diff(entry_index: String) -> Patch {
         from_value(json!([{
@@ -113,6 +137,23 @@ impl ConversationPatch {
 
         from_value(json!([patch_entry])).unwrap()
     }
+
+    /// Create an ADD patch setting the latest resource usage sample, replacing the previous one
+    pub fn set_resource_usage(usage: ResourceUsage) -> Patch {
+        let patch_entry = PatchEntry {
+            op: PatchOperation::Add,
+            path: RESOURCE_USAGE_PATH.to_string(),
+            value: PatchType::ResourceUsage(usage),
+        };
+
+        from_value(json!([patch_entry])).unwrap()
+    }
+}
+
+pub fn is_resource_usage_patch(patch: &Patch) -> bool {
+    patch
+        .iter()
+        .any(|op| op.path().starts_with(RESOURCE_USAGE_PATH))
 }
 
 /// Extract the entry index and `NormalizedEntry` from a JsonPatch if it contains one
@@ -131,3 +172,53 @@ pub fn extract_normalized_entry_from_patch(patch: &Patch) -> Option<(usize, Norm
             .map(|entry| (entry_index, entry))
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use workspace_utils::diff::DiffChangeKind;
+
+    use super::*;
+
+    fn modified(old: &str, new: &str) -> Diff {
+        Diff {
+            repository_id: None,
+            repository_name: None,
+            repository_root: None,
+            change: DiffChangeKind::Modified,
+            old_path: Some("src/lib.rs".to_string()),
+            new_path: Some("src/lib.rs".to_string()),
+            old_content: Some(old.to_string()),
+            new_content: Some(new.to_string()),
+            content_omitted: false,
+            additions: None,
+            deletions: None,
+            lfs: None,
+            intraline: Vec::new(),
+            binary: None,
+            collapsed: false,
+            moved_blocks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn update_diff_leaves_unchanged_contents_out() {
+        let previous = modified("base", "first edit");
+        let next = modified("base", "second edit");
+        let entry = escape_json_pointer_segment("src/lib.rs");
+
+        let mut state = json!({ "entries": {} });
+        json_patch::patch(
+            &mut state,
+            &ConversationPatch::add_diff(entry.clone(), previous.clone()),
+        )
+        .unwrap();
+        let update = ConversationPatch::update_diff(entry, &previous, &next);
+        assert!(!to_value(&update).unwrap().to_string().contains("base"));
+
+        json_patch::patch(&mut state, &update).unwrap();
+        assert_eq!(
+            state["entries"]["src/lib.rs"],
+            json!({ "type": "DIFF", "content": to_value(&next).unwrap() })
+        );
+    }
+}
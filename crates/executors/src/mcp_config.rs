@@ -289,19 +289,31 @@ fn apply_adapter(adapter: Adapter, canonical: Value) -> Value {
 }
 
 impl CodingAgent {
-    pub fn preconfigured_mcp(&self) -> Value {
+    fn mcp_adapter(&self) -> Adapter {
         use Adapter::*;
 
-        let adapter = match self {
+        match self {
             CodingAgent::ClaudeCode(_) | CodingAgent::Amp(_) => Passthrough,
             CodingAgent::QwenCode(_) | CodingAgent::Gemini(_) => Gemini,
             CodingAgent::Cursor(_) => Cursor,
             CodingAgent::Codex(_) => Codex,
             CodingAgent::Opencode(_) => Opencode,
             CodingAgent::Copilot(..) => Copilot,
-        };
+        }
+    }
 
+    pub fn preconfigured_mcp(&self) -> Value {
         let canonical = PRECONFIGURED_MCP_SERVERS.clone();
-        apply_adapter(adapter, canonical)
+        apply_adapter(self.mcp_adapter(), canonical)
+    }
+
+    /// Convert a single server definition in the canonical (Claude-style) shape into the
+    /// shape this agent's config file expects. Returns `None` when the agent cannot run
+    /// that kind of server, e.g. HTTP servers for Codex.
+    pub fn adapt_mcp_server(&self, name: &str, server: Value) -> Option<Value> {
+        let canonical = Value::Object(Map::from_iter([(name.to_string(), server)]));
+        apply_adapter(self.mcp_adapter(), canonical)
+            .get(name)
+            .cloned()
     }
 }
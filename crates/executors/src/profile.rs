@@ -219,6 +219,12 @@ impl ExecutorConfigs {
             }
         };
 
+        if let Ok(raw) = serde_json::from_str::<serde_json::Value>(&content)
+            && let Err(e) = Self::check_tool_policies(&raw)
+        {
+            tracing::warn!("profiles.json: {}; the policy is ignored", e);
+        }
+
         // Parse user overrides
         match serde_json::from_str::<Self>(&content) {
             Ok(mut user_overrides) => {
@@ -364,12 +370,35 @@ impl ExecutorConfigs {
             }
 
             // Ensure configuration names don't conflict with reserved words
-            for config_name in profile.configurations.keys() {
+            for (config_name, config) in &profile.configurations {
                 if config_name.starts_with("__") {
                     return Err(ProfileError::Validation(format!(
                         "Configuration name '{config_name}' is reserved (starts with '__')"
                     )));
                 }
+                config.validate().map_err(|e| {
+                    ProfileError::Validation(format!("{executor_key}:{config_name}: {e}"))
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject a `tool_policy` on agents other than Claude Code, which would otherwise drop it
+    /// silently. Checked on the raw JSON since only Claude Code's configuration has the field.
+    pub fn check_tool_policies(raw: &serde_json::Value) -> Result<(), ProfileError> {
+        let supported = BaseCodingAgent::ClaudeCode.to_string();
+        let executors = raw.get("executors").and_then(|e| e.as_object());
+        for (executor_key, profile) in executors.into_iter().flatten() {
+            for (config_name, config) in profile.as_object().into_iter().flatten() {
+                for (agent, options) in config.as_object().into_iter().flatten() {
+                    if *agent != supported && options.get("tool_policy").is_some() {
+                        return Err(ProfileError::Validation(format!(
+                            "{executor_key}:{config_name}: tool policies are only enforced for \
+                             {supported}"
+                        )));
+                    }
+                }
             }
         }
         Ok(())
@@ -0,0 +1,414 @@
+//! Running executor processes inside a Docker or Podman container instead of on the host.
+//!
+//! Executors prepare their commands as usual. When a spawn happens inside
+//! [`ContainerSandbox::scope`], [`crate::env::apply_env`] re-targets the prepared command at a
+//! throwaway container that mounts the worktree at the same path, so agent-spawned code cannot
+//! touch the rest of the host.
+
+use std::{
+    ffi::{OsStr, OsString},
+    future::Future,
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub fn program(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
+
+    pub fn from_program(program: &str) -> Self {
+        match program {
+            "podman" => ContainerRuntime::Podman,
+            _ => ContainerRuntime::Docker,
+        }
+    }
+}
+
+/// CPU and memory caps for an execution process and everything it starts. `None` leaves the
+/// resource unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct ResourceLimits {
+    /// Percent of one CPU core, so 250 allows two and a half cores
+    pub cpu_percent: Option<u32>,
+    pub memory_mb: Option<u32>,
+}
+
+impl ResourceLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.cpu_percent.is_none() && self.memory_mb.is_none()
+    }
+
+    /// `<runtime> run` flags enforcing the limits on a container
+    pub fn runtime_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(percent) = self.cpu_percent {
+            args.push("--cpus".to_string());
+            args.push(format!("{}.{:02}", percent / 100, percent % 100));
+        }
+        if let Some(memory_mb) = self.memory_mb {
+            args.push("--memory".to_string());
+            args.push(format!("{memory_mb}m"));
+        }
+        args
+    }
+}
+
+/// CPU and memory an execution process and everything it started use at one point in time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct ResourceUsage {
+    /// Percent of one CPU core since the previous sample, so 250 is two and a half busy cores
+    pub cpu_percent: f64,
+    #[ts(type = "number")]
+    pub memory_bytes: u64,
+    pub processes: u32,
+}
+
+/// A host path visible at the same path inside the container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandboxMount {
+    pub path: PathBuf,
+    pub read_only: bool,
+}
+
+impl SandboxMount {
+    pub fn read_write(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            read_only: false,
+        }
+    }
+
+    pub fn read_only(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            read_only: true,
+        }
+    }
+
+    /// Mounts for committing in a linked worktree without being able to change what git runs
+    /// on the host. The worktree is writable, but its `.git` file and the repository's git dir
+    /// are read-only, so hooks, `config` (`core.hooksPath`, `core.fsmonitor`) and the paths to
+    /// them stay as they are. Only the worktree's own admin dir and the objects, refs and
+    /// reflogs a commit writes are writable. Parents come before the paths mounted inside them.
+    pub fn for_worktree(worktree: &Path) -> Vec<Self> {
+        let mut mounts = vec![Self::read_write(worktree)];
+        let dot_git = worktree.join(".git");
+        if dot_git.is_dir() {
+            mounts.extend(
+                ["config", "hooks"]
+                    .map(|name| dot_git.join(name))
+                    .into_iter()
+                    .filter(|path| path.exists())
+                    .map(Self::read_only),
+            );
+            return mounts;
+        }
+        let Some(admin_dir) = std::fs::read_to_string(&dot_git).ok().and_then(|contents| {
+            let dir = contents.strip_prefix("gitdir:")?.trim();
+            Some(normalize(&worktree.join(dir)))
+        }) else {
+            return mounts;
+        };
+        let common_dir = std::fs::read_to_string(admin_dir.join("commondir"))
+            .map(|dir| normalize(&admin_dir.join(dir.trim())))
+            .unwrap_or_else(|_| normalize(&admin_dir.join("../..")));
+
+        mounts.push(Self::read_only(dot_git));
+        mounts.push(Self::read_only(&common_dir));
+        mounts.extend(
+            ["objects", "refs", "logs"]
+                .map(|name| common_dir.join(name))
+                .into_iter()
+                .filter(|path| path.is_dir())
+                .map(Self::read_write),
+        );
+        mounts.push(Self::read_write(&admin_dir));
+        mounts.extend(
+            ["commondir", "gitdir"]
+                .map(|name| admin_dir.join(name))
+                .into_iter()
+                .filter(|path| path.exists())
+                .map(Self::read_only),
+        );
+        mounts
+    }
+}
+
+/// Resolve `.` and `..` without touching the filesystem, the way git reads the paths in a
+/// worktree's `.git` and `commondir` files
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Container a single execution process runs in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerSandbox {
+    pub runtime: ContainerRuntime,
+    pub image: String,
+    /// Container name, so a stopped process's container can be removed
+    pub name: String,
+    /// Host paths mounted at the same path inside the container, parents first
+    pub mounts: Vec<SandboxMount>,
+    pub limits: ResourceLimits,
+}
+
+tokio::task_local! {
+    static SANDBOX: ContainerSandbox;
+}
+
+impl ContainerSandbox {
+    /// Run `f` with commands prepared through [`crate::env::apply_env`] starting in `sandbox`.
+    /// Without a sandbox `f` runs unchanged.
+    pub async fn scope<F: Future>(sandbox: Option<Self>, f: F) -> F::Output {
+        match sandbox {
+            Some(sandbox) => SANDBOX.scope(sandbox, f).await,
+            None => f.await,
+        }
+    }
+
+    pub fn current() -> Option<Self> {
+        SANDBOX.try_with(Clone::clone).ok()
+    }
+
+    /// `<runtime> run` arguments that execute `program args` in the container. Environment
+    /// variables are passed by name only, so their values are taken from the runtime client's
+    /// environment and never show up in the process list.
+    pub fn run_args<'a>(
+        &self,
+        program: &OsStr,
+        args: impl IntoIterator<Item = &'a OsStr>,
+        current_dir: Option<&Path>,
+        env_keys: impl IntoIterator<Item = &'a OsStr>,
+    ) -> Vec<OsString> {
+        let mut run_args: Vec<OsString> = ["run", "--rm", "-i", "--init", "--name"]
+            .into_iter()
+            .map(OsString::from)
+            .collect();
+        run_args.push(self.name.clone().into());
+        run_args.extend(self.limits.runtime_args().into_iter().map(OsString::from));
+        for mount in &self.mounts {
+            let mut volume = mount.path.as_os_str().to_os_string();
+            volume.push(":");
+            volume.push(mount.path.as_os_str());
+            if mount.read_only {
+                volume.push(":ro");
+            }
+            run_args.push("-v".into());
+            run_args.push(volume);
+        }
+        if let Some(dir) = current_dir {
+            run_args.push("-w".into());
+            run_args.push(dir.as_os_str().to_os_string());
+        }
+        for key in env_keys {
+            run_args.push("-e".into());
+            run_args.push(key.to_os_string());
+        }
+        run_args.push(self.image.clone().into());
+        run_args.push(program.to_os_string());
+        run_args.extend(args.into_iter().map(OsStr::to_os_string));
+        run_args
+    }
+
+    /// Build the container runtime invocation for a prepared command. Stdio is always piped, the
+    /// same as executors set it up.
+    pub fn wrap(&self, command: &Command) -> Command {
+        let std_command = command.as_std();
+        let envs: Vec<(&OsStr, &OsStr)> = std_command
+            .get_envs()
+            .filter_map(|(key, value)| value.map(|value| (key, value)))
+            .collect();
+
+        let mut wrapped = Command::new(self.runtime.program());
+        wrapped
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .args(self.run_args(
+                std_command.get_program(),
+                std_command.get_args(),
+                std_command.get_current_dir(),
+                envs.iter().map(|(key, _)| *key),
+            ))
+            .envs(envs.iter().copied());
+        if let Some(dir) = std_command.get_current_dir() {
+            wrapped.current_dir(dir);
+        }
+        wrapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_args_mount_worktree_and_pass_env_by_name() {
+        let sandbox = ContainerSandbox {
+            runtime: ContainerRuntime::Podman,
+            image: "node:20".to_string(),
+            name: "gybe-exec-1".to_string(),
+            mounts: vec![
+                SandboxMount::read_write("/wt/attempt"),
+                SandboxMount::read_only("/repos/app/.git"),
+            ],
+            limits: ResourceLimits::default(),
+        };
+        let args = sandbox.run_args(
+            OsStr::new("bash"),
+            [OsStr::new("-c"), OsStr::new("npm test")],
+            Some(Path::new("/wt/attempt")),
+            [OsStr::new("NODE_NO_WARNINGS")],
+        );
+        assert_eq!(
+            args,
+            [
+                "run",
+                "--rm",
+                "-i",
+                "--init",
+                "--name",
+                "gybe-exec-1",
+                "-v",
+                "/wt/attempt:/wt/attempt",
+                "-v",
+                "/repos/app/.git:/repos/app/.git:ro",
+                "-w",
+                "/wt/attempt",
+                "-e",
+                "NODE_NO_WARNINGS",
+                "node:20",
+                "bash",
+                "-c",
+                "npm test",
+            ]
+            .map(OsString::from)
+        );
+    }
+
+    #[test]
+    fn worktree_git_metadata_is_read_only_except_what_commits_write() {
+        let root = std::env::temp_dir().join(format!("sandbox-mounts-{}", uuid::Uuid::new_v4()));
+        let repo_git = root.join("repo/.git");
+        let admin_dir = repo_git.join("worktrees/attempt");
+        let worktree = root.join("worktrees/attempt");
+        for dir in [
+            repo_git.join("hooks"),
+            repo_git.join("objects"),
+            repo_git.join("refs"),
+            admin_dir.clone(),
+            worktree.clone(),
+        ] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(admin_dir.join("commondir"), "../..\n").unwrap();
+        std::fs::write(
+            admin_dir.join("gitdir"),
+            worktree.join(".git").to_string_lossy().as_ref(),
+        )
+        .unwrap();
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", admin_dir.display()),
+        )
+        .unwrap();
+
+        let mounts = SandboxMount::for_worktree(&worktree);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            mounts,
+            [
+                SandboxMount::read_write(&worktree),
+                SandboxMount::read_only(worktree.join(".git")),
+                SandboxMount::read_only(&repo_git),
+                SandboxMount::read_write(repo_git.join("objects")),
+                SandboxMount::read_write(repo_git.join("refs")),
+                SandboxMount::read_write(&admin_dir),
+                SandboxMount::read_only(admin_dir.join("commondir")),
+                SandboxMount::read_only(admin_dir.join("gitdir")),
+            ]
+        );
+    }
+
+    #[test]
+    fn run_args_pass_resource_limits_to_the_runtime() {
+        let sandbox = ContainerSandbox {
+            runtime: ContainerRuntime::Docker,
+            image: "alpine".to_string(),
+            name: "gybe-exec-3".to_string(),
+            mounts: vec![],
+            limits: ResourceLimits {
+                cpu_percent: Some(250),
+                memory_mb: Some(2048),
+            },
+        };
+        let args = sandbox.run_args(OsStr::new("true"), [], None, []);
+        assert_eq!(
+            args,
+            [
+                "run",
+                "--rm",
+                "-i",
+                "--init",
+                "--name",
+                "gybe-exec-3",
+                "--cpus",
+                "2.50",
+                "--memory",
+                "2048m",
+                "alpine",
+                "true",
+            ]
+            .map(OsString::from)
+        );
+    }
+
+    #[tokio::test]
+    async fn apply_env_only_wraps_inside_scope() {
+        let sandbox = ContainerSandbox {
+            runtime: ContainerRuntime::Docker,
+            image: "alpine".to_string(),
+            name: "gybe-exec-2".to_string(),
+            mounts: vec![],
+            limits: ResourceLimits::default(),
+        };
+        let build = || {
+            let mut command = Command::new("sh");
+            command.arg("-c").arg("true");
+            crate::env::apply_env(&mut command, None);
+            command.as_std().get_program().to_os_string()
+        };
+
+        assert_eq!(build(), "sh");
+        let program = ContainerSandbox::scope(Some(sandbox), async { build() }).await;
+        assert_eq!(program, "docker");
+    }
+}
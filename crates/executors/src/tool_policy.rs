@@ -0,0 +1,139 @@
+//! Per-profile allow/deny/ask lists for Claude Code's tool calls. Other agents have no per-tool
+//! permissions to map the lists onto, so their profiles reject a policy.
+//!
+//! The policy is part of the executor profile and is turned into the agent's own permission
+//! configuration when the agent is spawned. Calls to tools on the `ask` list are routed through
+//! the approvals service, so they show up as pending approvals on the task attempt.
+
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct ToolPolicy {
+    /// Tools the agent may call without asking. Claude Code rule syntax such as
+    /// `Bash(npm run test:*)` is passed through unchanged.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Tools the agent must never call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+    /// Tools whose calls wait for approval on the task attempt
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ask: Vec<String>,
+}
+
+impl ToolPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty() && self.ask.is_empty()
+    }
+
+    /// Reject blank entries and rules listed more than once, since which list wins would
+    /// otherwise depend on the agent
+    pub fn validate(&self) -> Result<(), String> {
+        let mut seen = HashSet::new();
+        for rule in self.allow.iter().chain(&self.deny).chain(&self.ask) {
+            if rule.trim().is_empty() {
+                return Err("Tool policy entries must not be empty".to_string());
+            }
+            if !seen.insert(rule.trim()) {
+                return Err(format!(
+                    "'{rule}' appears more than once in the tool policy"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Claude Code `permissions` settings for the allow and deny lists. `ask` is handled by the
+    /// approval hook instead, since Claude Code cannot prompt in non-interactive mode.
+    pub fn claude_permissions(&self) -> Option<Value> {
+        if self.allow.is_empty() && self.deny.is_empty() {
+            return None;
+        }
+        Some(json!({
+            "allow": self.allow,
+            "deny": self.deny,
+        }))
+    }
+
+    /// Hook matcher selecting the tools on the `ask` list by name. Any `(argument)` part of a
+    /// rule is dropped, since hooks only see the tool name.
+    pub fn ask_matcher(&self) -> Option<String> {
+        let mut names: Vec<String> = self
+            .ask
+            .iter()
+            .map(|rule| rule.split('(').next().unwrap_or(rule).trim())
+            .filter(|name| !name.is_empty())
+            .map(regex::escape)
+            .collect();
+        if names.is_empty() {
+            return None;
+        }
+        names.sort();
+        names.dedup();
+        Some(format!("^({})$", names.join("|")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allow: &[&str], deny: &[&str], ask: &[&str]) -> ToolPolicy {
+        let owned = |rules: &[&str]| rules.iter().map(|r| r.to_string()).collect();
+        ToolPolicy {
+            allow: owned(allow),
+            deny: owned(deny),
+            ask: owned(ask),
+        }
+    }
+
+    #[test]
+    fn rules_may_only_appear_once() {
+        assert!(
+            policy(&["Read"], &["WebFetch"], &["Bash"])
+                .validate()
+                .is_ok()
+        );
+        assert!(policy(&["Bash"], &[], &["Bash"]).validate().is_err());
+        assert!(policy(&[" "], &[], &[]).validate().is_err());
+    }
+
+    #[test]
+    fn ask_matcher_uses_tool_names() {
+        let policy = policy(&[], &[], &["Bash(git push:*)", "Write", "Bash"]);
+        assert_eq!(policy.ask_matcher().as_deref(), Some("^(Bash|Write)$"));
+        assert!(ToolPolicy::default().ask_matcher().is_none());
+    }
+
+    #[test]
+    fn claude_permissions_skip_the_ask_list() {
+        let permissions = policy(&["Read"], &["WebFetch"], &["Bash"])
+            .claude_permissions()
+            .unwrap();
+        assert_eq!(
+            permissions,
+            json!({ "allow": ["Read"], "deny": ["WebFetch"] })
+        );
+        assert!(policy(&[], &[], &["Bash"]).claude_permissions().is_none());
+    }
+
+    #[test]
+    fn only_claude_code_profiles_take_a_policy() {
+        use crate::profile::ExecutorConfigs;
+
+        let claude = json!({ "executors": { "CLAUDE_CODE": { "DEFAULT": {
+            "CLAUDE_CODE": { "tool_policy": { "deny": ["WebFetch"] } }
+        } } } });
+        assert!(ExecutorConfigs::check_tool_policies(&claude).is_ok());
+
+        let codex = json!({ "executors": { "CODEX": { "DEFAULT": {
+            "CODEX": { "tool_policy": { "deny": ["WebFetch"] } }
+        } } } });
+        assert!(ExecutorConfigs::check_tool_policies(&codex).is_err());
+    }
+}
@@ -7,24 +7,34 @@ use nix::{
 use services::services::container::ContainerError;
 use tokio::time::Duration;
 
-pub async fn kill_process_group(child: &mut AsyncGroupChild) -> Result<(), ContainerError> {
+/// How often a stopping process is checked for having exited during its grace period
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Stop the child and everything it started. The group gets SIGTERM and up to `grace_period` to
+/// exit on its own, so agents can save their session, before it is killed.
+pub async fn kill_process_group(
+    child: &mut AsyncGroupChild,
+    grace_period: Duration,
+) -> Result<(), ContainerError> {
     // hit the whole process group, not just the leader
     #[cfg(unix)]
     {
-        if let Some(pid) = child.inner().id() {
+        if let Some(pid) = child.inner().id()
+            && !grace_period.is_zero()
+        {
             let pgid = getpgid(Some(Pid::from_raw(pid as i32)))
                 .map_err(|e| ContainerError::KillFailed(std::io::Error::other(e)))?;
 
-            for sig in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGKILL] {
-                if let Err(e) = killpg(pgid, sig) {
-                    tracing::warn!(
-                        "Failed to send signal {:?} to process group {}: {}",
-                        sig,
-                        pgid,
-                        e
-                    );
-                }
-                tokio::time::sleep(Duration::from_secs(2)).await;
+            if let Err(e) = killpg(pgid, Signal::SIGTERM) {
+                tracing::warn!(
+                    "Failed to send signal {:?} to process group {}: {}",
+                    Signal::SIGTERM,
+                    pgid,
+                    e
+                );
+            }
+            let deadline = tokio::time::Instant::now() + grace_period;
+            while tokio::time::Instant::now() < deadline {
                 if child
                     .inner()
                     .try_wait()
@@ -33,11 +43,81 @@ pub async fn kill_process_group(child: &mut AsyncGroupChild) -> Result<(), Conta
                 {
                     break;
                 }
+                tokio::time::sleep(EXIT_POLL_INTERVAL).await;
             }
         }
     }
+    #[cfg(not(unix))]
+    let _ = grace_period;
 
+    // Kills the whole group, including anything still running after the grace period
     let _ = child.kill().await;
     let _ = child.wait().await;
     Ok(())
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use command_group::AsyncCommandGroup;
+    use nix::errno::Errno;
+    use tokio::{process::Command, time::Instant};
+
+    use super::*;
+
+    fn spawn(script: &str) -> (AsyncGroupChild, Pid) {
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .group_spawn()
+            .expect("Failed to spawn sh");
+        let pgid = Pid::from_raw(child.id().expect("child has a pid") as i32);
+        (child, pgid)
+    }
+
+    fn group_is_gone(pgid: Pid) -> bool {
+        killpg(pgid, None) == Err(Errno::ESRCH)
+    }
+
+    #[tokio::test]
+    async fn processes_ignoring_sigterm_are_killed_after_the_grace_period() {
+        // exec keeps the ignored SIGTERM, so the group is just the leader
+        let (mut child, pgid) = spawn("trap '' TERM; exec sleep 30");
+        // Let the shell install the trap before it is signalled
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let grace_period = Duration::from_millis(500);
+        let started = Instant::now();
+        kill_process_group(&mut child, grace_period).await.unwrap();
+
+        assert!(started.elapsed() >= grace_period);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(group_is_gone(pgid));
+    }
+
+    #[tokio::test]
+    async fn processes_exiting_on_sigterm_do_not_wait_out_the_grace_period() {
+        let (mut child, pgid) = spawn("exec sleep 30");
+
+        let started = Instant::now();
+        kill_process_group(&mut child, Duration::from_secs(20))
+            .await
+            .unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(group_is_gone(pgid));
+    }
+
+    #[tokio::test]
+    async fn no_grace_period_kills_right_away() {
+        let (mut child, pgid) = spawn("trap '' TERM; exec sleep 30");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let started = Instant::now();
+        kill_process_group(&mut child, Duration::ZERO)
+            .await
+            .unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(group_is_gone(pgid));
+    }
+}
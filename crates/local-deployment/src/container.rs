@@ -12,22 +12,31 @@ use std::{
 use anyhow::anyhow;
 use async_stream::try_stream;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use command_group::AsyncGroupChild;
 use db::{
     DBService,
     models::{
+        activity_event::ActivityEventRecord,
+        attempt_cleanup_warning::{AttemptCleanupWarning, CLEANUP_WARNING_LEAD_HOURS},
+        attempt_verification::AttemptVerification,
+        audit_log::AuditLogEntry,
         draft::{Draft, DraftType},
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
+        execution_process_logs::ExecutionProcessLogs,
+        execution_process_token_usage::ExecutionProcessTokenUsage,
         executor_session::ExecutorSession,
         image::TaskImage,
-        merge::Merge,
-        project::Project,
+        local_analytics_event::LocalAnalyticsEvent,
+        merge::{Merge, MergeStatus},
+        project::{Project, ProjectWorktreeSettings},
         project_repository::ProjectRepository,
         task::{Task, TaskStatus},
         task_attempt::TaskAttempt,
         task_attempt_repository::TaskAttemptRepository,
+        warm_worktree::WarmWorktree,
     },
 };
 use deployment::DeploymentError;
@@ -40,32 +49,47 @@ use executors::{
             patch::{escape_json_pointer_segment, extract_normalized_entry_from_patch},
         },
     },
+    sandbox::{ContainerSandbox, SandboxMount},
 };
 use futures::{FutureExt, StreamExt, TryStreamExt, stream::select};
 use notify::RecommendedWatcher;
 use notify_debouncer_full::{DebouncedEvent, Debouncer, RecommendedCache};
 use serde_json::json;
-use services::services::{
-    analytics::AnalyticsContext,
-    config::Config,
-    container::{ContainerError, ContainerRef, ContainerService},
-    filesystem_watcher,
-    git::{Commit, DiffTarget, GitService},
-    image::ImageService,
-    notification::NotificationService,
-    worktree_manager::{WorktreeError, WorktreeManager},
+use services::{
+    activity_feed::ActivityEventRecorder,
+    services::{
+        analytics::AnalyticsContext,
+        auto_fix::build_auto_fix_prompt,
+        branch_cleanup, budgets,
+        config::Config,
+        container::{ContainerError, ContainerRef, ContainerService},
+        cost::token_usage_from_output,
+        db_backup, dependency_cache,
+        dev_server_ports::{DEV_SERVER_PORT_ENV, DEV_SERVER_URL_ENV, allocate_dev_server_port},
+        filesystem_watcher::{self, DiffIgnore, WatcherOptions},
+        git::{Commit, DiffTarget, GitService},
+        git_cli::GitCli,
+        image::ImageService,
+        log_snapshots,
+        notification::NotificationService,
+        project_stats::ProjectStatsService,
+        prompt_snippets, usage_limits,
+        users::{AGENT_TOKEN_ENV, agent_token},
+        webhooks::WebhookService,
+        worktree_manager::{WorktreeError, WorktreeManager},
+    },
 };
 use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
 use utils::{
-    diff::Diff,
+    diff::{Diff, DiffSide, HunkRange},
     log_msg::LogMsg,
     msg_store::MsgStore,
     text::{git_branch_id, git_branch_name_with_prefix, short_uuid},
 };
 use uuid::Uuid;
 
-use crate::command;
+use crate::{command, resource_limits, resource_usage};
 
 /// Stream wrapper that owns the filesystem watcher
 /// When this stream is dropped, the watcher is automatically cleaned up
@@ -86,6 +110,59 @@ impl futures::Stream for DiffStreamWithWatcher {
     }
 }
 
+/// What a live diff stream has already sent with full contents
+#[derive(Default)]
+struct SentDiffs {
+    paths: HashSet<String>,
+    /// The last version sent of each large diff; updates to it are sent as patches against it
+    large: HashMap<String, utils::diff::Diff>,
+}
+
+impl SentDiffs {
+    /// Record `diff` as sent and build the patch that sends it
+    fn patch_for(&mut self, file_path: &str, diff: utils::diff::Diff) -> json_patch::Patch {
+        let entry_index = escape_json_pointer_segment(file_path);
+        if diff.content_omitted {
+            self.large.remove(file_path);
+            return ConversationPatch::add_diff(entry_index, diff);
+        }
+        self.paths.insert(file_path.to_string());
+
+        if LocalContainerService::diff_content_len(&diff)
+            < LocalContainerService::DELTA_DIFF_MIN_BYTES
+        {
+            self.large.remove(file_path);
+            return ConversationPatch::add_diff(entry_index, diff);
+        }
+        let patch = match self.large.get(file_path) {
+            Some(previous) => ConversationPatch::update_diff(entry_index, previous, &diff),
+            None => ConversationPatch::add_diff(entry_index, diff.clone()),
+        };
+        self.large.insert(file_path.to_string(), diff);
+        patch
+    }
+}
+
+/// Where an attempt's diff is read from
+enum DiffSource {
+    Merged {
+        repo_path: PathBuf,
+        commit: String,
+    },
+    Live {
+        worktree_path: PathBuf,
+        base_commit: Commit,
+    },
+}
+
+/// The repository an attempt's diff is taken from, with what is needed to find its worktree
+struct DiffRepository {
+    task: Task,
+    repo: ProjectRepository,
+    attempt_entry: Option<TaskAttemptRepository>,
+    repo_lookup: Arc<RepositoryLookup>,
+}
+
 #[derive(Clone)]
 pub struct LocalContainerService {
     db: DBService,
@@ -95,6 +172,7 @@ pub struct LocalContainerService {
     git: GitService,
     image_service: ImageService,
     analytics: Option<AnalyticsContext>,
+    project_stats: ProjectStatsService,
 }
 
 #[derive(Clone, Debug)]
@@ -110,12 +188,17 @@ struct RepositoryInfo {
 struct RepositoryLookup {
     repos: Vec<RepositoryInfo>,
     primary_index: Option<usize>,
+    /// Endpoint serving either side of a binary file in this diff, without the query
+    content_endpoint: String,
+    repository_filter: Option<Uuid>,
 }
 
 impl RepositoryLookup {
     fn from_project_and_attempt(
+        task_attempt_id: Uuid,
         project_repositories: &[ProjectRepository],
         attempt_repositories: &[TaskAttemptRepository],
+        repository_filter: Option<Uuid>,
     ) -> Self {
         let attempt_map = attempt_repositories
             .iter()
@@ -151,10 +234,14 @@ impl RepositoryLookup {
         RepositoryLookup {
             repos,
             primary_index,
+            content_endpoint: format!("/api/task-attempts/{task_attempt_id}/diff/content"),
+            repository_filter,
         }
     }
 
     fn annotate_diff(&self, diff: &mut Diff) -> Option<Uuid> {
+        self.link_binary_content(diff);
+
         let path = diff
             .new_path
             .as_deref()
@@ -185,11 +272,47 @@ impl RepositoryLookup {
         Some(repo_info.id)
     }
 
+    /// Point each binary side at the endpoint serving its bytes
+    fn link_binary_content(&self, diff: &mut Diff) {
+        let Some(binary) = diff.binary.as_mut() else {
+            return;
+        };
+        let sides = [
+            (&mut binary.old, "old", diff.old_path.as_deref()),
+            (&mut binary.new, "new", diff.new_path.as_deref()),
+        ];
+        for (file, side, path) in sides {
+            if let (Some(file), Some(path)) = (file, path) {
+                let mut url = format!(
+                    "{}?side={side}&path={}",
+                    self.content_endpoint,
+                    encode_query_value(path)
+                );
+                if let Some(repo_id) = self.repository_filter {
+                    url.push_str(&format!("&repo_id={repo_id}"));
+                }
+                file.content_url = Some(url);
+            }
+        }
+    }
+
     fn match_path(&self, raw_path: &str) -> Option<&RepositoryInfo> {
         let path = normalize_diff_path(raw_path);
         self.repos.iter().find(|info| info.matches(path))
     }
 
+    /// Order diffs so each repository's files are contiguous: the repository at the project root
+    /// first, then the others by name, with files sorted by path within each
+    fn sort_diffs(&self, diffs: &mut [Diff]) {
+        diffs.sort_by_cached_key(|diff| {
+            (
+                diff.repository_root.is_some(),
+                diff.repository_name.clone(),
+                GitService::diff_path(diff),
+            )
+        });
+    }
+
     fn primary(&self) -> Option<&RepositoryInfo> {
         self.primary_index
             .and_then(|index| self.repos.get(index))
@@ -239,9 +362,24 @@ fn normalize_diff_path(path: &str) -> &str {
     path.trim_start_matches('/')
 }
 
+/// Percent-encode everything but unreserved characters and `/`, for a path in a query string
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
 impl LocalContainerService {
     // Max cumulative content bytes allowed per diff stream
     const MAX_CUMULATIVE_DIFF_BYTES: usize = 200 * 1024 * 1024; // 200MB
+    // Diffs at least this large are updated with patches against the version last sent
+    const DELTA_DIFF_MIN_BYTES: usize = 64 * 1024;
 
     // Apply stream-level omit policy based on cumulative bytes.
     // If adding this diff's contents exceeds the cap, strip contents and set stats.
@@ -250,20 +388,14 @@ impl LocalContainerService {
         sent_bytes: &Arc<AtomicUsize>,
         stats_only: bool,
     ) {
+        Self::fill_line_counts(diff);
         if stats_only {
             Self::omit_diff_contents(diff);
             return;
         }
 
         // Compute size of current diff payload
-        let mut size = 0usize;
-        if let Some(ref s) = diff.old_content {
-            size += s.len();
-        }
-        if let Some(ref s) = diff.new_content {
-            size += s.len();
-        }
-
+        let size = Self::diff_content_len(diff);
         if size == 0 {
             return; // nothing to account
         }
@@ -277,7 +409,13 @@ impl LocalContainerService {
         }
     }
 
-    fn omit_diff_contents(diff: &mut utils::diff::Diff) {
+    fn diff_content_len(diff: &utils::diff::Diff) -> usize {
+        diff.old_content.as_ref().map_or(0, String::len)
+            + diff.new_content.as_ref().map_or(0, String::len)
+    }
+
+    // Line counts travel with every diff so per-file and per-repository totals need no contents
+    fn fill_line_counts(diff: &mut utils::diff::Diff) {
         if diff.additions.is_none()
             && diff.deletions.is_none()
             && (diff.old_content.is_some() || diff.new_content.is_some())
@@ -288,12 +426,24 @@ impl LocalContainerService {
             diff.additions = Some(add);
             diff.deletions = Some(del);
         }
+    }
 
+    fn omit_diff_contents(diff: &mut utils::diff::Diff) {
+        Self::fill_line_counts(diff);
         diff.old_content = None;
         diff.new_content = None;
         diff.content_omitted = true;
     }
 
+    /// Collapse a diff matching the project's ignore patterns. Its contents are dropped before
+    /// the omit policy runs, so they never count towards the stream budget.
+    fn collapse_ignored_diff(diff: &mut utils::diff::Diff, diff_ignore: &DiffIgnore) {
+        if diff_ignore.is_ignored(&GitService::diff_path(diff)) {
+            diff.collapsed = true;
+            Self::omit_diff_contents(diff);
+        }
+    }
+
     async fn build_executor_env(
         &self,
         task_attempt: &TaskAttempt,
@@ -330,6 +480,7 @@ impl LocalContainerService {
         git: GitService,
         image_service: ImageService,
         analytics: Option<AnalyticsContext>,
+        project_stats: ProjectStatsService,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
 
@@ -341,6 +492,7 @@ impl LocalContainerService {
             git,
             image_service,
             analytics,
+            project_stats,
         }
     }
 
@@ -374,13 +526,396 @@ impl LocalContainerService {
             ))
     }
 
-    /// Finalize task execution by updating status to InReview and sending notifications
-    async fn finalize_task(db: &DBService, config: &Arc<RwLock<Config>>, ctx: &ExecutionContext) {
-        if let Err(e) = Task::update_status(&db.pool, ctx.task.id, TaskStatus::InReview).await {
-            tracing::error!("Failed to update task status to InReview: {e}");
+    /// Finalize task execution following the project's rule for the run reason: update the task
+    /// status and send notifications (by default: move to InReview and notify). After a verify
+    /// script run the task only moves on if the script passed. Returns whether a queued follow-up
+    /// should be started.
+    async fn finalize_task(
+        db: &DBService,
+        config: &Arc<RwLock<Config>>,
+        ctx: &ExecutionContext,
+    ) -> bool {
+        if matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::VerifyScript
+        ) && !Self::record_verification(db, config, ctx).await
+        {
+            return false;
+        }
+
+        let settings = Project::find_finalization_settings(&db.pool, ctx.task.project_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to load finalization settings: {e}");
+                Default::default()
+            });
+        let Some(rule) = settings.rule_for(&ctx.execution_process.run_reason) else {
+            return false;
+        };
+
+        if let Some(status) = rule.task_status.clone()
+            && let Err(e) = Task::update_status(&db.pool, ctx.task.id, status.clone()).await
+        {
+            tracing::error!("Failed to update task status to {status}: {e}");
+        }
+        if rule.notify {
+            let notify_cfg = config.read().await.notifications.clone();
+            NotificationService::notify_execution_halted(notify_cfg, ctx).await;
+        }
+        rule.consume_queued_follow_up
+    }
+
+    /// Push the commits of a finished run to the attempt's open pull request, so follow-up work
+    /// updates the existing PR. Skipped when the remote branch has commits missing locally.
+    async fn push_open_pr_updates(&self, ctx: &ExecutionContext) {
+        let pr =
+            match Merge::find_latest_by_task_attempt_id(&self.db.pool, ctx.task_attempt.id).await {
+                Ok(Some(Merge::Pr(pr))) if matches!(pr.pr_info.status, MergeStatus::Open) => pr,
+                Ok(_) => return,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to look up PR for attempt {}: {}",
+                        ctx.task_attempt.id,
+                        e
+                    );
+                    return;
+                }
+            };
+        let Some(token) = self
+            .config
+            .read()
+            .await
+            .forge_token_for_url(&pr.pr_info.url)
+        else {
+            return;
+        };
+
+        let worktree_dir = self.task_attempt_to_current_dir(&ctx.task_attempt);
+        match self
+            .git
+            .push_branch_updates(&worktree_dir, &ctx.task_attempt.branch, &token)
+        {
+            Ok(outcome) if outcome.remote_commits_behind > 0 => tracing::warn!(
+                "Not pushing attempt {} to {}: remote branch has {} commits missing locally",
+                ctx.task_attempt.id,
+                pr.pr_info.url,
+                outcome.remote_commits_behind
+            ),
+            Ok(outcome) if outcome.pushed_commits > 0 => tracing::info!(
+                "Pushed {} commits from attempt {} to {}",
+                outcome.pushed_commits,
+                ctx.task_attempt.id,
+                pr.pr_info.url
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(
+                "Failed to push attempt {} to {}: {}",
+                ctx.task_attempt.id,
+                pr.pr_info.url,
+                e
+            ),
         }
-        let notify_cfg = config.read().await.notifications.clone();
-        NotificationService::notify_execution_halted(notify_cfg, ctx).await;
+    }
+
+    fn sandbox_container_name(execution_process_id: Uuid) -> String {
+        format!("gybe-exec-{execution_process_id}")
+    }
+
+    /// Container the process should run in when its project has sandboxing enabled. Dev servers
+    /// stay on the host so their ports are reachable.
+    async fn execution_sandbox(
+        &self,
+        task_attempt: &TaskAttempt,
+        execution_process: &ExecutionProcess,
+        worktree_path: &Path,
+    ) -> Result<Option<ContainerSandbox>, ContainerError> {
+        if matches!(
+            execution_process.run_reason,
+            ExecutionProcessRunReason::DevServer
+        ) {
+            return Ok(None);
+        }
+        let task = task_attempt
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(ContainerError::Other(anyhow!("Parent task not found")))?;
+        let Some(project) = Project::find_by_id(&self.db.pool, task.project_id).await? else {
+            return Ok(None);
+        };
+        let settings = Project::find_sandbox_settings(&self.db.pool, project.id).await?;
+        let (true, Some(image)) = (settings.enabled, settings.image) else {
+            return Ok(None);
+        };
+
+        // Every repository of the attempt has its own worktree
+        let mut worktrees = vec![worktree_path.to_path_buf()];
+        for repo in
+            TaskAttemptRepository::list_for_attempt_with_repo(&self.db.pool, task_attempt.id)
+                .await?
+        {
+            if let Some(container_ref) = repo.container_ref {
+                worktrees.push(PathBuf::from(container_ref));
+            }
+        }
+        let mut mounts: Vec<SandboxMount> = Vec::new();
+        for worktree in worktrees {
+            for mount in SandboxMount::for_worktree(&worktree) {
+                if !mounts.iter().any(|existing| existing.path == mount.path) {
+                    mounts.push(mount);
+                }
+            }
+        }
+        Ok(Some(ContainerSandbox {
+            runtime: settings.runtime,
+            image,
+            name: Self::sandbox_container_name(execution_process.id),
+            mounts,
+            limits: Project::find_resource_limits(&self.db.pool, project.id).await?,
+        }))
+    }
+
+    /// Apply the project's resource limits to a process running on the host. Best-effort: the
+    /// process keeps running unlimited when the platform refuses, e.g. without a delegated cgroup.
+    /// Returns a message for the process's log when the limits could not be applied.
+    async fn limit_host_process(
+        &self,
+        task_attempt: &TaskAttempt,
+        execution_process_id: Uuid,
+        pid: u32,
+    ) -> Option<String> {
+        let limits = match task_attempt.parent_task(&self.db.pool).await {
+            Ok(Some(task)) => Project::find_resource_limits(&self.db.pool, task.project_id).await,
+            Ok(None) => return None,
+            Err(e) => Err(e),
+        };
+        let limits = match limits {
+            Ok(limits) => limits,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load resource limits for execution {}: {}",
+                    execution_process_id,
+                    e
+                );
+                return None;
+            }
+        };
+        let Err(e) = resource_limits::apply(execution_process_id, pid, &limits) else {
+            return None;
+        };
+        tracing::warn!(
+            "Could not apply resource limits to execution {}: {}",
+            execution_process_id,
+            e
+        );
+        Some(format!(
+            "Warning: the project's CPU and memory limits were not applied, this process runs \
+             unlimited: {e}\n"
+        ))
+    }
+
+    /// Killing the runtime client does not always stop the container, so remove it explicitly.
+    /// Best-effort: the container usually does not exist, either because the process was not
+    /// sandboxed or because it already exited.
+    async fn remove_sandbox_container(&self, execution_process: &ExecutionProcess) {
+        let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
+        else {
+            return;
+        };
+        let Ok(settings) = Project::find_sandbox_settings(&self.db.pool, ctx.task.project_id).await
+        else {
+            return;
+        };
+        if !settings.enabled {
+            return;
+        }
+        let name = Self::sandbox_container_name(execution_process.id);
+        if let Err(e) = tokio::process::Command::new(settings.runtime.program())
+            .args(["rm", "-f", &name])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .await
+        {
+            tracing::debug!("Failed to remove sandbox container {}: {}", name, e);
+        }
+    }
+
+    /// Store the outcome of a verify script run and notify on failure. Returns whether it passed;
+    /// a run stopped by the user counts as neither and is not recorded.
+    async fn record_verification(
+        db: &DBService,
+        config: &Arc<RwLock<Config>>,
+        ctx: &ExecutionContext,
+    ) -> bool {
+        let process = &ctx.execution_process;
+        if matches!(process.status, ExecutionProcessStatus::Killed) {
+            return false;
+        }
+
+        let passed = matches!(process.status, ExecutionProcessStatus::Completed)
+            && process.exit_code == Some(0);
+        if let Err(e) = AttemptVerification::create(
+            &db.pool,
+            ctx.task_attempt.id,
+            process.id,
+            passed,
+            process.exit_code,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to record verification result for attempt {}: {}",
+                ctx.task_attempt.id,
+                e
+            );
+        }
+
+        if !passed {
+            let notify_cfg = config.read().await.notifications.clone();
+            let title = format!("Verification Failed: {}", ctx.task.title);
+            let message = match process.exit_code {
+                Some(code) => format!("Verify script exited with code {code}"),
+                None => "Verify script did not complete".to_string(),
+            };
+            NotificationService::notify(notify_cfg, &title, &message).await;
+        }
+
+        passed
+    }
+
+    /// Send the output of a failed cleanup or verify script back to the coding agent when the
+    /// project allows automatic fixes. A passing run resets the attempt's fix count. Returns
+    /// whether a follow-up was started.
+    async fn try_start_auto_fix(&self, ctx: &ExecutionContext) -> bool {
+        match self.start_auto_fix(ctx).await {
+            Ok(started) => started,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to start automatic fix for attempt {}: {}",
+                    ctx.task_attempt.id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    async fn start_auto_fix(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        let process = &ctx.execution_process;
+        if !matches!(
+            process.run_reason,
+            ExecutionProcessRunReason::CleanupScript | ExecutionProcessRunReason::VerifyScript
+        ) {
+            return Ok(false);
+        }
+        match process.status {
+            ExecutionProcessStatus::Completed if process.exit_code == Some(0) => {
+                TaskAttempt::reset_auto_fix_count(&self.db.pool, ctx.task_attempt.id).await?;
+                return Ok(false);
+            }
+            ExecutionProcessStatus::Completed | ExecutionProcessStatus::Failed => {}
+            // Stopped by the user
+            _ => return Ok(false),
+        }
+
+        let max_attempts =
+            Project::find_auto_fix_attempts(&self.db.pool, ctx.task.project_id).await?;
+        if max_attempts == 0 {
+            return Ok(false);
+        }
+
+        let procs =
+            ExecutionProcess::find_by_task_attempt_id(&self.db.pool, ctx.task_attempt.id, false)
+                .await?;
+        if procs
+            .iter()
+            .any(|p| matches!(p.status, ExecutionProcessStatus::Running))
+        {
+            return Ok(false);
+        }
+
+        let Some(session_id) = ExecutionProcess::find_latest_session_id_by_task_attempt(
+            &self.db.pool,
+            ctx.task_attempt.id,
+        )
+        .await?
+        else {
+            return Ok(false);
+        };
+        let Some(latest) = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+            &self.db.pool,
+            ctx.task_attempt.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?
+        else {
+            return Ok(false);
+        };
+        use executors::actions::ExecutorActionType;
+        let executor_profile_id = match &latest.executor_action()?.typ {
+            ExecutorActionType::CodingAgentInitialRequest(req) => req.executor_profile_id.clone(),
+            ExecutorActionType::CodingAgentFollowUpRequest(req) => req.executor_profile_id.clone(),
+            ExecutorActionType::ScriptRequest(_) => return Ok(false),
+        };
+
+        let Some(attempt) =
+            TaskAttempt::try_claim_auto_fix(&self.db.pool, ctx.task_attempt.id, max_attempts)
+                .await?
+        else {
+            tracing::info!(
+                "Attempt {} used all {} automatic fixes; leaving the failure for review",
+                ctx.task_attempt.id,
+                max_attempts
+            );
+            return Ok(false);
+        };
+
+        let output: String = match self.msg_stores.read().await.get(&process.id) {
+            Some(msg_store) => msg_store
+                .get_history()
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::Stdout(chunk) | LogMsg::Stderr(chunk) => Some(chunk),
+                    _ => None,
+                })
+                .collect(),
+            None => String::new(),
+        };
+        let prompt = build_auto_fix_prompt(
+            &process.run_reason,
+            process.exit_code,
+            &output,
+            attempt,
+            max_attempts,
+        );
+
+        let cleanup_action = match ctx.task.parent_project(&self.db.pool).await? {
+            Some(project) => self.post_agent_action(&project).await?,
+            None => None,
+        };
+        let action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentFollowUpRequest(
+                executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest {
+                    prompt,
+                    session_id,
+                    executor_profile_id,
+                },
+            ),
+            cleanup_action,
+        );
+        self.start_execution(
+            &ctx.task_attempt,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+        tracing::info!(
+            "Started automatic fix {}/{} for attempt {}",
+            attempt,
+            max_attempts,
+            ctx.task_attempt.id
+        );
+        Ok(true)
     }
 
     /// Defensively check for externally deleted worktrees and mark them as deleted in the database
@@ -457,6 +992,8 @@ impl LocalContainerService {
             let worktree_path_str = path.to_string_lossy().to_string();
             if let Ok(false) =
                 TaskAttempt::container_ref_exists(&self.db().pool, &worktree_path_str).await
+                && let Ok(false) =
+                    WarmWorktree::path_exists(&self.db().pool, &worktree_path_str).await
             {
                 // This is an orphaned worktree - delete it
                 tracing::info!("Found orphaned worktree: {}", worktree_path_str);
@@ -489,48 +1026,407 @@ impl LocalContainerService {
         Ok(())
     }
 
-    pub async fn cleanup_expired_attempts(db: &DBService) -> Result<(), DeploymentError> {
-        let expired_attempts = TaskAttempt::find_expired_for_cleanup(&db.pool).await?;
-        if expired_attempts.is_empty() {
-            tracing::debug!("No expired worktrees found");
-            return Ok(());
-        }
-        tracing::info!(
-            "Found {} expired worktrees to clean up",
-            expired_attempts.len()
-        );
-        for (attempt_id, worktree_path, git_repo_path) in expired_attempts {
-            Self::cleanup_expired_attempt(
-                db,
-                attempt_id,
-                PathBuf::from(worktree_path),
-                PathBuf::from(git_repo_path),
-            )
-            .await
-            .unwrap_or_else(|e| {
-                tracing::error!("Failed to clean up expired attempt {attempt_id}: {e}",);
-            });
+    /// Warn about attempts that will be cleaned up soon, both as a notification and in the
+    /// activity feed. Cleanup waits until a warning's grace period has passed.
+    pub async fn warn_expiring_attempts(
+        db: &DBService,
+        config: &Arc<RwLock<Config>>,
+    ) -> Result<(), DeploymentError> {
+        let attempts = AttemptCleanupWarning::find_attempts_to_warn(&db.pool).await?;
+        for attempt in attempts {
+            let cleanup_at = Utc::now() + chrono::Duration::hours(CLEANUP_WARNING_LEAD_HOURS);
+            AttemptCleanupWarning::create(&db.pool, attempt.task_attempt_id, cleanup_at).await?;
+            tracing::info!(
+                "Warned attempt {} (idle since {}) about worktree cleanup",
+                attempt.task_attempt_id,
+                attempt.last_activity_at
+            );
+
+            let notify_cfg = config.read().await.notifications.clone();
+            let title = format!("Workspace Cleanup: {}", attempt.task_title);
+            let message = format!(
+                "The workspace for '{}' will be cleaned up in {}h. Keep it from the activity feed.",
+                attempt.task_title, CLEANUP_WARNING_LEAD_HOURS
+            );
+            NotificationService::notify(notify_cfg, &title, &message).await;
+        }
+        Ok(())
+    }
+
+    pub async fn cleanup_expired_attempts(db: &DBService) -> Result<(), DeploymentError> {
+        let expired_attempts = TaskAttempt::find_expired_for_cleanup(&db.pool).await?;
+        if expired_attempts.is_empty() {
+            tracing::debug!("No expired worktrees found");
+            return Ok(());
+        }
+        tracing::info!(
+            "Found {} expired worktrees to clean up",
+            expired_attempts.len()
+        );
+        let now = Utc::now();
+        for (attempt_id, worktree_path, git_repo_path) in expired_attempts {
+            if !AttemptCleanupWarning::cleanup_due(&db.pool, attempt_id, now).await? {
+                tracing::debug!(
+                    "Skipping cleanup of attempt {attempt_id} until its warning period ends"
+                );
+                continue;
+            }
+            Self::cleanup_expired_attempt(
+                db,
+                attempt_id,
+                PathBuf::from(worktree_path),
+                PathBuf::from(git_repo_path),
+            )
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to clean up expired attempt {attempt_id}: {e}",);
+            });
+        }
+        Ok(())
+    }
+
+    pub async fn spawn_worktree_cleanup(&self) {
+        let db = self.db.clone();
+        let config = self.config.clone();
+        let mut cleanup_interval = tokio::time::interval(tokio::time::Duration::from_secs(1800)); // 30 minutes
+        self.cleanup_orphaned_worktrees().await;
+        tokio::spawn(async move {
+            loop {
+                cleanup_interval.tick().await;
+                tracing::info!("Starting periodic worktree cleanup...");
+                Self::check_externally_deleted_worktrees(&db)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to check externally deleted worktrees: {}", e);
+                    });
+                Self::warn_expiring_attempts(&db, &config)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to warn about expiring worktrees: {}", e);
+                    });
+                Self::cleanup_expired_attempts(&db)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to clean up expired worktree attempts: {}", e)
+                    });
+            }
+        });
+    }
+
+    /// Delete recorded activity events past the configured retention
+    pub async fn prune_activity_events(
+        db: &DBService,
+        config: &Arc<RwLock<Config>>,
+    ) -> Result<(), DeploymentError> {
+        let feed_config = config.read().await.activity_feed.clone();
+        let before = feed_config
+            .effective_retention_days()
+            .map(|days| Utc::now() - chrono::Duration::days(days as i64));
+        if before.is_none() && feed_config.max_events.is_none() {
+            return Ok(());
+        }
+        let deleted = ActivityEventRecord::prune(&db.pool, before, feed_config.max_events).await?;
+        if deleted > 0 {
+            tracing::info!("Pruned {} activity events", deleted);
+        }
+        Ok(())
+    }
+
+    /// Delete audit log entries past the configured retention
+    pub async fn prune_audit_log(
+        db: &DBService,
+        config: &Arc<RwLock<Config>>,
+    ) -> Result<(), DeploymentError> {
+        let Some(retention_days) = config.read().await.audit_log.retention_days else {
+            return Ok(());
+        };
+        let before = Utc::now() - chrono::Duration::days(retention_days.into());
+        let deleted = AuditLogEntry::prune(&db.pool, before).await?;
+        if deleted > 0 {
+            tracing::info!("Pruned {} audit log entries", deleted);
+        }
+        Ok(())
+    }
+
+    pub fn spawn_audit_log_pruning(&self) {
+        let db = self.db.clone();
+        let config = self.config.clone();
+        let mut prune_interval = tokio::time::interval(tokio::time::Duration::from_secs(3600)); // hourly
+        tokio::spawn(async move {
+            loop {
+                prune_interval.tick().await;
+                Self::prune_audit_log(&db, &config)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to prune the audit log: {}", e);
+                    });
+            }
+        });
+    }
+
+    /// Checks hourly whether a database backup is due, so interval changes apply without a
+    /// restart
+    pub fn spawn_database_backups(&self) {
+        let db = self.db.clone();
+        let config = self.config.clone();
+        let mut check_interval = tokio::time::interval(tokio::time::Duration::from_secs(3600));
+        tokio::spawn(async move {
+            loop {
+                check_interval.tick().await;
+                let backup_config = config.read().await.database_backup.clone();
+                if !backup_config.enabled {
+                    continue;
+                }
+                match db_backup::backup_due(&backup_config, Utc::now()) {
+                    Ok(false) => continue,
+                    Ok(true) => {}
+                    Err(e) => {
+                        tracing::error!("Failed to list database backups: {}", e);
+                        continue;
+                    }
+                }
+                match db_backup::create_backup(&db, &backup_config).await {
+                    Ok(backup) => tracing::info!("Backed up database to {}", backup.path),
+                    Err(e) => tracing::error!("Failed to back up database: {}", e),
+                }
+            }
+        });
+    }
+
+    pub fn spawn_activity_event_pruning(&self) {
+        let db = self.db.clone();
+        let config = self.config.clone();
+        let mut prune_interval = tokio::time::interval(tokio::time::Duration::from_secs(3600)); // hourly
+        tokio::spawn(async move {
+            loop {
+                prune_interval.tick().await;
+                Self::prune_activity_events(&db, &config)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to prune activity events: {}", e);
+                    });
+            }
+        });
+    }
+
+    /// Checks hourly whether maintenance is due, starting an hour after startup so it does not
+    /// compete with the startup work. Databases without incremental vacuum are never converted
+    /// here; that takes a full `VACUUM` and is left to the maintenance endpoint.
+    pub fn spawn_database_maintenance(&self) {
+        let db = self.db.clone();
+        let config = self.config.clone();
+        let mut check_interval = tokio::time::interval(tokio::time::Duration::from_secs(3600));
+        tokio::spawn(async move {
+            // The first tick completes immediately
+            check_interval.tick().await;
+            let mut last_run: Option<std::time::Instant> = None;
+            loop {
+                check_interval.tick().await;
+                let maintenance_config = config.read().await.database_maintenance.clone();
+                if !maintenance_config.enabled {
+                    continue;
+                }
+                let interval = std::time::Duration::from_secs(
+                    u64::from(maintenance_config.interval_hours.max(1)) * 3600,
+                );
+                if last_run.is_some_and(|at| at.elapsed() < interval) {
+                    continue;
+                }
+                last_run = Some(std::time::Instant::now());
+                match db.run_maintenance(false).await {
+                    Ok(report) => tracing::info!(
+                        "Database maintenance took {}ms, checkpointed {} WAL frames and freed {} pages",
+                        report.duration_ms,
+                        report.checkpointed_frames,
+                        report.freed_pages
+                    ),
+                    Err(e) => tracing::error!("Failed to run database maintenance: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Archive processes that finished past the configured age, moving their logs into the
+    /// archive table
+    pub async fn archive_execution_logs(
+        db: &DBService,
+        config: &Arc<RwLock<Config>>,
+    ) -> Result<(), DeploymentError> {
+        let archive_config = config.read().await.log_archive.clone();
+        if !archive_config.enabled {
+            return Ok(());
+        }
+        let before = Utc::now() - chrono::Duration::days(archive_config.after_days.into());
+        let archived = ExecutionProcessLogs::archive_finished_before(&db.pool, before).await?;
+        if archived > 0 {
+            tracing::info!("Archived {} execution processes", archived);
+        }
+        Ok(())
+    }
+
+    async fn stop_grace_period(config: &Arc<RwLock<Config>>) -> Duration {
+        Duration::from_secs(config.read().await.stop_grace_period_secs.into())
+    }
+
+    /// Keep a compressed copy of a finished coding agent's history, so opening its logs later
+    /// streams the normalized entries instead of normalizing the stored output again. Histories
+    /// that lost messages to the store's size limit are left to the database copy.
+    async fn snapshot_msg_store(config: &Arc<RwLock<Config>>, exec_id: Uuid, store: &MsgStore) {
+        if !config.read().await.log_snapshots.enabled || !store.is_history_complete() {
+            return;
+        }
+        let history = store.get_history();
+        if !history
+            .iter()
+            .any(|msg| matches!(msg, LogMsg::JsonPatch(_)))
+        {
+            return;
+        }
+        let result = tokio::task::spawn_blocking(move || {
+            log_snapshots::write_snapshot(&log_snapshots::snapshot_dir(), exec_id, &history)
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::warn!("Failed to write log snapshot for {}: {}", exec_id, e),
+            Err(e) => tracing::warn!("Failed to write log snapshot for {}: {}", exec_id, e),
+        }
+    }
+
+    /// Delete log snapshots nobody opened within the retention period
+    pub async fn prune_log_snapshots(config: &Arc<RwLock<Config>>) -> Result<(), DeploymentError> {
+        let snapshot_config = config.read().await.log_snapshots.clone();
+        let cutoff = std::time::SystemTime::now()
+            - Duration::from_secs(u64::from(snapshot_config.retention_days) * 24 * 3600);
+        let removed = tokio::task::spawn_blocking(move || {
+            log_snapshots::prune_snapshots(&log_snapshots::snapshot_dir(), cutoff)
+        })
+        .await
+        .map_err(|e| DeploymentError::Other(anyhow!(e)))??;
+        if removed > 0 {
+            tracing::info!("Removed {} expired log snapshots", removed);
+        }
+        Ok(())
+    }
+
+    pub fn spawn_log_archival(&self) {
+        let db = self.db.clone();
+        let config = self.config.clone();
+        let mut archive_interval = tokio::time::interval(tokio::time::Duration::from_secs(3600));
+        tokio::spawn(async move {
+            loop {
+                archive_interval.tick().await;
+                Self::archive_execution_logs(&db, &config)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to archive execution logs: {}", e);
+                    });
+                Self::prune_log_snapshots(&config)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to prune log snapshots: {}", e);
+                    });
+            }
+        });
+    }
+
+    /// Notify about attempt branches that can be deleted. Nothing is deleted here; branches are
+    /// removed only when the user confirms through the stale branches endpoint.
+    pub async fn report_stale_branches(
+        db: &DBService,
+        config: &Arc<RwLock<Config>>,
+        git: &GitService,
+    ) -> Result<(), DeploymentError> {
+        for project in Project::find_all(&db.pool).await? {
+            let candidates =
+                TaskAttempt::find_branch_cleanup_candidates(&db.pool, project.id).await?;
+            let stale =
+                branch_cleanup::find_stale_branches(git, &project.git_repo_path, &candidates);
+            if stale.is_empty() {
+                continue;
+            }
+            tracing::info!(
+                "Project {} has {} stale attempt branches",
+                project.id,
+                stale.len()
+            );
+
+            let notify_cfg = config.read().await.notifications.clone();
+            let title = format!("Stale Branches: {}", project.name);
+            let message = format!(
+                "{} attempt branches are merged or belong to finished tasks. Review them in the project's branch cleanup.",
+                stale.len()
+            );
+            NotificationService::notify(notify_cfg, &title, &message).await;
         }
         Ok(())
     }
 
-    pub async fn spawn_worktree_cleanup(&self) {
+    pub fn spawn_stale_branch_scan(&self) {
         let db = self.db.clone();
-        let mut cleanup_interval = tokio::time::interval(tokio::time::Duration::from_secs(1800)); // 30 minutes
-        self.cleanup_orphaned_worktrees().await;
+        let config = self.config.clone();
+        let git = self.git.clone();
+        let mut scan_interval = tokio::time::interval(tokio::time::Duration::from_secs(86400)); // daily
         tokio::spawn(async move {
+            // The first tick completes immediately; skip it so startup is not noisy
+            scan_interval.tick().await;
             loop {
-                cleanup_interval.tick().await;
-                tracing::info!("Starting periodic worktree cleanup...");
-                Self::check_externally_deleted_worktrees(&db)
+                scan_interval.tick().await;
+                Self::report_stale_branches(&db, &config, &git)
                     .await
                     .unwrap_or_else(|e| {
-                        tracing::error!("Failed to check externally deleted worktrees: {}", e);
+                        tracing::error!("Failed to scan for stale branches: {}", e);
                     });
-                Self::cleanup_expired_attempts(&db)
+            }
+        });
+    }
+
+    /// Run `git maintenance` on the repositories of every project whose maintenance interval has
+    /// elapsed. Failures are logged and retried after the next interval.
+    pub async fn run_due_git_maintenance(db: &DBService) -> Result<(), DeploymentError> {
+        for project_id in Project::find_due_for_git_maintenance(&db.pool).await? {
+            let mut repo_paths: Vec<PathBuf> =
+                ProjectRepository::list_for_project(&db.pool, project_id)
+                    .await?
+                    .into_iter()
+                    .map(|repo| repo.git_repo_path)
+                    .collect();
+            if repo_paths.is_empty()
+                && let Some(project) = Project::find_by_id(&db.pool, project_id).await?
+            {
+                repo_paths.push(project.git_repo_path);
+            }
+            repo_paths.dedup();
+
+            for repo_path in repo_paths {
+                let result = tokio::task::spawn_blocking({
+                    let repo_path = repo_path.clone();
+                    move || GitCli::new().run_maintenance(&repo_path)
+                })
+                .await;
+                match result {
+                    Ok(Ok(())) => tracing::info!("Ran git maintenance in {}", repo_path.display()),
+                    Ok(Err(e)) => {
+                        tracing::warn!("git maintenance failed in {}: {}", repo_path.display(), e)
+                    }
+                    Err(e) => tracing::error!("git maintenance task failed: {}", e),
+                }
+            }
+            Project::mark_git_maintenance_run(&db.pool, project_id).await?;
+        }
+        Ok(())
+    }
+
+    pub fn spawn_git_maintenance(&self) {
+        let db = self.db.clone();
+        let mut check_interval = tokio::time::interval(tokio::time::Duration::from_secs(3600)); // hourly
+        tokio::spawn(async move {
+            loop {
+                check_interval.tick().await;
+                Self::run_due_git_maintenance(&db)
                     .await
                     .unwrap_or_else(|e| {
-                        tracing::error!("Failed to clean up expired worktree attempts: {}", e)
+                        tracing::error!("Failed to run scheduled git maintenance: {}", e);
                     });
             }
         });
@@ -568,8 +1464,9 @@ impl LocalContainerService {
                 _ = &mut exit_signal_future => {
                     // Executor signaled completion: kill group and remember to force Completed(0)
                     if let Some(child_lock) = child_store.read().await.get(&exec_id).cloned() {
+                        let grace_period = Self::stop_grace_period(&config).await;
                         let mut child = child_lock.write().await ;
-                        if let Err(err) = command::kill_process_group(&mut child).await {
+                        if let Err(err) = command::kill_process_group(&mut child, grace_period).await {
                             tracing::error!("Failed to kill process group after exit signal: {} {}", exec_id, err);
                         }
                     }
@@ -581,6 +1478,8 @@ impl LocalContainerService {
                 }
             }
 
+            resource_limits::release(exec_id);
+
             let (exit_code, status) = match status_result {
                 Ok(exit_status) => {
                     let code = exit_status.code().unwrap_or(-1) as i64;
@@ -602,11 +1501,29 @@ impl LocalContainerService {
             }
 
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::DevServer
+                ) {
+                    ActivityEventRecorder::dev_server(&db.pool, exec_id).await;
+                }
+
                 // Update executor session summary if available
                 if let Err(e) = container.update_executor_session_summary(&exec_id).await {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::CodingAgent
+                ) {
+                    if let Err(e) = container.record_token_usage(&exec_id).await {
+                        tracing::warn!("Failed to record token usage: {}", e);
+                    } else if let Err(e) = container.notify_budget_alerts(&ctx).await {
+                        tracing::warn!("Failed to check usage budgets: {}", e);
+                    }
+                }
+
                 let success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
@@ -651,13 +1568,33 @@ impl LocalContainerService {
                             ctx.task_attempt.id
                         );
 
-                        // Manually finalize task since we're bypassing normal execution flow
-                        Self::finalize_task(&db, &config, &ctx).await;
+                        // The verify script still decides whether the task moves on; without one,
+                        // finalize manually since we're bypassing normal execution flow
+                        let verify_started = container
+                            .try_start_verify_action(&ctx)
+                            .await
+                            .unwrap_or_else(|e| {
+                                tracing::error!("Failed to start verify script: {}", e);
+                                false
+                            });
+                        if !verify_started {
+                            Self::finalize_task(&db, &config, &ctx).await;
+                        }
                     }
                 }
 
                 if Self::should_finalize(&ctx) {
-                    Self::finalize_task(&db, &config, &ctx).await;
+                    container.push_open_pr_updates(&ctx).await;
+                }
+
+                // A started fix run is finalized once it and its scripts finish
+                let auto_fix_started =
+                    Self::should_finalize(&ctx) && container.try_start_auto_fix(&ctx).await;
+
+                if Self::should_finalize(&ctx)
+                    && !auto_fix_started
+                    && Self::finalize_task(&db, &config, &ctx).await
+                {
                     // After finalization, check if a queued follow-up exists and start it
                     if let Err(e) = container.try_consume_queued_followup(&ctx).await {
                         tracing::error!(
@@ -668,21 +1605,34 @@ impl LocalContainerService {
                     }
                 }
 
-                // Fire analytics event when CodingAgent execution has finished
-                if config.read().await.analytics_enabled == Some(true)
-                    && matches!(
-                        &ctx.execution_process.run_reason,
-                        ExecutionProcessRunReason::CodingAgent
-                    )
-                    && let Some(analytics) = &analytics
-                {
-                    analytics.analytics_service.track_event(&analytics.user_id, "task_attempt_finished", Some(json!({
+                // Fire analytics event when CodingAgent execution has finished, or keep it locally
+                // when it is not sent
+                if matches!(
+                    &ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::CodingAgent
+                ) {
+                    WebhookService::attempt_finished(&db.pool, &ctx);
+                    let properties = json!({
                         "task_id": ctx.task.id.to_string(),
                         "project_id": ctx.task.project_id.to_string(),
                         "attempt_id": ctx.task_attempt.id.to_string(),
                         "execution_success": matches!(ctx.execution_process.status, ExecutionProcessStatus::Completed),
                         "exit_code": ctx.execution_process.exit_code,
-                    })));
+                    });
+                    if config.read().await.analytics_enabled == Some(true)
+                        && let Some(analytics) = &analytics
+                    {
+                        analytics.analytics_service.track_event(
+                            &analytics.user_id,
+                            "task_attempt_finished",
+                            Some(properties),
+                        );
+                    } else if let Err(e) =
+                        LocalAnalyticsEvent::record(&db.pool, "task_attempt_finished", &properties)
+                            .await
+                    {
+                        tracing::warn!("Failed to record local analytics event: {}", e);
+                    }
                 }
             }
 
@@ -703,6 +1653,7 @@ impl LocalContainerService {
             if let Some(msg_arc) = msg_stores.write().await.remove(&exec_id) {
                 msg_arc.push_finished();
                 tokio::time::sleep(Duration::from_millis(50)).await; // Wait for the finish message to propogate
+                Self::snapshot_msg_store(&config, exec_id, &msg_arc).await;
                 match Arc::try_unwrap(msg_arc) {
                     Ok(inner) => drop(inner),
                     Err(arc) => tracing::error!(
@@ -844,6 +1795,7 @@ impl LocalContainerService {
             Self::apply_stream_omit_policy(&mut diff, &cum, stats_only);
             filtered_diffs.push(diff);
         }
+        repo_lookup.sort_diffs(&mut filtered_diffs);
 
         let stream = futures::stream::iter(filtered_diffs.into_iter().map(|diff| {
             let entry_index = GitService::diff_path(&diff);
@@ -862,6 +1814,225 @@ impl LocalContainerService {
         })
     }
 
+    /// Work out what an attempt's diff should be computed against: the recorded merge commit for
+    /// merged attempts with nothing new on top, otherwise the live worktree vs its base commit.
+    /// The worktree is recreated if it has gone.
+    async fn resolve_diff_source(
+        &self,
+        task_attempt: &TaskAttempt,
+        repository_filter: Option<Uuid>,
+    ) -> Result<(DiffSource, Arc<RepositoryLookup>), ContainerError> {
+        let repository = self
+            .diff_repository(task_attempt, repository_filter)
+            .await?;
+        let (container_ref, _) = self
+            .ensure_repository_container(
+                task_attempt,
+                &repository.task,
+                &repository.repo,
+                repository.attempt_entry.as_ref(),
+            )
+            .await?;
+        let source = self
+            .diff_source_at(
+                task_attempt,
+                &repository.repo,
+                PathBuf::from(&container_ref),
+            )
+            .await?;
+        Ok((source, repository.repo_lookup))
+    }
+
+    /// Like `resolve_diff_source`, but only for a worktree that is already on disk: `None` when
+    /// it has been removed, and nothing is created or fetched.
+    async fn existing_diff_source(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<Option<(DiffSource, Arc<RepositoryLookup>)>, ContainerError> {
+        let repository = self.diff_repository(task_attempt, None).await?;
+        let entry_is_primary = repository
+            .attempt_entry
+            .as_ref()
+            .map(|entry| entry.is_primary)
+            .unwrap_or(repository.repo.is_primary);
+        let Some(worktree_path) = repository
+            .attempt_entry
+            .as_ref()
+            .and_then(|entry| entry.container_ref.clone())
+            .or_else(|| {
+                entry_is_primary
+                    .then(|| task_attempt.container_ref.clone())
+                    .flatten()
+            })
+            .map(PathBuf::from)
+            .filter(|path| path.exists())
+        else {
+            return Ok(None);
+        };
+        let source = self
+            .diff_source_at(task_attempt, &repository.repo, worktree_path)
+            .await?;
+        Ok(Some((source, repository.repo_lookup)))
+    }
+
+    /// Contents-omitted diff of an already resolved source
+    fn diff_stats_from(
+        &self,
+        source: &DiffSource,
+        repo_lookup: &RepositoryLookup,
+    ) -> Result<Vec<Diff>, ContainerError> {
+        let diffs = match source {
+            DiffSource::Merged { repo_path, commit } => self.git().get_diffs(
+                DiffTarget::Commit {
+                    repo_path,
+                    commit_sha: commit,
+                },
+                None,
+            )?,
+            DiffSource::Live {
+                worktree_path,
+                base_commit,
+            } => self.git().get_diffs(
+                DiffTarget::Worktree {
+                    worktree_path,
+                    base_commit,
+                },
+                None,
+            )?,
+        };
+
+        let cumulative = Arc::new(AtomicUsize::new(0));
+        Ok(diffs
+            .into_iter()
+            .map(|mut diff| {
+                repo_lookup.annotate_diff(&mut diff);
+                Self::apply_stream_omit_policy(&mut diff, &cumulative, true);
+                diff
+            })
+            .collect())
+    }
+
+    /// The repository an attempt's diff is taken from: the filtered one, or the attempt's primary
+    async fn diff_repository(
+        &self,
+        task_attempt: &TaskAttempt,
+        repository_filter: Option<Uuid>,
+    ) -> Result<DiffRepository, ContainerError> {
+        let task = task_attempt
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(ContainerError::Other(anyhow!("Parent task not found")))?;
+        let project = task
+            .parent_project(&self.db.pool)
+            .await?
+            .ok_or(ContainerError::Other(anyhow!("Parent project not found")))?;
+
+        let project_repositories =
+            ProjectRepository::list_for_project(&self.db.pool, project.id).await?;
+        let attempt_repositories =
+            TaskAttemptRepository::list_for_attempt(&self.db.pool, task_attempt.id).await?;
+        let attempt_repo_map: HashMap<Uuid, TaskAttemptRepository> = attempt_repositories
+            .iter()
+            .map(|entry| (entry.project_repository_id, entry.clone()))
+            .collect();
+
+        let repo_lookup = Arc::new(RepositoryLookup::from_project_and_attempt(
+            task_attempt.id,
+            &project_repositories,
+            &attempt_repositories,
+            repository_filter,
+        ));
+
+        let selected_repo = if let Some(repo_id) = repository_filter {
+            project_repositories
+                .iter()
+                .find(|repo| repo.id == repo_id)
+                .cloned()
+                .ok_or_else(|| {
+                    ContainerError::Other(anyhow!(
+                        "Repository {} not found for task attempt {}",
+                        repo_id,
+                        task_attempt.id
+                    ))
+                })?
+        } else {
+            project_repositories
+                .iter()
+                .find(|repo| {
+                    attempt_repo_map
+                        .get(&repo.id)
+                        .map(|entry| entry.is_primary)
+                        .unwrap_or(repo.is_primary)
+                })
+                .cloned()
+                .or_else(|| project_repositories.first().cloned())
+                .ok_or_else(|| {
+                    ContainerError::Other(anyhow!(
+                        "No repositories configured for project {}",
+                        project.id
+                    ))
+                })?
+        };
+
+        let attempt_entry = attempt_repo_map.get(&selected_repo.id).cloned();
+        Ok(DiffRepository {
+            task,
+            repo: selected_repo,
+            attempt_entry,
+            repo_lookup,
+        })
+    }
+
+    async fn diff_source_at(
+        &self,
+        task_attempt: &TaskAttempt,
+        repo: &ProjectRepository,
+        worktree_path: PathBuf,
+    ) -> Result<DiffSource, ContainerError> {
+        let project_repo_path = repo.git_repo_path.clone();
+
+        let latest_merge =
+            Merge::find_latest_by_task_attempt_id(&self.db.pool, task_attempt.id).await?;
+
+        let is_ahead = if let Ok((ahead, _)) = self.git().get_branch_status(
+            &project_repo_path,
+            &task_attempt.branch,
+            &task_attempt.target_branch,
+        ) {
+            ahead > 0
+        } else {
+            false
+        };
+
+        if let Some(merge) = &latest_merge
+            && let Some(commit) = merge.merge_commit()
+            && self.is_container_clean(task_attempt).await?
+            && !is_ahead
+        {
+            return Ok((
+                DiffSource::Merged {
+                    repo_path: project_repo_path,
+                    commit,
+                },
+                repo_lookup,
+            ));
+        }
+
+        let base_commit = self.git().get_base_commit(
+            &project_repo_path,
+            &task_attempt.branch,
+            &task_attempt.target_branch,
+        )?;
+
+        Ok((
+            DiffSource::Live {
+                worktree_path,
+                base_commit,
+            },
+            repo_lookup,
+        ))
+    }
+
     /// Create a live diff log stream for ongoing attempts for WebSocket
     /// Returns a stream that owns the filesystem watcher - when dropped, watcher is cleaned up
     async fn create_live_diff_stream(
@@ -871,6 +2042,7 @@ impl LocalContainerService {
         stats_only: bool,
         repository_filter: Option<Uuid>,
         repo_lookup: Arc<RepositoryLookup>,
+        watcher_options: WatcherOptions,
     ) -> Result<DiffStreamWithWatcher, ContainerError> {
         // Get initial snapshot
         let git_service = self.git().clone();
@@ -882,8 +2054,14 @@ impl LocalContainerService {
             None,
         )?;
 
+        let diff_ignore = Arc::new(
+            DiffIgnore::new(&watcher_options.diff_ignore_patterns).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid diff ignore patterns: {}", e);
+                DiffIgnore::empty()
+            }),
+        );
         let cumulative = Arc::new(AtomicUsize::new(0));
-        let full_sent = Arc::new(std::sync::RwLock::new(HashSet::<String>::new()));
+        let full_sent = Arc::new(std::sync::RwLock::new(SentDiffs::default()));
         let mut initial_diffs_vec = Vec::new();
         for mut diff in initial_diffs {
             let repo_match = repo_lookup.annotate_diff(&mut diff);
@@ -893,27 +2071,26 @@ impl LocalContainerService {
                 }
             }
 
+            Self::collapse_ignored_diff(&mut diff, &diff_ignore);
             Self::apply_stream_omit_policy(&mut diff, &cumulative, stats_only);
             initial_diffs_vec.push(diff);
         }
+        repo_lookup.sort_diffs(&mut initial_diffs_vec);
 
         // Record which paths were sent with full content
-        {
+        let initial_patches: Vec<_> = {
             let mut guard = full_sent.write().unwrap();
-            for d in &initial_diffs_vec {
-                if !d.content_omitted {
-                    let p = GitService::diff_path(d);
-                    guard.insert(p);
-                }
-            }
-        }
+            initial_diffs_vec
+                .into_iter()
+                .map(|diff| guard.patch_for(&GitService::diff_path(&diff), diff))
+                .collect()
+        };
 
-        let initial_stream = futures::stream::iter(initial_diffs_vec.into_iter().map(|diff| {
-            let entry_index = GitService::diff_path(&diff);
-            let patch =
-                ConversationPatch::add_diff(escape_json_pointer_segment(&entry_index), diff);
-            Ok::<_, std::io::Error>(LogMsg::JsonPatch(patch))
-        }))
+        let initial_stream = futures::stream::iter(
+            initial_patches
+                .into_iter()
+                .map(|patch| Ok::<_, std::io::Error>(LogMsg::JsonPatch(patch))),
+        )
         .boxed();
 
         // Create live update stream
@@ -921,7 +2098,7 @@ impl LocalContainerService {
         let base_commit = base_commit.clone();
         let worktree_path_for_spawn = worktree_path.clone();
         let watcher_result = tokio::task::spawn_blocking(move || {
-            filesystem_watcher::async_watcher(worktree_path_for_spawn)
+            filesystem_watcher::async_watcher(worktree_path_for_spawn, watcher_options)
         })
         .await
         .map_err(|e| io::Error::other(format!("Failed to spawn watcher setup: {e}")))?;
@@ -933,6 +2110,7 @@ impl LocalContainerService {
             let cumulative = Arc::clone(&cumulative);
             let full_sent = Arc::clone(&full_sent);
             let repo_lookup = Arc::clone(&repo_lookup);
+            let diff_ignore = Arc::clone(&diff_ignore);
 
             try_stream! {
                 while let Some(result) = rx.next().await {
@@ -951,6 +2129,7 @@ impl LocalContainerService {
                                     stats_only,
                                     repo_lookup.as_ref(),
                                     repository_filter,
+                                    diff_ignore.as_ref(),
                                 ).map_err(|e| {
                                     tracing::error!("Error processing file changes: {}", e);
                                     io::Error::other(e.to_string())
@@ -980,6 +2159,19 @@ impl LocalContainerService {
         })
     }
 
+    /// Watcher exclusions and rate cap configured on the attempt's project
+    async fn watcher_options_for(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<WatcherOptions, ContainerError> {
+        let task = task_attempt
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(ContainerError::Other(anyhow!("Parent task not found")))?;
+        let settings = Project::find_watcher_settings(&self.db.pool, task.project_id).await?;
+        Ok(WatcherOptions::for_project(&settings))
+    }
+
     /// Extract changed file paths from filesystem events
     fn extract_changed_paths(
         events: &[DebouncedEvent],
@@ -1000,16 +2192,18 @@ impl LocalContainerService {
     }
 
     /// Process file changes and generate diff messages (for WS)
+    #[allow(clippy::too_many_arguments)]
     fn process_file_changes(
         git_service: &GitService,
         worktree_path: &Path,
         base_commit: &Commit,
         changed_paths: &[String],
         cumulative_bytes: &Arc<AtomicUsize>,
-        full_sent_paths: &Arc<std::sync::RwLock<HashSet<String>>>,
+        full_sent_paths: &Arc<std::sync::RwLock<SentDiffs>>,
         stats_only: bool,
         repo_lookup: &RepositoryLookup,
         repository_filter: Option<Uuid>,
+        diff_ignore: &DiffIgnore,
     ) -> Result<Vec<LogMsg>, ContainerError> {
         let path_filter: Vec<&str> = changed_paths.iter().map(|s| s.as_str()).collect();
 
@@ -1035,19 +2229,15 @@ impl LocalContainerService {
 
             let file_path = GitService::diff_path(&diff);
             files_with_diffs.insert(file_path.clone());
+            Self::collapse_ignored_diff(&mut diff, diff_ignore);
             // Apply stream-level omit policy (affects contents and stats)
             Self::apply_stream_omit_policy(&mut diff, cumulative_bytes, stats_only);
 
-            if diff.content_omitted {
-                if full_sent_paths.read().unwrap().contains(&file_path) {
-                    continue;
-                }
-            } else {
-                let mut guard = full_sent_paths.write().unwrap();
-                guard.insert(file_path.clone());
+            if diff.content_omitted && full_sent_paths.read().unwrap().paths.contains(&file_path) {
+                continue;
             }
 
-            let patch = ConversationPatch::add_diff(escape_json_pointer_segment(&file_path), diff);
+            let patch = full_sent_paths.write().unwrap().patch_for(&file_path, diff);
             msgs.push(LogMsg::JsonPatch(patch));
         }
 
@@ -1061,6 +2251,7 @@ impl LocalContainerService {
             }
 
             if !files_with_diffs.contains(changed_path) {
+                full_sent_paths.write().unwrap().large.remove(changed_path);
                 let patch =
                     ConversationPatch::remove_diff(escape_json_pointer_segment(changed_path));
                 msgs.push(LogMsg::JsonPatch(patch));
@@ -1325,6 +2516,9 @@ impl LocalContainerService {
             }
         }
 
+        let init_submodules = Project::find_worktree_settings(&self.db.pool, repo.project_id)
+            .await?
+            .init_submodules;
         let branch_exists = self
             .git()
             .branch_exists(&repo.git_repo_path, &branch_to_use)?;
@@ -1334,6 +2528,7 @@ impl LocalContainerService {
                 &repo.git_repo_path,
                 &branch_to_use,
                 &worktree_path,
+                init_submodules,
             )
             .await
             {
@@ -1345,6 +2540,7 @@ impl LocalContainerService {
                             &worktree_path,
                             &base_branch_to_use,
                             true,
+                            init_submodules,
                         )
                         .await?;
                     }
@@ -1358,6 +2554,7 @@ impl LocalContainerService {
                             &worktree_path,
                             &base_branch_to_use,
                             true,
+                            init_submodules,
                         )
                         .await?;
                     }
@@ -1373,6 +2570,7 @@ impl LocalContainerService {
                 &worktree_path,
                 &base_branch_to_use,
                 true,
+                init_submodules,
             )
             .await?;
         }
@@ -1627,6 +2825,14 @@ impl ContainerService for LocalContainerService {
         &self.git
     }
 
+    fn project_stats(&self) -> &ProjectStatsService {
+        &self.project_stats
+    }
+
+    fn config(&self) -> &Arc<RwLock<Config>> {
+        &self.config
+    }
+
     fn git_branch_from_task_attempt(&self, attempt_id: &Uuid, task_title: &str) -> String {
         let prefix = match tokio::runtime::Handle::try_current() {
             Ok(_) => tokio::task::block_in_place(|| {
@@ -1642,10 +2848,23 @@ impl ContainerService for LocalContainerService {
         git_branch_name_with_prefix(&prefix, attempt_id, task_title)
     }
 
-    fn task_attempt_to_current_dir(&self, task_attempt: &TaskAttempt) -> PathBuf {
-        PathBuf::from(task_attempt.container_ref.clone().unwrap_or_default())
-    }
-    /// Create a container
+    fn task_attempt_to_current_dir(&self, task_attempt: &TaskAttempt) -> PathBuf {
+        PathBuf::from(task_attempt.container_ref.clone().unwrap_or_default())
+    }
+    /// Create a container
+    fn refill_worktree_pool(&self, project_id: Uuid) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = container.fill_worktree_pool(project_id).await {
+                tracing::warn!(
+                    "Failed to refill the worktree pool of project {}: {}",
+                    project_id,
+                    e
+                );
+            }
+        });
+    }
+
     async fn create(&self, task_attempt: &TaskAttempt) -> Result<ContainerRef, ContainerError> {
         let task = task_attempt
             .parent_task(&self.db.pool)
@@ -1662,25 +2881,36 @@ impl ContainerService for LocalContainerService {
             .await?
             .ok_or(sqlx::Error::RowNotFound)?;
 
-        WorktreeManager::create_worktree(
-            &project.git_repo_path,
-            &task_attempt.branch,
-            &worktree_path,
-            &task_attempt.target_branch,
-            true, // create new branch
-        )
-        .await?;
-
-        // Copy files specified in the project's copy_files field
-        if let Some(copy_files) = &project.copy_files
-            && !copy_files.trim().is_empty()
+        let worktree_settings = Project::find_worktree_settings(&self.db.pool, project.id).await?;
+        let worktree_path = match self
+            .claim_warm_worktree(&project, task_attempt, &worktree_path)
+            .await?
         {
-            self.copy_project_files(&project.git_repo_path, &worktree_path, copy_files)
-                .await
-                .unwrap_or_else(|e| {
-                    tracing::warn!("Failed to copy project files: {}", e);
-                });
-        }
+            Some(claimed_path) => {
+                tracing::info!(
+                    "Attempt {} claimed a warm worktree at {}",
+                    task_attempt.id,
+                    claimed_path.display()
+                );
+                self.refill_worktree_pool(project.id);
+                claimed_path
+            }
+            None => {
+                WorktreeManager::create_worktree(
+                    &project.git_repo_path,
+                    &task_attempt.branch,
+                    &worktree_path,
+                    &task_attempt.target_branch,
+                    true, // create new branch
+                    worktree_settings.init_submodules,
+                )
+                .await?;
+                worktree_path
+            }
+        };
+
+        self.populate_worktree(&project, &worktree_settings, &worktree_path)
+            .await;
 
         // Copy task images from cache to worktree
         if let Err(e) = self
@@ -1779,6 +3009,7 @@ impl ContainerService for LocalContainerService {
                     &repo_worktree_path,
                     &base_branch_to_use,
                     true,
+                    init_submodules,
                 )
                 .await?;
             }
@@ -1911,19 +3142,61 @@ impl ContainerService for LocalContainerService {
         let current_dir = PathBuf::from(&container_ref);
 
         // Compute environment for executor processes
-        let repo_env = self.build_executor_env(task_attempt).await?;
+        let mut repo_env = self.build_executor_env(task_attempt).await?;
+        // Lets the MCP server and hooks the agent runs call back into the API once accounts exist
+        repo_env.insert(AGENT_TOKEN_ENV.to_string(), agent_token().to_string());
+        if matches!(
+            execution_process.run_reason,
+            ExecutionProcessRunReason::DevServer
+        ) {
+            match allocate_dev_server_port(&self.db.pool, execution_process.id).await? {
+                Some(assigned) => {
+                    repo_env.insert(DEV_SERVER_PORT_ENV.to_string(), assigned.port.to_string());
+                    repo_env.insert(DEV_SERVER_URL_ENV.to_string(), assigned.url);
+                }
+                None => tracing::warn!(
+                    "No free dev server port left for execution {}",
+                    execution_process.id
+                ),
+            }
+        }
 
         let spawn_ctx = ExecutorSpawnContext {
             current_dir: &current_dir,
             env: Some(&repo_env),
         };
+        let sandbox = self
+            .execution_sandbox(task_attempt, execution_process, &current_dir)
+            .await?;
+
+        let sandboxed = sandbox.is_some();
 
         // Create the child and stream, add to execution tracker
-        let mut spawned = executor_action.spawn(&spawn_ctx).await?;
+        let mut spawned =
+            ContainerSandbox::scope(sandbox, executor_action.spawn(&spawn_ctx)).await?;
+
+        // Sandboxed processes are limited by the container runtime
+        let limits_warning = match spawned.child.id() {
+            Some(pid) if !sandboxed => {
+                self.limit_host_process(task_attempt, execution_process.id, pid)
+                    .await
+            }
+            _ => None,
+        };
 
         self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
             .await;
 
+        if let Some(warning) = limits_warning
+            && let Some(store) = self.get_msg_store_by_id(&execution_process.id).await
+        {
+            store.push_stderr(warning);
+        }
+
+        if !sandboxed && let Some(pid) = spawned.child.id() {
+            resource_usage::spawn_sampler(execution_process.id, pid, self.msg_stores.clone());
+        }
+
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
 
@@ -1952,11 +3225,15 @@ impl ContainerService for LocalContainerService {
 
         ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, exit_code)
             .await?;
+        if execution_process.run_reason == ExecutionProcessRunReason::DevServer {
+            ActivityEventRecorder::dev_server(&self.db.pool, execution_process.id).await;
+        }
 
         // Kill the child process and remove from the store
         {
+            let grace_period = Self::stop_grace_period(&self.config).await;
             let mut child_guard = child.write().await;
-            if let Err(e) = command::kill_process_group(&mut child_guard).await {
+            if let Err(e) = command::kill_process_group(&mut child_guard, grace_period).await {
                 tracing::error!(
                     "Failed to stop execution process {}: {}",
                     execution_process.id,
@@ -1966,10 +3243,13 @@ impl ContainerService for LocalContainerService {
             }
         }
         self.remove_child_from_store(&execution_process.id).await;
+        self.remove_sandbox_container(execution_process).await;
+        resource_limits::release(execution_process.id);
 
         // Mark the process finished in the MsgStore
         if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
             msg.push_finished();
+            Self::snapshot_msg_store(&self.config, execution_process.id, &msg).await;
         }
 
         // Update task status to InReview when execution is stopped
@@ -2012,111 +3292,159 @@ impl ContainerService for LocalContainerService {
         repository_filter: Option<Uuid>,
     ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>
     {
-        let task = task_attempt
-            .parent_task(&self.db.pool)
-            .await?
-            .ok_or(ContainerError::Other(anyhow!("Parent task not found")))?;
-        let project = task
-            .parent_project(&self.db.pool)
-            .await?
-            .ok_or(ContainerError::Other(anyhow!("Parent project not found")))?;
+        let (source, repo_lookup) = self
+            .resolve_diff_source(task_attempt, repository_filter)
+            .await?;
 
-        let project_repositories =
-            ProjectRepository::list_for_project(&self.db.pool, project.id).await?;
-        let attempt_repositories =
-            TaskAttemptRepository::list_for_attempt(&self.db.pool, task_attempt.id).await?;
-        let attempt_repo_map: HashMap<Uuid, TaskAttemptRepository> = attempt_repositories
-            .iter()
-            .map(|entry| (entry.project_repository_id, entry.clone()))
-            .collect();
+        match source {
+            DiffSource::Merged { repo_path, commit } => {
+                let wrapper = self.create_merged_diff_stream(
+                    &repo_path,
+                    &commit,
+                    stats_only,
+                    repository_filter,
+                    repo_lookup,
+                )?;
+                Ok(Box::pin(wrapper))
+            }
+            DiffSource::Live {
+                worktree_path,
+                base_commit,
+            } => {
+                let watcher_options = self.watcher_options_for(task_attempt).await?;
+                let wrapper = self
+                    .create_live_diff_stream(
+                        &worktree_path,
+                        &base_commit,
+                        stats_only,
+                        repository_filter,
+                        repo_lookup,
+                        watcher_options,
+                    )
+                    .await?;
+                Ok(Box::pin(wrapper))
+            }
+        }
+    }
 
-        let repo_lookup = Arc::new(RepositoryLookup::from_project_and_attempt(
-            &project_repositories,
-            &attempt_repositories,
-        ));
+    async fn diff_stats(&self, task_attempt: &TaskAttempt) -> Result<Vec<Diff>, ContainerError> {
+        let (source, repo_lookup) = self.resolve_diff_source(task_attempt, None).await?;
+        self.diff_stats_from(&source, &repo_lookup)
+    }
 
-        let selected_repo = if let Some(repo_id) = repository_filter {
-            project_repositories
-                .iter()
-                .find(|repo| repo.id == repo_id)
-                .cloned()
-                .ok_or_else(|| {
-                    ContainerError::Other(anyhow!(
-                        "Repository {} not found for task attempt {}",
-                        repo_id,
-                        task_attempt.id
-                    ))
-                })?
-        } else {
-            project_repositories
-                .iter()
-                .find(|repo| {
-                    attempt_repo_map
-                        .get(&repo.id)
-                        .map(|entry| entry.is_primary)
-                        .unwrap_or(repo.is_primary)
-                })
-                .cloned()
-                .or_else(|| project_repositories.first().cloned())
-                .ok_or_else(|| {
-                    ContainerError::Other(anyhow!(
-                        "No repositories configured for project {}",
-                        project.id
-                    ))
-                })?
+    async fn existing_diff_stats(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<Option<Vec<Diff>>, ContainerError> {
+        let Some((source, repo_lookup)) = self.existing_diff_source(task_attempt).await? else {
+            return Ok(None);
         };
+        self.diff_stats_from(&source, &repo_lookup).map(Some)
+    }
 
-        let attempt_entry = attempt_repo_map.get(&selected_repo.id);
-        let (container_ref, _) = self
-            .ensure_repository_container(task_attempt, &task, &selected_repo, attempt_entry)
+    async fn diff_file_content(
+        &self,
+        task_attempt: &TaskAttempt,
+        repository_filter: Option<Uuid>,
+        side: DiffSide,
+        path: &str,
+    ) -> Result<Option<Vec<u8>>, ContainerError> {
+        let (source, _) = self
+            .resolve_diff_source(task_attempt, repository_filter)
             .await?;
-        let worktree_path = PathBuf::from(&container_ref);
-        let project_repo_path = selected_repo.git_repo_path.clone();
-
-        let latest_merge =
-            Merge::find_latest_by_task_attempt_id(&self.db.pool, task_attempt.id).await?;
-
-        let is_ahead = if let Ok((ahead, _)) = self.git().get_branch_status(
-            &project_repo_path,
-            &task_attempt.branch,
-            &task_attempt.target_branch,
-        ) {
-            ahead > 0
-        } else {
-            false
+        let target = match &source {
+            DiffSource::Merged { repo_path, commit } => DiffTarget::Commit {
+                repo_path,
+                commit_sha: commit,
+            },
+            DiffSource::Live {
+                worktree_path,
+                base_commit,
+            } => DiffTarget::Worktree {
+                worktree_path,
+                base_commit,
+            },
         };
+        Ok(self.git().read_diff_side(target, side, path)?)
+    }
 
-        if let Some(merge) = &latest_merge
-            && let Some(commit) = merge.merge_commit()
-            && self.is_container_clean(task_attempt).await?
-            && !is_ahead
-        {
-            let wrapper = self.create_merged_diff_stream(
-                &project_repo_path,
-                &commit,
-                stats_only,
-                repository_filter,
-                Arc::clone(&repo_lookup),
-            )?;
-            return Ok(Box::pin(wrapper));
-        }
+    async fn execution_process_diff(
+        &self,
+        execution_process: &ExecutionProcess,
+        stats_only: bool,
+    ) -> Result<Option<Vec<Diff>>, ContainerError> {
+        let Some(before) = execution_process.before_head_commit.as_deref() else {
+            return Ok(None);
+        };
+        let task_attempt =
+            TaskAttempt::find_by_id(&self.db.pool, execution_process.task_attempt_id)
+                .await?
+                .ok_or(ContainerError::Other(anyhow!("Task attempt not found")))?;
+        let (source, repo_lookup) = self.resolve_diff_source(&task_attempt, None).await?;
+
+        let diffs = match (&source, execution_process.after_head_commit.as_deref()) {
+            (
+                DiffSource::Merged { repo_path, .. }
+                | DiffSource::Live {
+                    worktree_path: repo_path,
+                    ..
+                },
+                Some(after),
+            ) => self.git().get_diffs(
+                DiffTarget::Range {
+                    repo_path,
+                    from_sha: before,
+                    to_sha: after,
+                },
+                None,
+            )?,
+            (DiffSource::Live { worktree_path, .. }, None) => {
+                let base_commit = Commit::from_sha(before)?;
+                self.git().get_diffs(
+                    DiffTarget::Worktree {
+                        worktree_path,
+                        base_commit: &base_commit,
+                    },
+                    None,
+                )?
+            }
+            (DiffSource::Merged { .. }, None) => return Ok(None),
+        };
 
-        let base_commit = self.git().get_base_commit(
-            &project_repo_path,
-            &task_attempt.branch,
-            &task_attempt.target_branch,
-        )?;
+        let cumulative = Arc::new(AtomicUsize::new(0));
+        Ok(Some(
+            diffs
+                .into_iter()
+                .map(|mut diff| {
+                    repo_lookup.annotate_diff(&mut diff);
+                    Self::apply_stream_omit_policy(&mut diff, &cumulative, stats_only);
+                    diff
+                })
+                .collect(),
+        ))
+    }
 
-        let wrapper = self
-            .create_live_diff_stream(
-                &worktree_path,
-                &base_commit,
-                stats_only,
-                repository_filter,
-                repo_lookup,
-            )
+    async fn revert_diff_change(
+        &self,
+        task_attempt: &TaskAttempt,
+        repository_filter: Option<Uuid>,
+        path: &str,
+        old_path: Option<&str>,
+        hunk: Option<&HunkRange>,
+    ) -> Result<bool, ContainerError> {
+        let (source, _) = self
+            .resolve_diff_source(task_attempt, repository_filter)
             .await?;
-        Ok(Box::pin(wrapper))
+        let DiffSource::Live {
+            worktree_path,
+            base_commit,
+        } = source
+        else {
+            return Ok(false);
+        };
+        self.git()
+            .revert_worktree_change(&worktree_path, &base_commit, path, old_path, hunk)?;
+        Ok(true)
     }
 
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
@@ -2181,7 +3509,10 @@ impl ContainerService for LocalContainerService {
             message
         );
 
-        let changes_committed = self.git().commit(Path::new(&container_ref), &message)?;
+        let signing = self.config.read().await.commit_signing.clone();
+        let changes_committed =
+            self.git()
+                .commit_with_signing(Path::new(&container_ref), &message, Some(&signing))?;
         Ok(changes_committed)
     }
 
@@ -2230,6 +3561,42 @@ impl ContainerService for LocalContainerService {
 }
 
 impl LocalContainerService {
+    /// Copy the project's configured files and share its dependency directories into a freshly
+    /// created worktree
+    pub(crate) async fn populate_worktree(
+        &self,
+        project: &Project,
+        worktree_settings: &ProjectWorktreeSettings,
+        worktree_path: &Path,
+    ) {
+        // Copy files specified in the project's copy_files field
+        if let Some(copy_files) = &project.copy_files
+            && !copy_files.trim().is_empty()
+        {
+            self.copy_project_files(&project.git_repo_path, worktree_path, copy_files)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to copy project files: {}", e);
+                });
+        }
+
+        // Share dependency directories so setup scripts don't reinstall them
+        for dir in &worktree_settings.shared_dirs {
+            match dependency_cache::share_dependency_dir(
+                &project.git_repo_path,
+                worktree_path,
+                dir,
+                worktree_settings.share_mode,
+            )
+            .await
+            {
+                Ok(true) => tracing::info!("Shared {} into worktree", dir),
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Failed to share {} into worktree: {}", dir, e),
+            }
+        }
+    }
+
     /// Extract the last assistant message from the MsgStore history
     fn extract_last_assistant_message(&self, exec_id: &Uuid) -> Option<String> {
         // Get the MsgStore for this execution
@@ -2281,6 +3648,129 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Store the tokens the coding agent reported in its output, for cost estimates
+    async fn record_token_usage(&self, exec_id: &Uuid) -> Result<(), anyhow::Error> {
+        let stdout: String = {
+            let msg_stores = self.msg_stores.read().await;
+            let Some(msg_store) = msg_stores.get(exec_id) else {
+                return Ok(());
+            };
+            msg_store
+                .get_history()
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::Stdout(chunk) => Some(chunk),
+                    _ => None,
+                })
+                .collect()
+        };
+        let usage = token_usage_from_output(stdout.lines());
+        ExecutionProcessTokenUsage::replace(&self.db.pool, *exec_id, &usage).await?;
+        Ok(())
+    }
+
+    /// Alert when the usage just recorded brings the agent's monthly budgets near or past their
+    /// limits
+    async fn notify_budget_alerts(&self, ctx: &ExecutionContext) -> Result<(), anyhow::Error> {
+        use executors::actions::ExecutorActionType;
+        let agent = match ctx.execution_process.executor_action()?.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                request.executor_profile_id.executor
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                request.executor_profile_id.executor
+            }
+            ExecutorActionType::ScriptRequest(_) => return Ok(()),
+        };
+        let config = self.config.read().await.clone();
+        budgets::notify_budget_alerts(&self.db.pool, &config, agent).await?;
+        Ok(())
+    }
+
+    /// When the queued follow-up should start instead, if the agent it continues has used more of
+    /// its rate limit window than `followup_usage_threshold` allows
+    async fn queued_followup_wait(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<DateTime<Utc>>, ContainerError> {
+        let Some(threshold) = self.config.read().await.followup_usage_threshold else {
+            return Ok(None);
+        };
+        let Some(latest) = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+            &self.db.pool,
+            ctx.task_attempt.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+        use executors::actions::ExecutorActionType;
+        let agent = match &latest.executor_action()?.typ {
+            ExecutorActionType::CodingAgentInitialRequest(req) => req.executor_profile_id.executor,
+            ExecutorActionType::CodingAgentFollowUpRequest(req) => req.executor_profile_id.executor,
+            ExecutorActionType::ScriptRequest(_) => return Ok(None),
+        };
+        Ok(usage_limits::rate_limit_wait(&self.db.pool, agent, threshold).await?)
+    }
+
+    /// Start queued follow-ups once their `run_at` has passed, checking every minute. An attempt
+    /// that is still running keeps its follow-up queued until the running process finishes.
+    pub fn spawn_scheduled_followups(&self) {
+        let container = self.clone();
+        let mut schedule_interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+        tokio::spawn(async move {
+            loop {
+                schedule_interval.tick().await;
+                container.start_due_followups().await.unwrap_or_else(|e| {
+                    tracing::error!("Failed to start scheduled follow-ups: {}", e);
+                });
+            }
+        });
+    }
+
+    async fn start_due_followups(&self) -> Result<(), ContainerError> {
+        for draft in Draft::find_due_scheduled(&self.db.pool, Utc::now()).await? {
+            let Some(latest) = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+                &self.db.pool,
+                draft.task_attempt_id,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?
+            else {
+                continue;
+            };
+            let ctx = ExecutionProcess::load_context(&self.db.pool, latest.id).await?;
+            if let Err(e) = self.try_consume_queued_followup(&ctx).await {
+                tracing::error!(
+                    "Failed to start scheduled follow-up for attempt {}: {}",
+                    draft.task_attempt_id,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Try the queued follow-up again once `at` has passed
+    fn retry_queued_followup_at(&self, exec_id: Uuid, at: DateTime<Utc>) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep((at - Utc::now()).to_std().unwrap_or_default()).await;
+            let result = match ExecutionProcess::load_context(&container.db.pool, exec_id).await {
+                Ok(ctx) => container.try_consume_queued_followup(&ctx).await,
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = result {
+                tracing::error!(
+                    "Failed to start deferred follow-up after process {}: {}",
+                    exec_id,
+                    e
+                );
+            }
+        });
+    }
+
     /// If a queued follow-up draft exists for this attempt and nothing is running,
     /// start it immediately and clear the draft.
     async fn try_consume_queued_followup(
@@ -2321,6 +3811,22 @@ impl LocalContainerService {
             return Ok(());
         }
 
+        // Scheduled follow-ups wait for their start time; the scheduler picks them up then
+        if draft.run_at.is_some_and(|at| at > Utc::now()) {
+            return Ok(());
+        }
+
+        // Leave the draft queued rather than run into the agent's rate limit
+        if let Some(resets_at) = self.queued_followup_wait(ctx).await? {
+            tracing::info!(
+                "Deferring queued follow-up for attempt {} until the rate limit window resets at {}",
+                ctx.task_attempt.id,
+                resets_at
+            );
+            self.retry_queued_followup_at(ctx.execution_process.id, resets_at);
+            return Ok(());
+        }
+
         // Atomically acquire sending lock; if not acquired, someone else is sending.
         if !Draft::try_mark_sending(&self.db.pool, ctx.task_attempt.id, DraftType::FollowUp)
             .await
@@ -2380,14 +3886,16 @@ impl LocalContainerService {
         };
 
         // Prepare cleanup action
-        let cleanup_action = ctx
-            .task
-            .parent_project(&self.db.pool)
-            .await?
-            .and_then(|project| self.cleanup_action(project.cleanup_script));
+        let cleanup_action = match ctx.task.parent_project(&self.db.pool).await? {
+            Some(project) => self.post_agent_action(&project).await?,
+            None => None,
+        };
 
         // Handle images: associate, copy to worktree, canonicalize prompt
-        let mut prompt = draft.prompt.clone();
+        let mut prompt =
+            prompt_snippets::expand_for_project(&self.db.pool, ctx.task.project_id, &draft.prompt)
+                .await?
+                .prompt;
         if let Some(image_ids) = &draft.image_ids {
             // Associate to task
             let _ = TaskImage::associate_many_dedup(&self.db.pool, ctx.task.id, image_ids).await;
@@ -4,28 +4,37 @@ use async_trait::async_trait;
 use db::DBService;
 use deployment::{Deployment, DeploymentError};
 use executors::profile::ExecutorConfigs;
-use services::services::{
-    analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
-    approvals::Approvals,
-    auth::AuthService,
-    config::{Config, load_config_from_file, save_config_to_file},
-    container::ContainerService,
-    drafts::DraftsService,
-    events::EventService,
-    file_search_cache::FileSearchCache,
-    filesystem::FilesystemService,
-    git::GitService,
-    image::ImageService,
-    sentry::SentryService,
+use services::{
+    activity_feed::ActivityNotifier,
+    services::{
+        analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
+        approvals::Approvals,
+        auth::AuthService,
+        config::{Config, load_config_from_file, save_config_to_file},
+        container::ContainerService,
+        drafts::DraftsService,
+        events::EventService,
+        file_search_cache::FileSearchCache,
+        filesystem::FilesystemService,
+        git::GitService,
+        image::ImageService,
+        project_stats::ProjectStatsService,
+        sentry::SentryService,
+    },
 };
 use tokio::sync::RwLock;
 use utils::{assets::config_path, msg_store::MsgStore};
 use uuid::Uuid;
 
-use crate::container::LocalContainerService;
+use crate::{container::LocalContainerService, manual_changes::ManualChangeMonitor};
 
 mod command;
 pub mod container;
+mod manual_changes;
+mod resource_limits;
+mod resource_usage;
+mod task_scheduler;
+mod worktree_pool;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
@@ -44,6 +53,7 @@ pub struct LocalDeployment {
     file_search_cache: Arc<FileSearchCache>,
     approvals: Approvals,
     drafts: DraftsService,
+    project_stats: ProjectStatsService,
 }
 
 #[async_trait]
@@ -115,6 +125,7 @@ impl Deployment for LocalDeployment {
             user_id: user_id.clone(),
             analytics_service: s.clone(),
         });
+        let project_stats = ProjectStatsService::new();
         let container = LocalContainerService::new(
             db.clone(),
             msg_stores.clone(),
@@ -122,8 +133,21 @@ impl Deployment for LocalDeployment {
             git.clone(),
             image.clone(),
             analytics_ctx,
+            project_stats.clone(),
         );
         container.spawn_worktree_cleanup().await;
+        container.spawn_stale_branch_scan();
+        container.spawn_activity_event_pruning();
+        container.spawn_audit_log_pruning();
+        container.spawn_database_backups();
+        container.spawn_database_maintenance();
+        container.spawn_log_archival();
+        container.spawn_scheduled_followups();
+        container.spawn_git_maintenance();
+        container.spawn_worktree_pool();
+        container.spawn_task_scheduler();
+        ManualChangeMonitor::new(db.clone(), git.clone(), config.clone()).spawn();
+        ActivityNotifier::new(db.clone(), config.clone()).spawn();
 
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
         let drafts = DraftsService::new(db.clone(), image.clone());
@@ -145,6 +169,7 @@ impl Deployment for LocalDeployment {
             file_search_cache,
             approvals,
             drafts,
+            project_stats,
         })
     }
 
@@ -210,4 +235,8 @@ impl Deployment for LocalDeployment {
     fn drafts(&self) -> &DraftsService {
         &self.drafts
     }
+
+    fn project_stats(&self) -> &ProjectStatsService {
+        &self.project_stats
+    }
 }
@@ -0,0 +1,320 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::Utc;
+use db::{
+    DBService,
+    models::{
+        attempt_manual_changes::AttemptManualChanges,
+        execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+        task::Task,
+        task_attempt::TaskAttempt,
+    },
+};
+use futures::StreamExt;
+use notify::RecommendedWatcher;
+use notify_debouncer_full::{Debouncer, RecommendedCache};
+use services::services::{
+    config::Config, filesystem_watcher, git::GitService, notification::NotificationService,
+};
+use tokio::{
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+};
+use uuid::Uuid;
+
+/// How often the set of watched worktrees is reconciled against the database.
+const RECONCILE_INTERVAL: Duration = Duration::from_secs(60);
+/// Wait after the first event of a burst so that editor saves, formatters etc. collapse into one
+/// `git status` call.
+const EVENT_COALESCE_DELAY: Duration = Duration::from_secs(2);
+/// File events this soon after an agent process finished are attributed to the agent (its final
+/// writes and the auto-commit), not to the user.
+const AGENT_SETTLE_GRACE: chrono::Duration = chrono::Duration::seconds(15);
+
+struct WatchedWorktree {
+    path: PathBuf,
+    _debouncer: Debouncer<RecommendedWatcher, RecommendedCache>,
+    listener: JoinHandle<()>,
+}
+
+impl Drop for WatchedWorktree {
+    fn drop(&mut self) {
+        self.listener.abort();
+    }
+}
+
+/// Watches active attempt worktrees and records uncommitted edits that were made while no coding
+/// agent was running, so the board can flag them and offer to commit them.
+#[derive(Clone)]
+pub struct ManualChangeMonitor {
+    db: DBService,
+    git: GitService,
+    config: Arc<RwLock<Config>>,
+    watchers: Arc<Mutex<HashMap<Uuid, WatchedWorktree>>>,
+}
+
+impl ManualChangeMonitor {
+    pub fn new(db: DBService, git: GitService, config: Arc<RwLock<Config>>) -> Self {
+        Self {
+            db,
+            git,
+            config,
+            watchers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RECONCILE_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.reconcile().await {
+                    tracing::warn!("Manual change monitor reconcile failed: {}", e);
+                }
+            }
+        })
+    }
+
+    async fn reconcile(&self) -> Result<(), sqlx::Error> {
+        let active: HashMap<Uuid, PathBuf> = TaskAttempt::find_by_worktree_deleted(&self.db.pool)
+            .await?
+            .into_iter()
+            .map(|(id, path)| (id, PathBuf::from(path)))
+            .filter(|(_, path)| path.exists())
+            .collect();
+
+        self.watchers
+            .lock()
+            .await
+            .retain(|id, watched| active.get(id).is_some_and(|path| *path == watched.path));
+
+        for (attempt_id, path) in &active {
+            if self.watchers.lock().await.contains_key(attempt_id) {
+                continue;
+            }
+            match self.watch(*attempt_id, path.clone()).await {
+                Ok(watched) => {
+                    self.watchers.lock().await.insert(*attempt_id, watched);
+                }
+                Err(e) => {
+                    tracing::debug!("Failed to watch worktree {}: {}", path.display(), e);
+                }
+            }
+        }
+
+        // Recorded changes may have been committed, discarded or had their worktree removed since
+        // the last event; re-validate every record against the current status.
+        for attempt_id in AttemptManualChanges::list_attempt_ids(&self.db.pool).await? {
+            match active.get(&attempt_id) {
+                Some(path) => self.refresh(attempt_id, path, &[]).await,
+                None => AttemptManualChanges::clear(&self.db.pool, attempt_id).await?,
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn watch(
+        &self,
+        attempt_id: Uuid,
+        path: PathBuf,
+    ) -> Result<WatchedWorktree, filesystem_watcher::FilesystemWatcherError> {
+        let watch_root = path.clone();
+        let (debouncer, mut rx, canonical_root) = tokio::task::spawn_blocking(move || {
+            filesystem_watcher::async_watcher(
+                watch_root,
+                filesystem_watcher::WatcherOptions::default(),
+            )
+        })
+        .await
+        .map_err(|e| {
+            filesystem_watcher::FilesystemWatcherError::IoError(std::io::Error::other(e))
+        })??;
+
+        let monitor = self.clone();
+        let worktree = path.clone();
+        let listener = tokio::spawn(async move {
+            while let Some(result) = rx.next().await {
+                let mut touched = BTreeSet::new();
+                let mut collect = |result: notify_debouncer_full::DebounceEventResult| {
+                    if let Ok(events) = result {
+                        for event in events {
+                            for event_path in &event.paths {
+                                if let Some(rel) =
+                                    relative_path(event_path, &canonical_root, &worktree)
+                                {
+                                    touched.insert(rel);
+                                }
+                            }
+                        }
+                    }
+                };
+                collect(result);
+                tokio::time::sleep(EVENT_COALESCE_DELAY).await;
+                while let Ok(Some(result)) = rx.try_next() {
+                    collect(result);
+                }
+
+                if touched.is_empty() {
+                    continue;
+                }
+                let touched: Vec<String> = touched.into_iter().collect();
+                monitor.refresh(attempt_id, &worktree, &touched).await;
+            }
+        });
+
+        Ok(WatchedWorktree {
+            path,
+            _debouncer: debouncer,
+            listener,
+        })
+    }
+
+    /// Recompute the manual-changes record for an attempt. `touched` are worktree-relative paths
+    /// that just changed on disk; previously recorded paths are kept while they remain dirty.
+    async fn refresh(&self, attempt_id: Uuid, worktree: &Path, touched: &[String]) {
+        let pool = &self.db.pool;
+
+        match self.agent_recently_active(attempt_id).await {
+            Ok(true) => return,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load execution processes for attempt {}: {}",
+                    attempt_id,
+                    e
+                );
+                return;
+            }
+        }
+
+        let status = match self.git.get_worktree_status(worktree) {
+            Ok(status) => status,
+            Err(e) => {
+                tracing::debug!("Failed to read status of {}: {}", worktree.display(), e);
+                return;
+            }
+        };
+        let dirty: BTreeSet<String> = status.entries.into_iter().map(|e| e.path).collect();
+
+        let existing = match AttemptManualChanges::find_by_task_attempt_id(pool, attempt_id).await {
+            Ok(existing) => existing,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load manual changes for attempt {}: {}",
+                    attempt_id,
+                    e
+                );
+                return;
+            }
+        };
+
+        let changed: Vec<String> = existing
+            .iter()
+            .flat_map(|record| record.changed_paths.iter())
+            .chain(touched.iter())
+            .filter(|path| is_dirty(path, &dirty))
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        if changed.is_empty() {
+            if existing.is_some()
+                && let Err(e) = AttemptManualChanges::clear(pool, attempt_id).await
+            {
+                tracing::warn!(
+                    "Failed to clear manual changes for attempt {}: {}",
+                    attempt_id,
+                    e
+                );
+            }
+            return;
+        }
+
+        if existing
+            .as_ref()
+            .is_some_and(|record| record.changed_paths == changed)
+        {
+            return;
+        }
+
+        match AttemptManualChanges::upsert(pool, attempt_id, &changed).await {
+            Ok(true) => self.notify_detected(attempt_id, changed.len()).await,
+            Ok(false) => {}
+            Err(e) => tracing::warn!(
+                "Failed to record manual changes for attempt {}: {}",
+                attempt_id,
+                e
+            ),
+        }
+    }
+
+    /// True while a non dev-server process is running, or shortly after one finished.
+    async fn agent_recently_active(&self, attempt_id: Uuid) -> Result<bool, sqlx::Error> {
+        let processes =
+            ExecutionProcess::find_by_task_attempt_id(&self.db.pool, attempt_id, false).await?;
+        let now = Utc::now();
+
+        Ok(processes.iter().any(|process| {
+            if matches!(process.run_reason, ExecutionProcessRunReason::DevServer) {
+                return false;
+            }
+            match process.status {
+                ExecutionProcessStatus::Running => true,
+                _ => process
+                    .completed_at
+                    .is_some_and(|completed_at| now - completed_at < AGENT_SETTLE_GRACE),
+            }
+        }))
+    }
+
+    async fn notify_detected(&self, attempt_id: Uuid, file_count: usize) {
+        let pool = &self.db.pool;
+        let task_title = match TaskAttempt::find_by_id(pool, attempt_id).await {
+            Ok(Some(attempt)) => Task::find_by_id(pool, attempt.task_id)
+                .await
+                .ok()
+                .flatten()
+                .map(|task| task.title),
+            _ => None,
+        };
+        let Some(task_title) = task_title else {
+            return;
+        };
+
+        let notifications = self.config.read().await.notifications.clone();
+        let title = format!("Manual Changes: {}", task_title);
+        let message = format!(
+            "{} uncommitted file{} edited outside the coding agent",
+            file_count,
+            if file_count == 1 { "" } else { "s" }
+        );
+        NotificationService::notify(notifications, &title, &message).await;
+    }
+}
+
+/// `git status --porcelain` collapses untracked directories to `dir/`, so a file under one counts
+/// as dirty too.
+fn is_dirty(path: &str, dirty: &BTreeSet<String>) -> bool {
+    dirty.contains(path)
+        || dirty
+            .iter()
+            .any(|entry| entry.ends_with('/') && path.starts_with(entry.as_str()))
+}
+
+fn relative_path(path: &Path, canonical_root: &Path, root: &Path) -> Option<String> {
+    let rel = path
+        .strip_prefix(canonical_root)
+        .or_else(|_| path.strip_prefix(root))
+        .ok()?;
+    if rel.as_os_str().is_empty() || rel.starts_with(".git") {
+        return None;
+    }
+    Some(rel.to_string_lossy().replace('\\', "/"))
+}
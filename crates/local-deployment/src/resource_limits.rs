@@ -0,0 +1,267 @@
+//! CPU and memory limits for execution processes running on the host.
+//!
+//! Right after spawning, the process is placed in its own cgroup (Linux, cgroup v2) or job object
+//! (Windows), which everything it starts afterwards inherits. On Linux the server's cgroup must be
+//! delegated to it; the server moves itself into a leaf group below it the first time limits are
+//! applied. Sandboxed processes are limited by the container runtime instead, see
+//! [`executors::sandbox::ResourceLimits::runtime_args`].
+
+use executors::sandbox::ResourceLimits;
+use uuid::Uuid;
+
+/// Limit the process `pid` started for `execution_process_id`
+pub fn apply(execution_process_id: Uuid, pid: u32, limits: &ResourceLimits) -> std::io::Result<()> {
+    if limits.is_unlimited() {
+        return Ok(());
+    }
+    platform_apply(execution_process_id, pid, limits)
+}
+
+/// Clean up after the process exited. Job objects go away with their last process, so only the
+/// cgroup needs removing.
+pub fn release(execution_process_id: Uuid) {
+    #[cfg(target_os = "linux")]
+    cgroup::release(execution_process_id);
+    #[cfg(not(target_os = "linux"))]
+    let _ = execution_process_id;
+}
+
+/// The cgroup the process was placed in, if limits were applied to it
+#[cfg(target_os = "linux")]
+pub fn cgroup_path(execution_process_id: Uuid) -> Option<std::path::PathBuf> {
+    cgroup::group_path(execution_process_id)
+        .ok()
+        .filter(|group| group.exists())
+}
+
+#[cfg(target_os = "linux")]
+fn platform_apply(
+    execution_process_id: Uuid,
+    pid: u32,
+    limits: &ResourceLimits,
+) -> std::io::Result<()> {
+    cgroup::apply(execution_process_id, pid, limits)
+}
+
+#[cfg(windows)]
+fn platform_apply(_: Uuid, pid: u32, limits: &ResourceLimits) -> std::io::Result<()> {
+    job_object::apply(pid, limits)
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn platform_apply(_: Uuid, _: u32, _: &ResourceLimits) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "resource limits are only supported on Linux and Windows",
+    ))
+}
+
+#[cfg(any(target_os = "linux", test))]
+const CPU_PERIOD_US: u64 = 100_000;
+
+/// `cpu.max` value allowing `cpu_percent` of one core per period
+#[cfg(any(target_os = "linux", test))]
+fn cpu_max(cpu_percent: u32) -> String {
+    let quota = (u64::from(cpu_percent) * CPU_PERIOD_US / 100).max(1_000);
+    format!("{quota} {CPU_PERIOD_US}")
+}
+
+#[cfg(target_os = "linux")]
+mod cgroup {
+    use std::{
+        fs, io,
+        path::{Path, PathBuf},
+    };
+
+    use executors::sandbox::ResourceLimits;
+    use nix::errno::Errno;
+    use uuid::Uuid;
+
+    const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+    /// Leaf group the server moves itself into. cgroup v2 only lets a group hand controllers to
+    /// its children while it holds no processes itself.
+    const SERVER_LEAF: &str = "gybe-server";
+
+    /// The group execution process groups are created below: the server's own cgroup, or its
+    /// parent once the server has moved into [`SERVER_LEAF`]
+    fn base_cgroup() -> io::Result<PathBuf> {
+        let membership = fs::read_to_string("/proc/self/cgroup")?;
+        let relative = membership
+            .lines()
+            .find_map(|line| line.strip_prefix("0::"))
+            .ok_or_else(|| io::Error::other("cgroup v2 is not available"))?;
+        let own = Path::new(CGROUP_ROOT).join(relative.trim().trim_start_matches('/'));
+        match own.parent() {
+            Some(parent) if own.ends_with(SERVER_LEAF) => Ok(parent.to_path_buf()),
+            _ => Ok(own),
+        }
+    }
+
+    pub fn group_path(execution_process_id: Uuid) -> io::Result<PathBuf> {
+        Ok(base_cgroup()?.join(format!("gybe-exec-{execution_process_id}")))
+    }
+
+    /// Move everything in `base` into the server leaf and enable the cpu and memory controllers
+    /// for its children. Fails when `base` is not delegated to the server's user, e.g. when the
+    /// server runs in a terminal's session scope rather than a systemd service or scope with
+    /// `Delegate=yes`.
+    fn enable_controllers(base: &Path) -> io::Result<()> {
+        let subtree_control = base.join("cgroup.subtree_control");
+        let enabled = fs::read_to_string(&subtree_control)?;
+        if enabled.split_whitespace().any(|c| c == "cpu")
+            && enabled.split_whitespace().any(|c| c == "memory")
+        {
+            return Ok(());
+        }
+
+        let leaf = base.join(SERVER_LEAF);
+        fs::create_dir_all(&leaf)?;
+        for pid in fs::read_to_string(base.join("cgroup.procs"))?.lines() {
+            match fs::write(leaf.join("cgroup.procs"), pid) {
+                Ok(()) => {}
+                // Exited since the list was read
+                Err(e) if e.raw_os_error() == Some(Errno::ESRCH as i32) => {}
+                Err(e) => {
+                    return Err(io::Error::new(
+                        e.kind(),
+                        format!(
+                            "could not move process {pid} out of {}: {e}",
+                            base.display()
+                        ),
+                    ));
+                }
+            }
+        }
+        fs::write(&subtree_control, "+cpu +memory").map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!(
+                    "could not enable cpu and memory controllers in {}: {e}",
+                    base.display()
+                ),
+            )
+        })
+    }
+
+    pub fn apply(execution_process_id: Uuid, pid: u32, limits: &ResourceLimits) -> io::Result<()> {
+        let base = base_cgroup()?;
+        enable_controllers(&base)?;
+        let group = base.join(format!("gybe-exec-{execution_process_id}"));
+        fs::create_dir_all(&group)?;
+        if let Some(cpu_percent) = limits.cpu_percent {
+            fs::write(group.join("cpu.max"), super::cpu_max(cpu_percent))?;
+        }
+        if let Some(memory_mb) = limits.memory_mb {
+            fs::write(
+                group.join("memory.max"),
+                (u64::from(memory_mb) * 1024 * 1024).to_string(),
+            )?;
+        }
+        fs::write(group.join("cgroup.procs"), pid.to_string())
+    }
+
+    pub fn release(execution_process_id: Uuid) {
+        let Ok(group) = group_path(execution_process_id) else {
+            return;
+        };
+        if group.exists()
+            && let Err(e) = fs::remove_dir(&group)
+        {
+            tracing::debug!("Failed to remove cgroup {}: {}", group.display(), e);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod job_object {
+    use std::{ffi::c_void, io, mem};
+
+    use executors::sandbox::ResourceLimits;
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, HANDLE},
+        System::{
+            JobObjects::{
+                AssignProcessToJobObject, CreateJobObjectW, JOB_OBJECT_CPU_RATE_CONTROL_ENABLE,
+                JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP, JOB_OBJECT_LIMIT_JOB_MEMORY,
+                JOBOBJECT_CPU_RATE_CONTROL_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+                JobObjectCpuRateControlInformation, JobObjectExtendedLimitInformation,
+                SetInformationJobObject,
+            },
+            Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE},
+        },
+    };
+
+    pub fn apply(pid: u32, limits: &ResourceLimits) -> io::Result<()> {
+        let job = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+        if job.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let result = configure(job, limits).and_then(|()| assign(job, pid));
+        // The job object lives on as long as the assigned process does
+        unsafe { CloseHandle(job) };
+        result
+    }
+
+    fn configure(job: HANDLE, limits: &ResourceLimits) -> io::Result<()> {
+        if let Some(memory_mb) = limits.memory_mb {
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { mem::zeroed() };
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_JOB_MEMORY;
+            info.JobMemoryLimit = memory_mb as usize * 1024 * 1024;
+            set_information(
+                job,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const c_void,
+                mem::size_of_val(&info),
+            )?;
+        }
+        if let Some(cpu_percent) = limits.cpu_percent {
+            // Job CPU rates are hundredths of a percent of all processors together
+            let cores = std::thread::available_parallelism().map_or(1, |n| n.get()) as u32;
+            let mut info: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION = unsafe { mem::zeroed() };
+            info.ControlFlags =
+                JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP;
+            info.Anonymous.CpuRate = (cpu_percent.saturating_mul(100) / cores).clamp(1, 10_000);
+            set_information(
+                job,
+                JobObjectCpuRateControlInformation,
+                &info as *const _ as *const c_void,
+                mem::size_of_val(&info),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn set_information(job: HANDLE, class: i32, info: *const c_void, len: usize) -> io::Result<()> {
+        if unsafe { SetInformationJobObject(job, class, info, len as u32) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn assign(job: HANDLE, pid: u32) -> io::Result<()> {
+        let process = unsafe { OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid) };
+        if process.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let assigned = unsafe { AssignProcessToJobObject(job, process) };
+        unsafe { CloseHandle(process) };
+        if assigned == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_max_scales_percent_of_one_core() {
+        assert_eq!(cpu_max(100), "100000 100000");
+        assert_eq!(cpu_max(250), "250000 100000");
+        // The kernel rejects quotas below 1ms
+        assert_eq!(cpu_max(0), "1000 100000");
+    }
+}
@@ -0,0 +1,233 @@
+//! CPU and memory samples of execution processes running on the host.
+//!
+//! Samples go to the live listeners of the process's log stream as a `/resource_usage` patch and
+//! are not kept in its history. Processes that [`crate::resource_limits`] placed in a cgroup are
+//! read from the cgroup, others by adding up their process group in `/proc`. Other platforms and
+//! sandboxed processes are not sampled.
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use executors::{logs::utils::patch::ConversationPatch, sandbox::ResourceUsage};
+use tokio::sync::RwLock;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Running totals for a process and everything it started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct Counters {
+    cpu_time: Duration,
+    memory_bytes: u64,
+    processes: u32,
+}
+
+/// Processes that exit take their CPU time out of the total, so a shrinking total counts as idle
+fn cpu_percent(previous: Duration, current: Duration, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        return 0.0;
+    }
+    current.saturating_sub(previous).as_secs_f64() / elapsed.as_secs_f64() * 100.0
+}
+
+/// Sample the process until its log stream is closed or none of its processes are left
+pub fn spawn_sampler(
+    execution_process_id: Uuid,
+    pid: u32,
+    msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+) {
+    tokio::spawn(async move {
+        let Some(mut previous) = read_counters(execution_process_id, pid).await else {
+            return;
+        };
+        let mut previous_at = Instant::now();
+        let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+        interval.tick().await; // The first tick completes immediately
+        loop {
+            interval.tick().await;
+            let Some(counters) = read_counters(execution_process_id, pid).await else {
+                break;
+            };
+            let now = Instant::now();
+            let usage = ResourceUsage {
+                cpu_percent: cpu_percent(previous.cpu_time, counters.cpu_time, now - previous_at),
+                memory_bytes: counters.memory_bytes,
+                processes: counters.processes,
+            };
+            let Some(store) = msg_stores.read().await.get(&execution_process_id).cloned() else {
+                break;
+            };
+            store.push_transient(LogMsg::JsonPatch(ConversationPatch::set_resource_usage(
+                usage,
+            )));
+            previous = counters;
+            previous_at = now;
+        }
+    });
+}
+
+async fn read_counters(execution_process_id: Uuid, pid: u32) -> Option<Counters> {
+    tokio::task::spawn_blocking(move || platform_counters(execution_process_id, pid))
+        .await
+        .ok()
+        .flatten()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_counters(execution_process_id: Uuid, pid: u32) -> Option<Counters> {
+    match crate::resource_limits::cgroup_path(execution_process_id) {
+        Some(group) => linux::cgroup_counters(&group),
+        None => linux::process_group_counters(pid),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn platform_counters(_: Uuid, _: u32) -> Option<Counters> {
+    None
+}
+
+/// Process group and CPU time in clock ticks from `/proc/<pid>/stat`
+#[cfg(any(target_os = "linux", test))]
+fn parse_stat(stat: &str) -> Option<(u32, u64)> {
+    // The command name may contain spaces and parentheses, so fields are counted from its end.
+    // What follows starts at field 3 (state); pgrp is field 5, utime and stime 14 and 15.
+    let fields: Vec<&str> = stat
+        .get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .collect();
+    let pgrp = fields.get(2)?.parse().ok()?;
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some((pgrp, utime + stime))
+}
+
+/// Resident memory in bytes from `/proc/<pid>/status`
+#[cfg(any(target_os = "linux", test))]
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let kb: u64 = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+/// Total CPU time in microseconds from a cgroup's `cpu.stat`
+#[cfg(any(target_os = "linux", test))]
+fn parse_cpu_usage_usec(cpu_stat: &str) -> Option<u64> {
+    cpu_stat
+        .lines()
+        .find_map(|line| line.strip_prefix("usage_usec "))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{fs, path::Path, time::Duration};
+
+    use super::{Counters, parse_cpu_usage_usec, parse_stat, parse_vm_rss};
+
+    /// `/proc` reports CPU time in `USER_HZ` ticks, which is 100 on all common architectures
+    const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+    pub fn cgroup_counters(group: &Path) -> Option<Counters> {
+        let processes = fs::read_to_string(group.join("cgroup.procs"))
+            .ok()?
+            .lines()
+            .count() as u32;
+        if processes == 0 {
+            return None;
+        }
+        let cpu_usec = parse_cpu_usage_usec(&fs::read_to_string(group.join("cpu.stat")).ok()?)?;
+        let memory_bytes = fs::read_to_string(group.join("memory.current"))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+        Some(Counters {
+            cpu_time: Duration::from_micros(cpu_usec),
+            memory_bytes,
+            processes,
+        })
+    }
+
+    pub fn process_group_counters(pgid: u32) -> Option<Counters> {
+        let mut ticks = 0;
+        let mut memory_bytes = 0;
+        let mut processes = 0;
+        for entry in fs::read_dir("/proc").ok()?.flatten() {
+            if !entry
+                .file_name()
+                .to_string_lossy()
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+            {
+                continue;
+            }
+            // Processes can exit between listing /proc and reading their files
+            let Ok(stat) = fs::read_to_string(entry.path().join("stat")) else {
+                continue;
+            };
+            let Some((pgrp, cpu_ticks)) = parse_stat(&stat) else {
+                continue;
+            };
+            if pgrp != pgid {
+                continue;
+            }
+            ticks += cpu_ticks;
+            processes += 1;
+            if let Ok(status) = fs::read_to_string(entry.path().join("status")) {
+                memory_bytes += parse_vm_rss(&status).unwrap_or(0);
+            }
+        }
+        if processes == 0 {
+            return None;
+        }
+        Some(Counters {
+            cpu_time: Duration::from_millis(ticks * 1000 / CLOCK_TICKS_PER_SEC),
+            memory_bytes,
+            processes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_percent_is_relative_to_one_core() {
+        let second = Duration::from_secs(1);
+        assert_eq!(cpu_percent(second, second * 3, second), 200.0);
+        assert_eq!(cpu_percent(second * 3, second, second), 0.0);
+        assert_eq!(cpu_percent(second, second * 2, Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn stat_fields_are_counted_after_the_command_name() {
+        let stat = "4242 (npm run (build)) S 4200 4242 4242 0 -1 4194560 1234 0 0 0 \
+                    350 25 0 0 20 0 11 0 987654 1073741824 51200 18446744073709551615";
+        assert_eq!(parse_stat(stat), Some((4242, 375)));
+        assert_eq!(parse_stat("4242 (truncated"), None);
+    }
+
+    #[test]
+    fn memory_and_cgroup_cpu_are_parsed() {
+        let status = "Name:\tnode\nVmPeak:\t  900000 kB\nVmRSS:\t  204800 kB\nThreads:\t11\n";
+        assert_eq!(parse_vm_rss(status), Some(204800 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tkthreadd\n"), None);
+
+        let cpu_stat = "usage_usec 1500000\nuser_usec 1000000\nsystem_usec 500000\n";
+        assert_eq!(parse_cpu_usage_usec(cpu_stat), Some(1_500_000));
+    }
+}
@@ -0,0 +1,139 @@
+//! Creates tasks from templates when their schedules fire, and starts an attempt for them when
+//! the schedule names an executor. Runs missed while the app was closed are caught up once, not
+//! once per missed occurrence.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    project::Project,
+    task::{CreateTask, Task},
+    task_attempt::{CreateTaskAttempt, TaskAttempt},
+    task_schedule::TaskSchedule,
+    task_template::TaskTemplate,
+};
+use executors::profile::ExecutorProfileId;
+use services::services::{
+    container::{ContainerError, ContainerService},
+    cron_schedule::CronSchedule,
+    webhooks::WebhookService,
+};
+use uuid::Uuid;
+
+use crate::container::LocalContainerService;
+
+/// Cron expressions have minute resolution
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+impl LocalContainerService {
+    pub fn spawn_task_scheduler(&self) {
+        let container = self.clone();
+        let mut check_interval = tokio::time::interval(CHECK_INTERVAL);
+        tokio::spawn(async move {
+            loop {
+                check_interval.tick().await;
+                let now = Utc::now();
+                let due = match TaskSchedule::find_due(&container.db().pool, now).await {
+                    Ok(due) => due,
+                    Err(e) => {
+                        tracing::error!("Failed to load due task schedules: {}", e);
+                        continue;
+                    }
+                };
+                for schedule in due {
+                    if let Err(e) = container.run_task_schedule(&schedule, now).await {
+                        tracing::warn!("Failed to run task schedule {}: {}", schedule.id, e);
+                    }
+                }
+            }
+        });
+    }
+
+    async fn run_task_schedule(
+        &self,
+        schedule: &TaskSchedule,
+        now: DateTime<Utc>,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let next_run_at = match CronSchedule::parse(&schedule.cron_expression) {
+            Ok(cron) => cron.next_after(now),
+            Err(e) => {
+                tracing::warn!(
+                    "Task schedule {} has an invalid cron expression: {}",
+                    schedule.id,
+                    e
+                );
+                None
+            }
+        };
+        let Some(template) = TaskTemplate::find_by_id(pool, schedule.template_id).await? else {
+            return Ok(());
+        };
+
+        let task = Task::create(
+            pool,
+            &CreateTask::from_title_description(
+                schedule.project_id,
+                template.title,
+                template.description,
+            ),
+            Uuid::new_v4(),
+        )
+        .await?;
+        WebhookService::task_created(pool, &task);
+        // Recorded before the attempt starts, so a failing start cannot create a task every minute
+        TaskSchedule::record_run(pool, schedule.id, now, task.id, next_run_at).await?;
+        tracing::info!(
+            "Task schedule {} created task {} in project {}",
+            schedule.id,
+            task.id,
+            schedule.project_id
+        );
+
+        if let Some(profile) = &schedule.executor_profile_id {
+            self.start_scheduled_attempt(schedule, &task, profile.0.clone())
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn start_scheduled_attempt(
+        &self,
+        schedule: &TaskSchedule,
+        task: &Task,
+        executor_profile_id: ExecutorProfileId,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let base_branch = match schedule
+            .base_branch
+            .as_deref()
+            .map(str::trim)
+            .filter(|branch| !branch.is_empty())
+        {
+            Some(branch) => branch.to_string(),
+            None => {
+                let project = Project::find_by_id(pool, task.project_id)
+                    .await?
+                    .ok_or(sqlx::Error::RowNotFound)?;
+                self.git().get_head_info(&project.git_repo_path)?.branch
+            }
+        };
+
+        let attempt_id = Uuid::new_v4();
+        let task_attempt = TaskAttempt::create(
+            pool,
+            &CreateTaskAttempt {
+                executor: executor_profile_id.executor,
+                base_branch,
+                branch: self.git_branch_from_task_attempt(&attempt_id, &task.title),
+                repositories: None,
+            },
+            attempt_id,
+            task.id,
+        )
+        .await?;
+        self.start_attempt(&task_attempt, executor_profile_id)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+//! Worktrees created ahead of time, with the project's setup script already run, so a new attempt
+//! can claim one instead of waiting for `git worktree add` and a full dependency install.
+//!
+//! Pool worktrees check out a placeholder branch off the main checkout's current branch. They are
+//! only handed to attempts that target that branch at the same commit; once the branch moves on,
+//! the pool is rebuilt on the next refill.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use db::models::{
+    project::{Project, ProjectWorktreeSettings},
+    task_attempt::TaskAttempt,
+    warm_worktree::WarmWorktree,
+};
+use services::services::{
+    container::{ContainerError, ContainerService},
+    worktree_manager::WorktreeManager,
+};
+use tokio::{process::Command, sync::Mutex};
+use utils::shell::get_shell_command;
+use uuid::Uuid;
+
+use crate::container::LocalContainerService;
+
+/// Placeholder branches of pool worktrees; renamed to the attempt branch when claimed
+const WARM_BRANCH_PREFIX: &str = "gybe/warm";
+/// How often every project's pool is checked against its settings and base branch
+const REFILL_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Refills run one at a time so two of them cannot overshoot the pool size
+static REFILL_LOCK: Mutex<()> = Mutex::const_new(());
+
+impl LocalContainerService {
+    /// Hand a ready pool worktree to the attempt, moving it to `worktree_path`. Returns where the
+    /// worktree ended up, or None when no worktree matches the attempt's target branch.
+    pub(crate) async fn claim_warm_worktree(
+        &self,
+        project: &Project,
+        task_attempt: &TaskAttempt,
+        worktree_path: &Path,
+    ) -> Result<Option<PathBuf>, ContainerError> {
+        let pool = &self.db().pool;
+        // The setup script has to run inside the sandbox, not on the host
+        if Project::find_sandbox_settings(pool, project.id)
+            .await?
+            .enabled
+        {
+            return Ok(None);
+        }
+        let Ok(base_commit) = self
+            .git()
+            .get_branch_oid(&project.git_repo_path, &task_attempt.target_branch)
+        else {
+            return Ok(None);
+        };
+        let Some(warm) = WarmWorktree::claim(
+            pool,
+            project.id,
+            &task_attempt.target_branch,
+            &base_commit,
+            task_attempt.id,
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        match self.git().reassign_worktree(
+            &project.git_repo_path,
+            Path::new(&warm.worktree_path),
+            &warm.branch,
+            &task_attempt.branch,
+            worktree_path,
+        ) {
+            Ok(path) => Ok(Some(path)),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to claim warm worktree {}: {}",
+                    warm.worktree_path,
+                    e
+                );
+                self.discard_warm_worktree(project, &warm).await;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Bring the project's pool to its configured size, replacing worktrees built from an older
+    /// commit of the base branch
+    pub(crate) async fn fill_worktree_pool(&self, project_id: Uuid) -> Result<(), ContainerError> {
+        let _guard = REFILL_LOCK.lock().await;
+        let pool = &self.db().pool;
+        let Some(project) = Project::find_by_id(pool, project_id).await? else {
+            return Ok(());
+        };
+        let settings = Project::find_worktree_settings(pool, project.id).await?;
+        let sandboxed = Project::find_sandbox_settings(pool, project.id)
+            .await?
+            .enabled;
+        let target_size = if sandboxed {
+            0
+        } else {
+            settings.warm_pool_size as usize
+        };
+
+        let unclaimed = WarmWorktree::find_unclaimed_by_project(pool, project.id).await?;
+        if target_size == 0 && unclaimed.is_empty() {
+            return Ok(());
+        }
+        let head = self.git().get_head_info(&project.git_repo_path)?;
+
+        let mut available = 0;
+        for warm in unclaimed {
+            let current = warm.base_branch == head.branch && warm.base_commit == head.oid;
+            if current && available < target_size {
+                available += 1;
+            } else {
+                self.discard_warm_worktree(&project, &warm).await;
+            }
+        }
+
+        while available < target_size {
+            self.prepare_warm_worktree(&project, &settings, &head.branch, &head.oid)
+                .await?;
+            available += 1;
+        }
+        Ok(())
+    }
+
+    async fn prepare_warm_worktree(
+        &self,
+        project: &Project,
+        settings: &ProjectWorktreeSettings,
+        base_branch: &str,
+        base_commit: &str,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let suffix = Uuid::new_v4().simple().to_string()[..8].to_string();
+        let branch = format!("{WARM_BRANCH_PREFIX}/{suffix}");
+        let worktree_path = WorktreeManager::get_worktree_base_dir().join(format!("warm-{suffix}"));
+        let warm = WarmWorktree::create(
+            pool,
+            project.id,
+            &worktree_path.to_string_lossy(),
+            &branch,
+            base_branch,
+            base_commit,
+        )
+        .await?;
+
+        let prepared = async {
+            WorktreeManager::create_worktree(
+                &project.git_repo_path,
+                &branch,
+                &worktree_path,
+                base_branch,
+                true, // create new branch
+                settings.init_submodules,
+            )
+            .await?;
+            self.populate_worktree(project, settings, &worktree_path)
+                .await;
+            if let Some(script) = project
+                .setup_script
+                .as_deref()
+                .filter(|script| !script.trim().is_empty())
+            {
+                run_setup_script(script, &worktree_path).await?;
+            }
+            Ok::<(), ContainerError>(())
+        }
+        .await;
+
+        match prepared {
+            Ok(()) => {
+                WarmWorktree::mark_ready(pool, warm.id).await?;
+                tracing::info!(
+                    "Prepared warm worktree {} for project {}",
+                    worktree_path.display(),
+                    project.id
+                );
+                Ok(())
+            }
+            Err(e) => {
+                self.discard_warm_worktree(project, &warm).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn discard_warm_worktree(&self, project: &Project, warm: &WarmWorktree) {
+        if let Err(e) = WorktreeManager::cleanup_worktree(
+            Path::new(&warm.worktree_path),
+            Some(&project.git_repo_path),
+        )
+        .await
+        {
+            tracing::warn!(
+                "Failed to remove warm worktree {}: {}",
+                warm.worktree_path,
+                e
+            );
+        }
+        if let Err(e) = self
+            .git()
+            .delete_local_branch(&project.git_repo_path, &warm.branch)
+        {
+            tracing::debug!("Failed to delete warm branch {}: {}", warm.branch, e);
+        }
+        if let Err(e) = WarmWorktree::delete(&self.db().pool, warm.id).await {
+            tracing::warn!("Failed to forget warm worktree {}: {}", warm.id, e);
+        }
+    }
+
+    /// Drop pool worktrees left half-prepared by a previous run, then keep every project's pool
+    /// filled
+    pub fn spawn_worktree_pool(&self) {
+        let container = self.clone();
+        let mut refill_interval = tokio::time::interval(REFILL_INTERVAL);
+        tokio::spawn(async move {
+            let pool = &container.db().pool;
+            match WarmWorktree::find_unfinished(pool).await {
+                Ok(unfinished) => {
+                    for warm in unfinished {
+                        if let Ok(Some(project)) = Project::find_by_id(pool, warm.project_id).await
+                        {
+                            container.discard_warm_worktree(&project, &warm).await;
+                        }
+                    }
+                }
+                Err(e) => tracing::error!("Failed to load unfinished warm worktrees: {}", e),
+            }
+
+            loop {
+                refill_interval.tick().await;
+                let projects = match Project::find_all(pool).await {
+                    Ok(projects) => projects,
+                    Err(e) => {
+                        tracing::error!("Failed to load projects for the worktree pool: {}", e);
+                        continue;
+                    }
+                };
+                for project in projects {
+                    if let Err(e) = container.fill_worktree_pool(project.id).await {
+                        tracing::warn!(
+                            "Failed to refill the worktree pool of project {}: {}",
+                            project.id,
+                            e
+                        );
+                    }
+                }
+            }
+        });
+    }
+}
+
+async fn run_setup_script(script: &str, worktree_path: &Path) -> Result<(), ContainerError> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let output = Command::new(shell_cmd)
+        .arg(shell_arg)
+        .arg(script)
+        .current_dir(worktree_path)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(ContainerError::Other(anyhow!(
+            "Setup script failed in warm worktree ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
@@ -41,6 +41,14 @@ pub struct ActivityFeedItemCta {
     pub href: String,
 }
 
+#[derive(Debug, Clone, Serialize, TS, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityFeedItemAction {
+    pub label: String,
+    /// Called with POST to perform the action
+    pub endpoint: String,
+}
+
 #[derive(Debug, Clone, Serialize, TS, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ActivityFeedItem {
@@ -48,8 +56,11 @@ pub struct ActivityFeedItem {
     pub headline: String,
     pub summary: Option<String>,
     pub cta: Option<ActivityFeedItemCta>,
+    pub action: Option<ActivityFeedItemAction>,
     pub urgency_score: u32,
     pub action_required: bool,
+    /// Whether the current user acknowledged the event
+    pub read: bool,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
 }
@@ -164,8 +175,13 @@ pub fn map_event_to_item(event: &ActivityEvent) -> ActivityFeedItem {
             label: cta.label.clone(),
             href: cta.href.clone(),
         }),
+        action: event.action.as_ref().map(|action| ActivityFeedItemAction {
+            label: action.label.clone(),
+            endpoint: action.endpoint.clone(),
+        }),
         urgency_score: event.urgency_score as u32,
         action_required: event.urgency_score >= ACTION_REQUIRED_THRESHOLD,
+        read: event.read,
         created_at: event.created_at,
     }
 }
@@ -197,8 +213,10 @@ mod tests {
             body: Some("A detailed update".to_string()),
             actors: vec![],
             cta: None,
+            action: None,
             urgency_score: 75,
             created_at: Utc::now() - Duration::seconds(ts_offset_secs),
+            read: false,
         }
     }
 
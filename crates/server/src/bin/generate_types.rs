@@ -20,6 +20,32 @@ fn generate_types_content() -> String {
         db::models::project_repository::ProjectRepository::decl(),
         db::models::project_repository::CreateProjectRepository::decl(),
         db::models::project_repository::UpdateProjectRepository::decl(),
+        server::routes::projects::ProjectWithUnreadActivity::decl(),
+        server::routes::projects::AttemptDiffStats::decl(),
+        server::routes::projects::VerifyScriptBody::decl(),
+        db::models::project::ProjectWatcherSettings::decl(),
+        db::models::project::DependencyShareMode::decl(),
+        db::models::project::ProjectWorktreeSettings::decl(),
+        db::models::project::ProjectGitMaintenanceSettings::decl(),
+        db::models::project::FinalizationRule::decl(),
+        db::models::project::ProjectFinalizationSettings::decl(),
+        db::models::project::ProjectPrSettings::decl(),
+        executors::sandbox::ContainerRuntime::decl(),
+        db::models::project::ProjectSandboxSettings::decl(),
+        executors::sandbox::ResourceLimits::decl(),
+        executors::sandbox::ResourceUsage::decl(),
+        services::services::project_stats::ProjectActivityKind::decl(),
+        services::services::project_stats::ActivityStats::decl(),
+        services::services::project_stats::ProjectUsageStats::decl(),
+        server::routes::projects::release_notes::ReleaseNotesRange::decl(),
+        server::routes::projects::release_notes::PolishReleaseNotesRequest::decl(),
+        server::routes::projects::release_notes::PolishedReleaseNotes::decl(),
+        services::services::release_notes::ReleaseNotes::decl(),
+        db::models::task::CompletedTask::decl(),
+        server::routes::projects::stale_branches::DeleteStaleBranchesRequest::decl(),
+        services::services::branch_cleanup::StaleBranchReason::decl(),
+        services::services::branch_cleanup::StaleBranch::decl(),
+        services::services::branch_cleanup::StaleBranchDeletion::decl(),
         executors::actions::ExecutorAction::decl(),
         executors::mcp_config::McpConfig::decl(),
         executors::actions::ExecutorActionType::decl(),
@@ -31,12 +57,63 @@ fn generate_types_content() -> String {
         db::models::task_template::TaskTemplate::decl(),
         db::models::task_template::CreateTaskTemplate::decl(),
         db::models::task_template::UpdateTaskTemplate::decl(),
+        db::models::prompt_snippet::PromptSnippet::decl(),
+        db::models::prompt_snippet::CreatePromptSnippet::decl(),
+        db::models::prompt_snippet::UpdatePromptSnippet::decl(),
+        services::services::prompt_snippets::ExpandedPrompt::decl(),
+        server::routes::prompt_snippets::ExpandPromptRequest::decl(),
+        db::models::task_schedule::TaskSchedule::decl(),
+        db::models::task_schedule::CreateTaskSchedule::decl(),
+        db::models::task_schedule::UpdateTaskSchedule::decl(),
         db::models::task::TaskStatus::decl(),
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::task::SetTaskDueDate::decl(),
+        db::models::task::OverdueTask::decl(),
+        db::models::task::TaskSortField::decl(),
+        db::pagination::SortDirection::decl(),
+        db::models::label::Label::decl(),
+        db::models::label::CreateLabel::decl(),
+        db::models::label::UpdateLabel::decl(),
+        db::models::label::SetTaskLabels::decl(),
+        services::services::task_import::TaskImportFormat::decl(),
+        services::services::task_import::ImportTasksRequest::decl(),
+        services::services::task_import::TaskImportRowError::decl(),
+        services::services::task_import::TaskImportResult::decl(),
+        services::services::project_bundle::ProjectBundle::decl(),
+        services::services::project_bundle::BundledRepository::decl(),
+        services::services::project_bundle::BundledLabel::decl(),
+        services::services::project_bundle::BundledTask::decl(),
+        services::services::project_bundle::BundledAttempt::decl(),
+        services::services::project_bundle::BundledAttemptRepository::decl(),
+        services::services::project_bundle::BundledImage::decl(),
+        services::services::project_bundle::ProjectBundleImportSummary::decl(),
+        services::services::project_bundle::ImportProjectBundle::decl(),
+        services::services::project_bundle::ImportedProjectBundle::decl(),
+        db::models::issue_sync::IssueTracker::decl(),
+        db::models::issue_sync::IssueSync::decl(),
+        db::models::issue_sync::LinkIssueSync::decl(),
+        db::models::issue_sync::IssueState::decl(),
+        db::models::issue_sync::TaskIssue::decl(),
+        services::services::issue_sync::IssueSyncSummary::decl(),
+        db::models::jira_issue::JiraSync::decl(),
+        db::models::jira_issue::UpsertJiraSync::decl(),
+        db::models::jira_issue::TaskJiraIssue::decl(),
+        services::services::jira_sync::JiraSyncSummary::decl(),
+        db::models::webhook::WebhookEvent::decl(),
+        db::models::webhook::Webhook::decl(),
+        db::models::webhook::CreateWebhook::decl(),
+        db::models::webhook::UpdateWebhook::decl(),
+        db::models::user::UserRole::decl(),
+        db::models::user::User::decl(),
+        db::models::user::CreateUser::decl(),
+        db::models::user::UpdateUser::decl(),
+        server::routes::users::LoginRequest::decl(),
+        server::routes::users::LoginResponse::decl(),
+        db::models::audit_log::AuditLogEntry::decl(),
         db::models::image::Image::decl(),
         db::models::image::CreateImage::decl(),
         utils::response::ApiResponse::<()>::decl(),
@@ -45,17 +122,40 @@ fn generate_types_content() -> String {
         server::routes::config::McpServerQuery::decl(),
         server::routes::config::UpdateMcpServersBody::decl(),
         server::routes::config::GetMcpServerResponse::decl(),
+        server::routes::config::McpServerEntry::decl(),
+        server::routes::config::McpServerList::decl(),
+        server::routes::config::UpsertMcpServerBody::decl(),
         server::routes::task_attempts::CreateFollowUpAttempt::decl(),
+        server::routes::task_attempts::HandoffTaskAttemptRequest::decl(),
         server::routes::task_attempts::CreateTaskAttemptRepositoryBody::decl(),
         services::services::drafts::DraftResponse::decl(),
         services::services::drafts::UpdateFollowUpDraftRequest::decl(),
         services::services::drafts::UpdateRetryFollowUpDraftRequest::decl(),
+        services::services::review_comments::ReviewThreadComment::decl(),
+        services::services::review_comments::ReviewThread::decl(),
+        server::routes::task_attempts::review_comments::ImportReviewCommentsRequest::decl(),
+        server::routes::task_attempts::review_comments::ImportReviewCommentsResponse::decl(),
+        db::models::diff_comment::DiffCommentSide::decl(),
+        db::models::diff_comment::DiffComment::decl(),
+        db::models::diff_comment::CreateDiffComment::decl(),
+        db::models::diff_comment::UpdateDiffComment::decl(),
+        server::routes::task_attempts::diff_comments::SendDiffCommentsRequest::decl(),
+        server::routes::task_attempts::diff_comments::SendDiffCommentsResponse::decl(),
+        server::routes::task_attempts::diff_revert::RevertDiffChangeRequest::decl(),
+        server::routes::task_attempts::diff_summary::DiffFileSummary::decl(),
+        server::routes::task_attempts::diff_summary::DiffSummary::decl(),
         server::routes::task_attempts::ChangeTargetBranchRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        db::models::task_dependency::TaskDependency::decl(),
+        server::routes::tasks::dependencies::CreateTaskDependency::decl(),
+        server::routes::tasks::dependencies::TaskDependencies::decl(),
+        server::routes::tasks::dependencies::TaskDependencyGraph::decl(),
         server::routes::task_attempts::CreateGitHubPrRequest::decl(),
+        server::routes::task_attempts::gitlab::CreateGitLabMrRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         services::services::github_service::GitHubServiceError::decl(),
+        services::services::gitlab_service::GitLabServiceError::decl(),
         server::routes::usage::CodexUsageSnapshot::decl(),
         server::routes::usage::CodexUsageRateLimits::decl(),
         server::routes::usage::CodexUsageWindow::decl(),
@@ -64,7 +164,20 @@ fn generate_types_content() -> String {
         server::routes::usage::ClaudeCodeUsageSnapshot::decl(),
         server::routes::usage::ClaudeCodeSessionInfo::decl(),
         server::routes::usage::ClaudeCodeTokenUsage::decl(),
+        db::models::usage_sample::UsageAgent::decl(),
+        db::models::usage_sample::UsageSample::decl(),
+        server::routes::usage::UsageWindow::decl(),
+        server::routes::usage::AgentUsageSummary::decl(),
+        server::routes::usage::UsageUpdate::decl(),
+        services::services::usage_attribution::TaskTokenUsage::decl(),
+        services::services::usage_attribution::ProjectTokenUsage::decl(),
+        services::services::usage_attribution::UsageAttribution::decl(),
+        db::models::local_analytics_event::DailyRuns::decl(),
+        db::models::local_analytics_event::ExecutorUsage::decl(),
+        db::models::local_analytics_event::EventCount::decl(),
+        server::routes::analytics::LocalAnalyticsSummary::decl(),
         server::activity_feed::ActivityFeedItemCta::decl(),
+        server::activity_feed::ActivityFeedItemAction::decl(),
         server::activity_feed::ActivityFeedItem::decl(),
         server::activity_feed::ActivityFeedResponse::decl(),
         services::services::config::Config::decl(),
@@ -73,6 +186,25 @@ fn generate_types_content() -> String {
         services::services::config::EditorConfig::decl(),
         services::services::config::EditorType::decl(),
         services::services::config::GitHubConfig::decl(),
+        services::services::config::GitLabConfig::decl(),
+        services::services::config::GiteaConfig::decl(),
+        services::services::config::JiraConfig::decl(),
+        services::services::config::RateLimitConfig::decl(),
+        services::services::config::ModelPrice::decl(),
+        services::services::config::UsageBudget::decl(),
+        services::services::config::DatabaseBackupConfig::decl(),
+        services::services::db_backup::DatabaseBackup::decl(),
+        services::services::config::LogArchiveConfig::decl(),
+        services::services::config::LogSnapshotConfig::decl(),
+        services::services::config::AuditLogConfig::decl(),
+        services::services::config::DatabaseMaintenanceConfig::decl(),
+        db::maintenance::DatabaseMaintenance::decl(),
+        db::stats::DatabaseStats::decl(),
+        db::stats::TableStats::decl(),
+        services::services::config::SlackConfig::decl(),
+        services::services::config::DiscordConfig::decl(),
+        services::services::config::CommitSigningFormat::decl(),
+        services::services::config::CommitSigningConfig::decl(),
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ActivityFeedConfig::decl(),
@@ -82,16 +214,31 @@ fn generate_types_content() -> String {
         server::routes::auth::CheckTokenResponse::decl(),
         services::services::git::GitBranch::decl(),
         services::services::git::GitRemote::decl(),
+        services::services::branch_suggestions::BranchSuggestion::decl(),
+        services::services::branch_suggestions::BranchSuggestionReason::decl(),
         utils::diff::Diff::decl(),
+        utils::diff::IntralineRange::decl(),
+        utils::diff::IntralineChange::decl(),
+        utils::diff::HunkRange::decl(),
+        utils::diff::ImageInfo::decl(),
+        utils::diff::BinaryFile::decl(),
+        utils::diff::BinaryChange::decl(),
+        utils::diff::MovedBlock::decl(),
+        utils::diff::DiffSide::decl(),
         utils::diff::DiffChangeKind::decl(),
+        utils::diff::LfsPointer::decl(),
+        utils::diff::LfsChange::decl(),
         services::services::github_service::RepositoryInfo::decl(),
+        services::services::github_service::BranchProtection::decl(),
         executors::command::CommandBuilder::decl(),
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
         executors::executors::BaseAgentCapability::decl(),
         executors::executors::claude::ClaudeCode::decl(),
+        executors::tool_policy::ToolPolicy::decl(),
         executors::executors::gemini::Gemini::decl(),
         executors::executors::gemini::GeminiModel::decl(),
+        executors::executors::gemini::GeminiApprovalMode::decl(),
         executors::executors::amp::Amp::decl(),
         executors::executors::codex::Codex::decl(),
         executors::executors::codex::SandboxMode::decl(),
@@ -107,22 +254,54 @@ fn generate_types_content() -> String {
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
+        server::routes::task_attempts::MergeTaskAttemptRequest::decl(),
+        server::routes::task_attempts::AttemptStack::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::ReplaceProcessRequest::decl(),
         server::routes::task_attempts::CommitInfo::decl(),
         server::routes::task_attempts::BranchStatus::decl(),
+        server::routes::task_attempts::manual_changes::CommitManualChangesRequest::decl(),
+        server::routes::task_attempts::manual_changes::CommitManualChangesResponse::decl(),
+        server::routes::task_attempts::conflicts::ConflictState::decl(),
+        server::routes::task_attempts::conflicts::FileConflictResolution::decl(),
+        server::routes::task_attempts::conflicts::ResolveConflictsRequest::decl(),
+        server::routes::task_attempts::snapshots::RestoreSnapshotRequest::decl(),
         services::services::git::ConflictOp::decl(),
+        services::services::git::ConflictHunk::decl(),
+        services::services::git::ConflictedFile::decl(),
+        services::services::git::MergePreview::decl(),
+        services::services::git::BranchPushOutcome::decl(),
+        services::services::git::WorktreeSnapshot::decl(),
+        services::services::git::ConflictFileVersions::decl(),
+        services::services::git::ConflictResolution::decl(),
         db::models::task_attempt::TaskAttempt::decl(),
+        db::models::task_attempt::TaskAttemptSortField::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
+        db::models::dev_server_port::DevServerPort::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_process_replay::ExecutionProcessReplay::decl(),
+        db::models::execution_process_token_usage::ModelTokenUsage::decl(),
+        services::services::cost::ModelCost::decl(),
+        services::services::cost::CostEstimate::decl(),
+        server::routes::costs::ExecutionProcessCost::decl(),
+        server::routes::costs::TaskAttemptCost::decl(),
+        db::models::budget_alert::BudgetLevel::decl(),
+        services::services::budgets::BudgetStatus::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
         db::models::merge::MergeStatus::decl(),
+        db::models::merge::MergeStrategy::decl(),
         db::models::merge::PullRequestInfo::decl(),
+        db::models::pr_check_status::PrReviewState::decl(),
+        db::models::pr_check_status::PrCheckState::decl(),
+        db::models::pr_check_status::PrCheckRun::decl(),
+        db::models::pr_check_status::PrCheckStatus::decl(),
         db::models::draft::Draft::decl(),
         db::models::draft::DraftType::decl(),
+        db::models::attempt_manual_changes::AttemptManualChanges::decl(),
+        db::models::attempt_verification::AttemptVerification::decl(),
         executors::logs::CommandExitStatus::decl(),
         executors::logs::CommandRunResult::decl(),
         executors::logs::NormalizedEntry::decl(),
@@ -137,6 +316,7 @@ fn generate_types_content() -> String {
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::CreateApprovalRequest::decl(),
         utils::approvals::ApprovalResponse::decl(),
+        utils::approvals::ApprovalPendingInfo::decl(),
         serde_json::Value::decl(),
     ];
 
@@ -1,5 +1,9 @@
 use rmcp::{ServiceExt, transport::stdio};
-use server::mcp::task_server::TaskServer;
+use server::{
+    mcp::{auth, http, task_server::TaskServer},
+    middleware::normalize_base_path,
+};
+use services::services::users::AGENT_TOKEN_ENV;
 use tracing_subscriber::{EnvFilter, prelude::*};
 use utils::{port_file::read_port_file, sentry::sentry_layer};
 
@@ -59,18 +63,36 @@ fn main() -> anyhow::Result<()> {
                     }
                 };
 
-                let url = format!("http://{}:{}", host, port);
+                let base_path = std::env::var("BASE_PATH")
+                    .map(|path| normalize_base_path(&path))
+                    .unwrap_or_default();
+                let url = format!("http://{}:{}{}", host, port, base_path);
                 tracing::info!("[MCP] Using backend URL: {}", url);
                 url
             };
 
-            let service = TaskServer::new(&base_url)
-                .serve(stdio())
-                .await
-                .inspect_err(|e| {
-                    tracing::error!("serving error: {:?}", e);
-                    sentry::capture_error(e);
-                })?;
+            let mut server = TaskServer::new(&base_url);
+            if let Ok(token) = std::env::var(AGENT_TOKEN_ENV) {
+                tracing::info!("[MCP] Authenticating with {}", AGENT_TOKEN_ENV);
+                server = server.with_session_token(&token);
+            }
+
+            // Over HTTP the tools are reachable by anyone who can connect, so a token is required
+            if let Ok(addr) = std::env::var("VIBE_MCP_HTTP_ADDR") {
+                let token = auth::token_from_env()?;
+                let listener = tokio::net::TcpListener::bind(&addr).await?;
+                tracing::info!(
+                    "[MCP] Serving MCP over HTTP at http://{}/mcp",
+                    listener.local_addr()?
+                );
+                axum::serve(listener, http::router(server, token)).await?;
+                return Ok(());
+            }
+
+            let service = server.serve(stdio()).await.inspect_err(|e| {
+                tracing::error!("serving error: {:?}", e);
+                sentry::capture_error(e);
+            })?;
 
             service.waiting().await?;
             Ok(())
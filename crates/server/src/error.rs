@@ -5,14 +5,16 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use db::models::{
-    execution_process::ExecutionProcessError, project::ProjectError, task_attempt::TaskAttemptError,
+    execution_process::ExecutionProcessError, project::ProjectError,
+    project_repository::ProjectRepositoryError, task_attempt::TaskAttemptError,
 };
 use deployment::DeploymentError;
 use executors::executors::ExecutorError;
 use git2::Error as Git2Error;
 use services::services::{
     auth::AuthError, config::ConfigError, container::ContainerError, drafts::DraftsServiceError,
-    git::GitServiceError, github_service::GitHubServiceError, image::ImageError,
+    git::GitServiceError, github_service::GitHubServiceError, gitlab_service::GitLabServiceError,
+    image::ImageError, project_bundle::ProjectBundleError, release_notes::ReleaseNotesError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
@@ -32,6 +34,8 @@ pub enum ApiError {
     #[error(transparent)]
     GitHubService(#[from] GitHubServiceError),
     #[error(transparent)]
+    GitLabService(#[from] GitLabServiceError),
+    #[error(transparent)]
     Auth(#[from] AuthError),
     #[error(transparent)]
     Deployment(#[from] DeploymentError),
@@ -49,6 +53,10 @@ pub enum ApiError {
     Image(#[from] ImageError),
     #[error(transparent)]
     Drafts(#[from] DraftsServiceError),
+    #[error(transparent)]
+    ProjectBundle(#[from] ProjectBundleError),
+    #[error(transparent)]
+    ReleaseNotes(#[from] ReleaseNotesError),
     #[error("Multipart error: {0}")]
     Multipart(#[from] MultipartError),
     #[error("IO error: {0}")]
@@ -98,12 +106,25 @@ impl IntoResponse for ApiError {
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
             },
             ApiError::GitHubService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
+            ApiError::GitLabService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitLabServiceError"),
             ApiError::Auth(_) => (StatusCode::INTERNAL_SERVER_ERROR, "AuthError"),
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
+            ApiError::Container(ContainerError::BudgetExceeded(_)) => {
+                (StatusCode::CONFLICT, "BudgetExceeded")
+            }
+            ApiError::Container(ContainerError::GitServiceError(
+                services::services::git::GitServiceError::InvalidPath(_),
+            )) => (StatusCode::BAD_REQUEST, "InvalidPath"),
+            ApiError::Container(ContainerError::GitServiceError(
+                services::services::git::GitServiceError::HunkNotFound(_),
+            )) => (StatusCode::CONFLICT, "HunkNotFound"),
             ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
             ApiError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError"),
             ApiError::Worktree(_) => (StatusCode::INTERNAL_SERVER_ERROR, "WorktreeError"),
+            ApiError::Config(ConfigError::ValidationError(_)) => {
+                (StatusCode::BAD_REQUEST, "ConfigValidationError")
+            }
             ApiError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ConfigError"),
             ApiError::Image(img_err) => match img_err {
                 ImageError::InvalidFormat => (StatusCode::BAD_REQUEST, "InvalidImageFormat"),
@@ -124,6 +145,20 @@ impl IntoResponse for ApiError {
                     (StatusCode::INTERNAL_SERVER_ERROR, "ExecutionProcessError")
                 }
             },
+            ApiError::ProjectBundle(bundle_err) => match bundle_err {
+                ProjectBundleError::UnsupportedVersion(_)
+                | ProjectBundleError::InvalidImageData(_)
+                | ProjectBundleError::NoRepositories
+                | ProjectBundleError::RepositoryPaths(_)
+                | ProjectBundleError::DuplicateProjectPath(_)
+                | ProjectBundleError::ProjectRepository(
+                    ProjectRepositoryError::Validation(_)
+                    | ProjectRepositoryError::DuplicateName
+                    | ProjectRepositoryError::DuplicatePath,
+                ) => (StatusCode::BAD_REQUEST, "ProjectBundleError"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "ProjectBundleError"),
+            },
+            ApiError::ReleaseNotes(_) => (StatusCode::BAD_REQUEST, "ReleaseNotesError"),
             ApiError::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "IoError"),
             ApiError::Multipart(_) => (StatusCode::BAD_REQUEST, "MultipartError"),
             ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
@@ -149,8 +184,14 @@ impl IntoResponse for ApiError {
                 }
                 _ => format!("{}: {}", error_type, self),
             },
+            ApiError::ProjectBundle(bundle_err) if status_code == StatusCode::BAD_REQUEST => {
+                bundle_err.to_string()
+            }
+            ApiError::ReleaseNotes(err) => err.to_string(),
             ApiError::Multipart(_) => "Failed to upload file. Please ensure the file is valid and try again.".to_string(),
             ApiError::Conflict(msg) => msg.clone(),
+            ApiError::Config(ConfigError::ValidationError(msg)) => msg.clone(),
+            ApiError::Container(ContainerError::BudgetExceeded(msg)) => msg.clone(),
             ApiError::Drafts(drafts_err) => match drafts_err {
                 DraftsServiceError::Conflict(msg) => msg.clone(),
                 DraftsServiceError::Database(_) => format!("{}: {}", error_type, drafts_err),
@@ -0,0 +1,314 @@
+//! Read-only GraphQL schema over projects, tasks, attempts and activity, so a client can load a
+//! board in one round trip instead of chaining REST calls.
+
+use async_graphql::{
+    ComplexObject, Context, EmptyMutation, EmptySubscription, Enum, Object, Result, Schema,
+    SimpleObject,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    label::Label,
+    project::Project,
+    task::{Task, TaskStatus, TaskWithAttemptStatus},
+    task_attempt::TaskAttempt,
+};
+use deployment::Deployment;
+use services::activity_feed::ActivityEventRepository;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    activity_feed::{ActivityFeedItem, map_event_to_item, paginate_events},
+    middleware::CurrentUser,
+};
+
+pub type KanbanSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Deep or wide queries are refused before they reach the database
+const MAX_DEPTH: usize = 8;
+const MAX_COMPLEXITY: usize = 500;
+const MAX_ACTIVITY_ITEMS: i32 = 100;
+
+/// The deployment is added to each request as context data
+pub fn schema() -> KanbanSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+#[graphql(name = "TaskStatus")]
+pub enum TaskStatusValue {
+    Todo,
+    InProgress,
+    InReview,
+    Done,
+    Cancelled,
+}
+
+impl From<&TaskStatus> for TaskStatusValue {
+    fn from(status: &TaskStatus) -> Self {
+        match status {
+            TaskStatus::Todo => Self::Todo,
+            TaskStatus::InProgress => Self::InProgress,
+            TaskStatus::InReview => Self::InReview,
+            TaskStatus::Done => Self::Done,
+            TaskStatus::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(name = "Project", complex)]
+pub struct ProjectNode {
+    pub id: Uuid,
+    pub name: String,
+    pub git_repo_path: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Project> for ProjectNode {
+    fn from(project: Project) -> Self {
+        Self {
+            id: project.id,
+            name: project.name,
+            git_repo_path: project.git_repo_path.to_string_lossy().into_owned(),
+            created_at: project.created_at,
+            updated_at: project.updated_at,
+        }
+    }
+}
+
+#[ComplexObject]
+impl ProjectNode {
+    /// Tasks with their attempt status, optionally only those in `status`
+    async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<TaskStatusValue>,
+    ) -> Result<Vec<TaskNode>> {
+        let deployment = ctx.data::<DeploymentImpl>()?;
+        let tasks =
+            Task::find_by_project_id_with_attempt_status(&deployment.db().pool, self.id).await?;
+        Ok(tasks
+            .into_iter()
+            .filter(|task| {
+                status.is_none_or(|status| TaskStatusValue::from(&task.status) == status)
+            })
+            .map(TaskNode::from)
+            .collect())
+    }
+
+    /// Newest first, as in the activity feed
+    async fn activity(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 25)] limit: i32,
+    ) -> Result<Vec<ActivityItemNode>> {
+        let deployment = ctx.data::<DeploymentImpl>()?;
+        let current_user = ctx.data::<CurrentUser>()?;
+        let repository = {
+            let config = deployment.config().read().await;
+            ActivityEventRepository::from_config(
+                deployment.db().pool.clone(),
+                &config.activity_feed,
+            )
+        };
+        let events = repository
+            .list_recent_for_reader(
+                self.id,
+                current_user.id(),
+                &current_user.reader_id(deployment.user_id()),
+            )
+            .await?;
+        let (page, _) = paginate_events(events, None, limit.clamp(1, MAX_ACTIVITY_ITEMS) as usize);
+        Ok(page
+            .iter()
+            .map(|event| ActivityItemNode::from(map_event_to_item(event)))
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(name = "Label")]
+pub struct LabelNode {
+    pub id: Uuid,
+    pub name: String,
+    pub color: String,
+}
+
+impl From<Label> for LabelNode {
+    fn from(label: Label) -> Self {
+        Self {
+            id: label.id,
+            name: label.name,
+            color: label.color,
+        }
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(name = "Task", complex)]
+pub struct TaskNode {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatusValue,
+    pub parent_task_id: Option<Uuid>,
+    pub has_in_progress_attempt: bool,
+    pub has_merged_attempt: bool,
+    pub last_attempt_failed: bool,
+    pub labels: Vec<LabelNode>,
+    pub due_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<TaskWithAttemptStatus> for TaskNode {
+    fn from(value: TaskWithAttemptStatus) -> Self {
+        let TaskWithAttemptStatus {
+            task,
+            has_in_progress_attempt,
+            has_merged_attempt,
+            last_attempt_failed,
+            labels,
+            due_at,
+            ..
+        } = value;
+        Self {
+            id: task.id,
+            project_id: task.project_id,
+            title: task.title,
+            description: task.description,
+            status: TaskStatusValue::from(&task.status),
+            parent_task_id: task.parent_task_id,
+            has_in_progress_attempt,
+            has_merged_attempt,
+            last_attempt_failed,
+            labels: labels.into_iter().map(LabelNode::from).collect(),
+            due_at,
+            created_at: task.created_at,
+            updated_at: task.updated_at,
+        }
+    }
+}
+
+#[ComplexObject]
+impl TaskNode {
+    /// Newest first
+    async fn attempts(&self, ctx: &Context<'_>) -> Result<Vec<TaskAttemptNode>> {
+        let deployment = ctx.data::<DeploymentImpl>()?;
+        let attempts = TaskAttempt::fetch_all(&deployment.db().pool, Some(self.id)).await?;
+        Ok(attempts.into_iter().map(TaskAttemptNode::from).collect())
+    }
+
+    async fn latest_attempt(&self, ctx: &Context<'_>) -> Result<Option<TaskAttemptNode>> {
+        let deployment = ctx.data::<DeploymentImpl>()?;
+        let attempt = TaskAttempt::find_latest_for_task(&deployment.db().pool, self.id).await?;
+        Ok(attempt.map(TaskAttemptNode::from))
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(name = "TaskAttempt")]
+pub struct TaskAttemptNode {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub branch: String,
+    pub target_branch: String,
+    pub executor: String,
+    pub worktree_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<TaskAttempt> for TaskAttemptNode {
+    fn from(attempt: TaskAttempt) -> Self {
+        Self {
+            id: attempt.id,
+            task_id: attempt.task_id,
+            branch: attempt.branch,
+            target_branch: attempt.target_branch,
+            executor: attempt.executor,
+            worktree_deleted: attempt.worktree_deleted,
+            created_at: attempt.created_at,
+            updated_at: attempt.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(name = "ActivityItem")]
+pub struct ActivityItemNode {
+    pub id: Uuid,
+    pub headline: String,
+    pub summary: Option<String>,
+    pub cta_label: Option<String>,
+    /// Path within the app, relative to where it is served
+    pub cta_href: Option<String>,
+    pub urgency_score: u32,
+    pub action_required: bool,
+    pub read: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ActivityFeedItem> for ActivityItemNode {
+    fn from(item: ActivityFeedItem) -> Self {
+        let (cta_label, cta_href) = match item.cta {
+            Some(cta) => (Some(cta.label), Some(cta.href)),
+            None => (None, None),
+        };
+        Self {
+            id: item.id,
+            headline: item.headline,
+            summary: item.summary,
+            cta_label,
+            cta_href,
+            urgency_score: item.urgency_score,
+            action_required: item.action_required,
+            read: item.read,
+            created_at: item.created_at,
+        }
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn projects(&self, ctx: &Context<'_>) -> Result<Vec<ProjectNode>> {
+        let deployment = ctx.data::<DeploymentImpl>()?;
+        let projects = Project::find_all(&deployment.db().pool).await?;
+        Ok(projects.into_iter().map(ProjectNode::from).collect())
+    }
+
+    async fn project(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<ProjectNode>> {
+        let deployment = ctx.data::<DeploymentImpl>()?;
+        let project = Project::find_by_id(&deployment.db().pool, id).await?;
+        Ok(project.map(ProjectNode::from))
+    }
+
+    async fn task(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<TaskNode>> {
+        let deployment = ctx.data::<DeploymentImpl>()?;
+        let task = Task::find_by_id_with_attempt_status(&deployment.db().pool, id).await?;
+        Ok(task.map(TaskNode::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_exposes_a_board_in_one_query() {
+        let sdl = schema().sdl();
+        assert!(sdl.contains("type Project"));
+        assert!(sdl.contains("tasks(status: TaskStatus): [Task!]!"));
+        assert!(sdl.contains("latestAttempt: TaskAttempt"));
+        assert!(sdl.contains("activity(limit: Int! = 25): [ActivityItem!]!"));
+        assert!(!sdl.contains("type Mutation"));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod activity_feed;
 pub mod error;
+#[cfg(feature = "graphql")]
+pub mod graphql;
 pub mod mcp;
 pub mod middleware;
 pub mod routes;
@@ -1,6 +1,6 @@
 use anyhow::{self, Error as AnyhowError};
 use deployment::{Deployment, DeploymentError};
-use server::{DeploymentImpl, routes};
+use server::{DeploymentImpl, middleware::ProxyConfig, routes};
 use sqlx::Error as SqlxError;
 use strip_ansi_escapes::strip;
 use thiserror::Error;
@@ -44,6 +44,9 @@ async fn main() -> Result<(), VibeKanbanError> {
     deployment.cleanup_orphan_executions().await?;
     deployment.backfill_before_head_commits().await?;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_issue_sync_service().await;
+    deployment.spawn_jira_sync_service().await;
+    routes::usage::spawn_usage_sampler(deployment.db().pool.clone());
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -60,7 +63,9 @@ async fn main() -> Result<(), VibeKanbanError> {
         }
     });
 
-    let app_router = routes::router(deployment);
+    let proxy = ProxyConfig::from_env();
+    let base_path = proxy.base_path.clone();
+    let app_router = routes::router(deployment, proxy);
 
     let port = std::env::var("BACKEND_PORT")
         .or_else(|_| std::env::var("PORT"))
@@ -85,16 +90,19 @@ async fn main() -> Result<(), VibeKanbanError> {
         tracing::warn!("Failed to write port file: {}", e);
     }
 
-    tracing::info!("Server running on http://{host}:{actual_port}");
+    tracing::info!("Server running on http://{host}:{actual_port}{base_path}");
 
     if !cfg!(debug_assertions) {
         tracing::info!("Opening browser...");
         tokio::spawn(async move {
-            if let Err(e) = open_browser(&format!("http://127.0.0.1:{actual_port}")).await {
+            if let Err(e) =
+                open_browser(&format!("http://127.0.0.1:{actual_port}{base_path}")).await
+            {
                 tracing::warn!(
-                    "Failed to open browser automatically: {}. Please open http://127.0.0.1:{} manually.",
+                    "Failed to open browser automatically: {}. Please open http://127.0.0.1:{}{} manually.",
                     e,
-                    actual_port
+                    actual_port,
+                    base_path
                 );
             }
         });
@@ -0,0 +1,88 @@
+//! Bearer-token authentication for MCP transports reachable over the network. The tools can
+//! create tasks and start coding agents, so no request gets through without the token.
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+
+/// Variable holding the token MCP clients must send
+pub const TOKEN_ENV: &str = "VIBE_MCP_TOKEN";
+
+/// Shortest token accepted, so a placeholder value cannot end up guarding the endpoint
+pub const MIN_TOKEN_LEN: usize = 16;
+
+/// The token from [`TOKEN_ENV`], or an error when it is unset or shorter than [`MIN_TOKEN_LEN`]
+pub fn token_from_env() -> anyhow::Result<String> {
+    let token = std::env::var(TOKEN_ENV)
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    if token.len() < MIN_TOKEN_LEN {
+        anyhow::bail!("{TOKEN_ENV} must be at least {MIN_TOKEN_LEN} characters");
+    }
+    Ok(token)
+}
+
+/// Whether the request's bearer token is `expected`. Digests are compared instead of the tokens
+/// so the comparison time does not reveal how much of a guess was right.
+pub fn bearer_token_matches(headers: &HeaderMap, expected: &str) -> bool {
+    let Some(token) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+    Sha256::digest(token.trim().as_bytes()) == Sha256::digest(expected.as_bytes())
+}
+
+/// Middleware answering 401 to requests without the token held in its state
+pub async fn require_token(
+    State(token): State<String>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if bearer_token_matches(request.headers(), &token) {
+        Ok(next.run(request).await)
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn headers(authorization: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(authorization).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn only_the_configured_bearer_token_is_accepted() {
+        let token = "s3cret-mcp-token-value";
+        assert!(bearer_token_matches(
+            &headers("Bearer s3cret-mcp-token-value"),
+            token
+        ));
+        assert!(!bearer_token_matches(
+            &headers("Bearer s3cret-mcp-token-valu"),
+            token
+        ));
+        assert!(!bearer_token_matches(
+            &headers("Basic s3cret-mcp-token-value"),
+            token
+        ));
+        assert!(!bearer_token_matches(&HeaderMap::new(), token));
+    }
+}
@@ -0,0 +1,22 @@
+//! Streamable HTTP transport for the task server, for MCP clients that cannot spawn it over
+//! stdio
+
+use axum::{Router, middleware};
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpService, session::local::LocalSessionManager,
+};
+
+use crate::mcp::{auth::require_token, task_server::TaskServer};
+
+/// The MCP endpoint at `/mcp`, with one task server per client session. Requests without
+/// `token` as their bearer token are turned away.
+pub fn router(server: TaskServer, token: String) -> Router {
+    let service = StreamableHttpService::new(
+        move || Ok(server.clone()),
+        LocalSessionManager::default().into(),
+        Default::default(),
+    );
+    Router::new()
+        .nest_service("/mcp", service)
+        .layer(middleware::from_fn_with_state(token, require_token))
+}
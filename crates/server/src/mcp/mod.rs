@@ -1 +1,3 @@
+pub mod auth;
+pub mod http;
 pub mod task_server;
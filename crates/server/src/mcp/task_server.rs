@@ -218,6 +218,46 @@ pub struct StartTaskAttemptResponse {
     pub attempt_id: String,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct StopExecutionRequest {
+    #[schemars(description = "The ID of the task attempt whose execution should stop")]
+    pub attempt_id: Uuid,
+    #[schemars(
+        description = "Optional ID of a single execution process to stop. If not provided, every running process of the attempt is stopped."
+    )]
+    pub execution_process_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct StopExecutionResponse {
+    pub message: String,
+    pub attempt_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct QueueFollowUpRequest {
+    #[schemars(description = "The ID of the task attempt to follow up on")]
+    pub attempt_id: Uuid,
+    #[schemars(description = "The follow-up instructions for the coding agent")]
+    pub prompt: String,
+    #[schemars(description = "Optional executor variant for the follow-up")]
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct QueueFollowUpResponse {
+    pub message: String,
+    pub attempt_id: String,
+    pub queued: bool,
+}
+
+/// The parts of a follow-up draft needed to queue it
+#[derive(Debug, Deserialize)]
+struct FollowUpDraftState {
+    queued: bool,
+    version: i64,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct DeleteTaskResponse {
     pub message: String,
@@ -251,6 +291,19 @@ impl TaskServer {
             tool_router: Self::tool_router(),
         }
     }
+
+    /// Authenticate as a signed-in user, needed once the instance has user accounts
+    pub fn with_session_token(mut self, token: &str) -> Self {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Ok(value) = reqwest::header::HeaderValue::from_str(&format!("Bearer {token}")) {
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+        self.client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap_or_default();
+        self
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -283,10 +336,10 @@ impl TaskServer {
         Self::err_value(v)
     }
 
-    async fn send_json<T: DeserializeOwned>(
+    async fn send_envelope<T: DeserializeOwned>(
         &self,
         rb: reqwest::RequestBuilder,
-    ) -> Result<T, CallToolResult> {
+    ) -> Result<Option<T>, CallToolResult> {
         let resp = rb
             .send()
             .await
@@ -308,11 +361,25 @@ impl TaskServer {
             return Err(Self::err("VK API returned error", Some(msg)).unwrap());
         }
 
-        api_response
-            .data
+        Ok(api_response.data)
+    }
+
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        rb: reqwest::RequestBuilder,
+    ) -> Result<T, CallToolResult> {
+        self.send_envelope(rb)
+            .await?
             .ok_or_else(|| Self::err("VK API response missing data field", None).unwrap())
     }
 
+    /// For endpoints that succeed without returning data
+    async fn send_empty(&self, rb: reqwest::RequestBuilder) -> Result<(), CallToolResult> {
+        self.send_envelope::<serde_json::Value>(rb)
+            .await
+            .map(|_| ())
+    }
+
     fn url(&self, path: &str) -> String {
         format!(
             "{}/{}",
@@ -502,6 +569,8 @@ impl TaskServer {
             executor_profile_id,
             base_branch,
             repositories,
+            parent_attempt_id: None,
+            transfer_follow_up_from: None,
         };
 
         let url = self.url("/api/task-attempts");
@@ -520,6 +589,89 @@ impl TaskServer {
         TaskServer::success(&response)
     }
 
+    #[tool(
+        description = "Stop the running execution of a task attempt, such as a coding agent that is going in the wrong direction. `attempt_id` is required!"
+    )]
+    async fn stop_execution(
+        &self,
+        Parameters(StopExecutionRequest {
+            attempt_id,
+            execution_process_id,
+        }): Parameters<StopExecutionRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = match execution_process_id {
+            Some(process_id) => self.url(&format!("/api/execution-processes/{process_id}/stop")),
+            None => self.url(&format!("/api/task-attempts/{attempt_id}/stop")),
+        };
+        if let Err(e) = self.send_empty(self.client.post(&url)).await {
+            return Ok(e);
+        }
+
+        TaskServer::success(&StopExecutionResponse {
+            message: "Execution stopped".to_string(),
+            attempt_id: attempt_id.to_string(),
+        })
+    }
+
+    #[tool(
+        description = "Queue a follow-up prompt for a task attempt. It starts as soon as the attempt's current execution finishes, or right away if nothing is running. `attempt_id` and `prompt` are required!"
+    )]
+    async fn queue_follow_up(
+        &self,
+        Parameters(QueueFollowUpRequest {
+            attempt_id,
+            prompt,
+            variant,
+        }): Parameters<QueueFollowUpRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let prompt = prompt.trim().to_string();
+        if prompt.is_empty() {
+            return Self::err("Prompt must not be empty.".to_string(), None::<String>);
+        }
+        let variant = variant
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let draft_url = self.url(&format!(
+            "/api/task-attempts/{attempt_id}/draft?type=follow_up"
+        ));
+        let draft: FollowUpDraftState = match self
+            .send_json(self.client.put(&draft_url).json(&serde_json::json!({
+                "prompt": prompt,
+                "variant": variant,
+            })))
+            .await
+        {
+            Ok(draft) => draft,
+            Err(e) => return Ok(e),
+        };
+
+        let queue_url = self.url(&format!(
+            "/api/task-attempts/{attempt_id}/draft/queue?type=follow_up"
+        ));
+        let queued: FollowUpDraftState = match self
+            .send_json(self.client.post(&queue_url).json(&serde_json::json!({
+                "queued": true,
+                "expected_queued": draft.queued,
+                "expected_version": draft.version,
+            })))
+            .await
+        {
+            Ok(draft) => draft,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&QueueFollowUpResponse {
+            message: if queued.queued {
+                "Follow-up queued; it starts when the current execution finishes".to_string()
+            } else {
+                "Follow-up started".to_string()
+            },
+            attempt_id: attempt_id.to_string(),
+            queued: queued.queued,
+        })
+    }
+
     #[tool(
         description = "Update an existing task/ticket's title, description, or status. `project_id` and `task_id` are required! `title`, `description`, and `status` are optional."
     )]
@@ -627,7 +779,7 @@ impl ServerHandler for TaskServer {
                 name: "vibe-kanban".to_string(),
                 version: "1.0.0".to_string(),
             },
-            instructions: Some("A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. This should be provided to you. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string()),
+            instructions: Some("A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. This should be provided to you. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'stop_execution', 'queue_follow_up', 'get_task', 'update_task', 'delete_task'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string()),
         }
     }
 }
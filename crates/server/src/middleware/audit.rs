@@ -0,0 +1,194 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::{Method, header},
+    middleware::Next,
+    response::Response,
+};
+use db::models::audit_log::{AuditLogEntry, CreateAuditLogEntry};
+use deployment::Deployment;
+use futures_util::{StreamExt, stream};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, middleware::CurrentUser};
+
+/// The entity a call acts on: the last id in the path and the collection it belongs to, so
+/// `/task-attempts/{id}/merge` is a `task-attempts` entity. Paths without an id, such as
+/// `POST /tasks`, name only the collection.
+pub fn audit_target(path: &str) -> Option<(String, Option<Uuid>)> {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let with_id = segments.windows(2).rev().find_map(|pair| {
+        let id = Uuid::parse_str(pair[1]).ok()?;
+        Uuid::parse_str(pair[0])
+            .is_err()
+            .then(|| (pair[0].to_string(), Some(id)))
+    });
+    with_id.or_else(|| segments.first().map(|s| (s.to_string(), None)))
+}
+
+/// Largest response body read to find the id of a created entity
+const MAX_BUFFERED_BODY: usize = 256 * 1024;
+
+/// Draft autosaves fire on every pause in typing, so they would flood the log without telling
+/// anyone who changed what.
+fn is_autosave(method: &Method, path: &str) -> bool {
+    *method == Method::PUT && path.starts_with("/task-attempts/") && path.ends_with("/draft")
+}
+
+/// Reads a response body of at most `limit` bytes. A larger body, or one that fails to read,
+/// is handed back whole (what was already read followed by the rest) so the client still gets
+/// the original response.
+async fn buffer_body(body: Body, limit: usize) -> Result<Bytes, Body> {
+    let mut stream = body.into_data_stream();
+    let mut buffered = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            Ok(chunk) if buffered.len() + chunk.len() <= limit => {
+                buffered.extend_from_slice(&chunk)
+            }
+            chunk => {
+                let rest = stream::iter([Ok(Bytes::from(buffered)), chunk]).chain(stream);
+                return Err(Body::from_stream(rest));
+            }
+        }
+    }
+    Ok(Bytes::from(buffered))
+}
+
+/// `data.id` of a JSON API response, the id of whatever a create call made
+fn created_id(body: &[u8]) -> Option<Uuid> {
+    let value: Value = serde_json::from_slice(body).ok()?;
+    value.get("data")?.get("id")?.as_str()?.parse().ok()
+}
+
+/// Records every request that can change state, with who made it and a summary of the entity
+/// before and after. Must run inside `require_user_middleware` so the user is known. Failing to
+/// record is logged and never fails the request.
+pub async fn audit_log_middleware(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) || is_autosave(request.method(), request.uri().path())
+    {
+        return next.run(request).await;
+    }
+
+    let pool = deployment.db().pool.clone();
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let actor = request
+        .extensions()
+        .get::<CurrentUser>()
+        .and_then(|user| user.0.clone());
+    let (entity_type, mut entity_id) = match audit_target(&path) {
+        Some((entity_type, entity_id)) => (Some(entity_type), entity_id),
+        None => (None, None),
+    };
+
+    let summary = |entity_id: Option<Uuid>| {
+        let pool = pool.clone();
+        let entity_type = entity_type.clone();
+        async move {
+            let (Some(entity_type), Some(entity_id)) = (entity_type, entity_id) else {
+                return None;
+            };
+            AuditLogEntry::entity_summary(&pool, &entity_type, entity_id)
+                .await
+                .inspect_err(|e| tracing::warn!("Failed to summarize {}: {}", entity_type, e))
+                .ok()
+                .flatten()
+        }
+    };
+
+    let before_summary = summary(entity_id).await;
+    let mut response = next.run(request).await;
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if entity_id.is_none() && response.status().is_success() && is_json {
+        let (parts, body) = response.into_parts();
+        let body = match buffer_body(body, MAX_BUFFERED_BODY).await {
+            Ok(bytes) => {
+                entity_id = created_id(&bytes);
+                Body::from(bytes)
+            }
+            Err(body) => body,
+        };
+        response = Response::from_parts(parts, body);
+    }
+
+    let after_summary = summary(entity_id).await;
+    let entry = CreateAuditLogEntry {
+        actor_user_id: actor.as_ref().map(|user| user.id),
+        actor_name: actor.map(|user| user.username),
+        method,
+        path,
+        entity_type,
+        entity_id,
+        status_code: response.status().as_u16(),
+        before_summary,
+        after_summary,
+    };
+    if let Err(e) = AuditLogEntry::create(&pool, entry).await {
+        tracing::error!("Failed to write audit log entry: {}", e);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_are_the_last_id_in_the_path() {
+        let attempt = Uuid::new_v4();
+        let project = Uuid::new_v4();
+        assert_eq!(
+            audit_target(&format!("/task-attempts/{attempt}/merge")),
+            Some(("task-attempts".to_string(), Some(attempt)))
+        );
+        assert_eq!(
+            audit_target(&format!("/projects/{project}/labels/{attempt}")),
+            Some(("labels".to_string(), Some(attempt)))
+        );
+        assert_eq!(audit_target("/tasks"), Some(("tasks".to_string(), None)));
+        assert_eq!(audit_target("/"), None);
+    }
+
+    #[test]
+    fn created_ids_come_from_the_response_data() {
+        let id = Uuid::new_v4();
+        let body = format!(r#"{{"success":true,"data":{{"id":"{id}","title":"x"}}}}"#);
+        assert_eq!(created_id(body.as_bytes()), Some(id));
+        assert_eq!(created_id(br#"{"success":true,"data":null}"#), None);
+    }
+
+    #[test]
+    fn draft_autosaves_are_not_audited() {
+        let path = format!("/task-attempts/{}/draft", Uuid::new_v4());
+        assert!(is_autosave(&Method::PUT, &path));
+        assert!(!is_autosave(&Method::DELETE, &path));
+        assert!(!is_autosave(&Method::PUT, &format!("{path}/queue")));
+    }
+
+    #[tokio::test]
+    async fn oversized_bodies_are_passed_through_whole() {
+        let small = buffer_body(Body::from("{}"), 16).await;
+        assert_eq!(small.ok(), Some(Bytes::from("{}")));
+
+        let large = "x".repeat(32);
+        let Err(body) = buffer_body(Body::from(large.clone()), 16).await else {
+            panic!("a body over the limit should not be buffered");
+        };
+        let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        assert_eq!(bytes, Bytes::from(large));
+    }
+}
@@ -0,0 +1,310 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, Method, StatusCode, header},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+use db::models::{
+    user::{User, UserRole},
+    user_session::UserSession,
+};
+use deployment::Deployment;
+use services::services::{
+    calendar_feed::feed_signature_matches,
+    users::{hash_session_token, is_agent_token},
+};
+use uuid::Uuid;
+
+use crate::DeploymentImpl;
+
+/// Cookie the session token is kept in by the browser
+pub const SESSION_COOKIE: &str = "vk_session";
+
+/// The signed-in user making the request; `None` while no accounts exist
+#[derive(Debug, Clone)]
+pub struct CurrentUser(pub Option<User>);
+
+impl CurrentUser {
+    pub fn id(&self) -> Option<Uuid> {
+        self.0.as_ref().map(|user| user.id)
+    }
+
+    /// Whose activity read state the request sees: the user's, or the installation's
+    /// (`installation_id`) while no accounts exist
+    pub fn reader_id(&self, installation_id: &str) -> String {
+        match self.id() {
+            Some(id) => id.to_string(),
+            None => installation_id.to_string(),
+        }
+    }
+}
+
+/// Marks requests made with the agent token by the server's own execution processes, which
+/// have no user of their own
+#[derive(Debug, Clone, Copy)]
+pub struct InternalCaller;
+
+/// Session token from an `Authorization: Bearer` header, or else the session cookie
+pub fn session_token(headers: &HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let cookie = || {
+        headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .find_map(|pair| {
+                let (name, value) = pair.trim().split_once('=')?;
+                (name == SESSION_COOKIE).then_some(value)
+            })
+    };
+    bearer
+        .or_else(cookie)
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+}
+
+/// Least role needed for `method` on `path` (relative to `/api`). Reading is open to every
+/// role except browsing the host filesystem, which needs a contributor; account management,
+/// the audit log, database backups and maintenance, webhooks and MCP servers (which hold
+/// signing keys and API keys), and app settings are for admins; other changes need a
+/// contributor.
+pub fn required_role(method: &Method, path: &str) -> UserRole {
+    let path = path.trim_end_matches('/');
+    if path == "/users/me" {
+        return UserRole::Viewer;
+    }
+    // The GraphQL schema only has queries, though they are sent with POST
+    if path == "/graphql" {
+        return UserRole::Viewer;
+    }
+    if path == "/users"
+        || path.starts_with("/users/")
+        || path == "/audit-log"
+        || path.starts_with("/database/")
+        || path == "/webhooks"
+        || path.starts_with("/webhooks/")
+        || path == "/mcp-config"
+        || path.starts_with("/mcp-config/")
+    {
+        return UserRole::Admin;
+    }
+    // Browsing the host's directories and repositories reveals more than the projects do
+    if path.starts_with("/filesystem/") {
+        return UserRole::Contributor;
+    }
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return UserRole::Viewer;
+    }
+    if matches!(path, "/config" | "/profiles") {
+        return UserRole::Admin;
+    }
+    UserRole::Contributor
+}
+
+/// Project and signature of a calendar feed request (`/projects/{id}/calendar.ics?signature=..`)
+pub fn signed_feed_request(path: &str, query: Option<&str>) -> Option<(Uuid, String)> {
+    let project_id = path
+        .strip_prefix("/projects/")?
+        .strip_suffix("/calendar.ics")?
+        .parse()
+        .ok()?;
+    let signature = query?.split('&').find_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        (name == "signature").then_some(value)
+    })?;
+    Some((project_id, signature.to_string()))
+}
+
+/// Resolves the signed-in user and enforces their role. Until the first account is created the
+/// app is single-user and every request goes through, as it did before accounts existed.
+///
+/// Execution processes send the agent token and get a contributor's access without a user;
+/// calendar feeds are let through when their address is signed.
+pub async fn require_user_middleware(
+    State(deployment): State<DeploymentImpl>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if request.method() == Method::GET
+        && let Some((project_id, signature)) =
+            signed_feed_request(request.uri().path(), request.uri().query())
+        && feed_signature_matches(project_id, &signature)
+    {
+        request.extensions_mut().insert(CurrentUser(None));
+        return Ok(next.run(request).await);
+    }
+
+    let token = session_token(request.headers());
+    if let Some(token) = &token
+        && is_agent_token(token)
+    {
+        let required = required_role(request.method(), request.uri().path());
+        if !UserRole::Contributor.allows(required) {
+            return Err(StatusCode::FORBIDDEN);
+        }
+        request.extensions_mut().insert(CurrentUser(None));
+        request.extensions_mut().insert(InternalCaller);
+        return Ok(next.run(request).await);
+    }
+
+    let pool = &deployment.db().pool;
+    let user = match token {
+        Some(token) => UserSession::find_user(pool, &hash_session_token(&token), Utc::now())
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to look up session: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?,
+        None => None,
+    };
+
+    match &user {
+        Some(user) => {
+            let required = required_role(request.method(), request.uri().path());
+            if !user.role.allows(required) {
+                return Err(StatusCode::FORBIDDEN);
+            }
+        }
+        None => {
+            let accounts = User::count(pool).await.map_err(|e| {
+                tracing::error!("Failed to count users: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            if accounts > 0 {
+                return Err(StatusCode::UNAUTHORIZED);
+            }
+        }
+    }
+
+    request.extensions_mut().insert(CurrentUser(user));
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn roles_needed_follow_the_kind_of_request() {
+        assert_eq!(
+            required_role(&Method::GET, "/projects/1/tasks"),
+            UserRole::Viewer
+        );
+        assert_eq!(
+            required_role(&Method::POST, "/tasks"),
+            UserRole::Contributor
+        );
+        assert_eq!(required_role(&Method::PUT, "/config"), UserRole::Admin);
+        assert_eq!(
+            required_role(&Method::PUT, "/mcp-config/servers/fs"),
+            UserRole::Admin
+        );
+        assert_eq!(
+            required_role(&Method::DELETE, "/mcp-config/servers/fs"),
+            UserRole::Admin
+        );
+        assert_eq!(required_role(&Method::GET, "/users"), UserRole::Admin);
+        assert_eq!(required_role(&Method::GET, "/users/me"), UserRole::Viewer);
+        assert_eq!(required_role(&Method::POST, "/graphql"), UserRole::Viewer);
+        assert_eq!(required_role(&Method::GET, "/audit-log"), UserRole::Admin);
+        assert_eq!(
+            required_role(&Method::GET, "/database/backups"),
+            UserRole::Admin
+        );
+        assert_eq!(
+            required_role(&Method::POST, "/database/maintenance"),
+            UserRole::Admin
+        );
+        assert_eq!(
+            required_role(&Method::GET, "/database/stats"),
+            UserRole::Admin
+        );
+        assert_eq!(required_role(&Method::GET, "/webhooks"), UserRole::Admin);
+        assert_eq!(required_role(&Method::PUT, "/webhooks/1"), UserRole::Admin);
+        assert_eq!(required_role(&Method::GET, "/mcp-config"), UserRole::Admin);
+        assert_eq!(
+            required_role(&Method::GET, "/mcp-config/servers"),
+            UserRole::Admin
+        );
+
+        assert_eq!(
+            required_role(&Method::GET, "/filesystem/directory"),
+            UserRole::Contributor
+        );
+        assert_eq!(
+            required_role(&Method::GET, "/filesystem/git-repos"),
+            UserRole::Contributor
+        );
+
+        assert!(UserRole::Admin.allows(UserRole::Contributor));
+        assert!(UserRole::Contributor.allows(UserRole::Viewer));
+        assert!(!UserRole::Viewer.allows(UserRole::Contributor));
+        assert!(!UserRole::Contributor.allows(UserRole::Admin));
+    }
+
+    #[test]
+    fn only_signed_calendar_feeds_are_recognised() {
+        let id = Uuid::new_v4();
+        let path = format!("/projects/{id}/calendar.ics");
+
+        assert_eq!(
+            signed_feed_request(&path, Some("foo=1&signature=abc")),
+            Some((id, "abc".to_string()))
+        );
+        assert_eq!(signed_feed_request(&path, None), None);
+        assert_eq!(signed_feed_request(&path, Some("sig=abc")), None);
+        assert_eq!(
+            signed_feed_request(&format!("/projects/{id}/tasks"), Some("signature=abc")),
+            None
+        );
+        assert_eq!(
+            signed_feed_request("/projects/not-a-uuid/calendar.ics", Some("signature=abc")),
+            None
+        );
+    }
+
+    #[test]
+    fn session_token_prefers_the_authorization_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; vk_session=from-cookie"),
+        );
+        assert_eq!(session_token(&headers).as_deref(), Some("from-cookie"));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer from-header"),
+        );
+        assert_eq!(session_token(&headers).as_deref(), Some("from-header"));
+
+        assert_eq!(session_token(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn activity_is_read_as_the_user_or_else_the_installation() {
+        assert_eq!(CurrentUser(None).reader_id("npm_user_1"), "npm_user_1");
+
+        let user = User {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            display_name: "Alice".to_string(),
+            role: UserRole::Viewer,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let id = user.id;
+        assert_eq!(
+            CurrentUser(Some(user)).reader_id("npm_user_1"),
+            id.to_string()
+        );
+    }
+}
@@ -1,3 +1,11 @@
+pub mod audit;
+pub mod auth;
 pub mod model_loaders;
+pub mod proxy;
+pub mod rate_limit;
 
+pub use audit::*;
+pub use auth::*;
 pub use model_loaders::*;
+pub use proxy::*;
+pub use rate_limit::*;
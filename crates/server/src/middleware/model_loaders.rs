@@ -5,8 +5,8 @@ use axum::{
     response::Response,
 };
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, task::Task, task_attempt::TaskAttempt,
-    task_template::TaskTemplate,
+    execution_process::ExecutionProcess, project::Project, prompt_snippet::PromptSnippet,
+    task::Task, task_attempt::TaskAttempt, task_template::TaskTemplate,
 };
 use deployment::Deployment;
 use uuid::Uuid;
@@ -203,3 +203,26 @@ pub async fn load_task_template_middleware(
     // Continue with the next middleware/handler
     Ok(next.run(request).await)
 }
+
+// Middleware that loads and injects PromptSnippet based on the snippet_id path parameter
+pub async fn load_prompt_snippet_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(snippet_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let snippet = match PromptSnippet::find_by_id(&deployment.db().pool, snippet_id).await {
+        Ok(Some(snippet)) => snippet,
+        Ok(None) => {
+            tracing::warn!("PromptSnippet {} not found", snippet_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch prompt snippet {}: {}", snippet_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(snippet);
+    Ok(next.run(request).await)
+}
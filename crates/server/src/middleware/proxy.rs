@@ -0,0 +1,278 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+
+/// A proxy address or network from `TRUSTED_PROXIES`, e.g. `10.0.0.5` or `172.17.0.0/16`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrustedProxy {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl TrustedProxy {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (address, prefix_len) = match value.trim().split_once('/') {
+            Some((address, len)) => (address, Some(len.parse::<u8>().ok()?)),
+            None => (value.trim(), None),
+        };
+        let network: IpAddr = address.parse().ok()?;
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix_len.unwrap_or(max_len);
+        (prefix_len <= max_len).then_some(Self {
+            network,
+            prefix_len,
+        })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// `/kanban/` and `kanban` become `/kanban`; empty, `/` and prefixes with characters that have no
+/// place in a URL path become `""`
+pub fn normalize_base_path(raw: &str) -> String {
+    let segments: Vec<&str> = raw.split('/').filter(|s| !s.is_empty()).collect();
+    let valid = segments.iter().all(|segment| {
+        segment != &"."
+            && segment != &".."
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '~'))
+    });
+    if !valid {
+        return String::new();
+    }
+    segments.iter().map(|s| format!("/{s}")).collect()
+}
+
+/// How the server is reached through a reverse proxy. Read from `BASE_PATH`, the prefix the app
+/// is served under, and `TRUSTED_PROXIES`, comma separated addresses or networks whose
+/// `X-Forwarded-*` headers are believed. Proxies on this machine are always trusted.
+#[derive(Debug, Clone, Default)]
+pub struct ProxyConfig {
+    pub base_path: String,
+    pub trusted_proxies: Vec<TrustedProxy>,
+}
+
+impl ProxyConfig {
+    pub fn new(base_path: &str, trusted_proxies: &str) -> Self {
+        let trusted_proxies = trusted_proxies
+            .split(',')
+            .filter(|value| !value.trim().is_empty())
+            .filter_map(|value| {
+                let proxy = TrustedProxy::parse(value);
+                if proxy.is_none() {
+                    tracing::warn!("Ignoring invalid TRUSTED_PROXIES entry '{}'", value.trim());
+                }
+                proxy
+            })
+            .collect();
+        Self {
+            base_path: normalize_base_path(base_path),
+            trusted_proxies,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(
+            &std::env::var("BASE_PATH").unwrap_or_default(),
+            &std::env::var("TRUSTED_PROXIES").unwrap_or_default(),
+        )
+    }
+
+    pub fn trusts(&self, peer: IpAddr) -> bool {
+        peer.is_loopback()
+            || self
+                .trusted_proxies
+                .iter()
+                .any(|proxy| proxy.contains(peer))
+    }
+
+    /// Client address from `X-Forwarded-For`, read right to left: every proxy appends the address
+    /// it was reached from, so the first hop that is not a trusted proxy is the client. Entries
+    /// further left were supplied by that client and can be anything.
+    fn forwarded_client(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Option<IpAddr> {
+        let hops: Vec<&str> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|hop| !hop.is_empty())
+            .collect();
+        let mut client = peer;
+        for hop in hops.iter().rev() {
+            let Ok(ip) = hop.parse::<IpAddr>() else {
+                break;
+            };
+            client = Some(ip);
+            if !self.trusts(ip) {
+                break;
+            }
+        }
+        client
+    }
+
+    /// The request as the client made it: forwarded headers are used when `peer` is a trusted
+    /// proxy and ignored otherwise
+    pub fn resolve(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> ForwardedRequest {
+        let trusted = peer.is_none_or(|peer| self.trusts(peer));
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.split(',').next())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let client_ip = if trusted {
+            self.forwarded_client(headers, peer)
+        } else {
+            peer
+        };
+        let secure = trusted && header("x-forwarded-proto") == Some("https");
+        // A proxy that strips the prefix before passing requests on says what it was
+        let base_path = match header("x-forwarded-prefix") {
+            Some(prefix) if trusted && self.base_path.is_empty() => normalize_base_path(prefix),
+            _ => self.base_path.clone(),
+        };
+        ForwardedRequest {
+            client_ip,
+            secure,
+            base_path,
+        }
+    }
+}
+
+/// Where a request came from once trusted proxies are accounted for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForwardedRequest {
+    pub client_ip: Option<IpAddr>,
+    /// Whether the client connected over HTTPS
+    pub secure: bool,
+    /// Prefix the browser sees the app under, `""` at the root
+    pub base_path: String,
+}
+
+pub async fn forwarded_middleware(
+    State(proxy): State<Arc<ProxyConfig>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let forwarded = proxy.resolve(request.headers(), peer);
+    request.extensions_mut().insert(forwarded);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn base_paths_are_normalized() {
+        assert_eq!(normalize_base_path("/kanban/"), "/kanban");
+        assert_eq!(normalize_base_path("kanban"), "/kanban");
+        assert_eq!(normalize_base_path("//tools//kanban"), "/tools/kanban");
+        assert_eq!(normalize_base_path("/"), "");
+        assert_eq!(normalize_base_path(""), "");
+        assert_eq!(normalize_base_path("/../etc"), "");
+        assert_eq!(normalize_base_path("/\"><script>"), "");
+    }
+
+    #[test]
+    fn forwarded_headers_are_only_believed_from_trusted_proxies() {
+        let proxy = ProxyConfig::new("/kanban/", "10.0.0.5, 172.17.0.0/16, nonsense");
+        assert_eq!(proxy.trusted_proxies.len(), 2);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("203.0.113.7, 10.0.0.5"),
+        );
+        headers.insert("x-forwarded-proto", HeaderValue::from_static("https"));
+
+        for peer in ["127.0.0.1", "10.0.0.5", "172.17.3.4"] {
+            let forwarded = proxy.resolve(&headers, Some(peer.parse().unwrap()));
+            assert_eq!(forwarded.client_ip, Some("203.0.113.7".parse().unwrap()));
+            assert!(forwarded.secure);
+            assert_eq!(forwarded.base_path, "/kanban");
+        }
+
+        let stranger: IpAddr = "192.168.1.20".parse().unwrap();
+        let forwarded = proxy.resolve(&headers, Some(stranger));
+        assert_eq!(forwarded.client_ip, Some(stranger));
+        assert!(!forwarded.secure);
+    }
+
+    #[test]
+    fn forwarded_for_is_read_from_the_nearest_untrusted_hop() {
+        let proxy = ProxyConfig::new("", "10.0.0.0/8");
+        let peer = Some("10.0.0.5".parse().unwrap());
+        let client_ip = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-forwarded-for", HeaderValue::from_static(value));
+            proxy.resolve(&headers, peer).client_ip
+        };
+
+        // A client can put anything on the left; the hop our proxy saw is the one that counts
+        assert_eq!(
+            client_ip("1.1.1.1, 203.0.113.7, 10.0.0.9"),
+            Some("203.0.113.7".parse().unwrap())
+        );
+        assert_eq!(
+            client_ip("spoofed, 203.0.113.7"),
+            Some("203.0.113.7".parse().unwrap())
+        );
+        // Every hop is one of ours
+        assert_eq!(
+            client_ip("10.1.2.3, 10.0.0.9"),
+            Some("10.1.2.3".parse().unwrap())
+        );
+        assert_eq!(client_ip("garbage"), peer);
+    }
+
+    #[test]
+    fn stripped_prefixes_come_from_the_proxy() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-prefix", HeaderValue::from_static("/kanban/"));
+        let loopback = Some("127.0.0.1".parse().unwrap());
+
+        let forwarded = ProxyConfig::default().resolve(&headers, loopback);
+        assert_eq!(forwarded.base_path, "/kanban");
+        // A configured base path wins
+        let forwarded = ProxyConfig::new("/boards", "").resolve(&headers, loopback);
+        assert_eq!(forwarded.base_path, "/boards");
+        let forwarded =
+            ProxyConfig::default().resolve(&headers, Some("192.168.1.20".parse().unwrap()));
+        assert_eq!(forwarded.base_path, "");
+    }
+}
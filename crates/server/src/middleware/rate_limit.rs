@@ -0,0 +1,260 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use db::models::user_session::UserSession;
+use deployment::Deployment;
+use services::services::users::{hash_session_token, is_agent_token};
+use utils::response::ApiResponse;
+
+use crate::{
+    DeploymentImpl,
+    middleware::{ForwardedRequest, session_token},
+};
+
+pub const LIMIT_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+pub const REMAINING_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+
+/// Past this many clients, those whose allowance has refilled are forgotten
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    /// Requests the client can still make right away
+    pub remaining: u32,
+    /// When a refused client can try again
+    pub retry_after: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Token buckets per client: each holds up to a minute's allowance and refills continuously, so
+/// bursts are allowed but the average rate is capped.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a request by `client` at `now` against `per_minute`
+    pub fn check(&self, client: &str, per_minute: u32, now: Instant) -> RateLimitDecision {
+        let capacity = f64::from(per_minute.max(1));
+        let refill_per_sec = capacity / 60.0;
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if buckets.len() >= MAX_TRACKED_CLIENTS {
+            buckets.retain(|_, bucket| {
+                now.saturating_duration_since(bucket.updated_at) < Duration::from_secs(60)
+            });
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * refill_per_sec).min(capacity);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            RateLimitDecision {
+                allowed: true,
+                remaining: bucket.tokens as u32,
+                retry_after: None,
+            }
+        } else {
+            let wait = (1.0 - bucket.tokens) / refill_per_sec;
+            RateLimitDecision {
+                allowed: false,
+                remaining: 0,
+                retry_after: Some(Duration::from_secs(wait.ceil() as u64)),
+            }
+        }
+    }
+}
+
+/// Who to count a request against: the hash of a session the caller has shown to be valid,
+/// otherwise the client address as resolved through trusted proxies. `None` for requests made
+/// directly on this machine, signed in or not.
+pub fn client_key(client_ip: Option<IpAddr>, valid_session: Option<&str>) -> Option<String> {
+    let client_ip = client_ip.filter(|ip| !ip.is_loopback())?;
+    Some(match valid_session {
+        Some(hash) => format!("session:{hash}"),
+        None => format!("ip:{client_ip}"),
+    })
+}
+
+/// Hash of the request's session token if it belongs to a live session. Made-up tokens would
+/// otherwise each get a fresh allowance. Logging in is always counted by address, so guessing
+/// passwords cannot be spread across sessions.
+async fn valid_session(
+    deployment: &DeploymentImpl,
+    path: &str,
+    headers: &HeaderMap,
+) -> Option<String> {
+    if path.starts_with("/auth/") {
+        return None;
+    }
+    let token = session_token(headers)?;
+    let hash = hash_session_token(&token);
+    if is_agent_token(&token) {
+        return Some(hash);
+    }
+    match UserSession::find_user(&deployment.db().pool, &hash, Utc::now()).await {
+        Ok(Some(_)) => Some(hash),
+        Ok(None) => None,
+        Err(e) => {
+            tracing::warn!("Failed to look up session for rate limiting: {}", e);
+            None
+        }
+    }
+}
+
+/// Refuses requests that change things with 429 once a client exceeds the configured rate.
+/// Reads are never limited.
+pub async fn rate_limit_middleware(
+    State((deployment, limiter)): State<(DeploymentImpl, Arc<RateLimiter>)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) {
+        return next.run(request).await;
+    }
+    let config = deployment.config().read().await.rate_limit.clone();
+    if !config.enabled {
+        return next.run(request).await;
+    }
+
+    let client_ip = match request.extensions().get::<ForwardedRequest>() {
+        Some(forwarded) => forwarded.client_ip,
+        None => request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip()),
+    };
+    let session = match client_ip {
+        Some(ip) if !ip.is_loopback() => {
+            valid_session(&deployment, request.uri().path(), request.headers()).await
+        }
+        _ => None,
+    };
+    let key = match client_key(client_ip, session.as_deref()) {
+        Some(key) => key,
+        None if config.exempt_local => return next.run(request).await,
+        None => "local".to_string(),
+    };
+
+    let decision = limiter.check(&key, config.requests_per_minute, Instant::now());
+    let limit = HeaderValue::from(config.requests_per_minute);
+    let remaining = HeaderValue::from(decision.remaining);
+    if !decision.allowed {
+        let retry_after = decision.retry_after.unwrap_or_default().as_secs().max(1);
+        tracing::debug!("Rate limited {} for {}s", key, retry_after);
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [
+                (LIMIT_HEADER, limit),
+                (REMAINING_HEADER, remaining),
+                (header::RETRY_AFTER, HeaderValue::from(retry_after)),
+            ],
+            Json(ApiResponse::<()>::error(
+                "Too many requests, please try again shortly",
+            )),
+        )
+            .into_response();
+    }
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(LIMIT_HEADER, limit);
+    headers.insert(REMAINING_HEADER, remaining);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::ProxyConfig;
+
+    #[test]
+    fn clients_get_a_burst_then_the_refill_rate() {
+        let limiter = RateLimiter::new();
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check("ip:10.0.0.2", 3, start).allowed);
+        }
+        let refused = limiter.check("ip:10.0.0.2", 3, start);
+        assert!(!refused.allowed);
+        assert_eq!(refused.retry_after, Some(Duration::from_secs(20)));
+        // Other clients have their own allowance
+        assert!(limiter.check("ip:10.0.0.3", 3, start).allowed);
+
+        let later = limiter.check("ip:10.0.0.2", 3, start + Duration::from_secs(21));
+        assert!(later.allowed);
+        assert_eq!(later.remaining, 0);
+    }
+
+    #[test]
+    fn local_requests_are_only_limited_when_forwarded() {
+        let proxy = ProxyConfig::default();
+        let key = |headers: &HeaderMap, peer: IpAddr| {
+            client_key(proxy.resolve(headers, Some(peer)).client_ip, None)
+        };
+        let loopback: IpAddr = "127.0.0.1".parse().unwrap();
+        let lan: IpAddr = "192.168.1.20".parse().unwrap();
+        assert_eq!(key(&HeaderMap::new(), loopback), None);
+        assert_eq!(
+            key(&HeaderMap::new(), lan).as_deref(),
+            Some("ip:192.168.1.20")
+        );
+
+        let mut forwarded = HeaderMap::new();
+        forwarded.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("198.51.100.2, 203.0.113.7"),
+        );
+        assert_eq!(key(&forwarded, loopback).as_deref(), Some("ip:203.0.113.7"));
+        // Only a trusted proxy is believed about who the client is
+        assert_eq!(key(&forwarded, lan).as_deref(), Some("ip:192.168.1.20"));
+    }
+
+    #[test]
+    fn validated_sessions_are_counted_apart_from_their_address() {
+        let lan: IpAddr = "192.168.1.20".parse().unwrap();
+        assert_eq!(
+            client_key(Some(lan), Some("abc")).as_deref(),
+            Some("session:abc")
+        );
+        assert_eq!(
+            client_key(Some(lan), None).as_deref(),
+            Some("ip:192.168.1.20")
+        );
+        assert_eq!(client_key(Some("::1".parse().unwrap()), Some("abc")), None);
+    }
+}
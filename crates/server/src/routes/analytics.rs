@@ -0,0 +1,63 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{Duration, Utc};
+use db::models::local_analytics_event::{
+    DailyRuns, EventCount, ExecutorUsage, LocalAnalyticsEvent,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const DEFAULT_DAYS: i64 = 30;
+const MAX_DAYS: i64 = 365;
+
+#[derive(Debug, Deserialize)]
+pub struct LocalAnalyticsQuery {
+    pub days: Option<i64>,
+}
+
+/// Aggregates over analytics events kept on this machine. Events are only stored locally while
+/// external analytics is disabled.
+#[derive(Debug, Serialize, TS)]
+pub struct LocalAnalyticsSummary {
+    #[ts(type = "number")]
+    pub days: i64,
+    pub runs_per_day: Vec<DailyRuns>,
+    /// Share of finished coding agent runs that succeeded; null when none finished
+    pub success_rate: Option<f64>,
+    pub executor_mix: Vec<ExecutorUsage>,
+    pub event_counts: Vec<EventCount>,
+}
+
+pub async fn get_local_analytics(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<LocalAnalyticsQuery>,
+) -> Result<ResponseJson<ApiResponse<LocalAnalyticsSummary>>, ApiError> {
+    let days = query.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+    let since = Utc::now() - Duration::days(days);
+    let pool = &deployment.db().pool;
+
+    let runs_per_day = LocalAnalyticsEvent::runs_per_day(pool, since).await?;
+    let finished: i64 = runs_per_day.iter().map(|d| d.finished).sum();
+    let succeeded: i64 = runs_per_day.iter().map(|d| d.succeeded).sum();
+    let success_rate = (finished > 0).then(|| succeeded as f64 / finished as f64);
+
+    Ok(ResponseJson(ApiResponse::success(LocalAnalyticsSummary {
+        days,
+        runs_per_day,
+        success_rate,
+        executor_mix: LocalAnalyticsEvent::executor_mix(pool, since).await?,
+        event_counts: LocalAnalyticsEvent::event_counts(pool, since).await?,
+    })))
+}
+
+pub fn router(_: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().route("/analytics/local", get(get_local_analytics))
+}
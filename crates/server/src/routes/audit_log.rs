@@ -0,0 +1,53 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    http::HeaderMap,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::{
+    models::audit_log::{AuditLogEntry, AuditLogFilter},
+    pagination::Page,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, routes::tasks::total_count_headers};
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    pub actor_user_id: Option<Uuid>,
+    /// Collection name as it appears in API paths, e.g. `task-attempts`
+    pub entity_type: Option<String>,
+    pub entity_id: Option<Uuid>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+/// Newest first. The total across pages is in `X-Total-Count`.
+pub async fn get_audit_log(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<(HeaderMap, ResponseJson<ApiResponse<Vec<AuditLogEntry>>>), ApiError> {
+    let filter = AuditLogFilter {
+        actor_user_id: query.actor_user_id,
+        entity_type: query.entity_type,
+        entity_id: query.entity_id,
+    };
+    let (entries, total) = AuditLogEntry::find(
+        &deployment.db().pool,
+        &filter,
+        Page::new(query.limit, query.offset),
+    )
+    .await?;
+    Ok((
+        total_count_headers(total),
+        ResponseJson(ApiResponse::success(entries)),
+    ))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/audit-log", get(get_audit_log))
+}
@@ -13,6 +13,7 @@ use services::services::{
     auth::{AuthError, DeviceFlowStartResponse},
     config::save_config_to_file,
     github_service::{GitHubService, GitHubServiceError},
+    gitlab_service::{GitLabService, GitLabServiceError},
 };
 use utils::response::ApiResponse;
 
@@ -23,6 +24,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/auth/github/device/start", post(device_start))
         .route("/auth/github/device/poll", post(device_poll))
         .route("/auth/github/check", get(github_check_token))
+        .route("/auth/gitlab/check", get(gitlab_check_token))
         .layer(from_fn_with_state(
             deployment.clone(),
             sentry_user_context_middleware,
@@ -117,6 +119,28 @@ async fn github_check_token(
     }
 }
 
+/// GET /auth/gitlab/check
+async fn gitlab_check_token(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<CheckTokenResponse>>, ApiError> {
+    let gl_config = deployment.config().read().await.gitlab.clone();
+    let Some(token) = gl_config.token() else {
+        return Ok(ResponseJson(ApiResponse::success(
+            CheckTokenResponse::Invalid,
+        )));
+    };
+    let gl = GitLabService::new(&token)?;
+    match gl.check_token(&gl_config.resolved_base_url()).await {
+        Ok(()) => Ok(ResponseJson(ApiResponse::success(
+            CheckTokenResponse::Valid,
+        ))),
+        Err(GitLabServiceError::TokenInvalid) => Ok(ResponseJson(ApiResponse::success(
+            CheckTokenResponse::Invalid,
+        ))),
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// Middleware to set Sentry user context for every request
 pub async fn sentry_user_context_middleware(
     State(deployment): State<DeploymentImpl>,
@@ -0,0 +1,21 @@
+//! Monthly usage budgets per coding agent and how much of each has been used. The budgets
+//! themselves are part of the config.
+
+use axum::{Router, extract::State, response::Json as ResponseJson, routing::get};
+use deployment::Deployment;
+use services::services::budgets::{BudgetStatus, budget_statuses};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn get_budgets(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<BudgetStatus>>>, ApiError> {
+    let config = deployment.config().read().await.clone();
+    let statuses = budget_statuses(&deployment.db().pool, &config).await?;
+    Ok(ResponseJson(ApiResponse::success(statuses)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/budgets", get(get_budgets))
+}
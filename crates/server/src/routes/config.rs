@@ -1,16 +1,17 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     body::Body,
     extract::{Path, Query, State},
     http,
     response::{Json as ResponseJson, Response},
     routing::{get, put},
 };
+use db::models::user::UserRole;
 use deployment::{Deployment, DeploymentError};
 use executors::{
-    executors::{BaseAgentCapability, BaseCodingAgent, StandardCodingAgentExecutor},
+    executors::{BaseAgentCapability, BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor},
     mcp_config::{McpConfig, read_agent_config, write_agent_config},
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
@@ -21,7 +22,11 @@ use tokio::fs;
 use ts_rs::TS;
 use utils::{assets::config_path, response::ApiResponse};
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{CurrentUser, InternalCaller},
+};
 
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
@@ -29,6 +34,11 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/config", put(update_config))
         .route("/sounds/{sound}", get(get_sound))
         .route("/mcp-config", get(get_mcp_servers).post(update_mcp_servers))
+        .route("/mcp-config/servers", get(list_mcp_servers))
+        .route(
+            "/mcp-config/servers/{name}",
+            put(upsert_mcp_server).delete(delete_mcp_server),
+        )
         .route("/profiles", get(get_profiles).put(update_profiles))
 }
 
@@ -72,11 +82,23 @@ pub struct UserSystemInfo {
 #[axum::debug_handler]
 async fn get_user_system_info(
     State(deployment): State<DeploymentImpl>,
+    Extension(current_user): Extension<CurrentUser>,
+    internal: Option<Extension<InternalCaller>>,
 ) -> ResponseJson<ApiResponse<UserSystemInfo>> {
     let config = deployment.config().read().await;
+    // Credentials are only shown to whoever may change them: an admin, or anyone while no
+    // accounts exist
+    let can_see_secrets = match &current_user.0 {
+        Some(user) => user.role.allows(UserRole::Admin),
+        None => internal.is_none(),
+    };
 
     let user_system_info = UserSystemInfo {
-        config: config.clone(),
+        config: if can_see_secrets {
+            config.clone()
+        } else {
+            config.without_secrets()
+        },
         profiles: ExecutorConfigs::get_cached(),
         environment: Environment::new(),
         capabilities: {
@@ -319,6 +341,178 @@ async fn update_mcp_servers_in_config(
     Ok(message)
 }
 
+#[derive(TS, Debug, Serialize, Deserialize)]
+pub struct McpServerEntry {
+    pub name: String,
+    /// Server definition as written to the agent's config file.
+    pub config: Value,
+}
+
+#[derive(TS, Debug, Serialize, Deserialize)]
+pub struct McpServerList {
+    pub config_path: String,
+    pub servers: Vec<McpServerEntry>,
+}
+
+#[derive(TS, Debug, Serialize, Deserialize)]
+pub struct UpsertMcpServerBody {
+    /// Canonical (Claude-style) definition: `{ command, args?, env? }` for stdio servers or
+    /// `{ type: "http", url, headers? }` for HTTP servers. It is converted to the agent's own
+    /// format before being written.
+    pub server: Value,
+}
+
+/// An agent that supports MCP together with the config file its servers live in.
+struct McpTarget {
+    agent: CodingAgent,
+    mcp_config: McpConfig,
+    config_path: PathBuf,
+}
+
+/// Resolve the MCP target for an executor. The inner error is a user-facing message for
+/// executors that can't be configured.
+fn resolve_mcp_target(executor: BaseCodingAgent) -> Result<McpTarget, ApiError> {
+    let agent = ExecutorConfigs::get_cached()
+        .get_coding_agent(&ExecutorProfileId::new(executor))
+        .ok_or(ConfigError::ValidationError(
+            "Executor not found".to_string(),
+        ))?;
+
+    if !agent.supports_mcp() {
+        return Err(ConfigError::ValidationError(format!(
+            "{executor} does not support MCP servers"
+        ))
+        .into());
+    }
+    let Some(config_path) = agent.default_mcp_config_path() else {
+        return Err(ConfigError::ValidationError(
+            "Could not determine config file path".to_string(),
+        )
+        .into());
+    };
+
+    let mcp_config = agent.get_mcp_config();
+    Ok(McpTarget {
+        agent,
+        mcp_config,
+        config_path: config_path.to_path_buf(),
+    })
+}
+
+async fn read_mcp_servers(target: &McpTarget) -> Result<HashMap<String, Value>, ApiError> {
+    let raw_config = read_agent_config(&target.config_path, &target.mcp_config).await?;
+    Ok(get_mcp_servers_from_config_path(
+        &raw_config,
+        &target.mcp_config.servers_path,
+    ))
+}
+
+fn sorted_mcp_entries(servers: HashMap<String, Value>) -> Vec<McpServerEntry> {
+    let mut entries: Vec<McpServerEntry> = servers
+        .into_iter()
+        .map(|(name, config)| McpServerEntry { name, config })
+        .collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries
+}
+
+fn validate_mcp_server(name: &str, server: &Value) -> Result<(), String> {
+    if name.trim().is_empty() || name.trim() != name {
+        return Err("Server name must be non-empty without surrounding whitespace".to_string());
+    }
+    if name == "meta" {
+        return Err("\"meta\" is reserved and cannot be used as a server name".to_string());
+    }
+    let Some(obj) = server.as_object() else {
+        return Err("Server definition must be a JSON object".to_string());
+    };
+    let (field, label) = match obj.get("type").and_then(Value::as_str) {
+        Some("http") => ("url", "HTTP"),
+        _ => ("command", "Stdio"),
+    };
+    if obj
+        .get(field)
+        .and_then(Value::as_str)
+        .is_none_or(str::is_empty)
+    {
+        return Err(format!("{label} servers require a non-empty \"{field}\""));
+    }
+    Ok(())
+}
+
+async fn list_mcp_servers(
+    State(_deployment): State<DeploymentImpl>,
+    Query(query): Query<McpServerQuery>,
+) -> Result<ResponseJson<ApiResponse<McpServerList>>, ApiError> {
+    let target = resolve_mcp_target(query.executor)?;
+
+    let servers = read_mcp_servers(&target).await?;
+    Ok(ResponseJson(ApiResponse::success(McpServerList {
+        config_path: target.config_path.to_string_lossy().to_string(),
+        servers: sorted_mcp_entries(servers),
+    })))
+}
+
+async fn upsert_mcp_server(
+    State(_deployment): State<DeploymentImpl>,
+    Path(name): Path<String>,
+    Query(query): Query<McpServerQuery>,
+    Json(payload): Json<UpsertMcpServerBody>,
+) -> Result<ResponseJson<ApiResponse<McpServerEntry>>, ApiError> {
+    let target = resolve_mcp_target(query.executor)?;
+    if let Err(message) = validate_mcp_server(&name, &payload.server) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
+    let Some(config) = target.agent.adapt_mcp_server(&name, payload.server) else {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "{} cannot run this kind of MCP server",
+            query.executor
+        ))));
+    };
+
+    let mut servers = read_mcp_servers(&target).await?;
+    servers.insert(name.clone(), config.clone());
+    if let Err(e) =
+        update_mcp_servers_in_config(&target.config_path, &target.mcp_config, servers).await
+    {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Failed to update MCP servers: {}",
+            e
+        ))));
+    }
+
+    Ok(ResponseJson(ApiResponse::success(McpServerEntry {
+        name,
+        config,
+    })))
+}
+
+async fn delete_mcp_server(
+    State(_deployment): State<DeploymentImpl>,
+    Path(name): Path<String>,
+    Query(query): Query<McpServerQuery>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let target = resolve_mcp_target(query.executor)?;
+
+    let mut servers = read_mcp_servers(&target).await?;
+    if servers.remove(&name).is_none() {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "MCP server '{name}' is not configured"
+        ))));
+    }
+    if let Err(e) =
+        update_mcp_servers_in_config(&target.config_path, &target.mcp_config, servers).await
+    {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Failed to update MCP servers: {}",
+            e
+        ))));
+    }
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 /// Helper function to get MCP servers from config using a path
 fn get_mcp_servers_from_config_path(raw_config: &Value, path: &[String]) -> HashMap<String, Value> {
     let mut current = raw_config;
@@ -404,6 +598,12 @@ async fn update_profiles(
     State(_deployment): State<DeploymentImpl>,
     body: String,
 ) -> ResponseJson<ApiResponse<String>> {
+    if let Ok(raw) = serde_json::from_str::<serde_json::Value>(&body)
+        && let Err(e) = ExecutorConfigs::check_tool_policies(&raw)
+    {
+        return ResponseJson(ApiResponse::error(&e.to_string()));
+    }
+
     // Try to parse as ExecutorProfileConfigs format
     match serde_json::from_str::<ExecutorConfigs>(&body) {
         Ok(executor_profiles) => {
@@ -432,3 +632,42 @@ async fn update_profiles(
         ))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn validates_mcp_server_definitions() {
+        assert!(validate_mcp_server("fs", &json!({"command": "npx", "args": ["fs"]})).is_ok());
+        assert!(validate_mcp_server("docs", &json!({"type": "http", "url": "https://x"})).is_ok());
+
+        assert!(validate_mcp_server("", &json!({"command": "npx"})).is_err());
+        assert!(validate_mcp_server(" fs", &json!({"command": "npx"})).is_err());
+        assert!(validate_mcp_server("meta", &json!({"command": "npx"})).is_err());
+        assert!(validate_mcp_server("fs", &json!(["npx"])).is_err());
+        assert!(validate_mcp_server("fs", &json!({"args": ["fs"]})).is_err());
+        assert!(validate_mcp_server("docs", &json!({"type": "http"})).is_err());
+    }
+
+    #[test]
+    fn nested_server_path_round_trips() {
+        let path = vec!["mcp".to_string(), "servers".to_string()];
+        let mut config = json!({"theme": "dark"});
+        let servers = HashMap::from([("fs".to_string(), json!({"command": "npx"}))]);
+
+        set_mcp_servers_in_config_path(&mut config, &path, &servers).unwrap();
+
+        assert_eq!(config["theme"], "dark");
+        assert_eq!(get_mcp_servers_from_config_path(&config, &path), servers);
+        assert_eq!(
+            sorted_mcp_entries(servers)
+                .into_iter()
+                .map(|e| e.name)
+                .collect::<Vec<_>>(),
+            vec!["fs".to_string()]
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! Estimated dollar cost of coding agent runs, per execution process and rolled up per attempt,
+//! task and project. Prices come from the `model_pricing` setting and a built-in list.
+
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use db::models::{
+    execution_process::ExecutionProcess,
+    execution_process_token_usage::{ExecutionProcessTokenUsage, ModelTokenUsage},
+    project::Project,
+    task::Task,
+    task_attempt::TaskAttempt,
+};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::cost::CostEstimate;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Serialize, TS)]
+pub struct ExecutionProcessCost {
+    pub execution_process_id: Uuid,
+    pub cost: CostEstimate,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct TaskAttemptCost {
+    pub total: CostEstimate,
+    /// Processes that reported token usage, oldest first
+    pub execution_processes: Vec<ExecutionProcessCost>,
+}
+
+async fn estimate(deployment: &DeploymentImpl, usage: Vec<ModelTokenUsage>) -> CostEstimate {
+    let config = deployment.config().read().await;
+    CostEstimate::new(usage, &config.model_pricing)
+}
+
+pub async fn get_execution_process_cost(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<CostEstimate>>, ApiError> {
+    let usage = ExecutionProcessTokenUsage::find_by_execution_process_id(
+        &deployment.db().pool,
+        execution_process.id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(
+        estimate(&deployment, usage).await,
+    )))
+}
+
+pub async fn get_task_attempt_cost(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskAttemptCost>>, ApiError> {
+    let rows =
+        ExecutionProcessTokenUsage::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id)
+            .await?;
+
+    let mut totals: Vec<ModelTokenUsage> = Vec::new();
+    let mut by_process: Vec<(Uuid, Vec<ModelTokenUsage>)> = Vec::new();
+    for row in rows {
+        match totals
+            .iter_mut()
+            .find(|total| total.model == row.usage.model)
+        {
+            Some(total) => total.add(&row.usage),
+            None => totals.push(row.usage.clone()),
+        }
+        match by_process.last_mut() {
+            Some((id, usage)) if *id == row.execution_process_id => usage.push(row.usage),
+            _ => by_process.push((row.execution_process_id, vec![row.usage])),
+        }
+    }
+    totals.sort_by(|a, b| a.model.cmp(&b.model));
+
+    let mut execution_processes = Vec::with_capacity(by_process.len());
+    for (execution_process_id, usage) in by_process {
+        execution_processes.push(ExecutionProcessCost {
+            execution_process_id,
+            cost: estimate(&deployment, usage).await,
+        });
+    }
+    Ok(ResponseJson(ApiResponse::success(TaskAttemptCost {
+        total: estimate(&deployment, totals).await,
+        execution_processes,
+    })))
+}
+
+pub async fn get_task_cost(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<CostEstimate>>, ApiError> {
+    let usage = ExecutionProcessTokenUsage::sum_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        estimate(&deployment, usage).await,
+    )))
+}
+
+pub async fn get_project_cost(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<CostEstimate>>, ApiError> {
+    let usage =
+        ExecutionProcessTokenUsage::sum_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        estimate(&deployment, usage).await,
+    )))
+}
@@ -0,0 +1,74 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::{maintenance::DatabaseMaintenance, stats::DatabaseStats};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::db_backup::{self, DatabaseBackup};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Backups in the configured directory, newest first
+pub async fn get_database_backups(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DatabaseBackup>>>, ApiError> {
+    let config = deployment.config().read().await.database_backup.clone();
+    let backups = db_backup::list_backups(&db_backup::backup_dir(&config))?;
+    Ok(ResponseJson(ApiResponse::success(backups)))
+}
+
+/// Back the database up now, whether or not scheduled backups are enabled
+pub async fn create_database_backup(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<DatabaseBackup>>, ApiError> {
+    let config = deployment.config().read().await.database_backup.clone();
+    let backup = db_backup::create_backup(deployment.db(), &config).await?;
+    deployment
+        .track_if_analytics_allowed(
+            "database_backup_created",
+            serde_json::json!({ "size_bytes": backup.size_bytes }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(backup)))
+}
+
+/// File, write-ahead log and per-table sizes
+pub async fn get_database_stats(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<DatabaseStats>>, ApiError> {
+    let stats = deployment.db().stats().await?;
+    Ok(ResponseJson(ApiResponse::success(stats)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MaintenanceQuery {
+    /// Rebuild a database created without incremental vacuum so free pages can be released
+    #[serde(default)]
+    pub convert_to_incremental: bool,
+}
+
+/// Run database maintenance now, whether or not the scheduled run is enabled
+pub async fn run_database_maintenance(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<MaintenanceQuery>,
+) -> Result<ResponseJson<ApiResponse<DatabaseMaintenance>>, ApiError> {
+    let report = deployment
+        .db()
+        .run_maintenance(query.convert_to_incremental)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/database/backups",
+            get(get_database_backups).post(create_database_backup),
+        )
+        .route("/database/maintenance", post(run_database_maintenance))
+        .route("/database/stats", get(get_database_stats))
+}
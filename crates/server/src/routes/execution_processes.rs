@@ -1,3 +1,5 @@
+use std::{collections::BTreeMap, time::Duration};
+
 use anyhow;
 use axum::{
     Extension, Router,
@@ -5,21 +7,31 @@ use axum::{
         Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
+    http::header,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
     routing::{get, post},
 };
-use db::models::execution_process::{
-    ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus,
+use db::models::{
+    dev_server_port::DevServerPort,
+    execution_process::{
+        ExecutionProcess, ExecutionProcessError, ExecutionProcessRunReason, ExecutionProcessStatus,
+    },
+    execution_process_replay::ExecutionProcessReplay,
+    task_attempt::TaskAttemptError,
 };
 use deployment::Deployment;
-use futures_util::{SinkExt, StreamExt, TryStreamExt};
-use serde::Deserialize;
+use executors::logs::{NormalizedEntry, utils::patch::extract_normalized_entry_from_patch};
+use futures_util::{SinkExt, StreamExt, TryStreamExt, stream::BoxStream};
+use serde::{Deserialize, Serialize};
 use services::services::container::ContainerService;
-use utils::{log_msg::LogMsg, response::ApiResponse};
+use sqlx::Error as SqlxError;
+use utils::{diff::Diff, log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware, routes::costs,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct ExecutionProcessQuery {
@@ -51,6 +63,217 @@ pub async fn get_execution_process_by_id(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+/// Port and URL assigned to a dev server process; None for other processes
+pub async fn get_dev_server_port(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<DevServerPort>>>, ApiError> {
+    let port =
+        DevServerPort::find_by_execution_process_id(&deployment.db().pool, execution_process.id)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(port)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExecutionProcessReplayQuery {
+    pub task_attempt_id: Uuid,
+}
+
+/// Which of the attempt's processes re-ran another one
+pub async fn get_execution_process_replays(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExecutionProcessReplayQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionProcessReplay>>>, ApiError> {
+    let replays = ExecutionProcessReplay::find_by_task_attempt_id(
+        &deployment.db().pool,
+        query.task_attempt_id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(replays)))
+}
+
+/// Run the process's executor action again, with the same prompt and profile, in the attempt's
+/// current worktree. Earlier processes are left as they are, unlike a retry.
+pub async fn replay_execution_process(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task_attempt = execution_process
+        .parent_task_attempt(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    // Dev servers run alongside everything else; anything else would share the worktree
+    let busy = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id, false)
+        .await?
+        .into_iter()
+        .any(|process| {
+            process.status == ExecutionProcessStatus::Running
+                && (process.run_reason != ExecutionProcessRunReason::DevServer
+                    || process.id == execution_process.id)
+        });
+    if busy {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Stop the attempt's running processes before replaying one",
+        )));
+    }
+
+    let action = execution_process
+        .executor_action()
+        .map_err(|e| ApiError::TaskAttempt(TaskAttemptError::ValidationError(e.to_string())))?
+        .clone();
+    deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    let replay = deployment
+        .container()
+        .start_execution(&task_attempt, &action, &execution_process.run_reason)
+        .await?;
+    ExecutionProcessReplay::create(pool, replay.id, execution_process.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "execution_process_replayed",
+            serde_json::json!({
+                "task_attempt_id": task_attempt.id.to_string(),
+                "run_reason": execution_process.run_reason,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(replay)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExecutionProcessDiffQuery {
+    /// Leave file contents out and only report change sizes
+    #[serde(default)]
+    pub stats_only: bool,
+}
+
+/// The changes one execution process made, so each follow-up can be reviewed on its own
+pub async fn get_execution_process_diff(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ExecutionProcessDiffQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Diff>>>, ApiError> {
+    let Some(diffs) = deployment
+        .container()
+        .execution_process_diff(&execution_process, query.stats_only)
+        .await?
+    else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "No commits were recorded for this process",
+        )));
+    };
+    Ok(ResponseJson(ApiResponse::success(diffs)))
+}
+
+/// A running process keeps its log stream open, so downloads stop once no new message arrives
+/// within this window and return what was logged so far
+const LOG_EXPORT_IDLE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize)]
+pub struct ExecutionProcessLogExport {
+    pub execution_process: ExecutionProcess,
+    pub entries: Vec<NormalizedEntry>,
+}
+
+async fn collect_log_messages(
+    mut stream: BoxStream<'static, Result<LogMsg, std::io::Error>>,
+) -> Result<Vec<LogMsg>, ApiError> {
+    let mut messages = Vec::new();
+    while let Ok(Some(item)) = tokio::time::timeout(LOG_EXPORT_IDLE_TIMEOUT, stream.next()).await {
+        match item {
+            Ok(LogMsg::Finished) => break,
+            Ok(msg) => messages.push(msg),
+            Err(e) => return Err(ApiError::Io(e)),
+        }
+    }
+    Ok(messages)
+}
+
+fn raw_log_text(messages: &[LogMsg]) -> String {
+    messages
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::Stdout(content) | LogMsg::Stderr(content) => Some(content.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Later patches for the same entry replace earlier ones, so only the final state of each entry
+/// is kept
+fn normalized_entries(messages: &[LogMsg]) -> Vec<NormalizedEntry> {
+    let mut entries = BTreeMap::new();
+    for msg in messages {
+        if let LogMsg::JsonPatch(patch) = msg
+            && let Some((index, entry)) = extract_normalized_entry_from_patch(patch)
+        {
+            entries.insert(index, entry);
+        }
+    }
+    entries.into_values().collect()
+}
+
+/// Download the process's stdout and stderr, interleaved as they were written
+pub async fn download_raw_logs(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<impl IntoResponse, ApiError> {
+    let stream = deployment
+        .container()
+        .stream_raw_logs(&execution_process.id)
+        .await
+        .ok_or_else(|| {
+            ApiError::ExecutionProcess(ExecutionProcessError::ExecutionProcessNotFound)
+        })?;
+    let messages = collect_log_messages(stream).await?;
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/plain; charset=utf-8".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.log\"", execution_process.id),
+            ),
+        ],
+        raw_log_text(&messages),
+    ))
+}
+
+/// Download the normalized conversation of the process as JSON
+pub async fn download_normalized_logs(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<impl IntoResponse, ApiError> {
+    let stream = deployment
+        .container()
+        .stream_normalized_logs(&execution_process.id)
+        .await
+        .ok_or_else(|| {
+            ApiError::ExecutionProcess(ExecutionProcessError::ExecutionProcessNotFound)
+        })?;
+    let messages = collect_log_messages(stream).await?;
+    let file_name = format!("{}.json", execution_process.id);
+    let export = ExecutionProcessLogExport {
+        entries: normalized_entries(&messages),
+        execution_process,
+    };
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{file_name}\""),
+        )],
+        ResponseJson(export),
+    ))
+}
+
 pub async fn stream_raw_logs_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -107,7 +330,9 @@ async fn handle_raw_logs_ws(
                 LogMsg::JsonPatch(patch).to_ws_message_unchecked()
             }
             LogMsg::Finished => LogMsg::Finished.to_ws_message_unchecked(),
-            _ => unreachable!("Raw stream should only have Stdout/Stderr/Finished"),
+            // Resource usage samples are already patches
+            LogMsg::JsonPatch(patch) => LogMsg::JsonPatch(patch).to_ws_message_unchecked(),
+            _ => unreachable!("Raw stream should only have Stdout/Stderr/JsonPatch/Finished"),
         }
     });
 
@@ -251,8 +476,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_attempt_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
+        .route("/replay", post(replay_execution_process))
+        .route("/dev-server", get(get_dev_server_port))
+        .route("/diff", get(get_execution_process_diff))
+        .route("/cost", get(costs::get_execution_process_cost))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
+        .route("/raw-logs/download", get(download_raw_logs))
+        .route("/normalized-logs/download", get(download_normalized_logs))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_execution_process_middleware,
@@ -260,8 +491,51 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_attempts_router = Router::new()
         .route("/", get(get_execution_processes))
+        .route("/replays", get(get_execution_process_replays))
         .route("/stream/ws", get(stream_execution_processes_ws))
         .nest("/{id}", task_attempt_id_router);
 
     Router::new().nest("/execution-processes", task_attempts_router)
 }
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{NormalizedEntryType, utils::patch::ConversationPatch};
+
+    use super::*;
+
+    fn entry(content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn raw_log_text_interleaves_stdout_and_stderr() {
+        let messages = vec![
+            LogMsg::Stdout("building\n".to_string()),
+            LogMsg::Stderr("warning: unused\n".to_string()),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(0, entry("ignored"))),
+            LogMsg::Stdout("done\n".to_string()),
+        ];
+        assert_eq!(raw_log_text(&messages), "building\nwarning: unused\ndone\n");
+    }
+
+    #[test]
+    fn normalized_entries_keep_the_latest_version_in_order() {
+        let messages = vec![
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(1, entry("second"))),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(0, entry("draft"))),
+            LogMsg::JsonPatch(ConversationPatch::replace(0, entry("first"))),
+            LogMsg::Stdout("not an entry".to_string()),
+        ];
+        let contents: Vec<String> = normalized_entries(&messages)
+            .into_iter()
+            .map(|entry| entry.content)
+            .collect();
+        assert_eq!(contents, vec!["first", "second"]);
+    }
+}
@@ -1,4 +1,5 @@
 use axum::{
+    Extension,
     body::Body,
     http::HeaderValue,
     response::{IntoResponse, Response},
@@ -6,23 +7,45 @@ use axum::{
 use reqwest::{StatusCode, header};
 use rust_embed::RustEmbed;
 
+use crate::middleware::ForwardedRequest;
+
 #[derive(RustEmbed)]
 #[folder = "../../frontend/dist"]
 pub struct Assets;
 
-pub async fn serve_frontend(uri: axum::extract::Path<String>) -> impl IntoResponse {
+pub async fn serve_frontend(
+    Extension(forwarded): Extension<ForwardedRequest>,
+    uri: axum::extract::Path<String>,
+) -> impl IntoResponse {
     let path = uri.trim_start_matches('/');
-    serve_file(path).await
+    serve_file(path, &forwarded.base_path).await
+}
+
+pub async fn serve_frontend_root(
+    Extension(forwarded): Extension<ForwardedRequest>,
+) -> impl IntoResponse {
+    serve_file("index.html", &forwarded.base_path).await
 }
 
-pub async fn serve_frontend_root() -> impl IntoResponse {
-    serve_file("index.html").await
+/// Tells the app where it is served from: assets resolve against the `<base>` element and the
+/// router and API calls read `window.__VIBE_BASE_PATH__`
+fn index_html(index: &[u8], base_path: &str) -> Vec<u8> {
+    let html = String::from_utf8_lossy(index);
+    let tags = format!(
+        "<head>\n    <base href=\"{base_path}/\" />\n    <script>window.__VIBE_BASE_PATH__ = \"{base_path}\";</script>"
+    );
+    html.replacen("<head>", &tags, 1).into_bytes()
 }
 
-async fn serve_file(path: &str) -> impl IntoResponse + use<> {
+async fn serve_file(path: &str, base_path: &str) -> impl IntoResponse + use<> {
     let file = Assets::get(path);
 
     match file {
+        Some(content) if path == "index.html" => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, HeaderValue::from_static("text/html"))
+            .body(Body::from(index_html(&content.data, base_path)))
+            .unwrap(),
         Some(content) => {
             let mime = mime_guess::from_path(path).first_or_octet_stream();
 
@@ -41,7 +64,7 @@ async fn serve_file(path: &str) -> impl IntoResponse + use<> {
                 Response::builder()
                     .status(StatusCode::OK)
                     .header(header::CONTENT_TYPE, HeaderValue::from_static("text/html"))
-                    .body(Body::from(index.data.into_owned()))
+                    .body(Body::from(index_html(&index.data, base_path)))
                     .unwrap()
             } else {
                 Response::builder()
@@ -52,3 +75,20 @@ async fn serve_file(path: &str) -> impl IntoResponse + use<> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_html_points_at_the_base_path() {
+        let index = b"<html><head><title>vibe-kanban</title></head></html>";
+        let html = String::from_utf8(index_html(index, "/kanban")).unwrap();
+        assert!(html.contains(r#"<base href="/kanban/" />"#));
+        assert!(html.contains(r#"window.__VIBE_BASE_PATH__ = "/kanban";"#));
+        assert!(html.find("<base").unwrap() < html.find("<title>").unwrap());
+
+        let html = String::from_utf8(index_html(index, "")).unwrap();
+        assert!(html.contains(r#"<base href="/" />"#));
+    }
+}
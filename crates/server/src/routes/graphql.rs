@@ -0,0 +1,40 @@
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    response::{Html, IntoResponse},
+    routing::get,
+};
+
+use crate::{
+    DeploymentImpl,
+    graphql::{KanbanSchema, schema},
+    middleware::CurrentUser,
+};
+
+pub async fn graphql_query(
+    State(deployment): State<DeploymentImpl>,
+    Extension(schema): Extension<KanbanSchema>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(
+        schema
+            .execute(request.data(deployment).data(current_user))
+            .await,
+    )
+}
+
+/// GraphiQL, for exploring the schema from a browser
+pub async fn graphiql() -> impl IntoResponse {
+    Html(
+        async_graphql::http::GraphiQLSource::build()
+            .endpoint("graphql")
+            .finish(),
+    )
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/graphql", get(graphiql).post(graphql_query))
+        .layer(Extension(schema()))
+}
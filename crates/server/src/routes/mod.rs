@@ -1,32 +1,54 @@
+use std::{net::SocketAddr, sync::Arc};
+
 use axum::{
-    Router,
-    routing::{IntoMakeService, get},
+    Router, extract::connect_info::IntoMakeServiceWithConnectInfo, middleware::from_fn_with_state,
+    routing::get,
 };
 
-use crate::DeploymentImpl;
+use crate::{
+    DeploymentImpl,
+    middleware::{
+        ProxyConfig, RateLimiter, audit_log_middleware, forwarded_middleware,
+        rate_limit_middleware, require_user_middleware,
+    },
+};
 
+pub mod analytics;
 pub mod approvals;
+pub mod audit_log;
 pub mod auth;
+pub mod budgets;
 pub mod config;
 pub mod containers;
+pub mod costs;
+pub mod database;
 pub mod filesystem;
 // pub mod github;
 pub mod drafts;
 pub mod events;
 pub mod execution_processes;
 pub mod frontend;
+#[cfg(feature = "graphql")]
+pub mod graphql;
 pub mod health;
 pub mod images;
 pub mod projects;
+pub mod prompt_snippets;
 pub mod task_attempts;
 pub mod task_templates;
 pub mod tasks;
 pub mod usage;
+pub mod users;
+pub mod webhooks;
 
-pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
+/// Everything is served under `proxy.base_path` when one is set. Requests whose prefix was already
+/// stripped by the proxy are served too.
+pub fn router(
+    deployment: DeploymentImpl,
+    proxy: ProxyConfig,
+) -> IntoMakeServiceWithConnectInfo<Router, SocketAddr> {
     // Create routers with different middleware layers
-    let base_routes = Router::new()
-        .route("/health", get(health::health_check))
+    let protected_routes = Router::new()
         .merge(config::router())
         .merge(containers::router(&deployment))
         .merge(projects::router(&deployment))
@@ -35,17 +57,49 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(task_templates::router(&deployment))
+        .merge(prompt_snippets::router(&deployment))
         .merge(auth::router(&deployment))
         .merge(filesystem::router())
         .merge(events::router(&deployment))
         .merge(approvals::router())
         .merge(usage::router())
-        .nest("/images", images::routes())
+        .merge(budgets::router())
+        .merge(analytics::router(&deployment))
+        .merge(webhooks::router())
+        .merge(users::router())
+        .merge(audit_log::router())
+        .merge(database::router())
+        .nest("/images", images::routes());
+    #[cfg(feature = "graphql")]
+    let protected_routes = protected_routes.merge(graphql::router());
+    // Auditing runs inside the user check, which knows who is asking
+    let protected_routes = protected_routes
+        .layer(from_fn_with_state(deployment.clone(), audit_log_middleware))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            require_user_middleware,
+        ));
+
+    let base_routes = Router::new()
+        .route("/health", get(health::health_check))
+        .merge(users::session_router())
+        .merge(protected_routes)
+        .layer(from_fn_with_state(
+            (deployment.clone(), Arc::new(RateLimiter::new())),
+            rate_limit_middleware,
+        ))
         .with_state(deployment);
 
-    Router::new()
+    let app = Router::new()
         .route("/", get(frontend::serve_frontend_root))
         .route("/{*path}", get(frontend::serve_frontend))
-        .nest("/api", base_routes)
-        .into_make_service()
+        .nest("/api", base_routes);
+    let app = if proxy.base_path.is_empty() {
+        app
+    } else {
+        Router::new().nest(&proxy.base_path, app.clone()).merge(app)
+    };
+
+    app.layer(from_fn_with_state(Arc::new(proxy), forwarded_middleware))
+        .into_make_service_with_connect_info::<SocketAddr>()
 }
@@ -4,34 +4,64 @@ use std::{
 };
 
 pub(crate) mod activity_feed;
+pub(crate) mod bundle;
+pub(crate) mod calendar;
+pub(crate) mod issues;
+pub(crate) mod jira;
+pub(crate) mod labels;
+pub(crate) mod release_notes;
+pub(crate) mod schedules;
+pub(crate) mod stale_branches;
+pub(crate) mod task_import;
 
 use axum::{
     Extension, Json, Router,
-    extract::{Path as AxumPath, Query, State},
+    extract::{DefaultBodyLimit, Path as AxumPath, Query, State},
     http::StatusCode,
     middleware::from_fn_with_state,
     response::Json as ResponseJson,
     routing::{get, post, put},
 };
-use db::models::project::{
-    CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject,
-};
-use db::models::project_repository::{
-    CreateProjectRepository, ProjectRepository, ProjectRepositoryError, UpdateProjectRepository,
+use db::models::{
+    attempt_manual_changes::AttemptManualChanges,
+    project::{
+        CreateProject, Project, ProjectError, ProjectFinalizationSettings,
+        ProjectGitMaintenanceSettings, ProjectPrSettings, ProjectSandboxSettings,
+        ProjectWatcherSettings, ProjectWorktreeSettings, SearchMatchType, SearchResult,
+        UpdateProject,
+    },
+    project_repository::{
+        CreateProjectRepository, ProjectRepository, ProjectRepositoryError, UpdateProjectRepository,
+    },
+    task_attempt::TaskAttempt,
 };
 use deployment::Deployment;
+use executors::sandbox::ResourceLimits;
+use futures_util::{StreamExt, future, stream};
 use ignore::WalkBuilder;
-use serde::Deserialize;
-use services::services::{
-    file_ranker::FileRanker,
-    file_search_cache::{CacheError, SearchMode, SearchQuery},
-    git::{GitBranch, GitRemote},
+use serde::{Deserialize, Serialize};
+use services::{
+    activity_feed::ActivityEventRepository,
+    services::{
+        container::ContainerService,
+        dependency_cache::is_valid_shared_dir,
+        file_ranker::FileRanker,
+        file_search_cache::{CacheError, SearchMode, SearchQuery},
+        filesystem_watcher,
+        git::{GitBranch, GitRemote},
+        pr_template::is_valid_template_path,
+        project_stats::{ProjectActivityKind, ProjectUsageStats},
+    },
 };
+use ts_rs::TS;
 use utils::{path::expand_tilde, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_project_middleware,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{CurrentUser, load_project_middleware},
+    routes::costs,
     websocket::project_events::project_activity_feed_ws,
 };
 
@@ -145,11 +175,47 @@ async fn fetch_results_for_context(
     Ok(results)
 }
 
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectWithUnreadActivity {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub project: Project,
+    /// Events in the project's activity feed the current user has not acknowledged
+    pub unread_activity_count: usize,
+}
+
 pub async fn get_projects(
     State(deployment): State<DeploymentImpl>,
-) -> Result<ResponseJson<ApiResponse<Vec<Project>>>, ApiError> {
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectWithUnreadActivity>>>, ApiError> {
     let projects = Project::find_all(&deployment.db().pool).await?;
-    Ok(ResponseJson(ApiResponse::success(projects)))
+    let repository = {
+        let config = deployment.config().read().await;
+        ActivityEventRepository::from_config(deployment.db().pool.clone(), &config.activity_feed)
+    };
+    let user_id = current_user.id();
+    let reader = current_user.reader_id(deployment.user_id());
+
+    let mut with_unread = Vec::with_capacity(projects.len());
+    for project in projects {
+        // A broken feed should not keep the projects from loading
+        let unread_activity_count = repository
+            .unread_count(project.id, user_id, &reader)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to count unread activity for project {}: {}",
+                    project.id,
+                    e
+                );
+                0
+            });
+        with_unread.push(ProjectWithUnreadActivity {
+            project,
+            unread_activity_count,
+        });
+    }
+    Ok(ResponseJson(ApiResponse::success(with_unread)))
 }
 
 pub async fn get_project(
@@ -467,6 +533,349 @@ pub async fn delete_project_repository(
     }
 }
 
+pub async fn get_project_manual_changes(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptManualChanges>>>, ApiError> {
+    let changes = AttemptManualChanges::list_for_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(changes)))
+}
+
+/// Upper bound on automatic fix follow-ups, so a script that never passes cannot keep the agent
+/// busy indefinitely
+const MAX_AUTO_FIX_ATTEMPTS: u32 = 10;
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct VerifyScriptBody {
+    /// Command run after the coding agent; the task only moves to review if it exits 0
+    pub verify_script: Option<String>,
+    /// Follow-ups sent automatically with the failing output when the cleanup or verify script
+    /// fails after a coding agent run; 0 turns them off
+    #[serde(default)]
+    pub auto_fix_attempts: u32,
+}
+
+pub async fn get_project_verify_script(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<VerifyScriptBody>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let verify_script = Project::find_verify_script(pool, project.id).await?;
+    let auto_fix_attempts = Project::find_auto_fix_attempts(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(VerifyScriptBody {
+        verify_script,
+        auto_fix_attempts,
+    })))
+}
+
+pub async fn update_project_verify_script(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<VerifyScriptBody>,
+) -> Result<ResponseJson<ApiResponse<VerifyScriptBody>>, ApiError> {
+    let verify_script = payload
+        .verify_script
+        .map(|script| script.trim().to_string())
+        .filter(|script| !script.is_empty());
+    if payload.auto_fix_attempts > MAX_AUTO_FIX_ATTEMPTS {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "auto_fix_attempts must be at most {MAX_AUTO_FIX_ATTEMPTS}"
+        ))));
+    }
+    let pool = &deployment.db().pool;
+    Project::set_verify_script(pool, project.id, verify_script.as_deref()).await?;
+    Project::set_auto_fix_attempts(pool, project.id, payload.auto_fix_attempts).await?;
+    Ok(ResponseJson(ApiResponse::success(VerifyScriptBody {
+        verify_script,
+        auto_fix_attempts: payload.auto_fix_attempts,
+    })))
+}
+
+pub async fn get_project_watcher_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectWatcherSettings>>, ApiError> {
+    let settings = Project::find_watcher_settings(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_project_watcher_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ProjectWatcherSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectWatcherSettings>>, ApiError> {
+    for patterns in [&payload.exclude_patterns, &payload.diff_ignore_patterns]
+        .into_iter()
+        .flatten()
+    {
+        if let Err(e) = filesystem_watcher::validate_exclude_patterns(patterns) {
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+        }
+    }
+
+    let pool = &deployment.db().pool;
+    Project::set_watcher_settings(pool, project.id, &payload).await?;
+    let settings = Project::find_watcher_settings(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn get_project_worktree_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectWorktreeSettings>>, ApiError> {
+    let settings = Project::find_worktree_settings(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Applies to worktrees created from now on; existing worktrees are left as they are.
+/// Each pool worktree is a full checkout with its dependencies installed
+const MAX_WARM_POOL_SIZE: u32 = 4;
+
+pub async fn update_project_worktree_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ProjectWorktreeSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectWorktreeSettings>>, ApiError> {
+    if let Some(dir) = payload
+        .shared_dirs
+        .iter()
+        .find(|dir| !is_valid_shared_dir(dir))
+    {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Shared directory '{dir}' must be a path inside the repository"
+        ))));
+    }
+    if payload.warm_pool_size > MAX_WARM_POOL_SIZE {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Warm worktree pool size must be at most {MAX_WARM_POOL_SIZE}"
+        ))));
+    }
+    let pool = &deployment.db().pool;
+    Project::set_worktree_settings(pool, project.id, &payload).await?;
+    deployment.container().refill_worktree_pool(project.id);
+    let settings = Project::find_worktree_settings(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn get_project_git_maintenance_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectGitMaintenanceSettings>>, ApiError> {
+    let settings =
+        Project::find_git_maintenance_settings(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_project_git_maintenance_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ProjectGitMaintenanceSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectGitMaintenanceSettings>>, ApiError> {
+    if payload.interval_hours == 0 {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Maintenance interval must be at least one hour",
+        )));
+    }
+
+    let pool = &deployment.db().pool;
+    Project::set_git_maintenance_settings(pool, project.id, &payload).await?;
+    let settings = Project::find_git_maintenance_settings(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn get_project_finalization_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectFinalizationSettings>>, ApiError> {
+    let settings = Project::find_finalization_settings(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Applies to runs that finish from now on
+pub async fn update_project_finalization_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ProjectFinalizationSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectFinalizationSettings>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Project::set_finalization_settings(pool, project.id, &payload).await?;
+    let settings = Project::find_finalization_settings(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn get_project_pr_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectPrSettings>>, ApiError> {
+    let settings = Project::find_pr_settings(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_project_pr_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ProjectPrSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectPrSettings>>, ApiError> {
+    if let Some(path) = payload.template_path.as_deref()
+        && !path.trim().is_empty()
+        && !is_valid_template_path(path)
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "PR template path must be relative to the repository root",
+        )));
+    }
+    let pool = &deployment.db().pool;
+    Project::set_pr_settings(pool, project.id, &payload).await?;
+    let settings = Project::find_pr_settings(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn get_project_sandbox_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectSandboxSettings>>, ApiError> {
+    let settings = Project::find_sandbox_settings(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Applies to processes started from now on
+pub async fn update_project_sandbox_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ProjectSandboxSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectSandboxSettings>>, ApiError> {
+    if payload.enabled
+        && payload
+            .image
+            .as_deref()
+            .is_none_or(|image| image.trim().is_empty())
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Choose an image to run sandboxed processes in",
+        )));
+    }
+    let pool = &deployment.db().pool;
+    Project::set_sandbox_settings(pool, project.id, &payload).await?;
+    let settings = Project::find_sandbox_settings(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Smallest memory limit accepted, so a typo cannot leave every process killed on start
+const MIN_MEMORY_LIMIT_MB: u32 = 64;
+
+pub async fn get_project_resource_limits(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ResourceLimits>>, ApiError> {
+    let limits = Project::find_resource_limits(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(limits)))
+}
+
+/// Applies to processes started from now on
+pub async fn update_project_resource_limits(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResourceLimits>,
+) -> Result<ResponseJson<ApiResponse<ResourceLimits>>, ApiError> {
+    if payload.cpu_percent == Some(0) {
+        return Ok(ResponseJson(ApiResponse::error(
+            "CPU limit must be at least 1% of a core",
+        )));
+    }
+    if payload
+        .memory_mb
+        .is_some_and(|memory_mb| memory_mb < MIN_MEMORY_LIMIT_MB)
+    {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Memory limit must be at least {MIN_MEMORY_LIMIT_MB} MB"
+        ))));
+    }
+    let pool = &deployment.db().pool;
+    Project::set_resource_limits(pool, project.id, &payload).await?;
+    let limits = Project::find_resource_limits(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(limits)))
+}
+
+/// Usage counters for every project that has seen activity since the server started, busiest first
+pub async fn get_projects_usage_stats(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectUsageStats>>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        deployment.project_stats().all(),
+    )))
+}
+
+pub async fn get_project_usage_stats(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectUsageStats>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        deployment.project_stats().for_project(project.id),
+    )))
+}
+
+/// How many attempt diffs are computed at once for the project diff stats endpoint
+const DIFF_STATS_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Serialize, TS)]
+pub struct AttemptDiffStats {
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Set when the diff could not be computed; the counts are zero in that case
+    pub error: Option<String>,
+}
+
+/// Change sizes for every attempt in the project that still has a worktree, in one call.
+/// Attempts whose worktree has been cleaned up are left out rather than recreated.
+pub async fn get_project_diff_stats(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptDiffStats>>>, ApiError> {
+    let attempts =
+        TaskAttempt::find_active_by_project_id(&deployment.db().pool, project.id).await?;
+
+    let stats: Vec<AttemptDiffStats> = stream::iter(attempts)
+        .map(|attempt| {
+            let deployment = deployment.clone();
+            async move {
+                let mut stats = AttemptDiffStats {
+                    task_attempt_id: attempt.id,
+                    task_id: attempt.task_id,
+                    files_changed: 0,
+                    additions: 0,
+                    deletions: 0,
+                    error: None,
+                };
+                match deployment.container().existing_diff_stats(&attempt).await {
+                    Ok(None) => return None,
+                    Ok(Some(diffs)) => {
+                        stats.files_changed = diffs.len();
+                        stats.additions = diffs.iter().filter_map(|d| d.additions).sum();
+                        stats.deletions = diffs.iter().filter_map(|d| d.deletions).sum();
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to compute diff stats for attempt {}: {}",
+                            attempt.id,
+                            e
+                        );
+                        stats.error = Some(e.to_string());
+                    }
+                }
+                Some(stats)
+            }
+        })
+        .buffered(DIFF_STATS_CONCURRENCY)
+        .filter_map(future::ready)
+        .collect()
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(stats)))
+}
+
 pub async fn get_project_remotes(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
@@ -751,6 +1160,9 @@ pub async fn search_project_files(
             "Query parameter 'q' is required and cannot be empty",
         )));
     }
+    let _search_timer = deployment
+        .project_stats()
+        .start(project.id, ProjectActivityKind::SearchRun);
 
     let mut requested_repo_ids: Vec<Uuid> = Vec::new();
     let mut seen_repo_ids = HashSet::new();
@@ -1041,9 +1453,67 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             get(get_project).put(update_project).delete(delete_project),
         )
         .route("/activity_feed", get(activity_feed::get_activity_feed))
+        .route(
+            "/activity_feed/read-all",
+            post(activity_feed::mark_all_activity_read),
+        )
+        .route(
+            "/activity_feed/{event_id}/read",
+            post(activity_feed::mark_activity_event_read),
+        )
         .route("/activity_feed/ws", get(project_activity_feed_ws))
         .route("/branches", get(get_project_branches))
         .route("/remotes", get(get_project_remotes))
+        .route("/manual-changes", get(get_project_manual_changes))
+        .route("/diff-stats", get(get_project_diff_stats))
+        .route("/usage-stats", get(get_project_usage_stats))
+        .route("/cost", get(costs::get_project_cost))
+        .route("/release-notes", get(release_notes::get_release_notes))
+        .route(
+            "/release-notes/polish",
+            post(release_notes::polish_release_notes),
+        )
+        .route(
+            "/release-notes/polish/{task_attempt_id}",
+            get(release_notes::get_polished_release_notes),
+        )
+        .route("/stale-branches", get(stale_branches::get_stale_branches))
+        .route(
+            "/stale-branches/delete",
+            post(stale_branches::delete_stale_branches),
+        )
+        .route(
+            "/verify-script",
+            get(get_project_verify_script).put(update_project_verify_script),
+        )
+        .route(
+            "/watcher-settings",
+            get(get_project_watcher_settings).put(update_project_watcher_settings),
+        )
+        .route(
+            "/worktree-settings",
+            get(get_project_worktree_settings).put(update_project_worktree_settings),
+        )
+        .route(
+            "/git-maintenance-settings",
+            get(get_project_git_maintenance_settings).put(update_project_git_maintenance_settings),
+        )
+        .route(
+            "/finalization-settings",
+            get(get_project_finalization_settings).put(update_project_finalization_settings),
+        )
+        .route(
+            "/pr-settings",
+            get(get_project_pr_settings).put(update_project_pr_settings),
+        )
+        .route(
+            "/sandbox-settings",
+            get(get_project_sandbox_settings).put(update_project_sandbox_settings),
+        )
+        .route(
+            "/resource-limits",
+            get(get_project_resource_limits).put(update_project_resource_limits),
+        )
         .route(
             "/repositories",
             get(get_project_repositories).post(create_project_repository),
@@ -1052,6 +1522,44 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/repositories/{repo_id}",
             put(update_project_repository).delete(delete_project_repository),
         )
+        .route(
+            "/labels",
+            get(labels::get_labels).post(labels::create_label),
+        )
+        .route(
+            "/labels/{label_id}",
+            put(labels::update_label).delete(labels::delete_label),
+        )
+        .route(
+            "/schedules",
+            get(schedules::get_schedules).post(schedules::create_schedule),
+        )
+        .route(
+            "/schedules/{schedule_id}",
+            put(schedules::update_schedule).delete(schedules::delete_schedule),
+        )
+        .route("/tasks/import", post(task_import::import_tasks))
+        .route(
+            "/issues",
+            get(issues::get_issue_sync)
+                .put(issues::link_issues)
+                .delete(issues::unlink_issues),
+        )
+        .route("/issues/sync", post(issues::sync_issues))
+        .route(
+            "/jira",
+            get(jira::get_jira_sync)
+                .put(jira::upsert_jira_sync)
+                .delete(jira::delete_jira_sync),
+        )
+        .route("/jira/sync", post(jira::sync_jira))
+        .route("/calendar.ics", get(calendar::get_calendar))
+        .route("/calendar-url", get(calendar::get_calendar_url))
+        .route("/export", get(bundle::export_project))
+        .route(
+            "/import",
+            post(bundle::import_project).layer(DefaultBodyLimit::max(bundle::MAX_BUNDLE_BYTES)),
+        )
         .route("/search", get(search_project_files))
         .route("/open-editor", post(open_project_in_editor))
         .layer(from_fn_with_state(
@@ -1061,6 +1569,11 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let projects_router = Router::new()
         .route("/", get(get_projects).post(create_project))
+        .route("/usage-stats", get(get_projects_usage_stats))
+        .route(
+            "/import",
+            post(bundle::import_new_project).layer(DefaultBodyLimit::max(bundle::MAX_BUNDLE_BYTES)),
+        )
         .nest("/{id}", project_id_router);
 
     Router::new().nest("/projects", projects_router)
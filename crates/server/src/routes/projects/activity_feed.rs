@@ -3,18 +3,18 @@ use std::{collections::HashMap, time::Duration};
 use axum::{
     Extension,
     body::Body,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::{
         HeaderMap, HeaderValue, StatusCode,
         header::{ETAG, IF_NONE_MATCH},
     },
-    response::{IntoResponse, Response},
+    response::{IntoResponse, Json as ResponseJson, Response},
 };
-use db::models::project::Project;
+use db::models::{activity_event_read::ActivityEventRead, project::Project};
 use deployment::Deployment;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
-use services::activity_feed::ActivityEventRepository;
+use services::activity_feed::{ActivityEvent, ActivityEventRepository};
 use sha2::{Digest, Sha256};
 use tokio::sync::RwLock;
 use utils::{
@@ -30,6 +30,7 @@ use crate::{
         decode_cursor, paginate_events,
     },
     error::ApiError,
+    middleware::CurrentUser,
 };
 
 static FEED_CACHE: Lazy<RwLock<HashMap<String, CacheEnvelope<ActivityFeedResponse>>>> =
@@ -44,6 +45,7 @@ pub struct ActivityFeedQuery {
 pub async fn get_activity_feed(
     headers: HeaderMap,
     Extension(project): Extension<Project>,
+    Extension(current_user): Extension<CurrentUser>,
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<ActivityFeedQuery>,
 ) -> Result<Response, ApiError> {
@@ -57,9 +59,10 @@ pub async fn get_activity_feed(
     }
 
     let user_id = match scope {
-        ActivityFeedScope::Mine => Uuid::parse_str(deployment.user_id()).ok(),
+        ActivityFeedScope::Mine => current_user.id(),
         ActivityFeedScope::All => None,
     };
+    let reader = current_user.reader_id(deployment.user_id());
 
     let config = deployment.config().read().await;
     let repository =
@@ -79,8 +82,12 @@ pub async fn get_activity_feed(
         None => None,
     };
 
-    let cache_key =
-        activity_feed_cache_key(project.id, &scope.to_string(), query.cursor.as_deref());
+    let cache_key = activity_feed_cache_key(
+        project.id,
+        &reader,
+        &scope.to_string(),
+        query.cursor.as_deref(),
+    );
     let if_none_match = headers
         .get(IF_NONE_MATCH)
         .and_then(|value| value.to_str().ok())
@@ -102,7 +109,7 @@ pub async fn get_activity_feed(
     }
 
     let events = repository
-        .list_recent(project.id, user_id)
+        .list_recent_for_reader(project.id, user_id, &reader)
         .await
         .map_err(map_anyhow_error)?;
     let (page, next_cursor) = paginate_events(events, cursor, FEED_PAGE_SIZE);
@@ -125,6 +132,65 @@ pub async fn get_activity_feed(
     Ok(success_response(response_payload, &etag))
 }
 
+/// Events of the current user's feed, which are the ones they can acknowledge
+async fn readable_events(
+    deployment: &DeploymentImpl,
+    current_user: &CurrentUser,
+    project_id: Uuid,
+) -> Result<Vec<ActivityEvent>, ApiError> {
+    let repository = {
+        let config = deployment.config().read().await;
+        ActivityEventRepository::from_config(deployment.db().pool.clone(), &config.activity_feed)
+    };
+    repository
+        .list_recent(project_id, current_user.id())
+        .await
+        .map_err(map_anyhow_error)
+}
+
+pub async fn mark_activity_event_read(
+    Extension(project): Extension<Project>,
+    Extension(current_user): Extension<CurrentUser>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, event_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let events = readable_events(&deployment, &current_user, project.id).await?;
+    if !events.iter().any(|event| event.event_id == event_id) {
+        return Ok(ResponseJson(ApiResponse::error("Activity event not found")));
+    }
+    ActivityEventRead::mark_read(
+        &deployment.db().pool,
+        &current_user.reader_id(deployment.user_id()),
+        project.id,
+        &[event_id],
+    )
+    .await?;
+    invalidate_activity_feed_cache(project.id).await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Acknowledge everything currently in the feed, returning how many events were unread
+pub async fn mark_all_activity_read(
+    Extension(project): Extension<Project>,
+    Extension(current_user): Extension<CurrentUser>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<u64>>, ApiError> {
+    let event_ids: Vec<Uuid> = readable_events(&deployment, &current_user, project.id)
+        .await?
+        .iter()
+        .map(|event| event.event_id)
+        .collect();
+    let marked = ActivityEventRead::mark_read(
+        &deployment.db().pool,
+        &current_user.reader_id(deployment.user_id()),
+        project.id,
+        &event_ids,
+    )
+    .await?;
+    invalidate_activity_feed_cache(project.id).await;
+    Ok(ResponseJson(ApiResponse::success(marked)))
+}
+
 pub async fn invalidate_activity_feed_cache(project_id: Uuid) {
     let mut cache = FEED_CACHE.write().await;
     cache.retain(|key, _| !key.starts_with(&format!("activity_feed:{project_id}")));
@@ -0,0 +1,82 @@
+use axum::{
+    Extension, Json,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Json as ResponseJson},
+};
+use db::models::project::Project;
+use deployment::Deployment;
+use services::services::project_bundle::{
+    ImportProjectBundle, ImportedProjectBundle, ProjectBundle, ProjectBundleImportSummary,
+    export_project_bundle, import_project_bundle, import_project_bundle_as_project,
+};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Bundles embed their images, so they are allowed to be much larger than other request bodies
+pub const MAX_BUNDLE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Download the project's bundle as a JSON file
+pub async fn export_project(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<impl IntoResponse, ApiError> {
+    let bundle = export_project_bundle(&deployment.db().pool, deployment.image(), &project).await?;
+    let file_name: String = project
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{file_name}.gybe.json\""),
+        )],
+        Json(bundle),
+    ))
+}
+
+/// Add the tasks of a bundle exported elsewhere to this project
+pub async fn import_project(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(bundle): Json<ProjectBundle>,
+) -> Result<ResponseJson<ApiResponse<ProjectBundleImportSummary>>, ApiError> {
+    let summary =
+        import_project_bundle(&deployment.db().pool, deployment.image(), &project, bundle).await?;
+    deployment
+        .track_if_analytics_allowed(
+            "project_bundle_imported",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "tasks": summary.tasks,
+                "attempts": summary.attempts,
+            }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+/// Create a new project from a bundle exported elsewhere, with its repositories at the paths
+/// given for this machine
+pub async fn import_new_project(
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<ImportProjectBundle>,
+) -> Result<ResponseJson<ApiResponse<ImportedProjectBundle>>, ApiError> {
+    let imported =
+        import_project_bundle_as_project(&deployment.db().pool, deployment.image(), request)
+            .await?;
+    deployment
+        .track_if_analytics_allowed(
+            "project_bundle_imported",
+            serde_json::json!({
+                "project_id": imported.project.id.to_string(),
+                "tasks": imported.summary.tasks,
+                "attempts": imported.summary.attempts,
+                "new_project": true,
+            }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(imported)))
+}
@@ -0,0 +1,64 @@
+use axum::{
+    Extension,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Json as ResponseJson},
+};
+use chrono::Utc;
+use db::models::project::Project;
+use deployment::Deployment;
+use services::services::calendar_feed::{feed_signature, project_calendar};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::ForwardedRequest};
+
+/// Read-only iCal feed of the project's due dates and scheduled runs, for calendar subscriptions
+pub async fn get_calendar(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<impl IntoResponse, ApiError> {
+    let notifications = deployment.config().read().await.notifications.clone();
+    let calendar =
+        project_calendar(&deployment.db().pool, &project, &notifications, Utc::now()).await?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        calendar,
+    ))
+}
+
+/// Path of the project's feed under `base_path`, carrying its signature
+fn feed_path(base_path: &str, project_id: Uuid, signature: &str) -> String {
+    format!("{base_path}/api/projects/{project_id}/calendar.ics?signature={signature}")
+}
+
+/// Address of the project's feed as the browser reaches the app, signed so calendar apps can
+/// fetch it without logging in
+pub async fn get_calendar_url(
+    Extension(project): Extension<Project>,
+    Extension(forwarded): Extension<ForwardedRequest>,
+) -> ResponseJson<ApiResponse<String>> {
+    ResponseJson(ApiResponse::success(feed_path(
+        &forwarded.base_path,
+        project.id,
+        &feed_signature(project.id),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feed_path_is_served_under_the_base_path() {
+        let project_id = Uuid::new_v4();
+        assert_eq!(
+            feed_path("/kanban", project_id, "abc"),
+            format!("/kanban/api/projects/{project_id}/calendar.ics?signature=abc")
+        );
+        assert_eq!(
+            feed_path("", project_id, "abc"),
+            format!("/api/projects/{project_id}/calendar.ics?signature=abc")
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    issue_sync::{IssueSync, IssueTracker, LinkIssueSync},
+    project::Project,
+};
+use deployment::Deployment;
+use services::services::{
+    github_service::GitHubRepoInfo,
+    gitlab_service::GitLabRepoInfo,
+    issue_provider::issue_provider_for,
+    issue_sync::{IssueSyncSummary, sync_project_issues},
+};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+fn valid_segment(part: &str) -> bool {
+    !part.is_empty()
+        && part
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Accepts `owner/name` as well as the HTTPS or SSH URL of the repository
+fn parse_github_repo(repo: &str) -> Option<(String, String)> {
+    if repo.contains("github.com") {
+        let info = GitHubRepoInfo::from_remote_url(repo).ok()?;
+        return Some((info.owner, info.repo_name));
+    }
+    let (owner, name) = repo.split_once('/')?;
+    let name = name.strip_suffix(".git").unwrap_or(name);
+    (valid_segment(owner) && valid_segment(name)).then(|| (owner.to_string(), name.to_string()))
+}
+
+/// Accepts `group/subgroup/project` as well as a URL of the project on the configured instance.
+/// Returns the namespace path and the project name.
+fn parse_gitlab_project(repo: &str, base_url: &str) -> Option<(String, String)> {
+    let project_path = if repo.contains("://") || repo.starts_with("git@") {
+        GitLabRepoInfo::from_remote_url(repo, base_url)?.project_path
+    } else {
+        repo.strip_suffix(".git").unwrap_or(repo).to_string()
+    };
+    let (namespace, name) = project_path.rsplit_once('/')?;
+    (namespace.split('/').all(valid_segment) && valid_segment(name))
+        .then(|| (namespace.to_string(), name.to_string()))
+}
+
+pub async fn get_issue_sync(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<IssueSync>>>, ApiError> {
+    let sync = IssueSync::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(sync)))
+}
+
+pub async fn link_issues(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<LinkIssueSync>,
+) -> Result<ResponseJson<ApiResponse<IssueSync>>, ApiError> {
+    let repo = payload.repo.trim();
+    let parsed = match payload.tracker {
+        IssueTracker::GitHub => parse_github_repo(repo)
+            .ok_or_else(|| "Expected a GitHub repository as owner/name or its URL".to_string()),
+        IssueTracker::GitLab => {
+            let base_url = deployment.config().read().await.gitlab.resolved_base_url();
+            parse_gitlab_project(repo, &base_url).ok_or_else(|| {
+                format!("Expected a project on {base_url} as group/project or its URL")
+            })
+        }
+    };
+    let (owner, name) = match parsed {
+        Ok(parsed) => parsed,
+        Err(message) => return Ok(ResponseJson(ApiResponse::error(&message))),
+    };
+    let sync = IssueSync::upsert(
+        &deployment.db().pool,
+        project.id,
+        payload.tracker,
+        &owner,
+        &name,
+    )
+    .await?;
+    deployment
+        .track_if_analytics_allowed(
+            "issues_linked",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "tracker": payload.tracker,
+            }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(sync)))
+}
+
+pub async fn unlink_issues(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    IssueSync::delete(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Sync now instead of waiting for the background sync
+pub async fn sync_issues(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<IssueSyncSummary>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(sync) = IssueSync::find_by_project_id(pool, project.id).await? else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Project is not linked to an issue tracker",
+        )));
+    };
+    let provider = {
+        let config = deployment.config().read().await;
+        match issue_provider_for(&config, &sync) {
+            Ok(provider) => provider,
+            Err(e) => return Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+        }
+    };
+    match sync_project_issues(pool, provider.as_ref(), &sync).await {
+        Ok(summary) => Ok(ResponseJson(ApiResponse::success(summary))),
+        Err(e) => {
+            IssueSync::record_error(pool, project.id, &e.to_string()).await?;
+            Ok(ResponseJson(ApiResponse::error(&format!(
+                "Failed to sync issues: {e}"
+            ))))
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    jira_issue::{JiraSync, UpsertJiraSync},
+    project::Project,
+};
+use deployment::Deployment;
+use services::services::{
+    jira_service::JiraService,
+    jira_sync::{JiraSyncSummary, sync_project_jira},
+};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn get_jira_sync(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<JiraSync>>>, ApiError> {
+    let sync = JiraSync::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(sync)))
+}
+
+pub async fn upsert_jira_sync(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertJiraSync>,
+) -> Result<ResponseJson<ApiResponse<JiraSync>>, ApiError> {
+    if payload.jql.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "A JQL query is required, e.g. project = PROJ AND assignee = currentUser()",
+        )));
+    }
+    let sync = JiraSync::upsert(&deployment.db().pool, project.id, &payload).await?;
+    deployment
+        .track_if_analytics_allowed(
+            "jira_sync_configured",
+            serde_json::json!({ "project_id": project.id.to_string() }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(sync)))
+}
+
+pub async fn delete_jira_sync(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    JiraSync::delete(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Sync now instead of waiting for the background sync
+pub async fn sync_jira(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<JiraSyncSummary>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(sync) = JiraSync::find_by_project_id(pool, project.id).await? else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Project is not linked to Jira",
+        )));
+    };
+    let jira_config = deployment.config().read().await.jira.clone();
+    let jira = match JiraService::new(&jira_config) {
+        Ok(jira) => jira,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    };
+    match sync_project_jira(pool, &jira, &sync).await {
+        Ok(summary) => Ok(ResponseJson(ApiResponse::success(summary))),
+        Err(e) => {
+            JiraSync::record_error(pool, project.id, &e.to_string()).await?;
+            Ok(ResponseJson(ApiResponse::error(&format!(
+                "Failed to sync Jira issues: {e}"
+            ))))
+        }
+    }
+}
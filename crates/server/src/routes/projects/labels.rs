@@ -0,0 +1,101 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    label::{CreateLabel, Label, UpdateLabel, is_valid_label_color},
+    project::Project,
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Check a label's name and color, including that no other label of the project has the name
+async fn validate_label(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    label_id: Option<Uuid>,
+    name: &str,
+    color: &str,
+) -> Result<Option<String>, ApiError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Ok(Some("Label name cannot be empty".to_string()));
+    }
+    if !is_valid_label_color(color) {
+        return Ok(Some(format!(
+            "Label color '{color}' must be a hex color like #3b82f6"
+        )));
+    }
+    if let Some(existing) = Label::find_by_name(&deployment.db().pool, project_id, name).await?
+        && Some(existing.id) != label_id
+    {
+        return Ok(Some(format!("A label named '{name}' already exists")));
+    }
+    Ok(None)
+}
+
+pub async fn get_labels(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Label>>>, ApiError> {
+    let labels = Label::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(labels)))
+}
+
+pub async fn create_label(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateLabel>,
+) -> Result<ResponseJson<ApiResponse<Label>>, ApiError> {
+    if let Some(message) =
+        validate_label(&deployment, project.id, None, &payload.name, &payload.color).await?
+    {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    let label = Label::create(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(label)))
+}
+
+pub async fn update_label(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, label_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateLabel>,
+) -> Result<ResponseJson<ApiResponse<Label>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(existing) = Label::find_by_id(pool, label_id)
+        .await?
+        .filter(|label| label.project_id == project.id)
+    else {
+        return Ok(ResponseJson(ApiResponse::error("Label not found")));
+    };
+    let name = payload.name.unwrap_or(existing.name);
+    let color = payload.color.unwrap_or(existing.color);
+    if let Some(message) =
+        validate_label(&deployment, project.id, Some(label_id), &name, &color).await?
+    {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    let label = Label::update(pool, label_id, &name, &color).await?;
+    Ok(ResponseJson(ApiResponse::success(label)))
+}
+
+pub async fn delete_label(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, label_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if Label::find_by_id(pool, label_id)
+        .await?
+        .is_none_or(|label| label.project_id != project.id)
+    {
+        return Ok(ResponseJson(ApiResponse::error("Label not found")));
+    }
+    Label::delete(pool, label_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
@@ -0,0 +1,312 @@
+use std::path::{Path, PathBuf};
+
+use axum::{
+    Extension, Json,
+    extract::{Path as AxumPath, Query, State},
+    response::Json as ResponseJson,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    executor_session::ExecutorSession,
+    label::{CreateLabel, Label},
+    project::Project,
+    project_repository::ProjectRepository,
+    task::{CreateTask, Task},
+    task_attempt::{CreateTaskAttempt, TaskAttempt},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    release_notes::{POLISH_TASK_LABEL, POLISH_TASK_LABEL_COLOR, ReleaseNotes, ReleaseNotesError},
+    webhooks::WebhookService,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::CurrentUser};
+
+/// Bounds of a release: each side is either a timestamp or a git tag of the primary repository.
+/// A missing start means "from the beginning", a missing end means "now".
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct ReleaseNotesRange {
+    #[ts(type = "Date | null")]
+    pub from: Option<DateTime<Utc>>,
+    #[ts(type = "Date | null")]
+    pub to: Option<DateTime<Utc>>,
+    pub from_tag: Option<String>,
+    pub to_tag: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PolishReleaseNotesRequest {
+    #[serde(default)]
+    pub range: ReleaseNotesRange,
+    /// Defaults to the executor profile from the user config
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    /// Defaults to the branch checked out in the primary repository
+    pub base_branch: Option<String>,
+}
+
+/// The attempt polishing a set of notes. `markdown` is the agent's reply and stays empty until
+/// the agent has completed; poll `GET /release-notes/polish/{task_attempt_id}` for it. The
+/// attempt's worktree and branch are removed once the reply is available.
+#[derive(Debug, Serialize, TS)]
+pub struct PolishedReleaseNotes {
+    pub task_id: Uuid,
+    pub task_attempt_id: Uuid,
+    /// Status of the agent run, `None` before it has started
+    pub status: Option<ExecutionProcessStatus>,
+    pub markdown: Option<String>,
+}
+
+async fn primary_repo_path(
+    deployment: &DeploymentImpl,
+    project: &Project,
+) -> Result<PathBuf, ApiError> {
+    Ok(
+        match ProjectRepository::find_primary(&deployment.db().pool, project.id).await? {
+            Some(primary) => primary.git_repo_path,
+            None => project.git_repo_path.clone(),
+        },
+    )
+}
+
+/// Resolve one side of the range to a timestamp, `None` when the side is left open
+fn resolve_bound(
+    deployment: &DeploymentImpl,
+    repo_path: &Path,
+    date: Option<DateTime<Utc>>,
+    tag: Option<&str>,
+) -> Result<Option<DateTime<Utc>>, ApiError> {
+    match (date, tag.map(str::trim).filter(|t| !t.is_empty())) {
+        (Some(_), Some(_)) => Err(ReleaseNotesError::DateAndTag.into()),
+        (Some(date), None) => Ok(Some(date)),
+        (None, Some(tag)) => match deployment.git().get_tag_commit_time(repo_path, tag)? {
+            Some(time) => Ok(Some(time)),
+            None => Err(ReleaseNotesError::TagNotFound(tag.to_string()).into()),
+        },
+        (None, None) => Ok(None),
+    }
+}
+
+/// Assemble the draft notes for a range
+async fn build_release_notes(
+    deployment: &DeploymentImpl,
+    project: &Project,
+    range: &ReleaseNotesRange,
+) -> Result<ReleaseNotes, ApiError> {
+    let repo_path = primary_repo_path(deployment, project).await?;
+
+    let from = resolve_bound(
+        deployment,
+        &repo_path,
+        range.from,
+        range.from_tag.as_deref(),
+    )?
+    .unwrap_or(DateTime::<Utc>::UNIX_EPOCH);
+    let to = resolve_bound(deployment, &repo_path, range.to, range.to_tag.as_deref())?
+        .unwrap_or_else(Utc::now);
+    if from >= to {
+        return Err(ReleaseNotesError::EmptyRange.into());
+    }
+
+    let heading = match (range.from_tag.as_deref(), range.to_tag.as_deref()) {
+        (_, Some(to_tag)) => to_tag.trim().to_string(),
+        (Some(from_tag), None) => format!("Changes since {}", from_tag.trim()),
+        (None, None) => format!("Changes {} to {}", from.date_naive(), to.date_naive()),
+    };
+
+    let tasks = Task::find_completed_between(
+        &deployment.db().pool,
+        project.id,
+        from,
+        to,
+        POLISH_TASK_LABEL,
+    )
+    .await?;
+    Ok(ReleaseNotes::new(&heading, from, to, tasks))
+}
+
+/// Where the agent polishing notes in `task_attempt` has got to, with its reply once complete.
+/// The agent never leaves changes worth keeping, so the worktree and branch go once it replies.
+async fn polished_release_notes(
+    deployment: &DeploymentImpl,
+    project: &Project,
+    task_attempt: &TaskAttempt,
+) -> Result<PolishedReleaseNotes, ApiError> {
+    let pool = &deployment.db().pool;
+    let process = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+        pool,
+        task_attempt.id,
+        &ExecutionProcessRunReason::CodingAgent,
+    )
+    .await?;
+    let markdown = match &process {
+        Some(process) if process.status == ExecutionProcessStatus::Completed => {
+            ExecutorSession::find_by_execution_process_id(pool, process.id)
+                .await?
+                .and_then(|session| session.summary)
+        }
+        _ => None,
+    };
+    if markdown.is_some() && !task_attempt.worktree_deleted {
+        deployment.container().delete(task_attempt).await?;
+        TaskAttempt::mark_worktree_deleted(pool, task_attempt.id).await?;
+        if let Err(e) = deployment
+            .git()
+            .delete_local_branch(&project.git_repo_path, &task_attempt.branch)
+        {
+            tracing::warn!(
+                "Failed to delete release notes branch {}: {}",
+                task_attempt.branch,
+                e
+            );
+        }
+    }
+    Ok(PolishedReleaseNotes {
+        task_id: task_attempt.task_id,
+        task_attempt_id: task_attempt.id,
+        status: process.map(|process| process.status),
+        markdown,
+    })
+}
+
+pub async fn get_release_notes(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(range): Query<ReleaseNotesRange>,
+) -> Result<ResponseJson<ApiResponse<ReleaseNotes>>, ApiError> {
+    let notes = build_release_notes(&deployment, &project, &range).await?;
+    Ok(ResponseJson(ApiResponse::success(notes)))
+}
+
+/// Start an agent on a new task whose prompt is the draft notes. The task is labelled
+/// `POLISH_TASK_LABEL` so it is told apart on the board and left out of later notes. The polished
+/// text is the agent's reply in that attempt, returned by `get_polished_release_notes` once it
+/// completes.
+pub async fn polish_release_notes(
+    Extension(project): Extension<Project>,
+    Extension(current_user): Extension<CurrentUser>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PolishReleaseNotesRequest>,
+) -> Result<ResponseJson<ApiResponse<PolishedReleaseNotes>>, ApiError> {
+    let notes = build_release_notes(&deployment, &project, &payload.range).await?;
+    if notes.tasks.is_empty() {
+        return Err(ReleaseNotesError::NoTasks.into());
+    }
+
+    let executor_profile_id = match payload.executor_profile_id {
+        Some(profile) => profile,
+        None => deployment.config().read().await.executor_profile.clone(),
+    };
+    let base_branch = match payload.base_branch.filter(|b| !b.trim().is_empty()) {
+        Some(branch) => branch,
+        None => {
+            let repo_path = primary_repo_path(&deployment, &project).await?;
+            deployment.git().get_current_branch(&repo_path)?
+        }
+    };
+
+    let pool = &deployment.db().pool;
+    let existing_label = Label::find_by_name(pool, project.id, POLISH_TASK_LABEL).await?;
+    let mut tx = pool.begin().await?;
+    let task = Task::create(
+        &mut *tx,
+        &CreateTask::from_title_description(
+            project.id,
+            format!(
+                "Polish release notes ({} to {})",
+                notes.from.date_naive(),
+                notes.to.date_naive()
+            ),
+            Some(notes.polish_prompt()),
+        ),
+        Uuid::new_v4(),
+    )
+    .await?;
+    let label_id = match existing_label {
+        Some(label) => label.id,
+        None => {
+            Label::create(
+                &mut *tx,
+                project.id,
+                &CreateLabel {
+                    name: POLISH_TASK_LABEL.to_string(),
+                    color: POLISH_TASK_LABEL_COLOR.to_string(),
+                },
+            )
+            .await?
+            .id
+        }
+    };
+    Label::set_for_task(&mut *tx, task.id, project.id, &[label_id]).await?;
+    tx.commit().await?;
+    WebhookService::task_created(pool, &task);
+
+    let attempt_id = Uuid::new_v4();
+    let task_attempt = TaskAttempt::create(
+        pool,
+        &CreateTaskAttempt {
+            executor: executor_profile_id.executor,
+            base_branch,
+            branch: deployment
+                .container()
+                .git_branch_from_task_attempt(&attempt_id, &task.title),
+            repositories: None,
+        },
+        attempt_id,
+        task.id,
+    )
+    .await?;
+    if let Some(user_id) = current_user.id() {
+        TaskAttempt::set_created_by(pool, task_attempt.id, user_id).await?;
+    }
+    deployment
+        .container()
+        .start_attempt(&task_attempt, executor_profile_id.clone())
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "release_notes_polish_started",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "task_id": task.id.to_string(),
+                "task_count": notes.tasks.len(),
+                "executor": &executor_profile_id.executor,
+            }),
+        )
+        .await;
+
+    let polished = polished_release_notes(&deployment, &project, &task_attempt).await?;
+    Ok(ResponseJson(ApiResponse::success(polished)))
+}
+
+pub async fn get_polished_release_notes(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((_, task_attempt_id)): AxumPath<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<PolishedReleaseNotes>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task_attempt = match TaskAttempt::find_by_id(pool, task_attempt_id).await? {
+        Some(task_attempt) => task_attempt,
+        None => return Ok(ResponseJson(ApiResponse::error("Task attempt not found"))),
+    };
+    match Task::find_by_id(pool, task_attempt.task_id).await? {
+        Some(task) if task.project_id == project.id => {}
+        _ => return Ok(ResponseJson(ApiResponse::error("Task attempt not found"))),
+    }
+    // Only attempts started by `polish_release_notes` are cleaned up here
+    let labels = Label::find_by_task_id(pool, task_attempt.task_id).await?;
+    if !labels.iter().any(|label| label.name == POLISH_TASK_LABEL) {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Task attempt is not polishing release notes",
+        )));
+    }
+    let polished = polished_release_notes(&deployment, &project, &task_attempt).await?;
+    Ok(ResponseJson(ApiResponse::success(polished)))
+}
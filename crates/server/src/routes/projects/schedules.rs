@@ -0,0 +1,91 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    project::Project,
+    task_schedule::{CreateTaskSchedule, TaskSchedule, UpdateTaskSchedule},
+    task_template::TaskTemplate,
+};
+use deployment::Deployment;
+use services::services::cron_schedule::CronSchedule;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// When the expression fires next, or the message explaining why it cannot be scheduled
+fn next_run(cron_expression: &str) -> Result<DateTime<Utc>, String> {
+    let schedule = CronSchedule::parse(cron_expression).map_err(|e| e.to_string())?;
+    schedule
+        .next_after(Utc::now())
+        .ok_or_else(|| format!("'{}' never fires", cron_expression.trim()))
+}
+
+pub async fn get_schedules(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskSchedule>>>, ApiError> {
+    let schedules = TaskSchedule::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(schedules)))
+}
+
+pub async fn create_schedule(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    let pool = &deployment.db().pool;
+    // Global templates can be scheduled in any project
+    if TaskTemplate::find_by_id(pool, payload.template_id)
+        .await?
+        .is_none_or(|template| template.project_id.is_some_and(|id| id != project.id))
+    {
+        return Ok(ResponseJson(ApiResponse::error("Template not found")));
+    }
+    let next_run_at = match next_run(&payload.cron_expression) {
+        Ok(next_run_at) => next_run_at,
+        Err(message) => return Ok(ResponseJson(ApiResponse::error(&message))),
+    };
+    let schedule = TaskSchedule::create(pool, project.id, &payload, next_run_at).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn update_schedule(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, schedule_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if TaskSchedule::find_by_id(pool, schedule_id)
+        .await?
+        .is_none_or(|schedule| schedule.project_id != project.id)
+    {
+        return Ok(ResponseJson(ApiResponse::error("Schedule not found")));
+    }
+    let next_run_at = match next_run(&payload.cron_expression) {
+        Ok(next_run_at) => next_run_at,
+        Err(message) => return Ok(ResponseJson(ApiResponse::error(&message))),
+    };
+    let schedule = TaskSchedule::update(pool, schedule_id, &payload, next_run_at).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn delete_schedule(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, schedule_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if TaskSchedule::find_by_id(pool, schedule_id)
+        .await?
+        .is_none_or(|schedule| schedule.project_id != project.id)
+    {
+        return Ok(ResponseJson(ApiResponse::error("Schedule not found")));
+    }
+    TaskSchedule::delete(pool, schedule_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
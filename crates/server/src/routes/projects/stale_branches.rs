@@ -0,0 +1,134 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{project::Project, task_attempt::TaskAttempt};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    branch_cleanup::{StaleBranch, StaleBranchDeletion, find_stale_branches},
+    container::ContainerService,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct DeleteStaleBranchesRequest {
+    /// Attempts whose branches the user confirmed for deletion
+    pub task_attempt_ids: Vec<Uuid>,
+    /// Also delete the branch on its remote
+    #[serde(default)]
+    pub delete_remote: bool,
+}
+
+async fn stale_branches_for(
+    deployment: &DeploymentImpl,
+    project: &Project,
+) -> Result<Vec<StaleBranch>, ApiError> {
+    let candidates =
+        TaskAttempt::find_branch_cleanup_candidates(&deployment.db().pool, project.id).await?;
+    Ok(find_stale_branches(
+        deployment.git(),
+        &project.git_repo_path,
+        &candidates,
+    ))
+}
+
+/// Attempt branches of the primary repository that are merged or belong to finished tasks
+pub async fn get_stale_branches(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<StaleBranch>>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        stale_branches_for(&deployment, &project).await?,
+    )))
+}
+
+async fn delete_stale_branch(
+    deployment: &DeploymentImpl,
+    project: &Project,
+    stale: &StaleBranch,
+    delete_remote: bool,
+) -> Result<StaleBranchDeletion, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut outcome = StaleBranchDeletion {
+        task_attempt_id: stale.task_attempt_id,
+        branch: stale.branch.clone(),
+        deleted_local: false,
+        deleted_remote: false,
+        error: None,
+    };
+
+    // The branch cannot be deleted while a worktree has it checked out
+    if stale.has_worktree
+        && let Some(attempt) = TaskAttempt::find_by_id(pool, stale.task_attempt_id).await?
+    {
+        deployment.container().delete(&attempt).await?;
+        TaskAttempt::mark_worktree_deleted(pool, attempt.id).await?;
+    }
+
+    if let Err(e) = deployment
+        .git()
+        .delete_local_branch(&project.git_repo_path, &stale.branch)
+    {
+        outcome.error = Some(e.to_string());
+        return Ok(outcome);
+    }
+    outcome.deleted_local = true;
+
+    if delete_remote && let Some(remote) = &stale.remote {
+        match deployment
+            .git()
+            .delete_remote_branch(&project.git_repo_path, remote, &stale.branch)
+        {
+            Ok(()) => outcome.deleted_remote = true,
+            Err(e) => outcome.error = Some(e.to_string()),
+        }
+    }
+    Ok(outcome)
+}
+
+/// Delete the confirmed branches. Staleness is checked again, so an attempt that was resumed
+/// since the list was fetched is left alone.
+pub async fn delete_stale_branches(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DeleteStaleBranchesRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<StaleBranchDeletion>>>, ApiError> {
+    if payload.task_attempt_ids.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Select at least one branch to delete",
+        )));
+    }
+
+    let stale = stale_branches_for(&deployment, &project).await?;
+    let mut outcomes = Vec::with_capacity(payload.task_attempt_ids.len());
+    for task_attempt_id in &payload.task_attempt_ids {
+        match stale.iter().find(|b| b.task_attempt_id == *task_attempt_id) {
+            Some(branch) => outcomes.push(
+                delete_stale_branch(&deployment, &project, branch, payload.delete_remote).await?,
+            ),
+            None => outcomes.push(StaleBranchDeletion {
+                task_attempt_id: *task_attempt_id,
+                branch: String::new(),
+                deleted_local: false,
+                deleted_remote: false,
+                error: Some("Branch is no longer stale or was already deleted".to_string()),
+            }),
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "stale_branches_deleted",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "requested": payload.task_attempt_ids.len(),
+                "deleted": outcomes.iter().filter(|o| o.deleted_local).count(),
+                "delete_remote": payload.delete_remote,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(outcomes)))
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    label::{CreateLabel, Label},
+    project::Project,
+    task::{CreateTask, Task, TaskStatus},
+};
+use deployment::Deployment;
+use services::services::{
+    task_import::{ImportTasksRequest, TaskImportResult, TaskImportRowError, parse_tasks},
+    webhooks::WebhookService,
+};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Upper bound on tasks per import, so a wrong file cannot flood the board
+const MAX_IMPORTED_TASKS: usize = 500;
+/// Color of labels the import creates because the project has none by that name
+const IMPORTED_LABEL_COLOR: &str = "#6b7280";
+
+pub async fn import_tasks(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ImportTasksRequest>,
+) -> Result<ResponseJson<ApiResponse<TaskImportResult>>, ApiError> {
+    let rows = match parse_tasks(payload.format, &payload.content) {
+        Ok(rows) => rows,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    };
+    if rows.len() > MAX_IMPORTED_TASKS {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Imports are limited to {MAX_IMPORTED_TASKS} tasks, found {}",
+            rows.len()
+        ))));
+    }
+
+    let pool = &deployment.db().pool;
+    // Label names match case-insensitively, so "Bug" in the file reuses an existing "bug"
+    let mut label_ids: HashMap<String, Uuid> = Label::find_by_project_id(pool, project.id)
+        .await?
+        .into_iter()
+        .map(|label| (label.name.to_lowercase(), label.id))
+        .collect();
+
+    let mut result = TaskImportResult::default();
+    for (index, row) in rows.into_iter().enumerate() {
+        let imported = match row {
+            Ok(imported) => imported,
+            Err(message) => {
+                result.errors.push(TaskImportRowError {
+                    row: index + 1,
+                    message,
+                });
+                continue;
+            }
+        };
+
+        let mut task_label_ids = Vec::with_capacity(imported.labels.len());
+        for name in &imported.labels {
+            let id = match label_ids.get(&name.to_lowercase()) {
+                Some(id) => *id,
+                None => {
+                    let label = Label::create(
+                        pool,
+                        project.id,
+                        &CreateLabel {
+                            name: name.clone(),
+                            color: IMPORTED_LABEL_COLOR.to_string(),
+                        },
+                    )
+                    .await?;
+                    label_ids.insert(name.to_lowercase(), label.id);
+                    label.id
+                }
+            };
+            task_label_ids.push(id);
+        }
+
+        let task = Task::create(
+            pool,
+            &CreateTask::from_title_description(project.id, imported.title, imported.description),
+            Uuid::new_v4(),
+        )
+        .await?;
+        if imported.status != TaskStatus::Todo {
+            Task::update_status(pool, task.id, imported.status).await?;
+        }
+        if !task_label_ids.is_empty() {
+            Label::set_for_task(pool, task.id, project.id, &task_label_ids).await?;
+        }
+        WebhookService::task_created(pool, &task);
+        result.created.push(task.id);
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "tasks_imported",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "created": result.created.len(),
+                "failed": result.errors.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
@@ -0,0 +1,141 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::prompt_snippet::{CreatePromptSnippet, PromptSnippet, UpdatePromptSnippet};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::prompt_snippets::{self, ExpandedPrompt};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_prompt_snippet_middleware};
+
+#[derive(Debug, Deserialize)]
+pub struct PromptSnippetQuery {
+    global: Option<bool>,
+    project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ExpandPromptRequest {
+    pub project_id: Uuid,
+    pub prompt: String,
+}
+
+fn invalid_name_message(name: &str) -> String {
+    format!("Snippet name '{name}' may only contain letters, digits, '-', '_' and '.'")
+}
+
+pub async fn get_snippets(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<PromptSnippetQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<PromptSnippet>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let snippets = match (query.global, query.project_id) {
+        // All snippets: global and project-specific
+        (None, None) => PromptSnippet::find_all(pool).await?,
+        // Only global snippets
+        (Some(true), None) => PromptSnippet::find_by_project_id(pool, None).await?,
+        // Only project-specific snippets
+        (Some(false), Some(project_id)) => {
+            PromptSnippet::find_by_project_id(pool, Some(project_id)).await?
+        }
+        // Everything a prompt in this project can reference
+        (None, Some(project_id)) => PromptSnippet::find_for_project(pool, project_id).await?,
+        (Some(false), None) => vec![],
+        (Some(true), Some(_)) => {
+            return Err(ApiError::Database(SqlxError::InvalidArgument(
+                "Cannot query both global and project-specific snippets".to_string(),
+            )));
+        }
+    };
+    Ok(ResponseJson(ApiResponse::success(snippets)))
+}
+
+pub async fn get_snippet(
+    Extension(snippet): Extension<PromptSnippet>,
+) -> Result<ResponseJson<ApiResponse<PromptSnippet>>, ApiError> {
+    Ok(Json(ApiResponse::success(snippet)))
+}
+
+pub async fn create_snippet(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreatePromptSnippet>,
+) -> Result<ResponseJson<ApiResponse<PromptSnippet>>, ApiError> {
+    if !PromptSnippet::is_valid_name(&payload.name) {
+        return Ok(ResponseJson(ApiResponse::error(&invalid_name_message(
+            &payload.name,
+        ))));
+    }
+    Ok(ResponseJson(ApiResponse::success(
+        PromptSnippet::create(&deployment.db().pool, &payload).await?,
+    )))
+}
+
+pub async fn update_snippet(
+    Extension(snippet): Extension<PromptSnippet>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdatePromptSnippet>,
+) -> Result<ResponseJson<ApiResponse<PromptSnippet>>, ApiError> {
+    if let Some(name) = &payload.name
+        && !PromptSnippet::is_valid_name(name)
+    {
+        return Ok(ResponseJson(ApiResponse::error(&invalid_name_message(
+            name,
+        ))));
+    }
+    Ok(ResponseJson(ApiResponse::success(
+        PromptSnippet::update(&deployment.db().pool, snippet.id, &payload).await?,
+    )))
+}
+
+pub async fn delete_snippet(
+    Extension(snippet): Extension<PromptSnippet>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = PromptSnippet::delete(&deployment.db().pool, snippet.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Preview how a prompt reads once its snippet placeholders are expanded
+pub async fn expand_prompt(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ExpandPromptRequest>,
+) -> Result<ResponseJson<ApiResponse<ExpandedPrompt>>, ApiError> {
+    let expanded = prompt_snippets::expand_for_project(
+        &deployment.db().pool,
+        payload.project_id,
+        &payload.prompt,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(expanded)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let snippet_router = Router::new()
+        .route(
+            "/",
+            get(get_snippet).put(update_snippet).delete(delete_snippet),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_prompt_snippet_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_snippets).post(create_snippet))
+        .route("/expand", post(expand_prompt))
+        .nest("/{snippet_id}", snippet_router);
+
+    Router::new().nest("/snippets", inner)
+}
@@ -1,24 +1,48 @@
+pub mod branch_protection;
+pub mod conflicts;
+pub mod diff_comments;
+pub mod diff_content;
+pub mod diff_revert;
+pub mod diff_summary;
 pub mod drafts;
+pub mod gitea;
+pub mod gitlab;
+pub mod manual_changes;
+pub mod review_comments;
+pub mod snapshots;
 pub mod util;
 
+use std::collections::HashSet;
+
 use axum::{
     Extension, Json, Router,
     extract::{
         Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    routing::{get, post, put},
 };
-use db::models::{
-    draft::{Draft, DraftType},
-    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
-    merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
-    project::{Project, ProjectError},
-    task::{Task, TaskRelationships, TaskStatus},
-    task_attempt::{CreateTaskAttempt, CreateTaskAttemptRepository, TaskAttempt, TaskAttemptError},
+use db::{
+    models::{
+        attempt_cleanup_warning::AttemptCleanupWarning,
+        attempt_verification::AttemptVerification,
+        draft::{Draft, DraftType},
+        execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+        executor_session::ExecutorSession,
+        issue_sync::IssueTracker,
+        merge::{Merge, MergeStatus, MergeStrategy, PrMerge, PullRequestInfo},
+        pr_check_status::PrCheckStatus,
+        project::{Project, ProjectError},
+        task::{Task, TaskRelationships, TaskStatus},
+        task_attempt::{
+            CreateTaskAttempt, CreateTaskAttemptRepository, TaskAttempt, TaskAttemptError,
+            TaskAttemptSortField,
+        },
+    },
+    pagination::{Page, SortDirection},
 };
 use deployment::Deployment;
 use executors::{
@@ -27,25 +51,43 @@ use executors::{
         coding_agent_follow_up::CodingAgentFollowUpRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
-    profile::ExecutorProfileId,
+    profile::{ExecutorConfigs, ExecutorProfileId},
 };
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
-use services::services::{
-    container::ContainerService,
-    git::{ConflictOp, GitServiceError, WorktreeResetOptions},
-    github_service::{CreatePrRequest, GitHubService, GitHubServiceError},
+use services::{
+    activity_feed::ActivityEventRecorder,
+    services::{
+        branch_cleanup,
+        container::ContainerService,
+        git::{
+            BranchPushOutcome, ConflictOp, ConflictedFile, GitServiceError, MergePreview,
+            WorktreeResetOptions,
+        },
+        github_service::{CreatePrRequest, GitHubService, GitHubServiceError},
+        handoff::build_handoff_prompt,
+        project_stats::ProjectActivityKind,
+        prompt_snippets,
+        webhooks::WebhookService,
+    },
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{approvals::ApprovalPendingInfo, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
     error::ApiError,
-    middleware::load_task_attempt_middleware,
-    routes::task_attempts::util::{ensure_worktree_path, handle_images_for_prompt},
+    middleware::{CurrentUser, load_task_attempt_middleware},
+    routes::{
+        costs,
+        task_attempts::util::{
+            ensure_worktree_path, handle_images_for_prompt, pr_body_with_issue_reference,
+            pr_body_with_template,
+        },
+        tasks::{dependencies::blocked_reason, total_count_headers},
+    },
 };
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -58,7 +100,13 @@ pub struct RebaseTaskAttemptRequest {
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
 pub enum GitOperationError {
-    MergeConflicts { message: String, op: ConflictOp },
+    MergeConflicts {
+        message: String,
+        op: ConflictOp,
+        /// Files left conflicted in the worktree, with their conflict regions
+        #[serde(default)]
+        conflicts: Vec<ConflictedFile>,
+    },
     RebaseInProgress,
 }
 
@@ -104,6 +152,15 @@ pub struct FollowUpResponse {
 #[derive(Debug, Deserialize)]
 pub struct TaskAttemptQuery {
     pub task_id: Option<Uuid>,
+    #[serde(default)]
+    pub sort: TaskAttemptSortField,
+    #[serde(default)]
+    pub order: SortDirection,
+    /// Page size; all attempts are returned when left out
+    #[serde(default)]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    pub offset: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -117,10 +174,20 @@ pub struct DiffStreamQuery {
 pub async fn get_task_attempts(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<TaskAttemptQuery>,
-) -> Result<ResponseJson<ApiResponse<Vec<TaskAttempt>>>, ApiError> {
+) -> Result<(HeaderMap, ResponseJson<ApiResponse<Vec<TaskAttempt>>>), ApiError> {
     let pool = &deployment.db().pool;
-    let attempts = TaskAttempt::fetch_all(pool, query.task_id).await?;
-    Ok(ResponseJson(ApiResponse::success(attempts)))
+    let (attempts, total) = TaskAttempt::fetch_page(
+        pool,
+        query.task_id,
+        query.sort,
+        query.order,
+        Page::new(query.limit, query.offset),
+    )
+    .await?;
+    Ok((
+        total_count_headers(total),
+        ResponseJson(ApiResponse::success(attempts)),
+    ))
 }
 
 pub async fn get_task_attempt(
@@ -147,6 +214,14 @@ pub struct CreateTaskAttemptBody {
     pub base_branch: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub repositories: Option<Vec<CreateTaskAttemptRepositoryBody>>,
+    /// Stack the attempt on another attempt of the same project; that attempt's branch is used
+    /// as the base branch instead of `base_branch`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_attempt_id: Option<Uuid>,
+    /// Earlier attempt of the same task whose queued follow-up moves to the new attempt; it runs
+    /// once the new attempt's first run finishes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer_follow_up_from: Option<Uuid>,
 }
 
 impl CreateTaskAttemptBody {
@@ -159,12 +234,43 @@ impl CreateTaskAttemptBody {
 #[axum::debug_handler]
 pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
+    Extension(current_user): Extension<CurrentUser>,
     Json(payload): Json<CreateTaskAttemptBody>,
 ) -> Result<ResponseJson<ApiResponse<TaskAttempt>>, ApiError> {
     let executor_profile_id = payload.get_executor_profile_id();
     let task = Task::find_by_id(&deployment.db().pool, payload.task_id)
         .await?
         .ok_or(SqlxError::RowNotFound)?;
+    if let Some(reason) = blocked_reason(&deployment, &task).await? {
+        return Ok(ResponseJson(ApiResponse::error(&reason)));
+    }
+
+    let mut base_branch = payload.base_branch.clone();
+    if let Some(parent_attempt_id) = payload.parent_attempt_id {
+        let Some(parent) =
+            TaskAttempt::find_by_id(&deployment.db().pool, parent_attempt_id).await?
+        else {
+            return Ok(ResponseJson(ApiResponse::error("Parent attempt not found")));
+        };
+        let parent_task = parent
+            .parent_task(&deployment.db().pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        if parent_task.project_id != task.project_id {
+            return Ok(ResponseJson(ApiResponse::error(
+                "Parent attempt belongs to a different project",
+            )));
+        }
+        base_branch = parent.branch;
+    }
+    if let Some(previous_attempt_id) = payload.transfer_follow_up_from {
+        let previous = TaskAttempt::find_by_id(&deployment.db().pool, previous_attempt_id).await?;
+        if previous.is_none_or(|previous| previous.task_id != task.id) {
+            return Ok(ResponseJson(ApiResponse::error(
+                "Follow-ups can only be carried over from an attempt of the same task",
+            )));
+        }
+    }
 
     let attempt_id = Uuid::new_v4();
     let git_branch_name = deployment
@@ -189,7 +295,7 @@ pub async fn create_task_attempt(
 
     let create_request = CreateTaskAttempt {
         executor: executor_profile_id.executor,
-        base_branch: payload.base_branch.clone(),
+        base_branch,
         branch: git_branch_name.clone(),
         repositories: repository_selection,
     };
@@ -201,12 +307,30 @@ pub async fn create_task_attempt(
         payload.task_id,
     )
     .await?;
+    if let Some(parent_attempt_id) = payload.parent_attempt_id {
+        TaskAttempt::set_parent_attempt(&deployment.db().pool, task_attempt.id, parent_attempt_id)
+            .await?;
+    }
+    if let Some(user_id) = current_user.id() {
+        TaskAttempt::set_created_by(&deployment.db().pool, task_attempt.id, user_id).await?;
+    }
 
     let execution_process = deployment
         .container()
         .start_attempt(&task_attempt, executor_profile_id.clone())
         .await?;
 
+    let mut follow_up_transferred = false;
+    if let Some(previous_attempt_id) = payload.transfer_follow_up_from {
+        follow_up_transferred = Draft::transfer_queued_follow_up(
+            &deployment.db().pool,
+            previous_attempt_id,
+            task_attempt.id,
+        )
+        .await?
+        .is_some();
+    }
+
     deployment
         .track_if_analytics_allowed(
             "task_attempt_started",
@@ -215,6 +339,8 @@ pub async fn create_task_attempt(
                 "variant": &executor_profile_id.variant,
                 "executor": &executor_profile_id.executor,
                 "attempt_id": task_attempt.id.to_string(),
+                "stacked": payload.parent_attempt_id.is_some(),
+                "follow_up_transferred": follow_up_transferred,
             }),
         )
         .await;
@@ -305,6 +431,9 @@ pub async fn follow_up(
                 .map(|is_clean| !is_clean)
                 .unwrap_or(false);
 
+            if perform_git_reset && (!is_dirty || force_when_dirty) {
+                snapshots::snapshot_before(&deployment, &task_attempt, wt, "retry");
+            }
             deployment.git().reconcile_worktree_to_commit(
                 wt,
                 target_oid,
@@ -333,15 +462,16 @@ pub async fn follow_up(
     )
     .await?;
 
-    let mut prompt = payload.prompt;
+    let mut prompt =
+        prompt_snippets::expand_for_project(&deployment.db().pool, project.id, &payload.prompt)
+            .await?
+            .prompt;
     if let Some(image_ids) = &payload.image_ids {
         prompt = handle_images_for_prompt(&deployment, &task_attempt, task.id, image_ids, &prompt)
             .await?;
     }
 
-    let cleanup_action = deployment
-        .container()
-        .cleanup_action(project.cleanup_script);
+    let cleanup_action = deployment.container().post_agent_action(&project).await?;
 
     let action_type = if let Some(session_id) = latest_session_id {
         ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
@@ -381,6 +511,98 @@ pub async fn follow_up(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct HandoffTaskAttemptRequest {
+    /// Executor that continues the attempt
+    pub executor_profile_id: ExecutorProfileId,
+    /// Extra instructions appended after the summary of the previous session
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+/// Continue the attempt in its existing worktree with a different executor. Sessions cannot be
+/// resumed across executors, so the new agent starts fresh with a prompt summarising the previous
+/// session's requests and replies.
+pub async fn handoff_task_attempt(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<HandoffTaskAttemptRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let previous_profile_id =
+        ExecutionProcess::latest_executor_profile_for_attempt(pool, task_attempt.id).await?;
+    let executor_profile_id = payload.executor_profile_id;
+    if executor_profile_id.executor == previous_profile_id.executor {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The attempt already uses this executor; send a follow-up instead",
+        )));
+    }
+    if ExecutorConfigs::get_cached()
+        .get_coding_agent(&executor_profile_id)
+        .is_none()
+    {
+        return Ok(ResponseJson(ApiResponse::error("Executor not found")));
+    }
+
+    let _ = ensure_worktree_path(&deployment, &task_attempt).await?;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let project = task
+        .parent_project(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+
+    // The previous agent must not keep editing the worktree once the new one starts
+    deployment.container().try_stop(&task_attempt).await;
+
+    let sessions = ExecutorSession::find_active_by_task_attempt_id(pool, task_attempt.id).await?;
+    let prompt = build_handoff_prompt(
+        &task,
+        &task_attempt.branch,
+        &previous_profile_id.executor,
+        &sessions,
+        payload.prompt.as_deref(),
+    );
+
+    let cleanup_action = deployment.container().post_agent_action(&project).await?;
+    let action = ExecutorAction::new(
+        ExecutorActionType::CodingAgentInitialRequest(
+            executors::actions::coding_agent_initial::CodingAgentInitialRequest {
+                prompt,
+                executor_profile_id: executor_profile_id.clone(),
+            },
+        ),
+        cleanup_action,
+    );
+    let execution_process = deployment
+        .container()
+        .start_execution(
+            &task_attempt,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+    TaskAttempt::update_executor(pool, task_attempt.id, &executor_profile_id.executor).await?;
+    let _ = Draft::clear_after_send(pool, task_attempt.id, DraftType::FollowUp).await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_handed_off",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+                "from_executor": &previous_profile_id.executor,
+                "executor": &executor_profile_id.executor,
+                "variant": &executor_profile_id.variant,
+                "previous_turns": sessions.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
 #[axum::debug_handler]
 pub async fn replace_process(
     Extension(task_attempt): Extension<TaskAttempt>,
@@ -426,6 +648,9 @@ pub async fn replace_process(
             .map(|is_clean| !is_clean)
             .unwrap_or(false);
 
+        if perform_git_reset && (!is_dirty || force_when_dirty) {
+            snapshots::snapshot_before(&deployment, &task_attempt, wt, "retry");
+        }
         let outcome = deployment.git().reconcile_worktree_to_commit(
             wt,
             target_oid,
@@ -465,13 +690,21 @@ pub async fn replace_process(
             .or(initial_executor_profile_id.variant.clone()),
     };
 
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let prompt = prompt_snippets::expand_for_project(pool, task.project_id, &payload.prompt)
+        .await?
+        .prompt;
+
     // Use latest session_id from remaining (earlier) processes; if none exists, start a fresh initial request
     let latest_session_id =
         ExecutionProcess::find_latest_session_id_by_task_attempt(pool, task_attempt.id).await?;
 
     let action = if let Some(session_id) = latest_session_id {
         let follow_up_request = CodingAgentFollowUpRequest {
-            prompt: payload.prompt.clone(),
+            prompt,
             session_id,
             executor_profile_id,
         };
@@ -484,7 +717,7 @@ pub async fn replace_process(
         ExecutorAction::new(
             ExecutorActionType::CodingAgentInitialRequest(
                 executors::actions::coding_agent_initial::CodingAgentInitialRequest {
-                    prompt: payload.prompt.clone(),
+                    prompt,
                     executor_profile_id,
                 },
             ),
@@ -540,10 +773,19 @@ async fn handle_task_attempt_diff_ws(
     use futures_util::{SinkExt, StreamExt, TryStreamExt};
     use utils::log_msg::LogMsg;
 
+    let started = std::time::Instant::now();
     let stream = deployment
         .container()
         .stream_diff(&task_attempt, stats_only, repo_id)
         .await?;
+    let setup_time = started.elapsed();
+    if let Some(task) = task_attempt.parent_task(&deployment.db().pool).await? {
+        deployment.project_stats().record(
+            task.project_id,
+            ProjectActivityKind::DiffStreamed,
+            setup_time,
+        );
+    }
 
     let mut stream = stream.map_ok(|msg: LogMsg| msg.to_ws_message_unchecked());
 
@@ -638,11 +880,74 @@ pub async fn compare_commit_to_head(
     })))
 }
 
+/// A stacked attempt may merge into its parent's branch at any time, but merging it anywhere
+/// else before the parent has landed would carry the parent's unmerged commits along.
+async fn unmerged_parent_message(
+    pool: &sqlx::SqlitePool,
+    task_attempt: &TaskAttempt,
+) -> Result<Option<String>, ApiError> {
+    let Some(parent_id) = TaskAttempt::find_parent_attempt_id(pool, task_attempt.id).await? else {
+        return Ok(None);
+    };
+    let Some(parent) = TaskAttempt::find_by_id(pool, parent_id).await? else {
+        return Ok(None);
+    };
+    if parent.branch == task_attempt.target_branch {
+        return Ok(None);
+    }
+    let parent_merged = Merge::find_latest_by_task_attempt_id(pool, parent.id)
+        .await?
+        .is_some_and(|merge| merge.is_merged());
+    Ok((!parent_merged).then(|| {
+        format!(
+            "This attempt is stacked on '{}', which has not been merged yet. Merge the parent attempt first.",
+            parent.branch
+        )
+    }))
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct AttemptStack {
+    /// Attempt this one was branched off, if it is stacked
+    pub parent: Option<TaskAttempt>,
+    /// Attempts stacked directly on this one
+    pub children: Vec<TaskAttempt>,
+}
+
+pub async fn get_task_attempt_stack(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AttemptStack>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let parent = match TaskAttempt::find_parent_attempt_id(pool, task_attempt.id).await? {
+        Some(parent_id) => TaskAttempt::find_by_id(pool, parent_id).await?,
+        None => None,
+    };
+    let children = TaskAttempt::find_child_attempts(pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(AttemptStack {
+        parent,
+        children,
+    })))
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, TS)]
+pub struct MergeTaskAttemptRequest {
+    /// Defaults to squash
+    #[serde(default)]
+    pub strategy: MergeStrategy,
+    /// Merge even though the target branch requires reviews or status checks on its remote
+    #[serde(default)]
+    pub override_branch_protection: bool,
+}
+
 #[axum::debug_handler]
 pub async fn merge_task_attempt(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    payload: Option<Json<MergeTaskAttemptRequest>>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let request = payload.map(|Json(request)| request).unwrap_or_default();
+    let strategy = request.strategy;
     let pool = &deployment.db().pool;
 
     let task = task_attempt
@@ -651,6 +956,25 @@ pub async fn merge_task_attempt(
         .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
     let ctx = TaskAttempt::load_context(pool, task_attempt.id, task.id, task.project_id).await?;
 
+    if let Some(message) = unmerged_parent_message(pool, &task_attempt).await? {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
+    if !request.override_branch_protection
+        && let Some(protection) = branch_protection::target_branch_protection(
+            &deployment,
+            &ctx.project.git_repo_path,
+            &ctx.task_attempt.target_branch,
+        )
+        .await
+        && protection.blocks_direct_merge()
+    {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "{}. Open a pull request instead, or merge anyway to override.",
+            protection.describe()
+        ))));
+    }
+
     let worktree_path_buf = ensure_worktree_path(&deployment, &task_attempt).await?;
     let worktree_path = worktree_path_buf.as_path();
 
@@ -687,22 +1011,40 @@ pub async fn merge_task_attempt(
         commit_message.push_str(description);
     }
 
-    let merge_commit_id = deployment.git().merge_changes(
+    let merge_commit_id = deployment.git().merge_changes_with_strategy(
         &ctx.project.git_repo_path,
         worktree_path,
         &ctx.task_attempt.branch,
         &ctx.task_attempt.target_branch,
         &commit_message,
+        strategy,
     )?;
 
-    Merge::create_direct(
+    let merge = Merge::create_direct(
         pool,
         task_attempt.id,
         &ctx.task_attempt.target_branch,
         &merge_commit_id,
+        strategy,
     )
     .await?;
+    ActivityEventRecorder::merge(pool, merge.id).await;
     Task::update_status(pool, ctx.task.id, TaskStatus::Done).await?;
+    TaskAttempt::retarget_child_attempts(
+        pool,
+        task_attempt.id,
+        &task_attempt.branch,
+        &ctx.task_attempt.target_branch,
+    )
+    .await?;
+    branch_cleanup::delete_remote_branch_after_merge(pool, deployment.git(), &task_attempt).await;
+    WebhookService::merge_completed(
+        pool,
+        &ctx.task,
+        &task_attempt,
+        &ctx.task_attempt.target_branch,
+        None,
+    );
 
     deployment
         .track_if_analytics_allowed(
@@ -711,6 +1053,7 @@ pub async fn merge_task_attempt(
                 "task_id": ctx.task.id.to_string(),
                 "project_id": ctx.project.id.to_string(),
                 "attempt_id": task_attempt.id.to_string(),
+                "strategy": strategy,
             }),
         )
         .await;
@@ -718,6 +1061,28 @@ pub async fn merge_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Dry-run the merge so the outcome is known before merging; nothing is written.
+pub async fn preview_merge_task_attempt(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<MergePreview>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let project = Project::find_by_id(pool, task.project_id)
+        .await?
+        .ok_or(ApiError::Project(ProjectError::ProjectNotFound))?;
+
+    let preview = deployment.git().preview_merge(
+        &project.git_repo_path,
+        &task_attempt.branch,
+        &task_attempt.target_branch,
+    )?;
+    Ok(ResponseJson(ApiResponse::success(preview)))
+}
+
 pub async fn push_task_attempt_branch(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
@@ -745,11 +1110,62 @@ pub async fn push_task_attempt_branch(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Push commits made since the PR was opened to the branch behind the attempt's open PR. The
+/// response carries the remote ahead/behind counts; nothing is pushed while the remote branch has
+/// commits missing from the attempt branch.
+pub async fn push_pr_updates(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<BranchPushOutcome>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let pr = match Merge::find_latest_by_task_attempt_id(pool, task_attempt.id).await? {
+        Some(Merge::Pr(pr)) if matches!(pr.pr_info.status, MergeStatus::Open) => pr,
+        _ => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "Attempt has no open pull request",
+            )));
+        }
+    };
+    let Some(token) = deployment
+        .config()
+        .read()
+        .await
+        .forge_token_for_url(&pr.pr_info.url)
+    else {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "No access token configured for {}",
+            pr.pr_info.url
+        ))));
+    };
+
+    let ws_path = ensure_worktree_path(&deployment, &task_attempt).await?;
+    let outcome = deployment
+        .git()
+        .push_branch_updates(&ws_path, &task_attempt.branch, &token)?;
+    if outcome.pushed_commits > 0 {
+        deployment
+            .track_if_analytics_allowed(
+                "pr_updates_pushed",
+                serde_json::json!({
+                    "attempt_id": task_attempt.id.to_string(),
+                    "commits": outcome.pushed_commits,
+                }),
+            )
+            .await;
+    }
+    Ok(ResponseJson(ApiResponse::success(outcome)))
+}
+
 pub async fn create_github_pr(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
     Json(request): Json<CreateGitHubPrRequest>,
 ) -> Result<ResponseJson<ApiResponse<String, GitHubServiceError>>, ApiError> {
+    if let Some(response) = gitea::try_create_gitea_pr(&deployment, &task_attempt, &request).await?
+    {
+        return Ok(ResponseJson(response));
+    }
+
     let github_config = deployment.config().read().await.github.clone();
     let Some(github_token) = github_config.token() else {
         return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -858,22 +1274,37 @@ pub async fn create_github_pr(
             .ok()
     });
 
+    // Use GitService to get the remote URL, then create GitHubRepoInfo
+    let repo_info = deployment
+        .git()
+        .get_github_repo_info(&project.git_repo_path, preferred_remote.as_deref())?;
+    let body = pr_body_with_template(
+        &deployment,
+        project.id,
+        &workspace_path,
+        request.body.as_deref(),
+    )
+    .await?;
+    let body = pr_body_with_issue_reference(
+        &deployment,
+        &task,
+        IssueTracker::GitHub,
+        &format!("{}/{}", repo_info.owner, repo_info.repo_name),
+        body,
+    )
+    .await?;
     let pr_request = CreatePrRequest {
         title: request.title.clone(),
-        body: request.body.clone(),
+        body,
         head_branch: task_attempt.branch.clone(),
         base_branch: norm_target_branch_name.clone(),
         head_repo: head_repo_info.clone(),
     };
-    // Use GitService to get the remote URL, then create GitHubRepoInfo
-    let repo_info = deployment
-        .git()
-        .get_github_repo_info(&project.git_repo_path, preferred_remote.as_deref())?;
 
     match github_service.create_pr(&repo_info, &pr_request).await {
         Ok(pr_info) => {
             // Update the task attempt with PR information
-            if let Err(e) = Merge::create_pr(
+            match Merge::create_pr(
                 pool,
                 task_attempt.id,
                 &norm_target_branch_name,
@@ -882,7 +1313,8 @@ pub async fn create_github_pr(
             )
             .await
             {
-                tracing::error!("Failed to update task attempt PR status: {}", e);
+                Ok(merge) => ActivityEventRecorder::pull_request(pool, merge.id).await,
+                Err(e) => tracing::error!("Failed to update task attempt PR status: {}", e),
             }
 
             // Auto-open PR in browser
@@ -1102,17 +1534,21 @@ pub async fn get_task_attempt_branch_status(
     // Fetch merges for this task attempt and add to branch status
     let merges = Merge::find_by_task_attempt_id(pool, task_attempt.id).await?;
     let (remote_ahead, remote_behind) = if let Some(Merge::Pr(PrMerge {
-        pr_info: PullRequestInfo {
-            status: MergeStatus::Open,
-            ..
-        },
+        pr_info:
+            PullRequestInfo {
+                status: MergeStatus::Open,
+                url: pr_url,
+                ..
+            },
         ..
     })) = merges.first()
     {
-        // check remote status if the attempt has an open PR
-        let github_config = deployment.config().read().await.github.clone();
-        let token = github_config
-            .token()
+        // check remote status if the attempt has an open PR, using the token of the PR's forge
+        let token = deployment
+            .config()
+            .read()
+            .await
+            .forge_token_for_url(pr_url)
             .ok_or(ApiError::GitHubService(GitHubServiceError::TokenInvalid))?;
         let (remote_commits_ahead, remote_commits_behind) =
             deployment.git().get_remote_branch_status(
@@ -1253,6 +1689,7 @@ pub async fn rebase_task_attempt(
     let worktree_path_buf = ensure_worktree_path(&deployment, &task_attempt).await?;
     let worktree_path = worktree_path_buf.as_path();
 
+    snapshots::snapshot_before(&deployment, &task_attempt, worktree_path, "rebase");
     let result = deployment.git().rebase_branch(
         &ctx.project.git_repo_path,
         worktree_path,
@@ -1264,15 +1701,25 @@ pub async fn rebase_task_attempt(
     if let Err(e) = result {
         use services::services::git::GitServiceError;
         return match e {
-            GitServiceError::MergeConflicts(msg) => Ok(ResponseJson(ApiResponse::<
-                (),
-                GitOperationError,
-            >::error_with_data(
-                GitOperationError::MergeConflicts {
-                    message: msg,
-                    op: ConflictOp::Rebase,
-                },
-            ))),
+            GitServiceError::MergeConflicts(msg) => {
+                // The rebase stops with the conflicts checked out, so they can be read back
+                let conflicts = deployment
+                    .git()
+                    .get_conflict_details(worktree_path)
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("Failed to read rebase conflicts: {}", e);
+                        Vec::new()
+                    });
+                Ok(ResponseJson(
+                    ApiResponse::<(), GitOperationError>::error_with_data(
+                        GitOperationError::MergeConflicts {
+                            message: msg,
+                            op: ConflictOp::Rebase,
+                            conflicts,
+                        },
+                    ),
+                ))
+            }
             GitServiceError::RebaseInProgress => Ok(ResponseJson(ApiResponse::<
                 (),
                 GitOperationError,
@@ -1429,6 +1876,78 @@ pub async fn get_task_attempt_children(
     }
 }
 
+/// Latest verify script result for the attempt, if the project has a verify script that has run.
+pub async fn get_task_attempt_verification(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<AttemptVerification>>>, ApiError> {
+    let verification =
+        AttemptVerification::find_latest_by_task_attempt_id(&deployment.db().pool, task_attempt.id)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(verification)))
+}
+
+/// Tool calls from the attempt's agents that are waiting on an approval decision, e.g. tools on
+/// the `ask` list of the profile's tool policy
+pub async fn get_task_attempt_approvals(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalPendingInfo>>>, ApiError> {
+    let process_ids: HashSet<Uuid> =
+        ExecutionProcess::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id, false)
+            .await?
+            .into_iter()
+            .map(|process| process.id)
+            .collect();
+
+    let mut pending: Vec<ApprovalPendingInfo> = deployment
+        .approvals()
+        .pending()
+        .await
+        .into_iter()
+        .filter(|approval| process_ids.contains(&approval.execution_process_id))
+        .collect();
+    pending.sort_by_key(|approval| approval.requested_at);
+    Ok(ResponseJson(ApiResponse::success(pending)))
+}
+
+/// Keep an idle attempt's worktree: counts as activity and cancels any pending cleanup warning.
+pub async fn keep_task_attempt(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if task_attempt.worktree_deleted {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The worktree for this attempt has already been cleaned up",
+        )));
+    }
+
+    let pool = &deployment.db().pool;
+    TaskAttempt::touch(pool, task_attempt.id).await?;
+    AttemptCleanupWarning::delete_by_task_attempt_id(pool, task_attempt.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_kept",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Review decision and CI checks of the attempt's open GitHub PR, as last seen by the PR monitor
+pub async fn get_task_attempt_pr_checks(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<PrCheckStatus>>>, ApiError> {
+    let status =
+        PrCheckStatus::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
 pub async fn stop_task_attempt_execution(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
@@ -1520,6 +2039,7 @@ pub async fn attach_existing_pr(
             )
             .await?;
         }
+        ActivityEventRecorder::pull_request(pool, merge.id).await;
 
         // If PR is merged, mark task as done
         if matches!(pr_info.status, MergeStatus::Merged) {
@@ -1546,6 +2066,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_attempt_id_router = Router::new()
         .route("/", get(get_task_attempt))
         .route("/follow-up", post(follow_up))
+        .route("/handoff", post(handoff_task_attempt))
         .route(
             "/draft",
             get(drafts::get_draft)
@@ -1559,17 +2080,58 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/start-dev-server", post(start_dev_server))
         .route("/branch-status", get(get_task_attempt_branch_status))
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
+        .route("/diff/content", get(diff_content::get_diff_content))
+        .route("/diff/revert", post(diff_revert::revert_diff_change))
+        .route("/diff/summary", get(diff_summary::get_diff_summary))
+        .route(
+            "/diff-comments",
+            get(diff_comments::get_diff_comments).post(diff_comments::create_diff_comment),
+        )
+        .route(
+            "/diff-comments/send",
+            post(diff_comments::send_diff_comments),
+        )
+        .route(
+            "/diff-comments/{comment_id}",
+            put(diff_comments::update_diff_comment).delete(diff_comments::delete_diff_comment),
+        )
         .route("/merge", post(merge_task_attempt))
+        .route("/merge/preview", get(preview_merge_task_attempt))
+        .route(
+            "/merge/protection",
+            get(branch_protection::get_target_branch_protection),
+        )
         .route("/push", post(push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
+        .route("/conflicts", get(conflicts::get_conflicts))
+        .route("/conflicts/resolve", post(conflicts::resolve_conflicts))
         .route("/conflicts/abort", post(abort_conflicts_task_attempt))
         .route("/pr", post(create_github_pr))
         .route("/pr/attach", post(attach_existing_pr))
+        .route("/pr/push", post(push_pr_updates))
+        .route("/pr/checks", get(get_task_attempt_pr_checks))
+        .route(
+            "/pr/review-comments/import",
+            post(review_comments::import_review_comments),
+        )
+        .route("/gitlab/mr", post(gitlab::create_gitlab_mr))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/delete-file", post(delete_task_attempt_file))
         .route("/children", get(get_task_attempt_children))
+        .route("/stack", get(get_task_attempt_stack))
         .route("/stop", post(stop_task_attempt_execution))
+        .route("/keep", post(keep_task_attempt))
+        .route("/verification", get(get_task_attempt_verification))
+        .route("/approvals", get(get_task_attempt_approvals))
+        .route("/cost", get(costs::get_task_attempt_cost))
         .route("/change-target-branch", post(change_target_branch))
+        .route("/snapshots", get(snapshots::list_snapshots))
+        .route("/snapshots/restore", post(snapshots::restore_snapshot))
+        .route("/manual-changes", get(manual_changes::get_manual_changes))
+        .route(
+            "/manual-changes/commit",
+            post(manual_changes::commit_manual_changes),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_task_attempt_middleware,
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use db::models::{
+    project::{Project, ProjectError},
+    task_attempt::{TaskAttempt, TaskAttemptError},
+};
+use deployment::Deployment;
+use services::services::github_service::{BranchProtection, GitHubService};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, routes::task_attempts::util::bare_target_branch};
+
+/// Protection of the attempt's target branch on GitHub. Best-effort: None when the target branch
+/// does not live on GitHub, no token is configured or the lookup fails.
+pub async fn target_branch_protection(
+    deployment: &DeploymentImpl,
+    repo_path: &Path,
+    target_branch: &str,
+) -> Option<BranchProtection> {
+    let token = deployment.config().read().await.github.token()?;
+    let remote = deployment
+        .git()
+        .get_remote_name_from_branch_name(repo_path, target_branch)
+        .ok();
+    let repo_info = deployment
+        .git()
+        .get_github_repo_info(repo_path, remote.as_deref())
+        .ok()?;
+    let branch = bare_target_branch(deployment, repo_path, target_branch).ok()?;
+
+    let github_service = GitHubService::new(&token).ok()?;
+    match github_service
+        .fetch_branch_protection(&repo_info, &branch)
+        .await
+    {
+        Ok(protection) => protection,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to look up protection of {}/{}@{}: {}",
+                repo_info.owner,
+                repo_info.repo_name,
+                branch,
+                e
+            );
+            None
+        }
+    }
+}
+
+pub async fn get_target_branch_protection(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<BranchProtection>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let project = Project::find_by_id(pool, task.project_id)
+        .await?
+        .ok_or(ApiError::Project(ProjectError::ProjectNotFound))?;
+
+    let protection = target_branch_protection(
+        &deployment,
+        &project.git_repo_path,
+        &task_attempt.target_branch,
+    )
+    .await;
+    Ok(ResponseJson(ApiResponse::success(protection)))
+}
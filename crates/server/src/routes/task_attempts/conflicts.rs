@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::task_attempt::TaskAttempt;
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::git::{
+    ConflictFileVersions, ConflictOp, ConflictResolution, GitServiceError,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, routes::task_attempts::util::ensure_worktree_path};
+
+#[derive(Debug, Serialize, TS)]
+pub struct ConflictState {
+    /// Operation waiting on the conflicts, or None when the worktree is clean
+    pub op: Option<ConflictOp>,
+    pub files: Vec<ConflictFileVersions>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct FileConflictResolution {
+    pub path: String,
+    pub resolution: ConflictResolution,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ResolveConflictsRequest {
+    pub resolutions: Vec<FileConflictResolution>,
+}
+
+fn conflict_state(
+    deployment: &DeploymentImpl,
+    worktree_path: &Path,
+) -> Result<ConflictState, ApiError> {
+    Ok(ConflictState {
+        op: deployment.git().detect_conflict_op(worktree_path)?,
+        files: deployment.git().get_conflict_versions(worktree_path)?,
+    })
+}
+
+pub async fn get_conflicts(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ConflictState>>, ApiError> {
+    let worktree_path = ensure_worktree_path(&deployment, &task_attempt).await?;
+    Ok(ResponseJson(ApiResponse::success(conflict_state(
+        &deployment,
+        &worktree_path,
+    )?)))
+}
+
+/// Apply per-file resolutions and, once no conflicts remain, continue the interrupted operation
+/// so the result is committed in the worktree. The returned state lists any conflicts left,
+/// including ones a rebase hits on its next commit.
+pub async fn resolve_conflicts(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<ConflictState>>, ApiError> {
+    let worktree_path = ensure_worktree_path(&deployment, &task_attempt).await?;
+    if deployment
+        .git()
+        .detect_conflict_op(&worktree_path)?
+        .is_none()
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "No merge, rebase, cherry-pick or revert is in progress",
+        )));
+    }
+
+    for FileConflictResolution { path, resolution } in &payload.resolutions {
+        if let Err(e) = deployment
+            .git()
+            .resolve_conflict(&worktree_path, path, resolution)
+        {
+            return match e {
+                GitServiceError::InvalidRepository(msg) => {
+                    Ok(ResponseJson(ApiResponse::error(&msg)))
+                }
+                other => Err(other.into()),
+            };
+        }
+    }
+
+    if deployment
+        .git()
+        .get_conflicted_files(&worktree_path)?
+        .is_empty()
+    {
+        match deployment
+            .git()
+            .continue_conflicted_operation(&worktree_path)
+        {
+            Ok(_) | Err(GitServiceError::MergeConflicts(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "conflicts_resolved",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+                "file_count": payload.resolutions.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(conflict_state(
+        &deployment,
+        &worktree_path,
+    )?)))
+}
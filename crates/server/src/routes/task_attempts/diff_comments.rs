@@ -0,0 +1,194 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    diff_comment::{CreateDiffComment, DiffComment, UpdateDiffComment},
+    draft::DraftType,
+    task_attempt::TaskAttempt,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    drafts::{DraftResponse, SetQueueRequest, UpdateFollowUpDraftRequest},
+    review_comments::render_diff_comments_prompt,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct DiffCommentsQuery {
+    /// Also list comments that were already sent to the agent
+    #[serde(default)]
+    pub include_sent: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SendDiffCommentsRequest {
+    /// Queue the follow-up so it is sent as soon as the agent is idle
+    #[serde(default)]
+    pub queue: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SendDiffCommentsResponse {
+    pub comments: Vec<DiffComment>,
+    pub draft: DraftResponse,
+}
+
+/// The comment with `comment_id`, if it belongs to the attempt
+async fn find_attempt_comment(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    comment_id: Uuid,
+) -> Result<Option<DiffComment>, ApiError> {
+    Ok(DiffComment::find_by_id(&deployment.db().pool, comment_id)
+        .await?
+        .filter(|comment| comment.task_attempt_id == task_attempt.id))
+}
+
+pub async fn get_diff_comments(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DiffCommentsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<DiffComment>>>, ApiError> {
+    let comments = DiffComment::find_by_task_attempt_id(
+        &deployment.db().pool,
+        task_attempt.id,
+        query.include_sent,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(comments)))
+}
+
+pub async fn create_diff_comment(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateDiffComment>,
+) -> Result<ResponseJson<ApiResponse<DiffComment>>, ApiError> {
+    if payload.file_path.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "File path cannot be empty",
+        )));
+    }
+    if payload.body.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error("Comment cannot be empty")));
+    }
+    let end_line = payload.end_line.unwrap_or(payload.start_line);
+    if payload.start_line < 1 || end_line < payload.start_line {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Invalid line range {}-{end_line}",
+            payload.start_line
+        ))));
+    }
+    let comment = DiffComment::create(&deployment.db().pool, task_attempt.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+pub async fn update_diff_comment(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, comment_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateDiffComment>,
+) -> Result<ResponseJson<ApiResponse<DiffComment>>, ApiError> {
+    if find_attempt_comment(&deployment, &task_attempt, comment_id)
+        .await?
+        .is_none()
+    {
+        return Ok(ResponseJson(ApiResponse::error("Comment not found")));
+    }
+    if payload.body.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error("Comment cannot be empty")));
+    }
+    let comment =
+        DiffComment::update_body(&deployment.db().pool, comment_id, &payload.body).await?;
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+pub async fn delete_diff_comment(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if find_attempt_comment(&deployment, &task_attempt, comment_id)
+        .await?
+        .is_none()
+    {
+        return Ok(ResponseJson(ApiResponse::error("Comment not found")));
+    }
+    DiffComment::delete(&deployment.db().pool, comment_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Compose the attempt's pending diff comments into the follow-up draft, appending them to any
+/// text already there, and mark them sent.
+pub async fn send_diff_comments(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<SendDiffCommentsRequest>,
+) -> Result<ResponseJson<ApiResponse<SendDiffCommentsResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let comments = DiffComment::find_by_task_attempt_id(pool, task_attempt.id, false).await?;
+    if comments.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "There are no pending comments to send",
+        )));
+    }
+
+    let drafts = deployment.drafts();
+    let current = drafts
+        .get_draft(task_attempt.id, DraftType::FollowUp)
+        .await?;
+    let rendered = render_diff_comments_prompt(&comments);
+    let prompt = if current.prompt.trim().is_empty() {
+        rendered
+    } else {
+        format!("{}\n\n{rendered}", current.prompt.trim_end())
+    };
+
+    let mut draft = drafts
+        .save_follow_up_draft(
+            &task_attempt,
+            &UpdateFollowUpDraftRequest {
+                prompt: Some(prompt),
+                variant: None,
+                image_ids: None,
+                version: Some(current.version),
+            },
+        )
+        .await?;
+    let ids: Vec<Uuid> = comments.iter().map(|comment| comment.id).collect();
+    DiffComment::mark_sent(pool, &ids).await?;
+    if request.queue {
+        draft = drafts
+            .set_follow_up_queue(
+                deployment.container(),
+                &task_attempt,
+                &SetQueueRequest {
+                    queued: true,
+                    expected_queued: Some(false),
+                    expected_version: Some(draft.version),
+                },
+            )
+            .await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "diff_comments_sent",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+                "comments": comments.len(),
+                "queued": request.queue,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        SendDiffCommentsResponse { comments, draft },
+    )))
+}
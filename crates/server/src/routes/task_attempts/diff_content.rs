@@ -0,0 +1,60 @@
+use axum::{
+    Extension,
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use db::models::task_attempt::TaskAttempt;
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::container::ContainerService;
+use utils::{
+    diff::{DiffSide, ImageInfo},
+    response::ApiResponse,
+};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct DiffContentQuery {
+    pub side: DiffSide,
+    pub path: String,
+    /// The repository the diff was streamed for, when it was filtered to one
+    #[serde(default)]
+    pub repo_id: Option<Uuid>,
+}
+
+/// Bytes of one side of a file in the attempt's diff, so binary files such as images can be
+/// previewed. Only recognized images are served with their own content type.
+pub async fn get_diff_content(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DiffContentQuery>,
+) -> Result<Response, ApiError> {
+    let Some(bytes) = deployment
+        .container()
+        .diff_file_content(&task_attempt, query.repo_id, query.side, &query.path)
+        .await?
+    else {
+        return Ok((
+            StatusCode::NOT_FOUND,
+            ResponseJson(ApiResponse::<()>::error(
+                "File not found on that side of the diff",
+            )),
+        )
+            .into_response());
+    };
+    let content_type = ImageInfo::sniff(&bytes)
+        .map(|image| image.mime_type)
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            (header::CACHE_CONTROL, "no-cache".to_string()),
+        ],
+        bytes,
+    )
+        .into_response())
+}
@@ -0,0 +1,78 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    task_attempt::TaskAttempt,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::container::ContainerService;
+use ts_rs::TS;
+use utils::{diff::HunkRange, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RevertDiffChangeRequest {
+    pub path: String,
+    /// Where a renamed file used to live; it is restored there
+    #[serde(default)]
+    pub old_path: Option<String>,
+    /// The repository the diff was streamed for, when it was filtered to one
+    #[serde(default)]
+    pub repo_id: Option<Uuid>,
+    /// Revert only this hunk instead of the whole file
+    #[serde(default)]
+    pub hunk: Option<HunkRange>,
+}
+
+/// Discard a file's changes, or one hunk of them, in the attempt worktree. Open diff streams pick
+/// the result up from the worktree watcher.
+pub async fn revert_diff_change(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RevertDiffChangeRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let agent_running = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id, false)
+        .await?
+        .iter()
+        .any(|p| {
+            matches!(p.status, ExecutionProcessStatus::Running)
+                && !matches!(p.run_reason, ExecutionProcessRunReason::DevServer)
+        });
+    if agent_running {
+        return Err(ApiError::Conflict(
+            "Cannot revert changes while a process is running".to_string(),
+        ));
+    }
+
+    let reverted = deployment
+        .container()
+        .revert_diff_change(
+            &task_attempt,
+            payload.repo_id,
+            &payload.path,
+            payload.old_path.as_deref(),
+            payload.hunk.as_ref(),
+        )
+        .await?;
+    if !reverted {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The attempt has been merged; its changes can no longer be reverted",
+        )));
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "diff_change_reverted",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+                "hunk": payload.hunk.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
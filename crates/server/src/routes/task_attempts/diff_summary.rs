@@ -0,0 +1,74 @@
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::task_attempt::TaskAttempt;
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{container::ContainerService, git::GitService};
+use ts_rs::TS;
+use utils::{diff::DiffChangeKind, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct DiffSummaryQuery {
+    /// Only count files in this repository
+    #[serde(default)]
+    pub repo_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DiffFileSummary {
+    pub path: String,
+    /// Set for renames and copies
+    pub old_path: Option<String>,
+    pub change: DiffChangeKind,
+    pub repository_id: Option<Uuid>,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DiffSummary {
+    pub files: Vec<DiffFileSummary>,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Per-file line counts for the attempt's changes, computed without file contents so list views
+/// can show change sizes without opening a diff stream.
+pub async fn get_diff_summary(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DiffSummaryQuery>,
+) -> Result<ResponseJson<ApiResponse<DiffSummary>>, ApiError> {
+    let files: Vec<DiffFileSummary> = deployment
+        .container()
+        .diff_stats(&task_attempt)
+        .await?
+        .into_iter()
+        .filter(|diff| query.repo_id.is_none() || diff.repository_id == query.repo_id)
+        .map(|diff| DiffFileSummary {
+            path: GitService::diff_path(&diff),
+            old_path: diff.old_path.clone().filter(|_| {
+                matches!(
+                    diff.change,
+                    DiffChangeKind::Renamed | DiffChangeKind::Copied
+                )
+            }),
+            change: diff.change,
+            repository_id: diff.repository_id,
+            additions: diff.additions.unwrap_or(0),
+            deletions: diff.deletions.unwrap_or(0),
+        })
+        .collect();
+
+    Ok(ResponseJson(ApiResponse::success(DiffSummary {
+        additions: files.iter().map(|file| file.additions).sum(),
+        deletions: files.iter().map(|file| file.deletions).sum(),
+        files,
+    })))
+}
@@ -1,6 +1,6 @@
 use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
 use db::models::{
-    draft::DraftType,
+    draft::{Draft, DraftType},
     task_attempt::{TaskAttempt, TaskAttemptError},
 };
 use deployment::Deployment;
@@ -10,7 +10,20 @@ use services::services::drafts::{
 };
 use utils::response::ApiResponse;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, middleware::CurrentUser};
+
+/// Record the signed-in user as the last to change the draft
+async fn record_editor(
+    deployment: &DeploymentImpl,
+    current_user: &CurrentUser,
+    task_attempt: &TaskAttempt,
+    draft_type: DraftType,
+) -> Result<(), ApiError> {
+    if let Some(user_id) = current_user.id() {
+        Draft::set_updated_by(&deployment.db().pool, task_attempt.id, draft_type, user_id).await?;
+    }
+    Ok(())
+}
 
 #[derive(Debug, Deserialize)]
 pub struct DraftTypeQuery {
@@ -21,6 +34,7 @@ pub struct DraftTypeQuery {
 #[axum::debug_handler]
 pub async fn save_follow_up_draft(
     Extension(task_attempt): Extension<TaskAttempt>,
+    Extension(current_user): Extension<CurrentUser>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateFollowUpDraftRequest>,
 ) -> Result<ResponseJson<ApiResponse<DraftResponse>>, ApiError> {
@@ -28,12 +42,20 @@ pub async fn save_follow_up_draft(
     let resp = service
         .save_follow_up_draft(&task_attempt, &payload)
         .await?;
+    record_editor(
+        &deployment,
+        &current_user,
+        &task_attempt,
+        DraftType::FollowUp,
+    )
+    .await?;
     Ok(ResponseJson(ApiResponse::success(resp)))
 }
 
 #[axum::debug_handler]
 pub async fn save_retry_follow_up_draft(
     Extension(task_attempt): Extension<TaskAttempt>,
+    Extension(current_user): Extension<CurrentUser>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateRetryFollowUpDraftRequest>,
 ) -> Result<ResponseJson<ApiResponse<DraftResponse>>, ApiError> {
@@ -41,6 +63,7 @@ pub async fn save_retry_follow_up_draft(
     let resp = service
         .save_retry_follow_up_draft(&task_attempt, &payload)
         .await?;
+    record_editor(&deployment, &current_user, &task_attempt, DraftType::Retry).await?;
     Ok(ResponseJson(ApiResponse::success(resp)))
 }
 
@@ -81,6 +104,7 @@ pub async fn get_draft(
 #[axum::debug_handler]
 pub async fn save_draft(
     Extension(task_attempt): Extension<TaskAttempt>,
+    Extension(current_user): Extension<CurrentUser>,
     State(deployment): State<DeploymentImpl>,
     axum::extract::Query(q): axum::extract::Query<DraftTypeQuery>,
     Json(payload): Json<serde_json::Value>,
@@ -93,6 +117,7 @@ pub async fn save_draft(
                     ApiError::TaskAttempt(TaskAttemptError::ValidationError(e.to_string()))
                 })?;
             let resp = service.save_follow_up_draft(&task_attempt, &body).await?;
+            record_editor(&deployment, &current_user, &task_attempt, q.draft_type).await?;
             Ok(ResponseJson(ApiResponse::success(resp)))
         }
         DraftType::Retry => {
@@ -103,6 +128,7 @@ pub async fn save_draft(
             let resp = service
                 .save_retry_follow_up_draft(&task_attempt, &body)
                 .await?;
+            record_editor(&deployment, &current_user, &task_attempt, q.draft_type).await?;
             Ok(ResponseJson(ApiResponse::success(resp)))
         }
     }
@@ -0,0 +1,157 @@
+use db::models::{
+    merge::Merge,
+    project::{Project, ProjectError},
+    task_attempt::{TaskAttempt, TaskAttemptError},
+};
+use deployment::Deployment;
+use services::{
+    activity_feed::ActivityEventRecorder,
+    services::{
+        gitea_service::{CreateGiteaPrRequest, GiteaRepoInfo, GiteaService, GiteaServiceError},
+        github_service::GitHubServiceError,
+    },
+};
+use utils::response::ApiResponse;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::task_attempts::{
+        CreateGitHubPrRequest,
+        util::{bare_target_branch, ensure_worktree_path, pr_body_with_template},
+    },
+};
+
+/// Create the PR on the configured Gitea instance when the attempt's remote lives there.
+/// Returns None when Gitea is not configured or the remote belongs to another host, in which
+/// case the caller continues with GitHub.
+pub async fn try_create_gitea_pr(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    request: &CreateGitHubPrRequest,
+) -> Result<Option<ApiResponse<String, GitHubServiceError>>, ApiError> {
+    let gitea_config = deployment.config().read().await.gitea.clone();
+    let (Some(base_url), Some(token)) = (gitea_config.resolved_base_url(), gitea_config.token())
+    else {
+        return Ok(None);
+    };
+
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let project = Project::find_by_id(pool, task.project_id)
+        .await?
+        .ok_or(ApiError::Project(ProjectError::ProjectNotFound))?;
+
+    // Gitea PRs are opened within a single repository, so head and base share one remote
+    let workspace_path = ensure_worktree_path(deployment, task_attempt).await?;
+    let remote_name = request
+        .head_remote_name
+        .clone()
+        .or_else(|| request.remote_name.clone())
+        .or_else(|| {
+            deployment
+                .git()
+                .get_remote_name_from_branch_name(&workspace_path, &task_attempt.branch)
+                .ok()
+        });
+    let Ok(remote_url) = deployment
+        .git()
+        .get_remote_url(&project.git_repo_path, remote_name.as_deref())
+    else {
+        return Ok(None);
+    };
+    let Some(repo_info) = GiteaRepoInfo::from_remote_url(&remote_url, &base_url) else {
+        return Ok(None);
+    };
+
+    if let Err(e) = deployment.git().push_with_token(
+        &workspace_path,
+        &task_attempt.branch,
+        remote_name.as_deref(),
+        &token,
+    ) {
+        tracing::error!("Failed to push branch to Gitea: {}", e);
+        return Ok(Some(ApiResponse::error(&format!(
+            "Failed to push branch to Gitea: {e}"
+        ))));
+    }
+
+    let target_branch = request
+        .target_branch
+        .clone()
+        .filter(|branch| !branch.trim().is_empty())
+        .unwrap_or_else(|| task_attempt.target_branch.clone());
+    let target_branch = bare_target_branch(deployment, &project.git_repo_path, &target_branch)?;
+
+    let gitea_service = match GiteaService::new(&token) {
+        Ok(service) => service,
+        Err(e) => {
+            return Ok(Some(ApiResponse::error(&format!(
+                "Failed to create Gitea client: {e}"
+            ))));
+        }
+    };
+    let body = pr_body_with_template(
+        deployment,
+        project.id,
+        &workspace_path,
+        request.body.as_deref(),
+    )
+    .await?;
+    let pr_request = CreateGiteaPrRequest {
+        title: request.title.clone(),
+        body,
+        head_branch: task_attempt.branch.clone(),
+        base_branch: target_branch.clone(),
+    };
+
+    match gitea_service.create_pr(&repo_info, &pr_request).await {
+        Ok(pr_info) => {
+            match Merge::create_pr(
+                pool,
+                task_attempt.id,
+                &target_branch,
+                pr_info.number,
+                &pr_info.url,
+            )
+            .await
+            {
+                Ok(merge) => ActivityEventRecorder::pull_request(pool, merge.id).await,
+                Err(e) => tracing::error!("Failed to update task attempt PR status: {}", e),
+            }
+
+            if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
+                tracing::warn!("Failed to open PR in browser: {}", e);
+            }
+            deployment
+                .track_if_analytics_allowed(
+                    "gitea_pr_created",
+                    serde_json::json!({
+                        "task_id": task.id.to_string(),
+                        "project_id": project.id.to_string(),
+                        "attempt_id": task_attempt.id.to_string(),
+                    }),
+                )
+                .await;
+
+            Ok(Some(ApiResponse::success(pr_info.url)))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to create Gitea PR for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            let message = match e {
+                GiteaServiceError::TokenInvalid => {
+                    "Gitea token is invalid or expired. Update it in settings.".to_string()
+                }
+                other => format!("Failed to create PR: {other}"),
+            };
+            Ok(Some(ApiResponse::error(&message)))
+        }
+    }
+}
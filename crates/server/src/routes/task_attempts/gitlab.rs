@@ -0,0 +1,170 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    issue_sync::IssueTracker,
+    merge::Merge,
+    project::{Project, ProjectError},
+    task_attempt::{TaskAttempt, TaskAttemptError},
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::{
+    activity_feed::ActivityEventRecorder,
+    services::gitlab_service::{
+        CreateMrRequest, GitLabRepoInfo, GitLabService, GitLabServiceError,
+    },
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::task_attempts::util::{
+        bare_target_branch, ensure_worktree_path, pr_body_with_issue_reference,
+        pr_body_with_template,
+    },
+};
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CreateGitLabMrRequest {
+    pub title: String,
+    pub body: Option<String>,
+    pub target_branch: Option<String>,
+    /// Remote pointing at the GitLab project; defaults to the branch's remote
+    pub remote_name: Option<String>,
+}
+
+/// Push the attempt branch to GitLab and open a merge request for it. The MR is stored like a
+/// GitHub PR, so its status is tracked and shown on the attempt the same way.
+pub async fn create_gitlab_mr(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<CreateGitLabMrRequest>,
+) -> Result<ResponseJson<ApiResponse<String, GitLabServiceError>>, ApiError> {
+    let gitlab_config = deployment.config().read().await.gitlab.clone();
+    let Some(gitlab_token) = gitlab_config.token() else {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            GitLabServiceError::TokenInvalid,
+        )));
+    };
+    let gitlab_service = GitLabService::new(&gitlab_token)?;
+
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let project = Project::find_by_id(pool, task.project_id)
+        .await?
+        .ok_or(ApiError::Project(ProjectError::ProjectNotFound))?;
+
+    let workspace_path = ensure_worktree_path(&deployment, &task_attempt).await?;
+    let remote_name = request.remote_name.clone().or_else(|| {
+        deployment
+            .git()
+            .get_remote_name_from_branch_name(&workspace_path, &task_attempt.branch)
+            .ok()
+    });
+
+    let remote_url = deployment
+        .git()
+        .get_remote_url(&project.git_repo_path, remote_name.as_deref())?;
+    let base_url = gitlab_config.resolved_base_url();
+    let Some(repo_info) = GitLabRepoInfo::from_remote_url(&remote_url, &base_url) else {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Remote '{remote_url}' is not a project on {base_url}"
+        ))));
+    };
+
+    if let Err(e) = deployment.git().push_with_token(
+        &workspace_path,
+        &task_attempt.branch,
+        remote_name.as_deref(),
+        &gitlab_token,
+    ) {
+        tracing::error!("Failed to push branch to GitLab: {}", e);
+        let gl_e = GitLabServiceError::from(e);
+        if gl_e.is_api_data() {
+            return Ok(ResponseJson(ApiResponse::error_with_data(gl_e)));
+        } else {
+            return Ok(ResponseJson(ApiResponse::error(
+                format!("Failed to push branch to GitLab: {}", gl_e).as_str(),
+            )));
+        }
+    }
+
+    let target_branch = request
+        .target_branch
+        .filter(|branch| !branch.trim().is_empty())
+        .unwrap_or_else(|| task_attempt.target_branch.clone());
+    let target_branch = bare_target_branch(&deployment, &project.git_repo_path, &target_branch)?;
+
+    let description = pr_body_with_template(
+        &deployment,
+        project.id,
+        &workspace_path,
+        request.body.as_deref(),
+    )
+    .await?;
+    let description = pr_body_with_issue_reference(
+        &deployment,
+        &task,
+        IssueTracker::GitLab,
+        &repo_info.project_path,
+        description,
+    )
+    .await?;
+    let mr_request = CreateMrRequest {
+        title: request.title.clone(),
+        description,
+        source_branch: task_attempt.branch.clone(),
+        target_branch: target_branch.clone(),
+    };
+
+    match gitlab_service.create_mr(&repo_info, &mr_request).await {
+        Ok(mr_info) => {
+            match Merge::create_pr(
+                pool,
+                task_attempt.id,
+                &target_branch,
+                mr_info.number,
+                &mr_info.url,
+            )
+            .await
+            {
+                Ok(merge) => ActivityEventRecorder::pull_request(pool, merge.id).await,
+                Err(e) => tracing::error!("Failed to record merge request for attempt: {}", e),
+            }
+
+            if let Err(e) = utils::browser::open_browser(&mr_info.url).await {
+                tracing::warn!("Failed to open MR in browser: {}", e);
+            }
+            deployment
+                .track_if_analytics_allowed(
+                    "gitlab_mr_created",
+                    serde_json::json!({
+                        "task_id": task.id.to_string(),
+                        "project_id": project.id.to_string(),
+                        "attempt_id": task_attempt.id.to_string(),
+                    }),
+                )
+                .await;
+
+            Ok(ResponseJson(ApiResponse::success(mr_info.url)))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to create GitLab MR for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            if e.is_api_data() {
+                Ok(ResponseJson(ApiResponse::error_with_data(e)))
+            } else {
+                Ok(ResponseJson(ApiResponse::error(
+                    format!("Failed to create merge request: {}", e).as_str(),
+                )))
+            }
+        }
+    }
+}
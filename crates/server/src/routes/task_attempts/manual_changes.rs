@@ -0,0 +1,78 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    attempt_manual_changes::AttemptManualChanges,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    task_attempt::TaskAttempt,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, routes::task_attempts::util::ensure_worktree_path};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CommitManualChangesRequest {
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct CommitManualChangesResponse {
+    /// False when the worktree had nothing to commit
+    pub committed: bool,
+}
+
+pub async fn get_manual_changes(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<AttemptManualChanges>>>, ApiError> {
+    let changes =
+        AttemptManualChanges::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(changes)))
+}
+
+pub async fn commit_manual_changes(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CommitManualChangesRequest>,
+) -> Result<ResponseJson<ApiResponse<CommitManualChangesResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let agent_running = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id, false)
+        .await?
+        .iter()
+        .any(|p| {
+            matches!(p.status, ExecutionProcessStatus::Running)
+                && !matches!(p.run_reason, ExecutionProcessRunReason::DevServer)
+        });
+    if agent_running {
+        return Err(ApiError::Conflict(
+            "Cannot commit manual changes while a process is running".to_string(),
+        ));
+    }
+
+    let message = payload
+        .message
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| format!("Commit manual changes for task attempt {}", task_attempt.id));
+
+    let worktree_path = ensure_worktree_path(&deployment, &task_attempt).await?;
+    let committed = deployment.git().commit(&worktree_path, &message)?;
+    AttemptManualChanges::clear(pool, task_attempt.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "manual_changes_committed",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+                "committed": committed,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        CommitManualChangesResponse { committed },
+    )))
+}
@@ -0,0 +1,124 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    draft::DraftType,
+    merge::{Merge, MergeStatus},
+    task_attempt::TaskAttempt,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    drafts::{DraftResponse, SetQueueRequest, UpdateFollowUpDraftRequest},
+    github_service::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+    review_comments::{ReviewThread, render_review_threads_prompt},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ImportReviewCommentsRequest {
+    /// Queue the follow-up so it is sent as soon as the agent is idle
+    #[serde(default)]
+    pub queue: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ImportReviewCommentsResponse {
+    pub threads: Vec<ReviewThread>,
+    pub draft: DraftResponse,
+}
+
+/// Pull the unresolved review threads of the attempt's open GitHub PR into the follow-up draft.
+/// The threads are appended to any text already in the draft.
+pub async fn import_review_comments(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<ImportReviewCommentsRequest>,
+) -> Result<ResponseJson<ApiResponse<ImportReviewCommentsResponse, GitHubServiceError>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let pr = match Merge::find_latest_by_task_attempt_id(pool, task_attempt.id).await? {
+        Some(Merge::Pr(pr)) if matches!(pr.pr_info.status, MergeStatus::Open) => pr,
+        _ => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "Attempt has no open pull request",
+            )));
+        }
+    };
+    let Ok(repo_info) = GitHubRepoInfo::from_remote_url(&pr.pr_info.url) else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Review comments can only be imported from GitHub pull requests",
+        )));
+    };
+
+    let github_config = deployment.config().read().await.github.clone();
+    let Some(github_token) = github_config.token() else {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            GitHubServiceError::TokenInvalid,
+        )));
+    };
+    let threads = match GitHubService::new(&github_token)?
+        .fetch_unresolved_review_threads(&repo_info, pr.pr_info.number)
+        .await
+    {
+        Ok(threads) => threads,
+        Err(e) if e.is_api_data() => return Ok(ResponseJson(ApiResponse::error_with_data(e))),
+        Err(e) => return Err(e.into()),
+    };
+    if threads.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The pull request has no unresolved review comments",
+        )));
+    }
+
+    let drafts = deployment.drafts();
+    let current = drafts
+        .get_draft(task_attempt.id, DraftType::FollowUp)
+        .await?;
+    let rendered = render_review_threads_prompt(&pr.pr_info.url, &threads);
+    let prompt = if current.prompt.trim().is_empty() {
+        rendered
+    } else {
+        format!("{}\n\n{rendered}", current.prompt.trim_end())
+    };
+
+    let mut draft = drafts
+        .save_follow_up_draft(
+            &task_attempt,
+            &UpdateFollowUpDraftRequest {
+                prompt: Some(prompt),
+                variant: None,
+                image_ids: None,
+                version: Some(current.version),
+            },
+        )
+        .await?;
+    if request.queue {
+        draft = drafts
+            .set_follow_up_queue(
+                deployment.container(),
+                &task_attempt,
+                &SetQueueRequest {
+                    queued: true,
+                    expected_queued: Some(false),
+                    expected_version: Some(draft.version),
+                },
+            )
+            .await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_review_comments_imported",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+                "threads": threads.len(),
+                "queued": request.queue,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        ImportReviewCommentsResponse { threads, draft },
+    )))
+}
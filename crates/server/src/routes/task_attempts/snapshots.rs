@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::task_attempt::TaskAttempt;
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::git::{GitServiceError, WorktreeSnapshot};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, routes::task_attempts::util::ensure_worktree_path};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RestoreSnapshotRequest {
+    pub oid: String,
+}
+
+/// Save the attempt's uncommitted changes before `reason` runs. Best-effort: a failed snapshot is
+/// logged and does not block the operation.
+pub fn snapshot_before(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    worktree_path: &Path,
+    reason: &str,
+) -> Option<WorktreeSnapshot> {
+    match deployment
+        .git()
+        .snapshot_worktree(worktree_path, &task_attempt.id.to_string(), reason)
+    {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to snapshot worktree of attempt {} before {}: {}",
+                task_attempt.id,
+                reason,
+                e
+            );
+            None
+        }
+    }
+}
+
+pub async fn list_snapshots(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorktreeSnapshot>>>, ApiError> {
+    let worktree_path = ensure_worktree_path(&deployment, &task_attempt).await?;
+    let snapshots = deployment
+        .git()
+        .list_worktree_snapshots(&worktree_path, &task_attempt.id.to_string())?;
+    Ok(ResponseJson(ApiResponse::success(snapshots)))
+}
+
+pub async fn restore_snapshot(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RestoreSnapshotRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let worktree_path = ensure_worktree_path(&deployment, &task_attempt).await?;
+    let known = deployment
+        .git()
+        .list_worktree_snapshots(&worktree_path, &task_attempt.id.to_string())?
+        .iter()
+        .any(|snapshot| snapshot.oid == payload.oid);
+    if !known {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Snapshot not found for this attempt",
+        )));
+    }
+    match deployment
+        .git()
+        .restore_worktree_snapshot(&worktree_path, &payload.oid)
+    {
+        Ok(()) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(GitServiceError::MergeConflicts(message)) => {
+            Ok(ResponseJson(ApiResponse::error(&message)))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
@@ -1,6 +1,16 @@
-use db::models::image::TaskImage;
+use db::models::{
+    image::TaskImage,
+    issue_sync::{IssueSync, IssueTracker, TaskIssue},
+    project::Project,
+    task::Task,
+};
 use deployment::Deployment;
-use services::services::{container::ContainerService, image::ImageService};
+use services::services::{
+    container::ContainerService,
+    image::ImageService,
+    issue_sync::{issue_reference, with_closing_reference},
+    pr_template::{find_pr_template, merge_pr_template},
+};
 use uuid::Uuid;
 
 use crate::error::ApiError;
@@ -43,3 +53,67 @@ pub async fn handle_images_for_prompt(
         &worktree_path,
     ))
 }
+
+/// Strip the `{remote}/` prefix from a remote-tracking target branch, since forge APIs expect the
+/// bare branch name.
+pub fn bare_target_branch(
+    deployment: &crate::DeploymentImpl,
+    repo_path: &std::path::Path,
+    target_branch: &str,
+) -> Result<String, ApiError> {
+    if !matches!(
+        deployment
+            .git()
+            .find_branch_type(repo_path, target_branch)?,
+        git2::BranchType::Remote
+    ) {
+        return Ok(target_branch.to_string());
+    }
+    let remote = deployment
+        .git()
+        .get_remote_name_from_branch_name(repo_path, target_branch)?;
+    Ok(target_branch
+        .strip_prefix(&format!("{remote}/"))
+        .unwrap_or(target_branch)
+        .to_string())
+}
+
+/// PR description to send to the forge: the requested body merged with the repository's PR
+/// template when the worktree has one.
+pub async fn pr_body_with_template(
+    deployment: &crate::DeploymentImpl,
+    project_id: Uuid,
+    worktree_path: &std::path::Path,
+    body: Option<&str>,
+) -> Result<Option<String>, ApiError> {
+    let settings = Project::find_pr_settings(&deployment.db().pool, project_id).await?;
+    Ok(
+        match find_pr_template(worktree_path, settings.template_path.as_deref()) {
+            Some(template) => Some(merge_pr_template(body, &template)),
+            None => body.map(str::to_string),
+        },
+    )
+}
+
+/// For tasks imported from an issue, add a closing reference to the PR or MR description so the
+/// forge links it and closes the issue when it merges. `pr_repo_path` is the full path of the
+/// repository the PR is opened in on `pr_tracker`.
+pub async fn pr_body_with_issue_reference(
+    deployment: &crate::DeploymentImpl,
+    task: &Task,
+    pr_tracker: IssueTracker,
+    pr_repo_path: &str,
+    body: Option<String>,
+) -> Result<Option<String>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(issue) = TaskIssue::find_by_task_id(pool, task.id).await? else {
+        return Ok(body);
+    };
+    let Some(sync) = IssueSync::find_by_project_id(pool, task.project_id).await? else {
+        return Ok(body);
+    };
+    match issue_reference(&sync, pr_tracker, pr_repo_path, issue.issue_number) {
+        Some(issue_ref) => Ok(Some(with_closing_reference(body.as_deref(), &issue_ref))),
+        None => Ok(body),
+    }
+}
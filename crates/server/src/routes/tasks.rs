@@ -1,3 +1,5 @@
+pub mod dependencies;
+
 use std::path::PathBuf;
 
 use anyhow;
@@ -7,22 +9,35 @@ use axum::{
         Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
-    http::StatusCode,
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    routing::{delete, get, post, put},
 };
-use db::models::{
-    image::TaskImage,
-    task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
-    task_attempt::{CreateTaskAttempt, CreateTaskAttemptRepository, TaskAttempt},
+use db::{
+    models::{
+        image::TaskImage,
+        issue_sync::TaskIssue,
+        jira_issue::TaskJiraIssue,
+        label::{Label, SetTaskLabels},
+        project_repository::ProjectRepository,
+        task::{
+            CreateTask, OverdueTask, SetTaskDueDate, Task, TaskListOptions, TaskSortField,
+            TaskWithAttemptStatus, UpdateTask,
+        },
+        task_attempt::{CreateTaskAttempt, CreateTaskAttemptRepository, TaskAttempt},
+        task_attempt_repository::TaskAttemptRepository,
+    },
+    pagination::{Page, SortDirection},
 };
 use deployment::Deployment;
 use executors::profile::ExecutorProfileId;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use services::services::container::{
-    ContainerService, WorktreeCleanupData, cleanup_worktrees_direct,
+use services::services::{
+    branch_suggestions::{BranchSuggestion, BranchSuggestionInput, rank_branches},
+    container::{ContainerService, WorktreeCleanupData, cleanup_worktrees_direct},
+    webhooks::WebhookService,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
@@ -30,23 +45,78 @@ use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_task_middleware,
-    routes::task_attempts::CreateTaskAttemptRepositoryBody,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{CurrentUser, load_task_middleware},
+    routes::{costs, task_attempts::CreateTaskAttemptRepositoryBody},
 };
 
+/// Set on paged list responses to the number of items across all pages
+pub const TOTAL_COUNT_HEADER: HeaderName = HeaderName::from_static("x-total-count");
+
+pub fn total_count_headers(total: i64) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(TOTAL_COUNT_HEADER, HeaderValue::from(total));
+    headers
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TaskQuery {
     pub project_id: Uuid,
+    /// Only tasks carrying this label
+    #[serde(default)]
+    pub label_id: Option<Uuid>,
+    #[serde(default)]
+    pub sort: TaskSortField,
+    #[serde(default)]
+    pub order: SortDirection,
+    /// Page size; all tasks are returned when left out
+    #[serde(default)]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    pub offset: Option<u32>,
+}
+
+impl TaskQuery {
+    pub fn list_options(&self) -> TaskListOptions {
+        TaskListOptions {
+            label_id: self.label_id,
+            sort: self.sort,
+            direction: self.order,
+            page: Page::new(self.limit, self.offset),
+        }
+    }
 }
 
 pub async fn get_tasks(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<TaskQuery>,
-) -> Result<ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>, ApiError> {
-    let tasks =
-        Task::find_by_project_id_with_attempt_status(&deployment.db().pool, query.project_id)
-            .await?;
+) -> Result<
+    (
+        HeaderMap,
+        ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>,
+    ),
+    ApiError,
+> {
+    let (tasks, total) = Task::find_page_by_project_id_with_attempt_status(
+        &deployment.db().pool,
+        query.project_id,
+        &query.list_options(),
+    )
+    .await?;
 
+    Ok((
+        total_count_headers(total),
+        ResponseJson(ApiResponse::success(tasks)),
+    ))
+}
+
+pub async fn get_overdue_tasks(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<OverdueTask>>>, ApiError> {
+    let tasks =
+        Task::find_overdue(&deployment.db().pool, query.project_id, chrono::Utc::now()).await?;
     Ok(ResponseJson(ApiResponse::success(tasks)))
 }
 
@@ -56,7 +126,9 @@ pub async fn stream_tasks_ws(
     Query(query): Query<TaskQuery>,
 ) -> impl IntoResponse {
     ws.on_upgrade(move |socket| async move {
-        if let Err(e) = handle_tasks_ws(socket, deployment, query.project_id).await {
+        if let Err(e) =
+            handle_tasks_ws(socket, deployment, query.project_id, query.list_options()).await
+        {
             tracing::warn!("tasks WS closed: {}", e);
         }
     })
@@ -66,11 +138,12 @@ async fn handle_tasks_ws(
     socket: WebSocket,
     deployment: DeploymentImpl,
     project_id: Uuid,
+    options: TaskListOptions,
 ) -> anyhow::Result<()> {
     // Get the raw stream and convert LogMsg to WebSocket messages
     let mut stream = deployment
         .events()
-        .stream_tasks_raw(project_id)
+        .stream_tasks_raw(project_id, options)
         .await?
         .map_ok(|msg| msg.to_ws_message_unchecked());
 
@@ -129,6 +202,7 @@ pub async fn create_task(
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
+    WebhookService::task_created(&deployment.db().pool, &task);
 
     deployment
         .track_if_analytics_allowed(
@@ -156,6 +230,7 @@ pub struct CreateAndStartTaskRequest {
 
 pub async fn create_task_and_start(
     State(deployment): State<DeploymentImpl>,
+    Extension(current_user): Extension<CurrentUser>,
     Json(payload): Json<CreateAndStartTaskRequest>,
 ) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
     let task_id = Uuid::new_v4();
@@ -164,6 +239,7 @@ pub async fn create_task_and_start(
     if let Some(image_ids) = &payload.task.image_ids {
         TaskImage::associate_many(&deployment.db().pool, task.id, image_ids).await?;
     }
+    WebhookService::task_created(&deployment.db().pool, &task);
 
     deployment
         .track_if_analytics_allowed(
@@ -206,6 +282,9 @@ pub async fn create_task_and_start(
 
     let task_attempt =
         TaskAttempt::create(&deployment.db().pool, &create_request, attempt_id, task.id).await?;
+    if let Some(user_id) = current_user.id() {
+        TaskAttempt::set_created_by(&deployment.db().pool, task_attempt.id, user_id).await?;
+    }
     let execution_process = deployment
         .container()
         .start_attempt(&task_attempt, payload.executor_profile_id.clone())
@@ -234,6 +313,8 @@ pub async fn create_task_and_start(
         has_merged_attempt: false,
         last_attempt_failed: false,
         executor: task_attempt.executor,
+        labels: Vec::new(),
+        due_at: None,
     })))
 }
 
@@ -349,14 +430,187 @@ pub async fn delete_task(
     Ok((StatusCode::ACCEPTED, ResponseJson(ApiResponse::success(()))))
 }
 
+pub async fn set_task_labels(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetTaskLabels>,
+) -> Result<ResponseJson<ApiResponse<Vec<Label>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Label::set_for_task(pool, task.id, task.project_id, &payload.label_ids).await?;
+    let labels = Label::find_by_task_for_project(pool, task.project_id)
+        .await?
+        .remove(&task.id)
+        .unwrap_or_default();
+    Ok(ResponseJson(ApiResponse::success(labels)))
+}
+
+pub async fn set_task_due_date(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetTaskDueDate>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    Task::set_due_at(&deployment.db().pool, task.id, payload.due_at).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// The GitHub or GitLab issue the task was imported from, if any
+pub async fn get_task_issue(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskIssue>>>, ApiError> {
+    let issue = TaskIssue::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(issue)))
+}
+
+/// The Jira issue the task was imported from, if any
+pub async fn get_task_jira_issue(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskJiraIssue>>>, ApiError> {
+    let issue = TaskJiraIssue::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(issue)))
+}
+
+/// Suggest base branches for a new attempt of this task, best candidates first.
+pub async fn get_task_branch_suggestions(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<BranchSuggestion>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let project = task
+        .parent_project(pool)
+        .await?
+        .ok_or_else(|| ApiError::Database(SqlxError::RowNotFound))?;
+
+    let attempts = TaskAttempt::fetch_all(pool, Some(task.id)).await?;
+    let parent_attempt = match task.parent_task_attempt {
+        Some(parent_id) => TaskAttempt::find_by_id(pool, parent_id).await?,
+        None => None,
+    };
+
+    // Per-repository base branches used by earlier attempts (newest first), plus the parent
+    // attempt's per-repository working branches.
+    let mut previous_by_repo: Vec<(Uuid, String)> = Vec::new();
+    for attempt in &attempts {
+        for repo in TaskAttemptRepository::list_for_attempt(pool, attempt.id).await? {
+            if let Some(base) = repo.base_branch {
+                previous_by_repo.push((repo.project_repository_id, base));
+            }
+        }
+    }
+    let parent_by_repo: Vec<(Uuid, String)> = match parent_attempt.as_ref() {
+        Some(parent) => TaskAttemptRepository::list_for_attempt(pool, parent.id)
+            .await?
+            .into_iter()
+            .filter_map(|repo| repo.branch.map(|b| (repo.project_repository_id, b)))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    // Prefer the repositories the task has already worked in; otherwise every project root.
+    let mut repositories = ProjectRepository::list_for_project(pool, project.id).await?;
+    if repositories
+        .iter()
+        .any(|repo| previous_by_repo.iter().any(|(id, _)| *id == repo.id))
+    {
+        repositories.retain(|repo| previous_by_repo.iter().any(|(id, _)| *id == repo.id));
+    }
+
+    let targets: Vec<(Option<ProjectRepository>, PathBuf)> = if repositories.is_empty() {
+        vec![(None, project.git_repo_path.clone())]
+    } else {
+        repositories
+            .into_iter()
+            .map(|repo| {
+                let path = repo.git_repo_path.clone();
+                (Some(repo), path)
+            })
+            .collect()
+    };
+
+    let mut suggestions = Vec::new();
+    for (repo, repo_path) in targets {
+        let mut branches = match deployment.git().get_all_branches(&repo_path) {
+            Ok(branches) => branches,
+            Err(e) => {
+                tracing::warn!("Failed to list branches for {}: {}", repo_path.display(), e);
+                continue;
+            }
+        };
+        if let Some(repo) = repo.as_ref() {
+            for branch in branches.iter_mut() {
+                branch.repository_id = Some(repo.id);
+                branch.repository_name = Some(repo.name.clone());
+            }
+        }
+
+        let is_primary = repo.as_ref().is_none_or(|r| r.is_primary);
+        let repo_id = repo.as_ref().map(|r| r.id);
+        let mut previous_targets: Vec<String> = previous_by_repo
+            .iter()
+            .filter(|(id, _)| Some(*id) == repo_id)
+            .map(|(_, branch)| branch.clone())
+            .collect();
+        let mut parent_attempt_branch = parent_by_repo
+            .iter()
+            .find(|(id, _)| Some(*id) == repo_id)
+            .map(|(_, branch)| branch.clone());
+        // Legacy single-repo attempts only record their branches on the attempt itself
+        if is_primary {
+            if previous_targets.is_empty() {
+                previous_targets = attempts.iter().map(|a| a.target_branch.clone()).collect();
+            }
+            if parent_attempt_branch.is_none() {
+                parent_attempt_branch = parent_attempt.as_ref().map(|p| p.branch.clone());
+            }
+        }
+
+        let default_branch = deployment
+            .git()
+            .get_remote_default_branch(&repo_path)
+            .ok()
+            .flatten();
+
+        suggestions.extend(rank_branches(
+            BranchSuggestionInput {
+                branches,
+                default_branch,
+                previous_targets,
+                parent_attempt_branch,
+                task_title: &task.title,
+                task_description: task.description.as_deref(),
+            },
+            chrono::Utc::now(),
+        ));
+    }
+
+    Ok(ResponseJson(ApiResponse::success(suggestions)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_id_router = Router::new()
         .route("/", get(get_task).put(update_task).delete(delete_task))
+        .route("/branch-suggestions", get(get_task_branch_suggestions))
+        .route("/labels", put(set_task_labels))
+        .route("/due-date", put(set_task_due_date))
+        .route("/cost", get(costs::get_task_cost))
+        .route("/issue", get(get_task_issue))
+        .route("/jira-issue", get(get_task_jira_issue))
+        .route(
+            "/dependencies",
+            get(dependencies::get_task_dependencies).post(dependencies::add_task_dependency),
+        )
+        .route(
+            "/dependencies/{depends_on_task_id}",
+            delete(dependencies::remove_task_dependency),
+        )
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
     let inner = Router::new()
         .route("/", get(get_tasks).post(create_task))
         .route("/stream/ws", get(stream_tasks_ws))
+        .route("/dependencies", get(dependencies::get_dependency_graph))
+        .route("/overdue", get(get_overdue_tasks))
         .route("/create-and-start", post(create_task_and_start))
         .nest("/{task_id}", task_id_router);
 
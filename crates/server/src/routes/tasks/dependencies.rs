@@ -0,0 +1,127 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    task::Task,
+    task_dependency::{TaskDependency, TaskDependencyError},
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, routes::tasks::TaskQuery};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTaskDependency {
+    pub depends_on_task_id: Uuid,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct TaskDependencies {
+    /// Tasks that have to be done before this one can start
+    pub depends_on: Vec<Task>,
+    /// Tasks waiting on this one
+    pub dependents: Vec<Task>,
+    pub blocked: bool,
+}
+
+/// Dependency edges of a project's board, plus the tasks they currently block
+#[derive(Debug, Serialize, TS)]
+pub struct TaskDependencyGraph {
+    pub dependencies: Vec<TaskDependency>,
+    pub blocked_task_ids: Vec<Uuid>,
+}
+
+/// Message explaining why `task` cannot start attempts, if any of its dependencies are not done
+pub async fn blocked_reason(
+    deployment: &DeploymentImpl,
+    task: &Task,
+) -> Result<Option<String>, ApiError> {
+    let unfinished =
+        TaskDependency::find_unfinished_dependencies(&deployment.db().pool, task.id).await?;
+    if unfinished.is_empty() {
+        return Ok(None);
+    }
+    let titles = unfinished
+        .iter()
+        .map(|(_, title)| format!("'{title}'"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok(Some(format!(
+        "Task is blocked until these tasks are done: {titles}"
+    )))
+}
+
+async fn load_tasks(deployment: &DeploymentImpl, ids: Vec<Uuid>) -> Result<Vec<Task>, ApiError> {
+    let mut tasks = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(task) = Task::find_by_id(&deployment.db().pool, id).await? {
+            tasks.push(task);
+        }
+    }
+    Ok(tasks)
+}
+
+pub async fn get_dependency_graph(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskQuery>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencyGraph>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let dependencies = TaskDependency::find_by_project_id(pool, query.project_id).await?;
+    let blocked_task_ids = TaskDependency::find_blocked_task_ids(pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(TaskDependencyGraph {
+        dependencies,
+        blocked_task_ids,
+    })))
+}
+
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let depends_on = load_tasks(
+        &deployment,
+        TaskDependency::find_dependency_ids(pool, task.id).await?,
+    )
+    .await?;
+    let dependents = load_tasks(
+        &deployment,
+        TaskDependency::find_dependent_ids(pool, task.id).await?,
+    )
+    .await?;
+    let blocked = blocked_reason(&deployment, &task).await?.is_some();
+    Ok(ResponseJson(ApiResponse::success(TaskDependencies {
+        depends_on,
+        dependents,
+        blocked,
+    })))
+}
+
+pub async fn add_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskDependency>,
+) -> Result<ResponseJson<ApiResponse<TaskDependency>>, ApiError> {
+    match TaskDependency::add(&deployment.db().pool, &task, payload.depends_on_task_id).await {
+        Ok(dependency) => Ok(ResponseJson(ApiResponse::success(dependency))),
+        Err(TaskDependencyError::Database(e)) => Err(e.into()),
+        Err(e) => Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    }
+}
+
+pub async fn remove_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, depends_on_task_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if TaskDependency::delete(&deployment.db().pool, task.id, depends_on_task_id).await? {
+        Ok(ResponseJson(ApiResponse::success(())))
+    } else {
+        Ok(ResponseJson(ApiResponse::error("Dependency not found")))
+    }
+}
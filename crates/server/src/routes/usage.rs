@@ -1,14 +1,40 @@
 use std::{
+    collections::HashMap,
     fs::File,
     io::{BufRead, BufReader},
     path::{Path, PathBuf},
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
-use axum::{Router, response::Json as ResponseJson, routing::get};
+use axum::{
+    Router,
+    extract::{
+        Path as UrlPath, Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::{StatusCode, header},
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::get,
+};
 use chrono::{DateTime, Timelike, Utc};
+use db::models::{
+    execution_process_token_usage::ExecutionProcessTokenUsage,
+    executor_session::ExecutorSession,
+    usage_sample::{CreateUsageSample, UsageAgent, UsageSample},
+};
+use deployment::Deployment;
+use executors::executors::BaseCodingAgent;
+use futures_util::{SinkExt, StreamExt, stream::SplitSink};
 use ignore::WalkBuilder;
 use serde::{Deserialize, Serialize};
+use services::services::{
+    filesystem_watcher::{self, WatcherOptions},
+    usage_attribution::{
+        UsageAttribution, attribute_usage, claude_code_projects_dir, codex_sessions_dir,
+    },
+    usage_export::usage_csv,
+};
+use sqlx::SqlitePool;
 use tokio::task;
 use tracing::warn;
 use ts_rs::TS;
@@ -16,11 +42,17 @@ use ts_rs::TS;
 use crate::{DeploymentImpl, error::ApiError};
 
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
         .route("/usage/codex", get(get_codex_usage))
         .route("/usage/claude-code", get(get_claude_code_usage))
+        .route("/usage/summary", get(get_usage_summary))
+        .route("/usage/stream/ws", get(stream_usage_ws))
+        .route("/usage/attribution", get(get_usage_attribution))
+        .route("/usage/export", get(export_usage_csv))
+        .route("/usage/{agent}/history", get(get_usage_history))
 }
 
 #[derive(Debug, Clone, TS, serde::Serialize)]
@@ -74,26 +106,24 @@ pub struct CodexTokenUsage {
 
 pub async fn get_codex_usage()
 -> Result<ResponseJson<ApiResponse<Option<CodexUsageSnapshot>>>, ApiError> {
-    let snapshot = task::spawn_blocking(collect_codex_usage)
+    let snapshot = load_codex_usage().await?;
+    Ok(ResponseJson(ApiResponse::success(snapshot)))
+}
+
+async fn load_codex_usage() -> std::io::Result<Option<CodexUsageSnapshot>> {
+    task::spawn_blocking(collect_codex_usage)
         .await
         .map_err(|err| {
             warn!("failed to join codex usage task: {err}");
             std::io::Error::new(std::io::ErrorKind::Other, "codex usage task failed")
-        })??;
-
-    Ok(ResponseJson(ApiResponse::success(snapshot)))
+        })?
 }
 
 fn collect_codex_usage() -> std::io::Result<Option<CodexUsageSnapshot>> {
-    let Some(home) = dirs::home_dir() else {
+    let Some(sessions_dir) = codex_sessions_dir().filter(|dir| dir.exists()) else {
         return Ok(None);
     };
 
-    let sessions_dir = home.join(".codex").join("sessions");
-    if !sessions_dir.exists() {
-        return Ok(None);
-    }
-
     let mut candidates: Vec<(SystemTime, PathBuf)> = Vec::new();
     for entry in WalkBuilder::new(&sessions_dir)
         .hidden(false)
@@ -711,33 +741,31 @@ pub struct ClaudeCodeTokenUsage {
 
 pub async fn get_claude_code_usage()
 -> Result<ResponseJson<ApiResponse<Option<ClaudeCodeUsageSnapshot>>>, ApiError> {
+    let snapshot = load_claude_code_usage().await?;
+    Ok(ResponseJson(ApiResponse::success(snapshot)))
+}
+
+async fn load_claude_code_usage() -> std::io::Result<Option<ClaudeCodeUsageSnapshot>> {
     // Load config to get the Claude plan
     let config_path = utils::assets::config_path();
     let config = services::services::config::load_config_from_file(&config_path).await;
     let estimated_limit = config.claude_plan.token_limit_per_5h_block();
 
-    let snapshot = task::spawn_blocking(move || collect_claude_code_usage(estimated_limit))
+    task::spawn_blocking(move || collect_claude_code_usage(estimated_limit))
         .await
         .map_err(|err| {
             warn!("failed to join claude code usage task: {err}");
             std::io::Error::new(std::io::ErrorKind::Other, "claude code usage task failed")
-        })??;
-
-    Ok(ResponseJson(ApiResponse::success(snapshot)))
+        })?
 }
 
 fn collect_claude_code_usage(
     estimated_limit: u64,
 ) -> std::io::Result<Option<ClaudeCodeUsageSnapshot>> {
-    let Some(home) = dirs::home_dir() else {
+    let Some(projects_dir) = claude_code_projects_dir().filter(|dir| dir.exists()) else {
         return Ok(None);
     };
 
-    let projects_dir = home.join(".claude").join("projects");
-    if !projects_dir.exists() {
-        return Ok(None);
-    }
-
     let mut candidates: Vec<(SystemTime, PathBuf)> = Vec::new();
     for entry in WalkBuilder::new(&projects_dir)
         .hidden(false)
@@ -813,6 +841,16 @@ fn collect_claude_code_usage(
     Ok(latest.map(|(_, snapshot)| snapshot))
 }
 
+/// End of the five-hour block a Claude Code snapshot captured at `captured_at` counts usage in
+fn claude_code_block_end(captured_at: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(captured_at)
+        .ok()
+        .map(|captured_at| {
+            get_five_hour_block_start(&captured_at.with_timezone(&Utc))
+                + chrono::Duration::minutes(CLAUDE_CODE_WINDOW_MINUTES as i64)
+        })
+}
+
 fn get_five_hour_block_start(timestamp: &DateTime<Utc>) -> DateTime<Utc> {
     let hour = timestamp.hour();
     let block_number = hour / 5;
@@ -1050,3 +1088,578 @@ mod claude_code_tests {
         assert_eq!(snapshot.token_usage.total_tokens, 285);
     }
 }
+
+// ============================================================================
+// Usage History
+// ============================================================================
+
+/// How often usage is sampled, and how long samples are kept
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const SAMPLE_RETENTION_DAYS: i64 = 90;
+const DEFAULT_HISTORY_HOURS: i64 = 24;
+
+#[derive(Debug, Deserialize)]
+pub struct UsageHistoryQuery {
+    /// Defaults to a day before `to`
+    pub from: Option<DateTime<Utc>>,
+    /// Defaults to now
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Samples between `from` and `to`, oldest first, for charting
+pub async fn get_usage_history(
+    State(deployment): State<DeploymentImpl>,
+    UrlPath(agent): UrlPath<UsageAgent>,
+    Query(query): Query<UsageHistoryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<UsageSample>>>, ApiError> {
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query
+        .from
+        .unwrap_or_else(|| to - chrono::Duration::hours(DEFAULT_HISTORY_HOURS));
+    if from > to {
+        return Ok(ResponseJson(ApiResponse::error(
+            "`from` must not be after `to`",
+        )));
+    }
+    let samples = UsageSample::find_range(&deployment.db().pool, agent, from, to).await?;
+    Ok(ResponseJson(ApiResponse::success(samples)))
+}
+
+fn codex_sample(snapshot: &CodexUsageSnapshot) -> CreateUsageSample {
+    let limits = &snapshot.rate_limits;
+    let resets_at = DateTime::parse_from_rfc3339(&snapshot.captured_at)
+        .ok()
+        .zip(
+            limits
+                .primary
+                .as_ref()
+                .and_then(|window| window.resets_in_seconds),
+        )
+        .map(|(captured_at, resets_in_seconds)| {
+            captured_at.with_timezone(&Utc) + chrono::Duration::seconds(resets_in_seconds as i64)
+        });
+    CreateUsageSample {
+        agent: UsageAgent::Codex,
+        used_percent: limits.primary.as_ref().map(|window| window.used_percent),
+        secondary_used_percent: limits.secondary.as_ref().map(|window| window.used_percent),
+        total_tokens: snapshot
+            .token_usage
+            .as_ref()
+            .and_then(|info| i64::try_from(info.total_token_usage.total_tokens).ok()),
+        resets_at,
+    }
+}
+
+fn claude_code_sample(snapshot: &ClaudeCodeUsageSnapshot) -> CreateUsageSample {
+    CreateUsageSample {
+        agent: UsageAgent::ClaudeCode,
+        used_percent: Some(snapshot.used_percent),
+        secondary_used_percent: None,
+        total_tokens: i64::try_from(snapshot.token_usage.total_tokens).ok(),
+        resets_at: claude_code_block_end(&snapshot.captured_at),
+    }
+}
+
+async fn record_usage_samples(pool: &SqlitePool) {
+    let mut samples = Vec::new();
+    match load_codex_usage().await {
+        Ok(Some(snapshot)) => samples.push(codex_sample(&snapshot)),
+        Ok(None) => {}
+        Err(err) => warn!("failed to sample codex usage: {err}"),
+    }
+    match load_claude_code_usage().await {
+        Ok(Some(snapshot)) => samples.push(claude_code_sample(&snapshot)),
+        Ok(None) => {}
+        Err(err) => warn!("failed to sample claude code usage: {err}"),
+    }
+    for sample in &samples {
+        if let Err(err) = UsageSample::create(pool, sample).await {
+            warn!("failed to store {} usage sample: {err}", sample.agent);
+        }
+    }
+
+    let cutoff = Utc::now() - chrono::Duration::days(SAMPLE_RETENTION_DAYS);
+    if let Err(err) = UsageSample::delete_before(pool, cutoff).await {
+        warn!("failed to prune usage samples: {err}");
+    }
+}
+
+/// Samples usage of each agent that has local logs every few minutes, so history can be shown
+/// rather than only the latest snapshot
+pub fn spawn_usage_sampler(pool: SqlitePool) -> task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+        loop {
+            interval.tick().await;
+            record_usage_samples(&pool).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+
+    #[test]
+    fn samples_keep_the_charted_figures() {
+        let codex = CodexUsageSnapshot {
+            captured_at: "2025-11-08T09:00:00Z".to_string(),
+            rate_limits: CodexUsageRateLimits {
+                primary: Some(CodexUsageWindow {
+                    used_percent: 42.5,
+                    window_minutes: Some(300),
+                    resets_in_seconds: Some(600),
+                }),
+                secondary: None,
+            },
+            token_usage: None,
+        };
+        let sample = codex_sample(&codex);
+        assert_eq!(sample.agent, UsageAgent::Codex);
+        assert_eq!(sample.used_percent, Some(42.5));
+        assert_eq!(sample.secondary_used_percent, None);
+        assert_eq!(sample.total_tokens, None);
+        assert_eq!(
+            sample.resets_at.map(|at| at.to_rfc3339()),
+            Some("2025-11-08T09:10:00+00:00".to_string())
+        );
+
+        let claude = ClaudeCodeUsageSnapshot {
+            captured_at: "2025-11-08T09:00:00Z".to_string(),
+            session_info: ClaudeCodeSessionInfo {
+                session_id: "session".to_string(),
+                version: "1.0.0".to_string(),
+                git_branch: None,
+                cwd: None,
+            },
+            token_usage: ClaudeCodeTokenUsage {
+                total_tokens: 1_200,
+                ..Default::default()
+            },
+            estimated_limit: 10_000,
+            used_percent: 12.0,
+        };
+        let sample = claude_code_sample(&claude);
+        assert_eq!(sample.agent, UsageAgent::ClaudeCode);
+        assert_eq!(sample.used_percent, Some(12.0));
+        assert_eq!(sample.total_tokens, Some(1_200));
+        assert_eq!(
+            sample.resets_at.map(|at| at.to_rfc3339()),
+            Some("2025-11-08T10:00:00+00:00".to_string())
+        );
+    }
+}
+
+// ============================================================================
+// Usage Summary
+// ============================================================================
+
+/// Length of the Claude Code block that usage is counted in
+const CLAUDE_CODE_WINDOW_MINUTES: u64 = 5 * 60;
+
+/// A rate limit window, in the same shape for every agent
+#[derive(Debug, Clone, PartialEq, TS, Serialize)]
+pub struct UsageWindow {
+    /// `primary` or `secondary` for Codex, `five_hour` for Claude Code
+    pub name: String,
+    pub used_percent: f64,
+    #[ts(type = "number | null")]
+    pub window_minutes: Option<u64>,
+    #[ts(type = "number | null")]
+    pub resets_in_seconds: Option<u64>,
+}
+
+/// One agent's latest usage, normalized so agents can be shown side by side
+#[derive(Debug, Clone, TS, Serialize)]
+pub struct AgentUsageSummary {
+    pub agent: UsageAgent,
+    /// `false` when the agent has no local logs to read usage from
+    pub available: bool,
+    pub captured_at: Option<String>,
+    pub windows: Vec<UsageWindow>,
+    #[ts(type = "number | null")]
+    pub total_tokens: Option<u64>,
+    /// Why usage could not be read; the other agents are still summarized
+    pub error: Option<String>,
+}
+
+impl AgentUsageSummary {
+    fn unavailable(agent: UsageAgent, error: Option<String>) -> Self {
+        Self {
+            agent,
+            available: false,
+            captured_at: None,
+            windows: Vec::new(),
+            total_tokens: None,
+            error,
+        }
+    }
+}
+
+fn codex_summary(result: std::io::Result<Option<CodexUsageSnapshot>>) -> AgentUsageSummary {
+    let snapshot = match result {
+        Ok(Some(snapshot)) => snapshot,
+        Ok(None) => return AgentUsageSummary::unavailable(UsageAgent::Codex, None),
+        Err(err) => {
+            return AgentUsageSummary::unavailable(UsageAgent::Codex, Some(err.to_string()));
+        }
+    };
+    let windows = [
+        ("primary", snapshot.rate_limits.primary),
+        ("secondary", snapshot.rate_limits.secondary),
+    ]
+    .into_iter()
+    .filter_map(|(name, window)| {
+        window.map(|window| UsageWindow {
+            name: name.to_string(),
+            used_percent: window.used_percent,
+            window_minutes: window.window_minutes,
+            resets_in_seconds: window.resets_in_seconds,
+        })
+    })
+    .collect();
+    AgentUsageSummary {
+        agent: UsageAgent::Codex,
+        available: true,
+        captured_at: Some(snapshot.captured_at),
+        windows,
+        total_tokens: snapshot
+            .token_usage
+            .map(|info| info.total_token_usage.total_tokens),
+        error: None,
+    }
+}
+
+fn claude_code_summary(
+    result: std::io::Result<Option<ClaudeCodeUsageSnapshot>>,
+    now: DateTime<Utc>,
+) -> AgentUsageSummary {
+    let snapshot = match result {
+        Ok(Some(snapshot)) => snapshot,
+        Ok(None) => return AgentUsageSummary::unavailable(UsageAgent::ClaudeCode, None),
+        Err(err) => {
+            return AgentUsageSummary::unavailable(UsageAgent::ClaudeCode, Some(err.to_string()));
+        }
+    };
+    let resets_in_seconds = claude_code_block_end(&snapshot.captured_at)
+        .map(|block_end| (block_end - now).num_seconds().max(0) as u64);
+    AgentUsageSummary {
+        agent: UsageAgent::ClaudeCode,
+        available: true,
+        captured_at: Some(snapshot.captured_at),
+        windows: vec![UsageWindow {
+            name: "five_hour".to_string(),
+            used_percent: snapshot.used_percent,
+            window_minutes: Some(CLAUDE_CODE_WINDOW_MINUTES),
+            resets_in_seconds,
+        }],
+        total_tokens: Some(snapshot.token_usage.total_tokens),
+        error: None,
+    }
+}
+
+/// Latest usage of every agent with a usage collector, read concurrently
+pub async fn get_usage_summary()
+-> Result<ResponseJson<ApiResponse<Vec<AgentUsageSummary>>>, ApiError> {
+    let (codex, claude_code) = tokio::join!(load_codex_usage(), load_claude_code_usage());
+    let summaries = vec![
+        codex_summary(codex),
+        claude_code_summary(claude_code, Utc::now()),
+    ];
+    Ok(ResponseJson(ApiResponse::success(summaries)))
+}
+
+#[cfg(test)]
+mod summary_tests {
+    use super::*;
+
+    #[test]
+    fn agents_are_summarized_in_one_shape() {
+        let codex = codex_summary(Ok(Some(CodexUsageSnapshot {
+            captured_at: "2025-11-10T09:00:00+00:00".to_string(),
+            rate_limits: CodexUsageRateLimits {
+                primary: None,
+                secondary: Some(CodexUsageWindow {
+                    used_percent: 7.5,
+                    window_minutes: Some(10_080),
+                    resets_in_seconds: Some(3_600),
+                }),
+            },
+            token_usage: None,
+        })));
+        assert!(codex.available);
+        assert_eq!(
+            codex.windows,
+            [UsageWindow {
+                name: "secondary".to_string(),
+                used_percent: 7.5,
+                window_minutes: Some(10_080),
+                resets_in_seconds: Some(3_600),
+            }]
+        );
+        assert_eq!(codex.total_tokens, None);
+
+        let claude = claude_code_summary(
+            Ok(Some(ClaudeCodeUsageSnapshot {
+                captured_at: "2025-11-10T09:30:00+00:00".to_string(),
+                session_info: ClaudeCodeSessionInfo {
+                    session_id: "session".to_string(),
+                    version: "1.0.0".to_string(),
+                    git_branch: None,
+                    cwd: None,
+                },
+                token_usage: ClaudeCodeTokenUsage {
+                    total_tokens: 4_000,
+                    ..Default::default()
+                },
+                estimated_limit: 40_000,
+                used_percent: 10.0,
+            })),
+            "2025-11-10T09:45:00Z".parse().unwrap(),
+        );
+        assert_eq!(claude.total_tokens, Some(4_000));
+        // The 05:00-10:00 block resets 15 minutes later
+        assert_eq!(claude.windows[0].resets_in_seconds, Some(15 * 60));
+    }
+
+    #[test]
+    fn missing_logs_and_failures_are_unavailable() {
+        let missing = codex_summary(Ok(None));
+        assert!(!missing.available);
+        assert_eq!(missing.error, None);
+
+        let failed = claude_code_summary(
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "boom")),
+            Utc::now(),
+        );
+        assert!(!failed.available);
+        assert_eq!(failed.error.as_deref(), Some("boom"));
+    }
+}
+
+// ============================================================================
+// Live Usage Updates
+// ============================================================================
+
+/// An agent's latest snapshot, sent over `/usage/stream/ws` when the socket opens and again
+/// whenever the agent's session logs change
+#[derive(Debug, Clone, TS, Serialize)]
+#[serde(tag = "agent", content = "snapshot", rename_all = "kebab-case")]
+pub enum UsageUpdate {
+    Codex(Option<CodexUsageSnapshot>),
+    ClaudeCode(Option<ClaudeCodeUsageSnapshot>),
+}
+
+async fn load_usage_update(agent: UsageAgent) -> std::io::Result<UsageUpdate> {
+    Ok(match agent {
+        UsageAgent::Codex => UsageUpdate::Codex(load_codex_usage().await?),
+        UsageAgent::ClaudeCode => UsageUpdate::ClaudeCode(load_claude_code_usage().await?),
+    })
+}
+
+fn usage_log_dir(agent: UsageAgent) -> Option<PathBuf> {
+    match agent {
+        UsageAgent::Codex => codex_sessions_dir(),
+        UsageAgent::ClaudeCode => claude_code_projects_dir(),
+    }
+}
+
+/// Send the agent's snapshot unless it is the one sent last
+async fn send_usage_update(
+    sender: &mut SplitSink<WebSocket, Message>,
+    last_sent: &mut HashMap<UsageAgent, String>,
+    agent: UsageAgent,
+) -> anyhow::Result<()> {
+    let update = match load_usage_update(agent).await {
+        Ok(update) => update,
+        Err(e) => {
+            warn!("failed to read {} usage: {}", agent, e);
+            return Ok(());
+        }
+    };
+    let payload = serde_json::to_string(&update)?;
+    if last_sent.get(&agent) != Some(&payload) {
+        sender.send(Message::Text(payload.clone().into())).await?;
+        last_sent.insert(agent, payload);
+    }
+    Ok(())
+}
+
+pub async fn stream_usage_ws(ws: WebSocketUpgrade) -> impl IntoResponse {
+    ws.on_upgrade(|socket| async move {
+        if let Err(e) = handle_usage_ws(socket).await {
+            warn!("usage WS closed: {}", e);
+        }
+    })
+}
+
+async fn handle_usage_ws(socket: WebSocket) -> anyhow::Result<()> {
+    const AGENTS: [UsageAgent; 2] = [UsageAgent::Codex, UsageAgent::ClaudeCode];
+
+    let (mut sender, mut receiver) = socket.split();
+
+    // The watchers stop when their debouncers are dropped, at the end of this function
+    let (changed_tx, mut changed_rx) = tokio::sync::mpsc::channel::<UsageAgent>(16);
+    let mut debouncers = Vec::new();
+    for agent in AGENTS {
+        let Some(dir) = usage_log_dir(agent).filter(|dir| dir.exists()) else {
+            continue;
+        };
+        let options = WatcherOptions {
+            exclude_patterns: Vec::new(),
+            max_events_per_second: None,
+        };
+        let (debouncer, mut events, _) =
+            match task::spawn_blocking(move || filesystem_watcher::async_watcher(dir, options))
+                .await?
+            {
+                Ok(components) => components,
+                Err(e) => {
+                    warn!("failed to watch {} usage logs: {}", agent, e);
+                    continue;
+                }
+            };
+        debouncers.push(debouncer);
+        let changed_tx = changed_tx.clone();
+        tokio::spawn(async move {
+            while let Some(result) = events.next().await {
+                if result.is_ok() && changed_tx.send(agent).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    drop(changed_tx);
+
+    let mut last_sent: HashMap<UsageAgent, String> = HashMap::new();
+    for agent in AGENTS {
+        send_usage_update(&mut sender, &mut last_sent, agent).await?;
+    }
+    loop {
+        tokio::select! {
+            Some(agent) = changed_rx.recv() => {
+                // Collapse a burst of changes into one read per agent
+                let mut agents = vec![agent];
+                while let Ok(agent) = changed_rx.try_recv() {
+                    if !agents.contains(&agent) {
+                        agents.push(agent);
+                    }
+                }
+                for agent in agents {
+                    send_usage_update(&mut sender, &mut last_sent, agent).await?;
+                }
+            }
+            message = receiver.next() => {
+                if !matches!(message, Some(Ok(_))) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Usage per Project and Task
+// ============================================================================
+
+const DEFAULT_ATTRIBUTION_DAYS: i64 = 7;
+
+#[derive(Debug, Deserialize)]
+pub struct UsageAttributionQuery {
+    /// Defaults to a week before `to`
+    pub from: Option<DateTime<Utc>>,
+    /// Defaults to now
+    pub to: Option<DateTime<Utc>>,
+    pub project_id: Option<Uuid>,
+}
+
+/// Tokens the agents' session logs record between `from` and `to`, attributed to the projects and
+/// tasks that started the sessions
+pub async fn get_usage_attribution(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageAttributionQuery>,
+) -> Result<ResponseJson<ApiResponse<UsageAttribution>>, ApiError> {
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query
+        .from
+        .unwrap_or_else(|| to - chrono::Duration::days(DEFAULT_ATTRIBUTION_DAYS));
+    if from > to {
+        return Ok(ResponseJson(ApiResponse::error(
+            "`from` must not be after `to`",
+        )));
+    }
+    let owners = ExecutorSession::find_owners_active_between(
+        &deployment.db().pool,
+        from,
+        to,
+        query.project_id,
+    )
+    .await?;
+    let attribution = task::spawn_blocking(move || attribute_usage(owners, from, to))
+        .await
+        .map_err(|err| {
+            warn!("failed to join usage attribution task: {err}");
+            std::io::Error::new(std::io::ErrorKind::Other, "usage attribution task failed")
+        })?;
+    Ok(ResponseJson(ApiResponse::success(attribution)))
+}
+
+// ============================================================================
+// CSV Export
+// ============================================================================
+
+const DEFAULT_EXPORT_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize)]
+pub struct UsageExportQuery {
+    /// Defaults to 30 days before `to`
+    pub from: Option<DateTime<Utc>>,
+    /// Defaults to now
+    pub to: Option<DateTime<Utc>>,
+    /// Only processes started with this agent
+    pub agent: Option<BaseCodingAgent>,
+}
+
+/// Download the token usage recorded for processes started between `from` and `to`, with
+/// estimated costs, as a CSV file
+pub async fn export_usage_csv(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageExportQuery>,
+) -> Result<Response, ApiError> {
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query
+        .from
+        .unwrap_or_else(|| to - chrono::Duration::days(DEFAULT_EXPORT_DAYS));
+    if from > to {
+        return Ok((
+            StatusCode::BAD_REQUEST,
+            ResponseJson(ApiResponse::<()>::error("`from` must not be after `to`")),
+        )
+            .into_response());
+    }
+    let records = ExecutionProcessTokenUsage::find_records_between(
+        &deployment.db().pool,
+        from,
+        to,
+        query.agent,
+    )
+    .await?;
+    let model_pricing = deployment.config().read().await.model_pricing.clone();
+    let file_name = format!(
+        "usage-{}-to-{}.csv",
+        from.format("%Y-%m-%d"),
+        to.format("%Y-%m-%d")
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        usage_csv(&records, &model_pricing),
+    )
+        .into_response())
+}
@@ -0,0 +1,216 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, header},
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use chrono::Utc;
+use db::models::{
+    user::{CreateUser, UpdateUser, User, UserRole},
+    user_session::UserSession,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::users::{
+    SESSION_TTL, generate_session_token, hash_password, hash_session_token, validate_new_user,
+    validate_password, verify_password,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{CurrentUser, ForwardedRequest, SESSION_COOKIE, session_token},
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+/// The token is also set as a cookie; it is returned for clients that are not browsers, such as
+/// the MCP server, to send as a bearer token
+#[derive(Debug, Serialize, TS)]
+pub struct LoginResponse {
+    pub user: User,
+    pub token: String,
+}
+
+/// Scoped to the path the app is served under, and only sent over HTTPS when the client used it
+fn session_cookie(forwarded: &ForwardedRequest, value: &str, max_age_secs: i64) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    let secure = if forwarded.secure { "; Secure" } else { "" };
+    if let Ok(cookie) = HeaderValue::from_str(&format!(
+        "{SESSION_COOKIE}={value}; Path={}/; HttpOnly; SameSite=Strict; Max-Age={max_age_secs}{secure}",
+        forwarded.base_path
+    )) {
+        headers.insert(header::SET_COOKIE, cookie);
+    }
+    headers
+}
+
+pub async fn login(
+    State(deployment): State<DeploymentImpl>,
+    Extension(forwarded): Extension<ForwardedRequest>,
+    Json(payload): Json<LoginRequest>,
+) -> Result<(HeaderMap, ResponseJson<ApiResponse<LoginResponse>>), ApiError> {
+    let pool = &deployment.db().pool;
+    let Some((user, password_hash)) = User::find_credentials(pool, &payload.username).await? else {
+        return Ok((
+            HeaderMap::new(),
+            ResponseJson(ApiResponse::error("Invalid username or password")),
+        ));
+    };
+    if !verify_password(&payload.password, &password_hash) {
+        return Ok((
+            HeaderMap::new(),
+            ResponseJson(ApiResponse::error("Invalid username or password")),
+        ));
+    }
+
+    let now = Utc::now();
+    UserSession::delete_expired(pool, now).await?;
+    let token = generate_session_token();
+    UserSession::create(
+        pool,
+        user.id,
+        &hash_session_token(&token),
+        now + SESSION_TTL,
+    )
+    .await?;
+    Ok((
+        session_cookie(&forwarded, &token, SESSION_TTL.num_seconds()),
+        ResponseJson(ApiResponse::success(LoginResponse { user, token })),
+    ))
+}
+
+pub async fn logout(
+    State(deployment): State<DeploymentImpl>,
+    Extension(forwarded): Extension<ForwardedRequest>,
+    headers: HeaderMap,
+) -> Result<(HeaderMap, ResponseJson<ApiResponse<()>>), ApiError> {
+    if let Some(token) = session_token(&headers) {
+        UserSession::delete(&deployment.db().pool, &hash_session_token(&token)).await?;
+    }
+    Ok((
+        session_cookie(&forwarded, "", 0),
+        ResponseJson(ApiResponse::success(())),
+    ))
+}
+
+/// The signed-in user; `None` while the app is single-user
+pub async fn get_current_user(
+    Extension(current_user): Extension<CurrentUser>,
+) -> ResponseJson<ApiResponse<Option<User>>> {
+    ResponseJson(ApiResponse::success(current_user.0))
+}
+
+pub async fn get_users(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<User>>>, ApiError> {
+    let users = User::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(users)))
+}
+
+/// The first account is always an admin, so there is someone to manage the others
+pub async fn create_user(
+    State(deployment): State<DeploymentImpl>,
+    Json(mut payload): Json<CreateUser>,
+) -> Result<ResponseJson<ApiResponse<User>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if let Some(message) = validate_new_user(&payload.username, &payload.password) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    if User::find_credentials(pool, &payload.username)
+        .await?
+        .is_some()
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Username is already taken",
+        )));
+    }
+    if User::count(pool).await? == 0 {
+        payload.role = UserRole::Admin;
+    }
+    let user = User::create(pool, &payload, &hash_password(&payload.password)).await?;
+    deployment
+        .track_if_analytics_allowed(
+            "user_created",
+            serde_json::json!({ "role": user.role.to_string() }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(user)))
+}
+
+/// Changing a password or role signs the user out of their sessions
+pub async fn update_user(
+    State(deployment): State<DeploymentImpl>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<UpdateUser>,
+) -> Result<ResponseJson<ApiResponse<User>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(existing) = User::find_by_id(pool, user_id).await? else {
+        return Ok(ResponseJson(ApiResponse::error("User not found")));
+    };
+    if let Some(password) = &payload.password
+        && let Some(message) = validate_password(password)
+    {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    if existing.role == UserRole::Admin
+        && payload.role.is_some_and(|role| role != UserRole::Admin)
+        && User::count_admins(pool).await? <= 1
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The last admin cannot be given another role",
+        )));
+    }
+
+    let password_hash = payload.password.as_deref().map(hash_password);
+    let Some(user) = User::update(pool, user_id, &payload, password_hash.as_deref()).await? else {
+        return Ok(ResponseJson(ApiResponse::error("User not found")));
+    };
+    if password_hash.is_some() || user.role != existing.role {
+        UserSession::delete_for_user(pool, user_id).await?;
+    }
+    Ok(ResponseJson(ApiResponse::success(user)))
+}
+
+pub async fn delete_user(
+    State(deployment): State<DeploymentImpl>,
+    Path(user_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(existing) = User::find_by_id(pool, user_id).await? else {
+        return Ok(ResponseJson(ApiResponse::error("User not found")));
+    };
+    if existing.role == UserRole::Admin
+        && User::count_admins(pool).await? <= 1
+        && User::count(pool).await? > 1
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The last admin cannot be deleted while other users exist",
+        )));
+    }
+    User::delete(pool, user_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Login and logout, reachable without a session
+pub fn session_router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/auth/login", post(login))
+        .route("/auth/logout", post(logout))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let inner = Router::new()
+        .route("/", get(get_users).post(create_user))
+        .route("/me", get(get_current_user))
+        .route("/{user_id}", put(update_user).delete(delete_user));
+    Router::new().nest("/users", inner)
+}
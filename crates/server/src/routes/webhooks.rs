@@ -0,0 +1,101 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::{
+    project::Project,
+    webhook::{CreateWebhook, UpdateWebhook, Webhook, WebhookEvent},
+};
+use deployment::Deployment;
+use reqwest::Url;
+use services::services::webhooks::generate_secret;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Why the webhook settings cannot be saved, if they cannot
+fn validate(url: &str, events: &[WebhookEvent]) -> Option<String> {
+    match Url::parse(url.trim()) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+        _ => return Some("Webhook URL must be an http or https URL".to_string()),
+    }
+    events
+        .is_empty()
+        .then(|| "Select at least one event".to_string())
+}
+
+pub async fn get_webhooks(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Webhook>>>, ApiError> {
+    let webhooks = Webhook::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(webhooks)))
+}
+
+pub async fn create_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if let Some(message) = validate(&payload.url, &payload.events) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    if let Some(project_id) = payload.project_id
+        && Project::find_by_id(pool, project_id).await?.is_none()
+    {
+        return Ok(ResponseJson(ApiResponse::error("Project not found")));
+    }
+    let secret = payload
+        .secret
+        .as_deref()
+        .map(str::trim)
+        .filter(|secret| !secret.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(generate_secret);
+    let webhook = Webhook::create(pool, &payload, &secret).await?;
+    deployment
+        .track_if_analytics_allowed(
+            "webhook_created",
+            serde_json::json!({
+                "project_scoped": payload.project_id.is_some(),
+                "events": payload.events,
+            }),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn update_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(webhook_id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if Webhook::find_by_id(pool, webhook_id).await?.is_none() {
+        return Ok(ResponseJson(ApiResponse::error("Webhook not found")));
+    }
+    if let Some(message) = validate(&payload.url, &payload.events) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    let webhook = Webhook::update(pool, webhook_id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn delete_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if Webhook::delete(&deployment.db().pool, webhook_id).await? == 0 {
+        return Ok(ResponseJson(ApiResponse::error("Webhook not found")));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let inner = Router::new()
+        .route("/", get(get_webhooks).post(create_webhook))
+        .route("/{webhook_id}", put(update_webhook).delete(delete_webhook));
+    Router::new().nest("/webhooks", inner)
+}
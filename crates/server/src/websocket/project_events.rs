@@ -25,6 +25,7 @@ use crate::{
         ActivityFeedItem, ActivityFeedScope, FEED_PAGE_SIZE, decode_cursor, event_is_after_cursor,
         map_event_to_item,
     },
+    middleware::CurrentUser,
     routes::projects::activity_feed::{invalidate_activity_feed_cache, scope_all_enabled},
 };
 
@@ -37,6 +38,7 @@ pub struct ActivityFeedWsQuery {
 pub async fn project_activity_feed_ws(
     ws: WebSocketUpgrade,
     Extension(project): Extension<Project>,
+    Extension(current_user): Extension<CurrentUser>,
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<ActivityFeedWsQuery>,
 ) -> Result<Response, crate::error::ApiError> {
@@ -50,8 +52,15 @@ pub async fn project_activity_feed_ws(
     }
 
     Ok(ws.on_upgrade(move |socket| async move {
-        if let Err(err) =
-            handle_activity_feed_ws(socket, deployment, project.id, scope, query.cursor).await
+        if let Err(err) = handle_activity_feed_ws(
+            socket,
+            deployment,
+            current_user,
+            project.id,
+            scope,
+            query.cursor,
+        )
+        .await
         {
             tracing::warn!(
                 "activity feed websocket closed for project {}: {}",
@@ -65,6 +74,7 @@ pub async fn project_activity_feed_ws(
 async fn handle_activity_feed_ws(
     socket: WebSocket,
     deployment: DeploymentImpl,
+    current_user: CurrentUser,
     project_id: Uuid,
     scope: ActivityFeedScope,
     cursor: Option<String>,
@@ -81,16 +91,19 @@ async fn handle_activity_feed_ws(
     });
 
     let user_id = match scope {
-        ActivityFeedScope::Mine => Uuid::parse_str(deployment.user_id()).ok(),
+        ActivityFeedScope::Mine => current_user.id(),
         ActivityFeedScope::All => None,
     };
+    let reader = current_user.reader_id(deployment.user_id());
 
     let repository = {
         let config = deployment.config().read().await;
         ActivityEventRepository::from_config(deployment.db().pool.clone(), &config.activity_feed)
     };
 
-    let events = repository.list_recent(project_id, user_id).await?;
+    let events = repository
+        .list_recent_for_reader(project_id, user_id, &reader)
+        .await?;
     let mut state: HashMap<Uuid, ActivityFeedItem> = events
         .iter()
         .map(|event| {
@@ -129,7 +142,9 @@ async fn handle_activity_feed_ws(
     loop {
         ticker.tick().await;
 
-        let events = repository.list_recent(project_id, user_id).await?;
+        let events = repository
+            .list_recent_for_reader(project_id, user_id, &reader)
+            .await?;
         let mut latest: HashMap<Uuid, ActivityFeedItem> = HashMap::with_capacity(events.len());
         for event in events.iter() {
             let item = map_event_to_item(event);
@@ -8,7 +8,8 @@ use crate::notifications::priority::{self, UrgencyComputationContext, UrgencyLev
 
 use super::models::{
     ActivityDomainEvent, ActivityDomainEventKind, ActivityEntityType, ActivityEvent,
-    ActivityEventCta, ActivityUrgencyHint,
+    ActivityEventAction, ActivityEventCta, ActivityUrgencyHint, DevServerDomainDetails,
+    MergeDomainDetails, PullRequestDomainDetails,
 };
 
 #[derive(Debug, Clone)]
@@ -146,8 +147,10 @@ impl ActivityAggregator {
             body,
             actors,
             cta: self.derive_cta(entity_type, project_id, entity_id, &kind),
+            action: self.derive_action(entity_type, entity_id, &kind),
             urgency_score,
             created_at,
+            read: false,
         }
     }
 
@@ -164,14 +167,25 @@ impl ActivityAggregator {
                 href: format!("/projects/{}/tasks/{}", project_id, entity_id),
             }),
             (ActivityEntityType::Attempt, ActivityDomainEventKind::Attempt(details)) => {
-                Some(ActivityEventCta {
-                    label: "View attempt".to_string(),
-                    href: format!(
-                        "/projects/{}/tasks/{}/attempts/{}",
-                        project_id, details.task_id, entity_id
-                    ),
-                })
+                Some(Self::attempt_cta(project_id, details.task_id, entity_id))
             }
+            (
+                _,
+                ActivityDomainEventKind::Merge(MergeDomainDetails {
+                    task_id,
+                    attempt_id,
+                })
+                | ActivityDomainEventKind::PullRequest(PullRequestDomainDetails {
+                    task_id,
+                    attempt_id,
+                    ..
+                })
+                | ActivityDomainEventKind::DevServer(DevServerDomainDetails {
+                    task_id,
+                    attempt_id,
+                    ..
+                }),
+            ) => Some(Self::attempt_cta(project_id, *task_id, *attempt_id)),
             (ActivityEntityType::Deployment, ActivityDomainEventKind::Deployment(details)) => {
                 details.url.as_ref().map(|url| ActivityEventCta {
                     label: "Open deployment".to_string(),
@@ -189,12 +203,44 @@ impl ActivityAggregator {
         })
     }
 
+    fn attempt_cta(project_id: Uuid, task_id: Uuid, attempt_id: Uuid) -> ActivityEventCta {
+        ActivityEventCta {
+            label: "View attempt".to_string(),
+            href: format!(
+                "/projects/{}/tasks/{}/attempts/{}",
+                project_id, task_id, attempt_id
+            ),
+        }
+    }
+
+    fn derive_action(
+        &self,
+        entity_type: ActivityEntityType,
+        entity_id: Uuid,
+        kind: &ActivityDomainEventKind,
+    ) -> Option<ActivityEventAction> {
+        match (entity_type, kind) {
+            (ActivityEntityType::Attempt, ActivityDomainEventKind::Attempt(details))
+                if details.state.as_deref() == Some("cleanupscheduled") =>
+            {
+                Some(ActivityEventAction {
+                    label: "Keep workspace".to_string(),
+                    endpoint: format!("/api/task-attempts/{}/keep", entity_id),
+                })
+            }
+            _ => None,
+        }
+    }
+
     fn default_headline(&self, kind: &ActivityDomainEventKind) -> String {
         match kind {
             ActivityDomainEventKind::Task(_) => "Task updated".to_string(),
             ActivityDomainEventKind::Attempt(_) => "Task attempt activity".to_string(),
             ActivityDomainEventKind::Comment(_) => "New comment".to_string(),
             ActivityDomainEventKind::Deployment(_) => "Deployment event".to_string(),
+            ActivityDomainEventKind::Merge(_) => "Attempt merged".to_string(),
+            ActivityDomainEventKind::PullRequest(_) => "Pull request updated".to_string(),
+            ActivityDomainEventKind::DevServer(_) => "Dev server activity".to_string(),
         }
     }
 
@@ -213,6 +259,15 @@ impl ActivityAggregator {
                 .status
                 .as_ref()
                 .map(|status| format!("Deployment status: {}", status)),
+            ActivityDomainEventKind::Merge(_) => None,
+            ActivityDomainEventKind::PullRequest(details) => details
+                .status
+                .as_ref()
+                .map(|status| format!("Pull request status: {}", status)),
+            ActivityDomainEventKind::DevServer(details) => details
+                .status
+                .as_ref()
+                .map(|status| format!("Dev server status: {}", status)),
         }
     }
 
@@ -236,7 +291,10 @@ impl ActivityAggregator {
                 .map(|state| state.to_ascii_lowercase())
                 .as_deref()
             {
-                Some("executorfailed") | Some("setupfailed") => UrgencyLevel::Critical,
+                Some("executorfailed") | Some("setupfailed") | Some("verificationfailed") => {
+                    UrgencyLevel::Critical
+                }
+                Some("cleanupscheduled") => UrgencyLevel::Elevated,
                 Some("executorcomplete") => UrgencyLevel::Normal,
                 Some("executorrunning") => UrgencyLevel::Elevated,
                 _ => UrgencyLevel::Normal,
@@ -253,6 +311,18 @@ impl ActivityAggregator {
                 Some("succeeded") => UrgencyLevel::Normal,
                 _ => UrgencyLevel::Normal,
             },
+            ActivityDomainEventKind::Merge(_) => UrgencyLevel::Normal,
+            // An open pull request is waiting for review
+            ActivityDomainEventKind::PullRequest(details) => match details.status.as_deref() {
+                Some("open") => UrgencyLevel::High,
+                Some("closed") => UrgencyLevel::Low,
+                _ => UrgencyLevel::Normal,
+            },
+            ActivityDomainEventKind::DevServer(details) => match details.status.as_deref() {
+                Some("failed") => UrgencyLevel::Elevated,
+                Some("running") => UrgencyLevel::Normal,
+                _ => UrgencyLevel::Low,
+            },
         }
     }
 }
@@ -265,7 +335,7 @@ mod tests {
     use crate::activity_feed::models::{
         ActivityDomainEvent, ActivityDomainEventKind, ActivityEventActor, ActivityUrgencyHint,
         ActivityVisibility, AttemptDomainDetails, CommentDomainDetails, DeploymentDomainDetails,
-        TaskDomainDetails,
+        PullRequestDomainDetails, TaskDomainDetails,
     };
 
     fn build_event(
@@ -476,4 +546,87 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn cleanup_warnings_offer_keep_action() {
+        let now = Utc::now();
+        let aggregator = ActivityAggregator::new(ActivityAggregatorConfig::default());
+        let attempt_id = Uuid::new_v4();
+
+        let mut warning = build_event(
+            ActivityEntityType::Attempt,
+            ActivityDomainEventKind::Attempt(AttemptDomainDetails {
+                task_id: Uuid::new_v4(),
+                state: Some("cleanupscheduled".into()),
+                executor: None,
+            }),
+            now - Duration::minutes(1),
+            ActivityVisibility::Public,
+        );
+        warning.entity_id = attempt_id;
+        let mut update = warning.clone();
+        update.kind = ActivityDomainEventKind::Attempt(AttemptDomainDetails {
+            task_id: Uuid::new_v4(),
+            state: Some("executorcomplete".into()),
+            executor: None,
+        });
+        update.entity_id = Uuid::new_v4();
+
+        let events = aggregator.aggregate_with_now(None, vec![warning, update], now);
+        let keep = events
+            .iter()
+            .find(|event| event.entity_id == attempt_id)
+            .and_then(|event| event.action.as_ref())
+            .expect("cleanup warnings should offer a keep action");
+        assert_eq!(
+            keep.endpoint,
+            format!("/api/task-attempts/{}/keep", attempt_id)
+        );
+        assert!(
+            events
+                .iter()
+                .filter(|event| event.entity_id != attempt_id)
+                .all(|event| event.action.is_none())
+        );
+    }
+
+    #[test]
+    fn open_pull_requests_link_to_their_attempt() {
+        let now = Utc::now();
+        let aggregator = ActivityAggregator::new(ActivityAggregatorConfig::default());
+        let task_id = Uuid::new_v4();
+        let attempt_id = Uuid::new_v4();
+
+        let opened = build_event(
+            ActivityEntityType::PullRequest,
+            ActivityDomainEventKind::PullRequest(PullRequestDomainDetails {
+                task_id,
+                attempt_id,
+                status: Some("open".into()),
+                url: Some("https://github.com/acme/app/pull/7".into()),
+            }),
+            now - Duration::minutes(2),
+            ActivityVisibility::Public,
+        );
+        let project_id = opened.project_id;
+
+        let events = aggregator.aggregate_with_now(None, vec![opened], now);
+        assert_eq!(events.len(), 1);
+        let cta = events[0]
+            .cta
+            .as_ref()
+            .expect("pull request events should include CTA");
+        assert_eq!(cta.label, "View attempt");
+        assert_eq!(
+            cta.href,
+            format!(
+                "/projects/{}/tasks/{}/attempts/{}",
+                project_id, task_id, attempt_id
+            )
+        );
+        assert!(
+            events[0].urgency_score >= 70,
+            "open pull requests await review"
+        );
+    }
 }
@@ -1,10 +1,15 @@
 pub mod aggregator;
 pub mod models;
+pub mod notifier;
+pub mod recorder;
 pub mod repository;
 
 pub use aggregator::{ActivityAggregator, ActivityAggregatorConfig};
 pub use models::{
     ActivityDomainEvent, ActivityDomainEventKind, ActivityEntityType, ActivityEvent,
-    ActivityEventActor, ActivityVisibility,
+    ActivityEventActor, ActivityVisibility, DevServerDomainDetails, MergeDomainDetails,
+    PullRequestDomainDetails,
 };
+pub use notifier::ActivityNotifier;
+pub use recorder::ActivityEventRecorder;
 pub use repository::{ActivityEventRepository, ActivityFeedDataSource, SqlActivityFeedDataSource};
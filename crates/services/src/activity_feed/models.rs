@@ -13,6 +13,9 @@ pub enum ActivityEntityType {
     Attempt,
     Comment,
     Deployment,
+    Merge,
+    PullRequest,
+    DevServer,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
@@ -27,6 +30,13 @@ pub struct ActivityEventCta {
     pub href: String,
 }
 
+/// Action the client can trigger in place by POSTing to `endpoint`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+pub struct ActivityEventAction {
+    pub label: String,
+    pub endpoint: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ActivityEvent {
     pub event_id: Uuid,
@@ -37,8 +47,11 @@ pub struct ActivityEvent {
     pub body: Option<String>,
     pub actors: Vec<ActivityEventActor>,
     pub cta: Option<ActivityEventCta>,
+    pub action: Option<ActivityEventAction>,
     pub urgency_score: u8,
     pub created_at: DateTime<Utc>,
+    /// Whether the reader acknowledged the event; only known once read state is applied
+    pub read: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -78,6 +91,9 @@ pub enum ActivityDomainEventKind {
     Attempt(AttemptDomainDetails),
     Comment(CommentDomainDetails),
     Deployment(DeploymentDomainDetails),
+    Merge(MergeDomainDetails),
+    PullRequest(PullRequestDomainDetails),
+    DevServer(DevServerDomainDetails),
 }
 
 #[derive(Debug, Clone)]
@@ -103,6 +119,30 @@ pub struct DeploymentDomainDetails {
     pub url: Option<String>,
 }
 
+/// An attempt's branch was merged directly into its target
+#[derive(Debug, Clone)]
+pub struct MergeDomainDetails {
+    pub task_id: Uuid,
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Clone)]
+pub struct PullRequestDomainDetails {
+    pub task_id: Uuid,
+    pub attempt_id: Uuid,
+    /// `open`, `merged` or `closed`
+    pub status: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DevServerDomainDetails {
+    pub task_id: Uuid,
+    pub attempt_id: Uuid,
+    /// State of the dev server process
+    pub status: Option<String>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ActivityUrgencyHint {
     Low,
@@ -0,0 +1,126 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{activity_event_notification::ActivityEventNotification, project::Project},
+};
+use tokio::{sync::RwLock, task::JoinHandle};
+
+use super::{ActivityEvent, ActivityEventRepository};
+use crate::services::{
+    config::{Config, NotificationConfig},
+    notification::{ChatEvent, ChatMessage, NotificationService},
+};
+
+/// How often project feeds are checked for urgent events
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Older events are left alone, so enabling notifications or a restart does not replay history
+const LOOKBACK: chrono::Duration = chrono::Duration::hours(1);
+
+/// Events at or above `threshold` urgency created since `since`
+pub fn urgent_events(
+    events: &[ActivityEvent],
+    threshold: u8,
+    since: DateTime<Utc>,
+) -> Vec<&ActivityEvent> {
+    events
+        .iter()
+        .filter(|event| event.urgency_score >= threshold && event.created_at >= since)
+        .collect()
+}
+
+/// Chat message for an urgent event. The event's link is relative to the app, so it is only
+/// included when the app URL is configured.
+pub fn urgent_chat_message(
+    config: &NotificationConfig,
+    project_name: &str,
+    event: &ActivityEvent,
+) -> ChatMessage {
+    let mut fields = vec![
+        ("Project", project_name.to_string()),
+        ("Urgency", format!("{}/100", event.urgency_score)),
+    ];
+    if let Some(body) = &event.body {
+        fields.push(("Details", body.clone()));
+    }
+    ChatMessage {
+        event: ChatEvent::UrgentActivity,
+        title: event.headline.clone(),
+        summary: "🚨 Needs attention".to_string(),
+        fields,
+        task_url: config
+            .app_url()
+            .zip(event.cta.as_ref())
+            .and_then(|(app_url, cta)| {
+                cta.href
+                    .starts_with('/')
+                    .then(|| format!("{app_url}{}", cta.href))
+            }),
+    }
+}
+
+/// Sends activity feed events at or above the configured urgency through
+/// [`NotificationService`], so critical failures reach people who are not looking at the feed.
+/// Each event is sent once.
+#[derive(Clone)]
+pub struct ActivityNotifier {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+}
+
+impl ActivityNotifier {
+    pub fn new(db: DBService, config: Arc<RwLock<Config>>) -> Self {
+        Self { db, config }
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.notify_urgent().await {
+                    tracing::warn!("Failed to send urgent activity notifications: {}", e);
+                }
+            }
+        })
+    }
+
+    async fn notify_urgent(&self) -> anyhow::Result<()> {
+        let (feed_config, notifications) = {
+            let config = self.config.read().await;
+            (config.activity_feed.clone(), config.notifications.clone())
+        };
+        let Some(threshold) = feed_config.notify_threshold else {
+            return Ok(());
+        };
+        if !feed_config.enabled {
+            return Ok(());
+        }
+
+        let repository = ActivityEventRepository::from_config(self.db.pool.clone(), &feed_config);
+        let since = Utc::now() - LOOKBACK;
+        for project in Project::find_all(&self.db.pool).await? {
+            // Restricted events are left out: there is no one user to notify about them
+            let events = repository.list_recent(project.id, None).await?;
+            for event in urgent_events(&events, threshold, since) {
+                if !ActivityEventNotification::claim(&self.db.pool, event.event_id, project.id)
+                    .await?
+                {
+                    continue;
+                }
+                NotificationService::notify_chat(
+                    &notifications,
+                    urgent_chat_message(&notifications, &project.name, event),
+                );
+                NotificationService::notify(
+                    notifications.clone(),
+                    &format!("{}: {}", project.name, event.headline),
+                    event.body.as_deref().unwrap_or(&event.headline),
+                )
+                .await;
+            }
+        }
+        Ok(())
+    }
+}
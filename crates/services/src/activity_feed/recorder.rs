@@ -0,0 +1,135 @@
+use std::path::Path;
+
+use db::{
+    activity_feed_queries::ActivityActorRow,
+    models::{activity_event::ActivityEventRecord, execution_process::ExecutionProcess},
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::services::git::GitService;
+
+/// Actors for commit authors, in the order given. Authors are identified by email, so the same
+/// person gets the same id across repositories and events; authors with neither a name nor an
+/// email are left out.
+pub fn commit_author_actors(authors: &[(Option<String>, Option<String>)]) -> Vec<ActivityActorRow> {
+    let mut actors: Vec<ActivityActorRow> = Vec::new();
+    for (name, email) in authors {
+        let name = name.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        let email = email.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        let Some(display_name) = name.or(email) else {
+            continue;
+        };
+        let key = match email {
+            Some(email) => format!("mailto:{}", email.to_lowercase()),
+            None => format!("name:{display_name}"),
+        };
+        let actor = ActivityActorRow {
+            id: Uuid::new_v5(&Uuid::NAMESPACE_URL, key.as_bytes()),
+            display_name: display_name.to_string(),
+        };
+        if !actors.iter().any(|existing| existing.id == actor.id) {
+            actors.push(actor);
+        }
+    }
+    actors
+}
+
+/// Writes the `activity_events` the feed reads. It is driven by the database update hook that
+/// [`EventService`](crate::services::events::EventService) installs, so tasks and attempts are
+/// recorded whichever code path changes them. Merges, pull requests and dev servers are recorded
+/// by the code that performs them instead.
+pub struct ActivityEventRecorder;
+
+impl ActivityEventRecorder {
+    /// Whether changes to `table` produce activity
+    pub fn records(table: &str) -> bool {
+        matches!(
+            table,
+            "tasks" | "execution_processes" | "attempt_verifications" | "attempt_cleanup_warnings"
+        )
+    }
+
+    /// Record the row at `rowid` of `table` after it was inserted or updated. Returns whether an
+    /// event was written; rows that were already recorded in this state are skipped.
+    pub async fn record(pool: &SqlitePool, table: &str, rowid: i64) -> Result<bool, sqlx::Error> {
+        match table {
+            "tasks" => ActivityEventRecord::record_task(pool, rowid).await,
+            "execution_processes" => {
+                let recorded = ActivityEventRecord::record_execution_process(pool, rowid).await?;
+                // The head commit is saved after the process completes, in a write that records
+                // nothing new, so attribution is tried on every write until it has one
+                if let Some(process) = ExecutionProcess::find_by_rowid(pool, rowid).await?
+                    && process.after_head_commit.is_some()
+                {
+                    Self::attribute(pool, process.id).await;
+                }
+                Ok(recorded)
+            }
+            "attempt_verifications" => ActivityEventRecord::record_verification(pool, rowid).await,
+            "attempt_cleanup_warnings" => {
+                ActivityEventRecord::record_cleanup_warning(pool, rowid).await
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Record a direct merge. Failures are logged, they should not fail the merge itself.
+    pub async fn merge(pool: &SqlitePool, merge_id: Uuid) {
+        match ActivityEventRecord::record_merge(pool, merge_id).await {
+            Ok(true) => Self::attribute(pool, merge_id).await,
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to record merge {} activity: {}", merge_id, e),
+        }
+    }
+
+    /// Attribute the events of `source_id` to the authors of the commits behind it, so events
+    /// of agent runs and merges name who they were made as rather than no one. Sources without
+    /// commits, and repositories that cannot be read, leave the events unattributed.
+    async fn attribute(pool: &SqlitePool, source_id: Uuid) {
+        let range = match ActivityEventRecord::find_commit_range(pool, source_id).await {
+            Ok(Some(range)) => range,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to find commits of activity {}: {}", source_id, e);
+                return;
+            }
+        };
+        let authors = match GitService::new().get_commit_authors(
+            Path::new(&range.repo_path),
+            range.base.as_deref(),
+            &range.head,
+        ) {
+            Ok(authors) => authors,
+            Err(e) => {
+                tracing::debug!("Failed to read commit authors of {}: {}", source_id, e);
+                return;
+            }
+        };
+        let actors = commit_author_actors(&authors);
+        if actors.is_empty() {
+            return;
+        }
+        if let Err(e) = ActivityEventRecord::set_actors(pool, source_id, &actors).await {
+            tracing::warn!("Failed to attribute activity {}: {}", source_id, e);
+        }
+    }
+
+    /// Record a pull request in its current state
+    pub async fn pull_request(pool: &SqlitePool, merge_id: Uuid) {
+        if let Err(e) = ActivityEventRecord::record_pull_request(pool, merge_id).await {
+            tracing::warn!("Failed to record pull request {} activity: {}", merge_id, e);
+        }
+    }
+
+    /// Record a dev server starting or stopping
+    pub async fn dev_server(pool: &SqlitePool, execution_process_id: Uuid) {
+        if let Err(e) = ActivityEventRecord::record_dev_server(pool, execution_process_id).await {
+            tracing::warn!(
+                "Failed to record dev server {} activity: {}",
+                execution_process_id,
+                e
+            );
+        }
+    }
+}
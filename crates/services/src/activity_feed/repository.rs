@@ -1,6 +1,15 @@
+use std::collections::HashSet;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
+use db::{
+    activity_feed_queries::{ActivityActorRow, UrgencyHint, fetch_overdue_task_activity},
+    models::{
+        activity_event::{ActivityEventEntity, ActivityEventRecord},
+        activity_event_read::ActivityEventRead,
+    },
+};
 use sqlx::SqlitePool;
 use uuid::Uuid;
 
@@ -12,7 +21,8 @@ use crate::services::config::ActivityFeedConfig;
 
 use super::models::{
     ActivityDomainEventKind, ActivityEntityType, ActivityEventActor, ActivityUrgencyHint,
-    AttemptDomainDetails, CommentDomainDetails, DeploymentDomainDetails, TaskDomainDetails,
+    AttemptDomainDetails, CommentDomainDetails, DeploymentDomainDetails, DevServerDomainDetails,
+    MergeDomainDetails, PullRequestDomainDetails, TaskDomainDetails,
 };
 
 #[async_trait]
@@ -22,6 +32,15 @@ pub trait ActivityFeedDataSource: Send + Sync {
         project_id: Uuid,
         since: DateTime<Utc>,
     ) -> Result<Vec<ActivityDomainEvent>>;
+
+    /// Ids of the project's events `reader` has acknowledged
+    async fn fetch_read_event_ids(
+        &self,
+        _project_id: Uuid,
+        _reader: &str,
+    ) -> Result<HashSet<Uuid>> {
+        Ok(HashSet::new())
+    }
 }
 
 pub struct ActivityEventRepository<D: ActivityFeedDataSource> {
@@ -60,6 +79,40 @@ impl<D: ActivityFeedDataSource> ActivityEventRepository<D> {
 
         Ok(events)
     }
+
+    /// [`Self::list_recent`], with the events `reader` acknowledged marked read
+    pub async fn list_recent_for_reader(
+        &self,
+        project_id: Uuid,
+        user_id: Option<Uuid>,
+        reader: &str,
+    ) -> Result<Vec<ActivityEvent>> {
+        let mut events = self.list_recent(project_id, user_id).await?;
+        if events.is_empty() {
+            return Ok(events);
+        }
+        let read = self
+            .data_source
+            .fetch_read_event_ids(project_id, reader)
+            .await?;
+        for event in &mut events {
+            event.read = read.contains(&event.event_id);
+        }
+        Ok(events)
+    }
+
+    /// How many events of the project's feed `reader` has not acknowledged
+    pub async fn unread_count(
+        &self,
+        project_id: Uuid,
+        user_id: Option<Uuid>,
+        reader: &str,
+    ) -> Result<usize> {
+        let events = self
+            .list_recent_for_reader(project_id, user_id, reader)
+            .await?;
+        Ok(events.iter().filter(|event| !event.read).count())
+    }
 }
 
 pub struct SqlActivityFeedDataSource {
@@ -136,21 +189,183 @@ mod tests {
         assert!(events.is_empty());
         assert!(!called.load(Ordering::SeqCst));
     }
+
+    struct ReadStateDataSource {
+        events: Vec<ActivityDomainEvent>,
+        read: HashSet<Uuid>,
+    }
+
+    #[async_trait]
+    impl ActivityFeedDataSource for ReadStateDataSource {
+        async fn fetch_domain_events(
+            &self,
+            _project_id: Uuid,
+            _since: DateTime<Utc>,
+        ) -> Result<Vec<ActivityDomainEvent>> {
+            Ok(self.events.clone())
+        }
+
+        async fn fetch_read_event_ids(
+            &self,
+            _project_id: Uuid,
+            _reader: &str,
+        ) -> Result<HashSet<Uuid>> {
+            Ok(self.read.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn acknowledged_events_are_marked_read() {
+        let project_id = Uuid::new_v4();
+        let events: Vec<ActivityDomainEvent> = (0..3)
+            .map(|minutes| ActivityDomainEvent {
+                event_id: Uuid::new_v4(),
+                entity_type: ActivityEntityType::Task,
+                entity_id: Uuid::new_v4(),
+                project_id,
+                headline: None,
+                body: None,
+                actors: Vec::new(),
+                urgency_hint: None,
+                created_at: Utc::now() - Duration::minutes(minutes),
+                visibility: ActivityVisibility::Public,
+                kind: ActivityDomainEventKind::Task(TaskDomainDetails { status: None }),
+            })
+            .collect();
+        let acknowledged = events[1].event_id;
+        let repository = ActivityEventRepository::new(
+            ReadStateDataSource {
+                events,
+                read: HashSet::from([acknowledged]),
+            },
+            ActivityAggregator::new(ActivityAggregatorConfig::default()),
+            true,
+        );
+
+        let events = repository
+            .list_recent_for_reader(project_id, None, "reader")
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(
+            events
+                .iter()
+                .all(|event| event.read == (event.event_id == acknowledged))
+        );
+        assert_eq!(
+            repository
+                .unread_count(project_id, None, "reader")
+                .await
+                .unwrap(),
+            2
+        );
+    }
+}
+
+fn urgency_hint(hint: UrgencyHint) -> ActivityUrgencyHint {
+    match hint {
+        UrgencyHint::Low => ActivityUrgencyHint::Low,
+        UrgencyHint::Normal => ActivityUrgencyHint::Normal,
+        UrgencyHint::Elevated => ActivityUrgencyHint::Elevated,
+        UrgencyHint::High => ActivityUrgencyHint::High,
+        UrgencyHint::Critical => ActivityUrgencyHint::Critical,
+    }
+}
+
+fn actor(row: ActivityActorRow) -> ActivityEventActor {
+    ActivityEventActor {
+        id: row.id,
+        display_name: row.display_name,
+    }
+}
+
+fn domain_event(record: ActivityEventRecord) -> ActivityDomainEvent {
+    let (entity_type, kind) = match record.entity_type {
+        ActivityEventEntity::Task => (
+            ActivityEntityType::Task,
+            ActivityDomainEventKind::Task(TaskDomainDetails {
+                status: record.status,
+            }),
+        ),
+        ActivityEventEntity::Attempt => (
+            ActivityEntityType::Attempt,
+            ActivityDomainEventKind::Attempt(AttemptDomainDetails {
+                task_id: record.task_id.unwrap_or_default(),
+                state: record.status,
+                executor: record.executor,
+            }),
+        ),
+        ActivityEventEntity::Comment => (
+            ActivityEntityType::Comment,
+            ActivityDomainEventKind::Comment(CommentDomainDetails { author_id: None }),
+        ),
+        ActivityEventEntity::Deployment => (
+            ActivityEntityType::Deployment,
+            ActivityDomainEventKind::Deployment(DeploymentDomainDetails {
+                status: record.status,
+                url: None,
+            }),
+        ),
+        ActivityEventEntity::Merge => (
+            ActivityEntityType::Merge,
+            ActivityDomainEventKind::Merge(MergeDomainDetails {
+                task_id: record.task_id.unwrap_or_default(),
+                attempt_id: record.attempt_id.unwrap_or_default(),
+            }),
+        ),
+        ActivityEventEntity::PullRequest => (
+            ActivityEntityType::PullRequest,
+            ActivityDomainEventKind::PullRequest(PullRequestDomainDetails {
+                task_id: record.task_id.unwrap_or_default(),
+                attempt_id: record.attempt_id.unwrap_or_default(),
+                status: record.status,
+                url: record.url,
+            }),
+        ),
+        ActivityEventEntity::DevServer => (
+            ActivityEntityType::DevServer,
+            ActivityDomainEventKind::DevServer(DevServerDomainDetails {
+                task_id: record.task_id.unwrap_or_default(),
+                attempt_id: record.attempt_id.unwrap_or_default(),
+                status: record.status,
+            }),
+        ),
+    };
+
+    ActivityDomainEvent {
+        event_id: record.id,
+        entity_type,
+        entity_id: record.entity_id,
+        project_id: record.project_id,
+        headline: record.headline,
+        body: record.body,
+        actors: record
+            .actors
+            .map(|actors| actors.0.into_iter().map(actor).collect())
+            .unwrap_or_default(),
+        urgency_hint: record.urgency_hint.map(urgency_hint),
+        created_at: record.created_at,
+        visibility: ActivityVisibility::Public,
+        kind,
+    }
 }
 
 #[async_trait]
 impl ActivityFeedDataSource for SqlActivityFeedDataSource {
+    /// Recorded events, plus overdue tasks which have no write to be recorded from
     async fn fetch_domain_events(
         &self,
         project_id: Uuid,
         since: DateTime<Utc>,
     ) -> Result<Vec<ActivityDomainEvent>> {
-        use db::activity_feed_queries as queries;
-
-        let mut events = Vec::new();
+        let mut events: Vec<ActivityDomainEvent> =
+            ActivityEventRecord::find_since(&self.pool, project_id, since)
+                .await?
+                .into_iter()
+                .map(domain_event)
+                .collect();
 
-        let tasks = queries::fetch_task_activity(&self.pool, project_id, since).await?;
-        for task in tasks {
+        for task in fetch_overdue_task_activity(&self.pool, project_id, since).await? {
             let visibility = match task.restricted_to {
                 Some(users) if !users.is_empty() => ActivityVisibility::Restricted(users),
                 _ => ActivityVisibility::Public,
@@ -160,24 +375,11 @@ impl ActivityFeedDataSource for SqlActivityFeedDataSource {
                 event_id: task.event_id.unwrap_or(task.entity_id),
                 entity_type: ActivityEntityType::Task,
                 entity_id: task.entity_id,
-                project_id: project_id,
+                project_id,
                 headline: Some(task.headline.unwrap_or_else(|| task.title.clone())),
                 body: task.body,
-                actors: task
-                    .actors
-                    .into_iter()
-                    .map(|actor| ActivityEventActor {
-                        id: actor.id,
-                        display_name: actor.display_name,
-                    })
-                    .collect(),
-                urgency_hint: task.urgency_hint.map(|hint| match hint {
-                    queries::UrgencyHint::Low => ActivityUrgencyHint::Low,
-                    queries::UrgencyHint::Normal => ActivityUrgencyHint::Normal,
-                    queries::UrgencyHint::Elevated => ActivityUrgencyHint::Elevated,
-                    queries::UrgencyHint::High => ActivityUrgencyHint::High,
-                    queries::UrgencyHint::Critical => ActivityUrgencyHint::Critical,
-                }),
+                actors: task.actors.into_iter().map(actor).collect(),
+                urgency_hint: task.urgency_hint.map(urgency_hint),
                 created_at: task.created_at,
                 visibility,
                 kind: ActivityDomainEventKind::Task(TaskDomainDetails {
@@ -186,120 +388,10 @@ impl ActivityFeedDataSource for SqlActivityFeedDataSource {
             });
         }
 
-        let attempts = queries::fetch_attempt_activity(&self.pool, project_id, since).await?;
-        for attempt in attempts {
-            let visibility = match attempt.restricted_to {
-                Some(users) if !users.is_empty() => ActivityVisibility::Restricted(users),
-                _ => ActivityVisibility::Public,
-            };
-
-            events.push(ActivityDomainEvent {
-                event_id: attempt.event_id.unwrap_or(attempt.entity_id),
-                entity_type: ActivityEntityType::Attempt,
-                entity_id: attempt.entity_id,
-                project_id,
-                headline: attempt.headline,
-                body: attempt.body,
-                actors: attempt
-                    .actors
-                    .into_iter()
-                    .map(|actor| ActivityEventActor {
-                        id: actor.id,
-                        display_name: actor.display_name,
-                    })
-                    .collect(),
-                urgency_hint: attempt.urgency_hint.map(|hint| match hint {
-                    queries::UrgencyHint::Low => ActivityUrgencyHint::Low,
-                    queries::UrgencyHint::Normal => ActivityUrgencyHint::Normal,
-                    queries::UrgencyHint::Elevated => ActivityUrgencyHint::Elevated,
-                    queries::UrgencyHint::High => ActivityUrgencyHint::High,
-                    queries::UrgencyHint::Critical => ActivityUrgencyHint::Critical,
-                }),
-                created_at: attempt.created_at,
-                visibility,
-                kind: ActivityDomainEventKind::Attempt(AttemptDomainDetails {
-                    task_id: attempt.task_id,
-                    state: attempt.state,
-                    executor: attempt.executor,
-                }),
-            });
-        }
-
-        let comments = queries::fetch_comment_activity(&self.pool, project_id, since).await?;
-        for comment in comments {
-            let visibility = match comment.restricted_to {
-                Some(users) if !users.is_empty() => ActivityVisibility::Restricted(users),
-                _ => ActivityVisibility::Public,
-            };
-
-            events.push(ActivityDomainEvent {
-                event_id: comment.event_id.unwrap_or(comment.entity_id),
-                entity_type: ActivityEntityType::Comment,
-                entity_id: comment.entity_id,
-                project_id,
-                headline: comment.headline,
-                body: comment.body,
-                actors: comment
-                    .actors
-                    .into_iter()
-                    .map(|actor| ActivityEventActor {
-                        id: actor.id,
-                        display_name: actor.display_name,
-                    })
-                    .collect(),
-                urgency_hint: comment.urgency_hint.map(|hint| match hint {
-                    queries::UrgencyHint::Low => ActivityUrgencyHint::Low,
-                    queries::UrgencyHint::Normal => ActivityUrgencyHint::Normal,
-                    queries::UrgencyHint::Elevated => ActivityUrgencyHint::Elevated,
-                    queries::UrgencyHint::High => ActivityUrgencyHint::High,
-                    queries::UrgencyHint::Critical => ActivityUrgencyHint::Critical,
-                }),
-                created_at: comment.created_at,
-                visibility,
-                kind: ActivityDomainEventKind::Comment(CommentDomainDetails {
-                    author_id: comment.author_id,
-                }),
-            });
-        }
-
-        let deployments = queries::fetch_deployment_activity(&self.pool, project_id, since).await?;
-        for deployment in deployments {
-            let visibility = match deployment.restricted_to {
-                Some(users) if !users.is_empty() => ActivityVisibility::Restricted(users),
-                _ => ActivityVisibility::Public,
-            };
-
-            events.push(ActivityDomainEvent {
-                event_id: deployment.event_id.unwrap_or(deployment.entity_id),
-                entity_type: ActivityEntityType::Deployment,
-                entity_id: deployment.entity_id,
-                project_id,
-                headline: deployment.headline,
-                body: deployment.body,
-                actors: deployment
-                    .actors
-                    .into_iter()
-                    .map(|actor| ActivityEventActor {
-                        id: actor.id,
-                        display_name: actor.display_name,
-                    })
-                    .collect(),
-                urgency_hint: deployment.urgency_hint.map(|hint| match hint {
-                    queries::UrgencyHint::Low => ActivityUrgencyHint::Low,
-                    queries::UrgencyHint::Normal => ActivityUrgencyHint::Normal,
-                    queries::UrgencyHint::Elevated => ActivityUrgencyHint::Elevated,
-                    queries::UrgencyHint::High => ActivityUrgencyHint::High,
-                    queries::UrgencyHint::Critical => ActivityUrgencyHint::Critical,
-                }),
-                created_at: deployment.created_at,
-                visibility,
-                kind: ActivityDomainEventKind::Deployment(DeploymentDomainDetails {
-                    status: deployment.status,
-                    url: deployment.url,
-                }),
-            });
-        }
-
         Ok(events)
     }
+
+    async fn fetch_read_event_ids(&self, project_id: Uuid, reader: &str) -> Result<HashSet<Uuid>> {
+        Ok(ActivityEventRead::find_read_ids(&self.pool, reader, project_id).await?)
+    }
 }
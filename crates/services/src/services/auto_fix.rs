@@ -0,0 +1,55 @@
+use db::models::execution_process::ExecutionProcessRunReason;
+
+/// Only the end of the script output is sent; that is where test runners report failures
+pub const MAX_AUTO_FIX_OUTPUT_CHARS: usize = 8000;
+
+fn tail(text: &str, max_chars: usize) -> (&str, bool) {
+    let text = text.trim();
+    let total = text.chars().count();
+    if total <= max_chars {
+        return (text, false);
+    }
+    let (idx, _) = text
+        .char_indices()
+        .nth(total - max_chars)
+        .expect("index is within the text");
+    (&text[idx..], true)
+}
+
+/// Build the follow-up asking the coding agent to fix what a failed cleanup or verify script run
+/// reported. `attempt` counts from 1 up to `max_attempts`.
+pub fn build_auto_fix_prompt(
+    run_reason: &ExecutionProcessRunReason,
+    exit_code: Option<i64>,
+    output: &str,
+    attempt: u32,
+    max_attempts: u32,
+) -> String {
+    let script = match run_reason {
+        ExecutionProcessRunReason::VerifyScript => "verify script",
+        ExecutionProcessRunReason::CleanupScript => "cleanup script",
+        ExecutionProcessRunReason::SetupScript => "setup script",
+        ExecutionProcessRunReason::CodingAgent => "coding agent",
+        ExecutionProcessRunReason::DevServer => "dev server",
+    };
+    let outcome = match exit_code {
+        Some(code) => format!("exited with code {code}"),
+        None => "did not complete".to_string(),
+    };
+    let mut out = format!(
+        "The project's {script} {outcome} after your changes. Fix the problems it reports, then \
+         stop; the script runs again automatically (automatic fix {attempt} of {max_attempts}).\n"
+    );
+
+    let (output, truncated) = tail(output, MAX_AUTO_FIX_OUTPUT_CHARS);
+    if output.is_empty() {
+        out.push_str("\nThe script produced no output.\n");
+    } else {
+        out.push_str("\n## Script output\n\n");
+        if truncated {
+            out.push_str("(earlier output omitted)\n");
+        }
+        out.push_str(&format!("```\n{output}\n```\n"));
+    }
+    out
+}
@@ -0,0 +1,202 @@
+use std::{collections::HashMap, path::Path};
+
+use db::models::{
+    project::Project,
+    task::TaskStatus,
+    task_attempt::{BranchCleanupCandidate, TaskAttempt},
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::git::GitService;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleBranchReason {
+    /// All commits are in the target branch, or a merge was recorded for the attempt
+    Merged,
+    TaskDone,
+    TaskCancelled,
+}
+
+/// Attempt branch that can be deleted without losing work in progress
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct StaleBranch {
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub branch: String,
+    pub target_branch: String,
+    pub reasons: Vec<StaleBranchReason>,
+    /// The attempt's worktree still exists and is removed along with the branch
+    pub has_worktree: bool,
+    /// Remote holding a copy of the branch, if any
+    pub remote: Option<String>,
+}
+
+/// Outcome of deleting one stale branch
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct StaleBranchDeletion {
+    pub task_attempt_id: Uuid,
+    pub branch: String,
+    pub deleted_local: bool,
+    pub deleted_remote: bool,
+    pub error: Option<String>,
+}
+
+fn reasons_for(
+    git: &GitService,
+    repo_path: &Path,
+    candidate: &BranchCleanupCandidate,
+) -> Vec<StaleBranchReason> {
+    let mut reasons = Vec::new();
+    // Branches of unstarted or active tasks often have no commits yet, so ancestry alone would
+    // report them as merged
+    let git_merged = !matches!(
+        candidate.task_status,
+        TaskStatus::Todo | TaskStatus::InProgress
+    ) && git
+        .is_branch_merged(repo_path, &candidate.branch, &candidate.target_branch)
+        .unwrap_or(false);
+    if candidate.has_recorded_merge || git_merged {
+        reasons.push(StaleBranchReason::Merged);
+    }
+    match candidate.task_status {
+        TaskStatus::Done => reasons.push(StaleBranchReason::TaskDone),
+        TaskStatus::Cancelled => reasons.push(StaleBranchReason::TaskCancelled),
+        _ => {}
+    }
+    reasons
+}
+
+/// Pick the attempt branches of one repository that are safe to delete. Branches that no longer
+/// exist locally are skipped, as are branches shared with an attempt that is running or not
+/// stale itself.
+pub fn find_stale_branches(
+    git: &GitService,
+    repo_path: &Path,
+    candidates: &[BranchCleanupCandidate],
+) -> Vec<StaleBranch> {
+    let mut by_branch: HashMap<&str, Vec<&BranchCleanupCandidate>> = HashMap::new();
+    for candidate in candidates {
+        by_branch
+            .entry(candidate.branch.as_str())
+            .or_default()
+            .push(candidate);
+    }
+
+    let mut stale = Vec::new();
+    for candidate in candidates {
+        let attempts = &by_branch[candidate.branch.as_str()];
+        // Report each branch once, for its newest attempt
+        if attempts[0].task_attempt_id != candidate.task_attempt_id {
+            continue;
+        }
+        if attempts.iter().any(|a| a.has_running_process) {
+            continue;
+        }
+        if !git
+            .branch_exists(repo_path, &candidate.branch)
+            .unwrap_or(false)
+        {
+            continue;
+        }
+
+        let reasons = reasons_for(git, repo_path, candidate);
+        if reasons.is_empty()
+            || attempts[1..]
+                .iter()
+                .any(|other| reasons_for(git, repo_path, other).is_empty())
+        {
+            continue;
+        }
+
+        stale.push(StaleBranch {
+            task_attempt_id: candidate.task_attempt_id,
+            task_id: candidate.task_id,
+            task_title: candidate.task_title.clone(),
+            branch: candidate.branch.clone(),
+            target_branch: candidate.target_branch.clone(),
+            reasons,
+            has_worktree: !candidate.worktree_deleted
+                && candidate
+                    .container_ref
+                    .as_deref()
+                    .is_some_and(|path| Path::new(path).exists()),
+            remote: git
+                .find_remote_for_branch(repo_path, &candidate.branch)
+                .ok()
+                .flatten(),
+        });
+    }
+    stale
+}
+
+/// Delete the remote copy of a merged attempt branch when the project opted in. Failures are only
+/// logged since the merge itself already succeeded.
+pub async fn delete_remote_branch_after_merge(
+    pool: &SqlitePool,
+    git: &GitService,
+    task_attempt: &TaskAttempt,
+) {
+    let project = match task_attempt.parent_task(pool).await {
+        Ok(Some(task)) => Project::find_by_id(pool, task.project_id).await,
+        other => other.map(|_| None),
+    };
+    let project = match project {
+        Ok(Some(project)) => project,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to load project of attempt {} for branch cleanup: {}",
+                task_attempt.id,
+                e
+            );
+            return;
+        }
+    };
+    match Project::find_pr_settings(pool, project.id).await {
+        Ok(settings) if settings.delete_remote_branch_after_merge => {}
+        Ok(_) => return,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to load PR settings of project {}: {}",
+                project.id,
+                e
+            );
+            return;
+        }
+    }
+    // Never delete the branch the work was merged into
+    if task_attempt.branch == task_attempt.target_branch {
+        return;
+    }
+
+    let remote = match git.find_remote_for_branch(&project.git_repo_path, &task_attempt.branch) {
+        Ok(Some(remote)) => remote,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to find remote of branch {}: {}",
+                task_attempt.branch,
+                e
+            );
+            return;
+        }
+    };
+    match git.delete_remote_branch(&project.git_repo_path, &remote, &task_attempt.branch) {
+        Ok(()) => tracing::info!(
+            "Deleted merged branch {} from remote {}",
+            task_attempt.branch,
+            remote
+        ),
+        Err(e) => tracing::warn!(
+            "Failed to delete merged branch {} from remote {}: {}",
+            task_attempt.branch,
+            remote,
+            e
+        ),
+    }
+}
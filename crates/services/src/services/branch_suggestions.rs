@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::git::GitBranch;
+
+/// Upper bound on suggestions returned per repository.
+const MAX_SUGGESTIONS_PER_REPO: usize = 5;
+/// Branches with commits newer than this count as recently active.
+const RECENT_ACTIVITY_WINDOW_DAYS: i64 = 14;
+
+const HOTFIX_KEYWORDS: &[&str] = &["hotfix", "hot fix", "hot-fix", "backport", "patch release"];
+const RELEASE_PREFIXES: &[&str] = &["release/", "releases/", "release-", "rel/", "hotfix/"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum BranchSuggestionReason {
+    /// Branch of the attempt this task was spawned from
+    ParentAttempt,
+    /// Target branch used by an earlier attempt of the same task
+    PreviousAttempt,
+    /// Most recently updated release branch, suggested for hotfix-style tasks
+    LatestRelease,
+    /// The remote's default branch (e.g. origin/HEAD)
+    DefaultBranch,
+    /// Branch currently checked out in the main repository
+    CurrentBranch,
+    /// Branch with recent commits
+    RecentActivity,
+}
+
+impl BranchSuggestionReason {
+    fn weight(self) -> u32 {
+        match self {
+            BranchSuggestionReason::ParentAttempt => 120,
+            BranchSuggestionReason::PreviousAttempt => 100,
+            BranchSuggestionReason::LatestRelease => 80,
+            BranchSuggestionReason::DefaultBranch => 60,
+            BranchSuggestionReason::CurrentBranch => 40,
+            BranchSuggestionReason::RecentActivity => 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BranchSuggestion {
+    pub branch: String,
+    pub is_remote: bool,
+    pub reasons: Vec<BranchSuggestionReason>,
+    pub score: u32,
+    #[ts(type = "Date")]
+    pub last_commit_date: DateTime<Utc>,
+    #[ts(type = "string | null")]
+    pub repository_id: Option<Uuid>,
+    pub repository_name: Option<String>,
+}
+
+/// Signals gathered for one repository of the task's project.
+#[derive(Debug, Default)]
+pub struct BranchSuggestionInput<'a> {
+    pub branches: Vec<GitBranch>,
+    pub default_branch: Option<String>,
+    /// Target branches of earlier attempts of the task, newest first
+    pub previous_targets: Vec<String>,
+    pub parent_attempt_branch: Option<String>,
+    pub task_title: &'a str,
+    pub task_description: Option<&'a str>,
+}
+
+/// True when the task text reads like a fix for an already released version.
+pub fn is_hotfix_task(title: &str, description: Option<&str>) -> bool {
+    let text = format!("{} {}", title, description.unwrap_or_default()).to_lowercase();
+    HOTFIX_KEYWORDS.iter().any(|keyword| text.contains(keyword))
+}
+
+fn strip_remote(name: &str, is_remote: bool) -> &str {
+    if is_remote {
+        name.split_once('/').map(|(_, rest)| rest).unwrap_or(name)
+    } else {
+        name
+    }
+}
+
+fn is_release_branch(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    RELEASE_PREFIXES
+        .iter()
+        .any(|prefix| lower.starts_with(prefix))
+        || (lower.starts_with('v') && lower[1..].starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Rank the branches of a repository as base-branch candidates for a new attempt.
+///
+/// Remote branches are only considered when no local branch of the same name exists, and the
+/// result is ordered by score (highest first), then by most recent commit.
+pub fn rank_branches(
+    input: BranchSuggestionInput<'_>,
+    now: DateTime<Utc>,
+) -> Vec<BranchSuggestion> {
+    let local_names: Vec<String> = input
+        .branches
+        .iter()
+        .filter(|b| !b.is_remote)
+        .map(|b| b.name.clone())
+        .collect();
+
+    let candidates: Vec<GitBranch> = input
+        .branches
+        .into_iter()
+        .filter(|b| !b.is_remote || !local_names.iter().any(|l| l == strip_remote(&b.name, true)))
+        .collect();
+
+    let mut reasons: HashMap<usize, Vec<BranchSuggestionReason>> = HashMap::new();
+    let find = |name: &str| {
+        candidates
+            .iter()
+            .position(|b| b.name == name || strip_remote(&b.name, b.is_remote) == name)
+    };
+
+    if let Some(parent) = input.parent_attempt_branch.as_deref()
+        && let Some(idx) = find(parent)
+    {
+        reasons
+            .entry(idx)
+            .or_default()
+            .push(BranchSuggestionReason::ParentAttempt);
+    }
+
+    for target in &input.previous_targets {
+        if let Some(idx) = find(target) {
+            let entry = reasons.entry(idx).or_default();
+            if !entry.contains(&BranchSuggestionReason::PreviousAttempt) {
+                entry.push(BranchSuggestionReason::PreviousAttempt);
+            }
+        }
+    }
+
+    if is_hotfix_task(input.task_title, input.task_description)
+        && let Some((idx, _)) = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| is_release_branch(strip_remote(&b.name, b.is_remote)))
+            .max_by_key(|(_, b)| b.last_commit_date)
+    {
+        reasons
+            .entry(idx)
+            .or_default()
+            .push(BranchSuggestionReason::LatestRelease);
+    }
+
+    if let Some(default_branch) = input.default_branch.as_deref()
+        && let Some(idx) = find(default_branch)
+    {
+        reasons
+            .entry(idx)
+            .or_default()
+            .push(BranchSuggestionReason::DefaultBranch);
+    }
+
+    if let Some(idx) = candidates.iter().position(|b| b.is_current) {
+        reasons
+            .entry(idx)
+            .or_default()
+            .push(BranchSuggestionReason::CurrentBranch);
+    }
+
+    let recent_cutoff = now - Duration::days(RECENT_ACTIVITY_WINDOW_DAYS);
+    for (idx, branch) in candidates.iter().enumerate() {
+        if branch.last_commit_date >= recent_cutoff {
+            reasons
+                .entry(idx)
+                .or_default()
+                .push(BranchSuggestionReason::RecentActivity);
+        }
+    }
+
+    let mut suggestions: Vec<BranchSuggestion> = reasons
+        .into_iter()
+        .map(|(idx, reasons)| {
+            let branch = &candidates[idx];
+            BranchSuggestion {
+                branch: branch.name.clone(),
+                is_remote: branch.is_remote,
+                score: reasons.iter().map(|r| r.weight()).sum(),
+                reasons,
+                last_commit_date: branch.last_commit_date,
+                repository_id: branch.repository_id,
+                repository_name: branch.repository_name.clone(),
+            }
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.last_commit_date.cmp(&a.last_commit_date))
+    });
+    suggestions.truncate(MAX_SUGGESTIONS_PER_REPO);
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branch(name: &str, is_remote: bool, days_ago: i64, now: DateTime<Utc>) -> GitBranch {
+        GitBranch {
+            name: name.to_string(),
+            is_current: false,
+            is_remote,
+            last_commit_date: now - Duration::days(days_ago),
+            repository_id: None,
+            repository_name: None,
+        }
+    }
+
+    #[test]
+    fn previous_attempt_target_outranks_default_branch() {
+        let now = Utc::now();
+        let suggestions = rank_branches(
+            BranchSuggestionInput {
+                branches: vec![
+                    branch("main", false, 1, now),
+                    branch("develop", false, 30, now),
+                    branch("origin/main", true, 1, now),
+                ],
+                default_branch: Some("main".to_string()),
+                previous_targets: vec!["develop".to_string()],
+                task_title: "Add search",
+                ..Default::default()
+            },
+            now,
+        );
+
+        let names: Vec<&str> = suggestions.iter().map(|s| s.branch.as_str()).collect();
+        assert_eq!(names, vec!["develop", "main"]);
+        assert_eq!(
+            suggestions[1].reasons,
+            vec![
+                BranchSuggestionReason::DefaultBranch,
+                BranchSuggestionReason::RecentActivity
+            ]
+        );
+    }
+
+    #[test]
+    fn hotfix_task_suggests_latest_release_branch() {
+        let now = Utc::now();
+        let suggestions = rank_branches(
+            BranchSuggestionInput {
+                branches: vec![
+                    branch("main", false, 0, now),
+                    branch("release/1.2", false, 40, now),
+                    branch("origin/release/1.3", true, 20, now),
+                ],
+                default_branch: Some("main".to_string()),
+                task_title: "Hotfix crash on startup",
+                ..Default::default()
+            },
+            now,
+        );
+
+        assert_eq!(suggestions[0].branch, "origin/release/1.3");
+        assert_eq!(
+            suggestions[0].reasons,
+            vec![BranchSuggestionReason::LatestRelease]
+        );
+    }
+}
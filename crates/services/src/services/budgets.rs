@@ -0,0 +1,205 @@
+//! Monthly usage budgets per coding agent: how much of each is used, alerts as they run out, and
+//! the check that keeps an agent with a used-up hard-stop budget from starting.
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use db::models::{
+    budget_alert::{BudgetAlert, BudgetLevel},
+    execution_process_token_usage::{ExecutionProcessTokenUsage, ModelTokenUsage},
+};
+use executors::executors::BaseCodingAgent;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+
+use crate::services::{
+    config::{Config, UsageBudget},
+    cost::CostEstimate,
+    notification::NotificationService,
+};
+
+/// A budget and how much of it the current month has used
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BudgetStatus {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub budget: UsageBudget,
+    #[ts(type = "string")]
+    pub month_start: NaiveDate,
+    #[ts(type = "number")]
+    pub used_tokens: i64,
+    pub used_cost_usd: f64,
+    /// Some usage could not be priced, so the cost is a lower bound
+    pub cost_incomplete: bool,
+    /// `null` while the usage is below the warning threshold
+    pub level: Option<BudgetLevel>,
+}
+
+impl BudgetStatus {
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(limit) = self.budget.monthly_tokens {
+            parts.push(format!("{} of {} tokens", self.used_tokens, limit));
+        }
+        if let Some(limit) = self.budget.monthly_cost_usd {
+            parts.push(format!("${:.2} of ${:.2}", self.used_cost_usd, limit));
+        }
+        format!(
+            "{} has used {} this month",
+            self.budget.agent,
+            parts.join(" and ")
+        )
+    }
+}
+
+/// First day of the month containing `now`
+pub fn month_start(now: DateTime<Utc>) -> NaiveDate {
+    now.date_naive().with_day(1).unwrap_or(now.date_naive())
+}
+
+/// Where usage stands against the budget, taking whichever limit is closer to being reached.
+/// Budgets without limits never warn.
+pub fn budget_level(
+    budget: &UsageBudget,
+    used_tokens: i64,
+    used_cost_usd: f64,
+) -> Option<BudgetLevel> {
+    let fractions = [
+        budget
+            .monthly_tokens
+            .map(|limit| used_tokens as f64 / limit as f64),
+        budget.monthly_cost_usd.map(|limit| used_cost_usd / limit),
+    ];
+    let used = fractions.into_iter().flatten().reduce(f64::max)?;
+    if used >= 1.0 {
+        Some(BudgetLevel::Exceeded)
+    } else if used * 100.0 >= f64::from(budget.warn_percent) {
+        Some(BudgetLevel::Warning)
+    } else {
+        None
+    }
+}
+
+async fn budget_status(
+    pool: &SqlitePool,
+    config: &Config,
+    budget: &UsageBudget,
+    now: DateTime<Utc>,
+) -> Result<BudgetStatus, sqlx::Error> {
+    let month_start = month_start(now);
+    let since = Utc.from_utc_datetime(&month_start.and_hms_opt(0, 0, 0).unwrap_or_default());
+    let usage =
+        ExecutionProcessTokenUsage::sum_by_executor_since(pool, budget.agent, since).await?;
+    let used_tokens = usage.iter().map(ModelTokenUsage::total_tokens).sum();
+    let cost = CostEstimate::new(usage, &config.model_pricing);
+    Ok(BudgetStatus {
+        budget: budget.clone(),
+        month_start,
+        used_tokens,
+        used_cost_usd: cost.total_usd,
+        cost_incomplete: cost.incomplete,
+        level: budget_level(budget, used_tokens, cost.total_usd),
+    })
+}
+
+/// Every configured budget, as it stands this month
+pub async fn budget_statuses(
+    pool: &SqlitePool,
+    config: &Config,
+) -> Result<Vec<BudgetStatus>, sqlx::Error> {
+    let now = Utc::now();
+    let mut statuses = Vec::with_capacity(config.budgets.len());
+    for budget in &config.budgets {
+        statuses.push(budget_status(pool, config, budget, now).await?);
+    }
+    Ok(statuses)
+}
+
+/// The used-up hard-stop budget that keeps `agent` from starting, if there is one
+pub async fn blocking_budget(
+    pool: &SqlitePool,
+    config: &Config,
+    agent: BaseCodingAgent,
+) -> Result<Option<BudgetStatus>, sqlx::Error> {
+    let now = Utc::now();
+    for budget in &config.budgets {
+        if budget.agent != agent || !budget.hard_stop {
+            continue;
+        }
+        let status = budget_status(pool, config, budget, now).await?;
+        if status.level == Some(BudgetLevel::Exceeded) {
+            return Ok(Some(status));
+        }
+    }
+    Ok(None)
+}
+
+/// Message for a start refused because of `status`
+pub fn blocked_message(status: &BudgetStatus) -> String {
+    format!(
+        "The monthly budget for {} is used up: {}. Raise the budget or wait until next month.",
+        status.budget.agent,
+        status.describe()
+    )
+}
+
+/// Alert once per month and level when `agent`'s budgets near or pass their limits
+pub async fn notify_budget_alerts(
+    pool: &SqlitePool,
+    config: &Config,
+    agent: BaseCodingAgent,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    for budget in config.budgets.iter().filter(|budget| budget.agent == agent) {
+        let status = budget_status(pool, config, budget, now).await?;
+        let Some(level) = status.level else {
+            continue;
+        };
+        if !BudgetAlert::record(pool, agent, status.month_start, level).await? {
+            continue;
+        }
+        let (title, consequence) = match level {
+            BudgetLevel::Warning => ("Usage budget nearly used", ""),
+            BudgetLevel::Exceeded if budget.hard_stop => (
+                "Usage budget used up",
+                " New runs will not start until next month.",
+            ),
+            BudgetLevel::Exceeded => ("Usage budget used up", ""),
+        };
+        let message = format!("{}.{}", status.describe(), consequence);
+        NotificationService::notify(config.notifications.clone(), title, &message).await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(monthly_tokens: Option<u64>, monthly_cost_usd: Option<f64>) -> UsageBudget {
+        UsageBudget {
+            agent: BaseCodingAgent::ClaudeCode,
+            monthly_tokens,
+            monthly_cost_usd,
+            warn_percent: 80,
+            hard_stop: true,
+        }
+    }
+
+    #[test]
+    fn the_closest_limit_sets_the_level() {
+        let both = budget(Some(1_000), Some(10.0));
+        assert_eq!(budget_level(&both, 100, 1.0), None);
+        assert_eq!(budget_level(&both, 800, 1.0), Some(BudgetLevel::Warning));
+        assert_eq!(budget_level(&both, 100, 10.0), Some(BudgetLevel::Exceeded));
+        assert_eq!(budget_level(&budget(None, None), 1_000_000, 1_000.0), None);
+    }
+
+    #[test]
+    fn months_start_on_the_first() {
+        let now = Utc.with_ymd_and_hms(2025, 11, 17, 13, 45, 0).unwrap();
+        assert_eq!(
+            month_start(now),
+            NaiveDate::from_ymd_opt(2025, 11, 1).unwrap()
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! iCalendar (RFC 5545) feed of a project's due dates and upcoming scheduled runs, so they can be
+//! subscribed to from a calendar app. The feed is rebuilt on every request.
+//!
+//! Calendar apps cannot log in, so feed addresses carry an HMAC signature of the project id
+//! instead. The key lives in the asset directory and survives restarts, so subscriptions keep
+//! working.
+
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Duration, Utc};
+use db::models::{
+    project::Project, task::Task, task_schedule::TaskSchedule, task_template::TaskTemplate,
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use utils::assets::asset_dir;
+use uuid::Uuid;
+
+use crate::services::{
+    config::NotificationConfig, cron_schedule::CronSchedule, notification::task_link,
+};
+
+/// How far ahead scheduled runs are listed
+pub const SCHEDULE_HORIZON_DAYS: i64 = 60;
+/// Keeps frequent schedules (every few minutes) from flooding the calendar
+pub const MAX_RUNS_PER_SCHEDULE: usize = 100;
+
+/// Lines longer than this many octets are folded
+const MAX_LINE_OCTETS: usize = 75;
+
+const FEED_KEY_FILE: &str = "calendar-feed.key";
+
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    /// Same across refreshes, so calendar apps update the event rather than add it again
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub start: DateTime<Utc>,
+}
+
+/// Runs of `schedule` after `from` and up to `until`, at most `limit` of them
+pub fn upcoming_runs(
+    schedule: &CronSchedule,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+    limit: usize,
+) -> Vec<DateTime<Utc>> {
+    std::iter::successors(schedule.next_after(from), |run| schedule.next_after(*run))
+        .take_while(|run| *run <= until)
+        .take(limit)
+        .collect()
+}
+
+/// Escape a TEXT value
+pub fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+fn ics_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Split a content line into lines of at most 75 octets, continuation lines starting with a
+/// space. Multi-byte characters are never split.
+fn push_folded(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+pub fn render_calendar(
+    name: &str,
+    events: &[CalendarEvent],
+    generated_at: DateTime<Utc>,
+) -> String {
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//Vibe Kanban//Tasks//EN",
+        "CALSCALE:GREGORIAN",
+        "METHOD:PUBLISH",
+        "REFRESH-INTERVAL;VALUE=DURATION:PT15M",
+        "X-PUBLISHED-TTL:PT15M",
+    ] {
+        push_folded(&mut out, line);
+    }
+    push_folded(&mut out, &format!("X-WR-CALNAME:{}", ics_escape(name)));
+
+    let stamp = ics_time(generated_at);
+    for event in events {
+        push_folded(&mut out, "BEGIN:VEVENT");
+        push_folded(&mut out, &format!("UID:{}", event.uid));
+        push_folded(&mut out, &format!("DTSTAMP:{stamp}"));
+        push_folded(&mut out, &format!("DTSTART:{}", ics_time(event.start)));
+        push_folded(&mut out, &format!("SUMMARY:{}", ics_escape(&event.summary)));
+        if let Some(description) = &event.description {
+            push_folded(
+                &mut out,
+                &format!("DESCRIPTION:{}", ics_escape(description)),
+            );
+        }
+        if let Some(url) = &event.url {
+            push_folded(&mut out, &format!("URL:{url}"));
+        }
+        push_folded(&mut out, "END:VEVENT");
+    }
+    push_folded(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// Key feed signatures are made with, read from the asset directory or created there on first use
+fn feed_key() -> &'static [u8] {
+    static KEY: OnceLock<Vec<u8>> = OnceLock::new();
+    KEY.get_or_init(|| {
+        let path = asset_dir().join(FEED_KEY_FILE);
+        if let Ok(key) = std::fs::read(&path)
+            && !key.is_empty()
+        {
+            return key;
+        }
+        let key = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()).into_bytes();
+        if let Err(e) = std::fs::write(&path, &key) {
+            tracing::warn!(
+                "Failed to save the calendar feed key to {}, feed addresses will change on restart: {}",
+                path.display(),
+                e
+            );
+        }
+        key
+    })
+}
+
+/// Hex HMAC-SHA256 of the project id under `key`
+pub fn sign_feed(key: &[u8], project_id: Uuid) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(project_id.as_bytes());
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+/// Whether `signature` is [`sign_feed`] of the project under `key`, compared in constant time
+pub fn verify_feed_signature(key: &[u8], project_id: Uuid, signature: &str) -> bool {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(project_id.as_bytes());
+    hex_decode(signature).is_some_and(|bytes| mac.verify_slice(&bytes).is_ok())
+}
+
+fn hex_decode(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Signature that lets the project's feed be fetched without logging in
+pub fn feed_signature(project_id: Uuid) -> String {
+    sign_feed(feed_key(), project_id)
+}
+
+/// Whether a feed request for the project carries a valid signature
+pub fn feed_signature_matches(project_id: Uuid, signature: &str) -> bool {
+    verify_feed_signature(feed_key(), project_id, signature)
+}
+
+/// Due dates of the project's open tasks, and the runs of its enabled schedules over the next
+/// [`SCHEDULE_HORIZON_DAYS`] days
+pub async fn project_calendar(
+    pool: &SqlitePool,
+    project: &Project,
+    notifications: &NotificationConfig,
+    now: DateTime<Utc>,
+) -> Result<String, sqlx::Error> {
+    let mut events: Vec<CalendarEvent> = Task::find_open_with_due_date(pool, project.id)
+        .await?
+        .into_iter()
+        .map(|due| CalendarEvent {
+            uid: format!("task-{}@vibe-kanban", due.task.id),
+            summary: format!("Due: {}", due.task.title),
+            url: task_link(notifications, project.id, due.task.id, None),
+            description: due.task.description,
+            start: due.due_at,
+        })
+        .collect();
+
+    let until = now + Duration::days(SCHEDULE_HORIZON_DAYS);
+    for schedule in TaskSchedule::find_by_project_id(pool, project.id).await? {
+        if !schedule.enabled {
+            continue;
+        }
+        // Schedules were validated when saved; one that no longer parses has nothing to show
+        let Ok(cron) = CronSchedule::parse(&schedule.cron_expression) else {
+            continue;
+        };
+        let Some(template) = TaskTemplate::find_by_id(pool, schedule.template_id).await? else {
+            continue;
+        };
+        let description = format!(
+            "Creates a task from the template '{}' ({}){}",
+            template.template_name,
+            schedule.cron_expression,
+            if schedule.executor_profile_id.is_some() {
+                " and starts an attempt"
+            } else {
+                ""
+            }
+        );
+        events.extend(
+            upcoming_runs(&cron, now, until, MAX_RUNS_PER_SCHEDULE)
+                .into_iter()
+                .map(|run| CalendarEvent {
+                    uid: format!("schedule-{}-{}@vibe-kanban", schedule.id, run.timestamp()),
+                    summary: format!("Scheduled: {}", template.title),
+                    description: Some(description.clone()),
+                    url: None,
+                    start: run,
+                }),
+        );
+    }
+    events.sort_by_key(|event| event.start);
+
+    Ok(render_calendar(&project.name, &events, now))
+}
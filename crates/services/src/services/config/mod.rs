@@ -21,6 +21,21 @@ pub type ThemeMode = versions::v9::ThemeMode;
 pub type SoundFile = versions::v9::SoundFile;
 pub type EditorType = versions::v9::EditorType;
 pub type GitHubConfig = versions::v9::GitHubConfig;
+pub type GitLabConfig = versions::v9::GitLabConfig;
+pub type GiteaConfig = versions::v9::GiteaConfig;
+pub type JiraConfig = versions::v9::JiraConfig;
+pub type RateLimitConfig = versions::v9::RateLimitConfig;
+pub type ModelPrice = versions::v9::ModelPrice;
+pub type UsageBudget = versions::v9::UsageBudget;
+pub type DatabaseBackupConfig = versions::v9::DatabaseBackupConfig;
+pub type LogArchiveConfig = versions::v9::LogArchiveConfig;
+pub type LogSnapshotConfig = versions::v9::LogSnapshotConfig;
+pub type AuditLogConfig = versions::v9::AuditLogConfig;
+pub type DatabaseMaintenanceConfig = versions::v9::DatabaseMaintenanceConfig;
+pub type SlackConfig = versions::v9::SlackConfig;
+pub type DiscordConfig = versions::v9::DiscordConfig;
+pub type CommitSigningConfig = versions::v9::CommitSigningConfig;
+pub type CommitSigningFormat = versions::v9::CommitSigningFormat;
 pub type UiLanguage = versions::v9::UiLanguage;
 pub type ActivityFeedConfig = versions::v9::ActivityFeedConfig;
 pub type ClaudePlan = versions::v9::ClaudePlan;
@@ -131,14 +131,42 @@ pub struct ActivityFeedConfig {
     pub enabled: bool,
     #[serde(default = "ActivityFeedConfig::default_window")]
     pub window_days: u16,
+    /// Events scoring at least this much urgency (0-100) are also sent as notifications; `None`
+    /// keeps them in the feed only
+    #[serde(default = "ActivityFeedConfig::default_notify_threshold")]
+    pub notify_threshold: Option<u8>,
+    /// Recorded events older than this many days are deleted; `None` keeps them. Never less than
+    /// `window_days`, so pruning does not change what the feed shows.
+    #[serde(default = "ActivityFeedConfig::default_retention")]
+    pub retention_days: Option<u16>,
+    /// At most this many recorded events are kept, the oldest are deleted first; `None` for no
+    /// limit
+    #[serde(default)]
+    pub max_events: Option<u32>,
 }
 
 impl ActivityFeedConfig {
     const DEFAULT_WINDOW_DAYS: u16 = 21;
+    /// Critical events, such as failed attempts
+    const DEFAULT_NOTIFY_THRESHOLD: u8 = 90;
+    const DEFAULT_RETENTION_DAYS: u16 = 90;
 
     const fn default_window() -> u16 {
         Self::DEFAULT_WINDOW_DAYS
     }
+
+    const fn default_notify_threshold() -> Option<u8> {
+        Some(Self::DEFAULT_NOTIFY_THRESHOLD)
+    }
+
+    const fn default_retention() -> Option<u16> {
+        Some(Self::DEFAULT_RETENTION_DAYS)
+    }
+
+    /// Days recorded events are kept for, if they expire
+    pub fn effective_retention_days(&self) -> Option<u16> {
+        self.retention_days.map(|days| days.max(self.window_days))
+    }
 }
 
 impl Default for ActivityFeedConfig {
@@ -146,6 +174,9 @@ impl Default for ActivityFeedConfig {
         Self {
             enabled: true,
             window_days: Self::DEFAULT_WINDOW_DAYS,
+            notify_threshold: Self::default_notify_threshold(),
+            retention_days: Self::default_retention(),
+            max_events: None,
         }
     }
 }
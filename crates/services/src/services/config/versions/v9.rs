@@ -1,13 +1,14 @@
+use std::collections::HashMap;
+
 use anyhow::Error;
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 pub use v8::{
-    ActivityFeedConfig, EditorConfig, EditorType, GitHubConfig, NotificationConfig, SoundFile,
-    ThemeMode, UiLanguage,
+    ActivityFeedConfig, EditorConfig, EditorType, GitHubConfig, SoundFile, ThemeMode, UiLanguage,
 };
 
-use crate::services::config::versions::v8;
+use crate::services::{config::versions::v8, git_host::host_of};
 
 #[derive(Clone, Debug, Serialize, Deserialize, TS, Default)]
 #[ts(export)]
@@ -37,6 +38,478 @@ impl ClaudePlan {
     }
 }
 
+/// Credentials for GitLab merge requests. `base_url` points at a self-managed instance and
+/// defaults to gitlab.com.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct GitLabConfig {
+    pub pat: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl GitLabConfig {
+    pub const DEFAULT_BASE_URL: &'static str = "https://gitlab.com";
+
+    pub fn token(&self) -> Option<String> {
+        self.pat
+            .as_deref()
+            .map(str::trim)
+            .filter(|pat| !pat.is_empty())
+            .map(|pat| pat.to_string())
+    }
+
+    pub fn resolved_base_url(&self) -> String {
+        self.base_url
+            .as_deref()
+            .map(|url| url.trim().trim_end_matches('/'))
+            .filter(|url| !url.is_empty())
+            .unwrap_or(Self::DEFAULT_BASE_URL)
+            .to_string()
+    }
+}
+
+/// A self-hosted Gitea or Forgejo instance. PRs are only routed to it when both fields are set and
+/// the remote lives on the same host.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct GiteaConfig {
+    pub base_url: Option<String>,
+    pub token: Option<String>,
+}
+
+impl GiteaConfig {
+    pub fn token(&self) -> Option<String> {
+        self.token
+            .as_deref()
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(|token| token.to_string())
+    }
+
+    pub fn resolved_base_url(&self) -> Option<String> {
+        self.base_url
+            .as_deref()
+            .map(|url| url.trim().trim_end_matches('/'))
+            .filter(|url| !url.is_empty())
+            .map(|url| url.to_string())
+    }
+}
+
+/// Jira Cloud or Data Center site to pull issues from. Cloud authenticates with `email` and an API
+/// token; without an email the token is sent as a Data Center personal access token.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct JiraConfig {
+    pub base_url: Option<String>,
+    pub email: Option<String>,
+    pub api_token: Option<String>,
+}
+
+impl JiraConfig {
+    pub fn resolved_base_url(&self) -> Option<String> {
+        self.base_url
+            .as_deref()
+            .map(|url| url.trim().trim_end_matches('/'))
+            .filter(|url| !url.is_empty())
+            .map(|url| url.to_string())
+    }
+
+    pub fn email(&self) -> Option<String> {
+        self.email
+            .as_deref()
+            .map(str::trim)
+            .filter(|email| !email.is_empty())
+            .map(|email| email.to_string())
+    }
+
+    pub fn api_token(&self) -> Option<String> {
+        self.api_token
+            .as_deref()
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(|token| token.to_string())
+    }
+}
+
+/// Limits how often one client may change things, for instances reachable over a LAN or a
+/// tunnel. Clients are told apart by their signed-in session, or else their IP address.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct RateLimitConfig {
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    /// Requests other than reads allowed per client per minute, in bursts of up to this many
+    #[serde(default = "RateLimitConfig::default_requests_per_minute")]
+    pub requests_per_minute: u32,
+    /// Leave requests from this machine unlimited. Requests forwarded by a local proxy or tunnel
+    /// are limited by the client address it forwards.
+    #[serde(default = "enabled_by_default")]
+    pub exempt_local: bool,
+}
+
+impl RateLimitConfig {
+    const DEFAULT_REQUESTS_PER_MINUTE: u32 = 120;
+
+    const fn default_requests_per_minute() -> u32 {
+        Self::DEFAULT_REQUESTS_PER_MINUTE
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            requests_per_minute: Self::DEFAULT_REQUESTS_PER_MINUTE,
+            exempt_local: true,
+        }
+    }
+}
+
+/// Periodic copies of the database, taken while the app runs
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct DatabaseBackupConfig {
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    /// Where backups are written; a `backups` folder next to the database when unset
+    #[serde(default)]
+    pub directory: Option<String>,
+    #[serde(default = "DatabaseBackupConfig::default_interval_hours")]
+    pub interval_hours: u32,
+    /// How many backups to keep; older ones are deleted after each new backup
+    #[serde(default = "DatabaseBackupConfig::default_keep")]
+    pub keep: u32,
+}
+
+impl DatabaseBackupConfig {
+    const DEFAULT_INTERVAL_HOURS: u32 = 24;
+    const DEFAULT_KEEP: u32 = 7;
+
+    const fn default_interval_hours() -> u32 {
+        Self::DEFAULT_INTERVAL_HOURS
+    }
+
+    const fn default_keep() -> u32 {
+        Self::DEFAULT_KEEP
+    }
+}
+
+impl Default for DatabaseBackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            directory: None,
+            interval_hours: Self::DEFAULT_INTERVAL_HOURS,
+            keep: Self::DEFAULT_KEEP,
+        }
+    }
+}
+
+/// WAL checkpoint, `ANALYZE` and incremental vacuum, run in the background
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct DatabaseMaintenanceConfig {
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    #[serde(default = "DatabaseMaintenanceConfig::default_interval_hours")]
+    pub interval_hours: u32,
+}
+
+impl DatabaseMaintenanceConfig {
+    const DEFAULT_INTERVAL_HOURS: u32 = 24;
+
+    const fn default_interval_hours() -> u32 {
+        Self::DEFAULT_INTERVAL_HOURS
+    }
+}
+
+impl Default for DatabaseMaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: Self::DEFAULT_INTERVAL_HOURS,
+        }
+    }
+}
+
+/// Archives old execution processes, moving their logs out of the table the app reads on every
+/// view
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct LogArchiveConfig {
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    /// Processes that finished this many days ago or earlier are archived
+    #[serde(default = "LogArchiveConfig::default_after_days")]
+    pub after_days: u32,
+}
+
+impl LogArchiveConfig {
+    const DEFAULT_AFTER_DAYS: u32 = 30;
+
+    const fn default_after_days() -> u32 {
+        Self::DEFAULT_AFTER_DAYS
+    }
+}
+
+impl Default for LogArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            after_days: Self::DEFAULT_AFTER_DAYS,
+        }
+    }
+}
+
+/// Compressed copies of finished processes' logs, read instead of normalizing the stored output
+/// again when an old attempt is opened
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct LogSnapshotConfig {
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    /// Snapshots not opened for this many days are deleted
+    #[serde(default = "LogSnapshotConfig::default_retention_days")]
+    pub retention_days: u32,
+}
+
+impl LogSnapshotConfig {
+    const DEFAULT_RETENTION_DAYS: u32 = 14;
+
+    const fn default_retention_days() -> u32 {
+        Self::DEFAULT_RETENTION_DAYS
+    }
+}
+
+impl Default for LogSnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention_days: Self::DEFAULT_RETENTION_DAYS,
+        }
+    }
+}
+
+/// How long the record of who changed what is kept
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct AuditLogConfig {
+    /// Entries older than this many days are deleted; `None` keeps them
+    #[serde(default = "AuditLogConfig::default_retention_days")]
+    pub retention_days: Option<u32>,
+}
+
+impl AuditLogConfig {
+    const DEFAULT_RETENTION_DAYS: u32 = 90;
+
+    const fn default_retention_days() -> Option<u32> {
+        Some(Self::DEFAULT_RETENTION_DAYS)
+    }
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: Self::default_retention_days(),
+        }
+    }
+}
+
+/// What a model costs in US dollars per million tokens
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, TS)]
+pub struct ModelPrice {
+    pub input: f64,
+    /// Input read from the prompt cache
+    pub cached_input: f64,
+    /// Input written to the prompt cache
+    #[serde(default)]
+    pub cache_write: f64,
+    pub output: f64,
+}
+
+/// A monthly allowance for one coding agent, counted from the first of the calendar month (UTC)
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct UsageBudget {
+    pub agent: BaseCodingAgent,
+    /// Input, cached input and output tokens together
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub monthly_tokens: Option<u64>,
+    /// Estimated cost, priced as in the cost reports
+    #[serde(default)]
+    pub monthly_cost_usd: Option<f64>,
+    /// Share of the budget at which to warn
+    #[serde(default = "UsageBudget::default_warn_percent")]
+    pub warn_percent: u8,
+    /// Refuse to start the agent once the budget is used up
+    #[serde(default)]
+    pub hard_stop: bool,
+}
+
+impl UsageBudget {
+    const fn default_warn_percent() -> u8 {
+        80
+    }
+}
+
+const fn enabled_by_default() -> bool {
+    true
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+}
+
+/// Sound and desktop alerts, plus the chat channels events are posted to
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct NotificationConfig {
+    pub sound_enabled: bool,
+    pub push_enabled: bool,
+    pub sound_file: SoundFile,
+    /// Address this app is reached at, e.g. `http://localhost:3000`; chat messages link to the
+    /// task when set
+    #[serde(default)]
+    pub app_url: Option<String>,
+    #[serde(default)]
+    pub slack: SlackConfig,
+    #[serde(default)]
+    pub discord: DiscordConfig,
+}
+
+impl NotificationConfig {
+    pub fn app_url(&self) -> Option<String> {
+        non_empty(&self.app_url).map(|url| url.trim_end_matches('/').to_string())
+    }
+}
+
+impl From<v8::NotificationConfig> for NotificationConfig {
+    fn from(old: v8::NotificationConfig) -> Self {
+        Self {
+            sound_enabled: old.sound_enabled,
+            push_enabled: old.push_enabled,
+            sound_file: old.sound_file,
+            app_url: None,
+            slack: SlackConfig::default(),
+            discord: DiscordConfig::default(),
+        }
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        v8::NotificationConfig::default().into()
+    }
+}
+
+/// Slack channel that execution and PR events are posted to, through an incoming webhook or a bot
+/// token plus channel. The webhook wins when both are set.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct SlackConfig {
+    pub webhook_url: Option<String>,
+    pub bot_token: Option<String>,
+    /// Channel name or ID the bot posts to
+    pub channel: Option<String>,
+    #[serde(default = "enabled_by_default")]
+    pub execution_halted: bool,
+    #[serde(default = "enabled_by_default")]
+    pub attempt_failed: bool,
+    #[serde(default = "enabled_by_default")]
+    pub pr_merged: bool,
+    #[serde(default = "enabled_by_default")]
+    pub urgent_activity: bool,
+}
+
+impl SlackConfig {
+    pub fn webhook_url(&self) -> Option<String> {
+        non_empty(&self.webhook_url)
+    }
+
+    pub fn bot_token(&self) -> Option<String> {
+        non_empty(&self.bot_token)
+    }
+
+    pub fn channel(&self) -> Option<String> {
+        non_empty(&self.channel)
+    }
+}
+
+impl Default for SlackConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            bot_token: None,
+            channel: None,
+            execution_halted: true,
+            attempt_failed: true,
+            pr_merged: true,
+            urgent_activity: true,
+        }
+    }
+}
+
+/// Discord channel webhook that events are posted to as embeds
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct DiscordConfig {
+    pub webhook_url: Option<String>,
+    #[serde(default = "enabled_by_default")]
+    pub execution_halted: bool,
+    #[serde(default = "enabled_by_default")]
+    pub attempt_failed: bool,
+    #[serde(default = "enabled_by_default")]
+    pub pr_merged: bool,
+    #[serde(default = "enabled_by_default")]
+    pub urgent_activity: bool,
+}
+
+impl DiscordConfig {
+    pub fn webhook_url(&self) -> Option<String> {
+        non_empty(&self.webhook_url)
+    }
+}
+
+impl Default for DiscordConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            execution_halted: true,
+            attempt_failed: true,
+            pr_merged: true,
+            urgent_activity: true,
+        }
+    }
+}
+
+/// Value passed to git as `gpg.format`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitSigningFormat {
+    Openpgp,
+    Ssh,
+}
+
+impl CommitSigningFormat {
+    pub fn as_git_format(&self) -> &'static str {
+        match self {
+            CommitSigningFormat::Openpgp => "openpgp",
+            CommitSigningFormat::Ssh => "ssh",
+        }
+    }
+}
+
+/// Signing for commits made automatically after agent and cleanup runs. Unset `format` and `key`
+/// fall back to the user's git config (`gpg.format` and `user.signingkey`).
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct CommitSigningConfig {
+    pub enabled: bool,
+    pub format: Option<CommitSigningFormat>,
+    /// GPG key id or path to an SSH public key
+    pub key: Option<String>,
+}
+
+impl CommitSigningConfig {
+    pub fn key(&self) -> Option<&str> {
+        self.key
+            .as_deref()
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -59,9 +532,82 @@ pub struct Config {
     pub activity_feed: ActivityFeedConfig,
     #[serde(default)]
     pub claude_plan: ClaudePlan,
+    #[serde(default)]
+    pub gitlab: GitLabConfig,
+    #[serde(default)]
+    pub gitea: GiteaConfig,
+    #[serde(default)]
+    pub commit_signing: CommitSigningConfig,
+    #[serde(default)]
+    pub jira: JiraConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    /// Prices by model name, or by a prefix of it, used over the built-in ones when estimating
+    /// what attempts cost
+    #[serde(default)]
+    pub model_pricing: HashMap<String, ModelPrice>,
+    #[serde(default)]
+    pub budgets: Vec<UsageBudget>,
+    /// Share of an agent's rate limit window, 0-100, past which queued follow-ups wait for the
+    /// window to reset; `null` starts them regardless
+    #[serde(default = "Config::default_followup_usage_threshold")]
+    pub followup_usage_threshold: Option<f64>,
+    #[serde(default)]
+    pub database_backup: DatabaseBackupConfig,
+    #[serde(default)]
+    pub log_archive: LogArchiveConfig,
+    #[serde(default)]
+    pub log_snapshots: LogSnapshotConfig,
+    #[serde(default)]
+    pub database_maintenance: DatabaseMaintenanceConfig,
+    /// Seconds a stopped process gets between SIGTERM and SIGKILL to save its session state; 0
+    /// kills it right away
+    #[serde(default = "Config::default_stop_grace_period_secs")]
+    pub stop_grace_period_secs: u32,
+    #[serde(default)]
+    pub audit_log: AuditLogConfig,
 }
 
 impl Config {
+    const fn default_followup_usage_threshold() -> Option<f64> {
+        Some(90.0)
+    }
+
+    const fn default_stop_grace_period_secs() -> u32 {
+        5
+    }
+
+    /// Token for the forge hosting `url` (a PR or remote URL): the Gitea or GitLab token when the
+    /// URL is on the configured instance, the GitHub token otherwise.
+    pub fn forge_token_for_url(&self, url: &str) -> Option<String> {
+        let host = host_of(url);
+        if let Some(base_url) = self.gitea.resolved_base_url()
+            && host.is_some()
+            && host_of(&base_url) == host
+        {
+            return self.gitea.token();
+        }
+        if host.is_some() && host_of(&self.gitlab.resolved_base_url()) == host {
+            return self.gitlab.token();
+        }
+        self.github.token()
+    }
+
+    /// The config with forge, Jira and chat credentials cleared, for callers that may read
+    /// settings but not change them
+    pub fn without_secrets(&self) -> Self {
+        let mut config = self.clone();
+        config.github.pat = None;
+        config.github.oauth_token = None;
+        config.gitlab.pat = None;
+        config.gitea.token = None;
+        config.jira.api_token = None;
+        config.notifications.slack.webhook_url = None;
+        config.notifications.slack.bot_token = None;
+        config.notifications.discord.webhook_url = None;
+        config
+    }
+
     pub fn from_previous_version(raw_config: &str) -> Result<Self, Error> {
         let old_config = match serde_json::from_str::<v8::Config>(raw_config) {
             Ok(cfg) => cfg,
@@ -80,7 +626,7 @@ impl Config {
             onboarding_acknowledged: old_config.onboarding_acknowledged,
             github_login_acknowledged: old_config.github_login_acknowledged,
             telemetry_acknowledged: old_config.telemetry_acknowledged,
-            notifications: old_config.notifications,
+            notifications: old_config.notifications.into(),
             editor: old_config.editor,
             github: old_config.github,
             analytics_enabled: old_config.analytics_enabled,
@@ -90,6 +636,20 @@ impl Config {
             language: old_config.language,
             activity_feed: old_config.activity_feed,
             claude_plan: ClaudePlan::default(),
+            gitlab: GitLabConfig::default(),
+            gitea: GiteaConfig::default(),
+            commit_signing: CommitSigningConfig::default(),
+            jira: JiraConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            model_pricing: HashMap::new(),
+            budgets: Vec::new(),
+            followup_usage_threshold: Self::default_followup_usage_threshold(),
+            database_backup: DatabaseBackupConfig::default(),
+            log_archive: LogArchiveConfig::default(),
+            log_snapshots: LogSnapshotConfig::default(),
+            database_maintenance: DatabaseMaintenanceConfig::default(),
+            stop_grace_period_secs: Self::default_stop_grace_period_secs(),
+            audit_log: AuditLogConfig::default(),
         })
     }
 }
@@ -135,6 +695,20 @@ impl Default for Config {
             language: UiLanguage::default(),
             activity_feed: ActivityFeedConfig::default(),
             claude_plan: ClaudePlan::default(),
+            gitlab: GitLabConfig::default(),
+            gitea: GiteaConfig::default(),
+            commit_signing: CommitSigningConfig::default(),
+            jira: JiraConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            model_pricing: HashMap::new(),
+            budgets: Vec::new(),
+            followup_usage_threshold: Self::default_followup_usage_threshold(),
+            database_backup: DatabaseBackupConfig::default(),
+            log_archive: LogArchiveConfig::default(),
+            log_snapshots: LogSnapshotConfig::default(),
+            database_maintenance: DatabaseMaintenanceConfig::default(),
+            stop_grace_period_secs: Self::default_stop_grace_period_secs(),
+            audit_log: AuditLogConfig::default(),
         }
     }
 }
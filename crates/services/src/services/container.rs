@@ -2,6 +2,7 @@ use std::{
     collections::HashMap,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Instant,
 };
 
 use anyhow::{Error as AnyhowError, anyhow};
@@ -15,8 +16,10 @@ use db::{
         },
         execution_process_logs::ExecutionProcessLogs,
         executor_session::{CreateExecutorSession, ExecutorSession},
+        project::Project,
         task::{Task, TaskStatus},
         task_attempt::{TaskAttempt, TaskAttemptError},
+        warm_worktree::WarmWorktree,
     },
 };
 use executors::{
@@ -27,20 +30,34 @@ use executors::{
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::{ExecutorError, StandardCodingAgentExecutor},
+    logs::utils::patch::is_resource_usage_patch,
     profile::{ExecutorConfigs, ExecutorProfileId, to_default_variant},
 };
 use futures::{StreamExt, future};
 use sqlx::Error as SqlxError;
 use thiserror::Error;
 use tokio::{sync::RwLock, task::JoinHandle};
-use utils::{log_msg::LogMsg, msg_store::MsgStore, text::git_branch_name_with_prefix};
+use utils::{
+    diff::{Diff, DiffSide, HunkRange},
+    log_msg::LogMsg,
+    msg_store::MsgStore,
+    text::git_branch_name_with_prefix,
+};
 use uuid::Uuid;
 
-use crate::services::{
-    config::GitHubConfig,
-    git::{GitService, GitServiceError},
-    image::ImageService,
-    worktree_manager::{WorktreeError, WorktreeManager},
+use crate::{
+    activity_feed::ActivityEventRecorder,
+    services::{
+        budgets::{blocked_message, blocking_budget},
+        config::{Config, GitHubConfig},
+        git::{GitService, GitServiceError},
+        image::ImageService,
+        log_snapshots,
+        project_stats::{ProjectActivityKind, ProjectStatsService},
+        prompt_snippets,
+        webhooks::WebhookService,
+        worktree_manager::{WorktreeError, WorktreeManager},
+    },
 };
 pub type ContainerRef = String;
 
@@ -94,6 +111,8 @@ pub enum ContainerError {
     KillFailed(std::io::Error),
     #[error(transparent)]
     TaskAttemptError(#[from] TaskAttemptError),
+    #[error("{0}")]
+    BudgetExceeded(String),
     #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
@@ -106,10 +125,17 @@ pub trait ContainerService {
 
     fn git(&self) -> &GitService;
 
+    fn project_stats(&self) -> &ProjectStatsService;
+
+    fn config(&self) -> &Arc<RwLock<Config>>;
+
     fn task_attempt_to_current_dir(&self, task_attempt: &TaskAttempt) -> PathBuf;
 
     async fn create(&self, task_attempt: &TaskAttempt) -> Result<ContainerRef, ContainerError>;
 
+    /// Top up the project's pool of pre-created worktrees in the background
+    fn refill_worktree_pool(&self, project_id: Uuid);
+
     async fn delete(&self, task_attempt: &TaskAttempt) -> Result<(), ContainerError> {
         self.try_stop(task_attempt).await;
         self.delete_inner(task_attempt).await
@@ -158,6 +184,38 @@ pub trait ContainerService {
         })
     }
 
+    /// Actions chained after a coding agent request: the project's cleanup script, then its
+    /// verify script when one is configured.
+    async fn post_agent_action(
+        &self,
+        project: &Project,
+    ) -> Result<Option<Box<ExecutorAction>>, ContainerError> {
+        let verify_action = Project::find_verify_script(&self.db().pool, project.id)
+            .await?
+            .map(|script| {
+                Box::new(ExecutorAction::new(
+                    ExecutorActionType::ScriptRequest(ScriptRequest {
+                        script,
+                        language: ScriptRequestLanguage::Bash,
+                        context: ScriptContext::VerifyScript,
+                    }),
+                    None,
+                ))
+            });
+
+        Ok(match project.cleanup_script.clone() {
+            Some(script) => Some(Box::new(ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script,
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::CleanupScript,
+                }),
+                verify_action,
+            ))),
+            None => verify_action,
+        })
+    }
+
     async fn try_stop(&self, task_attempt: &TaskAttempt) {
         // stop all execution processes for this attempt
         if let Ok(processes) =
@@ -218,6 +276,48 @@ pub trait ContainerService {
         repository_filter: Option<Uuid>,
     ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>;
 
+    /// One-off snapshot of the attempt's diff with contents omitted (the `stats_only` shape),
+    /// for callers that only need change sizes.
+    async fn diff_stats(&self, task_attempt: &TaskAttempt) -> Result<Vec<Diff>, ContainerError>;
+
+    /// `diff_stats` for an attempt whose worktree is still on disk; `None` when it has been
+    /// removed. Never recreates the worktree, so it is safe to call for many attempts at once.
+    async fn existing_diff_stats(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<Option<Vec<Diff>>, ContainerError>;
+
+    /// Raw bytes of `path` on one side of the attempt's diff, read from the same source as
+    /// `stream_diff`; `None` when that side has no such file
+    async fn diff_file_content(
+        &self,
+        task_attempt: &TaskAttempt,
+        repository_filter: Option<Uuid>,
+        side: DiffSide,
+        path: &str,
+    ) -> Result<Option<Vec<u8>>, ContainerError>;
+
+    /// What one execution process changed: the diff between its `before_head_commit` and
+    /// `after_head_commit`, or against the live worktree while it has not recorded an end commit
+    /// yet. `None` when the process has no starting commit or nothing to compare it with.
+    async fn execution_process_diff(
+        &self,
+        execution_process: &ExecutionProcess,
+        stats_only: bool,
+    ) -> Result<Option<Vec<Diff>>, ContainerError>;
+
+    /// Revert `path`, or just `hunk` of it, in the attempt's worktree to how it reads at the diff
+    /// base. The change reaches diff streams through the worktree watcher. Returns false when the
+    /// attempt's changes are merged and there is no worktree to revert in.
+    async fn revert_diff_change(
+        &self,
+        task_attempt: &TaskAttempt,
+        repository_filter: Option<Uuid>,
+        path: &str,
+        old_path: Option<&str>,
+        hunk: Option<&HunkRange>,
+    ) -> Result<bool, ContainerError>;
+
     /// Fetch the MsgStore for a given execution ID, panicking if missing.
     async fn get_msg_store_by_id(&self, uuid: &Uuid) -> Option<Arc<MsgStore>> {
         let map = self.msg_stores().read().await;
@@ -228,6 +328,26 @@ pub trait ContainerService {
         git_branch_name_with_prefix(GitHubConfig::DEFAULT_BRANCH_PREFIX, attempt_id, task_title)
     }
 
+    /// Messages kept in the log snapshot of a finished process, if it has one
+    async fn load_log_snapshot(&self, id: &Uuid) -> Option<Vec<LogMsg>> {
+        let id = *id;
+        match tokio::task::spawn_blocking(move || {
+            log_snapshots::read_snapshot(&log_snapshots::snapshot_dir(), id)
+        })
+        .await
+        {
+            Ok(Ok(messages)) => messages,
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to read log snapshot for execution {}: {}", id, e);
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Failed to read log snapshot for execution {}: {}", id, e);
+                None
+            }
+        }
+    }
+
     async fn stream_raw_logs(
         &self,
         id: &Uuid,
@@ -238,17 +358,32 @@ pub trait ContainerService {
                 store
                     .history_plus_stream()
                     .filter(|msg| {
-                        future::ready(matches!(
-                            msg,
-                            Ok(LogMsg::Stdout(..) | LogMsg::Stderr(..) | LogMsg::Finished)
-                        ))
+                        future::ready(match msg {
+                            Ok(LogMsg::Stdout(..) | LogMsg::Stderr(..) | LogMsg::Finished) => true,
+                            // Live resource usage samples of a running process
+                            Ok(LogMsg::JsonPatch(patch)) => is_resource_usage_patch(patch),
+                            _ => false,
+                        })
                     })
                     .boxed(),
             );
         } else {
+            if let Some(messages) = self.load_log_snapshot(id).await {
+                return Some(
+                    futures::stream::iter(
+                        messages
+                            .into_iter()
+                            .filter(|m| matches!(m, LogMsg::Stdout(_) | LogMsg::Stderr(_)))
+                            .chain(std::iter::once(LogMsg::Finished))
+                            .map(Ok::<_, std::io::Error>),
+                    )
+                    .boxed(),
+                );
+            }
+
             // Fallback: load from DB and create direct stream
             let logs_record =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
+                match ExecutionProcessLogs::find_including_archived(&self.db().pool, *id).await {
                     Ok(Some(record)) => record,
                     Ok(None) => return None, // No logs exist
                     Err(e) => {
@@ -295,9 +430,23 @@ pub trait ContainerService {
                     .boxed(),
             )
         } else {
+            // Snapshots already hold the normalized entries
+            if let Some(messages) = self.load_log_snapshot(id).await {
+                return Some(
+                    futures::stream::iter(
+                        messages
+                            .into_iter()
+                            .filter(|m| matches!(m, LogMsg::JsonPatch(_)))
+                            .chain(std::iter::once(LogMsg::Finished))
+                            .map(Ok::<_, std::io::Error>),
+                    )
+                    .boxed(),
+                );
+            }
+
             // Fallback: load from DB and normalize
             let logs_record =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
+                match ExecutionProcessLogs::find_including_archived(&self.db().pool, *id).await {
                     Ok(Some(record)) => record,
                     Ok(None) => return None, // No logs exist
                     Err(e) => {
@@ -510,12 +659,20 @@ pub trait ContainerService {
                 .as_ref()
                 .ok_or_else(|| ContainerError::Other(anyhow!("Container ref not found")))?,
         );
-        let prompt = ImageService::canonicalise_image_paths(&task.to_prompt(), &worktree_path);
+        let prompt =
+            prompt_snippets::expand_for_project(&self.db().pool, project.id, &task.to_prompt())
+                .await?
+                .prompt;
+        let prompt = ImageService::canonicalise_image_paths(&prompt, &worktree_path);
 
-        let cleanup_action = self.cleanup_action(project.cleanup_script);
+        let cleanup_action = self.post_agent_action(&project).await?;
+
+        // A worktree claimed from the warm pool has already run the setup script
+        let setup_already_run = WarmWorktree::take_claim(&self.db().pool, task_attempt.id).await?;
+        let setup_script = project.setup_script.filter(|_| !setup_already_run);
 
         // Choose whether to execute the setup_script or coding agent first
-        let execution_process = if let Some(setup_script) = project.setup_script {
+        let execution_process = if let Some(setup_script) = setup_script {
             let executor_action = ExecutorAction::new(
                 ExecutorActionType::ScriptRequest(ScriptRequest {
                     script: setup_script,
@@ -554,6 +711,7 @@ pub trait ContainerService {
             )
             .await?
         };
+        WebhookService::attempt_started(&self.db().pool, &task, &task_attempt);
         Ok(execution_process)
     }
 
@@ -563,6 +721,23 @@ pub trait ContainerService {
         executor_action: &ExecutorAction,
         run_reason: &ExecutionProcessRunReason,
     ) -> Result<ExecutionProcess, ContainerError> {
+        let started = Instant::now();
+        // Refuse coding agents whose hard-stop budget is used up, before anything is recorded
+        let agent = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                Some(request.executor_profile_id.executor)
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                Some(request.executor_profile_id.executor)
+            }
+            ExecutorActionType::ScriptRequest(_) => None,
+        };
+        if let Some(agent) = agent {
+            let config = self.config().read().await.clone();
+            if let Some(status) = blocking_budget(&self.db().pool, &config, agent).await? {
+                return Err(ContainerError::BudgetExceeded(blocked_message(&status)));
+            }
+        }
         // Update task status to InProgress when starting an attempt
         let task = task_attempt
             .parent_task(&self.db().pool)
@@ -625,6 +800,9 @@ pub trait ContainerService {
         let _ = self
             .start_execution_inner(task_attempt, &execution_process, executor_action)
             .await?;
+        if run_reason == &ExecutionProcessRunReason::DevServer {
+            ActivityEventRecorder::dev_server(&self.db().pool, execution_process.id).await;
+        }
 
         // Start processing normalised logs for executor requests and follow ups
         match executor_action.typ() {
@@ -666,6 +844,11 @@ pub trait ContainerService {
         };
 
         self.spawn_stream_raw_logs_to_db(&execution_process.id);
+        self.project_stats().record(
+            task.project_id,
+            ProjectActivityKind::ExecutionStarted,
+            started.elapsed(),
+        );
         Ok(execution_process)
     }
 
@@ -685,10 +868,18 @@ pub trait ContainerService {
             return Ok(());
         };
 
-        // Determine the run reason of the next action
-        let next_run_reason = match ctx.execution_process.run_reason {
-            ExecutionProcessRunReason::SetupScript => ExecutionProcessRunReason::CodingAgent,
-            ExecutionProcessRunReason::CodingAgent => ExecutionProcessRunReason::CleanupScript,
+        // Determine the run reason of the next action; the verify script may follow either the
+        // coding agent or the cleanup script
+        let next_run_reason = match (next_action.typ(), &ctx.execution_process.run_reason) {
+            (
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    context: ScriptContext::VerifyScript,
+                    ..
+                }),
+                _,
+            ) => ExecutionProcessRunReason::VerifyScript,
+            (_, ExecutionProcessRunReason::SetupScript) => ExecutionProcessRunReason::CodingAgent,
+            (_, ExecutionProcessRunReason::CodingAgent) => ExecutionProcessRunReason::CleanupScript,
             _ => {
                 tracing::warn!(
                     "Unexpected run reason: {:?}, defaulting to current reason",
@@ -705,6 +896,35 @@ pub trait ContainerService {
         Ok(())
     }
 
+    /// Start the verify script queued after this process, skipping any actions before it. Used
+    /// when the cleanup script is skipped so the verify script still gates the task. Returns
+    /// whether a verify script was started.
+    async fn try_start_verify_action(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<bool, ContainerError> {
+        let mut next_action = ctx.execution_process.executor_action()?.next_action();
+        while let Some(action) = next_action {
+            if matches!(
+                action.typ(),
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    context: ScriptContext::VerifyScript,
+                    ..
+                })
+            ) {
+                self.start_execution(
+                    &ctx.task_attempt,
+                    action,
+                    &ExecutionProcessRunReason::VerifyScript,
+                )
+                .await?;
+                return Ok(true);
+            }
+            next_action = action.next_action();
+        }
+        Ok(false)
+    }
+
     async fn exit_plan_mode_tool(&self, ctx: ExecutionContext) -> Result<(), ContainerError> {
         let execution_id = ctx.execution_process.id;
 
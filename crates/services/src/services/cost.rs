@@ -0,0 +1,298 @@
+//! Dollar estimates of what coding agents cost, from the tokens they report and per-model prices.
+
+use std::collections::HashMap;
+
+use db::models::execution_process_token_usage::ModelTokenUsage;
+use serde::Serialize;
+use serde_json::Value;
+use ts_rs::TS;
+
+use crate::services::config::ModelPrice;
+
+const fn price(input: f64, cached_input: f64, cache_write: f64, output: f64) -> ModelPrice {
+    ModelPrice {
+        input,
+        cached_input,
+        cache_write,
+        output,
+    }
+}
+
+/// List prices by model name prefix; the longest matching prefix wins
+const BUILT_IN_PRICES: &[(&str, ModelPrice)] = &[
+    ("claude-opus-4-5", price(5.0, 0.5, 6.25, 25.0)),
+    ("claude-opus-4", price(15.0, 1.5, 18.75, 75.0)),
+    ("claude-sonnet-4", price(3.0, 0.3, 3.75, 15.0)),
+    ("claude-3-7-sonnet", price(3.0, 0.3, 3.75, 15.0)),
+    ("claude-3-5-sonnet", price(3.0, 0.3, 3.75, 15.0)),
+    ("claude-haiku-4-5", price(1.0, 0.1, 1.25, 5.0)),
+    ("claude-3-5-haiku", price(0.8, 0.08, 1.0, 4.0)),
+    ("gpt-5-nano", price(0.05, 0.005, 0.0, 0.4)),
+    ("gpt-5-mini", price(0.25, 0.025, 0.0, 2.0)),
+    ("gpt-5", price(1.25, 0.125, 0.0, 10.0)),
+    ("gpt-4.1", price(2.0, 0.5, 0.0, 8.0)),
+    ("o3-mini", price(1.1, 0.55, 0.0, 4.4)),
+    ("o3", price(2.0, 0.5, 0.0, 8.0)),
+    ("o4-mini", price(1.1, 0.275, 0.0, 4.4)),
+    ("codex-mini", price(1.5, 0.375, 0.0, 6.0)),
+];
+
+/// Price of `model`, from `overrides` (the `model_pricing` setting) before the built-in list.
+/// Both are matched by the longest prefix of the model name.
+pub fn price_for_model(model: &str, overrides: &HashMap<String, ModelPrice>) -> Option<ModelPrice> {
+    fn longest<'a>(
+        model: &str,
+        prices: impl Iterator<Item = (&'a str, &'a ModelPrice)>,
+    ) -> Option<ModelPrice> {
+        prices
+            .filter(|(prefix, _)| model.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, price)| *price)
+    }
+    longest(
+        model,
+        overrides
+            .iter()
+            .map(|(prefix, price)| (prefix.as_str(), price)),
+    )
+    .or_else(|| {
+        longest(
+            model,
+            BUILT_IN_PRICES
+                .iter()
+                .map(|(prefix, price)| (*prefix, price)),
+        )
+    })
+}
+
+pub fn estimate_usd(usage: &ModelTokenUsage, price: &ModelPrice) -> f64 {
+    (usage.input_tokens as f64 * price.input
+        + usage.cached_input_tokens as f64 * price.cached_input
+        + usage.cache_creation_input_tokens as f64 * price.cache_write
+        + usage.output_tokens as f64 * price.output)
+        / 1_000_000.0
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ModelCost {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub usage: ModelTokenUsage,
+    /// `null` when the model has no known price
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct CostEstimate {
+    pub models: Vec<ModelCost>,
+    /// Sum over the models with a known price
+    pub total_usd: f64,
+    /// Some usage could not be priced, so the total is a lower bound
+    pub incomplete: bool,
+}
+
+impl CostEstimate {
+    pub fn new(usage: Vec<ModelTokenUsage>, overrides: &HashMap<String, ModelPrice>) -> Self {
+        let models: Vec<ModelCost> = usage
+            .into_iter()
+            .map(|usage| {
+                let cost_usd = price_for_model(&usage.model, overrides)
+                    .map(|price| estimate_usd(&usage, &price));
+                ModelCost { usage, cost_usd }
+            })
+            .collect();
+        Self {
+            total_usd: models.iter().filter_map(|model| model.cost_usd).sum(),
+            incomplete: models.iter().any(|model| model.cost_usd.is_none()),
+            models,
+        }
+    }
+}
+
+fn token_count(value: &Value, key: &str) -> i64 {
+    value.get(key).and_then(Value::as_i64).unwrap_or(0)
+}
+
+/// Token usage per model reported in a coding agent's JSON output. Claude Code reports usage on
+/// each assistant message, repeated for every content block of the message; Codex reports a
+/// `token_count` event per turn for the model named in its opening config line.
+pub fn token_usage_from_output<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Vec<ModelTokenUsage> {
+    let mut claude_messages: HashMap<String, ModelTokenUsage> = HashMap::new();
+    let mut totals: HashMap<String, ModelTokenUsage> = HashMap::new();
+    let mut codex_model: Option<String> = None;
+
+    for line in lines {
+        let Ok(value) = serde_json::from_str::<Value>(line.trim()) else {
+            continue;
+        };
+
+        if value.get("type").and_then(Value::as_str) == Some("assistant")
+            && let Some(message) = value.get("message")
+            && let Some(usage) = message.get("usage")
+        {
+            let model = message
+                .get("model")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown")
+                .to_string();
+            let key = message
+                .get("id")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("line-{}", claude_messages.len()));
+            claude_messages.insert(
+                key,
+                ModelTokenUsage {
+                    model,
+                    input_tokens: token_count(usage, "input_tokens"),
+                    cached_input_tokens: token_count(usage, "cache_read_input_tokens"),
+                    cache_creation_input_tokens: token_count(usage, "cache_creation_input_tokens"),
+                    output_tokens: token_count(usage, "output_tokens"),
+                },
+            );
+            continue;
+        }
+
+        if let Some(model) = value.get("model").and_then(Value::as_str)
+            && value.get("msg").is_none()
+        {
+            codex_model = Some(model.to_string());
+            continue;
+        }
+
+        if let Some(msg) = value.get("msg")
+            && msg.get("type").and_then(Value::as_str) == Some("token_count")
+        {
+            // Newer Codex versions nest the counts and report running totals alongside
+            let counts = msg
+                .get("info")
+                .and_then(|info| info.get("last_token_usage"))
+                .unwrap_or(msg);
+            let cached = token_count(counts, "cached_input_tokens");
+            let model = codex_model.clone().unwrap_or_else(|| "unknown".to_string());
+            let turn = ModelTokenUsage {
+                model: model.clone(),
+                // OpenAI counts cached input as part of the input
+                input_tokens: (token_count(counts, "input_tokens") - cached).max(0),
+                cached_input_tokens: cached,
+                cache_creation_input_tokens: 0,
+                output_tokens: token_count(counts, "output_tokens"),
+            };
+            totals
+                .entry(model.clone())
+                .or_insert_with(|| ModelTokenUsage {
+                    model,
+                    ..Default::default()
+                })
+                .add(&turn);
+        }
+    }
+
+    for message in claude_messages.into_values() {
+        totals
+            .entry(message.model.clone())
+            .or_insert_with(|| ModelTokenUsage {
+                model: message.model.clone(),
+                ..Default::default()
+            })
+            .add(&message);
+    }
+
+    let mut usage: Vec<ModelTokenUsage> = totals
+        .into_values()
+        .filter(|usage| usage.total_tokens() > 0)
+        .collect();
+    usage.sort_by(|a, b| a.model.cmp(&b.model));
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claude_messages_are_counted_once() {
+        let output = r#"{"type":"system","subtype":"init","model":"claude-sonnet-4-5-20250929"}
+{"type":"assistant","message":{"id":"msg_1","model":"claude-sonnet-4-5-20250929","content":[{"type":"text","text":"Hi"}],"usage":{"input_tokens":10,"cache_creation_input_tokens":1000,"cache_read_input_tokens":2000,"output_tokens":50}}}
+{"type":"assistant","message":{"id":"msg_1","model":"claude-sonnet-4-5-20250929","content":[{"type":"tool_use","name":"Read"}],"usage":{"input_tokens":10,"cache_creation_input_tokens":1000,"cache_read_input_tokens":2000,"output_tokens":50}}}
+{"type":"assistant","message":{"id":"msg_2","model":"claude-sonnet-4-5-20250929","content":[],"usage":{"input_tokens":5,"cache_creation_input_tokens":0,"cache_read_input_tokens":3000,"output_tokens":20}}}"#;
+        let usage = token_usage_from_output(output.lines());
+        assert_eq!(
+            usage,
+            [ModelTokenUsage {
+                model: "claude-sonnet-4-5-20250929".to_string(),
+                input_tokens: 15,
+                cached_input_tokens: 5000,
+                cache_creation_input_tokens: 1000,
+                output_tokens: 70,
+            }]
+        );
+    }
+
+    #[test]
+    fn codex_turns_are_summed_for_the_configured_model() {
+        let output = r#"{"model":"gpt-5-codex","reasoning effort":"medium","provider":"openai"}
+{"id":"1","msg":{"type":"token_count","input_tokens":1674,"cached_input_tokens":1627,"output_tokens":384,"reasoning_output_tokens":384,"total_tokens":2058}}
+{"id":"2","msg":{"type":"token_count","input_tokens":100,"cached_input_tokens":0,"output_tokens":16,"reasoning_output_tokens":0,"total_tokens":116}}"#;
+        let usage = token_usage_from_output(output.lines());
+        assert_eq!(
+            usage,
+            [ModelTokenUsage {
+                model: "gpt-5-codex".to_string(),
+                input_tokens: 147,
+                cached_input_tokens: 1627,
+                cache_creation_input_tokens: 0,
+                output_tokens: 400,
+            }]
+        );
+    }
+
+    #[test]
+    fn prices_prefer_overrides_and_longer_prefixes() {
+        let mut overrides = HashMap::new();
+        assert_eq!(
+            price_for_model("claude-opus-4-5-20251101", &overrides).map(|p| p.input),
+            Some(5.0)
+        );
+        assert_eq!(
+            price_for_model("claude-opus-4-1-20250805", &overrides).map(|p| p.input),
+            Some(15.0)
+        );
+        assert_eq!(price_for_model("my-local-model", &overrides), None);
+
+        overrides.insert("my-local".to_string(), price(0.0, 0.0, 0.0, 0.0));
+        overrides.insert("claude-opus".to_string(), price(1.0, 0.1, 1.0, 2.0));
+        assert_eq!(
+            price_for_model("my-local-model", &overrides).map(|p| p.input),
+            Some(0.0)
+        );
+        assert_eq!(
+            price_for_model("claude-opus-4-5-20251101", &overrides).map(|p| p.input),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn estimates_total_the_priced_models() {
+        let usage = vec![
+            ModelTokenUsage {
+                model: "claude-sonnet-4-5".to_string(),
+                input_tokens: 1_000_000,
+                cached_input_tokens: 1_000_000,
+                cache_creation_input_tokens: 0,
+                output_tokens: 100_000,
+            },
+            ModelTokenUsage {
+                model: "mystery".to_string(),
+                input_tokens: 10,
+                ..Default::default()
+            },
+        ];
+        let estimate = CostEstimate::new(usage, &HashMap::new());
+        assert!((estimate.total_usd - 4.8).abs() < 1e-9);
+        assert!(estimate.incomplete);
+        assert_eq!(estimate.models[1].cost_usd, None);
+    }
+}
@@ -0,0 +1,138 @@
+//! Online copies of the SQLite database, written with `VACUUM INTO` so the app keeps running.
+//! To restore one, copy it next to the database as `db.sqlite.restore` and restart; it replaces
+//! the database before any connection is opened, and the replaced one is kept as
+//! `db.sqlite.pre-restore`.
+
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use chrono::{DateTime, Utc};
+use db::DBService;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::assets::asset_dir;
+
+use crate::services::config::DatabaseBackupConfig;
+
+const BACKUP_PREFIX: &str = "db-";
+const BACKUP_EXTENSION: &str = ".sqlite";
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DatabaseBackup {
+    pub path: String,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+pub fn backup_dir(config: &DatabaseBackupConfig) -> PathBuf {
+    config
+        .directory
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| asset_dir().join("backups"))
+}
+
+fn is_backup_file(name: &str) -> bool {
+    name.starts_with(BACKUP_PREFIX) && name.ends_with(BACKUP_EXTENSION)
+}
+
+/// Backups in `dir`, newest first. Names carry a sortable timestamp, so the order does not depend
+/// on file times, which copying can change.
+pub fn list_backups(dir: &Path) -> std::io::Result<Vec<DatabaseBackup>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut backups = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !is_backup_file(&name) {
+            continue;
+        }
+        let metadata = entry.metadata()?;
+        backups.push((
+            name,
+            DatabaseBackup {
+                path: entry.path().to_string_lossy().into_owned(),
+                size_bytes: metadata.len(),
+                created_at: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH).into(),
+            },
+        ));
+    }
+    backups.sort_by(|a, b| b.0.cmp(&a.0));
+    Ok(backups.into_iter().map(|(_, backup)| backup).collect())
+}
+
+/// Delete all but the newest `keep` backups in `dir`, returning how many were removed
+pub fn prune_backups(dir: &Path, keep: usize) -> std::io::Result<usize> {
+    let stale = list_backups(dir)?
+        .into_iter()
+        .skip(keep)
+        .collect::<Vec<_>>();
+    for backup in &stale {
+        std::fs::remove_file(&backup.path)?;
+    }
+    Ok(stale.len())
+}
+
+/// Run filesystem work on the blocking pool
+async fn blocking_io<T, F>(f: F) -> std::io::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> std::io::Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+/// Back the database up into the configured directory and apply the retention count
+pub async fn create_backup(
+    db: &DBService,
+    config: &DatabaseBackupConfig,
+) -> Result<DatabaseBackup, sqlx::Error> {
+    let dir = backup_dir(config);
+    blocking_io({
+        let dir = dir.clone();
+        move || std::fs::create_dir_all(dir)
+    })
+    .await?;
+
+    let now = Utc::now();
+    let path = dir.join(format!(
+        "{BACKUP_PREFIX}{}{BACKUP_EXTENSION}",
+        now.format("%Y%m%dT%H%M%S%.3fZ")
+    ));
+    db.backup_to(&path).await?;
+
+    let keep = config.keep.max(1) as usize;
+    let (size_bytes, pruned) = blocking_io({
+        let path = path.clone();
+        move || Ok((std::fs::metadata(path)?.len(), prune_backups(&dir, keep)?))
+    })
+    .await?;
+    if pruned > 0 {
+        tracing::info!("Removed {} old database backups", pruned);
+    }
+    Ok(DatabaseBackup {
+        path: path.to_string_lossy().into_owned(),
+        size_bytes,
+        created_at: now,
+    })
+}
+
+/// Whether the newest backup is at least `interval_hours` old, or there is none
+pub fn backup_due(config: &DatabaseBackupConfig, now: DateTime<Utc>) -> std::io::Result<bool> {
+    let newest = list_backups(&backup_dir(config))?.into_iter().next();
+    Ok(newest.is_none_or(|backup| {
+        now - backup.created_at >= chrono::Duration::hours(config.interval_hours.into())
+    }))
+}
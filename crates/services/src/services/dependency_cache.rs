@@ -0,0 +1,113 @@
+//! Sharing heavy, git-ignored dependency directories (`node_modules`, `target`, `.venv`) from the
+//! main checkout into new attempt worktrees, so setup scripts find them already populated instead
+//! of installing everything again.
+
+use std::{
+    io::{self, Write},
+    path::{Component, Path},
+};
+
+use db::models::project::DependencyShareMode;
+use git2::Repository;
+use tokio::process::Command;
+
+/// A shared directory must be a plain path inside the repository.
+pub fn is_valid_shared_dir(dir: &str) -> bool {
+    let path = Path::new(dir.trim());
+    !path.as_os_str().is_empty()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Bring `dir` from the main checkout into the worktree. Returns false when there is nothing to
+/// share: the directory is missing from the main checkout or already present in the worktree.
+pub async fn share_dependency_dir(
+    repo_path: &Path,
+    worktree_path: &Path,
+    dir: &str,
+    mode: DependencyShareMode,
+) -> io::Result<bool> {
+    if !is_valid_shared_dir(dir) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{dir} is not a path inside the repository"),
+        ));
+    }
+    let source = repo_path.join(dir.trim());
+    let target = worktree_path.join(dir.trim());
+    if !source.is_dir() || target.symlink_metadata().is_ok() {
+        return Ok(false);
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    match mode {
+        DependencyShareMode::Symlink => {
+            symlink_dir(&source, &target)?;
+            // Ignore rules like `node_modules/` only match directories, not links to them
+            exclude_from_git(repo_path, dir.trim())?;
+        }
+        DependencyShareMode::Clone => clone_dir(&source, &target).await?,
+    }
+    Ok(true)
+}
+
+#[cfg(unix)]
+fn symlink_dir(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(source, target)
+}
+
+#[cfg(windows)]
+fn symlink_dir(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_dir(source, target)
+}
+
+/// Copy the directory, sharing file extents where the filesystem can (reflinks on Btrfs/XFS,
+/// clonefile on APFS)
+async fn clone_dir(source: &Path, target: &Path) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("cp");
+        command.arg("-cR");
+        command
+    } else if cfg!(windows) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "cloning dependency directories is not supported on Windows; use symlinks",
+        ));
+    } else {
+        let mut command = Command::new("cp");
+        command.args(["-a", "--reflink=auto"]);
+        command
+    };
+    let output = command.arg(source).arg(target).output().await?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Add `/<dir>` to the repository's `info/exclude`, which all its worktrees share
+fn exclude_from_git(repo_path: &Path, dir: &str) -> io::Result<()> {
+    let repo = Repository::open(repo_path).map_err(io::Error::other)?;
+    let exclude_path = repo.commondir().join("info").join("exclude");
+    let pattern = format!("/{}", dir.replace('\\', "/"));
+    let existing = std::fs::read_to_string(&exclude_path).unwrap_or_default();
+    if existing.lines().any(|line| line.trim() == pattern) {
+        return Ok(());
+    }
+    if let Some(parent) = exclude_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&exclude_path)?;
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        writeln!(file)?;
+    }
+    writeln!(file, "{pattern}")
+}
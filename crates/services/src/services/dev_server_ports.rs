@@ -0,0 +1,41 @@
+//! Giving every dev server its own port, so attempts of different projects can run their dev
+//! servers side by side. The port is passed to the dev script through the environment.
+
+use std::{net::TcpListener, ops::RangeInclusive};
+
+use db::models::dev_server_port::DevServerPort;
+use sqlx::SqlitePool;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+pub const DEV_SERVER_PORT_ENV: &str = "VIBE_DEV_SERVER_PORT";
+pub const DEV_SERVER_URL_ENV: &str = "VIBE_DEV_SERVER_URL";
+
+/// Ports handed out to dev servers; kept clear of the common framework defaults
+pub const DEV_SERVER_PORT_RANGE: RangeInclusive<u16> = 4100..=4999;
+
+/// Serializes allocations so two dev servers starting together cannot pick the same port
+static ALLOCATION_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// First port in `range` that no running dev server holds and nothing else is listening on
+pub fn pick_free_port(range: RangeInclusive<u16>, taken: &[u16]) -> Option<u16> {
+    range
+        .filter(|port| !taken.contains(port))
+        .find(|port| TcpListener::bind(("127.0.0.1", *port)).is_ok())
+}
+
+/// Reserve a port for the dev server `execution_process_id`. None when the whole range is in use.
+pub async fn allocate_dev_server_port(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+) -> Result<Option<DevServerPort>, sqlx::Error> {
+    let _guard = ALLOCATION_LOCK.lock().await;
+    let taken = DevServerPort::ports_in_use(pool).await?;
+    let Some(port) = pick_free_port(DEV_SERVER_PORT_RANGE, &taken) else {
+        return Ok(None);
+    };
+    let url = format!("http://localhost:{port}");
+    DevServerPort::create(pool, execution_process_id, port, &url)
+        .await
+        .map(Some)
+}
@@ -1,5 +1,6 @@
 use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, Utc};
 use db::{
     DBService,
     models::{
@@ -24,6 +25,7 @@ use uuid::Uuid;
 use super::{
     container::{ContainerError, ContainerService},
     image::{ImageError, ImageService},
+    prompt_snippets,
 };
 
 #[derive(Debug, Error)]
@@ -49,6 +51,7 @@ pub struct DraftResponse {
     pub queued: bool,
     pub variant: Option<String>,
     pub image_ids: Option<Vec<Uuid>>,
+    pub run_at: Option<DateTime<Utc>>,
     pub version: i64,
 }
 
@@ -74,6 +77,9 @@ pub struct SetQueueRequest {
     pub queued: bool,
     pub expected_queued: Option<bool>,
     pub expected_version: Option<i64>,
+    /// Start the queued follow-up at this time instead of as soon as nothing is running
+    #[serde(default)]
+    pub run_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone)]
@@ -100,6 +106,7 @@ impl DraftsService {
             queued: d.queued,
             variant: d.variant,
             image_ids: d.image_ids,
+            run_at: d.run_at,
             version: d.version,
         }
     }
@@ -180,6 +187,7 @@ impl DraftsService {
                 queued: false,
                 variant: None,
                 image_ids: None,
+                run_at: None,
                 version: 0,
             }
         };
@@ -234,9 +242,12 @@ impl DraftsService {
             .ok_or(SqlxError::RowNotFound)
             .map_err(DraftsServiceError::from)?;
 
-        let cleanup_action = container.cleanup_action(project.cleanup_script);
+        let cleanup_action = container.post_agent_action(&project).await?;
 
-        let mut prompt = draft.prompt.clone();
+        let mut prompt =
+            prompt_snippets::expand_for_project(self.pool(), project.id, &draft.prompt)
+                .await?
+                .prompt;
         if let Some(image_ids) = &draft.image_ids {
             prompt = self
                 .handle_images_for_prompt(task_attempt.task_id, image_ids, &prompt, &worktree_path)
@@ -329,6 +340,7 @@ impl DraftsService {
                     queued: false,
                     variant: None,
                     image_ids: None,
+                    run_at: None,
                     version: 0,
                 });
 
@@ -428,6 +440,7 @@ impl DraftsService {
             payload.queued,
             payload.expected_queued,
             payload.expected_version,
+            payload.run_at,
         )
         .await?;
 
@@ -447,7 +460,10 @@ impl DraftsService {
             ));
         }
 
-        let should_consider_start = draft.as_ref().map(|c| c.queued).unwrap_or(false)
+        // Scheduled follow-ups are left for the scheduler until their time comes
+        let should_consider_start = draft
+            .as_ref()
+            .is_some_and(|d| d.queued && d.run_at.is_none_or(|at| at <= Utc::now()))
             && !self
                 .has_running_processes_for_attempt(task_attempt.id)
                 .await?;
@@ -5,6 +5,7 @@ use db::{
     models::{
         draft::{Draft, DraftType},
         execution_process::ExecutionProcess,
+        pr_check_status::PrCheckStatus,
         task::{Task, TaskWithAttemptStatus},
         task_attempt::TaskAttempt,
     },
@@ -15,6 +16,8 @@ use tokio::sync::RwLock;
 use utils::msg_store::MsgStore;
 use uuid::Uuid;
 
+use crate::activity_feed::ActivityEventRecorder;
+
 #[path = "events/patches.rs"]
 pub mod patches;
 #[path = "events/streams.rs"]
@@ -22,7 +25,9 @@ mod streams;
 #[path = "events/types.rs"]
 pub mod types;
 
-pub use patches::{draft_patch, execution_process_patch, task_attempt_patch, task_patch};
+pub use patches::{
+    draft_patch, execution_process_patch, pr_check_patch, task_attempt_patch, task_patch,
+};
 pub use types::{EventError, EventPatch, EventPatchInner, HookTables, RecordTypes};
 
 #[derive(Clone)]
@@ -161,6 +166,26 @@ impl EventService {
                     let msg_store_for_hook = msg_store_for_hook.clone();
                     let db = db_for_hook.clone();
 
+                    if !matches!(hook.operation, SqliteOperation::Delete)
+                        && ActivityEventRecorder::records(hook.table)
+                    {
+                        let db = db.clone();
+                        let table = hook.table.to_string();
+                        let rowid = hook.rowid;
+                        runtime_handle.spawn(async move {
+                            if let Err(e) =
+                                ActivityEventRecorder::record(&db.pool, &table, rowid).await
+                            {
+                                tracing::warn!(
+                                    "Failed to record activity for {} row {}: {:?}",
+                                    table,
+                                    rowid,
+                                    e
+                                );
+                            }
+                        });
+                    }
+
                     if let Ok(table) = HookTables::from_str(hook.table) {
                         let rowid = hook.rowid;
                         runtime_handle.spawn(async move {
@@ -172,6 +197,21 @@ impl EventService {
                                     // Deletions handled in preupdate hook for reliable data capture
                                     return;
                                 }
+                                // Statuses are only removed along with their attempt
+                                (HookTables::PrCheckStatuses, SqliteOperation::Delete) => return,
+                                (HookTables::PrCheckStatuses, _) => {
+                                    match PrCheckStatus::find_by_rowid(&db.pool, rowid).await {
+                                        Ok(Some(status)) => RecordTypes::PrCheckStatus(status),
+                                        Ok(None) => return,
+                                        Err(e) => {
+                                            tracing::error!(
+                                                "Failed to fetch pr_check_status: {:?}",
+                                                e
+                                            );
+                                            return;
+                                        }
+                                    }
+                                }
                                 (HookTables::Tasks, _) => {
                                     match Task::find_by_rowid(&db.pool, rowid).await {
                                         Ok(Some(task)) => RecordTypes::Task(task),
@@ -269,6 +309,8 @@ impl EventService {
                                                 has_merged_attempt: false,
                                                 last_attempt_failed: false,
                                                 executor: String::new(),
+                                                labels: Vec::new(),
+                                                due_at: None,
                                             },
                                             true,
                                         )
@@ -304,6 +346,11 @@ impl EventService {
                                     msg_store_for_hook.push_patch(patch);
                                     return;
                                 }
+                                RecordTypes::PrCheckStatus(status) => {
+                                    let patch = pr_check_patch::replace(status);
+                                    msg_store_for_hook.push_patch(patch);
+                                    return;
+                                }
                                 RecordTypes::DeletedDraft { draft_type, task_attempt_id: Some(id), .. } => {
                                     let patch = match draft_type {
                                         DraftType::FollowUp => draft_patch::follow_up_clear(*id),
@@ -1,6 +1,7 @@
 use db::models::{
     draft::{Draft, DraftType},
     execution_process::ExecutionProcess,
+    pr_check_status::PrCheckStatus,
     task::TaskWithAttemptStatus,
     task_attempt::TaskAttempt,
 };
@@ -202,3 +203,23 @@ pub mod task_attempt_patch {
         })])
     }
 }
+
+/// Helper functions for creating PR check status patches
+pub mod pr_check_patch {
+    use super::*;
+
+    fn pr_checks_path(attempt_id: Uuid) -> String {
+        format!("/pr_checks/{attempt_id}")
+    }
+
+    /// Replace the PR review and CI status for an attempt
+    pub fn replace(status: &PrCheckStatus) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: pr_checks_path(status.task_attempt_id)
+                .try_into()
+                .expect("Path should be valid"),
+            value: serde_json::to_value(status)
+                .expect("PR check status serialization should not fail"),
+        })])
+    }
+}
@@ -1,7 +1,7 @@
 use db::models::{
     draft::{Draft, DraftType},
     execution_process::ExecutionProcess,
-    task::{Task, TaskWithAttemptStatus},
+    task::{Task, TaskListOptions, TaskWithAttemptStatus},
 };
 use futures::StreamExt;
 use serde_json::json;
@@ -11,19 +11,42 @@ use uuid::Uuid;
 
 use super::{
     EventService,
-    patches::execution_process_patch,
+    patches::{execution_process_patch, task_patch},
     types::{EventError, EventPatch, RecordTypes},
 };
 
+fn has_label(task: &TaskWithAttemptStatus, label_id: Option<Uuid>) -> bool {
+    label_id.is_none_or(|label_id| task.labels.iter().any(|label| label.id == label_id))
+}
+
+/// Under a label filter a task update can move the task into or out of the client's view, so it
+/// becomes an add when the task carries the label and a remove when it does not
+fn filter_task_patch(
+    task: &TaskWithAttemptStatus,
+    label_id: Option<Uuid>,
+    patch: json_patch::Patch,
+) -> json_patch::Patch {
+    match label_id {
+        None => patch,
+        Some(_) if has_label(task, label_id) => task_patch::add(task),
+        Some(_) => task_patch::remove(task.id),
+    }
+}
+
 impl EventService {
-    /// Stream raw task messages for a specific project with initial snapshot
+    /// Stream raw task messages for a specific project with initial snapshot. The snapshot holds
+    /// the page of tasks `options` selects; live updates cover every task matching its label.
     pub async fn stream_tasks_raw(
         &self,
         project_id: Uuid,
+        options: TaskListOptions,
     ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
     {
+        let label_id = options.label_id;
         // Get initial snapshot of tasks
-        let tasks = Task::find_by_project_id_with_attempt_status(&self.db.pool, project_id).await?;
+        let (tasks, _) =
+            Task::find_page_by_project_id_with_attempt_status(&self.db.pool, project_id, &options)
+                .await?;
 
         // Convert task array to object keyed by task ID
         let tasks_map: serde_json::Map<String, serde_json::Value> = tasks
@@ -61,7 +84,9 @@ impl EventService {
                                                 )
                                                 && task.project_id == project_id
                                             {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
+                                                return Some(Ok(LogMsg::JsonPatch(
+                                                    filter_task_patch(&task, label_id, patch),
+                                                )));
                                             }
                                         }
                                         json_patch::PatchOperation::Replace(op) => {
@@ -72,7 +97,9 @@ impl EventService {
                                                 )
                                                 && task.project_id == project_id
                                             {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
+                                                return Some(Ok(LogMsg::JsonPatch(
+                                                    filter_task_patch(&task, label_id, patch),
+                                                )));
                                             }
                                         }
                                         json_patch::PatchOperation::Remove(_) => {
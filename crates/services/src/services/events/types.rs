@@ -2,6 +2,7 @@ use anyhow::Error as AnyhowError;
 use db::models::{
     draft::{Draft, DraftType},
     execution_process::ExecutionProcess,
+    pr_check_status::PrCheckStatus,
     task::Task,
     task_attempt::TaskAttempt,
 };
@@ -32,6 +33,8 @@ pub enum HookTables {
     ExecutionProcesses,
     #[strum(to_string = "drafts")]
     Drafts,
+    #[strum(to_string = "pr_check_statuses")]
+    PrCheckStatuses,
 }
 
 #[derive(Serialize, Deserialize, TS)]
@@ -42,6 +45,7 @@ pub enum RecordTypes {
     ExecutionProcess(ExecutionProcess),
     Draft(Draft),
     RetryDraft(Draft),
+    PrCheckStatus(PrCheckStatus),
     DeletedTask {
         rowid: i64,
         project_id: Option<Uuid>,
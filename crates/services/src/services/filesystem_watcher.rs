@@ -1,9 +1,10 @@
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+use db::models::project::ProjectWatcherSettings;
 use futures::{
     SinkExt,
     channel::mpsc::{Receiver, channel},
@@ -18,6 +19,111 @@ use notify_debouncer_full::{
 };
 use thiserror::Error;
 
+/// Directories skipped by default; build output churns heavily during dev-server runs.
+pub const DEFAULT_EXCLUDE_PATTERNS: &[&str] = &["target/", "node_modules/"];
+
+/// Default soft cap on forwarded events per second for a single watcher.
+pub const DEFAULT_MAX_EVENTS_PER_SECOND: u32 = 500;
+
+/// Files whose diffs start collapsed by default; lockfile churn rarely needs review.
+pub const DEFAULT_DIFF_IGNORE_PATTERNS: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "yarn.lock",
+];
+
+#[derive(Debug, Clone)]
+pub struct WatcherOptions {
+    /// Gitignore-style patterns, relative to the watched root, whose events are dropped
+    pub exclude_patterns: Vec<String>,
+    /// Soft cap on events forwarded per second; `None` disables throttling
+    pub max_events_per_second: Option<u32>,
+    /// Gitignore-style patterns for files whose diffs are collapsed with contents omitted
+    pub diff_ignore_patterns: Vec<String>,
+}
+
+impl Default for WatcherOptions {
+    fn default() -> Self {
+        Self {
+            exclude_patterns: DEFAULT_EXCLUDE_PATTERNS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+            max_events_per_second: Some(DEFAULT_MAX_EVENTS_PER_SECOND),
+            diff_ignore_patterns: DEFAULT_DIFF_IGNORE_PATTERNS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl WatcherOptions {
+    /// Apply a project's overrides on top of the defaults
+    pub fn for_project(settings: &ProjectWatcherSettings) -> Self {
+        let defaults = Self::default();
+        Self {
+            exclude_patterns: settings
+                .exclude_patterns
+                .clone()
+                .unwrap_or(defaults.exclude_patterns),
+            max_events_per_second: match settings.max_events_per_second {
+                Some(0) => None,
+                Some(max) => Some(max),
+                None => defaults.max_events_per_second,
+            },
+            diff_ignore_patterns: settings
+                .diff_ignore_patterns
+                .clone()
+                .unwrap_or(defaults.diff_ignore_patterns),
+        }
+    }
+}
+
+/// Fixed one-second window limiter. Batches over the cap are delayed rather than dropped, so the
+/// debouncer keeps coalescing events for the same paths while the window drains.
+#[derive(Debug)]
+pub struct EventRateLimiter {
+    max_per_second: u32,
+    window_start: Instant,
+    sent_in_window: u32,
+}
+
+impl EventRateLimiter {
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    pub fn new(max_per_second: u32, now: Instant) -> Self {
+        Self {
+            max_per_second: max_per_second.max(1),
+            window_start: now,
+            sent_in_window: 0,
+        }
+    }
+
+    /// Account for a batch of `events` and return how long to wait before forwarding it. A batch
+    /// larger than the cap is let through on its own once the previous window has elapsed.
+    pub fn delay_for(&mut self, events: usize, now: Instant) -> Duration {
+        let events = u32::try_from(events).unwrap_or(u32::MAX);
+        if now.duration_since(self.window_start) >= Self::WINDOW {
+            self.window_start = now;
+            self.sent_in_window = 0;
+        }
+
+        if self.sent_in_window > 0
+            && self.sent_in_window.saturating_add(events) > self.max_per_second
+        {
+            let next_window = self.window_start + Self::WINDOW;
+            self.window_start = next_window;
+            self.sent_in_window = events;
+            return next_window.saturating_duration_since(now);
+        }
+
+        self.sent_in_window = self.sent_in_window.saturating_add(events);
+        Duration::ZERO
+    }
+}
+
 pub type WatcherComponents = (
     Debouncer<RecommendedWatcher, RecommendedCache>,
     Receiver<DebounceEventResult>,
@@ -36,6 +142,8 @@ pub enum FilesystemWatcherError {
     GitignoreBuilder(String),
     #[error("Invalid path: {0}")]
     InvalidPath(String),
+    #[error("Invalid exclude pattern '{0}'")]
+    InvalidExcludePattern(String),
 }
 
 fn canonicalize_lossy(path: &Path) -> PathBuf {
@@ -77,6 +185,44 @@ fn build_gitignore_set(root: &Path) -> Result<Gitignore, FilesystemWatcherError>
     Ok(builder.build()?)
 }
 
+fn build_exclude_set(
+    root: &Path,
+    patterns: &[String],
+) -> Result<Gitignore, FilesystemWatcherError> {
+    let mut builder = GitignoreBuilder::new(root);
+    for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        builder
+            .add_line(None, pattern)
+            .map_err(|_| FilesystemWatcherError::InvalidExcludePattern(pattern.to_string()))?;
+    }
+    Ok(builder.build()?)
+}
+
+/// Check that every pattern parses, so bad project settings are rejected when saved rather than
+/// when a diff stream starts.
+pub fn validate_exclude_patterns(patterns: &[String]) -> Result<(), FilesystemWatcherError> {
+    build_exclude_set(Path::new(""), patterns).map(|_| ())
+}
+
+/// Matches repository-relative diff paths against a project's diff ignore patterns
+#[derive(Debug, Clone)]
+pub struct DiffIgnore(Gitignore);
+
+impl DiffIgnore {
+    pub fn new(patterns: &[String]) -> Result<Self, FilesystemWatcherError> {
+        build_exclude_set(Path::new(""), patterns).map(Self)
+    }
+
+    pub fn empty() -> Self {
+        Self(Gitignore::empty())
+    }
+
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let path = Path::new(path.trim_start_matches('/'));
+        self.0.matched_path_or_any_parents(path, false).is_ignore()
+    }
+}
+
 fn path_allowed(path: &Path, gi: &Gitignore, canonical_root: &Path) -> bool {
     let canonical_path = canonicalize_lossy(path);
 
@@ -97,22 +243,33 @@ fn path_allowed(path: &Path, gi: &Gitignore, canonical_root: &Path) -> bool {
     !matched.is_ignore()
 }
 
-fn debounced_should_forward(event: &DebouncedEvent, gi: &Gitignore, canonical_root: &Path) -> bool {
+fn debounced_should_forward(
+    event: &DebouncedEvent,
+    gi: &Gitignore,
+    excludes: &Gitignore,
+    canonical_root: &Path,
+) -> bool {
     // DebouncedEvent is a struct that wraps the underlying notify::Event
     // We can check its paths field to determine if the event should be forwarded
-    event
-        .paths
-        .iter()
-        .all(|path| path_allowed(path, gi, canonical_root))
+    event.paths.iter().all(|path| {
+        path_allowed(path, gi, canonical_root) && path_allowed(path, excludes, canonical_root)
+    })
 }
 
-pub fn async_watcher(root: PathBuf) -> Result<WatcherComponents, FilesystemWatcherError> {
+pub fn async_watcher(
+    root: PathBuf,
+    options: WatcherOptions,
+) -> Result<WatcherComponents, FilesystemWatcherError> {
     let canonical_root = canonicalize_lossy(&root);
     let gi_set = Arc::new(build_gitignore_set(&canonical_root)?);
+    let exclude_set = build_exclude_set(&canonical_root, &options.exclude_patterns)?;
     let (mut tx, rx) = channel(64); // Increased capacity for error bursts
 
     let gi_clone = gi_set.clone();
     let root_clone = canonical_root.clone();
+    let mut rate_limiter = options
+        .max_events_per_second
+        .map(|max| EventRateLimiter::new(max, Instant::now()));
 
     let mut debouncer = new_debouncer(
         Duration::from_millis(200),
@@ -123,10 +280,20 @@ pub fn async_watcher(root: PathBuf) -> Result<WatcherComponents, FilesystemWatch
                     // Filter events and only send allowed ones
                     let filtered_events: Vec<DebouncedEvent> = events
                         .into_iter()
-                        .filter(|ev| debounced_should_forward(ev, &gi_clone, &root_clone))
+                        .filter(|ev| {
+                            debounced_should_forward(ev, &gi_clone, &exclude_set, &root_clone)
+                        })
                         .collect();
 
                     if !filtered_events.is_empty() {
+                        // Sleeping here blocks only the debouncer thread; new events keep
+                        // accumulating and are merged into the next batch.
+                        if let Some(limiter) = rate_limiter.as_mut() {
+                            let delay = limiter.delay_for(filtered_events.len(), Instant::now());
+                            if !delay.is_zero() {
+                                std::thread::sleep(delay);
+                            }
+                        }
                         let filtered_result = Ok(filtered_events);
                         futures::executor::block_on(async {
                             tx.send(filtered_result).await.ok();
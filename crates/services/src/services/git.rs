@@ -1,6 +1,10 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use chrono::{DateTime, Utc};
+use db::models::merge::MergeStrategy;
 use git2::{
     BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, Reference, Remote,
     Repository, Sort, build::CheckoutBuilder,
@@ -8,13 +12,16 @@ use git2::{
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ts_rs::TS;
-use utils::diff::{Diff, DiffChangeKind, FileDiffDetails};
+use utils::diff::{
+    BinaryChange, BinaryFile, Diff, DiffChangeKind, DiffSide, FileDiffDetails, HunkRange,
+    LfsChange, LfsPointer, compute_intraline_changes, detect_moved_blocks, revert_hunk,
+};
 use uuid::Uuid;
 
 // Import for file ranking functionality
 use super::file_ranker::FileStat;
 use super::git_cli::{ChangeType, GitCli, GitCliError, StatusDiffEntry, StatusDiffOptions};
-use crate::services::github_service::GitHubRepoInfo;
+use crate::services::{config::CommitSigningConfig, github_service::GitHubRepoInfo};
 
 #[derive(Debug, Error)]
 pub enum GitServiceError {
@@ -38,6 +45,10 @@ pub enum GitServiceError {
     TokenUnavailable,
     #[error("Rebase in progress; resolve or abort it before retrying")]
     RebaseInProgress,
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+    #[error("The selected hunk of {0} no longer matches its changes; refresh the diff and retry")]
+    HunkNotFound(String),
 }
 /// Service for managing Git operations in task execution workflows
 #[derive(Clone)]
@@ -47,6 +58,9 @@ pub struct GitService {}
 // their contents omitted from the diff stream to avoid UI crashes.
 const MAX_INLINE_DIFF_BYTES: usize = 2 * 1024 * 1024; // ~2MB
 
+// How much of a binary file is read to find an image header
+const BINARY_SNIFF_BYTES: usize = 256 * 1024;
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 #[ts(rename_all = "snake_case")]
@@ -57,6 +71,76 @@ pub enum ConflictOp {
     Revert,
 }
 
+/// One `<<<<<<<` … `>>>>>>>` region of a conflicted file. During a rebase `ours` is the branch
+/// being rebased onto and `theirs` is the attempt commit being replayed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ConflictHunk {
+    /// 1-based line of the opening marker in the worktree file
+    pub start_line: usize,
+    pub ours: String,
+    /// Common ancestor text; only present with the `diff3`/`zdiff3` conflict style
+    pub base: Option<String>,
+    pub theirs: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ConflictedFile {
+    pub path: String,
+    /// Empty for binary files and conflicts without markers (e.g. modify/delete)
+    pub hunks: Vec<ConflictHunk>,
+}
+
+/// Outcome of merging an attempt branch into its target, computed in memory without touching
+/// any worktree or ref.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct MergePreview {
+    /// True when the merge produces no conflicts
+    pub clean: bool,
+    /// Commits on the target missing from the attempt branch; merging is refused while non-zero
+    pub commits_behind: usize,
+    /// Changes the merge would bring into the target. With conflicts these compare the target
+    /// against the attempt branch instead.
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    pub conflicted_files: Vec<String>,
+}
+
+/// Result of pushing new attempt commits to the branch behind an open pull request
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct BranchPushOutcome {
+    pub pushed_commits: usize,
+    /// Local commits still missing from the remote branch
+    pub remote_commits_ahead: usize,
+    /// Remote commits missing locally; nothing is pushed until the branch is brought up to date
+    pub remote_commits_behind: usize,
+}
+
+/// The three index stages of a conflicted path. A missing side means the file was deleted (or
+/// never existed) there; binary content is not inlined.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ConflictFileVersions {
+    pub path: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    pub is_binary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    Base,
+    /// Hand-merged file content
+    Custom {
+        content: String,
+    },
+    Delete,
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct GitBranch {
     pub name: String,
@@ -88,6 +172,11 @@ impl Commit {
     pub fn new(id: git2::Oid) -> Self {
         Self(id)
     }
+    pub fn from_sha(sha: &str) -> Result<Self, GitServiceError> {
+        git2::Oid::from_str(sha)
+            .map(Self)
+            .map_err(|_| GitServiceError::InvalidRepository(format!("Invalid commit SHA: {sha}")))
+    }
     pub fn as_oid(&self) -> git2::Oid {
         self.0
     }
@@ -129,6 +218,19 @@ pub struct WorktreeResetOutcome {
     pub applied: bool,
 }
 
+/// Ref namespace holding worktree snapshots, one directory per snapshot key (the attempt id)
+const SNAPSHOT_REF_PREFIX: &str = "refs/gybe/snapshots";
+
+/// Uncommitted worktree changes saved before an operation that could discard them. Snapshots are
+/// commits on top of the HEAD they were taken at, kept alive by a ref in the repository.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct WorktreeSnapshot {
+    pub oid: String,
+    /// Operation the snapshot was taken before
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Target for diff generation
 pub enum DiffTarget<'p> {
     /// Work-in-progress branch checked out in this worktree
@@ -147,6 +249,12 @@ pub enum DiffTarget<'p> {
         repo_path: &'p Path,
         commit_sha: &'p str,
     },
+    /// Everything committed after `from_sha` up to and including `to_sha`
+    Range {
+        repo_path: &'p Path,
+        from_sha: &'p str,
+        to_sha: &'p str,
+    },
 }
 
 impl Default for GitService {
@@ -294,6 +402,17 @@ impl GitService {
     }
 
     pub fn commit(&self, path: &Path, message: &str) -> Result<bool, GitServiceError> {
+        self.commit_with_signing(path, message, None)
+    }
+
+    /// Stage and commit all changes, signing the commit when `signing` is enabled. A signing
+    /// failure fails the commit rather than falling back to an unsigned one.
+    pub fn commit_with_signing(
+        &self,
+        path: &Path,
+        message: &str,
+        signing: Option<&CommitSigningConfig>,
+    ) -> Result<bool, GitServiceError> {
         // Use Git CLI to respect sparse-checkout semantics for staging and commit
         let git = GitCli::new();
         let has_changes = git
@@ -308,16 +427,40 @@ impl GitService {
             .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
         // Only ensure identity once we know we're about to commit
         self.ensure_cli_commit_identity(path)?;
-        git.commit(path, message)
-            .map_err(|e| GitServiceError::InvalidRepository(format!("git commit failed: {e}")))?;
+        match signing.filter(|signing| signing.enabled) {
+            Some(signing) => git
+                .commit_signed(
+                    path,
+                    message,
+                    signing.format.as_ref().map(|format| format.as_git_format()),
+                    signing.key(),
+                )
+                .map_err(|e| {
+                    GitServiceError::InvalidRepository(format!("signed git commit failed: {e}"))
+                })?,
+            None => git.commit(path, message).map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git commit failed: {e}"))
+            })?,
+        }
         Ok(true)
     }
 
-    /// Get diffs between branches or worktree changes
+    /// Get diffs between branches or worktree changes. Renames are detected by similarity and
+    /// blocks of code moved between or within files are annotated on the diffs involved.
     pub fn get_diffs(
         &self,
         target: DiffTarget,
         path_filter: Option<&[&str]>,
+    ) -> Result<Vec<Diff>, GitServiceError> {
+        let mut diffs = self.collect_diffs(target, path_filter)?;
+        detect_moved_blocks(&mut diffs);
+        Ok(diffs)
+    }
+
+    fn collect_diffs(
+        &self,
+        target: DiffTarget,
+        path_filter: Option<&[&str]>,
     ) -> Result<Vec<Diff>, GitServiceError> {
         match target {
             DiffTarget::Worktree {
@@ -382,6 +525,7 @@ impl GitService {
 
                 // Enable rename detection
                 let mut find_opts = DiffFindOptions::new();
+                find_opts.renames(true);
                 diff.find_similar(Some(&mut find_opts))?;
 
                 self.convert_diff_to_file_diffs(diff, &repo)
@@ -427,11 +571,190 @@ impl GitService {
 
                 // Enable rename detection
                 let mut find_opts = git2::DiffFindOptions::new();
+                find_opts.renames(true);
                 diff.find_similar(Some(&mut find_opts))?;
 
                 self.convert_diff_to_file_diffs(diff, &repo)
             }
+            DiffTarget::Range {
+                repo_path,
+                from_sha,
+                to_sha,
+            } => {
+                let repo = self.open_repo(repo_path)?;
+                let from_tree = Self::find_commit_by_sha(&repo, from_sha)?.tree()?;
+                let to_tree = Self::find_commit_by_sha(&repo, to_sha)?.tree()?;
+
+                let mut diff_opts = DiffOptions::new();
+                diff_opts.include_typechange(true);
+                if let Some(paths) = path_filter {
+                    for path in paths {
+                        diff_opts.pathspec(*path);
+                    }
+                }
+
+                let mut diff =
+                    repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), Some(&mut diff_opts))?;
+                let mut find_opts = DiffFindOptions::new();
+                find_opts.renames(true);
+                diff.find_similar(Some(&mut find_opts))?;
+
+                self.convert_diff_to_file_diffs(diff, &repo)
+            }
+        }
+    }
+
+    fn find_commit_by_sha<'r>(
+        repo: &'r Repository,
+        sha: &str,
+    ) -> Result<git2::Commit<'r>, GitServiceError> {
+        Ok(repo.find_commit(Commit::from_sha(sha)?.as_oid())?)
+    }
+
+    // a path from a diff, which must stay inside the repository
+    fn repo_relative_path(path: &str) -> Result<&Path, GitServiceError> {
+        let rel_path = Path::new(path);
+        if rel_path
+            .components()
+            .all(|component| matches!(component, std::path::Component::Normal(_)))
+        {
+            Ok(rel_path)
+        } else {
+            Err(GitServiceError::InvalidPath(path.to_string()))
+        }
+    }
+
+    /// Raw bytes of `path` on one side of a diff, for previewing files whose contents diffs leave
+    /// out. `None` when that side has no such file.
+    pub fn read_diff_side(
+        &self,
+        target: DiffTarget,
+        side: DiffSide,
+        path: &str,
+    ) -> Result<Option<Vec<u8>>, GitServiceError> {
+        let rel_path = Self::repo_relative_path(path)?;
+        let (repo, tree) = match target {
+            DiffTarget::Worktree {
+                worktree_path,
+                base_commit,
+            } => {
+                if side == DiffSide::New {
+                    return match std::fs::read(worktree_path.join(rel_path)) {
+                        Ok(bytes) => Ok(Some(bytes)),
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                        Err(e) => Err(e.into()),
+                    };
+                }
+                let repo = Repository::open(worktree_path)?;
+                let tree = repo.find_commit(base_commit.as_oid())?.tree()?;
+                (repo, tree.id())
+            }
+            DiffTarget::Branch {
+                repo_path,
+                branch_name,
+                base_branch,
+            } => {
+                let repo = self.open_repo(repo_path)?;
+                let branch = match side {
+                    DiffSide::Old => base_branch,
+                    DiffSide::New => branch_name,
+                };
+                let tree = Self::find_branch(&repo, branch)?
+                    .get()
+                    .peel_to_commit()?
+                    .tree()?
+                    .id();
+                (repo, tree)
+            }
+            DiffTarget::Commit {
+                repo_path,
+                commit_sha,
+            } => {
+                let repo = self.open_repo(repo_path)?;
+                let commit_oid = git2::Oid::from_str(commit_sha).map_err(|_| {
+                    GitServiceError::InvalidRepository(format!("Invalid commit SHA: {commit_sha}"))
+                })?;
+                let commit = repo.find_commit(commit_oid)?;
+                let tree = match side {
+                    DiffSide::Old => commit.parent(0)?.tree()?.id(),
+                    DiffSide::New => commit.tree()?.id(),
+                };
+                (repo, tree)
+            }
+            DiffTarget::Range {
+                repo_path,
+                from_sha,
+                to_sha,
+            } => {
+                let repo = self.open_repo(repo_path)?;
+                let sha = match side {
+                    DiffSide::Old => from_sha,
+                    DiffSide::New => to_sha,
+                };
+                let tree = Self::find_commit_by_sha(&repo, sha)?.tree()?.id();
+                (repo, tree)
+            }
+        };
+
+        let tree = repo.find_tree(tree)?;
+        let Ok(entry) = tree.get_path(rel_path) else {
+            return Ok(None);
+        };
+        Ok(repo
+            .find_blob(entry.id())
+            .ok()
+            .map(|blob| blob.content().to_vec()))
+    }
+
+    /// Undo a change from a worktree diff without touching the index. The whole file is restored
+    /// from `base_commit`, or removed if it was added, unless `hunk` picks out some of its lines. A
+    /// renamed file is reverted by removing `path` and restoring `old_path`.
+    pub fn revert_worktree_change(
+        &self,
+        worktree_path: &Path,
+        base_commit: &Commit,
+        path: &str,
+        old_path: Option<&str>,
+        hunk: Option<&HunkRange>,
+    ) -> Result<(), GitServiceError> {
+        let rel_path = Self::repo_relative_path(path)?;
+        let old_rel_path = match old_path {
+            Some(old_path) => Self::repo_relative_path(old_path)?,
+            None => rel_path,
+        };
+        let repo = Repository::open(worktree_path)?;
+        let base_tree = repo.find_commit(base_commit.as_oid())?.tree()?;
+        let base_entry = base_tree.get_path(old_rel_path).ok();
+        let target = worktree_path.join(rel_path);
+
+        if let Some(hunk) = hunk {
+            let base = match &base_entry {
+                Some(entry) => repo.find_blob(entry.id())?.content().to_vec(),
+                None => Vec::new(),
+            };
+            let hunk_not_found = || GitServiceError::HunkNotFound(path.to_string());
+            let old = String::from_utf8(base).map_err(|_| hunk_not_found())?;
+            let new = std::fs::read_to_string(&target).map_err(|_| hunk_not_found())?;
+            let reverted = revert_hunk(&old, &new, hunk).ok_or_else(hunk_not_found)?;
+            std::fs::write(&target, reverted)?;
+            return Ok(());
         }
+
+        if (base_entry.is_none() || old_rel_path != rel_path)
+            && std::fs::symlink_metadata(&target).is_ok()
+        {
+            std::fs::remove_file(&target)?;
+        }
+        if base_entry.is_some() {
+            let mut checkout = CheckoutBuilder::new();
+            checkout
+                .force()
+                .update_index(false)
+                .disable_pathspec_match(true)
+                .path(old_rel_path);
+            repo.checkout_tree(base_tree.as_object(), Some(&mut checkout))?;
+        }
+        Ok(())
     }
 
     /// Convert git2::Diff to our Diff structs
@@ -477,7 +800,7 @@ impl GitService {
                 }
 
                 // Only build old/new content if not omitted
-                let (old_path, old_content) = if matches!(status, Delta::Added) {
+                let (old_path, mut old_content) = if matches!(status, Delta::Added) {
                     (None, None)
                 } else {
                     let path_opt = delta
@@ -498,7 +821,7 @@ impl GitService {
                     }
                 };
 
-                let (new_path, new_content) = if matches!(status, Delta::Deleted) {
+                let (new_path, mut new_content) = if matches!(status, Delta::Deleted) {
                     (None, None)
                 } else {
                     let path_opt = delta
@@ -540,6 +863,28 @@ impl GitService {
                     }
                 }
 
+                let lfs = Self::detect_lfs_change(
+                    repo,
+                    new_path.as_deref().or(old_path.as_deref()),
+                    &mut old_content,
+                    &mut new_content,
+                );
+
+                let binary_side = |oid: git2::Oid| {
+                    (!oid.is_zero())
+                        .then(|| repo.find_blob(oid).ok())
+                        .flatten()
+                        .and_then(|blob| Self::binary_blob(&blob))
+                };
+                let binary = BinaryChange::new(
+                    (!matches!(status, Delta::Added))
+                        .then(|| binary_side(delta.old_file().id()))
+                        .flatten(),
+                    (!matches!(status, Delta::Deleted))
+                        .then(|| binary_side(delta.new_file().id()))
+                        .flatten(),
+                );
+
                 // If contents are omitted, try to compute line stats via libgit2 Patch
                 let mut additions: Option<usize> = None;
                 let mut deletions: Option<usize> = None;
@@ -551,6 +896,12 @@ impl GitService {
                     deletions = Some(dels);
                 }
 
+                let intraline = old_content
+                    .as_deref()
+                    .zip(new_content.as_deref())
+                    .map(|(old, new)| compute_intraline_changes(old, new))
+                    .unwrap_or_default();
+
                 file_diffs.push(Diff {
                     change,
                     old_path,
@@ -560,6 +911,11 @@ impl GitService {
                     content_omitted,
                     additions,
                     deletions,
+                    lfs,
+                    intraline,
+                    binary,
+                    collapsed: false,
+                    moved_blocks: Vec::new(),
                     repository_id: None,
                     repository_name: None,
                     repository_root: None,
@@ -595,6 +951,32 @@ impl GitService {
         }
     }
 
+    /// Size and image details of a binary blob; `None` for text
+    fn binary_blob(blob: &git2::Blob) -> Option<BinaryFile> {
+        blob.is_binary().then(|| {
+            let content = blob.content();
+            BinaryFile::new(
+                content.len() as u64,
+                &content[..content.len().min(BINARY_SNIFF_BYTES)],
+            )
+        })
+    }
+
+    /// Size and image details of a worktree file holding null bytes, which
+    /// `read_file_to_string` skips as binary; `None` for text
+    fn binary_worktree_file(repo: &Repository, rel_path: &Path) -> Option<BinaryFile> {
+        use std::io::Read;
+
+        let abs_path = repo.workdir()?.join(rel_path);
+        let file = std::fs::File::open(&abs_path).ok()?;
+        let size = file.metadata().ok()?.len();
+        let mut head = Vec::new();
+        file.take(BINARY_SNIFF_BYTES as u64)
+            .read_to_end(&mut head)
+            .ok()?;
+        head.contains(&0).then(|| BinaryFile::new(size, &head))
+    }
+
     /// Helper function to read file content from filesystem with safety guards
     fn read_file_to_string(repo: &Repository, rel_path: &Path) -> Option<String> {
         let workdir = repo.workdir()?;
@@ -722,7 +1104,7 @@ impl GitService {
         }
 
         // Load contents only if not omitted
-        let (old_content, new_content) = if content_omitted {
+        let (mut old_content, mut new_content) = if content_omitted {
             (None, None)
         } else {
             // Load old content from base tree if possible
@@ -749,6 +1131,24 @@ impl GitService {
             (old_content, new_content)
         };
 
+        let lfs = Self::detect_lfs_change(
+            repo,
+            new_path_opt.as_deref().or(old_path_opt.as_deref()),
+            &mut old_content,
+            &mut new_content,
+        );
+
+        let binary = BinaryChange::new(
+            old_path_opt.as_deref().and_then(|oldp| {
+                let entry = base_tree.get_path(Path::new(oldp)).ok()?;
+                let blob = repo.find_blob(entry.id()).ok()?;
+                Self::binary_blob(&blob)
+            }),
+            new_path_opt
+                .as_deref()
+                .and_then(|newp| Self::binary_worktree_file(repo, Path::new(newp))),
+        );
+
         // If reported as Modified but content is identical, treat as a permission-only change
         if matches!(change, DiffChangeKind::Modified)
             && old_content.is_some()
@@ -758,6 +1158,12 @@ impl GitService {
             change = DiffChangeKind::PermissionChange;
         }
 
+        let intraline = old_content
+            .as_deref()
+            .zip(new_content.as_deref())
+            .map(|(old, new)| compute_intraline_changes(old, new))
+            .unwrap_or_default();
+
         Diff {
             change,
             old_path: old_path_opt,
@@ -767,12 +1173,47 @@ impl GitService {
             content_omitted,
             additions: None,
             deletions: None,
+            lfs,
+            intraline,
+            binary,
+            collapsed: false,
+            moved_blocks: Vec::new(),
             repository_id: None,
             repository_name: None,
             repository_root: None,
         }
     }
 
+    /// Detect files stored in Git LFS, either from pointer contents or from the `filter=lfs`
+    /// attribute when the worktree side is checked out as real data. Contents of LFS files are
+    /// cleared so pointer text is not rendered as a content change.
+    fn detect_lfs_change(
+        repo: &Repository,
+        path: Option<&str>,
+        old_content: &mut Option<String>,
+        new_content: &mut Option<String>,
+    ) -> Option<LfsChange> {
+        let old = old_content.as_deref().and_then(LfsPointer::parse);
+        let new = new_content.as_deref().and_then(LfsPointer::parse);
+        let tracked = path.is_some_and(|p| {
+            matches!(
+                repo.get_attr(
+                    Path::new(p),
+                    "filter",
+                    git2::AttrCheckFlags::FILE_THEN_INDEX
+                ),
+                Ok(Some("lfs"))
+            )
+        });
+        if old.is_none() && new.is_none() && !tracked {
+            return None;
+        }
+
+        *old_content = None;
+        *new_content = None;
+        Some(LfsChange { old, new })
+    }
+
     /// Find where a branch is currently checked out
     fn find_checkout_path_for_branch(
         &self,
@@ -803,21 +1244,53 @@ impl GitService {
         base_branch_name: &str,
         commit_message: &str,
     ) -> Result<String, GitServiceError> {
-        // Open the repositories
-        let task_repo = self.open_repo(task_worktree_path)?;
-        let base_repo = self.open_repo(base_worktree_path)?;
+        self.merge_changes_with_strategy(
+            base_worktree_path,
+            task_worktree_path,
+            task_branch_name,
+            base_branch_name,
+            commit_message,
+            MergeStrategy::Squash,
+        )
+    }
 
+    /// Merge changes from a task branch into the base branch using `strategy`. The commit
+    /// message is unused for fast-forwards.
+    pub fn merge_changes_with_strategy(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
+        strategy: MergeStrategy,
+    ) -> Result<String, GitServiceError> {
         // Check if base branch is ahead of task branch - this indicates the base has moved
         // ahead since the task was created, which should block the merge
         let (_, task_behind) =
             self.get_branch_status(base_worktree_path, task_branch_name, base_branch_name)?;
 
         if task_behind > 0 {
-            return Err(GitServiceError::BranchesDiverged(format!(
-                "Cannot merge: base branch '{base_branch_name}' is {task_behind} commits ahead of task branch '{task_branch_name}'. The base branch has moved forward since the task was created.",
-            )));
+            if strategy != MergeStrategy::RebaseFastForward {
+                return Err(GitServiceError::BranchesDiverged(format!(
+                    "Cannot merge: base branch '{base_branch_name}' is {task_behind} commits ahead of task branch '{task_branch_name}'. The base branch has moved forward since the task was created.",
+                )));
+            }
+            // Replay the task commits on top of the base so it can be fast-forwarded
+            self.rebase_branch(
+                base_worktree_path,
+                task_worktree_path,
+                base_branch_name,
+                base_branch_name,
+                task_branch_name,
+                None,
+            )?;
         }
 
+        // Open the repositories
+        let task_repo = self.open_repo(task_worktree_path)?;
+        let base_repo = self.open_repo(base_worktree_path)?;
+
         // Check where base branch is checked out (if anywhere)
         match self.find_checkout_path_for_branch(base_worktree_path, base_branch_name)? {
             Some(base_checkout_path) => {
@@ -839,16 +1312,28 @@ impl GitService {
 
                 // Use CLI merge in base context
                 self.ensure_cli_commit_identity(&base_checkout_path)?;
-                let sha = git_cli
-                    .merge_squash_commit(
+                let sha = match strategy {
+                    MergeStrategy::Squash => git_cli.merge_squash_commit(
                         &base_checkout_path,
                         base_branch_name,
                         task_branch_name,
                         commit_message,
-                    )
-                    .map_err(|e| {
-                        GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
-                    })?;
+                    ),
+                    MergeStrategy::MergeCommit => git_cli.merge_no_ff_commit(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                        commit_message,
+                    ),
+                    MergeStrategy::RebaseFastForward => git_cli.merge_ff_only(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                    ),
+                }
+                .map_err(|e| {
+                    GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
+                })?;
 
                 // Update task branch ref for continuity
                 let task_refname = format!("refs/heads/{task_branch_name}");
@@ -869,28 +1354,38 @@ impl GitService {
                 let base_commit = base_branch.get().peel_to_commit()?;
                 let task_commit = task_branch.get().peel_to_commit()?;
 
-                // Create the squash commit in-memory (no checkout) and update the base branch ref
-                let signature = self.signature_with_fallback(&task_repo)?;
-                let squash_commit_id = self.perform_squash_merge(
-                    &task_repo,
-                    &base_commit,
-                    &task_commit,
-                    &signature,
-                    commit_message,
-                    base_branch_name,
-                )?;
+                let merged_commit_id = match strategy {
+                    MergeStrategy::RebaseFastForward => {
+                        let refname = format!("refs/heads/{base_branch_name}");
+                        task_repo.reference(&refname, task_commit.id(), true, "Fast-forward")?;
+                        task_commit.id()
+                    }
+                    MergeStrategy::Squash | MergeStrategy::MergeCommit => {
+                        // Create the commit in-memory (no checkout) and update the base branch ref
+                        let signature = self.signature_with_fallback(&task_repo)?;
+                        self.perform_in_memory_merge(
+                            &task_repo,
+                            &base_commit,
+                            &task_commit,
+                            &signature,
+                            commit_message,
+                            base_branch_name,
+                            strategy,
+                        )?
+                    }
+                };
 
-                // Update the task branch to the new squash commit so follow-up
+                // Update the task branch to the new commit so follow-up
                 // work can continue from the merged state without conflicts.
                 let task_refname = format!("refs/heads/{task_branch_name}");
                 base_repo.reference(
                     &task_refname,
-                    squash_commit_id,
+                    merged_commit_id,
                     true,
-                    "Reset task branch after squash merge",
+                    "Reset task branch after merge",
                 )?;
 
-                Ok(squash_commit_id.to_string())
+                Ok(merged_commit_id.to_string())
             }
         }
     }
@@ -945,6 +1440,64 @@ impl GitService {
         )
     }
 
+    /// Dry-run a merge of `task_branch_name` into `base_branch_name`.
+    pub fn preview_merge(
+        &self,
+        repo_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<MergePreview, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let task_commit = Self::find_branch(&repo, task_branch_name)?
+            .get()
+            .peel_to_commit()?;
+        let base_commit = Self::find_branch(&repo, base_branch_name)?
+            .get()
+            .peel_to_commit()?;
+        let (_, commits_behind) =
+            self.get_branch_status(repo_path, task_branch_name, base_branch_name)?;
+
+        let mut merge_opts = git2::MergeOptions::new();
+        merge_opts.find_renames(true);
+        let mut index = repo.merge_commits(&base_commit, &task_commit, Some(&merge_opts))?;
+
+        let mut conflicted_files = Vec::new();
+        if index.has_conflicts() {
+            for conflict in index.conflicts()? {
+                let conflict = conflict?;
+                if let Some(entry) = [&conflict.our, &conflict.their, &conflict.ancestor]
+                    .into_iter()
+                    .flatten()
+                    .next()
+                {
+                    conflicted_files.push(String::from_utf8_lossy(&entry.path).to_string());
+                }
+            }
+        }
+
+        let result_tree = if conflicted_files.is_empty() {
+            repo.find_tree(index.write_tree_to(&repo)?)?
+        } else {
+            task_commit.tree()?
+        };
+        let mut diff_opts = DiffOptions::new();
+        let diff = repo.diff_tree_to_tree(
+            Some(&base_commit.tree()?),
+            Some(&result_tree),
+            Some(&mut diff_opts),
+        )?;
+        let stats = diff.stats()?;
+
+        Ok(MergePreview {
+            clean: conflicted_files.is_empty(),
+            commits_behind,
+            files_changed: stats.files_changed(),
+            additions: stats.insertions(),
+            deletions: stats.deletions(),
+            conflicted_files,
+        })
+    }
+
     pub fn get_base_commit(
         &self,
         repo_path: &Path,
@@ -1102,6 +1655,45 @@ impl GitService {
         ))
     }
 
+    /// Distinct authors (name, email) of the commits reachable from `head` but not from `base`,
+    /// in the order first seen walking back from `head`. Without a `base`, only `head` is read.
+    pub fn get_commit_authors(
+        &self,
+        repo_path: &Path,
+        base: Option<&str>,
+        head: &str,
+    ) -> Result<Vec<(Option<String>, Option<String>)>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let parse = |sha: &str| {
+            git2::Oid::from_str(sha)
+                .map_err(|_| GitServiceError::InvalidRepository("Invalid commit SHA".into()))
+        };
+        let head = parse(head)?;
+        let oids = match base {
+            Some(base) => {
+                let mut revwalk = repo.revwalk()?;
+                revwalk.push(head)?;
+                revwalk.hide(parse(base)?)?;
+                revwalk.collect::<Result<Vec<_>, _>>()?
+            }
+            None => vec![head],
+        };
+
+        let mut authors = Vec::new();
+        for oid in oids {
+            let commit = repo.find_commit(oid)?;
+            let author = commit.author();
+            let author = (
+                author.name().map(|s| s.to_string()),
+                author.email().map(|s| s.to_string()),
+            );
+            if !authors.contains(&author) {
+                authors.push(author);
+            }
+        }
+        Ok(authors)
+    }
+
     /// Get the subject/summary line for a given commit OID
     pub fn get_commit_subject(
         &self,
@@ -1218,6 +1810,73 @@ impl GitService {
         Ok(())
     }
 
+    /// Save the worktree's uncommitted changes under `key` before `reason` runs. Returns None when
+    /// the worktree is clean.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        key: &str,
+        reason: &str,
+    ) -> Result<Option<WorktreeSnapshot>, GitServiceError> {
+        let cli = GitCli::new();
+        let Some(oid) = cli.snapshot_worktree(worktree_path, reason)? else {
+            return Ok(None);
+        };
+        let created_at = Utc::now();
+        let refname = format!(
+            "{SNAPSHOT_REF_PREFIX}/{key}/{}",
+            created_at.timestamp_millis()
+        );
+        cli.update_ref(worktree_path, &refname, &oid)?;
+        Ok(Some(WorktreeSnapshot {
+            oid,
+            reason: reason.to_string(),
+            created_at,
+        }))
+    }
+
+    /// Snapshots saved under `key`, newest first
+    pub fn list_worktree_snapshots(
+        &self,
+        repo_path: &Path,
+        key: &str,
+    ) -> Result<Vec<WorktreeSnapshot>, GitServiceError> {
+        let refs = GitCli::new().list_refs(repo_path, &format!("{SNAPSHOT_REF_PREFIX}/{key}/"))?;
+        Ok(refs
+            .into_iter()
+            .filter_map(|(_, oid, date, subject)| {
+                Some(WorktreeSnapshot {
+                    oid,
+                    reason: subject,
+                    created_at: DateTime::parse_from_rfc3339(&date)
+                        .ok()?
+                        .with_timezone(&Utc),
+                })
+            })
+            .collect())
+    }
+
+    /// Re-apply a snapshot's changes on top of the worktree's current state. The changes are left
+    /// uncommitted; overlapping edits made since the snapshot show up as conflicts.
+    pub fn restore_worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+        oid: &str,
+    ) -> Result<(), GitServiceError> {
+        let cli = GitCli::new();
+        if let Err(e) = cli.apply_commit_changes(worktree_path, oid) {
+            let conflicted = cli.get_conflicted_files(worktree_path).unwrap_or_default();
+            if !conflicted.is_empty() {
+                return Err(GitServiceError::MergeConflicts(format!(
+                    "Restoring the snapshot conflicts in {}",
+                    conflicted.join(", ")
+                )));
+            }
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
     /// Convenience: Get author of HEAD commit
     pub fn get_head_author(
         &self,
@@ -1387,7 +2046,7 @@ impl GitService {
     }
 
     /// Perform a squash merge of task branch into base branch, but fail on conflicts
-    fn perform_squash_merge(
+    fn perform_in_memory_merge(
         &self,
         repo: &Repository,
         base_commit: &git2::Commit,
@@ -1395,6 +2054,7 @@ impl GitService {
         signature: &git2::Signature,
         commit_message: &str,
         base_branch_name: &str,
+        strategy: MergeStrategy,
     ) -> Result<git2::Oid, GitServiceError> {
         // In-memory merge to detect conflicts without touching the working tree
         let mut merge_opts = git2::MergeOptions::new();
@@ -1414,21 +2074,25 @@ impl GitService {
         let tree_id = index.write_tree_to(repo)?;
         let tree = repo.find_tree(tree_id)?;
 
-        // Create a squash commit: use merged tree with base_commit as sole parent
-        let squash_commit_id = repo.commit(
+        // A squash commit has the base as sole parent; a merge commit also records the task
+        let parents: Vec<&git2::Commit> = match strategy {
+            MergeStrategy::MergeCommit => vec![base_commit, task_commit],
+            _ => vec![base_commit],
+        };
+        let commit_id = repo.commit(
             None,           // Don't update any reference yet
             signature,      // Author
             signature,      // Committer
             commit_message, // Custom message
             &tree,          // Merged tree content
-            &[base_commit], // Single parent: base branch commit
+            &parents,
         )?;
 
         // Update the base branch reference to point to the new commit
         let refname = format!("refs/heads/{base_branch_name}");
-        repo.reference(&refname, squash_commit_id, true, "Squash merge")?;
+        repo.reference(&refname, commit_id, true, "Merge task branch")?;
 
-        Ok(squash_commit_id)
+        Ok(commit_id)
     }
 
     /// Rebase a worktree branch onto a new base
@@ -1602,6 +2266,170 @@ impl GitService {
         })
     }
 
+    /// Conflicted files in the worktree together with the marker regions in each of them.
+    pub fn get_conflict_details(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Vec<ConflictedFile>, GitServiceError> {
+        let files = self.get_conflicted_files(worktree_path)?;
+        Ok(files
+            .into_iter()
+            .map(|path| {
+                let hunks = std::fs::read(worktree_path.join(&path))
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+                    .map(|content| parse_conflict_hunks(&content))
+                    .unwrap_or_default();
+                ConflictedFile { path, hunks }
+            })
+            .collect())
+    }
+
+    /// Base/ours/theirs content of every conflicted path, read from the index stages.
+    pub fn get_conflict_versions(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Vec<ConflictFileVersions>, GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        let index = repo.index()?;
+        let mut files = Vec::new();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let Some(path) = [&conflict.our, &conflict.their, &conflict.ancestor]
+                .into_iter()
+                .flatten()
+                .next()
+                .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+            else {
+                continue;
+            };
+
+            let mut is_binary = false;
+            let mut read_stage = |entry: Option<&git2::IndexEntry>| -> Option<String> {
+                let blob = repo.find_blob(entry?.id).ok()?;
+                if blob.is_binary() {
+                    is_binary = true;
+                    return None;
+                }
+                Some(String::from_utf8_lossy(blob.content()).to_string())
+            };
+            let base = read_stage(conflict.ancestor.as_ref());
+            let ours = read_stage(conflict.our.as_ref());
+            let theirs = read_stage(conflict.their.as_ref());
+
+            files.push(ConflictFileVersions {
+                path,
+                base,
+                ours,
+                theirs,
+                is_binary,
+            });
+        }
+        Ok(files)
+    }
+
+    /// Write the chosen side (or custom content) of a conflicted path to the worktree and stage
+    /// it. Choosing a side that does not exist in the index deletes the file.
+    pub fn resolve_conflict(
+        &self,
+        worktree_path: &Path,
+        path: &str,
+        resolution: &ConflictResolution,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        let index = repo.index()?;
+        let conflict = index
+            .conflicts()?
+            .filter_map(Result::ok)
+            .find(|conflict| {
+                [&conflict.our, &conflict.their, &conflict.ancestor]
+                    .into_iter()
+                    .flatten()
+                    .any(|entry| entry.path == path.as_bytes())
+            })
+            .ok_or_else(|| {
+                GitServiceError::InvalidRepository(format!("'{path}' is not conflicted"))
+            })?;
+
+        let content = match resolution {
+            ConflictResolution::Ours => conflict.our.as_ref(),
+            ConflictResolution::Theirs => conflict.their.as_ref(),
+            ConflictResolution::Base => conflict.ancestor.as_ref(),
+            ConflictResolution::Custom { .. } | ConflictResolution::Delete => None,
+        }
+        .map(|entry| repo.find_blob(entry.id).map(|blob| blob.content().to_vec()))
+        .transpose()?;
+        let content = match resolution {
+            ConflictResolution::Custom { content } => Some(content.as_bytes().to_vec()),
+            _ => content,
+        };
+
+        let file_path = worktree_path.join(path);
+        match content {
+            Some(bytes) => {
+                if let Some(parent) = file_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&file_path, bytes)?;
+            }
+            None => {
+                if file_path.exists() {
+                    std::fs::remove_file(&file_path)?;
+                }
+            }
+        }
+
+        GitCli::new().stage_path(worktree_path, path)?;
+        Ok(())
+    }
+
+    /// Continue the interrupted rebase/merge/cherry-pick/revert once nothing is conflicted.
+    /// A rebase can stop again on a later commit; that surfaces as `MergeConflicts`.
+    pub fn continue_conflicted_operation(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Option<ConflictOp>, GitServiceError> {
+        let remaining = self.get_conflicted_files(worktree_path)?;
+        if !remaining.is_empty() {
+            return Err(GitServiceError::MergeConflicts(format!(
+                "{} file(s) are still conflicted: {}",
+                remaining.len(),
+                remaining.join(", ")
+            )));
+        }
+
+        let Some(op) = self.detect_conflict_op(worktree_path)? else {
+            return Ok(None);
+        };
+        self.ensure_cli_commit_identity(worktree_path)?;
+
+        let git = GitCli::new();
+        let result = match op {
+            ConflictOp::Rebase => git.continue_rebase(worktree_path),
+            ConflictOp::Merge => git.commit_merge(worktree_path),
+            ConflictOp::CherryPick => git.continue_cherry_pick(worktree_path),
+            ConflictOp::Revert => git.continue_revert(worktree_path),
+        };
+        match result {
+            Ok(()) => Ok(Some(op)),
+            Err(GitCliError::CommandFailed(stderr)) => {
+                let conflicts = self.get_conflicted_files(worktree_path).unwrap_or_default();
+                if conflicts.is_empty() {
+                    Err(GitServiceError::InvalidRepository(format!(
+                        "Failed to continue: {}",
+                        stderr.lines().next().unwrap_or("")
+                    )))
+                } else {
+                    Err(GitServiceError::MergeConflicts(format!(
+                        "Continuing stopped on new conflicts in: {}",
+                        conflicts.join(", ")
+                    )))
+                }
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Abort an in-progress rebase in this worktree (no-op if none).
     pub fn abort_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
         let git = GitCli::new();
@@ -1678,6 +2506,115 @@ impl GitService {
         }
     }
 
+    /// Whether every commit on `branch_name` is reachable from `target_branch_name`. A branch
+    /// without commits of its own counts as merged.
+    pub fn is_branch_merged(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        target_branch_name: &str,
+    ) -> Result<bool, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let branch_oid = Self::find_branch(&repo, branch_name)?
+            .get()
+            .peel_to_commit()?
+            .id();
+        let target_oid = Self::find_branch(&repo, target_branch_name)?
+            .get()
+            .peel_to_commit()?
+            .id();
+        Ok(branch_oid == target_oid || repo.graph_descendant_of(target_oid, branch_oid)?)
+    }
+
+    /// Remote that has a tracking branch for the local `branch_name`, preferring its upstream
+    pub fn find_remote_for_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        if let Ok(branch) = repo.find_branch(branch_name, BranchType::Local)
+            && let Some(refname) = branch.get().name()
+            && let Ok(remote) = repo.branch_upstream_remote(refname)
+            && let Some(remote) = remote.as_str()
+        {
+            return Ok(Some(remote.to_string()));
+        }
+
+        let remotes = repo.remotes()?;
+        Ok(remotes
+            .iter()
+            .flatten()
+            .find(|remote| {
+                repo.find_reference(&format!("refs/remotes/{remote}/{branch_name}"))
+                    .is_ok()
+            })
+            .map(str::to_string))
+    }
+
+    /// Delete a local branch. Fails if the branch is checked out in any worktree.
+    pub fn delete_local_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut branch = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?;
+        if branch.is_head() {
+            return Err(GitServiceError::InvalidRepository(format!(
+                "Branch '{branch_name}' is checked out in the main repository"
+            )));
+        }
+        branch.delete()?;
+        Ok(())
+    }
+
+    /// Hand a worktree created on a placeholder branch to an attempt: rename the branch to
+    /// `branch_name` and move the worktree to `new_path`. Returns where the worktree ended up,
+    /// which is its old location when git could not move it.
+    pub fn reassign_worktree(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        placeholder_branch: &str,
+        branch_name: &str,
+        new_path: &Path,
+    ) -> Result<PathBuf, GitServiceError> {
+        let git = GitCli::new();
+        git.rename_branch(worktree_path, placeholder_branch, branch_name)?;
+        match git.worktree_move(repo_path, worktree_path, new_path) {
+            Ok(()) => Ok(new_path.to_path_buf()),
+            Err(e) => {
+                tracing::warn!(
+                    "Keeping worktree at {} after failing to move it: {}",
+                    worktree_path.display(),
+                    e
+                );
+                Ok(worktree_path.to_path_buf())
+            }
+        }
+    }
+
+    /// Delete a branch on a remote using the user's git credentials, then drop the local
+    /// remote-tracking ref.
+    pub fn delete_remote_branch(
+        &self,
+        repo_path: &Path,
+        remote_name: &str,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        GitCli::new().delete_remote_branch(repo_path, remote_name, branch_name)?;
+        let repo = self.open_repo(repo_path)?;
+        if let Ok(mut tracking) =
+            repo.find_reference(&format!("refs/remotes/{remote_name}/{branch_name}"))
+        {
+            tracking.delete()?;
+        }
+        Ok(())
+    }
+
     /// Delete a file from the repository and commit the change
     pub fn delete_file_and_commit(
         &self,
@@ -1741,6 +2678,59 @@ impl GitService {
         }
     }
 
+    /// Resolve the branch the default remote's HEAD points at (e.g. `origin/HEAD -> origin/main`),
+    /// returned without the remote prefix.
+    pub fn get_remote_default_branch(
+        &self,
+        repo_path: &Path,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let remote_name = self.default_remote_name(&repo);
+        let head_ref = match repo.find_reference(&format!("refs/remotes/{remote_name}/HEAD")) {
+            Ok(reference) => reference,
+            Err(_) => return Ok(None),
+        };
+        let prefix = format!("refs/remotes/{remote_name}/");
+        Ok(head_ref
+            .symbolic_target()
+            .and_then(|target| target.strip_prefix(&prefix))
+            .map(|name| name.to_string()))
+    }
+
+    /// Commit time of the commit a tag points at, or None when the tag does not exist.
+    pub fn get_tag_commit_time(
+        &self,
+        repo_path: &Path,
+        tag: &str,
+    ) -> Result<Option<DateTime<Utc>>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let reference = match repo.find_reference(&format!("refs/tags/{tag}")) {
+            Ok(reference) => reference,
+            Err(_) => return Ok(None),
+        };
+        let commit = reference.peel_to_commit()?;
+        Ok(DateTime::from_timestamp(commit.time().seconds(), 0))
+    }
+
+    /// URL of the preferred remote, falling back to the repository's default remote.
+    pub fn get_remote_url(
+        &self,
+        repo_path: &Path,
+        preferred_remote: Option<&str>,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let remote_name = preferred_remote
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| self.default_remote_name(&repo));
+        let remote = repo.find_remote(&remote_name).map_err(|_| {
+            GitServiceError::InvalidRepository(format!("No '{remote_name}' remote found"))
+        })?;
+        remote
+            .url()
+            .map(|url| url.to_string())
+            .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))
+    }
+
     /// Extract GitHub owner and repo name from git repo path
     pub fn get_github_repo_info(
         &self,
@@ -1834,6 +2824,18 @@ impl GitService {
         branch_name: &str,
         remote_override: Option<&str>,
         github_token: &str,
+    ) -> Result<(), GitServiceError> {
+        self.push_with_token(worktree_path, branch_name, remote_override, github_token)
+    }
+
+    /// Push a branch over HTTPS using a personal access token. Works for any host that accepts the
+    /// token as a Basic auth password (GitHub, GitLab).
+    pub fn push_with_token(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        remote_override: Option<&str>,
+        token: &str,
     ) -> Result<(), GitServiceError> {
         let repo = Repository::open(worktree_path)?;
         self.check_worktree_clean(&repo)?;
@@ -1863,10 +2865,8 @@ impl GitService {
             .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))?;
         let https_url = self.convert_to_https_url(remote_url);
         let git_cli = GitCli::new();
-        if let Err(e) =
-            git_cli.push_with_token(worktree_path, &https_url, branch_name, github_token)
-        {
-            tracing::error!("Push to GitHub failed: {}", e);
+        if let Err(e) = git_cli.push_with_token(worktree_path, &https_url, branch_name, token) {
+            tracing::error!("Push to {} failed: {}", https_url, e);
             return Err(e.into());
         }
 
@@ -1886,6 +2886,33 @@ impl GitService {
         Ok(())
     }
 
+    /// Fetch the branch's upstream and push the branch when it is strictly ahead of it, so new
+    /// commits land on an existing pull request. A push that would be rejected as non-fast-forward
+    /// is not attempted.
+    pub fn push_branch_updates(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        token: &str,
+    ) -> Result<BranchPushOutcome, GitServiceError> {
+        let (ahead, behind) =
+            self.get_remote_branch_status(worktree_path, branch_name, None, token.to_string())?;
+        if ahead == 0 || behind > 0 {
+            return Ok(BranchPushOutcome {
+                pushed_commits: 0,
+                remote_commits_ahead: ahead,
+                remote_commits_behind: behind,
+            });
+        }
+
+        self.push_with_token(worktree_path, branch_name, None, token)?;
+        Ok(BranchPushOutcome {
+            pushed_commits: ahead,
+            remote_commits_ahead: 0,
+            remote_commits_behind: 0,
+        })
+    }
+
     pub fn convert_to_https_url(&self, url: &str) -> String {
         // Convert SSH URL to HTTPS URL if necessary
         let new_url = if url.starts_with("git@github.com:") {
@@ -1894,6 +2921,19 @@ impl GitService {
         } else if url.starts_with("ssh://git@github.com/") {
             // Convert ssh://git@github.com/owner/repo.git to https://github.com/owner/repo.git
             url.replace("ssh://git@github.com/", "https://github.com/")
+        } else if let Some((host, path)) = url
+            .strip_prefix("git@")
+            .and_then(|rest| rest.split_once(':'))
+        {
+            // Other hosts (e.g. GitLab): git@host:group/repo.git -> https://host/group/repo.git
+            format!("https://{host}/{path}")
+        } else if let Some((authority, path)) = url
+            .strip_prefix("ssh://git@")
+            .and_then(|rest| rest.split_once('/'))
+        {
+            // The SSH port does not apply to HTTPS
+            let host = authority.split(':').next().unwrap_or(authority);
+            format!("https://{host}/{path}")
         } else {
             url.to_string()
         };
@@ -2181,3 +3221,59 @@ impl GitService {
 //         assert_eq!(branch_name, "main");
 //     }
 // }
+
+/// Split file content on git conflict markers. Unterminated regions are ignored.
+pub fn parse_conflict_hunks(content: &str) -> Vec<ConflictHunk> {
+    enum Section {
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    fn is_marker(line: &str, marker: &str) -> bool {
+        line.strip_prefix(marker)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    }
+
+    let mut hunks = Vec::new();
+    let mut open: Option<(Section, ConflictHunk)> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let Some((section, hunk)) = open.as_mut() else {
+            if is_marker(line, "<<<<<<<") {
+                open = Some((
+                    Section::Ours,
+                    ConflictHunk {
+                        start_line: index + 1,
+                        ..Default::default()
+                    },
+                ));
+            }
+            continue;
+        };
+
+        match section {
+            Section::Ours if is_marker(line, "|||||||") => {
+                *section = Section::Base;
+                hunk.base = Some(String::new());
+            }
+            Section::Ours | Section::Base if line == "=======" => *section = Section::Theirs,
+            Section::Theirs if is_marker(line, ">>>>>>>") => {
+                if let Some((_, hunk)) = open.take() {
+                    hunks.push(hunk);
+                }
+            }
+            _ => {
+                let target = match section {
+                    Section::Ours => &mut hunk.ours,
+                    Section::Base => hunk.base.get_or_insert_with(String::new),
+                    Section::Theirs => &mut hunk.theirs,
+                };
+                target.push_str(line);
+                target.push('\n');
+            }
+        }
+    }
+
+    hunks
+}
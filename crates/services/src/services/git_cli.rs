@@ -126,12 +126,73 @@ impl GitCli {
         Ok(())
     }
 
+    /// Run `git -C <repo> worktree move <from> <to>`. Git refuses to move worktrees that contain
+    /// submodules.
+    pub fn worktree_move(
+        &self,
+        repo_path: &Path,
+        from: &Path,
+        to: &Path,
+    ) -> Result<(), GitCliError> {
+        self.ensure_available()?;
+        let args: Vec<OsString> = vec![
+            "worktree".into(),
+            "move".into(),
+            from.as_os_str().into(),
+            to.as_os_str().into(),
+        ];
+        self.git(repo_path, args)?;
+        Ok(())
+    }
+
+    /// Rename the branch checked out in `worktree_path`
+    pub fn rename_branch(
+        &self,
+        worktree_path: &Path,
+        from: &str,
+        to: &str,
+    ) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["branch", "-m", from, to])?;
+        Ok(())
+    }
+
     /// Prune stale worktree metadata
     pub fn worktree_prune(&self, repo_path: &Path) -> Result<(), GitCliError> {
         self.git(repo_path, ["worktree", "prune"])?;
         Ok(())
     }
 
+    /// Prune stale worktree metadata, then let git decide which maintenance tasks (gc, repack,
+    /// commit-graph, ...) are worth running. Falls back to `git gc --auto` on git versions without
+    /// `git maintenance`.
+    pub fn run_maintenance(&self, repo_path: &Path) -> Result<(), GitCliError> {
+        self.git(repo_path, ["worktree", "prune"])?;
+        if self
+            .git(repo_path, ["maintenance", "run", "--auto"])
+            .is_err()
+        {
+            self.git(repo_path, ["gc", "--auto"])?;
+        }
+        Ok(())
+    }
+
+    /// Initialize and check out all submodules, including nested ones
+    pub fn submodule_update(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(
+            worktree_path,
+            ["submodule", "update", "--init", "--recursive"],
+        )?;
+        Ok(())
+    }
+
+    /// Install the LFS filters for the repository and download and check out LFS objects for the
+    /// current branch. Requires the `git-lfs` extension to be installed.
+    pub fn lfs_pull(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["lfs", "install", "--local"])?;
+        self.git(worktree_path, ["lfs", "pull"])?;
+        Ok(())
+    }
+
     /// Return true if there are any changes in the working tree (staged or unstaged).
     pub fn has_changes(&self, worktree_path: &Path) -> Result<bool, GitCliError> {
         let out = self.git(worktree_path, ["status", "--porcelain"])?;
@@ -298,6 +359,31 @@ impl GitCli {
         self.git(worktree_path, ["commit", "-m", message])?;
         Ok(())
     }
+
+    /// Commit staged changes and sign the commit. `format` overrides `gpg.format` and `key`
+    /// overrides `user.signingkey` for this commit only.
+    pub fn commit_signed(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+        format: Option<&str>,
+        key: Option<&str>,
+    ) -> Result<(), GitCliError> {
+        let mut args: Vec<OsString> = Vec::new();
+        if let Some(format) = format {
+            args.push("-c".into());
+            args.push(format!("gpg.format={format}").into());
+        }
+        args.push("commit".into());
+        args.push(match key {
+            Some(key) => format!("--gpg-sign={key}").into(),
+            None => "--gpg-sign".into(),
+        });
+        args.push("-m".into());
+        args.push(message.into());
+        self.git(worktree_path, args)?;
+        Ok(())
+    }
     /// Fetch a branch to the given remote using an HTTPS token for authentication.
     pub fn fetch_with_token_and_refspec(
         &self,
@@ -355,6 +441,20 @@ impl GitCli {
         }
     }
 
+    /// Delete `branch` on the named remote with whatever credentials git is configured to use
+    pub fn delete_remote_branch(
+        &self,
+        repo_path: &Path,
+        remote_name: &str,
+        branch: &str,
+    ) -> Result<(), GitCliError> {
+        match self.git(repo_path, ["push", remote_name, "--delete", branch]) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
     // Parse `git diff --name-status` output into structured entries.
     // Handles rename/copy scores like `R100` by matching the first letter.
     fn parse_name_status(output: &str) -> Vec<StatusDiffEntry> {
@@ -538,6 +638,39 @@ impl GitCli {
         Ok(sha)
     }
 
+    /// Checkout base branch and create a merge commit for from_branch. Returns new HEAD sha.
+    pub fn merge_no_ff_commit(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--no-ff", "-m", message, from_branch])
+            .map(|_| ())?;
+        Ok(self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string())
+    }
+
+    /// Checkout base branch and fast-forward it to from_branch. Returns new HEAD sha.
+    pub fn merge_ff_only(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--ff-only", from_branch])
+            .map(|_| ())?;
+        Ok(self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string())
+    }
+
     /// Update a ref to a specific sha in the repo.
     pub fn update_ref(
         &self,
@@ -549,6 +682,77 @@ impl GitCli {
             .map(|_| ())
     }
 
+    /// Record uncommitted changes, untracked files included, as a commit on top of HEAD. A
+    /// scratch index is used so neither the worktree nor the real index is touched. Returns None
+    /// when there is nothing to record.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+    ) -> Result<Option<String>, GitCliError> {
+        let index_path = self.git(
+            worktree_path,
+            ["rev-parse", "--git-path", "gybe-snapshot-index"],
+        )?;
+        let index_path = worktree_path.join(index_path.trim());
+        let envs = [(
+            OsString::from("GIT_INDEX_FILE"),
+            index_path.clone().into_os_string(),
+        )];
+        let snapshot = (|| {
+            self.git_with_env(worktree_path, ["read-tree", "HEAD"], &envs)?;
+            self.git_with_env(worktree_path, ["add", "-A"], &envs)?;
+            let tree = self.git_with_env(worktree_path, ["write-tree"], &envs)?;
+            let head_tree = self.git(worktree_path, ["rev-parse", "HEAD^{tree}"])?;
+            if tree.trim() == head_tree.trim() {
+                return Ok(None);
+            }
+            let sha = self.git(
+                worktree_path,
+                ["commit-tree", tree.trim(), "-p", "HEAD", "-m", message],
+            )?;
+            Ok(Some(sha.trim().to_string()))
+        })();
+        let _ = std::fs::remove_file(&index_path);
+        snapshot
+    }
+
+    /// `(refname, sha, committer date, subject)` of the refs below `prefix`, newest first
+    pub fn list_refs(
+        &self,
+        repo_path: &Path,
+        prefix: &str,
+    ) -> Result<Vec<(String, String, String, String)>, GitCliError> {
+        let out = self.git(
+            repo_path,
+            [
+                "for-each-ref",
+                "--sort=-committerdate",
+                "--format=%(refname)%00%(objectname)%00%(committerdate:iso-strict)%00%(contents:subject)",
+                prefix,
+            ],
+        )?;
+        Ok(out
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, '\0');
+                Some((
+                    fields.next()?.to_string(),
+                    fields.next()?.to_string(),
+                    fields.next()?.to_string(),
+                    fields.next().unwrap_or_default().to_string(),
+                ))
+            })
+            .collect())
+    }
+
+    /// Apply the changes `sha` introduced over its parent to the worktree, leaving them staged
+    /// and uncommitted.
+    pub fn apply_commit_changes(&self, worktree_path: &Path, sha: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["cherry-pick", "--no-commit", sha])
+            .map(|_| ())
+    }
+
     pub fn abort_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
         if !self.is_merge_in_progress(worktree_path)? {
             return Ok(());
@@ -571,6 +775,49 @@ impl GitCli {
         self.git(worktree_path, ["revert", "--abort"]).map(|_| ())
     }
 
+    /// Stage a resolved path, including deletions, which clears its conflict entries.
+    pub fn stage_path(&self, worktree_path: &Path, path: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["add", "-A", "--", path])
+            .map(|_| ())
+    }
+
+    pub fn continue_rebase(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["rebase", "--continue"],
+            &Self::no_editor_env(),
+        )
+        .map(|_| ())
+    }
+
+    /// Conclude a merge whose conflicts were resolved, keeping the prepared message.
+    pub fn commit_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["commit", "--no-edit"],
+            &Self::no_editor_env(),
+        )
+        .map(|_| ())
+    }
+
+    pub fn continue_cherry_pick(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["cherry-pick", "--continue"],
+            &Self::no_editor_env(),
+        )
+        .map(|_| ())
+    }
+
+    pub fn continue_revert(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git_with_env(
+            worktree_path,
+            ["revert", "--continue"],
+            &Self::no_editor_env(),
+        )
+        .map(|_| ())
+    }
+
     /// List files currently in a conflicted (unmerged) state in the worktree.
     pub fn get_conflicted_files(&self, worktree_path: &Path) -> Result<Vec<String>, GitCliError> {
         // `--diff-filter=U` lists paths with unresolved conflicts
@@ -606,6 +853,11 @@ impl GitCli {
         }
     }
 
+    /// `--continue` opens the commit message editor; accept the prepared message instead.
+    fn no_editor_env() -> Vec<(OsString, OsString)> {
+        vec![(OsString::from("GIT_EDITOR"), OsString::from("true"))]
+    }
+
     fn build_auth_header(&self, token: &str) -> String {
         let auth_value = BASE64_STANDARD.encode(format!("x-access-token:{token}"));
         format!("Authorization: Basic {auth_value}")
@@ -0,0 +1,38 @@
+//! URL helpers shared by the self-hostable forge integrations (GitLab, Gitea).
+
+/// Host name of an instance URL, without scheme, port or path.
+pub fn host_of(base_url: &str) -> Option<&str> {
+    let rest = base_url
+        .split_once("://")
+        .map_or(base_url, |(_, rest)| rest);
+    let authority = rest.split('/').next()?;
+    authority.split(':').next().filter(|host| !host.is_empty())
+}
+
+/// Repository path (`owner/repo`, `group/sub/repo`) of an SSH or HTTPS remote URL, if the remote
+/// lives on the same host as `base_url`. Ports are ignored since SSH and HTTPS usually differ.
+pub fn repo_path_on_host(remote_url: &str, base_url: &str) -> Option<String> {
+    let host = host_of(base_url.trim_end_matches('/'))?;
+    let remote_url = remote_url.trim();
+
+    let path = if let Some(rest) = remote_url.strip_prefix("git@") {
+        rest.strip_prefix(host)?.strip_prefix(':')?
+    } else if let Some(rest) = remote_url
+        .strip_prefix("ssh://")
+        .or_else(|| remote_url.strip_prefix("https://"))
+        .or_else(|| remote_url.strip_prefix("http://"))
+    {
+        let (authority, path) = rest.split_once('/')?;
+        let authority = authority.rsplit('@').next().unwrap_or(authority);
+        let authority_host = authority.split(':').next().unwrap_or(authority);
+        if !authority_host.eq_ignore_ascii_case(host) {
+            return None;
+        }
+        path
+    } else {
+        return None;
+    };
+
+    let repo_path = path.trim_matches('/').trim_end_matches(".git");
+    repo_path.contains('/').then(|| repo_path.to_string())
+}
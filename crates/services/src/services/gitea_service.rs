@@ -0,0 +1,249 @@
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use thiserror::Error;
+use tracing::info;
+
+use crate::services::git_host::{host_of, repo_path_on_host};
+
+#[derive(Debug, Error)]
+pub enum GiteaServiceError {
+    #[error(transparent)]
+    Client(#[from] reqwest::Error),
+    #[error("Pull request error: {0}")]
+    PullRequest(String),
+    #[error("Gitea token is invalid or expired.")]
+    TokenInvalid,
+    #[error("Insufficient permissions")]
+    InsufficientPermissions,
+    #[error("Gitea repository not found or no access")]
+    RepoNotFoundOrNoAccess,
+}
+
+impl GiteaServiceError {
+    pub fn should_retry(&self) -> bool {
+        matches!(self, GiteaServiceError::Client(_))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GiteaRepoInfo {
+    /// Instance URL, e.g. `https://git.example.com`
+    pub base_url: String,
+    pub owner: String,
+    pub repo_name: String,
+}
+
+impl GiteaRepoInfo {
+    /// Parse an SSH or HTTPS remote URL on the instance behind `base_url`
+    pub fn from_remote_url(remote_url: &str, base_url: &str) -> Option<Self> {
+        let path = repo_path_on_host(remote_url, base_url)?;
+        let (owner, repo_name) = path.split_once('/')?;
+        if repo_name.contains('/') {
+            return None;
+        }
+        Some(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            owner: owner.to_string(),
+            repo_name: repo_name.to_string(),
+        })
+    }
+
+    /// Parse a PR web URL (`https://host/owner/repo/pulls/12`) on the instance behind `base_url`
+    /// into the repository and the PR number.
+    pub fn from_pull_request_url(url: &str, base_url: &str) -> Option<(Self, i64)> {
+        let base_url = base_url.trim_end_matches('/');
+        if host_of(url)? != host_of(base_url)? {
+            return None;
+        }
+        let (repo_url, rest) = url.split_once("/pulls/")?;
+        let number = rest
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()?;
+        let repo = Self::from_remote_url(repo_url, base_url)?;
+        Some((repo, number))
+    }
+
+    fn api_repo_url(&self) -> String {
+        format!(
+            "{}/api/v1/repos/{}/{}",
+            self.base_url, self.owner, self.repo_name
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateGiteaPrRequest {
+    pub title: String,
+    pub body: Option<String>,
+    pub head_branch: String,
+    pub base_branch: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestResponse {
+    number: i64,
+    html_url: String,
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    message: Option<String>,
+}
+
+/// Client for the Gitea REST API; Forgejo exposes the same endpoints.
+#[derive(Debug, Clone)]
+pub struct GiteaService {
+    client: Client,
+    token: String,
+}
+
+impl GiteaService {
+    pub fn new(token: &str) -> Result<Self, GiteaServiceError> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("vibe-kanban")
+            .build()?;
+        Ok(Self {
+            client,
+            token: token.to_string(),
+        })
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        request.header("Authorization", format!("token {}", self.token))
+    }
+
+    pub async fn create_pr(
+        &self,
+        repo_info: &GiteaRepoInfo,
+        request: &CreateGiteaPrRequest,
+    ) -> Result<PullRequestInfo, GiteaServiceError> {
+        let pr = (|| async {
+            let response = self
+                .authorized(
+                    self.client
+                        .post(format!("{}/pulls", repo_info.api_repo_url())),
+                )
+                .json(&serde_json::json!({
+                    "head": request.head_branch,
+                    "base": request.base_branch,
+                    "title": request.title,
+                    "body": request.body.as_deref().unwrap_or(""),
+                }))
+                .send()
+                .await?;
+            Self::check_status(response, "create pull request")
+                .await?
+                .json::<PullRequestResponse>()
+                .await
+                .map_err(GiteaServiceError::from)
+        })
+        .retry(Self::retry_policy())
+        .when(|e| e.should_retry())
+        .notify(Self::log_retry)
+        .await?;
+
+        info!(
+            "Created Gitea PR #{} for branch {} in {}/{}",
+            pr.number, request.head_branch, repo_info.owner, repo_info.repo_name
+        );
+        Ok(Self::map_pull_request(pr))
+    }
+
+    pub async fn update_pr_status(
+        &self,
+        repo_info: &GiteaRepoInfo,
+        pr_number: i64,
+    ) -> Result<PullRequestInfo, GiteaServiceError> {
+        (|| async {
+            let response = self
+                .authorized(
+                    self.client
+                        .get(format!("{}/pulls/{pr_number}", repo_info.api_repo_url())),
+                )
+                .send()
+                .await?;
+            Self::check_status(response, &format!("get pull request #{pr_number}"))
+                .await?
+                .json::<PullRequestResponse>()
+                .await
+                .map(Self::map_pull_request)
+                .map_err(GiteaServiceError::from)
+        })
+        .retry(Self::retry_policy())
+        .when(|e| e.should_retry())
+        .notify(Self::log_retry)
+        .await
+    }
+
+    fn retry_policy() -> ExponentialBuilder {
+        ExponentialBuilder::default()
+            .with_min_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(30))
+            .with_max_times(3)
+            .with_jitter()
+    }
+
+    fn log_retry(err: &GiteaServiceError, dur: Duration) {
+        tracing::warn!(
+            "Gitea API call failed, retrying after {:.2}s: {}",
+            dur.as_secs_f64(),
+            err
+        );
+    }
+
+    async fn check_status(
+        response: reqwest::Response,
+        action: &str,
+    ) -> Result<reqwest::Response, GiteaServiceError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        match status {
+            StatusCode::UNAUTHORIZED => Err(GiteaServiceError::TokenInvalid),
+            StatusCode::FORBIDDEN => Err(GiteaServiceError::InsufficientPermissions),
+            StatusCode::NOT_FOUND => Err(GiteaServiceError::RepoNotFoundOrNoAccess),
+            _ => {
+                let detail = response
+                    .json::<ErrorResponse>()
+                    .await
+                    .ok()
+                    .and_then(|body| body.message)
+                    .unwrap_or_else(|| status.to_string());
+                Err(GiteaServiceError::PullRequest(format!(
+                    "Failed to {action}: {detail}"
+                )))
+            }
+        }
+    }
+
+    fn map_pull_request(pr: PullRequestResponse) -> PullRequestInfo {
+        let status = match pr.state.as_str() {
+            "open" => MergeStatus::Open,
+            "closed" if pr.merged => MergeStatus::Merged,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+
+        PullRequestInfo {
+            number: pr.number,
+            url: pr.html_url,
+            status,
+            merged_at: pr.merged_at,
+            merge_commit_sha: pr.merge_commit_sha,
+        }
+    }
+}
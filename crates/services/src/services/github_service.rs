@@ -1,7 +1,12 @@
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use backon::{ExponentialBuilder, Retryable};
-use db::models::merge::{MergeStatus, PullRequestInfo};
+use chrono::{DateTime, Utc};
+use db::models::{
+    issue_sync,
+    merge::{MergeStatus, PullRequestInfo},
+    pr_check_status::{PrCheckRun, PrCheckState, PrReviewState},
+};
 use octocrab::{Octocrab, OctocrabBuilder, models::IssueState};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -9,7 +14,11 @@ use thiserror::Error;
 use tracing::info;
 use ts_rs::TS;
 
-use crate::services::{git::GitServiceError, git_cli::GitCliError};
+use crate::services::{
+    git::GitServiceError,
+    git_cli::GitCliError,
+    review_comments::{ReviewThread, ReviewThreadComment},
+};
 
 #[derive(Debug, Error, Serialize, Deserialize, TS)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -139,6 +148,263 @@ pub struct RepositoryInfo {
     pub private: bool,
 }
 
+#[derive(Debug, Deserialize)]
+struct ReviewUser {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Review {
+    user: Option<ReviewUser>,
+    state: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRun {
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+    details_url: Option<String>,
+    html_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRunList {
+    check_runs: Vec<CheckRun>,
+}
+
+/// An issue as listed by the REST API, which returns pull requests as issues too
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubIssue {
+    pub number: i64,
+    pub title: String,
+    pub body: Option<String>,
+    pub state: issue_sync::IssueState,
+    /// `completed`, `not_planned` or `reopened`
+    pub state_reason: Option<String>,
+    pub html_url: String,
+    pub updated_at: DateTime<Utc>,
+    /// Present when the issue is a pull request
+    pub pull_request: Option<serde_json::Value>,
+}
+
+impl GitHubIssue {
+    pub fn is_pull_request(&self) -> bool {
+        self.pull_request.is_some()
+    }
+}
+
+const ISSUES_PER_PAGE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct GraphQlNodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlReviewThread {
+    is_resolved: bool,
+    is_outdated: bool,
+    path: String,
+    line: Option<i64>,
+    comments: GraphQlNodes<GraphQlReviewComment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlAuthor {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlReviewComment {
+    author: Option<GraphQlAuthor>,
+    body: String,
+    url: Option<String>,
+}
+
+const REVIEW_THREADS_QUERY: &str = r#"
+query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        nodes {
+          isResolved
+          isOutdated
+          path
+          line
+          comments(first: 50) {
+            nodes { author { login } body url }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+/// Overall decision from a PR's reviews, oldest first as returned by the API. Each reviewer's
+/// latest approval or change request counts; plain comments never override an earlier decision.
+pub fn review_state_from_reviews<'a>(
+    reviews: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> PrReviewState {
+    let mut decisions: HashMap<&str, PrReviewState> = HashMap::new();
+    let mut commented = false;
+    for (reviewer, state) in reviews {
+        match state {
+            "APPROVED" => {
+                decisions.insert(reviewer, PrReviewState::Approved);
+            }
+            "CHANGES_REQUESTED" => {
+                decisions.insert(reviewer, PrReviewState::ChangesRequested);
+            }
+            // A dismissed review no longer counts towards the decision
+            "DISMISSED" => {
+                decisions.remove(reviewer);
+            }
+            "COMMENTED" => commented = true,
+            _ => {}
+        }
+    }
+
+    if decisions
+        .values()
+        .any(|d| *d == PrReviewState::ChangesRequested)
+    {
+        PrReviewState::ChangesRequested
+    } else if !decisions.is_empty() {
+        PrReviewState::Approved
+    } else if commented {
+        PrReviewState::Commented
+    } else {
+        PrReviewState::ReviewRequired
+    }
+}
+
+/// Map a check run's `status`/`conclusion` pair onto a check state
+pub fn check_state_from_run(status: &str, conclusion: Option<&str>) -> PrCheckState {
+    if status != "completed" {
+        return PrCheckState::Pending;
+    }
+    match conclusion {
+        Some("success") => PrCheckState::Success,
+        Some("neutral") | Some("skipped") => PrCheckState::Neutral,
+        Some(_) => PrCheckState::Failure,
+        None => PrCheckState::Pending,
+    }
+}
+
+/// Protection on a branch as far as the token can see it: classic branch protection and the
+/// repository rulesets that apply to the branch. Required reviews of classic protection are only
+/// visible to admins, so `protected` may be set without any known requirement.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, TS)]
+pub struct BranchProtection {
+    pub branch: String,
+    pub protected: bool,
+    /// Changes must land through a pull request
+    pub requires_pull_request: bool,
+    pub required_approving_reviews: u32,
+    /// Status check contexts that must pass before merging
+    pub required_status_checks: Vec<String>,
+}
+
+impl BranchProtection {
+    /// Whether pushing a local merge straight to the branch would bypass required reviews or
+    /// status checks
+    pub fn blocks_direct_merge(&self) -> bool {
+        self.requires_pull_request
+            || self.required_approving_reviews > 0
+            || !self.required_status_checks.is_empty()
+    }
+
+    pub fn describe(&self) -> String {
+        let mut requirements = Vec::new();
+        if self.required_approving_reviews > 0 {
+            requirements.push(format!(
+                "{} approving review(s)",
+                self.required_approving_reviews
+            ));
+        } else if self.requires_pull_request {
+            requirements.push("a pull request".to_string());
+        }
+        if !self.required_status_checks.is_empty() {
+            requirements.push(format!(
+                "passing checks ({})",
+                self.required_status_checks.join(", ")
+            ));
+        }
+        if requirements.is_empty() {
+            format!("Branch '{}' is protected", self.branch)
+        } else {
+            format!(
+                "Branch '{}' is protected and requires {}",
+                self.branch,
+                requirements.join(" and ")
+            )
+        }
+    }
+}
+
+/// Combine the `GET /branches/{branch}` response with the rules from `GET /rules/branches/{branch}`.
+/// Returns None when neither reports any protection.
+pub fn branch_protection_from_api(
+    branch: &str,
+    details: &serde_json::Value,
+    rules: &serde_json::Value,
+) -> Option<BranchProtection> {
+    let classic_checks = &details["protection"]["required_status_checks"];
+    let rules = rules.as_array().map(Vec::as_slice).unwrap_or_default();
+    let mut protection = BranchProtection {
+        branch: branch.to_string(),
+        protected: details["protected"].as_bool().unwrap_or(false) || !rules.is_empty(),
+        ..Default::default()
+    };
+    let mut contexts: Vec<&str> = classic_checks["contexts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|context| context.as_str())
+        .chain(
+            classic_checks["checks"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|check| check["context"].as_str()),
+        )
+        .collect();
+
+    for rule in rules {
+        match rule["type"].as_str() {
+            Some("required_status_checks") => contexts.extend(
+                rule["parameters"]["required_status_checks"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|check| check["context"].as_str()),
+            ),
+            Some("pull_request") => {
+                protection.requires_pull_request = true;
+                let reviews = rule["parameters"]["required_approving_review_count"]
+                    .as_u64()
+                    .unwrap_or(0) as u32;
+                protection.required_approving_reviews =
+                    protection.required_approving_reviews.max(reviews);
+            }
+            _ => {}
+        }
+    }
+    for context in contexts {
+        if !protection
+            .required_status_checks
+            .iter()
+            .any(|c| c == context)
+        {
+            protection.required_status_checks.push(context.to_string());
+        }
+    }
+
+    protection.protected.then_some(protection)
+}
+
 #[derive(Debug, Clone)]
 pub struct GitHubService {
     client: Octocrab,
@@ -309,6 +575,313 @@ impl GitHubService {
         .await
     }
 
+    /// Fetch the review decision and the check runs of the PR's head commit
+    pub async fn fetch_pr_checks(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+    ) -> Result<(PrReviewState, Vec<PrCheckRun>), GitHubServiceError> {
+        (|| async { self.fetch_pr_checks_internal(repo_info, pr_number).await })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e| e.should_retry())
+            .notify(|err: &GitHubServiceError, dur: Duration| {
+                tracing::warn!(
+                    "GitHub API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            })
+            .await
+    }
+
+    async fn fetch_pr_checks_internal(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+    ) -> Result<(PrReviewState, Vec<PrCheckRun>), GitHubServiceError> {
+        let map_err = |err: octocrab::Error| match GitHubServiceError::from(err) {
+            GitHubServiceError::Client(source) => GitHubServiceError::PullRequest(format!(
+                "Failed to get checks for PR #{pr_number}: {source}",
+            )),
+            other => other,
+        };
+        let base = format!("/repos/{}/{}", repo_info.owner, repo_info.repo_name);
+
+        let pr = self
+            .client
+            .pulls(&repo_info.owner, &repo_info.repo_name)
+            .get(pr_number as u64)
+            .await
+            .map_err(map_err)?;
+        let reviews: Vec<Review> = self
+            .client
+            .get(
+                format!("{base}/pulls/{pr_number}/reviews"),
+                Some(&[("per_page", "100")]),
+            )
+            .await
+            .map_err(map_err)?;
+        let check_runs: CheckRunList = self
+            .client
+            .get(
+                format!("{base}/commits/{}/check-runs", pr.head.sha),
+                Some(&[("per_page", "100")]),
+            )
+            .await
+            .map_err(map_err)?;
+
+        let review_state = review_state_from_reviews(reviews.iter().filter_map(|review| {
+            review
+                .user
+                .as_ref()
+                .map(|user| (user.login.as_str(), review.state.as_str()))
+        }));
+        let checks = check_runs
+            .check_runs
+            .into_iter()
+            .map(|run| PrCheckRun {
+                state: check_state_from_run(&run.status, run.conclusion.as_deref()),
+                name: run.name,
+                details_url: run.html_url.or(run.details_url),
+            })
+            .collect();
+        Ok((review_state, checks))
+    }
+
+    /// Protection rules that apply to `branch`; None when the branch is unprotected or does not
+    /// exist on GitHub
+    pub async fn fetch_branch_protection(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        branch: &str,
+    ) -> Result<Option<BranchProtection>, GitHubServiceError> {
+        (|| async {
+            self.fetch_branch_protection_internal(repo_info, branch)
+                .await
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e| e.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn fetch_branch_protection_internal(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        branch: &str,
+    ) -> Result<Option<BranchProtection>, GitHubServiceError> {
+        let base = format!("/repos/{}/{}", repo_info.owner, repo_info.repo_name);
+        let details: serde_json::Value = match self
+            .client
+            .get(format!("{base}/branches/{branch}"), None::<&()>)
+            .await
+        {
+            Ok(details) => details,
+            Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+                return Ok(None);
+            }
+            Err(err) => {
+                return Err(match GitHubServiceError::from(err) {
+                    GitHubServiceError::Client(source) => GitHubServiceError::Branch(format!(
+                        "Failed to get branch '{branch}': {source}"
+                    )),
+                    other => other,
+                });
+            }
+        };
+        // Rulesets are optional; older GitHub Enterprise servers do not have the endpoint
+        let rules: serde_json::Value = self
+            .client
+            .get(
+                format!("{base}/rules/branches/{branch}"),
+                Some(&[("per_page", "100")]),
+            )
+            .await
+            .unwrap_or_default();
+        Ok(branch_protection_from_api(branch, &details, &rules))
+    }
+
+    /// Review threads of a PR that have not been resolved. The REST API has no notion of resolved
+    /// threads, so this goes through GraphQL.
+    pub async fn fetch_unresolved_review_threads(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+    ) -> Result<Vec<ReviewThread>, GitHubServiceError> {
+        let response: serde_json::Value = self
+            .client
+            .graphql(&serde_json::json!({
+                "query": REVIEW_THREADS_QUERY,
+                "variables": {
+                    "owner": repo_info.owner,
+                    "repo": repo_info.repo_name,
+                    "number": pr_number,
+                },
+            }))
+            .await
+            .map_err(|err| match GitHubServiceError::from(err) {
+                GitHubServiceError::Client(source) => GitHubServiceError::PullRequest(format!(
+                    "Failed to get review comments for PR #{pr_number}: {source}",
+                )),
+                other => other,
+            })?;
+
+        let threads = response
+            .pointer("/data/repository/pullRequest/reviewThreads/nodes")
+            .cloned()
+            .ok_or_else(|| {
+                GitHubServiceError::PullRequest(format!(
+                    "Failed to get review comments for PR #{pr_number}: {}",
+                    response
+                        .pointer("/errors/0/message")
+                        .and_then(|m| m.as_str())
+                        .unwrap_or("unexpected response")
+                ))
+            })?;
+        let threads: Vec<GraphQlReviewThread> = serde_json::from_value(threads).map_err(|e| {
+            GitHubServiceError::PullRequest(format!("Unexpected review thread format: {e}"))
+        })?;
+
+        Ok(threads
+            .into_iter()
+            .filter(|thread| !thread.is_resolved)
+            .map(|thread| ReviewThread {
+                path: thread.path,
+                line: thread.line,
+                outdated: thread.is_outdated,
+                comments: thread
+                    .comments
+                    .nodes
+                    .into_iter()
+                    .map(|comment| ReviewThreadComment {
+                        author: comment.author.map(|a| a.login),
+                        body: comment.body,
+                        url: comment.url,
+                    })
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Issues and pull requests updated at or after `since` (everything when None), least
+    /// recently updated first, up to about `max_issues`
+    pub async fn list_issues(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        since: Option<DateTime<Utc>>,
+        max_issues: usize,
+    ) -> Result<Vec<GitHubIssue>, GitHubServiceError> {
+        (|| async {
+            self.list_issues_internal(repo_info, since, max_issues)
+                .await
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e| e.should_retry())
+        .notify(|err: &GitHubServiceError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn list_issues_internal(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        since: Option<DateTime<Utc>>,
+        max_issues: usize,
+    ) -> Result<Vec<GitHubIssue>, GitHubServiceError> {
+        let map_err = |err: octocrab::Error| match GitHubServiceError::from(err) {
+            GitHubServiceError::Client(source) => GitHubServiceError::Repository(format!(
+                "Failed to list issues of {}/{}: {source}",
+                repo_info.owner, repo_info.repo_name
+            )),
+            other => other,
+        };
+        let route = format!("/repos/{}/{}/issues", repo_info.owner, repo_info.repo_name);
+        let mut issues = Vec::new();
+        for page in 1..=max_issues.div_ceil(ISSUES_PER_PAGE) {
+            let mut params = vec![
+                ("state", "all".to_string()),
+                ("sort", "updated".to_string()),
+                ("direction", "asc".to_string()),
+                ("per_page", ISSUES_PER_PAGE.to_string()),
+                ("page", page.to_string()),
+            ];
+            if let Some(since) = since {
+                params.push(("since", since.to_rfc3339()));
+            }
+            let batch: Vec<GitHubIssue> = self
+                .client
+                .get(&route, Some(&params))
+                .await
+                .map_err(map_err)?;
+            let last_page = batch.len() < ISSUES_PER_PAGE;
+            issues.extend(batch);
+            if last_page {
+                break;
+            }
+        }
+        Ok(issues)
+    }
+
+    /// Close or reopen an issue. `state_reason` is `completed` or `not_planned` when closing.
+    pub async fn set_issue_state(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        issue_number: i64,
+        state: issue_sync::IssueState,
+        state_reason: Option<&str>,
+    ) -> Result<(), GitHubServiceError> {
+        let mut body = serde_json::json!({ "state": state });
+        if let Some(reason) = state_reason {
+            body["state_reason"] = serde_json::Value::from(reason);
+        }
+        let _: serde_json::Value = self
+            .client
+            .patch(
+                format!(
+                    "/repos/{}/{}/issues/{issue_number}",
+                    repo_info.owner, repo_info.repo_name
+                ),
+                Some(&body),
+            )
+            .await
+            .map_err(|err| match GitHubServiceError::from(err) {
+                GitHubServiceError::Client(source) => GitHubServiceError::Repository(format!(
+                    "Failed to update issue #{issue_number}: {source}"
+                )),
+                other => other,
+            })?;
+        Ok(())
+    }
+
     fn map_pull_request(pr: octocrab::models::pulls::PullRequest) -> PullRequestInfo {
         let state = match pr.state {
             Some(IssueState::Open) => MergeStatus::Open,
@@ -0,0 +1,411 @@
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use chrono::{DateTime, Utc};
+use db::models::{
+    issue_sync::IssueState,
+    merge::{MergeStatus, PullRequestInfo},
+};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::info;
+use ts_rs::TS;
+
+use crate::services::{git::GitServiceError, git_cli::GitCliError, git_host::repo_path_on_host};
+
+#[derive(Debug, Error, Serialize, Deserialize, TS)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[ts(use_ts_enum)]
+pub enum GitLabServiceError {
+    #[ts(skip)]
+    #[serde(skip)]
+    #[error(transparent)]
+    Client(reqwest::Error),
+    #[ts(skip)]
+    #[error("Repository error: {0}")]
+    Repository(String),
+    #[ts(skip)]
+    #[error("Merge request error: {0}")]
+    MergeRequest(String),
+    #[error("GitLab token is invalid or expired.")]
+    TokenInvalid,
+    #[error("Insufficient permissions")]
+    InsufficientPermissions,
+    #[error("GitLab project not found or no access")]
+    RepoNotFoundOrNoAccess,
+    #[ts(skip)]
+    #[serde(skip)]
+    #[error(transparent)]
+    GitService(GitServiceError),
+}
+
+impl From<reqwest::Error> for GitLabServiceError {
+    fn from(err: reqwest::Error) -> Self {
+        GitLabServiceError::Client(err)
+    }
+}
+
+impl From<GitServiceError> for GitLabServiceError {
+    fn from(error: GitServiceError) -> Self {
+        match error {
+            GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => Self::TokenInvalid,
+            GitServiceError::GitCLI(GitCliError::CommandFailed(msg)) => {
+                let lower = msg.to_ascii_lowercase();
+                if lower.contains("the requested url returned error: 403") {
+                    Self::InsufficientPermissions
+                } else if lower.contains("the requested url returned error: 404") {
+                    Self::RepoNotFoundOrNoAccess
+                } else {
+                    Self::GitService(GitServiceError::GitCLI(GitCliError::CommandFailed(msg)))
+                }
+            }
+            other => Self::GitService(other),
+        }
+    }
+}
+
+impl GitLabServiceError {
+    pub fn is_api_data(&self) -> bool {
+        matches!(
+            self,
+            GitLabServiceError::TokenInvalid
+                | GitLabServiceError::InsufficientPermissions
+                | GitLabServiceError::RepoNotFoundOrNoAccess
+        )
+    }
+
+    pub fn should_retry(&self) -> bool {
+        !self.is_api_data()
+    }
+}
+
+/// A project on a GitLab instance, identified by its full namespace path (`group/sub/project`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitLabRepoInfo {
+    /// Scheme and host of the instance, e.g. `https://gitlab.com`
+    pub base_url: String,
+    pub project_path: String,
+}
+
+impl GitLabRepoInfo {
+    /// Parse an SSH or HTTPS remote URL that points at the instance behind `base_url`. Returns
+    /// None for remotes on other hosts.
+    pub fn from_remote_url(remote_url: &str, base_url: &str) -> Option<Self> {
+        Some(Self {
+            project_path: repo_path_on_host(remote_url, base_url)?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Parse a merge request web URL (`https://host/group/project/-/merge_requests/12`) into the
+    /// project and the MR iid.
+    pub fn from_merge_request_url(url: &str) -> Option<(Self, i64)> {
+        let (project_url, rest) = url.split_once("/-/merge_requests/")?;
+        let iid = rest
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()?;
+        let scheme_end = project_url.find("://")? + 3;
+        let (authority, project_path) = project_url[scheme_end..].split_once('/')?;
+        Some((
+            Self {
+                base_url: format!("{}{}", &project_url[..scheme_end], authority),
+                project_path: project_path.trim_matches('/').to_string(),
+            },
+            iid,
+        ))
+    }
+
+    fn api_project_url(&self) -> String {
+        format!(
+            "{}/api/v4/projects/{}",
+            self.base_url,
+            self.project_path.replace('/', "%2F")
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateMrRequest {
+    pub title: String,
+    pub description: Option<String>,
+    pub source_branch: String,
+    pub target_branch: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MergeRequestResponse {
+    iid: i64,
+    web_url: String,
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    squash_commit_sha: Option<String>,
+}
+
+/// A project issue as listed by the REST API
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabIssue {
+    /// Number of the issue within its project
+    pub iid: i64,
+    pub title: String,
+    pub description: Option<String>,
+    /// `opened` or `closed`
+    pub state: String,
+    pub web_url: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl GitLabIssue {
+    pub fn issue_state(&self) -> IssueState {
+        if self.state == "closed" {
+            IssueState::Closed
+        } else {
+            IssueState::Open
+        }
+    }
+}
+
+const ISSUES_PER_PAGE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    message: Option<serde_json::Value>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GitLabService {
+    client: Client,
+    token: String,
+}
+
+impl GitLabService {
+    pub fn new(gitlab_token: &str) -> Result<Self, GitLabServiceError> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("vibe-kanban")
+            .build()?;
+        Ok(Self {
+            client,
+            token: gitlab_token.to_string(),
+        })
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        request.header("PRIVATE-TOKEN", &self.token)
+    }
+
+    pub async fn check_token(&self, base_url: &str) -> Result<(), GitLabServiceError> {
+        let response = self
+            .authorized(
+                self.client
+                    .get(format!("{}/api/v4/user", base_url.trim_end_matches('/'))),
+            )
+            .send()
+            .await?;
+        Self::check_status(response, "check token").await?;
+        Ok(())
+    }
+
+    /// Create a merge request from `source_branch` into `target_branch`
+    pub async fn create_mr(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        request: &CreateMrRequest,
+    ) -> Result<PullRequestInfo, GitLabServiceError> {
+        let mr = (|| async {
+            let response = self
+                .authorized(
+                    self.client
+                        .post(format!("{}/merge_requests", repo_info.api_project_url())),
+                )
+                .json(&serde_json::json!({
+                    "source_branch": request.source_branch,
+                    "target_branch": request.target_branch,
+                    "title": request.title,
+                    "description": request.description.as_deref().unwrap_or(""),
+                }))
+                .send()
+                .await?;
+            Self::check_status(response, "create merge request")
+                .await?
+                .json::<MergeRequestResponse>()
+                .await
+                .map_err(GitLabServiceError::from)
+        })
+        .retry(Self::retry_policy())
+        .when(|e| e.should_retry())
+        .notify(Self::log_retry)
+        .await?;
+
+        info!(
+            "Created GitLab MR !{} for branch {} in {}",
+            mr.iid, request.source_branch, repo_info.project_path
+        );
+        Ok(Self::map_merge_request(mr))
+    }
+
+    /// Fetch the current state of a merge request
+    pub async fn update_mr_status(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        mr_iid: i64,
+    ) -> Result<PullRequestInfo, GitLabServiceError> {
+        (|| async {
+            let response = self
+                .authorized(self.client.get(format!(
+                    "{}/merge_requests/{mr_iid}",
+                    repo_info.api_project_url()
+                )))
+                .send()
+                .await?;
+            Self::check_status(response, &format!("get merge request !{mr_iid}"))
+                .await?
+                .json::<MergeRequestResponse>()
+                .await
+                .map(Self::map_merge_request)
+                .map_err(GitLabServiceError::from)
+        })
+        .retry(Self::retry_policy())
+        .when(|e| e.should_retry())
+        .notify(Self::log_retry)
+        .await
+    }
+
+    /// Issues updated at or after `since` (everything when None), least recently updated first,
+    /// up to about `max_issues`
+    pub async fn list_issues(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        since: Option<DateTime<Utc>>,
+        max_issues: usize,
+    ) -> Result<Vec<GitLabIssue>, GitLabServiceError> {
+        let mut issues = Vec::new();
+        for page in 1..=max_issues.div_ceil(ISSUES_PER_PAGE) {
+            let mut params = vec![
+                ("state", "all".to_string()),
+                ("order_by", "updated_at".to_string()),
+                ("sort", "asc".to_string()),
+                ("per_page", ISSUES_PER_PAGE.to_string()),
+                ("page", page.to_string()),
+            ];
+            if let Some(since) = since {
+                params.push(("updated_after", since.to_rfc3339()));
+            }
+            let batch = (|| async {
+                let response = self
+                    .authorized(
+                        self.client
+                            .get(format!("{}/issues", repo_info.api_project_url()))
+                            .query(&params),
+                    )
+                    .send()
+                    .await?;
+                Self::check_status(response, "list issues")
+                    .await?
+                    .json::<Vec<GitLabIssue>>()
+                    .await
+                    .map_err(GitLabServiceError::from)
+            })
+            .retry(Self::retry_policy())
+            .when(|e| e.should_retry())
+            .notify(Self::log_retry)
+            .await?;
+            let last_page = batch.len() < ISSUES_PER_PAGE;
+            issues.extend(batch);
+            if last_page {
+                break;
+            }
+        }
+        Ok(issues)
+    }
+
+    /// Close or reopen an issue
+    pub async fn set_issue_state(
+        &self,
+        repo_info: &GitLabRepoInfo,
+        issue_iid: i64,
+        state: IssueState,
+    ) -> Result<(), GitLabServiceError> {
+        let state_event = match state {
+            IssueState::Open => "reopen",
+            IssueState::Closed => "close",
+        };
+        let response = self
+            .authorized(self.client.put(format!(
+                "{}/issues/{issue_iid}",
+                repo_info.api_project_url()
+            )))
+            .json(&serde_json::json!({ "state_event": state_event }))
+            .send()
+            .await?;
+        Self::check_status(response, &format!("update issue #{issue_iid}")).await?;
+        Ok(())
+    }
+
+    fn retry_policy() -> ExponentialBuilder {
+        ExponentialBuilder::default()
+            .with_min_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(30))
+            .with_max_times(3)
+            .with_jitter()
+    }
+
+    fn log_retry(err: &GitLabServiceError, dur: Duration) {
+        tracing::warn!(
+            "GitLab API call failed, retrying after {:.2}s: {}",
+            dur.as_secs_f64(),
+            err
+        );
+    }
+
+    async fn check_status(
+        response: reqwest::Response,
+        action: &str,
+    ) -> Result<reqwest::Response, GitLabServiceError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        match status {
+            StatusCode::UNAUTHORIZED => Err(GitLabServiceError::TokenInvalid),
+            StatusCode::FORBIDDEN => Err(GitLabServiceError::InsufficientPermissions),
+            StatusCode::NOT_FOUND => Err(GitLabServiceError::RepoNotFoundOrNoAccess),
+            _ => {
+                let detail = match response.json::<ErrorResponse>().await {
+                    Ok(ErrorResponse {
+                        message: Some(message),
+                        ..
+                    }) => message.to_string(),
+                    Ok(ErrorResponse {
+                        error: Some(error), ..
+                    }) => error,
+                    _ => status.to_string(),
+                };
+                Err(GitLabServiceError::MergeRequest(format!(
+                    "Failed to {action}: {detail}"
+                )))
+            }
+        }
+    }
+
+    fn map_merge_request(mr: MergeRequestResponse) -> PullRequestInfo {
+        let status = match mr.state.as_str() {
+            "opened" | "locked" => MergeStatus::Open,
+            "merged" => MergeStatus::Merged,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+
+        PullRequestInfo {
+            number: mr.iid,
+            url: mr.web_url,
+            status,
+            merged_at: mr.merged_at,
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use db::models::{executor_session::ExecutorSession, task::Task};
+use executors::executors::BaseCodingAgent;
+
+/// Only the most recent turns are replayed; older ones are summarised by the worktree itself
+pub const MAX_HANDOFF_TURNS: usize = 5;
+const MAX_TURN_CHARS: usize = 2000;
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}...", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+/// Build the prompt that starts a fresh session with `to` in an attempt previously driven by
+/// `from`. The previous agent's prompts and final replies are replayed so the new agent knows what
+/// was asked, what was done and where it stopped.
+pub fn build_handoff_prompt(
+    task: &Task,
+    branch: &str,
+    from: &BaseCodingAgent,
+    sessions: &[ExecutorSession],
+    instructions: Option<&str>,
+) -> String {
+    let mut out = format!(
+        "You are taking over a task that another coding agent ({from}) was working on. Its work so \
+         far is already in this worktree on branch `{branch}`; review the current state of the \
+         code (for example with `git status`, `git diff` and `git log`) before making changes.\n\n\
+         ## Task\n\n{}\n",
+        task.title.trim()
+    );
+    if let Some(description) = task.description.as_deref().map(str::trim)
+        && !description.is_empty()
+    {
+        out.push_str(&format!("\n{description}\n"));
+    }
+
+    let turns: Vec<&ExecutorSession> = sessions
+        .iter()
+        .filter(|s| s.prompt.is_some() || s.summary.is_some())
+        .collect();
+    if !turns.is_empty() {
+        out.push_str("\n## Previous session\n");
+        let skipped = turns.len().saturating_sub(MAX_HANDOFF_TURNS);
+        if skipped > 0 {
+            out.push_str(&format!("\n({skipped} earlier turns omitted)\n"));
+        }
+        for (i, session) in turns.iter().enumerate().skip(skipped) {
+            out.push_str(&format!("\n### Turn {}\n", i + 1));
+            if let Some(prompt) = &session.prompt {
+                out.push_str(&format!(
+                    "\nRequest:\n\n{}\n",
+                    truncate(prompt, MAX_TURN_CHARS)
+                ));
+            }
+            match &session.summary {
+                Some(summary) => out.push_str(&format!(
+                    "\nAgent's final reply:\n\n{}\n",
+                    truncate(summary, MAX_TURN_CHARS)
+                )),
+                None => out.push_str("\nThe agent stopped before replying.\n"),
+            }
+        }
+    }
+
+    out.push_str("\n## Next step\n\n");
+    match instructions.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(instructions) => out.push_str(instructions),
+        None => out.push_str(
+            "Continue the task from where the previous agent stopped and finish any remaining work.",
+        ),
+    }
+    out.push('\n');
+    out
+}
@@ -0,0 +1,214 @@
+//! The issue trackers project tasks can be synced with. Each one lists and closes issues through
+//! [`IssueProvider`], so the sync itself works the same for GitHub and GitLab.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::issue_sync::{IssueState, IssueSync, IssueTracker};
+use thiserror::Error;
+
+use crate::services::{
+    config::Config,
+    github_service::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+    gitlab_service::{GitLabRepoInfo, GitLabService, GitLabServiceError},
+};
+
+/// Upper bound on issues fetched by one listing; the next sync continues after the last one
+pub const MAX_LISTED_ISSUES: usize = 1000;
+
+#[derive(Debug, Error)]
+pub enum IssueProviderError {
+    #[error("No GitHub token configured")]
+    NoGitHubToken,
+    #[error("No GitLab token configured")]
+    NoGitLabToken,
+    #[error(transparent)]
+    GitHub(#[from] GitHubServiceError),
+    #[error(transparent)]
+    GitLab(#[from] GitLabServiceError),
+}
+
+/// An issue on any tracker
+#[derive(Debug, Clone)]
+pub struct ProviderIssue {
+    /// GitHub issue number or GitLab iid
+    pub number: i64,
+    pub title: String,
+    pub body: Option<String>,
+    pub state: IssueState,
+    /// Closed as not planned rather than completed; only GitHub records the difference
+    pub not_planned: bool,
+    pub url: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IssueListing {
+    pub issues: Vec<ProviderIssue>,
+    /// Set when the listing stopped at [`MAX_LISTED_ISSUES`]: where the next sync picks up
+    pub continue_from: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait IssueProvider: Send + Sync {
+    /// Issues updated at or after `since` (all of them when None), least recently updated first
+    async fn list_issues(
+        &self,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<IssueListing, IssueProviderError>;
+
+    /// Close or reopen an issue. Trackers without close reasons ignore `not_planned`.
+    async fn set_issue_state(
+        &self,
+        number: i64,
+        state: IssueState,
+        not_planned: bool,
+    ) -> Result<(), IssueProviderError>;
+}
+
+/// Update time of the last issue when a listing hit the limit
+fn continue_from<T>(
+    issues: &[T],
+    updated_at: impl Fn(&T) -> DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    issues
+        .last()
+        .filter(|_| issues.len() >= MAX_LISTED_ISSUES)
+        .map(updated_at)
+}
+
+pub struct GitHubIssues {
+    github: GitHubService,
+    repo_info: GitHubRepoInfo,
+}
+
+#[async_trait]
+impl IssueProvider for GitHubIssues {
+    async fn list_issues(
+        &self,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<IssueListing, IssueProviderError> {
+        let issues = self
+            .github
+            .list_issues(&self.repo_info, since, MAX_LISTED_ISSUES)
+            .await?;
+        // Pull requests count towards the limit even though they are dropped here
+        let continue_from = continue_from(&issues, |issue| issue.updated_at);
+        let issues = issues
+            .into_iter()
+            .filter(|issue| !issue.is_pull_request())
+            .map(|issue| ProviderIssue {
+                number: issue.number,
+                not_planned: issue.state_reason.as_deref() == Some("not_planned"),
+                title: issue.title,
+                body: issue.body,
+                state: issue.state,
+                url: issue.html_url,
+                updated_at: issue.updated_at,
+            })
+            .collect();
+        Ok(IssueListing {
+            issues,
+            continue_from,
+        })
+    }
+
+    async fn set_issue_state(
+        &self,
+        number: i64,
+        state: IssueState,
+        not_planned: bool,
+    ) -> Result<(), IssueProviderError> {
+        let state_reason = match state {
+            IssueState::Closed if not_planned => Some("not_planned"),
+            IssueState::Closed => Some("completed"),
+            IssueState::Open => None,
+        };
+        self.github
+            .set_issue_state(&self.repo_info, number, state, state_reason)
+            .await?;
+        Ok(())
+    }
+}
+
+pub struct GitLabIssues {
+    gitlab: GitLabService,
+    repo_info: GitLabRepoInfo,
+}
+
+#[async_trait]
+impl IssueProvider for GitLabIssues {
+    async fn list_issues(
+        &self,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<IssueListing, IssueProviderError> {
+        let issues = self
+            .gitlab
+            .list_issues(&self.repo_info, since, MAX_LISTED_ISSUES)
+            .await?;
+        let continue_from = continue_from(&issues, |issue| issue.updated_at);
+        let issues = issues
+            .into_iter()
+            .map(|issue| ProviderIssue {
+                number: issue.iid,
+                state: issue.issue_state(),
+                not_planned: false,
+                title: issue.title,
+                body: issue.description,
+                url: issue.web_url,
+                updated_at: issue.updated_at,
+            })
+            .collect();
+        Ok(IssueListing {
+            issues,
+            continue_from,
+        })
+    }
+
+    async fn set_issue_state(
+        &self,
+        number: i64,
+        state: IssueState,
+        _not_planned: bool,
+    ) -> Result<(), IssueProviderError> {
+        self.gitlab
+            .set_issue_state(&self.repo_info, number, state)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Client for the tracker a project is linked to, using the token from settings. GitLab projects
+/// live on the configured GitLab instance.
+pub fn issue_provider_for(
+    config: &Config,
+    sync: &IssueSync,
+) -> Result<Box<dyn IssueProvider>, IssueProviderError> {
+    match sync.tracker {
+        IssueTracker::GitHub => {
+            let token = config
+                .github
+                .token()
+                .ok_or(IssueProviderError::NoGitHubToken)?;
+            Ok(Box::new(GitHubIssues {
+                github: GitHubService::new(&token)?,
+                repo_info: GitHubRepoInfo {
+                    owner: sync.owner.clone(),
+                    repo_name: sync.repo.clone(),
+                },
+            }))
+        }
+        IssueTracker::GitLab => {
+            let token = config
+                .gitlab
+                .token()
+                .ok_or(IssueProviderError::NoGitLabToken)?;
+            Ok(Box::new(GitLabIssues {
+                gitlab: GitLabService::new(&token)?,
+                repo_info: GitLabRepoInfo {
+                    base_url: config.gitlab.resolved_base_url(),
+                    project_path: sync.repo_path(),
+                },
+            }))
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Two-way sync between a project's tasks and the issues of a GitHub repository or GitLab project.
+//! New open issues become tasks; issues closed or reopened on the tracker move their task to done
+//! or back to todo; and tasks finished or cancelled here close their issue (merging the PR counts,
+//! since that marks the task done). PRs and MRs opened for a linked task reference the issue so
+//! the forge links them.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use db::{
+    DBService,
+    models::{
+        issue_sync::{IssueState, IssueSync, IssueTracker, TaskIssue},
+        task::{CreateTask, Task, TaskStatus},
+    },
+};
+use regex::Regex;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::{
+    config::Config,
+    issue_provider::{IssueProvider, IssueProviderError, issue_provider_for},
+    webhooks::WebhookService,
+};
+
+#[derive(Debug, Error)]
+pub enum IssueSyncError {
+    #[error(transparent)]
+    Provider(#[from] IssueProviderError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct IssueSyncSummary {
+    /// New tasks created from open issues
+    pub imported: usize,
+    /// Tasks whose issue was closed or reopened on the tracker
+    pub updated: usize,
+    /// Issues closed or reopened because their task changed
+    pub pushed: usize,
+}
+
+/// Status a task moves to when its issue is closed or reopened on the tracker. Closing an issue
+/// as not planned cancels the task; tasks that are already finished stay as they are.
+pub fn task_status_for_issue(
+    state: IssueState,
+    not_planned: bool,
+    current: &TaskStatus,
+) -> TaskStatus {
+    let finished = matches!(current, TaskStatus::Done | TaskStatus::Cancelled);
+    match state {
+        IssueState::Closed if finished => current.clone(),
+        IssueState::Closed if not_planned => TaskStatus::Cancelled,
+        IssueState::Closed => TaskStatus::Done,
+        IssueState::Open if finished => TaskStatus::Todo,
+        IssueState::Open => current.clone(),
+    }
+}
+
+/// Issue state matching a task's status, and whether to close it as not planned
+pub fn issue_state_for_task(status: &TaskStatus) -> (IssueState, bool) {
+    match status {
+        TaskStatus::Done => (IssueState::Closed, false),
+        TaskStatus::Cancelled => (IssueState::Closed, true),
+        _ => (IssueState::Open, false),
+    }
+}
+
+/// How a PR or MR in the repository at `pr_repo_path` on `pr_tracker` refers to the issue:
+/// `#123` in the same repository, `owner/repo#123` across repositories. None when the issue
+/// lives on another tracker, where no reference would resolve.
+pub fn issue_reference(
+    sync: &IssueSync,
+    pr_tracker: IssueTracker,
+    pr_repo_path: &str,
+    number: i64,
+) -> Option<String> {
+    if sync.tracker != pr_tracker {
+        return None;
+    }
+    let issue_repo_path = sync.repo_path();
+    Some(if issue_repo_path.eq_ignore_ascii_case(pr_repo_path) {
+        format!("#{number}")
+    } else {
+        format!("{issue_repo_path}#{number}")
+    })
+}
+
+/// PR or MR description that closes `issue_ref` on merge. A body that already uses a closing keyword for the
+/// issue is left alone.
+pub fn with_closing_reference(body: Option<&str>, issue_ref: &str) -> String {
+    let body = body.unwrap_or("").trim_end();
+    let pattern = format!(
+        r"(?i)\b(close[sd]?|fix(e[sd])?|resolve[sd]?):?\s+{}\b",
+        regex::escape(issue_ref)
+    );
+    if Regex::new(&pattern).is_ok_and(|re| re.is_match(body)) {
+        return body.to_string();
+    }
+    if body.is_empty() {
+        format!("Fixes {issue_ref}")
+    } else {
+        format!("{body}\n\nFixes {issue_ref}")
+    }
+}
+
+/// Pull issue changes into the project's tasks, then push task changes back to the issues
+pub async fn sync_project_issues(
+    pool: &SqlitePool,
+    provider: &dyn IssueProvider,
+    sync: &IssueSync,
+) -> Result<IssueSyncSummary, IssueSyncError> {
+    let started_at = Utc::now();
+    let mut summary = IssueSyncSummary::default();
+
+    let listing = provider.list_issues(sync.last_synced_at).await?;
+    // When the listing was cut short, the next sync continues after the last issue seen
+    let synced_until = listing.continue_from.unwrap_or(started_at);
+    for issue in &listing.issues {
+        match TaskIssue::find_by_issue_number(pool, sync.project_id, issue.number).await? {
+            Some(link) if link.issue_state != issue.state => {
+                TaskIssue::set_state(pool, link.task_id, issue.state).await?;
+                if let Some(task) = Task::find_by_id(pool, link.task_id).await? {
+                    let status =
+                        task_status_for_issue(issue.state, issue.not_planned, &task.status);
+                    if status != task.status {
+                        Task::update_status(pool, task.id, status).await?;
+                    }
+                }
+                summary.updated += 1;
+            }
+            Some(_) => {}
+            // Closed issues are history; only open ones are worth a task
+            None if issue.state == IssueState::Closed => {}
+            None => {
+                let task = Task::create(
+                    pool,
+                    &CreateTask::from_title_description(
+                        sync.project_id,
+                        issue.title.clone(),
+                        issue
+                            .body
+                            .as_deref()
+                            .map(str::trim)
+                            .filter(|body| !body.is_empty())
+                            .map(str::to_string),
+                    ),
+                    Uuid::new_v4(),
+                )
+                .await?;
+                WebhookService::task_created(pool, &task);
+                TaskIssue::create(
+                    pool,
+                    task.id,
+                    sync.project_id,
+                    issue.number,
+                    &issue.url,
+                    issue.state,
+                )
+                .await?;
+                summary.imported += 1;
+            }
+        }
+    }
+
+    for link in TaskIssue::find_out_of_sync(pool, sync.project_id).await? {
+        let Some(task) = Task::find_by_id(pool, link.task_id).await? else {
+            continue;
+        };
+        let (state, not_planned) = issue_state_for_task(&task.status);
+        provider
+            .set_issue_state(link.issue_number, state, not_planned)
+            .await?;
+        TaskIssue::set_state(pool, link.task_id, state).await?;
+        summary.pushed += 1;
+    }
+
+    IssueSync::record_success(pool, sync.project_id, synced_until).await?;
+    Ok(summary)
+}
+
+/// Periodically syncs every project linked to an issue tracker
+pub struct IssueSyncService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+}
+
+impl IssueSyncService {
+    pub async fn spawn(db: DBService, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            config,
+            poll_interval: Duration::from_secs(300),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting issue sync service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.sync_all().await {
+                error!("Error syncing issues: {}", e);
+            }
+        }
+    }
+
+    async fn sync_all(&self) -> Result<(), IssueSyncError> {
+        let syncs = IssueSync::find_all(&self.db.pool).await?;
+        if syncs.is_empty() {
+            return Ok(());
+        }
+        let config = self.config.read().await.clone();
+
+        for sync in syncs {
+            let result = match issue_provider_for(&config, &sync) {
+                Ok(provider) => sync_project_issues(&self.db.pool, provider.as_ref(), &sync).await,
+                Err(e) => Err(e.into()),
+            };
+            match result {
+                Ok(summary) => debug!(
+                    "Synced issues of {} into project {}: {:?}",
+                    sync.repo_path(),
+                    sync.project_id,
+                    summary
+                ),
+                Err(e) => {
+                    error!(
+                        "Failed to sync issues of {} into project {}: {}",
+                        sync.repo_path(),
+                        sync.project_id,
+                        e
+                    );
+                    IssueSync::record_error(&self.db.pool, sync.project_id, &e.to_string()).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
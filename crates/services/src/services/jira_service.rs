@@ -0,0 +1,307 @@
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::services::config::JiraConfig;
+
+#[derive(Debug, Error)]
+pub enum JiraServiceError {
+    #[error(transparent)]
+    Client(#[from] reqwest::Error),
+    #[error("Jira is not configured; set the site URL and API token in settings")]
+    NotConfigured,
+    #[error("Jira credentials are invalid or expired.")]
+    TokenInvalid,
+    #[error("Insufficient permissions")]
+    InsufficientPermissions,
+    #[error("Jira issue not found or no access")]
+    IssueNotFoundOrNoAccess,
+    #[error("Jira request failed: {0}")]
+    Request(String),
+}
+
+impl JiraServiceError {
+    pub fn should_retry(&self) -> bool {
+        matches!(self, JiraServiceError::Client(_))
+    }
+}
+
+/// Upper bound on issues returned by one search
+pub const MAX_SEARCHED_ISSUES: usize = 500;
+const ISSUES_PER_PAGE: usize = 100;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JiraStatusCategory {
+    /// `new`, `indeterminate` or `done`
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JiraStatus {
+    pub name: String,
+    #[serde(rename = "statusCategory")]
+    pub status_category: JiraStatusCategory,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JiraIssueFields {
+    pub summary: String,
+    /// Wiki markup; the v2 API returns plain strings instead of document trees
+    pub description: Option<String>,
+    pub status: JiraStatus,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JiraIssue {
+    pub key: String,
+    pub fields: JiraIssueFields,
+}
+
+impl JiraIssue {
+    pub fn is_done(&self) -> bool {
+        self.fields.status.status_category.key == "done"
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JiraTransition {
+    pub id: String,
+    pub name: String,
+    pub to: JiraStatus,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    issues: Vec<JiraIssue>,
+    /// Set by the token-paginated Cloud endpoint
+    #[serde(rename = "nextPageToken")]
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransitionsResponse {
+    transitions: Vec<JiraTransition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    #[serde(default, rename = "errorMessages")]
+    error_messages: Vec<String>,
+}
+
+/// The transition that moves an issue into `status` (matched by name), falling back to any
+/// transition into the status category `category_fallback`
+pub fn pick_transition<'a>(
+    transitions: &'a [JiraTransition],
+    status: &str,
+    category_fallback: Option<&str>,
+) -> Option<&'a JiraTransition> {
+    let status = status.trim();
+    transitions
+        .iter()
+        .find(|t| t.to.name.eq_ignore_ascii_case(status))
+        .or_else(|| {
+            transitions
+                .iter()
+                .find(|t| t.name.eq_ignore_ascii_case(status))
+        })
+        .or_else(|| {
+            let category = category_fallback?;
+            transitions
+                .iter()
+                .find(|t| t.to.status_category.key == category)
+        })
+}
+
+/// Client for the Jira REST API (v2, which Cloud and Data Center share)
+#[derive(Debug, Clone)]
+pub struct JiraService {
+    client: Client,
+    base_url: String,
+    email: Option<String>,
+    api_token: String,
+}
+
+impl JiraService {
+    pub fn new(config: &JiraConfig) -> Result<Self, JiraServiceError> {
+        let (Some(base_url), Some(api_token)) = (config.resolved_base_url(), config.api_token())
+        else {
+            return Err(JiraServiceError::NotConfigured);
+        };
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("vibe-kanban")
+            .build()?;
+        Ok(Self {
+            client,
+            base_url,
+            email: config.email(),
+            api_token,
+        })
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.email {
+            Some(email) => request.basic_auth(email, Some(&self.api_token)),
+            None => request.bearer_auth(&self.api_token),
+        }
+    }
+
+    /// Browser URL of an issue
+    pub fn issue_url(&self, key: &str) -> String {
+        format!("{}/browse/{key}", self.base_url)
+    }
+
+    /// Cloud sites only offer the token-paginated search endpoint
+    fn is_cloud(&self) -> bool {
+        self.base_url.ends_with(".atlassian.net")
+    }
+
+    /// Issues matching `jql`, up to [`MAX_SEARCHED_ISSUES`]
+    pub async fn search_issues(&self, jql: &str) -> Result<Vec<JiraIssue>, JiraServiceError> {
+        let mut issues = Vec::new();
+        let mut next_page_token: Option<String> = None;
+        while issues.len() < MAX_SEARCHED_ISSUES {
+            let start_at = issues.len();
+            let page = (|| async {
+                self.search_page(jql, start_at, next_page_token.as_deref())
+                    .await
+            })
+            .retry(Self::retry_policy())
+            .when(|e| e.should_retry())
+            .notify(Self::log_retry)
+            .await?;
+            let fetched = page.issues.len();
+            issues.extend(page.issues);
+            next_page_token = page.next_page_token;
+            let more = if self.is_cloud() {
+                next_page_token.is_some()
+            } else {
+                fetched == ISSUES_PER_PAGE
+            };
+            if !more || fetched == 0 {
+                break;
+            }
+        }
+        issues.truncate(MAX_SEARCHED_ISSUES);
+        Ok(issues)
+    }
+
+    async fn search_page(
+        &self,
+        jql: &str,
+        start_at: usize,
+        next_page_token: Option<&str>,
+    ) -> Result<SearchResponse, JiraServiceError> {
+        let mut params = vec![
+            ("jql", jql.to_string()),
+            ("fields", "summary,description,status".to_string()),
+            ("maxResults", ISSUES_PER_PAGE.to_string()),
+        ];
+        let url = if self.is_cloud() {
+            if let Some(token) = next_page_token {
+                params.push(("nextPageToken", token.to_string()));
+            }
+            format!("{}/rest/api/2/search/jql", self.base_url)
+        } else {
+            params.push(("startAt", start_at.to_string()));
+            format!("{}/rest/api/2/search", self.base_url)
+        };
+        let response = self
+            .authorized(self.client.get(url).query(&params))
+            .send()
+            .await?;
+        Ok(Self::check_status(response, "search issues")
+            .await?
+            .json::<SearchResponse>()
+            .await?)
+    }
+
+    pub async fn list_transitions(
+        &self,
+        issue_key: &str,
+    ) -> Result<Vec<JiraTransition>, JiraServiceError> {
+        (|| async {
+            let response = self
+                .authorized(self.client.get(format!(
+                    "{}/rest/api/2/issue/{issue_key}/transitions",
+                    self.base_url
+                )))
+                .send()
+                .await?;
+            Ok(
+                Self::check_status(response, &format!("get transitions of {issue_key}"))
+                    .await?
+                    .json::<TransitionsResponse>()
+                    .await?
+                    .transitions,
+            )
+        })
+        .retry(Self::retry_policy())
+        .when(|e| e.should_retry())
+        .notify(Self::log_retry)
+        .await
+    }
+
+    pub async fn transition_issue(
+        &self,
+        issue_key: &str,
+        transition_id: &str,
+    ) -> Result<(), JiraServiceError> {
+        let response = self
+            .authorized(self.client.post(format!(
+                "{}/rest/api/2/issue/{issue_key}/transitions",
+                self.base_url
+            )))
+            .json(&serde_json::json!({ "transition": { "id": transition_id } }))
+            .send()
+            .await?;
+        Self::check_status(response, &format!("transition {issue_key}")).await?;
+        Ok(())
+    }
+
+    fn retry_policy() -> ExponentialBuilder {
+        ExponentialBuilder::default()
+            .with_min_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(30))
+            .with_max_times(3)
+            .with_jitter()
+    }
+
+    fn log_retry(err: &JiraServiceError, dur: Duration) {
+        tracing::warn!(
+            "Jira API call failed, retrying after {:.2}s: {}",
+            dur.as_secs_f64(),
+            err
+        );
+    }
+
+    async fn check_status(
+        response: reqwest::Response,
+        action: &str,
+    ) -> Result<reqwest::Response, JiraServiceError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        match status {
+            StatusCode::UNAUTHORIZED => Err(JiraServiceError::TokenInvalid),
+            StatusCode::FORBIDDEN => Err(JiraServiceError::InsufficientPermissions),
+            StatusCode::NOT_FOUND => Err(JiraServiceError::IssueNotFoundOrNoAccess),
+            _ => {
+                let detail = response
+                    .json::<ErrorResponse>()
+                    .await
+                    .ok()
+                    .and_then(|body| body.error_messages.into_iter().next())
+                    .unwrap_or_else(|| status.to_string());
+                Err(JiraServiceError::Request(format!(
+                    "Failed to {action}: {detail}"
+                )))
+            }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Pulls the Jira issues matching a project's JQL query in as tasks, and moves each issue along
+//! its workflow when the task reaches InReview or Done. Issues that are already done in Jira are
+//! not imported.
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use db::{
+    DBService,
+    models::{
+        jira_issue::{JiraSync, TaskJiraIssue},
+        task::{CreateTask, Task, TaskStatus},
+    },
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::{
+    config::Config,
+    jira_service::{JiraService, JiraServiceError, pick_transition},
+    webhooks::WebhookService,
+};
+
+pub const DEFAULT_IN_REVIEW_STATUS: &str = "In Review";
+pub const DEFAULT_DONE_STATUS: &str = "Done";
+
+#[derive(Debug, Error)]
+pub enum JiraSyncError {
+    #[error(transparent)]
+    Jira(#[from] JiraServiceError),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct JiraSyncSummary {
+    /// New tasks created from issues
+    pub imported: usize,
+    /// Issues moved to the status matching their task
+    pub transitioned: usize,
+    /// Issues whose workflow has no transition to the configured status
+    pub skipped_transitions: usize,
+}
+
+/// Pull new issues into the project, then transition the issues of tasks that moved on
+pub async fn sync_project_jira(
+    pool: &SqlitePool,
+    jira: &JiraService,
+    sync: &JiraSync,
+) -> Result<JiraSyncSummary, JiraSyncError> {
+    let started_at = Utc::now();
+    let mut summary = JiraSyncSummary::default();
+
+    let known: HashSet<String> = TaskJiraIssue::find_keys_by_project_id(pool, sync.project_id)
+        .await?
+        .into_iter()
+        .collect();
+    for issue in jira.search_issues(&sync.jql).await? {
+        if issue.is_done() || known.contains(&issue.key) {
+            continue;
+        }
+        let task = Task::create(
+            pool,
+            &CreateTask::from_title_description(
+                sync.project_id,
+                format!("{}: {}", issue.key, issue.fields.summary.trim()),
+                issue
+                    .fields
+                    .description
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|description| !description.is_empty())
+                    .map(str::to_string),
+            ),
+            Uuid::new_v4(),
+        )
+        .await?;
+        WebhookService::task_created(pool, &task);
+        TaskJiraIssue::create(
+            pool,
+            task.id,
+            sync.project_id,
+            &issue.key,
+            &jira.issue_url(&issue.key),
+        )
+        .await?;
+        summary.imported += 1;
+    }
+
+    for pending in TaskJiraIssue::find_pending_transitions(pool, sync.project_id).await? {
+        let (target, category_fallback) = match pending.status {
+            TaskStatus::InReview => (
+                sync.in_review_status
+                    .as_deref()
+                    .unwrap_or(DEFAULT_IN_REVIEW_STATUS),
+                None,
+            ),
+            _ => (
+                sync.done_status.as_deref().unwrap_or(DEFAULT_DONE_STATUS),
+                Some("done"),
+            ),
+        };
+        let transitions = jira.list_transitions(&pending.issue_key).await?;
+        match pick_transition(&transitions, target, category_fallback) {
+            Some(transition) => {
+                jira.transition_issue(&pending.issue_key, &transition.id)
+                    .await?;
+                summary.transitioned += 1;
+            }
+            None => {
+                // Recorded anyway, so the same missing transition is not retried every sync
+                warn!(
+                    "Jira issue {} has no transition to '{}'",
+                    pending.issue_key, target
+                );
+                summary.skipped_transitions += 1;
+            }
+        }
+        TaskJiraIssue::set_pushed_status(pool, pending.task_id, pending.status).await?;
+    }
+
+    JiraSync::record_success(pool, sync.project_id, started_at).await?;
+    Ok(summary)
+}
+
+/// Periodically syncs every project linked to a Jira query
+pub struct JiraSyncService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+}
+
+impl JiraSyncService {
+    pub async fn spawn(db: DBService, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            config,
+            poll_interval: Duration::from_secs(300),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting Jira sync service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.sync_all().await {
+                error!("Error syncing Jira issues: {}", e);
+            }
+        }
+    }
+
+    async fn sync_all(&self) -> Result<(), JiraSyncError> {
+        let syncs = JiraSync::find_all(&self.db.pool).await?;
+        if syncs.is_empty() {
+            return Ok(());
+        }
+        let jira_config = self.config.read().await.jira.clone();
+        let jira = JiraService::new(&jira_config)?;
+
+        for sync in syncs {
+            match sync_project_jira(&self.db.pool, &jira, &sync).await {
+                Ok(summary) => debug!(
+                    "Synced Jira issues into project {}: {:?}",
+                    sync.project_id, summary
+                ),
+                Err(e) => {
+                    error!(
+                        "Failed to sync Jira issues into project {}: {}",
+                        sync.project_id, e
+                    );
+                    JiraSync::record_error(&self.db.pool, sync.project_id, &e.to_string()).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! Gzip-compressed copies of the message history of finished execution processes.
+//!
+//! The in-memory store of a process is dropped when it exits, and opening its logs afterwards
+//! used to mean parsing the stored output and running the agent's normalizer over it again. A
+//! snapshot keeps the normalized entries as well, so it can be streamed back as-is. Reading a
+//! snapshot refreshes its modification time, which the retention policy uses as "last opened".
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use utils::{assets::asset_dir, log_msg::LogMsg};
+use uuid::Uuid;
+
+const SNAPSHOT_EXTENSION: &str = ".jsonl.gz";
+
+pub fn snapshot_dir() -> PathBuf {
+    asset_dir().join("log-snapshots")
+}
+
+fn snapshot_path(dir: &Path, execution_id: Uuid) -> PathBuf {
+    dir.join(format!("{execution_id}{SNAPSHOT_EXTENSION}"))
+}
+
+/// Write the history of a finished process, one JSON message per line. The file is written under
+/// a temporary name first so readers never see a partial snapshot.
+pub fn write_snapshot(dir: &Path, execution_id: Uuid, messages: &[LogMsg]) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let path = snapshot_path(dir, execution_id);
+    let tmp_path = path.with_extension("tmp");
+
+    let mut encoder = GzEncoder::new(
+        BufWriter::new(File::create(&tmp_path)?),
+        Compression::default(),
+    );
+    for msg in messages {
+        if matches!(msg, LogMsg::Finished) {
+            continue;
+        }
+        serde_json::to_writer(&mut encoder, msg)?;
+        encoder.write_all(b"\n")?;
+    }
+    encoder.finish()?.flush()?;
+    fs::rename(&tmp_path, &path)
+}
+
+/// The messages of a snapshot, or None if the process has none
+pub fn read_snapshot(dir: &Path, execution_id: Uuid) -> io::Result<Option<Vec<LogMsg>>> {
+    let file = match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(snapshot_path(dir, execution_id))
+    {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if let Err(e) = file.set_modified(SystemTime::now()) {
+        tracing::debug!("Failed to touch log snapshot {}: {}", execution_id, e);
+    }
+
+    let mut messages = Vec::new();
+    for line in BufReader::new(GzDecoder::new(file)).lines() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        messages.push(serde_json::from_str(&line)?);
+    }
+    Ok(Some(messages))
+}
+
+pub fn remove_snapshot(dir: &Path, execution_id: Uuid) -> io::Result<()> {
+    match fs::remove_file(snapshot_path(dir, execution_id)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Delete snapshots last opened before `cutoff`, returning how many were removed
+pub fn prune_snapshots(dir: &Path, cutoff: SystemTime) -> io::Result<usize> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let mut removed = 0;
+    for entry in entries {
+        let entry = entry?;
+        if !entry
+            .file_name()
+            .to_string_lossy()
+            .ends_with(SNAPSHOT_EXTENSION)
+        {
+            continue;
+        }
+        if entry.metadata()?.modified()? < cutoff {
+            fs::remove_file(entry.path())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
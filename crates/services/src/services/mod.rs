@@ -1,8 +1,18 @@
 pub mod analytics;
 pub mod approvals;
 pub mod auth;
+pub mod auto_fix;
+pub mod branch_cleanup;
+pub mod branch_suggestions;
+pub mod budgets;
+pub mod calendar_feed;
 pub mod config;
 pub mod container;
+pub mod cost;
+pub mod cron_schedule;
+pub mod db_backup;
+pub mod dependency_cache;
+pub mod dev_server_ports;
 pub mod drafts;
 pub mod events;
 pub mod file_ranker;
@@ -11,9 +21,30 @@ pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git;
 pub mod git_cli;
+pub mod git_host;
+pub mod gitea_service;
 pub mod github_service;
+pub mod gitlab_service;
+pub mod handoff;
 pub mod image;
+pub mod issue_provider;
+pub mod issue_sync;
+pub mod jira_service;
+pub mod jira_sync;
+pub mod log_snapshots;
 pub mod notification;
 pub mod pr_monitor;
+pub mod pr_template;
+pub mod project_bundle;
+pub mod project_stats;
+pub mod prompt_snippets;
+pub mod release_notes;
+pub mod review_comments;
 pub mod sentry;
+pub mod task_import;
+pub mod usage_attribution;
+pub mod usage_export;
+pub mod usage_limits;
+pub mod users;
+pub mod webhooks;
 pub mod worktree_manager;
@@ -1,9 +1,13 @@
-use std::sync::OnceLock;
+use std::{sync::OnceLock, time::Duration};
 
 use db::models::execution_process::{ExecutionContext, ExecutionProcessStatus};
+use serde::Deserialize;
+use serde_json::json;
+use thiserror::Error;
 use utils;
+use uuid::Uuid;
 
-use crate::services::config::SoundFile;
+use crate::services::config::{DiscordConfig, SlackConfig, SoundFile};
 
 /// Service for handling cross-platform notifications including sound alerts and push notifications
 #[derive(Debug, Clone)]
@@ -13,6 +17,179 @@ use crate::services::config::NotificationConfig;
 /// Cache for WSL root path from PowerShell
 static WSL_ROOT_PATH_CACHE: OnceLock<Option<String>> = OnceLock::new();
 
+const SLACK_POST_MESSAGE_URL: &str = "https://slack.com/api/chat.postMessage";
+
+/// Events posted to Slack and Discord, each switchable per channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatEvent {
+    ExecutionHalted,
+    AttemptFailed,
+    PrMerged,
+    /// An activity feed event at or above the configured urgency
+    UrgentActivity,
+}
+
+impl ChatEvent {
+    fn enabled_in_slack(self, config: &SlackConfig) -> bool {
+        match self {
+            ChatEvent::ExecutionHalted => config.execution_halted,
+            ChatEvent::AttemptFailed => config.attempt_failed,
+            ChatEvent::PrMerged => config.pr_merged,
+            ChatEvent::UrgentActivity => config.urgent_activity,
+        }
+    }
+
+    fn enabled_in_discord(self, config: &DiscordConfig) -> bool {
+        match self {
+            ChatEvent::ExecutionHalted => config.execution_halted,
+            ChatEvent::AttemptFailed => config.attempt_failed,
+            ChatEvent::PrMerged => config.pr_merged,
+            ChatEvent::UrgentActivity => config.urgent_activity,
+        }
+    }
+
+    /// Discord embed side color: green, red, purple or orange
+    fn color(self) -> u32 {
+        match self {
+            ChatEvent::ExecutionHalted => 0x2ecc71,
+            ChatEvent::AttemptFailed => 0xe74c3c,
+            ChatEvent::PrMerged => 0x8e44ad,
+            ChatEvent::UrgentActivity => 0xe67e22,
+        }
+    }
+}
+
+/// An event as posted to chat: a headline, a one-line summary and labelled details
+#[derive(Debug, Clone)]
+pub struct ChatMessage {
+    pub event: ChatEvent,
+    pub title: String,
+    pub summary: String,
+    /// Lines of the Slack message; fields of the Discord embed
+    pub fields: Vec<(&'static str, String)>,
+    pub task_url: Option<String>,
+}
+
+#[derive(Debug, Error)]
+enum ChatError {
+    #[error(transparent)]
+    Request(#[from] reqwest::Error),
+    #[error("message rejected: {0}")]
+    Rejected(String),
+}
+
+/// Where a Slack message goes
+#[derive(Debug, Clone)]
+enum SlackTarget {
+    Webhook(String),
+    Bot { token: String, channel: String },
+}
+
+impl SlackTarget {
+    /// The webhook when set, else the bot if it has a channel
+    fn from_config(config: &SlackConfig) -> Option<Self> {
+        match (config.webhook_url(), config.bot_token(), config.channel()) {
+            (Some(url), _, _) => Some(SlackTarget::Webhook(url)),
+            (None, Some(token), Some(channel)) => Some(SlackTarget::Bot { token, channel }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackApiResponse {
+    ok: bool,
+    error: Option<String>,
+}
+
+/// Escape the characters Slack reads as markup
+pub fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Slack text: the bold title, the summary, one line per field and a link to the task
+pub fn slack_message(message: &ChatMessage) -> String {
+    let mut text = format!(
+        "*{}*\n{}",
+        slack_escape(&message.title),
+        slack_escape(&message.summary)
+    );
+    for (name, value) in &message.fields {
+        text.push_str(&format!("\n*{name}:* {}", slack_escape(value)));
+    }
+    if let Some(url) = &message.task_url {
+        text.push_str(&format!("\n<{url}|Open task>"));
+    }
+    text
+}
+
+/// Cut `text` to Discord's length limit for the embed part it goes in
+fn discord_truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Discord webhook body with one embed; the title links to the task when its URL is known
+pub fn discord_payload(message: &ChatMessage) -> serde_json::Value {
+    let fields: Vec<serde_json::Value> = message
+        .fields
+        .iter()
+        .map(|(name, value)| {
+            json!({
+                "name": name,
+                "value": discord_truncate(value, 1024),
+                "inline": true,
+            })
+        })
+        .collect();
+    let mut embed = json!({
+        "title": discord_truncate(&message.title, 256),
+        "description": discord_truncate(&message.summary, 4096),
+        "color": message.event.color(),
+        "fields": fields,
+    });
+    if let Some(url) = &message.task_url {
+        embed["url"] = json!(url);
+    }
+    json!({ "embeds": [embed] })
+}
+
+/// Web link to a task, or to one of its attempts, when the app URL is configured
+pub fn task_link(
+    config: &NotificationConfig,
+    project_id: Uuid,
+    task_id: Uuid,
+    attempt_id: Option<Uuid>,
+) -> Option<String> {
+    let app_url = config.app_url()?;
+    Some(match attempt_id {
+        Some(attempt_id) => {
+            format!("{app_url}/projects/{project_id}/tasks/{task_id}/attempts/{attempt_id}")
+        }
+        None => format!("{app_url}/projects/{project_id}/tasks/{task_id}"),
+    })
+}
+
+/// How a process ended, e.g. `Failed (exit code 1)`
+pub fn exit_status_label(status: &ExecutionProcessStatus, exit_code: Option<i64>) -> String {
+    let status = match status {
+        ExecutionProcessStatus::Running => "Running",
+        ExecutionProcessStatus::Completed => "Completed",
+        ExecutionProcessStatus::Failed => "Failed",
+        ExecutionProcessStatus::Killed => "Cancelled",
+    };
+    match exit_code {
+        Some(code) => format!("{status} (exit code {code})"),
+        None => status.to_string(),
+    }
+}
+
 impl NotificationService {
     pub async fn notify_execution_halted(mut config: NotificationConfig, ctx: &ExecutionContext) {
         // If the process was intentionally killed by user, suppress sound
@@ -42,9 +219,139 @@ impl NotificationService {
                 return;
             }
         };
+
+        // Cancelling is the user's own doing; nobody else needs to hear about it
+        let chat_event = match ctx.execution_process.status {
+            ExecutionProcessStatus::Failed => {
+                Some((ChatEvent::AttemptFailed, "❌ Execution failed"))
+            }
+            ExecutionProcessStatus::Completed => {
+                Some((ChatEvent::ExecutionHalted, "✅ Completed successfully"))
+            }
+            _ => None,
+        };
+        if let Some((event, summary)) = chat_event {
+            Self::notify_chat(
+                &config,
+                ChatMessage {
+                    event,
+                    title: title.clone(),
+                    summary: summary.to_string(),
+                    fields: vec![
+                        ("Branch", ctx.task_attempt.branch.clone()),
+                        ("Executor", ctx.task_attempt.executor.clone()),
+                        (
+                            "Exit status",
+                            exit_status_label(
+                                &ctx.execution_process.status,
+                                ctx.execution_process.exit_code,
+                            ),
+                        ),
+                    ],
+                    task_url: task_link(
+                        &config,
+                        ctx.task.project_id,
+                        ctx.task.id,
+                        Some(ctx.task_attempt.id),
+                    ),
+                },
+            );
+        }
+
         Self::notify(config, &title, &message).await;
     }
 
+    /// Post to the Slack and Discord channels that have the event switched on. Messages are sent
+    /// in the background; failures are only logged.
+    pub fn notify_chat(config: &NotificationConfig, message: ChatMessage) {
+        if message.event.enabled_in_slack(&config.slack)
+            && let Some(target) = SlackTarget::from_config(&config.slack)
+        {
+            let text = slack_message(&message);
+            let event = message.event;
+            tokio::spawn(async move {
+                if let Err(e) = Self::post_to_slack(&target, &text).await {
+                    tracing::error!("Failed to post {:?} to Slack: {}", event, e);
+                }
+            });
+        }
+
+        if let Some(webhook_url) = config.discord.webhook_url()
+            && message.event.enabled_in_discord(&config.discord)
+        {
+            let payload = discord_payload(&message);
+            let event = message.event;
+            tokio::spawn(async move {
+                if let Err(e) = Self::post_to_discord(&webhook_url, &payload).await {
+                    tracing::error!("Failed to post {:?} to Discord: {}", event, e);
+                }
+            });
+        }
+    }
+
+    fn chat_client() -> Result<reqwest::Client, reqwest::Error> {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .user_agent("vibe-kanban")
+            .build()
+    }
+
+    /// Webhooks answer failures with a plain-text or JSON reason worth logging
+    async fn check_chat_response(response: reqwest::Response) -> Result<(), ChatError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let reason = response.text().await.unwrap_or_default();
+        Err(ChatError::Rejected(format!("{status} {reason}")))
+    }
+
+    async fn post_to_slack(target: &SlackTarget, text: &str) -> Result<(), ChatError> {
+        let client = Self::chat_client()?;
+        match target {
+            SlackTarget::Webhook(url) => {
+                let response = client
+                    .post(url)
+                    .json(&json!({ "text": text }))
+                    .send()
+                    .await?;
+                Self::check_chat_response(response).await
+            }
+            SlackTarget::Bot { token, channel } => {
+                // The Web API reports failures in the body of a 200 response
+                let response: SlackApiResponse = client
+                    .post(SLACK_POST_MESSAGE_URL)
+                    .bearer_auth(token)
+                    .json(&json!({ "channel": channel, "text": text }))
+                    .send()
+                    .await?
+                    .json()
+                    .await?;
+                if response.ok {
+                    Ok(())
+                } else {
+                    Err(ChatError::Rejected(
+                        response
+                            .error
+                            .unwrap_or_else(|| "unknown error".to_string()),
+                    ))
+                }
+            }
+        }
+    }
+
+    async fn post_to_discord(
+        webhook_url: &str,
+        payload: &serde_json::Value,
+    ) -> Result<(), ChatError> {
+        let response = Self::chat_client()?
+            .post(webhook_url)
+            .json(payload)
+            .send()
+            .await?;
+        Self::check_chat_response(response).await
+    }
+
     /// Send both sound and push notifications if enabled
     pub async fn notify(config: NotificationConfig, title: &str, message: &str) {
         if config.sound_enabled {
@@ -3,7 +3,8 @@ use std::{sync::Arc, time::Duration};
 use db::{
     DBService,
     models::{
-        merge::{Merge, MergeStatus, PrMerge},
+        merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
+        pr_check_status::PrCheckStatus,
         task::{Task, TaskStatus},
         task_attempt::{TaskAttempt, TaskAttemptError},
     },
@@ -11,29 +12,46 @@ use db::{
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
 use tokio::{sync::RwLock, time::interval};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
-use crate::services::{
-    config::Config,
-    github_service::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+use crate::{
+    activity_feed::ActivityEventRecorder,
+    services::{
+        branch_cleanup,
+        config::Config,
+        git::GitService,
+        gitea_service::{GiteaRepoInfo, GiteaService, GiteaServiceError},
+        github_service::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+        gitlab_service::{GitLabRepoInfo, GitLabService, GitLabServiceError},
+        notification::{ChatEvent, ChatMessage, NotificationService, task_link},
+        webhooks::WebhookService,
+    },
 };
 
 #[derive(Debug, Error)]
 enum PrMonitorError {
     #[error("No GitHub token configured")]
     NoGitHubToken,
+    #[error("No GitLab token configured")]
+    NoGitLabToken,
     #[error(transparent)]
     GitHubServiceError(#[from] GitHubServiceError),
     #[error(transparent)]
+    GitLabServiceError(#[from] GitLabServiceError),
+    #[error(transparent)]
+    GiteaServiceError(#[from] GiteaServiceError),
+    #[error(transparent)]
     TaskAttemptError(#[from] TaskAttemptError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
 }
 
-/// Service to monitor GitHub PRs and update task status when they are merged
+/// Service to monitor GitHub/Gitea PRs and GitLab MRs and update task status when they are merged.
+/// For open GitHub PRs it also records the review decision and CI check results.
 pub struct PrMonitorService {
     db: DBService,
     config: Arc<RwLock<Config>>,
+    git: GitService,
     poll_interval: Duration,
 }
 
@@ -42,6 +60,7 @@ impl PrMonitorService {
         let service = Self {
             db,
             config,
+            git: GitService::new(),
             poll_interval: Duration::from_secs(60), // Check every minute
         };
         tokio::spawn(async move {
@@ -87,8 +106,29 @@ impl PrMonitorService {
         Ok(())
     }
 
-    /// Check the status of a specific PR
-    async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
+    /// Fetch the latest state of a PR or MR from the provider its URL belongs to
+    async fn fetch_pr_info(&self, pr_merge: &PrMerge) -> Result<PullRequestInfo, PrMonitorError> {
+        if let Some((repo_info, mr_iid)) =
+            GitLabRepoInfo::from_merge_request_url(&pr_merge.pr_info.url)
+        {
+            let gitlab_config = self.config.read().await.gitlab.clone();
+            let gitlab_token = gitlab_config.token().ok_or(PrMonitorError::NoGitLabToken)?;
+            let gitlab_service = GitLabService::new(&gitlab_token)?;
+            return Ok(gitlab_service.update_mr_status(&repo_info, mr_iid).await?);
+        }
+
+        let gitea_config = self.config.read().await.gitea.clone();
+        if let (Some(base_url), Some(gitea_token)) =
+            (gitea_config.resolved_base_url(), gitea_config.token())
+            && let Some((repo_info, pr_number)) =
+                GiteaRepoInfo::from_pull_request_url(&pr_merge.pr_info.url, &base_url)
+        {
+            let gitea_service = GiteaService::new(&gitea_token)?;
+            return Ok(gitea_service
+                .update_pr_status(&repo_info, pr_number)
+                .await?);
+        }
+
         let github_config = self.config.read().await.github.clone();
         let github_token = github_config.token().ok_or(PrMonitorError::NoGitHubToken)?;
 
@@ -96,15 +136,94 @@ impl PrMonitorService {
 
         let repo_info = GitHubRepoInfo::from_remote_url(&pr_merge.pr_info.url)?;
 
-        let pr_status = github_service
+        Ok(github_service
             .update_pr_status(&repo_info, pr_merge.pr_info.number)
+            .await?)
+    }
+
+    /// Record the review decision and check runs of an open GitHub PR. Other providers are skipped.
+    async fn refresh_pr_checks(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
+        let Ok(repo_info) = GitHubRepoInfo::from_remote_url(&pr_merge.pr_info.url) else {
+            return Ok(());
+        };
+        let github_config = self.config.read().await.github.clone();
+        let github_token = github_config.token().ok_or(PrMonitorError::NoGitHubToken)?;
+        let github_service = GitHubService::new(&github_token)?;
+
+        let (review_state, checks) = github_service
+            .fetch_pr_checks(&repo_info, pr_merge.pr_info.number)
             .await?;
+        if PrCheckStatus::upsert_if_changed(
+            &self.db.pool,
+            pr_merge.task_attempt_id,
+            pr_merge.pr_info.number,
+            &pr_merge.pr_info.url,
+            review_state,
+            &checks,
+        )
+        .await?
+        {
+            debug!(
+                "PR #{} review state {:?}, {} checks",
+                pr_merge.pr_info.number,
+                review_state,
+                checks.len()
+            );
+        }
+        Ok(())
+    }
+
+    async fn notify_merged(
+        &self,
+        pr_merge: &PrMerge,
+        task_attempt: &TaskAttempt,
+    ) -> Result<(), PrMonitorError> {
+        let Some(task) = Task::find_by_id(&self.db.pool, task_attempt.task_id).await? else {
+            return Ok(());
+        };
+        WebhookService::merge_completed(
+            &self.db.pool,
+            &task,
+            task_attempt,
+            &pr_merge.target_branch_name,
+            Some(&pr_merge.pr_info.url),
+        );
+        let notify_cfg = self.config.read().await.notifications.clone();
+        let task_url = task_link(&notify_cfg, task.project_id, task.id, Some(task_attempt.id));
+        NotificationService::notify_chat(
+            &notify_cfg,
+            ChatMessage {
+                event: ChatEvent::PrMerged,
+                title: format!("PR Merged: {}", task.title),
+                summary: format!("Merged into {}", pr_merge.target_branch_name),
+                fields: vec![
+                    ("Branch", task_attempt.branch.clone()),
+                    ("Pull request", pr_merge.pr_info.url.clone()),
+                ],
+                task_url,
+            },
+        );
+        Ok(())
+    }
+
+    /// Check the status of a specific PR
+    async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
+        let pr_status = self.fetch_pr_info(pr_merge).await?;
 
         debug!(
             "PR #{} status: {:?} (was open)",
             pr_merge.pr_info.number, pr_status.status
         );
 
+        if matches!(&pr_status.status, MergeStatus::Open)
+            && let Err(e) = self.refresh_pr_checks(pr_merge).await
+        {
+            warn!(
+                "Failed to refresh checks for PR #{}: {}",
+                pr_merge.pr_info.number, e
+            );
+        }
+
         // Update the PR status in the database
         if !matches!(&pr_status.status, MergeStatus::Open) {
             // Update merge status with the latest information from GitHub
@@ -115,6 +234,7 @@ impl PrMonitorService {
                 pr_status.merge_commit_sha,
             )
             .await?;
+            ActivityEventRecorder::pull_request(&self.db.pool, pr_merge.id).await;
 
             // If the PR was merged, update the task status to done
             if matches!(&pr_status.status, MergeStatus::Merged)
@@ -126,6 +246,20 @@ impl PrMonitorService {
                     pr_merge.pr_info.number, task_attempt.task_id
                 );
                 Task::update_status(&self.db.pool, task_attempt.task_id, TaskStatus::Done).await?;
+                TaskAttempt::retarget_child_attempts(
+                    &self.db.pool,
+                    task_attempt.id,
+                    &task_attempt.branch,
+                    &pr_merge.target_branch_name,
+                )
+                .await?;
+                branch_cleanup::delete_remote_branch_after_merge(
+                    &self.db.pool,
+                    &self.git,
+                    &task_attempt,
+                )
+                .await;
+                self.notify_merged(pr_merge, &task_attempt).await?;
             }
         }
 
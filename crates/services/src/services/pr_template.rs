@@ -0,0 +1,53 @@
+use std::path::{Component, Path};
+
+/// Locations checked for a pull request template, relative to the repository root, in the order
+/// forges themselves look them up.
+pub const DEFAULT_PR_TEMPLATE_PATHS: &[&str] = &[
+    ".github/PULL_REQUEST_TEMPLATE.md",
+    ".github/pull_request_template.md",
+    "PULL_REQUEST_TEMPLATE.md",
+    "pull_request_template.md",
+    "docs/PULL_REQUEST_TEMPLATE.md",
+    "docs/pull_request_template.md",
+    ".gitlab/merge_request_templates/Default.md",
+    ".gitea/pull_request_template.md",
+];
+
+/// A configured template path must point inside the repository.
+pub fn is_valid_template_path(path: &str) -> bool {
+    let path = Path::new(path.trim());
+    !path.as_os_str().is_empty()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// Read the repository's PR template from the worktree. A configured path takes precedence over
+/// the default locations; templates that are missing or blank are skipped.
+pub fn find_pr_template(worktree_path: &Path, configured_path: Option<&str>) -> Option<String> {
+    let configured = configured_path
+        .map(str::trim)
+        .filter(|path| is_valid_template_path(path));
+    configured
+        .into_iter()
+        .chain(DEFAULT_PR_TEMPLATE_PATHS.iter().copied())
+        .find_map(|relative| {
+            let contents = std::fs::read_to_string(worktree_path.join(relative)).ok()?;
+            (!contents.trim().is_empty()).then(|| contents.trim_end().to_string())
+        })
+}
+
+/// Combine the generated PR description with the repository template. The generated text goes
+/// first and the template's sections follow, so reviewers still get the checklist they expect.
+/// A body that already contains the template (e.g. the user started from it) is kept as is.
+pub fn merge_pr_template(body: Option<&str>, template: &str) -> String {
+    let body = body.map(str::trim).unwrap_or_default();
+    let template = template.trim();
+    if body.is_empty() {
+        return template.to_string();
+    }
+    if template.is_empty() || body.contains(template) {
+        return body.to_string();
+    }
+    format!("{body}\n\n{template}")
+}
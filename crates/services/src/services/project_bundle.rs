@@ -0,0 +1,721 @@
+//! Portable snapshot of a project's board for moving it to another machine: tasks with their
+//! labels, due dates and images, plus the history of attempts and merges. Everything tied to the
+//! original machine (worktrees, processes, logs) is left out, so imported attempts are archived
+//! and cannot be resumed.
+//!
+//! The bundle is a single JSON document; images are embedded as base64. A bundle can be imported
+//! into an existing project, or used to create a new one with the same repositories, which must
+//! already be cloned on the importing machine.
+
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use chrono::{DateTime, Utc};
+use db::models::{
+    image::{Image, TaskImage},
+    label::{CreateLabel, Label},
+    merge::{Merge, MergeStatus},
+    project::{CreateProject, Project},
+    project_repository::{
+        CreateProjectRepository, ProjectRepository, ProjectRepositoryError, UpdateProjectRepository,
+    },
+    task::{CreateTask, Task, TaskStatus},
+    task_attempt::{CreateTaskAttempt, CreateTaskAttemptRepository, TaskAttempt, TaskAttemptError},
+    task_attempt_repository::TaskAttemptRepository,
+};
+use executors::executors::BaseCodingAgent;
+use serde::{Deserialize, Serialize};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use thiserror::Error;
+use ts_rs::TS;
+use utils::path::expand_tilde;
+use uuid::Uuid;
+
+use crate::services::image::ImageService;
+
+/// Bumped whenever the layout changes in a way older readers cannot handle
+pub const PROJECT_BUNDLE_VERSION: u32 = 2;
+
+#[derive(Debug, Error)]
+pub enum ProjectBundleError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    TaskAttempt(#[from] TaskAttemptError),
+    #[error(transparent)]
+    ProjectRepository(#[from] ProjectRepositoryError),
+    #[error("Unsupported bundle version {0}, expected {PROJECT_BUNDLE_VERSION} or earlier")]
+    UnsupportedVersion(u32),
+    #[error("The bundle has no repositories; import it into an existing project instead")]
+    NoRepositories,
+    #[error("{}", .0.join("; "))]
+    RepositoryPaths(Vec<String>),
+    #[error("A project with the git repository path {0} already exists")]
+    DuplicateProjectPath(String),
+    #[error("Image {0} in the bundle is not valid base64")]
+    InvalidImageData(Uuid),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectBundle {
+    pub version: u32,
+    #[ts(type = "Date")]
+    pub exported_at: DateTime<Utc>,
+    pub project_name: String,
+    #[serde(default)]
+    pub setup_script: Option<String>,
+    #[serde(default)]
+    pub dev_script: Option<String>,
+    #[serde(default)]
+    pub cleanup_script: Option<String>,
+    #[serde(default)]
+    pub copy_files: Option<String>,
+    /// Empty in version 1 bundles
+    #[serde(default)]
+    pub repositories: Vec<BundledRepository>,
+    pub labels: Vec<BundledLabel>,
+    /// Oldest first, so parent tasks come before their subtasks
+    pub tasks: Vec<BundledTask>,
+    pub images: Vec<BundledImage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BundledRepository {
+    /// Id on the exporting machine, referenced by attempts
+    pub id: Uuid,
+    pub name: String,
+    /// Path on the exporting machine
+    pub git_repo_path: String,
+    pub root_path: String,
+    pub is_primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BundledLabel {
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BundledTask {
+    /// Id on the exporting machine; imported tasks get new ids
+    pub id: Uuid,
+    pub parent_task_id: Option<Uuid>,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    pub due_at: Option<DateTime<Utc>>,
+    /// Label names
+    pub labels: Vec<String>,
+    pub image_ids: Vec<Uuid>,
+    /// Oldest first
+    pub attempts: Vec<BundledAttempt>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BundledAttempt {
+    pub executor: String,
+    pub branch: String,
+    pub target_branch: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    pub merges: Vec<Merge>,
+    /// Branches per repository; empty in version 1 bundles
+    #[serde(default)]
+    pub repositories: Vec<BundledAttemptRepository>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BundledAttemptRepository {
+    /// The repository's id in the bundle
+    pub repository_id: Uuid,
+    pub is_primary: bool,
+    pub branch: Option<String>,
+    pub base_branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BundledImage {
+    pub id: Uuid,
+    pub original_name: String,
+    /// File contents, base64-encoded
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct ProjectBundleImportSummary {
+    pub tasks: usize,
+    pub attempts: usize,
+    pub merges: usize,
+    pub images: usize,
+    /// Attempts of executors this machine does not know
+    pub skipped_attempts: usize,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ImportProjectBundle {
+    pub bundle: ProjectBundle,
+    /// Name of the new project; the bundle's project name when unset
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Where bundled repositories are cloned on this machine, keyed by their id in the bundle.
+    /// Repositories left out are expected at their original path.
+    #[serde(default)]
+    #[ts(type = "Record<string, string>")]
+    pub repository_paths: HashMap<Uuid, String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ImportedProjectBundle {
+    pub project: Project,
+    pub summary: ProjectBundleImportSummary,
+}
+
+pub async fn export_project_bundle(
+    pool: &SqlitePool,
+    images: &ImageService,
+    project: &Project,
+) -> Result<ProjectBundle, ProjectBundleError> {
+    let repositories = ProjectRepository::list_for_project(pool, project.id)
+        .await?
+        .into_iter()
+        .map(|repository| BundledRepository {
+            id: repository.id,
+            name: repository.name,
+            git_repo_path: repository.git_repo_path.to_string_lossy().into_owned(),
+            root_path: repository.root_path,
+            is_primary: repository.is_primary,
+        })
+        .collect();
+
+    let labels = Label::find_by_project_id(pool, project.id)
+        .await?
+        .into_iter()
+        .map(|label| BundledLabel {
+            name: label.name,
+            color: label.color,
+        })
+        .collect();
+
+    let mut tasks = Task::find_by_project_id_with_attempt_status(pool, project.id).await?;
+    tasks.reverse();
+
+    let mut bundled_tasks = Vec::with_capacity(tasks.len());
+    let mut bundled_images: Vec<BundledImage> = Vec::new();
+    for task in tasks {
+        let mut image_ids = Vec::new();
+        for image in Image::find_by_task_id(pool, task.id).await? {
+            image_ids.push(image.id);
+            if bundled_images.iter().any(|bundled| bundled.id == image.id) {
+                continue;
+            }
+            match tokio::fs::read(images.get_absolute_path(&image)).await {
+                Ok(data) => bundled_images.push(BundledImage {
+                    id: image.id,
+                    original_name: image.original_name,
+                    data: BASE64_STANDARD.encode(data),
+                }),
+                Err(e) => {
+                    tracing::warn!("Leaving image {} out of the export: {}", image.id, e);
+                    image_ids.pop();
+                }
+            }
+        }
+
+        let mut attempts = TaskAttempt::fetch_all(pool, Some(task.id)).await?;
+        attempts.reverse();
+        let mut bundled_attempts = Vec::with_capacity(attempts.len());
+        for attempt in attempts {
+            let repositories = TaskAttemptRepository::list_for_attempt(pool, attempt.id)
+                .await?
+                .into_iter()
+                .map(|repository| BundledAttemptRepository {
+                    repository_id: repository.project_repository_id,
+                    is_primary: repository.is_primary,
+                    branch: repository.branch,
+                    base_branch: repository.base_branch,
+                })
+                .collect();
+            bundled_attempts.push(BundledAttempt {
+                merges: Merge::find_by_task_attempt_id(pool, attempt.id).await?,
+                repositories,
+                executor: attempt.executor,
+                branch: attempt.branch,
+                target_branch: attempt.target_branch,
+                created_at: attempt.created_at,
+            });
+        }
+
+        bundled_tasks.push(BundledTask {
+            id: task.task.id,
+            parent_task_id: task.task.parent_task_id,
+            title: task.task.title,
+            description: task.task.description,
+            status: task.task.status,
+            due_at: task.due_at,
+            labels: task.labels.into_iter().map(|label| label.name).collect(),
+            image_ids,
+            attempts: bundled_attempts,
+        });
+    }
+
+    Ok(ProjectBundle {
+        version: PROJECT_BUNDLE_VERSION,
+        exported_at: Utc::now(),
+        project_name: project.name.clone(),
+        setup_script: project.setup_script.clone(),
+        dev_script: project.dev_script.clone(),
+        cleanup_script: project.cleanup_script.clone(),
+        copy_files: project.copy_files.clone(),
+        repositories,
+        labels,
+        tasks: bundled_tasks,
+        images: bundled_images,
+    })
+}
+
+fn check_version(bundle: &ProjectBundle) -> Result<(), ProjectBundleError> {
+    if bundle.version == 0 || bundle.version > PROJECT_BUNDLE_VERSION {
+        return Err(ProjectBundleError::UnsupportedVersion(bundle.version));
+    }
+    Ok(())
+}
+
+/// Add the bundle's tasks to `project`. Labels and repositories are matched to the project's
+/// existing ones by name; images are deduplicated by content like any other upload. The board
+/// entities are created in one transaction, so a failed import leaves the project untouched.
+pub async fn import_project_bundle(
+    pool: &SqlitePool,
+    images: &ImageService,
+    project: &Project,
+    bundle: ProjectBundle,
+) -> Result<ProjectBundleImportSummary, ProjectBundleError> {
+    check_version(&bundle)?;
+    let project_repositories = ProjectRepository::list_for_project(pool, project.id).await?;
+    let repository_ids = bundle
+        .repositories
+        .iter()
+        .filter_map(|bundled| {
+            project_repositories
+                .iter()
+                .find(|repository| repository.name.eq_ignore_ascii_case(&bundled.name))
+                .map(|repository| (bundled.id, repository.id))
+        })
+        .collect();
+    let decoded_images = decode_images(&bundle.images)?;
+
+    let mut tx = pool.begin().await?;
+    let imported = import_tasks(&mut tx, project, bundle, &repository_ids).await?;
+    tx.commit().await?;
+
+    Ok(attach_images(pool, images, decoded_images, imported).await)
+}
+
+/// Where the bundle's repositories are on this machine: `repository_paths` overrides the
+/// exported paths, and each one must be a git repository containing the repository's root. All
+/// problems are reported together.
+pub fn resolve_repository_paths(
+    repositories: &[BundledRepository],
+    repository_paths: &HashMap<Uuid, String>,
+) -> Result<Vec<BundledRepository>, ProjectBundleError> {
+    if repositories.is_empty() {
+        return Err(ProjectBundleError::NoRepositories);
+    }
+    let mut resolved = Vec::with_capacity(repositories.len());
+    let mut problems = Vec::new();
+    for repository in repositories {
+        let requested = repository_paths
+            .get(&repository.id)
+            .map(|path| path.trim())
+            .filter(|path| !path.is_empty())
+            .unwrap_or(&repository.git_repo_path);
+        let path = std::path::absolute(expand_tilde(requested))
+            .unwrap_or_else(|_| PathBuf::from(requested));
+        if !path.is_dir() {
+            problems.push(format!(
+                "{}: {} does not exist on this machine",
+                repository.name,
+                path.display()
+            ));
+        } else if !path.join(".git").exists() {
+            problems.push(format!(
+                "{}: {} is not a git repository",
+                repository.name,
+                path.display()
+            ));
+        } else if !repository.root_path.is_empty() && !path.join(&repository.root_path).exists() {
+            problems.push(format!(
+                "{}: {} does not contain {}",
+                repository.name,
+                path.display(),
+                repository.root_path
+            ));
+        }
+        resolved.push(BundledRepository {
+            git_repo_path: path.to_string_lossy().into_owned(),
+            ..repository.clone()
+        });
+    }
+    if !problems.is_empty() {
+        return Err(ProjectBundleError::RepositoryPaths(problems));
+    }
+
+    // The project needs exactly one primary repository
+    let primary = resolved
+        .iter()
+        .position(|repository| repository.is_primary)
+        .unwrap_or(0);
+    for (index, repository) in resolved.iter_mut().enumerate() {
+        repository.is_primary = index == primary;
+    }
+    // Primary first, so it takes over the placeholder repository before any other is added
+    resolved.sort_by_key(|repository| !repository.is_primary);
+    Ok(resolved)
+}
+
+/// Create a new project from the bundle, with its scripts and repositories, and import the
+/// bundle's tasks into it. Every id is new; attempts are linked to the recreated repositories.
+/// Nothing is created unless all repository paths check out, and the project is created in the
+/// same transaction as everything imported into it.
+pub async fn import_project_bundle_as_project(
+    pool: &SqlitePool,
+    images: &ImageService,
+    request: ImportProjectBundle,
+) -> Result<ImportedProjectBundle, ProjectBundleError> {
+    let ImportProjectBundle {
+        bundle,
+        name,
+        repository_paths,
+    } = request;
+    check_version(&bundle)?;
+    let repositories = resolve_repository_paths(&bundle.repositories, &repository_paths)?;
+    let primary = repositories
+        .iter()
+        .find(|repository| repository.is_primary)
+        .ok_or(ProjectBundleError::NoRepositories)?;
+    if Project::find_by_git_repo_path(pool, &primary.git_repo_path)
+        .await?
+        .is_some()
+    {
+        return Err(ProjectBundleError::DuplicateProjectPath(
+            primary.git_repo_path.clone(),
+        ));
+    }
+
+    let decoded_images = decode_images(&bundle.images)?;
+
+    let mut tx = pool.begin().await?;
+    let project = Project::create(
+        &mut *tx,
+        &CreateProject {
+            name: name
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| bundle.project_name.clone()),
+            git_repo_path: primary.git_repo_path.clone(),
+            use_existing_repo: true,
+            setup_script: bundle.setup_script.clone(),
+            dev_script: bundle.dev_script.clone(),
+            cleanup_script: bundle.cleanup_script.clone(),
+            copy_files: bundle.copy_files.clone(),
+        },
+        Uuid::new_v4(),
+    )
+    .await?;
+
+    let imported = populate_project(&mut tx, &project, bundle, &repositories).await?;
+    tx.commit().await?;
+
+    let summary = attach_images(pool, images, decoded_images, imported).await;
+    Ok(ImportedProjectBundle { project, summary })
+}
+
+/// Recreate the bundle's repositories in a freshly created `project` and import its tasks
+async fn populate_project(
+    tx: &mut Transaction<'_, Sqlite>,
+    project: &Project,
+    bundle: ProjectBundle,
+    repositories: &[BundledRepository],
+) -> Result<ImportedTasks, ProjectBundleError> {
+    let mut repository_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    for repository in repositories {
+        let root_path = Some(repository.root_path.clone()).filter(|root| !root.is_empty());
+        let created = if repository.is_primary {
+            // Creating the project added the primary repository under a placeholder name
+            let placeholder = ProjectRepository::find_primary(&mut **tx, project.id)
+                .await?
+                .ok_or(ProjectRepositoryError::PrimaryRequired)?;
+            ProjectRepository::update(
+                &mut **tx,
+                project.id,
+                placeholder.id,
+                &UpdateProjectRepository {
+                    name: Some(repository.name.clone()),
+                    git_repo_path: None,
+                    root_path,
+                    is_primary: None,
+                },
+            )
+            .await?
+        } else {
+            ProjectRepository::create(
+                &mut **tx,
+                project.id,
+                &CreateProjectRepository {
+                    name: repository.name.clone(),
+                    git_repo_path: repository.git_repo_path.clone(),
+                    root_path,
+                    is_primary: false,
+                },
+            )
+            .await?
+        };
+        repository_ids.insert(repository.id, created.id);
+    }
+
+    import_tasks(tx, project, bundle, &repository_ids).await
+}
+
+/// A bundled image whose data has been checked to be valid base64
+struct DecodedImage {
+    id: Uuid,
+    original_name: String,
+    data: Vec<u8>,
+}
+
+fn decode_images(images: &[BundledImage]) -> Result<Vec<DecodedImage>, ProjectBundleError> {
+    images
+        .iter()
+        .map(|image| {
+            let data = BASE64_STANDARD
+                .decode(&image.data)
+                .map_err(|_| ProjectBundleError::InvalidImageData(image.id))?;
+            Ok(DecodedImage {
+                id: image.id,
+                original_name: image.original_name.clone(),
+                data,
+            })
+        })
+        .collect()
+}
+
+/// Result of `import_tasks`: the new tasks with the bundle's image ids they are waiting for
+struct ImportedTasks {
+    summary: ProjectBundleImportSummary,
+    task_images: Vec<(Uuid, Vec<Uuid>)>,
+}
+
+/// `repository_ids` maps repository ids in the bundle to repositories of `project`; attempt
+/// branches of unmapped repositories are dropped. Images are left to `attach_images`.
+async fn import_tasks(
+    tx: &mut Transaction<'_, Sqlite>,
+    project: &Project,
+    bundle: ProjectBundle,
+    repository_ids: &HashMap<Uuid, Uuid>,
+) -> Result<ImportedTasks, ProjectBundleError> {
+    let mut summary = ProjectBundleImportSummary::default();
+    let mut task_images = Vec::new();
+
+    let mut label_ids: HashMap<String, Uuid> = Label::find_by_project_id(&mut **tx, project.id)
+        .await?
+        .into_iter()
+        .map(|label| (label.name.to_lowercase(), label.id))
+        .collect();
+    for label in &bundle.labels {
+        if !label_ids.contains_key(&label.name.to_lowercase()) {
+            let created = Label::create(
+                &mut **tx,
+                project.id,
+                &CreateLabel {
+                    name: label.name.clone(),
+                    color: label.color.clone(),
+                },
+            )
+            .await?;
+            label_ids.insert(label.name.to_lowercase(), created.id);
+        }
+    }
+
+    let mut task_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    for bundled in bundle.tasks {
+        let task = Task::create(
+            &mut **tx,
+            &CreateTask {
+                project_id: project.id,
+                title: bundled.title,
+                description: bundled.description,
+                parent_task_attempt: None,
+                parent_task_id: bundled
+                    .parent_task_id
+                    .and_then(|id| task_ids.get(&id).copied()),
+                image_ids: None,
+            },
+            Uuid::new_v4(),
+        )
+        .await?;
+        task_ids.insert(bundled.id, task.id);
+        summary.tasks += 1;
+
+        if bundled.status != TaskStatus::Todo {
+            Task::update_status(&mut **tx, task.id, bundled.status).await?;
+        }
+        if bundled.due_at.is_some() {
+            Task::set_due_at(&mut **tx, task.id, bundled.due_at).await?;
+        }
+        let task_label_ids: Vec<Uuid> = bundled
+            .labels
+            .iter()
+            .filter_map(|name| label_ids.get(&name.to_lowercase()).copied())
+            .collect();
+        if !task_label_ids.is_empty() {
+            Label::set_for_task(&mut **tx, task.id, project.id, &task_label_ids).await?;
+        }
+        if !bundled.image_ids.is_empty() {
+            task_images.push((task.id, bundled.image_ids));
+        }
+
+        for attempt in bundled.attempts {
+            let Ok(executor) = BaseCodingAgent::from_str(&attempt.executor) else {
+                summary.skipped_attempts += 1;
+                continue;
+            };
+            let attempt_repositories: Vec<(Uuid, BundledAttemptRepository)> = attempt
+                .repositories
+                .into_iter()
+                .filter_map(|repository| {
+                    repository_ids
+                        .get(&repository.repository_id)
+                        .map(|id| (*id, repository))
+                })
+                .collect();
+            let task_attempt = TaskAttempt::create(
+                &mut **tx,
+                &CreateTaskAttempt {
+                    executor,
+                    base_branch: attempt.target_branch,
+                    branch: attempt.branch,
+                    repositories: (!attempt_repositories.is_empty()).then(|| {
+                        attempt_repositories
+                            .iter()
+                            .map(|(id, repository)| CreateTaskAttemptRepository {
+                                project_repository_id: *id,
+                                is_primary: repository.is_primary,
+                                base_branch: repository.base_branch.clone(),
+                            })
+                            .collect()
+                    }),
+                },
+                Uuid::new_v4(),
+                task.id,
+            )
+            .await?;
+            for (id, repository) in &attempt_repositories {
+                if repository.branch.is_some() {
+                    TaskAttemptRepository::upsert_branch(
+                        &mut **tx,
+                        task_attempt.id,
+                        *id,
+                        repository.is_primary,
+                        repository.branch.as_deref(),
+                        repository.base_branch.as_deref(),
+                    )
+                    .await?;
+                }
+            }
+            // There is no worktree on this machine to resume or clean up
+            TaskAttempt::mark_worktree_deleted(&mut **tx, task_attempt.id).await?;
+            summary.attempts += 1;
+
+            for merge in attempt.merges {
+                import_merge(tx, task_attempt.id, merge).await?;
+                summary.merges += 1;
+            }
+        }
+    }
+
+    Ok(ImportedTasks {
+        summary,
+        task_images,
+    })
+}
+
+/// Store the bundle's images and link them to the imported tasks. This runs once the import is
+/// committed, so an image that cannot be stored is left out with a warning instead of failing
+/// an import that has already happened.
+async fn attach_images(
+    pool: &SqlitePool,
+    images: &ImageService,
+    decoded_images: Vec<DecodedImage>,
+    imported: ImportedTasks,
+) -> ProjectBundleImportSummary {
+    let ImportedTasks {
+        mut summary,
+        task_images,
+    } = imported;
+
+    let mut image_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    for image in decoded_images {
+        match images.store_image(&image.data, &image.original_name).await {
+            Ok(stored) => {
+                image_ids.insert(image.id, stored.id);
+                summary.images += 1;
+            }
+            Err(e) => tracing::warn!("Leaving image {} out of the import: {}", image.id, e),
+        }
+    }
+
+    for (task_id, bundled_image_ids) in task_images {
+        let task_image_ids: Vec<Uuid> = bundled_image_ids
+            .iter()
+            .filter_map(|id| image_ids.get(id).copied())
+            .collect();
+        if let Err(e) = TaskImage::associate_many_dedup(pool, task_id, &task_image_ids).await {
+            tracing::warn!(
+                "Failed to attach images to imported task {}: {}",
+                task_id,
+                e
+            );
+        }
+    }
+
+    summary
+}
+
+async fn import_merge(
+    tx: &mut Transaction<'_, Sqlite>,
+    task_attempt_id: Uuid,
+    merge: Merge,
+) -> Result<(), sqlx::Error> {
+    match merge {
+        Merge::Direct(direct) => {
+            Merge::create_direct(
+                &mut **tx,
+                task_attempt_id,
+                &direct.target_branch_name,
+                &direct.merge_commit,
+                direct.merge_strategy,
+            )
+            .await?;
+        }
+        Merge::Pr(pr) => {
+            let created = Merge::create_pr(
+                &mut **tx,
+                task_attempt_id,
+                &pr.target_branch_name,
+                pr.pr_info.number,
+                &pr.pr_info.url,
+            )
+            .await?;
+            if !matches!(pr.pr_info.status, MergeStatus::Open) {
+                Merge::update_status(
+                    &mut **tx,
+                    created.id,
+                    pr.pr_info.status,
+                    pr.pr_info.merge_commit_sha,
+                )
+                .await?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Kind of server work attributed to a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectActivityKind {
+    ExecutionStarted,
+    DiffStreamed,
+    SearchRun,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct ActivityStats {
+    #[ts(type = "number")]
+    pub count: u64,
+    #[ts(type = "number")]
+    pub total_ms: u64,
+    #[ts(type = "number")]
+    pub max_ms: u64,
+    pub last_at: Option<DateTime<Utc>>,
+}
+
+impl ActivityStats {
+    fn record(&mut self, latency: Duration, at: DateTime<Utc>) {
+        let ms = latency.as_millis().min(u64::MAX as u128) as u64;
+        self.count += 1;
+        self.total_ms = self.total_ms.saturating_add(ms);
+        self.max_ms = self.max_ms.max(ms);
+        self.last_at = Some(at);
+    }
+
+    pub fn average_ms(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.total_ms as f64 / self.count as f64
+        }
+    }
+}
+
+/// Usage counters for one project since the server started
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct ProjectUsageStats {
+    pub project_id: Uuid,
+    pub executions_started: ActivityStats,
+    pub diffs_streamed: ActivityStats,
+    pub searches_run: ActivityStats,
+}
+
+impl ProjectUsageStats {
+    fn stats_mut(&mut self, kind: ProjectActivityKind) -> &mut ActivityStats {
+        match kind {
+            ProjectActivityKind::ExecutionStarted => &mut self.executions_started,
+            ProjectActivityKind::DiffStreamed => &mut self.diffs_streamed,
+            ProjectActivityKind::SearchRun => &mut self.searches_run,
+        }
+    }
+
+    pub fn total_count(&self) -> u64 {
+        self.executions_started.count + self.diffs_streamed.count + self.searches_run.count
+    }
+}
+
+/// In-memory per-project counts and latencies of API and WebSocket usage. Counters reset when the
+/// server restarts.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectStatsService {
+    stats: Arc<Mutex<HashMap<Uuid, ProjectUsageStats>>>,
+}
+
+impl ProjectStatsService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, project_id: Uuid, kind: ProjectActivityKind, latency: Duration) {
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        stats
+            .entry(project_id)
+            .or_insert_with(|| ProjectUsageStats {
+                project_id,
+                ..Default::default()
+            })
+            .stats_mut(kind)
+            .record(latency, Utc::now());
+    }
+
+    /// Time the work until the returned guard is dropped, so early returns are counted too
+    pub fn start(&self, project_id: Uuid, kind: ProjectActivityKind) -> ProjectStatsTimer {
+        ProjectStatsTimer {
+            service: self.clone(),
+            project_id,
+            kind,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn for_project(&self, project_id: Uuid) -> ProjectUsageStats {
+        let stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        stats
+            .get(&project_id)
+            .cloned()
+            .unwrap_or_else(|| ProjectUsageStats {
+                project_id,
+                ..Default::default()
+            })
+    }
+
+    /// All projects with recorded usage, busiest first
+    pub fn all(&self) -> Vec<ProjectUsageStats> {
+        let stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        let mut all: Vec<ProjectUsageStats> = stats.values().cloned().collect();
+        all.sort_by(|a, b| b.total_count().cmp(&a.total_count()));
+        all
+    }
+}
+
+pub struct ProjectStatsTimer {
+    service: ProjectStatsService,
+    project_id: Uuid,
+    kind: ProjectActivityKind,
+    started: Instant,
+}
+
+impl Drop for ProjectStatsTimer {
+    fn drop(&mut self) {
+        self.service
+            .record(self.project_id, self.kind, self.started.elapsed());
+    }
+}
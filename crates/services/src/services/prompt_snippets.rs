@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use db::models::prompt_snippet::PromptSnippet;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+static SNIPPET_PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*snippet:([A-Za-z0-9_.-]+)\s*\}\}").unwrap());
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ExpandedPrompt {
+    pub prompt: String,
+    /// Placeholders naming snippets that do not exist; they are left in the prompt as written
+    pub missing: Vec<String>,
+}
+
+/// Replace every `{{snippet:name}}` placeholder with the content of the named snippet. Snippet
+/// content is inserted as-is, so placeholders inside a snippet are not expanded again.
+pub fn expand(prompt: &str, snippets: &[PromptSnippet]) -> ExpandedPrompt {
+    let by_name: HashMap<&str, &str> = snippets
+        .iter()
+        .map(|snippet| (snippet.name.as_str(), snippet.content.as_str()))
+        .collect();
+    let mut missing = Vec::new();
+    let expanded = SNIPPET_PLACEHOLDER.replace_all(prompt, |caps: &Captures| {
+        let name = &caps[1];
+        match by_name.get(name) {
+            Some(content) => content.trim_end().to_string(),
+            None => {
+                if !missing.iter().any(|m| m == name) {
+                    missing.push(name.to_string());
+                }
+                caps[0].to_string()
+            }
+        }
+    });
+    ExpandedPrompt {
+        prompt: expanded.into_owned(),
+        missing,
+    }
+}
+
+/// Expand a prompt with the snippets visible to `project_id`. Prompts without placeholders are
+/// returned without touching the database.
+pub async fn expand_for_project(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    prompt: &str,
+) -> Result<ExpandedPrompt, sqlx::Error> {
+    if !SNIPPET_PLACEHOLDER.is_match(prompt) {
+        return Ok(ExpandedPrompt {
+            prompt: prompt.to_string(),
+            missing: Vec::new(),
+        });
+    }
+    let snippets = PromptSnippet::find_for_project(pool, project_id).await?;
+    let expanded = expand(prompt, &snippets);
+    if !expanded.missing.is_empty() {
+        tracing::warn!(
+            "Prompt for project {} references unknown snippets: {}",
+            project_id,
+            expanded.missing.join(", ")
+        );
+    }
+    Ok(expanded)
+}
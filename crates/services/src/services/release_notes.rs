@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use db::models::task::CompletedTask;
+use serde::Serialize;
+use thiserror::Error;
+use ts_rs::TS;
+
+/// Label marking the tasks created to polish release notes, which later notes leave out
+pub const POLISH_TASK_LABEL: &str = "release-notes";
+pub const POLISH_TASK_LABEL_COLOR: &str = "#6b7280";
+
+/// A release notes request that cannot be served, with a message meant for the user
+#[derive(Debug, Error)]
+pub enum ReleaseNotesError {
+    #[error("Specify either a date or a tag for each side of the range, not both")]
+    DateAndTag,
+    #[error("Tag '{0}' not found")]
+    TagNotFound(String),
+    #[error("The start of the range must be before its end")]
+    EmptyRange,
+    #[error("No completed tasks in this range")]
+    NoTasks,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ReleaseNotes {
+    #[ts(type = "Date")]
+    pub from: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub to: DateTime<Utc>,
+    pub tasks: Vec<CompletedTask>,
+    /// Draft notes rendered as markdown
+    pub markdown: String,
+}
+
+impl ReleaseNotes {
+    pub fn new(
+        heading: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        tasks: Vec<CompletedTask>,
+    ) -> Self {
+        let markdown = render_markdown(heading, &tasks);
+        Self {
+            from,
+            to,
+            tasks,
+            markdown,
+        }
+    }
+
+    /// Prompt asking a coding agent to turn the draft into user-facing release notes.
+    pub fn polish_prompt(&self) -> String {
+        format!(
+            "Rewrite the draft release notes below into polished, user-facing release notes. \
+             Group related changes, drop internal-only details and keep every pull request link. \
+             Do not modify any files; reply with the final notes as markdown only.\n\n{}",
+            self.markdown
+        )
+    }
+}
+
+/// Render one bullet per task: the title, its pull request link when there is one, and the first
+/// paragraph of the agent summary (or the task description) indented beneath it.
+pub fn render_markdown(heading: &str, tasks: &[CompletedTask]) -> String {
+    let mut out = format!("## {heading}\n\n");
+    if tasks.is_empty() {
+        out.push_str("No completed tasks in this range.\n");
+        return out;
+    }
+
+    for task in tasks {
+        out.push_str("- ");
+        out.push_str(task.title.trim());
+        if let (Some(number), Some(url)) = (task.pr_number, task.pr_url.as_deref()) {
+            out.push_str(&format!(" ([#{number}]({url}))"));
+        }
+        out.push('\n');
+
+        let detail = task
+            .summary
+            .as_deref()
+            .or(task.description.as_deref())
+            .and_then(|text| text.split("\n\n").map(str::trim).find(|p| !p.is_empty()));
+        if let Some(detail) = detail {
+            for line in detail.lines() {
+                out.push_str("  ");
+                out.push_str(line.trim_end());
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn completed(title: &str, summary: Option<&str>, pr: Option<(i64, &str)>) -> CompletedTask {
+        CompletedTask {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            description: Some("Fallback description".to_string()),
+            summary: summary.map(str::to_string),
+            pr_number: pr.map(|(number, _)| number),
+            pr_url: pr.map(|(_, url)| url.to_string()),
+            completed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn renders_pr_links_and_first_summary_paragraph() {
+        let markdown = render_markdown(
+            "v1.2.0",
+            &[
+                completed(
+                    "Add search",
+                    Some("Adds fuzzy file search.\n\nInternal: refactored cache."),
+                    Some((42, "https://github.com/acme/app/pull/42")),
+                ),
+                completed("Fix crash", None, None),
+            ],
+        );
+
+        assert_eq!(
+            markdown,
+            "## v1.2.0\n\n\
+             - Add search ([#42](https://github.com/acme/app/pull/42))\n  Adds fuzzy file search.\n\
+             - Fix crash\n  Fallback description\n"
+        );
+    }
+}
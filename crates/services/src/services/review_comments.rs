@@ -0,0 +1,83 @@
+use db::models::diff_comment::{DiffComment, DiffCommentSide};
+use serde::Serialize;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ReviewThreadComment {
+    pub author: Option<String>,
+    pub body: String,
+    pub url: Option<String>,
+}
+
+/// Unresolved review conversation on a pull request
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ReviewThread {
+    pub path: String,
+    /// Line in the current version of the file; missing when the thread is outdated
+    pub line: Option<i64>,
+    /// The commented code changed after the comment was made
+    pub outdated: bool,
+    pub comments: Vec<ReviewThreadComment>,
+}
+
+/// Render the threads as a follow-up prompt, grouped under their file location so the agent can
+/// find the code each comment refers to.
+pub fn render_review_threads_prompt(pr_url: &str, threads: &[ReviewThread]) -> String {
+    let mut out = format!(
+        "Address the unresolved review comments on {pr_url}. For each thread, change the code or \
+         explain why no change is needed.\n"
+    );
+    for thread in threads {
+        let location = match thread.line {
+            Some(line) => format!("{}:{line}", thread.path),
+            None => thread.path.clone(),
+        };
+        out.push_str(&format!("\n## {location}"));
+        if thread.outdated {
+            out.push_str(" (outdated)");
+        }
+        out.push('\n');
+        for comment in &thread.comments {
+            let author = comment.author.as_deref().unwrap_or("unknown");
+            out.push_str(&format!("\n{author}:\n"));
+            for line in comment.body.trim().lines() {
+                out.push_str(&format!("> {line}\n"));
+            }
+        }
+    }
+    out
+}
+
+/// Render comments left on the attempt's diff as a follow-up prompt. Each comment is shown under
+/// its file and line range, with the commented code when it was captured.
+pub fn render_diff_comments_prompt(comments: &[DiffComment]) -> String {
+    let mut out = String::from(
+        "Address these review comments on your changes. For each one, change the code or explain \
+         why no change is needed.\n",
+    );
+    for comment in comments {
+        let lines = if comment.start_line == comment.end_line {
+            comment.start_line.to_string()
+        } else {
+            format!("{}-{}", comment.start_line, comment.end_line)
+        };
+        out.push_str(&format!("\n## {}:{lines}", comment.file_path));
+        if comment.side == DiffCommentSide::Old {
+            out.push_str(" (removed lines)");
+        }
+        out.push('\n');
+        if let Some(code) = comment
+            .code
+            .as_deref()
+            .filter(|code| !code.trim().is_empty())
+        {
+            let fence = if code.contains("```") { "````" } else { "```" };
+            out.push_str(&format!("\n{fence}\n{}\n{fence}\n", code.trim_end()));
+        }
+        out.push('\n');
+        for line in comment.body.trim().lines() {
+            out.push_str(&format!("> {line}\n"));
+        }
+    }
+    out
+}
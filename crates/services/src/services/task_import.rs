@@ -0,0 +1,216 @@
+//! Reading task lists exported from spreadsheets or other trackers. Each task carries a title and
+//! optionally a description, label names and a status. CSV input needs a header row naming those
+//! columns (in any order, other columns are ignored); JSON input is an array of objects.
+
+use db::models::task::TaskStatus;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskImportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ImportTasksRequest {
+    pub format: TaskImportFormat,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TaskImportRowError {
+    /// 1-based position of the task in the payload, not counting the CSV header or blank lines
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct TaskImportResult {
+    pub created: Vec<Uuid>,
+    pub errors: Vec<TaskImportRowError>,
+}
+
+#[derive(Debug, Error)]
+pub enum TaskImportError {
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid CSV: {0}")]
+    Csv(&'static str),
+    #[error("The CSV header has no title column")]
+    MissingTitleColumn,
+}
+
+/// A task read from the payload, not yet created
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedTask {
+    pub title: String,
+    pub description: Option<String>,
+    pub labels: Vec<String>,
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTask {
+    title: Option<String>,
+    description: Option<String>,
+    labels: Option<RawLabels>,
+    status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawLabels {
+    List(Vec<String>),
+    Joined(String),
+}
+
+/// Parse the payload into one entry per task. A task that cannot be imported is an `Err` with the
+/// reason, so the other tasks can still be created; only an unreadable payload fails as a whole.
+pub fn parse_tasks(
+    format: TaskImportFormat,
+    content: &str,
+) -> Result<Vec<Result<ImportedTask, String>>, TaskImportError> {
+    let raw: Vec<Result<RawTask, String>> = match format {
+        TaskImportFormat::Json => serde_json::from_str::<Vec<serde_json::Value>>(content)?
+            .into_iter()
+            .map(|value| serde_json::from_value(value).map_err(|e| e.to_string()))
+            .collect(),
+        TaskImportFormat::Csv => parse_csv_tasks(content)?.into_iter().map(Ok).collect(),
+    };
+    Ok(raw
+        .into_iter()
+        .map(|task| task.and_then(into_imported))
+        .collect())
+}
+
+fn into_imported(raw: RawTask) -> Result<ImportedTask, String> {
+    let title = raw
+        .title
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+        .ok_or_else(|| "Title is required".to_string())?;
+    let status = match raw.status.as_deref().map(str::trim) {
+        None | Some("") => TaskStatus::Todo,
+        Some(status) => parse_status(status).ok_or_else(|| format!("Unknown status '{status}'"))?,
+    };
+    let labels = match raw.labels {
+        Some(RawLabels::List(labels)) => labels,
+        Some(RawLabels::Joined(labels)) => {
+            labels.split([',', ';']).map(ToOwned::to_owned).collect()
+        }
+        None => Vec::new(),
+    };
+    let mut unique_labels: Vec<String> = Vec::new();
+    for label in labels.iter().map(|label| label.trim()) {
+        if !label.is_empty()
+            && !unique_labels
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(label))
+        {
+            unique_labels.push(label.to_string());
+        }
+    }
+
+    Ok(ImportedTask {
+        title,
+        description: raw
+            .description
+            .map(|description| description.trim().to_string())
+            .filter(|description| !description.is_empty()),
+        labels: unique_labels,
+        status,
+    })
+}
+
+/// Accepts the stored names as well as spellings like "In Progress" or "in-review"
+fn parse_status(status: &str) -> Option<TaskStatus> {
+    let normalized = status
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    match normalized.as_str() {
+        "todo" => Some(TaskStatus::Todo),
+        "inprogress" => Some(TaskStatus::InProgress),
+        "inreview" => Some(TaskStatus::InReview),
+        "done" => Some(TaskStatus::Done),
+        "cancelled" | "canceled" => Some(TaskStatus::Cancelled),
+        _ => None,
+    }
+}
+
+fn parse_csv_tasks(content: &str) -> Result<Vec<RawTask>, TaskImportError> {
+    let mut records = parse_csv(content)?
+        .into_iter()
+        .filter(|record| record.iter().any(|field| !field.trim().is_empty()));
+    let Some(header) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|field| field.trim().eq_ignore_ascii_case(name))
+    };
+    let title = column("title").ok_or(TaskImportError::MissingTitleColumn)?;
+    let description = column("description");
+    let labels = column("labels");
+    let status = column("status");
+
+    Ok(records
+        .map(|record| {
+            let field = |index: Option<usize>| index.and_then(|i| record.get(i)).cloned();
+            RawTask {
+                title: field(Some(title)),
+                description: field(description),
+                labels: field(labels).map(RawLabels::Joined),
+                status: field(status),
+            }
+        })
+        .collect())
+}
+
+/// RFC 4180 records: comma-separated fields, double-quoted when they contain commas, quotes or
+/// line breaks
+fn parse_csv(content: &str) -> Result<Vec<Vec<String>>, TaskImportError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(TaskImportError::Csv("unterminated quoted field"));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
@@ -0,0 +1,408 @@
+//! Token usage read from the coding agents' own session logs and attributed to the projects and
+//! tasks whose executor sessions wrote them. Unlike the usage recorded from an execution's output,
+//! this also counts sessions resumed outside the app and can be limited to a time range.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process_token_usage::ModelTokenUsage, executor_session::ExecutorSessionOwner,
+};
+use ignore::WalkBuilder;
+use serde::Serialize;
+use serde_json::Value;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Where Codex writes its session rollouts, `rollout-<timestamp>-<session id>.jsonl`
+pub fn codex_sessions_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".codex").join("sessions"))
+}
+
+/// Where Claude Code writes its session logs, `<project>/<session id>.jsonl`
+pub fn claude_code_projects_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("projects"))
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TaskTokenUsage {
+    pub task_id: Uuid,
+    pub task_title: String,
+    #[ts(type = "number")]
+    pub total_tokens: i64,
+    pub models: Vec<ModelTokenUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectTokenUsage {
+    pub project_id: Uuid,
+    pub project_name: String,
+    #[ts(type = "number")]
+    pub total_tokens: i64,
+    /// Most tokens first
+    pub tasks: Vec<TaskTokenUsage>,
+}
+
+/// Tokens the coding agents logged between `from` and `to`, per project and task
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct UsageAttribution {
+    #[ts(type = "Date")]
+    pub from: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub to: DateTime<Utc>,
+    /// Most tokens first
+    pub projects: Vec<ProjectTokenUsage>,
+    /// Sessions whose log is not on this machine, so their usage is left out
+    #[ts(type = "number")]
+    pub missing_sessions: usize,
+}
+
+/// The session id a log file belongs to, from its name
+fn session_id_of(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    // Codex appends the id to the rollout's timestamp
+    let id = stem.get(stem.len().checked_sub(36)?..)?;
+    Uuid::parse_str(id).ok().map(|_| id.to_string())
+}
+
+/// Every session log of the agents that keep them, by session id
+fn session_log_index() -> HashMap<String, PathBuf> {
+    let mut index = HashMap::new();
+    let dirs = [codex_sessions_dir(), claude_code_projects_dir()];
+    for dir in dirs.into_iter().flatten().filter(|dir| dir.exists()) {
+        for entry in WalkBuilder::new(&dir)
+            .hidden(false)
+            .ignore(false)
+            .git_ignore(false)
+            .git_exclude(false)
+            .build()
+            .flatten()
+        {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "jsonl")
+                && let Some(session_id) = session_id_of(path)
+            {
+                index.insert(session_id, entry.into_path());
+            }
+        }
+    }
+    index
+}
+
+fn token_count(value: &Value, key: &str) -> i64 {
+    value.get(key).and_then(Value::as_i64).unwrap_or(0)
+}
+
+fn add_to(totals: &mut HashMap<String, ModelTokenUsage>, usage: ModelTokenUsage) {
+    totals
+        .entry(usage.model.clone())
+        .or_insert_with(|| ModelTokenUsage {
+            model: usage.model.clone(),
+            ..Default::default()
+        })
+        .add(&usage);
+}
+
+/// Tokens per model a Claude Code or Codex session log records between `from` and `to`. Claude
+/// Code repeats a message's usage for each of its content blocks, so messages are counted once.
+pub fn usage_from_session_log<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<ModelTokenUsage> {
+    let mut totals: HashMap<String, ModelTokenUsage> = HashMap::new();
+    let mut claude_messages: HashSet<String> = HashSet::new();
+    let mut codex_model: Option<String> = None;
+
+    for line in lines {
+        let Ok(value) = serde_json::from_str::<Value>(line.trim()) else {
+            continue;
+        };
+        let payload = value.get("payload");
+        if value.get("type").and_then(Value::as_str) == Some("turn_context")
+            && let Some(model) = payload.and_then(|p| p.get("model")).and_then(Value::as_str)
+        {
+            codex_model = Some(model.to_string());
+            continue;
+        }
+
+        let in_range = value
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+            .map(|timestamp| timestamp.with_timezone(&Utc))
+            .is_some_and(|timestamp| timestamp >= from && timestamp < to);
+        if !in_range {
+            continue;
+        }
+
+        if value.get("type").and_then(Value::as_str) == Some("assistant")
+            && let Some(message) = value.get("message")
+            && let Some(usage) = message.get("usage")
+        {
+            if let Some(id) = message.get("id").and_then(Value::as_str)
+                && !claude_messages.insert(id.to_string())
+            {
+                continue;
+            }
+            add_to(
+                &mut totals,
+                ModelTokenUsage {
+                    model: message
+                        .get("model")
+                        .and_then(Value::as_str)
+                        .unwrap_or("unknown")
+                        .to_string(),
+                    input_tokens: token_count(usage, "input_tokens"),
+                    cached_input_tokens: token_count(usage, "cache_read_input_tokens"),
+                    cache_creation_input_tokens: token_count(usage, "cache_creation_input_tokens"),
+                    output_tokens: token_count(usage, "output_tokens"),
+                },
+            );
+            continue;
+        }
+
+        if let Some(payload) = payload
+            && payload.get("type").and_then(Value::as_str) == Some("token_count")
+            && let Some(counts) = payload
+                .get("info")
+                .and_then(|info| info.get("last_token_usage"))
+        {
+            let cached = token_count(counts, "cached_input_tokens");
+            add_to(
+                &mut totals,
+                ModelTokenUsage {
+                    model: codex_model.clone().unwrap_or_else(|| "unknown".to_string()),
+                    // OpenAI counts cached input as part of the input
+                    input_tokens: (token_count(counts, "input_tokens") - cached).max(0),
+                    cached_input_tokens: cached,
+                    cache_creation_input_tokens: 0,
+                    output_tokens: token_count(counts, "output_tokens"),
+                },
+            );
+        }
+    }
+
+    let mut usage: Vec<ModelTokenUsage> = totals
+        .into_values()
+        .filter(|usage| usage.total_tokens() > 0)
+        .collect();
+    usage.sort_by(|a, b| a.model.cmp(&b.model));
+    usage
+}
+
+fn read_session_log(
+    path: &Path,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Option<Vec<ModelTokenUsage>> {
+    let file = File::open(path)
+        .inspect_err(|e| tracing::warn!("Failed to open session log {}: {}", path.display(), e))
+        .ok()?;
+    let lines: Vec<String> = BufReader::new(file).lines().map_while(Result::ok).collect();
+    Some(usage_from_session_log(
+        lines.iter().map(String::as_str),
+        from,
+        to,
+    ))
+}
+
+/// Sum the usage of each session into its task and project. `usage_of` reads a session's log and
+/// returns `None` when there is none.
+fn attribute(
+    owners: Vec<ExecutorSessionOwner>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    mut usage_of: impl FnMut(&str) -> Option<Vec<ModelTokenUsage>>,
+) -> UsageAttribution {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut missing_sessions = 0;
+    let mut projects: HashMap<Uuid, ProjectTokenUsage> = HashMap::new();
+    let mut task_models: HashMap<Uuid, HashMap<String, ModelTokenUsage>> = HashMap::new();
+
+    for owner in owners {
+        // Follow-ups that resume a session share its id
+        if !seen.insert(owner.session_id.clone()) {
+            continue;
+        }
+        let Some(usage) = usage_of(&owner.session_id) else {
+            missing_sessions += 1;
+            continue;
+        };
+        if usage.is_empty() {
+            continue;
+        }
+        let project = projects
+            .entry(owner.project_id)
+            .or_insert_with(|| ProjectTokenUsage {
+                project_id: owner.project_id,
+                project_name: owner.project_name.clone(),
+                total_tokens: 0,
+                tasks: Vec::new(),
+            });
+        if !task_models.contains_key(&owner.task_id) {
+            project.tasks.push(TaskTokenUsage {
+                task_id: owner.task_id,
+                task_title: owner.task_title.clone(),
+                total_tokens: 0,
+                models: Vec::new(),
+            });
+        }
+        let models = task_models.entry(owner.task_id).or_default();
+        for model in usage {
+            project.total_tokens += model.total_tokens();
+            add_to(models, model);
+        }
+    }
+
+    let mut projects: Vec<ProjectTokenUsage> = projects.into_values().collect();
+    for project in &mut projects {
+        for task in &mut project.tasks {
+            let mut models: Vec<ModelTokenUsage> = task_models
+                .remove(&task.task_id)
+                .unwrap_or_default()
+                .into_values()
+                .collect();
+            models.sort_by(|a, b| a.model.cmp(&b.model));
+            task.total_tokens = models.iter().map(ModelTokenUsage::total_tokens).sum();
+            task.models = models;
+        }
+        project
+            .tasks
+            .sort_by(|a, b| b.total_tokens.cmp(&a.total_tokens));
+    }
+    projects.sort_by(|a, b| b.total_tokens.cmp(&a.total_tokens));
+
+    UsageAttribution {
+        from,
+        to,
+        projects,
+        missing_sessions,
+    }
+}
+
+/// Read the session logs on this machine for `owners` and attribute their usage between `from`
+/// and `to`. Walks the agents' log directories, so run it on a blocking thread.
+pub fn attribute_usage(
+    owners: Vec<ExecutorSessionOwner>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> UsageAttribution {
+    let index = session_log_index();
+    attribute(owners, from, to, |session_id| {
+        index
+            .get(session_id)
+            .and_then(|path| read_session_log(path, from, to))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        timestamp.parse().unwrap()
+    }
+
+    #[test]
+    fn session_ids_come_from_log_names() {
+        let id = "0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b";
+        assert_eq!(
+            session_id_of(Path::new(&format!(
+                "/home/me/.codex/sessions/2025/11/10/rollout-2025-11-10T09-00-00-{id}.jsonl"
+            ))),
+            Some(id.to_string())
+        );
+        assert_eq!(
+            session_id_of(Path::new(&format!(
+                "/home/me/.claude/projects/repo/{id}.jsonl"
+            ))),
+            Some(id.to_string())
+        );
+        assert_eq!(session_id_of(Path::new("/tmp/notes.jsonl")), None);
+    }
+
+    #[test]
+    fn only_usage_within_the_range_counts() {
+        let claude = r#"{"type":"assistant","timestamp":"2025-11-09T23:59:00Z","message":{"id":"msg_0","model":"claude-sonnet-4-5","usage":{"input_tokens":1000,"output_tokens":1000}}}
+{"type":"assistant","timestamp":"2025-11-10T09:00:00Z","message":{"id":"msg_1","model":"claude-sonnet-4-5","usage":{"input_tokens":10,"cache_read_input_tokens":200,"cache_creation_input_tokens":30,"output_tokens":40}}}
+{"type":"assistant","timestamp":"2025-11-10T09:00:01Z","message":{"id":"msg_1","model":"claude-sonnet-4-5","usage":{"input_tokens":10,"cache_read_input_tokens":200,"cache_creation_input_tokens":30,"output_tokens":40}}}"#;
+        let from = at("2025-11-10T00:00:00Z");
+        let to = at("2025-11-11T00:00:00Z");
+        assert_eq!(
+            usage_from_session_log(claude.lines(), from, to),
+            [ModelTokenUsage {
+                model: "claude-sonnet-4-5".to_string(),
+                input_tokens: 10,
+                cached_input_tokens: 200,
+                cache_creation_input_tokens: 30,
+                output_tokens: 40,
+            }]
+        );
+
+        let codex = r#"{"timestamp":"2025-11-10T09:00:00Z","type":"turn_context","payload":{"cwd":"/repo","model":"gpt-5-codex"}}
+{"timestamp":"2025-11-10T09:00:05Z","type":"event_msg","payload":{"type":"token_count","info":{"last_token_usage":{"input_tokens":500,"cached_input_tokens":300,"output_tokens":50}}}}
+{"timestamp":"2025-11-11T09:00:05Z","type":"event_msg","payload":{"type":"token_count","info":{"last_token_usage":{"input_tokens":500,"cached_input_tokens":0,"output_tokens":50}}}}"#;
+        assert_eq!(
+            usage_from_session_log(codex.lines(), from, to),
+            [ModelTokenUsage {
+                model: "gpt-5-codex".to_string(),
+                input_tokens: 200,
+                cached_input_tokens: 300,
+                cache_creation_input_tokens: 0,
+                output_tokens: 50,
+            }]
+        );
+    }
+
+    #[test]
+    fn sessions_are_summed_per_task_and_project() {
+        let project_id = Uuid::new_v4();
+        let owner = |session_id: &str, task_id: Uuid, task_title: &str| ExecutorSessionOwner {
+            session_id: session_id.to_string(),
+            executor: Some("CLAUDE_CODE".to_string()),
+            task_id,
+            task_title: task_title.to_string(),
+            project_id,
+            project_name: "board".to_string(),
+        };
+        let (small, large) = (Uuid::new_v4(), Uuid::new_v4());
+        let owners = vec![
+            owner("a", small, "small"),
+            owner("b", large, "large"),
+            owner("b", large, "large"),
+            owner("c", large, "large"),
+            owner("gone", small, "small"),
+        ];
+        let tokens = |output_tokens| {
+            vec![ModelTokenUsage {
+                model: "claude-sonnet-4-5".to_string(),
+                output_tokens,
+                ..Default::default()
+            }]
+        };
+        let now = Utc::now();
+        let attribution = attribute(owners, now, now, |session_id| match session_id {
+            "a" => Some(tokens(10)),
+            "b" => Some(tokens(100)),
+            "c" => Some(tokens(5)),
+            _ => None,
+        });
+
+        assert_eq!(attribution.missing_sessions, 1);
+        assert_eq!(attribution.projects.len(), 1);
+        let project = &attribution.projects[0];
+        assert_eq!(project.total_tokens, 115);
+        let tasks: Vec<(&str, i64)> = project
+            .tasks
+            .iter()
+            .map(|task| (task.task_title.as_str(), task.total_tokens))
+            .collect();
+        assert_eq!(tasks, [("large", 105), ("small", 10)]);
+    }
+}
@@ -0,0 +1,109 @@
+//! Recorded token usage and its estimated cost as CSV, one row per model per execution process,
+//! for expense reports and spreadsheets.
+
+use std::collections::HashMap;
+
+use db::models::execution_process_token_usage::TokenUsageRecord;
+
+use crate::services::{
+    config::ModelPrice,
+    cost::{estimate_usd, price_for_model},
+};
+
+const HEADER: &[&str] = &[
+    "started_at",
+    "project",
+    "task",
+    "agent",
+    "model",
+    "input_tokens",
+    "cached_input_tokens",
+    "cache_creation_input_tokens",
+    "output_tokens",
+    "total_tokens",
+    "cost_usd",
+    "execution_process_id",
+];
+
+/// Quote a field when it holds a separator, quote or line break, doubling any quotes
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The records as CSV with a header row. The cost is left empty for models without a known price.
+pub fn usage_csv(records: &[TokenUsageRecord], overrides: &HashMap<String, ModelPrice>) -> String {
+    let mut csv = HEADER.join(",");
+    csv.push_str("\r\n");
+    for record in records {
+        let usage = &record.usage;
+        let cost = price_for_model(&usage.model, overrides)
+            .map(|price| format!("{:.6}", estimate_usd(usage, &price)))
+            .unwrap_or_default();
+        let row = [
+            record.started_at.to_rfc3339(),
+            csv_field(&record.project_name),
+            csv_field(&record.task_title),
+            csv_field(record.executor.as_deref().unwrap_or_default()),
+            csv_field(&usage.model),
+            usage.input_tokens.to_string(),
+            usage.cached_input_tokens.to_string(),
+            usage.cache_creation_input_tokens.to_string(),
+            usage.output_tokens.to_string(),
+            usage.total_tokens().to_string(),
+            cost,
+            record.execution_process_id.to_string(),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use db::models::execution_process_token_usage::ModelTokenUsage;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn rows_are_quoted_and_priced() {
+        let record = TokenUsageRecord {
+            execution_process_id: Uuid::nil(),
+            started_at: Utc.with_ymd_and_hms(2025, 11, 3, 9, 30, 0).unwrap(),
+            executor: Some("CLAUDE_CODE".to_string()),
+            task_title: "Fix \"login\", again".to_string(),
+            project_name: "web".to_string(),
+            usage: ModelTokenUsage {
+                model: "claude-sonnet-4-5".to_string(),
+                input_tokens: 1_000_000,
+                cached_input_tokens: 0,
+                cache_creation_input_tokens: 0,
+                output_tokens: 0,
+            },
+        };
+        let unpriced = TokenUsageRecord {
+            usage: ModelTokenUsage {
+                model: "mystery".to_string(),
+                output_tokens: 5,
+                ..Default::default()
+            },
+            ..record.clone()
+        };
+        let csv = usage_csv(&[record, unpriced], &HashMap::new());
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[0], HEADER.join(","));
+        assert_eq!(
+            lines[1],
+            "2025-11-03T09:30:00+00:00,web,\"Fix \"\"login\"\", again\",CLAUDE_CODE,\
+             claude-sonnet-4-5,1000000,0,0,0,1000000,3.000000,00000000-0000-0000-0000-000000000000"
+        );
+        assert!(lines[2].contains(",mystery,0,0,0,5,5,,"));
+        assert_eq!(lines[3], "");
+    }
+}
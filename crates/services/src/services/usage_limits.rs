@@ -0,0 +1,79 @@
+//! Whether a coding agent is close enough to its rate limit that queued work should wait for the
+//! window to reset, judged from the latest usage sample.
+
+use chrono::{DateTime, Utc};
+use db::models::usage_sample::{UsageAgent, UsageSample};
+use executors::executors::BaseCodingAgent;
+use sqlx::SqlitePool;
+
+/// The sampled agent matching `agent`, for the agents whose usage is sampled
+pub fn usage_agent(agent: BaseCodingAgent) -> Option<UsageAgent> {
+    match agent {
+        BaseCodingAgent::Codex => Some(UsageAgent::Codex),
+        BaseCodingAgent::ClaudeCode => Some(UsageAgent::ClaudeCode),
+        _ => None,
+    }
+}
+
+/// When the sampled window resets, if `sample` has used at least `threshold` percent of it and it
+/// has not reset by `now`. Samples without a reset time never hold work back.
+pub fn wait_until(
+    sample: &UsageSample,
+    threshold: f64,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let resets_at = sample.resets_at.filter(|resets_at| *resets_at > now)?;
+    sample
+        .used_percent
+        .is_some_and(|used| used >= threshold)
+        .then_some(resets_at)
+}
+
+/// When work for `agent` may start, if its latest sample says it should wait
+pub async fn rate_limit_wait(
+    pool: &SqlitePool,
+    agent: BaseCodingAgent,
+    threshold: f64,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let Some(usage_agent) = usage_agent(agent) else {
+        return Ok(None);
+    };
+    let sample = UsageSample::find_latest(pool, usage_agent).await?;
+    Ok(sample.and_then(|sample| wait_until(&sample, threshold, Utc::now())))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn sample(used_percent: f64, resets_at: Option<DateTime<Utc>>) -> UsageSample {
+        UsageSample {
+            id: Uuid::nil(),
+            agent: UsageAgent::Codex,
+            used_percent: Some(used_percent),
+            secondary_used_percent: None,
+            total_tokens: None,
+            sampled_at: Utc.with_ymd_and_hms(2025, 11, 11, 9, 0, 0).unwrap(),
+            resets_at,
+        }
+    }
+
+    #[test]
+    fn only_a_nearly_used_window_that_has_not_reset_waits() {
+        let now = Utc.with_ymd_and_hms(2025, 11, 11, 9, 5, 0).unwrap();
+        let resets_at = now + Duration::minutes(30);
+        assert_eq!(
+            wait_until(&sample(95.0, Some(resets_at)), 90.0, now),
+            Some(resets_at)
+        );
+        assert_eq!(wait_until(&sample(50.0, Some(resets_at)), 90.0, now), None);
+        assert_eq!(
+            wait_until(&sample(95.0, Some(now - Duration::minutes(1))), 90.0, now),
+            None
+        );
+        assert_eq!(wait_until(&sample(95.0, None), 90.0, now), None);
+    }
+}
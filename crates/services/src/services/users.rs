@@ -0,0 +1,78 @@
+//! Accounts and login sessions for instances shared by several people. Passwords are stored as
+//! Argon2id hashes; session tokens are random and stored as SHA-256 hashes, so neither can be
+//! read back from the database.
+
+use std::sync::OnceLock;
+
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier, password_hash::SaltString};
+use chrono::Duration;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// How long a login lasts
+pub const SESSION_TTL: Duration = Duration::days(30);
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+pub fn hash_password(password: &str) -> String {
+    let salt =
+        SaltString::encode_b64(Uuid::new_v4().as_bytes()).expect("16 bytes is a valid salt length");
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("default Argon2 parameters accept any password")
+        .to_string()
+}
+
+/// Whether `password` matches `hash`. A malformed hash matches nothing.
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// Token handed to the client when it logs in
+pub fn generate_session_token() -> String {
+    format!("vks_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// What is stored for a session token
+pub fn hash_session_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Environment variable the agent token is handed to execution processes in. The task server's
+/// stdio MCP binary and the Claude Code approval hook send it as their bearer token.
+pub const AGENT_TOKEN_ENV: &str = "VIBE_SESSION_TOKEN";
+
+/// Token the server's own execution processes authenticate with. It is generated per server run
+/// and only grants what a contributor may do.
+pub fn agent_token() -> &'static str {
+    static TOKEN: OnceLock<String> = OnceLock::new();
+    TOKEN.get_or_init(generate_session_token)
+}
+
+/// Whether `token` is the agent token; hashes are compared so timing does not leak its prefix
+pub fn is_agent_token(token: &str) -> bool {
+    hash_session_token(token) == hash_session_token(agent_token())
+}
+
+/// Why an account cannot be created with these details, if it cannot
+pub fn validate_new_user(username: &str, password: &str) -> Option<String> {
+    let username = username.trim();
+    if username.is_empty() {
+        return Some("Username is required".to_string());
+    }
+    if !username
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Some("Usernames may only contain letters, numbers, '-', '_' and '.'".to_string());
+    }
+    validate_password(password)
+}
+
+pub fn validate_password(password: &str) -> Option<String> {
+    (password.chars().count() < MIN_PASSWORD_LENGTH)
+        .then(|| format!("Password must be at least {MIN_PASSWORD_LENGTH} characters"))
+}
@@ -0,0 +1,250 @@
+//! Outbound webhooks. Each lifecycle event is POSTed as JSON to the enabled webhooks subscribed
+//! to it, signed with the webhook's secret so receivers can check where it came from.
+
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::ExecutionContext,
+    task::Task,
+    task_attempt::TaskAttempt,
+    webhook::{Webhook, WebhookEvent},
+};
+use hmac::{Hmac, Mac};
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Name of the event, e.g. `attempt_finished`
+pub const EVENT_HEADER: &str = "X-Vibe-Kanban-Event";
+/// Unique per event and kept across retries, so receivers can drop duplicates
+pub const DELIVERY_HEADER: &str = "X-Vibe-Kanban-Delivery";
+/// `sha256=` followed by the hex HMAC-SHA256 of the request body keyed with the secret
+pub const SIGNATURE_HEADER: &str = "X-Vibe-Kanban-Signature";
+
+#[derive(Debug, Error)]
+enum DeliveryError {
+    #[error(transparent)]
+    Client(#[from] reqwest::Error),
+    #[error("Receiver answered {0}")]
+    Rejected(StatusCode),
+}
+
+impl DeliveryError {
+    /// Connection problems, timeouts and server-side failures are retried; other rejections
+    /// would only be repeated
+    fn should_retry(&self) -> bool {
+        match self {
+            Self::Client(_) => true,
+            Self::Rejected(status) => {
+                status.is_server_error()
+                    || matches!(
+                        *status,
+                        StatusCode::REQUEST_TIMEOUT | StatusCode::TOO_MANY_REQUESTS
+                    )
+            }
+        }
+    }
+
+    fn status(&self) -> Option<i64> {
+        match self {
+            Self::Client(e) => e.status(),
+            Self::Rejected(status) => Some(*status),
+        }
+        .map(|status| i64::from(status.as_u16()))
+    }
+}
+
+/// Secret for a new webhook when the user does not pick one
+pub fn generate_secret() -> String {
+    format!(
+        "whsec_{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+/// Value of the signature header for `body`
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+/// Envelope shared by every event; `data` holds the event-specific fields
+pub fn webhook_payload(
+    delivery_id: Uuid,
+    event: WebhookEvent,
+    project_id: Uuid,
+    occurred_at: DateTime<Utc>,
+    data: Value,
+) -> Value {
+    json!({
+        "id": delivery_id,
+        "event": event,
+        "project_id": project_id,
+        "occurred_at": occurred_at,
+        "data": data,
+    })
+}
+
+pub struct WebhookService;
+
+impl WebhookService {
+    /// Deliver `event` to every enabled webhook subscribed to it. Runs in the background;
+    /// failures are recorded on the webhook.
+    pub fn dispatch(pool: &SqlitePool, event: WebhookEvent, project_id: Uuid, data: Value) {
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            let webhooks = match Webhook::find_subscribed(&pool, project_id, event).await {
+                Ok(webhooks) => webhooks,
+                Err(e) => {
+                    tracing::error!("Failed to load webhooks for {}: {}", event, e);
+                    return;
+                }
+            };
+            if webhooks.is_empty() {
+                return;
+            }
+            let delivery_id = Uuid::new_v4();
+            let body =
+                webhook_payload(delivery_id, event, project_id, Utc::now(), data).to_string();
+            for webhook in webhooks {
+                let pool = pool.clone();
+                let body = body.clone();
+                tokio::spawn(async move {
+                    Self::deliver(&pool, &webhook, event, delivery_id, body).await
+                });
+            }
+        });
+    }
+
+    pub fn task_created(pool: &SqlitePool, task: &Task) {
+        Self::dispatch(
+            pool,
+            WebhookEvent::TaskCreated,
+            task.project_id,
+            json!({ "task": task }),
+        );
+    }
+
+    pub fn attempt_started(pool: &SqlitePool, task: &Task, task_attempt: &TaskAttempt) {
+        Self::dispatch(
+            pool,
+            WebhookEvent::AttemptStarted,
+            task.project_id,
+            json!({ "task": task, "attempt": task_attempt }),
+        );
+    }
+
+    /// The coding agent of an attempt stopped, successfully or not
+    pub fn attempt_finished(pool: &SqlitePool, ctx: &ExecutionContext) {
+        Self::dispatch(
+            pool,
+            WebhookEvent::AttemptFinished,
+            ctx.task.project_id,
+            json!({
+                "task": ctx.task,
+                "attempt": ctx.task_attempt,
+                "execution_process_id": ctx.execution_process.id,
+                "status": ctx.execution_process.status,
+                "exit_code": ctx.execution_process.exit_code,
+            }),
+        );
+    }
+
+    /// The attempt's branch landed on `target_branch`, either merged directly or through its
+    /// pull request
+    pub fn merge_completed(
+        pool: &SqlitePool,
+        task: &Task,
+        task_attempt: &TaskAttempt,
+        target_branch: &str,
+        pull_request_url: Option<&str>,
+    ) {
+        Self::dispatch(
+            pool,
+            WebhookEvent::MergeCompleted,
+            task.project_id,
+            json!({
+                "task": task,
+                "attempt": task_attempt,
+                "target_branch": target_branch,
+                "pull_request_url": pull_request_url,
+            }),
+        );
+    }
+
+    async fn deliver(
+        pool: &SqlitePool,
+        webhook: &Webhook,
+        event: WebhookEvent,
+        delivery_id: Uuid,
+        body: String,
+    ) {
+        let signature = sign_payload(&webhook.secret, body.as_bytes());
+
+        let result = (|| async {
+            let response = Self::client()?
+                .post(&webhook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, event.to_string())
+                .header(DELIVERY_HEADER, delivery_id.to_string())
+                .header(SIGNATURE_HEADER, &signature)
+                .body(body.clone())
+                .send()
+                .await?;
+            let status = response.status();
+            if status.is_success() {
+                Ok(status)
+            } else {
+                Err(DeliveryError::Rejected(status))
+            }
+        })
+        .retry(Self::retry_policy())
+        .when(|e: &DeliveryError| e.should_retry())
+        .notify(|e: &DeliveryError, dur: Duration| {
+            tracing::warn!(
+                "Webhook delivery to {} failed, retrying after {:.2}s: {}",
+                webhook.url,
+                dur.as_secs_f64(),
+                e
+            );
+        })
+        .await;
+
+        let recorded = match &result {
+            Ok(status) => {
+                Webhook::record_delivery(pool, webhook.id, Some(i64::from(status.as_u16())), None)
+                    .await
+            }
+            Err(e) => {
+                tracing::error!("Failed to deliver {} to {}: {}", event, webhook.url, e);
+                Webhook::record_delivery(pool, webhook.id, e.status(), Some(&e.to_string())).await
+            }
+        };
+        if let Err(e) = recorded {
+            tracing::warn!("Failed to record webhook delivery: {}", e);
+        }
+    }
+
+    fn client() -> Result<Client, reqwest::Error> {
+        Client::builder()
+            .timeout(Duration::from_secs(10))
+            .user_agent("vibe-kanban")
+            .build()
+    }
+
+    fn retry_policy() -> ExponentialBuilder {
+        ExponentialBuilder::default()
+            .with_min_delay(Duration::from_secs(2))
+            .with_max_delay(Duration::from_secs(60))
+            .with_max_times(4)
+            .with_jitter()
+    }
+}
@@ -6,12 +6,12 @@ use std::{
 
 use git2::{Error as GitError, Repository};
 use thiserror::Error;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use utils::shell::get_shell_command;
 
 use super::{
     git::{GitService, GitServiceError},
-    git_cli::GitCli,
+    git_cli::{GitCli, GitCliError},
 };
 
 // Global synchronization for worktree creation to prevent race conditions
@@ -43,13 +43,15 @@ pub enum WorktreeError {
 pub struct WorktreeManager;
 
 impl WorktreeManager {
-    /// Create a worktree with a new branch
+    /// Create a worktree with a new branch. With `init_submodules`, submodules are checked out
+    /// recursively once the worktree exists.
     pub async fn create_worktree(
         repo_path: &Path,
         branch_name: &str,
         worktree_path: &Path,
         base_branch: &str,
         create_branch: bool,
+        init_submodules: bool,
     ) -> Result<(), WorktreeError> {
         if create_branch {
             let repo_path_owned = repo_path.to_path_buf();
@@ -71,7 +73,7 @@ impl WorktreeManager {
             .map_err(|e| WorktreeError::TaskJoin(format!("Task join error: {e}")))??;
         }
 
-        Self::ensure_worktree_exists(repo_path, branch_name, worktree_path).await
+        Self::ensure_worktree_exists(repo_path, branch_name, worktree_path, init_submodules).await
     }
 
     /// Ensure worktree exists, recreating if necessary with proper synchronization
@@ -80,6 +82,7 @@ impl WorktreeManager {
         repo_path: &Path,
         branch_name: &str,
         worktree_path: &Path,
+        init_submodules: bool,
     ) -> Result<(), WorktreeError> {
         let path_str = worktree_path.to_string_lossy().to_string();
 
@@ -102,7 +105,51 @@ impl WorktreeManager {
 
         // If worktree doesn't exist or isn't properly set up, recreate it
         info!("Worktree needs recreation at path: {}", path_str);
-        Self::recreate_worktree_internal(repo_path, branch_name, worktree_path).await
+        Self::recreate_worktree_internal(repo_path, branch_name, worktree_path).await?;
+
+        if Self::uses_lfs(worktree_path) {
+            Self::run_post_checkout_step(worktree_path, "fetch LFS objects", |path| {
+                GitCli::new().lfs_pull(path)
+            })
+            .await;
+        }
+        if init_submodules && worktree_path.join(".gitmodules").exists() {
+            Self::run_post_checkout_step(worktree_path, "update submodules", |path| {
+                GitCli::new().submodule_update(path)
+            })
+            .await;
+        }
+        Ok(())
+    }
+
+    /// Whether the top-level `.gitattributes` routes any paths through the LFS filter
+    fn uses_lfs(worktree_path: &Path) -> bool {
+        std::fs::read_to_string(worktree_path.join(".gitattributes"))
+            .is_ok_and(|attrs| attrs.contains("filter=lfs"))
+    }
+
+    /// Run a git step in a freshly created worktree. Failures (e.g. an unreachable remote or a
+    /// missing git extension) are logged rather than failing the worktree, which is still usable.
+    async fn run_post_checkout_step<F>(worktree_path: &Path, step: &'static str, run: F)
+    where
+        F: FnOnce(&Path) -> Result<(), GitCliError> + Send + 'static,
+    {
+        let worktree_path = worktree_path.to_path_buf();
+        let result = tokio::task::spawn_blocking({
+            let worktree_path = worktree_path.clone();
+            move || run(&worktree_path)
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => info!("Worktree {}: {} done", worktree_path.display(), step),
+            Ok(Err(e)) => warn!(
+                "Worktree {}: failed to {}: {}",
+                worktree_path.display(),
+                step,
+                e
+            ),
+            Err(e) => warn!("Worktree step '{}' task failed: {}", step, e),
+        }
     }
 
     /// Internal worktree recreation function (always recreates)
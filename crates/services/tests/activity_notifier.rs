@@ -0,0 +1,63 @@
+use chrono::{Duration, Utc};
+use services::{
+    activity_feed::{
+        ActivityEntityType, ActivityEvent,
+        models::ActivityEventCta,
+        notifier::{urgent_chat_message, urgent_events},
+    },
+    services::{config::NotificationConfig, notification::ChatEvent},
+};
+use uuid::Uuid;
+
+fn event(urgency_score: u8, minutes_ago: i64) -> ActivityEvent {
+    let project_id = Uuid::new_v4();
+    let task_id = Uuid::new_v4();
+    ActivityEvent {
+        event_id: Uuid::new_v4(),
+        entity_type: ActivityEntityType::Task,
+        entity_id: task_id,
+        project_id,
+        headline: "Verification failed: Fix login".to_string(),
+        body: Some("Verify script exited with code 1".to_string()),
+        actors: Vec::new(),
+        cta: Some(ActivityEventCta {
+            label: "Open task".to_string(),
+            href: format!("/projects/{project_id}/tasks/{task_id}"),
+        }),
+        action: None,
+        urgency_score,
+        created_at: Utc::now() - Duration::minutes(minutes_ago),
+        read: false,
+    }
+}
+
+#[test]
+fn only_recent_events_at_the_threshold_are_urgent() {
+    let events = vec![event(98, 5), event(90, 10), event(75, 1), event(98, 180)];
+    let urgent = urgent_events(&events, 90, Utc::now() - Duration::hours(1));
+    let scores: Vec<u8> = urgent.iter().map(|event| event.urgency_score).collect();
+    assert_eq!(scores, vec![98, 90]);
+}
+
+#[test]
+fn urgent_messages_link_to_the_event_when_the_app_url_is_known() {
+    let urgent = event(98, 0);
+    let mut config = NotificationConfig::default();
+
+    let message = urgent_chat_message(&config, "Web app", &urgent);
+    assert_eq!(message.event, ChatEvent::UrgentActivity);
+    assert_eq!(message.title, urgent.headline);
+    assert!(message.fields.contains(&("Project", "Web app".to_string())));
+    assert!(message.fields.contains(&("Urgency", "98/100".to_string())));
+    assert_eq!(message.task_url, None);
+
+    config.app_url = Some("http://localhost:3000/".to_string());
+    let message = urgent_chat_message(&config, "Web app", &urgent);
+    assert_eq!(
+        message.task_url,
+        Some(format!(
+            "http://localhost:3000{}",
+            urgent.cta.as_ref().unwrap().href
+        ))
+    );
+}
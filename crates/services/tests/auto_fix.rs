@@ -0,0 +1,47 @@
+use db::models::execution_process::ExecutionProcessRunReason;
+use services::services::auto_fix::{MAX_AUTO_FIX_OUTPUT_CHARS, build_auto_fix_prompt};
+
+#[test]
+fn includes_script_output_and_attempt() {
+    let prompt = build_auto_fix_prompt(
+        &ExecutionProcessRunReason::VerifyScript,
+        Some(1),
+        "running 3 tests\ntest parser::nested ... FAILED\n",
+        1,
+        3,
+    );
+    assert!(prompt.starts_with("The project's verify script exited with code 1"));
+    assert!(prompt.contains("automatic fix 1 of 3"));
+    assert!(prompt.contains("```\nrunning 3 tests\ntest parser::nested ... FAILED\n```"));
+}
+
+#[test]
+fn keeps_only_the_end_of_long_output() {
+    let output = format!(
+        "{}\nerror: the real failure",
+        "x".repeat(MAX_AUTO_FIX_OUTPUT_CHARS)
+    );
+    let prompt = build_auto_fix_prompt(
+        &ExecutionProcessRunReason::CleanupScript,
+        None,
+        &output,
+        2,
+        2,
+    );
+    assert!(prompt.contains("cleanup script did not complete"));
+    assert!(prompt.contains("(earlier output omitted)"));
+    assert!(prompt.contains("error: the real failure\n```"));
+    assert!(prompt.len() < output.len() + 500);
+}
+
+#[test]
+fn notes_when_there_is_no_output() {
+    let prompt = build_auto_fix_prompt(
+        &ExecutionProcessRunReason::VerifyScript,
+        Some(2),
+        "  \n",
+        1,
+        1,
+    );
+    assert!(prompt.contains("The script produced no output."));
+}
@@ -0,0 +1,70 @@
+use serde_json::json;
+use services::services::github_service::branch_protection_from_api;
+
+#[test]
+fn unprotected_branch_has_no_protection() {
+    let details = json!({ "name": "main", "protected": false });
+    assert_eq!(
+        branch_protection_from_api("main", &details, &json!([])),
+        None
+    );
+    // The rulesets endpoint failing leaves `rules` as null
+    assert_eq!(
+        branch_protection_from_api("main", &details, &serde_json::Value::Null),
+        None
+    );
+}
+
+#[test]
+fn classic_protection_checks_are_collected() {
+    let details = json!({
+        "protected": true,
+        "protection": {
+            "enabled": true,
+            "required_status_checks": {
+                "contexts": ["ci/build"],
+                "checks": [{ "context": "ci/build" }, { "context": "lint" }]
+            }
+        }
+    });
+    let protection = branch_protection_from_api("main", &details, &json!(null)).unwrap();
+    assert!(protection.protected);
+    assert_eq!(protection.required_status_checks, vec!["ci/build", "lint"]);
+    assert!(protection.blocks_direct_merge());
+    assert_eq!(
+        protection.describe(),
+        "Branch 'main' is protected and requires passing checks (ci/build, lint)"
+    );
+}
+
+#[test]
+fn rulesets_add_review_and_check_requirements() {
+    let details = json!({ "protected": false });
+    let rules = json!([
+        { "type": "deletion" },
+        {
+            "type": "pull_request",
+            "parameters": { "required_approving_review_count": 2 }
+        },
+        {
+            "type": "required_status_checks",
+            "parameters": { "required_status_checks": [{ "context": "test" }] }
+        }
+    ]);
+    let protection = branch_protection_from_api("release", &details, &rules).unwrap();
+    assert!(protection.requires_pull_request);
+    assert_eq!(protection.required_approving_reviews, 2);
+    assert_eq!(protection.required_status_checks, vec!["test"]);
+    assert_eq!(
+        protection.describe(),
+        "Branch 'release' is protected and requires 2 approving review(s) and passing checks (test)"
+    );
+}
+
+#[test]
+fn protection_without_visible_requirements_does_not_block() {
+    let details = json!({ "protected": true, "protection": { "enabled": true } });
+    let protection = branch_protection_from_api("main", &details, &json!([])).unwrap();
+    assert!(!protection.blocks_direct_merge());
+    assert_eq!(protection.describe(), "Branch 'main' is protected");
+}
@@ -0,0 +1,67 @@
+use chrono::{Duration, TimeZone, Utc};
+use services::services::{
+    calendar_feed::{
+        CalendarEvent, ics_escape, render_calendar, sign_feed, upcoming_runs, verify_feed_signature,
+    },
+    cron_schedule::CronSchedule,
+};
+use uuid::Uuid;
+
+#[test]
+fn calendars_escape_and_fold_event_text() {
+    assert_eq!(ics_escape("a, b; c\\d\r\ne"), r"a\, b\; c\\d\ne");
+
+    let now = Utc.with_ymd_and_hms(2025, 10, 31, 9, 0, 0).unwrap();
+    let event = CalendarEvent {
+        uid: "task-1@vibe-kanban".to_string(),
+        summary: "Due: Ship it, finally".to_string(),
+        description: Some("é".repeat(60)),
+        url: None,
+        start: now + Duration::hours(3),
+    };
+    let calendar = render_calendar("Web app", &[event], now);
+
+    assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+    assert!(calendar.contains("X-WR-CALNAME:Web app\r\n"));
+    assert!(calendar.contains("DTSTAMP:20251031T090000Z\r\n"));
+    assert!(calendar.contains("DTSTART:20251031T120000Z\r\n"));
+    assert!(calendar.contains("SUMMARY:Due: Ship it\\, finally\r\n"));
+    // Long lines are folded at 75 octets without splitting characters
+    assert!(calendar.split("\r\n").all(|line| line.len() <= 75));
+    assert!(calendar.contains("\r\n é"));
+    assert!(!calendar.contains("URL:"));
+}
+
+#[test]
+fn scheduled_runs_stop_at_the_horizon_or_limit() {
+    let from = Utc.with_ymd_and_hms(2025, 10, 31, 9, 0, 0).unwrap();
+    let daily = CronSchedule::parse("@daily").unwrap();
+    let runs = upcoming_runs(&daily, from, from + Duration::days(3), 100);
+    assert_eq!(
+        runs,
+        vec![
+            Utc.with_ymd_and_hms(2025, 11, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 11, 2, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 11, 3, 0, 0, 0).unwrap(),
+        ]
+    );
+
+    let every_minute = CronSchedule::parse("* * * * *").unwrap();
+    assert_eq!(
+        upcoming_runs(&every_minute, from, from + Duration::days(1), 10).len(),
+        10
+    );
+}
+
+#[test]
+fn feed_signatures_are_bound_to_the_project_and_key() {
+    let project = Uuid::new_v4();
+    let signature = sign_feed(b"key", project);
+
+    assert!(verify_feed_signature(b"key", project, &signature));
+    assert!(!verify_feed_signature(b"other key", project, &signature));
+    assert!(!verify_feed_signature(b"key", Uuid::new_v4(), &signature));
+    assert!(!verify_feed_signature(b"key", project, &signature[1..]));
+    assert!(!verify_feed_signature(b"key", project, ""));
+}
@@ -0,0 +1,75 @@
+use db::models::execution_process::ExecutionProcessStatus;
+use services::services::{
+    config::NotificationConfig,
+    notification::{
+        ChatEvent, ChatMessage, discord_payload, exit_status_label, slack_message, task_link,
+    },
+};
+use uuid::Uuid;
+
+fn failed_run(task_url: Option<String>) -> ChatMessage {
+    ChatMessage {
+        event: ChatEvent::AttemptFailed,
+        title: "Task Complete: Fix <script> & co".to_string(),
+        summary: "❌ Execution failed".to_string(),
+        fields: vec![
+            ("Branch", "vk/1a2b-fix-script".to_string()),
+            (
+                "Exit status",
+                exit_status_label(&ExecutionProcessStatus::Failed, Some(1)),
+            ),
+        ],
+        task_url,
+    }
+}
+
+#[test]
+fn slack_messages_escape_markup_and_link_the_task() {
+    let project_id = Uuid::new_v4();
+    let task_id = Uuid::new_v4();
+
+    let mut config = NotificationConfig::default();
+    assert_eq!(task_link(&config, project_id, task_id, None), None);
+    config.app_url = Some(" http://localhost:3000/ ".to_string());
+    let link = task_link(&config, project_id, task_id, None).unwrap();
+    assert_eq!(
+        link,
+        format!("http://localhost:3000/projects/{project_id}/tasks/{task_id}")
+    );
+
+    assert_eq!(
+        slack_message(&failed_run(Some(link.clone()))),
+        format!(
+            "*Task Complete: Fix &lt;script&gt; &amp; co*\n❌ Execution failed\n\
+             *Branch:* vk/1a2b-fix-script\n*Exit status:* Failed (exit code 1)\n\
+             <{link}|Open task>"
+        )
+    );
+}
+
+#[test]
+fn discord_embeds_carry_the_run_details() {
+    let payload = discord_payload(&failed_run(None));
+    let embed = &payload["embeds"][0];
+    assert_eq!(embed["title"], "Task Complete: Fix <script> & co");
+    assert_eq!(embed["color"], 0xe74c3c);
+    assert!(embed.get("url").is_none());
+    assert_eq!(embed["fields"][0]["name"], "Branch");
+    assert_eq!(embed["fields"][0]["value"], "vk/1a2b-fix-script");
+    assert_eq!(embed["fields"][1]["value"], "Failed (exit code 1)");
+
+    let long_title = ChatMessage {
+        title: "x".repeat(300),
+        ..failed_run(Some("http://localhost:3000/t".to_string()))
+    };
+    let payload = discord_payload(&long_title);
+    assert_eq!(
+        payload["embeds"][0]["title"]
+            .as_str()
+            .unwrap()
+            .chars()
+            .count(),
+        256
+    );
+    assert_eq!(payload["embeds"][0]["url"], "http://localhost:3000/t");
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, TimeZone, Utc};
+use services::services::cron_schedule::{CronError, CronSchedule};
+
+fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+        .unwrap()
+}
+
+#[test]
+fn rejects_malformed_expressions() {
+    assert_eq!(
+        CronSchedule::parse("0 9 * *"),
+        Err(CronError::FieldCount(4))
+    );
+    for expression in [
+        "60 * * * *",
+        "* 24 * * *",
+        "* * 0 * *",
+        "*/0 * * * *",
+        "5-1 * * * *",
+    ] {
+        assert!(
+            matches!(
+                CronSchedule::parse(expression),
+                Err(CronError::InvalidField { .. })
+            ),
+            "{expression} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn weekly_schedule_fires_on_the_next_matching_day() {
+    // Mondays at 09:00; 2025-10-15 is a Wednesday
+    let schedule = CronSchedule::parse("0 9 * * 1").unwrap();
+    assert_eq!(
+        schedule.next_after(at(2025, 10, 15, 12, 0)),
+        Some(at(2025, 10, 20, 9, 0))
+    );
+    // Strictly after: firing time itself moves on to the next week
+    assert_eq!(
+        schedule.next_after(at(2025, 10, 20, 9, 0)),
+        Some(at(2025, 10, 27, 9, 0))
+    );
+    assert_eq!(
+        CronSchedule::parse("@weekly").unwrap(),
+        CronSchedule::parse("0 0 * * 7").unwrap()
+    );
+}
+
+#[test]
+fn steps_ranges_and_lists() {
+    let schedule = CronSchedule::parse("*/15 9-17 * * 1-5").unwrap();
+    assert_eq!(
+        schedule.next_after(at(2025, 10, 17, 17, 50)),
+        Some(at(2025, 10, 20, 9, 0))
+    );
+    let schedule = CronSchedule::parse("30 6,18 * * *").unwrap();
+    assert_eq!(
+        schedule.next_after(at(2025, 10, 15, 7, 0)),
+        Some(at(2025, 10, 15, 18, 30))
+    );
+}
+
+#[test]
+fn restricted_day_fields_match_either_day() {
+    // The 1st of the month or any Friday
+    let schedule = CronSchedule::parse("0 0 1 * 5").unwrap();
+    assert_eq!(
+        schedule.next_after(at(2025, 10, 25, 0, 0)),
+        Some(at(2025, 10, 31, 0, 0))
+    );
+    assert_eq!(
+        schedule.next_after(at(2025, 10, 31, 0, 0)),
+        Some(at(2025, 11, 1, 0, 0))
+    );
+}
+
+#[test]
+fn impossible_dates_never_fire() {
+    let schedule = CronSchedule::parse("0 0 30 2 *").unwrap();
+    assert_eq!(schedule.next_after(at(2025, 1, 1, 0, 0)), None);
+}
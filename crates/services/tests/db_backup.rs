@@ -0,0 +1,35 @@
+use services::services::db_backup::{list_backups, prune_backups};
+
+#[test]
+fn pruning_keeps_the_newest_backups() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in [
+        "db-20251101T030000.000Z.sqlite",
+        "db-20251103T030000.000Z.sqlite",
+        "db-20251102T030000.000Z.sqlite",
+        "notes.txt",
+    ] {
+        std::fs::write(dir.path().join(name), b"backup").unwrap();
+    }
+
+    assert_eq!(prune_backups(dir.path(), 2).unwrap(), 1);
+
+    let remaining: Vec<String> = list_backups(dir.path())
+        .unwrap()
+        .into_iter()
+        .map(|backup| backup.path.rsplit('/').next().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        remaining,
+        vec![
+            "db-20251103T030000.000Z.sqlite",
+            "db-20251102T030000.000Z.sqlite"
+        ]
+    );
+    assert!(dir.path().join("notes.txt").exists());
+    assert!(
+        list_backups(&dir.path().join("missing"))
+            .unwrap()
+            .is_empty()
+    );
+}
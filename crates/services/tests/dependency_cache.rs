@@ -0,0 +1,96 @@
+#![cfg(unix)]
+
+use std::fs;
+
+use db::models::project::DependencyShareMode;
+use git2::Repository;
+use services::services::dependency_cache::{is_valid_shared_dir, share_dependency_dir};
+use tempfile::TempDir;
+
+fn repo_with_node_modules(root: &TempDir) -> std::path::PathBuf {
+    let repo_path = root.path().join("repo");
+    Repository::init(&repo_path).unwrap();
+    fs::create_dir_all(repo_path.join("node_modules/left-pad")).unwrap();
+    fs::write(repo_path.join("node_modules/left-pad/index.js"), "pad").unwrap();
+    repo_path
+}
+
+#[test]
+fn shared_dirs_must_stay_inside_the_repository() {
+    assert!(is_valid_shared_dir("node_modules"));
+    assert!(is_valid_shared_dir("frontend/node_modules"));
+    assert!(!is_valid_shared_dir(""));
+    assert!(!is_valid_shared_dir("../node_modules"));
+    assert!(!is_valid_shared_dir("/usr/lib"));
+}
+
+#[tokio::test]
+async fn symlinked_dir_is_excluded_from_git() {
+    let root = TempDir::new().unwrap();
+    let repo_path = repo_with_node_modules(&root);
+    let worktree_path = root.path().join("wt");
+    fs::create_dir_all(&worktree_path).unwrap();
+
+    let shared = share_dependency_dir(
+        &repo_path,
+        &worktree_path,
+        "node_modules",
+        DependencyShareMode::Symlink,
+    )
+    .await
+    .unwrap();
+    assert!(shared);
+    let link = worktree_path.join("node_modules");
+    assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+    assert_eq!(
+        fs::read_to_string(link.join("left-pad/index.js")).unwrap(),
+        "pad"
+    );
+    let exclude = fs::read_to_string(repo_path.join(".git/info/exclude")).unwrap();
+    assert_eq!(
+        exclude
+            .lines()
+            .filter(|line| *line == "/node_modules")
+            .count(),
+        1
+    );
+
+    // Already present in the worktree, and missing from the main checkout
+    for dir in ["node_modules", ".venv"] {
+        assert!(
+            !share_dependency_dir(
+                &repo_path,
+                &worktree_path,
+                dir,
+                DependencyShareMode::Symlink
+            )
+            .await
+            .unwrap()
+        );
+    }
+}
+
+#[tokio::test]
+async fn cloned_dir_is_independent_of_the_main_checkout() {
+    let root = TempDir::new().unwrap();
+    let repo_path = repo_with_node_modules(&root);
+    let worktree_path = root.path().join("wt");
+    fs::create_dir_all(&worktree_path).unwrap();
+
+    assert!(
+        share_dependency_dir(
+            &repo_path,
+            &worktree_path,
+            "node_modules",
+            DependencyShareMode::Clone,
+        )
+        .await
+        .unwrap()
+    );
+    let cloned = worktree_path.join("node_modules/left-pad/index.js");
+    fs::write(&cloned, "changed").unwrap();
+    assert_eq!(
+        fs::read_to_string(repo_path.join("node_modules/left-pad/index.js")).unwrap(),
+        "pad"
+    );
+}
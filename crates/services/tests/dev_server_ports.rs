@@ -0,0 +1,16 @@
+use std::net::TcpListener;
+
+use services::services::dev_server_ports::pick_free_port;
+
+#[test]
+fn skips_ports_that_are_taken_or_bound() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let bound = listener.local_addr().unwrap().port();
+
+    assert_eq!(pick_free_port(bound..=bound, &[]), None);
+    assert_eq!(pick_free_port(bound..=bound + 1, &[bound + 1]), None);
+    // Unless another process grabbed it in the meantime, the next port is free
+    if let Some(port) = pick_free_port(bound..=bound + 1, &[]) {
+        assert_eq!(port, bound + 1);
+    }
+}
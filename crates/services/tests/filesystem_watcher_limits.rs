@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use db::models::project::ProjectWatcherSettings;
+use services::services::filesystem_watcher::{
+    DEFAULT_DIFF_IGNORE_PATTERNS, DEFAULT_EXCLUDE_PATTERNS, DiffIgnore, EventRateLimiter,
+    WatcherOptions, validate_exclude_patterns,
+};
+
+#[test]
+fn rate_limiter_delays_batches_over_the_cap_until_the_next_window() {
+    let start = Instant::now();
+    let mut limiter = EventRateLimiter::new(10, start);
+
+    assert_eq!(limiter.delay_for(6, start), Duration::ZERO);
+    assert_eq!(
+        limiter.delay_for(6, start + Duration::from_millis(250)),
+        Duration::from_millis(750)
+    );
+    // The delayed batch consumed the next window's budget
+    assert_eq!(
+        limiter.delay_for(6, start + Duration::from_millis(1000)),
+        Duration::from_millis(1000)
+    );
+    // Oversized batches still go through once a window is fresh
+    assert_eq!(
+        limiter.delay_for(50, start + Duration::from_secs(5)),
+        Duration::ZERO
+    );
+}
+
+#[test]
+fn watcher_options_apply_project_overrides() {
+    let defaults = WatcherOptions::for_project(&ProjectWatcherSettings::default());
+    assert_eq!(defaults.exclude_patterns, DEFAULT_EXCLUDE_PATTERNS);
+    assert!(defaults.max_events_per_second.is_some());
+
+    let custom = WatcherOptions::for_project(&ProjectWatcherSettings {
+        exclude_patterns: Some(vec!["dist/".to_string()]),
+        max_events_per_second: Some(0),
+        diff_ignore_patterns: Some(Vec::new()),
+    });
+    assert_eq!(custom.exclude_patterns, vec!["dist/".to_string()]);
+    assert_eq!(custom.max_events_per_second, None);
+    assert!(custom.diff_ignore_patterns.is_empty());
+    assert_eq!(defaults.diff_ignore_patterns, DEFAULT_DIFF_IGNORE_PATTERNS);
+
+    assert!(validate_exclude_patterns(&["target/".to_string(), "*.log".to_string()]).is_ok());
+    assert!(validate_exclude_patterns(&["[".to_string()]).is_err());
+}
+
+#[test]
+fn diff_ignore_matches_nested_paths_and_directories() {
+    let ignore = DiffIgnore::new(&[
+        "*.lock".to_string(),
+        "generated/".to_string(),
+        "package-lock.json".to_string(),
+    ])
+    .unwrap();
+
+    assert!(ignore.is_ignored("Cargo.lock"));
+    assert!(ignore.is_ignored("frontend/package-lock.json"));
+    assert!(ignore.is_ignored("src/generated/types.ts"));
+    assert!(!ignore.is_ignored("src/lock.rs"));
+    assert!(!DiffIgnore::empty().is_ignored("Cargo.lock"));
+}
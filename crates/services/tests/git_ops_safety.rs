@@ -6,7 +6,7 @@ use std::{
 
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
 use services::services::{
-    git::GitService,
+    git::{ConflictOp, ConflictResolution, GitService, parse_conflict_hunks},
     git_cli::{GitCli, GitCliError},
 };
 use tempfile::TempDir;
@@ -406,6 +406,77 @@ fn push_and_fetch_roundtrip_updates_tracking_branch() {
     );
 }
 
+#[test]
+fn push_branch_updates_pushes_only_when_remote_is_not_ahead() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote_path = temp_dir.path().join("remote.git");
+    Repository::init_bare(&remote_path).expect("init bare remote");
+    let remote_url = remote_path.to_str().expect("remote path str");
+
+    let service = GitService::new();
+    let local_path = temp_dir.path().join("local");
+    service
+        .initialize_repo_with_main_branch(&local_path)
+        .expect("init local repo");
+    let local_repo = Repository::open(&local_path).expect("open local repo");
+    configure_user(&local_repo);
+    local_repo.remote("origin", remote_url).expect("add remote");
+    create_branch_from_head(&local_repo, "feature");
+    checkout_branch(&local_repo, "feature");
+    write_file(&local_path, "a.txt", "first\n");
+    commit_all(&local_repo, "first");
+    service
+        .push_with_token(&local_path, "feature", Some("origin"), "dummy-token")
+        .expect("initial push");
+
+    // Nothing new to push
+    let outcome = service
+        .push_branch_updates(&local_path, "feature", "dummy-token")
+        .expect("no-op push");
+    assert_eq!(outcome.pushed_commits, 0);
+    assert_eq!(outcome.remote_commits_behind, 0);
+
+    // A follow-up commit goes to the existing remote branch
+    write_file(&local_path, "a.txt", "second\n");
+    commit_all(&local_repo, "second");
+    let outcome = service
+        .push_branch_updates(&local_path, "feature", "dummy-token")
+        .expect("push update");
+    assert_eq!(outcome.pushed_commits, 1);
+    let remote_repo = Repository::open_bare(&remote_path).expect("open bare remote");
+    assert_eq!(
+        remote_repo
+            .find_reference("refs/heads/feature")
+            .unwrap()
+            .target(),
+        local_repo.head().unwrap().target()
+    );
+
+    // Someone else pushes to the PR branch; local work is held back
+    let other_path = temp_dir.path().join("other");
+    let other_repo = Repository::clone(remote_url, &other_path).expect("clone other");
+    configure_user(&other_repo);
+    let remote_feature = other_repo
+        .find_reference("refs/remotes/origin/feature")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    other_repo.branch("feature", &remote_feature, true).unwrap();
+    checkout_branch(&other_repo, "feature");
+    write_file(&other_path, "b.txt", "reviewer fix\n");
+    commit_all(&other_repo, "reviewer fix");
+    push_ref(&other_repo, "refs/heads/feature", "refs/heads/feature");
+
+    write_file(&local_path, "a.txt", "third\n");
+    commit_all(&local_repo, "third");
+    let outcome = service
+        .push_branch_updates(&local_path, "feature", "dummy-token")
+        .expect("status without push");
+    assert_eq!(outcome.pushed_commits, 0);
+    assert_eq!(outcome.remote_commits_ahead, 1);
+    assert_eq!(outcome.remote_commits_behind, 1);
+}
+
 #[test]
 fn rebase_preserves_untracked_files() {
     let td = TempDir::new().unwrap();
@@ -715,6 +786,118 @@ fn rebase_refuses_to_abort_existing_rebase() {
     // Note: We do not auto-abort; user should resolve or abort explicitly
 }
 
+#[test]
+fn rebase_conflict_details_report_files_and_hunks() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+
+    let svc = GitService::new();
+    let _ = svc
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            "new-base",
+            "old-base",
+            "feature",
+            None,
+        )
+        .expect_err("rebase should stop on conflicts");
+
+    let conflicts = svc.get_conflict_details(&worktree_path).unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].path, "conflict.txt");
+    assert_eq!(conflicts[0].hunks.len(), 1);
+    let hunk = &conflicts[0].hunks[0];
+    assert_eq!(hunk.start_line, 1);
+    assert_eq!(hunk.ours, "new-base version\n");
+    assert_eq!(hunk.theirs, "feature version\n");
+}
+
+#[test]
+fn resolving_rebase_conflicts_continues_and_commits() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+
+    let svc = GitService::new();
+    let _ = svc
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            "new-base",
+            "old-base",
+            "feature",
+            None,
+        )
+        .expect_err("rebase should stop on conflicts");
+
+    let versions = svc.get_conflict_versions(&worktree_path).unwrap();
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].path, "conflict.txt");
+    assert_eq!(versions[0].ours.as_deref(), Some("new-base version\n"));
+    assert_eq!(versions[0].theirs.as_deref(), Some("feature version\n"));
+    assert!(!versions[0].is_binary);
+
+    assert!(
+        svc.resolve_conflict(&worktree_path, "missing.txt", &ConflictResolution::Ours)
+            .is_err(),
+        "only conflicted paths can be resolved"
+    );
+    svc.resolve_conflict(
+        &worktree_path,
+        "conflict.txt",
+        &ConflictResolution::Custom {
+            content: "merged version\n".to_string(),
+        },
+    )
+    .unwrap();
+
+    let op = svc.continue_conflicted_operation(&worktree_path).unwrap();
+    assert_eq!(op, Some(ConflictOp::Rebase));
+    assert!(!svc.is_rebase_in_progress(&worktree_path).unwrap());
+    assert!(svc.get_conflicted_files(&worktree_path).unwrap().is_empty());
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("conflict.txt")).unwrap(),
+        "merged version\n"
+    );
+    assert!(svc.is_worktree_clean(&worktree_path).unwrap());
+}
+
+#[test]
+fn merge_preview_reports_conflicts_without_moving_refs() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, _worktree_path) = setup_direct_conflict_repo(&td);
+    let svc = GitService::new();
+    let main_before = svc.get_branch_oid(&repo_path, "main").unwrap();
+
+    let preview = svc.preview_merge(&repo_path, "feature", "main").unwrap();
+    assert!(!preview.clean);
+    assert_eq!(preview.commits_behind, 1);
+    assert_eq!(preview.conflicted_files, vec!["conflict.txt".to_string()]);
+    assert_eq!(svc.get_branch_oid(&repo_path, "main").unwrap(), main_before);
+
+    let td = TempDir::new().unwrap();
+    let (repo_path, _worktree_path) = setup_repo_with_worktree(&td);
+    let preview = svc
+        .preview_merge(&repo_path, "feature", "old-base")
+        .unwrap();
+    assert!(preview.clean);
+    assert_eq!(preview.commits_behind, 0);
+    assert_eq!(preview.files_changed, 1);
+    assert_eq!(preview.additions, 1);
+    assert!(preview.conflicted_files.is_empty());
+}
+
+#[test]
+fn parse_conflict_hunks_handles_diff3_style_and_unterminated_regions() {
+    let content = "keep\n<<<<<<< HEAD\nours\n||||||| base\nbase\n=======\ntheirs\n>>>>>>> abc123\ntail\n<<<<<<< HEAD\ndangling\n";
+    let hunks = parse_conflict_hunks(content);
+    assert_eq!(hunks.len(), 1);
+    assert_eq!(hunks[0].start_line, 2);
+    assert_eq!(hunks[0].ours, "ours\n");
+    assert_eq!(hunks[0].base.as_deref(), Some("base\n"));
+    assert_eq!(hunks[0].theirs, "theirs\n");
+}
+
 #[test]
 fn rebase_fast_forwards_when_no_unique_commits() {
     let td = TempDir::new().unwrap();
@@ -1410,3 +1593,90 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+#[test]
+fn worktree_snapshot_survives_hard_reset_and_restores() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let svc = GitService::new();
+    let head = svc.get_head_info(&worktree_path).unwrap().oid;
+
+    // Nothing to save in a clean worktree
+    assert!(
+        svc.snapshot_worktree(&worktree_path, "attempt-1", "retry")
+            .unwrap()
+            .is_none()
+    );
+
+    write_file(&worktree_path, "feat.txt", "uncommitted edit\n");
+    write_file(&worktree_path, "notes.txt", "untracked file\n");
+    let snapshot = svc
+        .snapshot_worktree(&worktree_path, "attempt-1", "retry")
+        .unwrap()
+        .expect("dirty worktree is snapshotted");
+    assert_eq!(snapshot.reason, "retry");
+    // Taking the snapshot leaves the worktree untouched
+    assert!(!svc.is_worktree_clean(&worktree_path).unwrap());
+
+    svc.reset_worktree_to_commit(&worktree_path, &head, true)
+        .unwrap();
+    fs::remove_file(worktree_path.join("notes.txt")).unwrap();
+    assert!(svc.is_worktree_clean(&worktree_path).unwrap());
+
+    let listed = svc
+        .list_worktree_snapshots(&repo_path, "attempt-1")
+        .unwrap();
+    assert_eq!(
+        listed.iter().map(|s| s.oid.as_str()).collect::<Vec<_>>(),
+        [snapshot.oid.as_str()]
+    );
+    assert!(
+        svc.list_worktree_snapshots(&repo_path, "attempt-2")
+            .unwrap()
+            .is_empty()
+    );
+
+    svc.restore_worktree_snapshot(&worktree_path, &snapshot.oid)
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("feat.txt")).unwrap(),
+        "uncommitted edit\n"
+    );
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("notes.txt")).unwrap(),
+        "untracked file\n"
+    );
+    assert_eq!(svc.get_head_info(&worktree_path).unwrap().oid, head);
+}
+
+#[test]
+fn reassign_worktree_renames_branch_and_keeps_untracked_files() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let svc = GitService::new();
+    let head = svc.get_head_info(&worktree_path).unwrap().oid;
+    // Stands in for dependencies installed by a setup script
+    write_file(&worktree_path, "node_modules/dep.js", "installed\n");
+
+    let new_path = td.path().join("wt-attempt");
+    let moved_to = svc
+        .reassign_worktree(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "attempt-branch",
+            &new_path,
+        )
+        .unwrap();
+    assert_eq!(moved_to, new_path);
+    assert!(!worktree_path.exists());
+
+    let info = svc.get_head_info(&new_path).unwrap();
+    assert_eq!(info.branch, "attempt-branch");
+    assert_eq!(info.oid, head);
+    assert_eq!(
+        fs::read_to_string(new_path.join("node_modules/dep.js")).unwrap(),
+        "installed\n"
+    );
+    assert!(!svc.branch_exists(&repo_path, "feature").unwrap());
+}
@@ -4,12 +4,18 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use services::services::{
-    git::{DiffTarget, GitService},
-    github_service::{GitHubRepoInfo, GitHubServiceError},
+use services::{
+    activity_feed::recorder::commit_author_actors,
+    services::{
+        config::{CommitSigningConfig, CommitSigningFormat},
+        git::{Commit, DiffTarget, GitService},
+        gitea_service::GiteaRepoInfo,
+        github_service::{GitHubRepoInfo, GitHubServiceError},
+        gitlab_service::GitLabRepoInfo,
+    },
 };
 use tempfile::TempDir;
-use utils::diff::DiffChangeKind;
+use utils::diff::{DiffChangeKind, HunkRange};
 
 fn write_file<P: AsRef<Path>>(base: P, rel: &str, content: &str) {
     let path = base.as_ref().join(rel);
@@ -328,6 +334,86 @@ fn delete_file_and_commit_creates_new_commit() {
     assert!(!repo_path.join("to_delete.txt").exists());
 }
 
+#[test]
+fn range_diff_covers_only_commits_in_the_range() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "first.txt", "one\n");
+    s.commit(&repo_path, "first").unwrap();
+    let before = s.get_head_info(&repo_path).unwrap().oid;
+    write_file(&repo_path, "second.txt", "two\n");
+    write_file(&repo_path, "first.txt", "one\nmore\n");
+    s.commit(&repo_path, "second").unwrap();
+    let after = s.get_head_info(&repo_path).unwrap().oid;
+
+    let diffs = s
+        .get_diffs(
+            DiffTarget::Range {
+                repo_path: Path::new(&repo_path),
+                from_sha: &before,
+                to_sha: &after,
+            },
+            None,
+        )
+        .unwrap();
+    let mut paths: Vec<_> = diffs.iter().filter_map(|d| d.new_path.clone()).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["first.txt", "second.txt"]);
+    let first = diffs
+        .iter()
+        .find(|d| d.new_path.as_deref() == Some("first.txt"))
+        .unwrap();
+    assert_eq!(first.old_content.as_deref(), Some("one\n"));
+    assert!(matches!(first.change, DiffChangeKind::Modified));
+}
+
+#[test]
+fn revert_worktree_change_restores_files_and_hunks() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "a.txt", "1\n2\n3\n4\n5\n6\n7\n8\n");
+    write_file(&repo_path, "b.txt", "keep me\n");
+    let s = GitService::new();
+    s.commit(&repo_path, "base").unwrap();
+    let base = Commit::new(git2::Oid::from_str(&s.get_head_info(&repo_path).unwrap().oid).unwrap());
+
+    write_file(&repo_path, "a.txt", "1\ntwo\n3\n4\n5\n6\nseven\n8\n");
+    write_file(&repo_path, "c.txt", "new\n");
+    fs::remove_file(repo_path.join("b.txt")).unwrap();
+
+    let second_hunk = HunkRange {
+        old_start: 6,
+        old_lines: 3,
+        new_start: 6,
+        new_lines: 3,
+    };
+    s.revert_worktree_change(&repo_path, &base, "a.txt", None, Some(&second_hunk))
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(repo_path.join("a.txt")).unwrap(),
+        "1\ntwo\n3\n4\n5\n6\n7\n8\n"
+    );
+    assert!(
+        s.revert_worktree_change(&repo_path, &base, "a.txt", None, Some(&second_hunk))
+            .is_err()
+    );
+
+    s.revert_worktree_change(&repo_path, &base, "c.txt", None, None)
+        .unwrap();
+    assert!(!repo_path.join("c.txt").exists());
+    s.revert_worktree_change(&repo_path, &base, "b.txt", None, None)
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(repo_path.join("b.txt")).unwrap(),
+        "keep me\n"
+    );
+    assert!(
+        s.revert_worktree_change(&repo_path, &base, "../a.txt", None, None)
+            .is_err()
+    );
+}
+
 #[test]
 fn get_github_repo_info_parses_origin() {
     let td = TempDir::new().unwrap();
@@ -402,6 +488,45 @@ fn get_branch_diffs_between_branches() {
     assert!(diffs.iter().any(|d| d.new_path.as_deref() == Some("b.txt")));
 }
 
+#[test]
+fn lfs_pointer_changes_are_marked_without_content() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let pointer = |oid: &str, size: u64| {
+        format!("version https://git-lfs.github.com/spec/v1\noid sha256:{oid}\nsize {size}\n")
+    };
+    write_file(&repo_path, "asset.bin", &pointer("aaaa", 10));
+    let _ = s.commit(&repo_path, "add asset").unwrap();
+
+    s.create_branch(&repo_path, "feature").unwrap();
+    s.checkout_branch(&repo_path, "feature").unwrap();
+    write_file(&repo_path, "asset.bin", &pointer("bbbb", 20));
+    let _ = s.commit(&repo_path, "update asset").unwrap();
+
+    let diffs = s
+        .get_diffs(
+            DiffTarget::Branch {
+                repo_path: Path::new(&repo_path),
+                branch_name: "feature",
+                base_branch: "main",
+            },
+            None,
+        )
+        .unwrap();
+    let asset = diffs
+        .iter()
+        .find(|d| d.new_path.as_deref() == Some("asset.bin"))
+        .expect("asset diff");
+    let lfs = asset.lfs.as_ref().expect("marked as LFS");
+    assert_eq!(
+        lfs.old.as_ref().map(|p| p.oid.as_str()),
+        Some("sha256:aaaa")
+    );
+    assert_eq!(lfs.new.as_ref().map(|p| p.size), Some(20));
+    assert!(asset.old_content.is_none() && asset.new_content.is_none());
+}
+
 #[test]
 fn worktree_diff_respects_path_filter() {
     // Use git CLI status diff under the hood
@@ -620,6 +745,69 @@ fn github_repo_info_parses_https_and_ssh_urls() {
     }
 }
 
+#[test]
+fn gitlab_repo_info_parses_remotes_and_merge_request_urls() {
+    let info =
+        GitLabRepoInfo::from_remote_url("git@gitlab.com:group/sub/repo.git", "https://gitlab.com")
+            .unwrap();
+    assert_eq!(info.project_path, "group/sub/repo");
+    assert_eq!(info.base_url, "https://gitlab.com");
+
+    let info = GitLabRepoInfo::from_remote_url(
+        "ssh://git@git.example.com:2222/team/repo.git",
+        "https://git.example.com/",
+    )
+    .unwrap();
+    assert_eq!(info.project_path, "team/repo");
+    assert_eq!(info.base_url, "https://git.example.com");
+
+    assert!(
+        GitLabRepoInfo::from_remote_url("https://github.com/owner/repo.git", "https://gitlab.com")
+            .is_none()
+    );
+
+    let (info, iid) = GitLabRepoInfo::from_merge_request_url(
+        "https://gitlab.com/group/sub/repo/-/merge_requests/42",
+    )
+    .unwrap();
+    assert_eq!(iid, 42);
+    assert_eq!(info.project_path, "group/sub/repo");
+    assert!(
+        GitLabRepoInfo::from_merge_request_url("https://github.com/owner/repo/pull/42").is_none()
+    );
+
+    assert_eq!(
+        GitService::new().convert_to_https_url("git@gitlab.com:group/repo.git"),
+        "https://gitlab.com/group/repo.git"
+    );
+}
+
+#[test]
+fn gitea_repo_info_only_matches_the_configured_instance() {
+    let base_url = "https://git.example.com";
+    let info =
+        GiteaRepoInfo::from_remote_url("git@git.example.com:owner/repo.git", base_url).unwrap();
+    assert_eq!(info.owner, "owner");
+    assert_eq!(info.repo_name, "repo");
+    assert_eq!(info.base_url, base_url);
+
+    assert!(
+        GiteaRepoInfo::from_remote_url("https://github.com/owner/repo.git", base_url).is_none()
+    );
+
+    let (info, number) = GiteaRepoInfo::from_pull_request_url(
+        "https://git.example.com/owner/repo/pulls/7",
+        base_url,
+    )
+    .unwrap();
+    assert_eq!(number, 7);
+    assert_eq!(info.repo_name, "repo");
+    assert!(
+        GiteaRepoInfo::from_pull_request_url("https://gitlab.com/owner/repo/pulls/7", base_url)
+            .is_none()
+    );
+}
+
 #[test]
 fn squash_merge_libgit2_sets_author_without_user() {
     // Verify merge_changes (libgit2 path) uses fallback author when no config exists
@@ -677,3 +865,175 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@vibekanban.com"));
     }
 }
+
+#[test]
+fn merge_strategies_shape_base_history() {
+    use db::models::merge::MergeStrategy;
+    use git2::Repository;
+
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "base.txt", "base\n");
+    s.commit(&repo_path, "base").unwrap();
+
+    // Keep main checked out elsewhere so the libgit2 path is used
+    s.create_branch(&repo_path, "dev").unwrap();
+    s.checkout_branch(&repo_path, "dev").unwrap();
+
+    let head_of = |branch: &str| {
+        let repo = Repository::open(&repo_path).unwrap();
+        repo.find_branch(branch, git2::BranchType::Local)
+            .unwrap()
+            .get()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+    };
+
+    // Merge commit keeps both parents
+    s.create_branch(&repo_path, "feature-merge").unwrap();
+    let wt_merge = td.path().join("wt_merge");
+    s.add_worktree(&repo_path, &wt_merge, "feature-merge", false)
+        .unwrap();
+    write_file(&wt_merge, "merge.txt", "merge\n");
+    s.commit(&wt_merge, "feature merge").unwrap();
+    let base_before = head_of("main");
+    let merge_sha = s
+        .merge_changes_with_strategy(
+            &repo_path,
+            &wt_merge,
+            "feature-merge",
+            "main",
+            "merge feature",
+            MergeStrategy::MergeCommit,
+        )
+        .unwrap();
+    let repo = Repository::open(&repo_path).unwrap();
+    let merge_commit = repo
+        .find_commit(git2::Oid::from_str(&merge_sha).unwrap())
+        .unwrap();
+    assert_eq!(merge_commit.parent_count(), 2);
+    assert_eq!(merge_commit.parent_id(0).unwrap(), base_before);
+
+    // Fast-forward moves main onto the task commit itself
+    s.create_branch(&repo_path, "feature-ff").unwrap();
+    let wt_ff = td.path().join("wt_ff");
+    s.add_worktree(&repo_path, &wt_ff, "feature-ff", false)
+        .unwrap();
+    write_file(&wt_ff, "ff.txt", "ff\n");
+    s.commit(&wt_ff, "feature ff").unwrap();
+    let task_head = head_of("feature-ff");
+    let ff_sha = s
+        .merge_changes_with_strategy(
+            &repo_path,
+            &wt_ff,
+            "feature-ff",
+            "main",
+            "unused",
+            MergeStrategy::RebaseFastForward,
+        )
+        .unwrap();
+    assert_eq!(ff_sha, task_head.to_string());
+    assert_eq!(head_of("main"), task_head);
+}
+
+#[test]
+fn signed_commit_failure_does_not_fall_back_to_unsigned() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let head_before = s.get_head_info(&repo_path).unwrap().oid;
+
+    write_file(&repo_path, "signed.txt", "needs a signature\n");
+    let signing = CommitSigningConfig {
+        enabled: true,
+        format: Some(CommitSigningFormat::Openpgp),
+        key: Some("no-such-signing-key@example.invalid".to_string()),
+    };
+    let res = s.commit_with_signing(&repo_path, "signed", Some(&signing));
+    assert!(res.is_err(), "signing with an unknown key must fail");
+    assert_eq!(s.get_head_info(&repo_path).unwrap().oid, head_before);
+
+    // Disabled signing commits as usual
+    let disabled = CommitSigningConfig {
+        enabled: false,
+        ..signing
+    };
+    assert!(
+        s.commit_with_signing(&repo_path, "unsigned", Some(&disabled))
+            .unwrap()
+    );
+    assert_ne!(s.get_head_info(&repo_path).unwrap().oid, head_before);
+}
+
+#[test]
+fn merged_branch_detection_and_deletion() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "a\n");
+    let _ = s.commit(&repo_path, "add a").unwrap();
+
+    s.create_branch(&repo_path, "feature").unwrap();
+    s.checkout_branch(&repo_path, "feature").unwrap();
+    write_file(&repo_path, "b.txt", "b\n");
+    let _ = s.commit(&repo_path, "add b").unwrap();
+    s.checkout_branch(&repo_path, "main").unwrap();
+    assert!(!s.is_branch_merged(&repo_path, "feature", "main").unwrap());
+
+    let repo = git2::Repository::open(&repo_path).unwrap();
+    let feature_tip = repo.revparse_single("feature").unwrap().id();
+    repo.reference("refs/heads/main", feature_tip, true, "fast-forward")
+        .unwrap();
+    assert!(s.is_branch_merged(&repo_path, "feature", "main").unwrap());
+
+    s.delete_local_branch(&repo_path, "feature").unwrap();
+    assert!(!s.branch_exists(&repo_path, "feature").unwrap());
+    assert!(s.delete_local_branch(&repo_path, "main").is_err());
+}
+
+#[test]
+fn commit_authors_of_a_range_are_distinct_and_become_actors() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let base = s.get_head_info(&repo_path).unwrap().oid;
+
+    write_file(&repo_path, "a.txt", "a\n");
+    s.commit(&repo_path, "first").unwrap();
+    s.configure_user(&repo_path, "Other Dev", "other@example.com")
+        .unwrap();
+    write_file(&repo_path, "b.txt", "b\n");
+    s.commit(&repo_path, "second").unwrap();
+    s.configure_user(&repo_path, "Test User", "test@example.com")
+        .unwrap();
+    write_file(&repo_path, "c.txt", "c\n");
+    s.commit(&repo_path, "third").unwrap();
+    let head = s.get_head_info(&repo_path).unwrap().oid;
+
+    let authors = s
+        .get_commit_authors(&repo_path, Some(&base), &head)
+        .unwrap();
+    assert_eq!(
+        authors,
+        vec![
+            (Some("Test User".into()), Some("test@example.com".into())),
+            (Some("Other Dev".into()), Some("other@example.com".into())),
+        ]
+    );
+    // Without a base only the head commit counts
+    assert_eq!(
+        s.get_commit_authors(&repo_path, None, &head).unwrap().len(),
+        1
+    );
+
+    let actors = commit_author_actors(&authors);
+    assert_eq!(actors.len(), 2);
+    assert_eq!(actors[0].display_name, "Test User");
+    // Ids follow the email, so a renamed author keeps theirs
+    let renamed = commit_author_actors(&[(None, Some("TEST@example.com".into()))]);
+    assert_eq!(renamed[0].id, actors[0].id);
+    assert_eq!(renamed[0].display_name, "TEST@example.com");
+    assert!(commit_author_actors(&[(None, None)]).is_empty());
+}
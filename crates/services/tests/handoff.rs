@@ -0,0 +1,70 @@
+use chrono::Utc;
+use db::models::{
+    executor_session::ExecutorSession,
+    task::{Task, TaskStatus},
+};
+use executors::executors::BaseCodingAgent;
+use services::services::handoff::{MAX_HANDOFF_TURNS, build_handoff_prompt};
+use uuid::Uuid;
+
+fn task() -> Task {
+    Task {
+        id: Uuid::new_v4(),
+        project_id: Uuid::new_v4(),
+        title: "Add dark mode".to_string(),
+        description: Some("Follow the system theme by default".to_string()),
+        status: TaskStatus::InProgress,
+        parent_task_attempt: None,
+        parent_task_id: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn session(prompt: &str, summary: Option<&str>) -> ExecutorSession {
+    ExecutorSession {
+        id: Uuid::new_v4(),
+        task_attempt_id: Uuid::new_v4(),
+        execution_process_id: Uuid::new_v4(),
+        session_id: None,
+        prompt: Some(prompt.to_string()),
+        summary: summary.map(str::to_string),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn handoff_prompt_replays_previous_turns() {
+    let sessions = vec![
+        session("Add a theme toggle", Some("Added the toggle to the navbar")),
+        session("Persist the choice", None),
+    ];
+    let prompt = build_handoff_prompt(
+        &task(),
+        "vk/1234-add-dark-mode",
+        &BaseCodingAgent::Codex,
+        &sessions,
+        Some("Write tests for the toggle"),
+    );
+
+    assert!(prompt.contains("(CODEX)"));
+    assert!(prompt.contains("`vk/1234-add-dark-mode`"));
+    assert!(prompt.contains("Follow the system theme by default"));
+    assert!(prompt.contains("Added the toggle to the navbar"));
+    assert!(prompt.contains("The agent stopped before replying."));
+    assert!(prompt.trim_end().ends_with("Write tests for the toggle"));
+}
+
+#[test]
+fn handoff_prompt_keeps_only_recent_turns() {
+    let sessions: Vec<ExecutorSession> = (1..=MAX_HANDOFF_TURNS + 2)
+        .map(|i| session(&format!("request {i}"), Some("done")))
+        .collect();
+    let prompt = build_handoff_prompt(&task(), "b", &BaseCodingAgent::Amp, &sessions, None);
+
+    assert!(prompt.contains("(2 earlier turns omitted)"));
+    assert!(!prompt.contains("request 1\n"));
+    assert!(prompt.contains(&format!("request {}", MAX_HANDOFF_TURNS + 2)));
+    assert!(prompt.contains("Continue the task from where the previous agent stopped"));
+}
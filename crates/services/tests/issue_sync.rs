@@ -0,0 +1,115 @@
+use chrono::Utc;
+use db::models::{
+    issue_sync::{IssueState, IssueSync, IssueTracker},
+    task::TaskStatus,
+};
+use services::services::{
+    gitlab_service::GitLabIssue,
+    issue_sync::{
+        issue_reference, issue_state_for_task, task_status_for_issue, with_closing_reference,
+    },
+};
+use uuid::Uuid;
+
+fn sync(tracker: IssueTracker, owner: &str, repo: &str) -> IssueSync {
+    IssueSync {
+        project_id: Uuid::new_v4(),
+        tracker,
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        last_synced_at: None,
+        last_error: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn issue_changes_move_the_task() {
+    assert_eq!(
+        task_status_for_issue(IssueState::Closed, false, &TaskStatus::InReview),
+        TaskStatus::Done
+    );
+    assert_eq!(
+        task_status_for_issue(IssueState::Closed, true, &TaskStatus::Todo),
+        TaskStatus::Cancelled
+    );
+    assert_eq!(
+        task_status_for_issue(IssueState::Closed, true, &TaskStatus::Done),
+        TaskStatus::Done
+    );
+    assert_eq!(
+        task_status_for_issue(IssueState::Open, false, &TaskStatus::Done),
+        TaskStatus::Todo
+    );
+    assert_eq!(
+        task_status_for_issue(IssueState::Open, false, &TaskStatus::InProgress),
+        TaskStatus::InProgress
+    );
+    assert_eq!(
+        issue_state_for_task(&TaskStatus::Cancelled),
+        (IssueState::Closed, true)
+    );
+    assert_eq!(
+        issue_state_for_task(&TaskStatus::InReview),
+        (IssueState::Open, false)
+    );
+}
+
+#[test]
+fn pr_body_closes_the_issue_once() {
+    let sync = sync(IssueTracker::GitHub, "Acme", "tracker");
+    assert_eq!(
+        issue_reference(&sync, IssueTracker::GitHub, "acme/Tracker", 12).as_deref(),
+        Some("#12")
+    );
+    assert_eq!(
+        issue_reference(&sync, IssueTracker::GitHub, "acme/app", 12).as_deref(),
+        Some("Acme/tracker#12")
+    );
+
+    assert_eq!(with_closing_reference(None, "#12"), "Fixes #12");
+    assert_eq!(
+        with_closing_reference(Some("Adds retries\n"), "#12"),
+        "Adds retries\n\nFixes #12"
+    );
+    assert_eq!(
+        with_closing_reference(Some("Closes: #12"), "#12"),
+        "Closes: #12"
+    );
+    // Mentioning another issue, or this one without a closing keyword, does not count
+    assert_eq!(
+        with_closing_reference(Some("Fixes #123, see #12"), "#12"),
+        "Fixes #123, see #12\n\nFixes #12"
+    );
+}
+
+#[test]
+fn gitlab_issues_map_onto_the_shared_model() {
+    let issues: Vec<GitLabIssue> = serde_json::from_value(serde_json::json!([
+        {"iid": 4, "title": "Crash on start", "description": null, "state": "opened",
+         "web_url": "https://gitlab.com/acme/platform/api/-/issues/4",
+         "updated_at": "2025-10-30T09:00:00.000Z"},
+        {"iid": 5, "title": "Old bug", "description": "Gone", "state": "closed",
+         "web_url": "https://gitlab.com/acme/platform/api/-/issues/5",
+         "updated_at": "2025-10-30T10:00:00.000Z"}
+    ]))
+    .unwrap();
+    assert_eq!(issues[0].issue_state(), IssueState::Open);
+    assert_eq!(issues[1].issue_state(), IssueState::Closed);
+
+    let sync = sync(IssueTracker::GitLab, "acme/platform", "api");
+    assert_eq!(
+        issue_reference(&sync, IssueTracker::GitLab, "acme/platform/api", 4).as_deref(),
+        Some("#4")
+    );
+    assert_eq!(
+        issue_reference(&sync, IssueTracker::GitLab, "acme/web", 4).as_deref(),
+        Some("acme/platform/api#4")
+    );
+    // A GitHub PR cannot close a GitLab issue
+    assert_eq!(
+        issue_reference(&sync, IssueTracker::GitHub, "acme/platform/api", 4),
+        None
+    );
+}
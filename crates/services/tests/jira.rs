@@ -0,0 +1,22 @@
+use services::services::jira_service::{JiraTransition, pick_transition};
+
+fn transitions() -> Vec<JiraTransition> {
+    serde_json::from_value(serde_json::json!([
+        {"id": "11", "name": "Start", "to": {"name": "In Progress", "statusCategory": {"key": "indeterminate"}}},
+        {"id": "21", "name": "Request review", "to": {"name": "Code Review", "statusCategory": {"key": "indeterminate"}}},
+        {"id": "31", "name": "Resolve", "to": {"name": "Resolved", "statusCategory": {"key": "done"}}}
+    ]))
+    .unwrap()
+}
+
+#[test]
+fn transitions_match_target_status_then_name_then_category() {
+    let transitions = transitions();
+    let id = |status: &str, category: Option<&str>| {
+        pick_transition(&transitions, status, category).map(|t| t.id.as_str())
+    };
+    assert_eq!(id("code review", None), Some("21"));
+    assert_eq!(id(" Request Review ", None), Some("21"));
+    assert_eq!(id("Done", Some("done")), Some("31"));
+    assert_eq!(id("In Review", None), None);
+}
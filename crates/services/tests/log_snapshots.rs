@@ -0,0 +1,53 @@
+use std::time::{Duration, SystemTime};
+
+use services::services::log_snapshots::{prune_snapshots, read_snapshot, write_snapshot};
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+#[test]
+fn snapshots_round_trip_without_the_finished_marker() {
+    let dir = tempfile::tempdir().unwrap();
+    let id = Uuid::new_v4();
+    let messages = vec![
+        LogMsg::Stdout("{\"type\":\"assistant\"}\n".to_string()),
+        LogMsg::Stderr("warning\n".to_string()),
+        LogMsg::SessionId("session-1".to_string()),
+        LogMsg::Finished,
+    ];
+
+    write_snapshot(dir.path(), id, &messages).unwrap();
+
+    let restored = read_snapshot(dir.path(), id).unwrap().unwrap();
+    assert_eq!(restored.len(), 3);
+    assert!(matches!(&restored[0], LogMsg::Stdout(s) if s == "{\"type\":\"assistant\"}\n"));
+    assert!(matches!(&restored[1], LogMsg::Stderr(s) if s == "warning\n"));
+    assert!(matches!(&restored[2], LogMsg::SessionId(s) if s == "session-1"));
+    assert!(read_snapshot(dir.path(), Uuid::new_v4()).unwrap().is_none());
+}
+
+#[test]
+fn pruning_removes_snapshots_not_opened_since_the_cutoff() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = Uuid::new_v4();
+    let recent = Uuid::new_v4();
+    write_snapshot(dir.path(), old, &[LogMsg::Stdout("old".to_string())]).unwrap();
+    write_snapshot(dir.path(), recent, &[LogMsg::Stdout("new".to_string())]).unwrap();
+    std::fs::write(dir.path().join("notes.txt"), b"keep").unwrap();
+
+    let week_ago = SystemTime::now() - Duration::from_secs(7 * 24 * 3600);
+    std::fs::File::options()
+        .write(true)
+        .open(dir.path().join(format!("{old}.jsonl.gz")))
+        .unwrap()
+        .set_modified(week_ago - Duration::from_secs(60))
+        .unwrap();
+
+    assert_eq!(prune_snapshots(dir.path(), week_ago).unwrap(), 1);
+    assert!(read_snapshot(dir.path(), old).unwrap().is_none());
+    assert!(read_snapshot(dir.path(), recent).unwrap().is_some());
+    assert!(dir.path().join("notes.txt").exists());
+    assert_eq!(
+        prune_snapshots(&dir.path().join("missing"), week_ago).unwrap(),
+        0
+    );
+}
@@ -0,0 +1,56 @@
+use db::models::pr_check_status::{PrCheckRun, PrCheckState, PrReviewState};
+use services::services::github_service::{check_state_from_run, review_state_from_reviews};
+
+#[test]
+fn latest_decision_per_reviewer_wins() {
+    assert_eq!(review_state_from_reviews([]), PrReviewState::ReviewRequired);
+    assert_eq!(
+        review_state_from_reviews([("alice", "COMMENTED")]),
+        PrReviewState::Commented
+    );
+    assert_eq!(
+        review_state_from_reviews([
+            ("alice", "CHANGES_REQUESTED"),
+            ("alice", "COMMENTED"),
+            ("bob", "APPROVED"),
+        ]),
+        PrReviewState::ChangesRequested
+    );
+    assert_eq!(
+        review_state_from_reviews([("alice", "CHANGES_REQUESTED"), ("alice", "APPROVED")]),
+        PrReviewState::Approved
+    );
+    assert_eq!(
+        review_state_from_reviews([("alice", "APPROVED"), ("alice", "DISMISSED")]),
+        PrReviewState::ReviewRequired
+    );
+}
+
+#[test]
+fn check_runs_summarize_to_worst_state() {
+    let run = |name: &str, status: &str, conclusion: Option<&str>| PrCheckRun {
+        name: name.to_string(),
+        state: check_state_from_run(status, conclusion),
+        details_url: None,
+    };
+
+    let passing = vec![
+        run("build", "completed", Some("success")),
+        run("docs", "completed", Some("skipped")),
+    ];
+    assert_eq!(passing[1].state, PrCheckState::Neutral);
+    assert_eq!(PrCheckState::summarize(&passing), PrCheckState::Success);
+
+    let running = vec![
+        run("build", "completed", Some("success")),
+        run("test", "in_progress", None),
+    ];
+    assert_eq!(PrCheckState::summarize(&running), PrCheckState::Pending);
+
+    let failing = vec![
+        run("test", "in_progress", None),
+        run("lint", "completed", Some("timed_out")),
+    ];
+    assert_eq!(PrCheckState::summarize(&failing), PrCheckState::Failure);
+    assert_eq!(PrCheckState::summarize(&[]), PrCheckState::Success);
+}
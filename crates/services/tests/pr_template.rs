@@ -0,0 +1,76 @@
+use services::services::pr_template::{
+    find_pr_template, is_valid_template_path, merge_pr_template,
+};
+use tempfile::TempDir;
+
+#[test]
+fn finds_default_template_location() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir_all(dir.path().join(".github")).unwrap();
+    std::fs::write(
+        dir.path().join(".github/pull_request_template.md"),
+        "## Checklist\n- [ ] Tests\n\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        find_pr_template(dir.path(), None).as_deref(),
+        Some("## Checklist\n- [ ] Tests")
+    );
+}
+
+#[test]
+fn configured_path_takes_precedence_and_falls_back_when_missing() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("PULL_REQUEST_TEMPLATE.md"), "default").unwrap();
+    std::fs::create_dir_all(dir.path().join("templates")).unwrap();
+    std::fs::write(dir.path().join("templates/pr.md"), "custom").unwrap();
+
+    assert_eq!(
+        find_pr_template(dir.path(), Some("templates/pr.md")).as_deref(),
+        Some("custom")
+    );
+    assert_eq!(
+        find_pr_template(dir.path(), Some("templates/missing.md")).as_deref(),
+        Some("default")
+    );
+    assert_eq!(
+        find_pr_template(dir.path(), Some("../outside.md")).as_deref(),
+        Some("default")
+    );
+}
+
+#[test]
+fn no_template_when_repo_has_none() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("PULL_REQUEST_TEMPLATE.md"), "  \n").unwrap();
+    assert_eq!(find_pr_template(dir.path(), None), None);
+}
+
+#[test]
+fn merges_generated_body_before_template() {
+    assert_eq!(
+        merge_pr_template(Some("Fixes the parser\n"), "## Checklist\n"),
+        "Fixes the parser\n\n## Checklist"
+    );
+    assert_eq!(merge_pr_template(None, "## Checklist"), "## Checklist");
+    assert_eq!(
+        merge_pr_template(Some("  "), "## Checklist"),
+        "## Checklist"
+    );
+}
+
+#[test]
+fn does_not_duplicate_template_already_in_body() {
+    let body = "## Checklist\n- [x] Tests\n\nMore detail";
+    assert_eq!(merge_pr_template(Some(body), "## Checklist"), body);
+}
+
+#[test]
+fn template_paths_must_stay_inside_repo() {
+    assert!(is_valid_template_path(".github/pr.md"));
+    assert!(is_valid_template_path("./docs/template.md"));
+    assert!(!is_valid_template_path("../pr.md"));
+    assert!(!is_valid_template_path("/etc/passwd"));
+    assert!(!is_valid_template_path(""));
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use db::models::{merge::Merge, task::TaskStatus};
+use services::services::project_bundle::{
+    BundledRepository, PROJECT_BUNDLE_VERSION, ProjectBundle, ProjectBundleError,
+    resolve_repository_paths,
+};
+use uuid::Uuid;
+
+#[test]
+fn bundle_json_round_trips_with_merges() {
+    let json = serde_json::json!({
+        "version": PROJECT_BUNDLE_VERSION,
+        "exported_at": "2025-10-20T12:00:00Z",
+        "project_name": "demo",
+        "setup_script": "pnpm install",
+        "dev_script": null,
+        "cleanup_script": null,
+        "copy_files": ".env",
+        "repositories": [{
+            "id": "3b4c5d6e-7f80-4a91-b2c3-d4e5f6a7b8c9",
+            "name": "web",
+            "git_repo_path": "/home/dev/web",
+            "root_path": "",
+            "is_primary": true
+        }],
+        "labels": [{"name": "bug", "color": "#ef4444"}],
+        "tasks": [{
+            "id": "8d3a3f0e-5a8e-4d4f-9c57-0a7e0f4c1b11",
+            "parent_task_id": null,
+            "title": "Fix login",
+            "description": null,
+            "status": "inreview",
+            "due_at": "2025-11-01T09:00:00Z",
+            "labels": ["bug"],
+            "image_ids": ["1f0b5d0c-6a7b-4e25-8e3f-2b9a3c4d5e6f"],
+            "attempts": [{
+                "executor": "CLAUDE_CODE",
+                "branch": "vk/fix-login",
+                "target_branch": "main",
+                "created_at": "2025-10-19T08:00:00Z",
+                "repositories": [{
+                    "repository_id": "3b4c5d6e-7f80-4a91-b2c3-d4e5f6a7b8c9",
+                    "is_primary": true,
+                    "branch": "vk/fix-login",
+                    "base_branch": "main"
+                }],
+                "merges": [
+                    {
+                        "type": "direct",
+                        "id": "0c8f2a4e-3b1d-4e6f-8a9b-1c2d3e4f5a6b",
+                        "task_attempt_id": "5e6f7a8b-9c0d-4e1f-a2b3-c4d5e6f7a8b9",
+                        "merge_commit": "abc123",
+                        "target_branch_name": "main",
+                        "merge_strategy": "squash",
+                        "created_at": "2025-10-19T09:00:00Z"
+                    },
+                    {
+                        "type": "pr",
+                        "id": "6a7b8c9d-0e1f-4a2b-8c3d-4e5f6a7b8c9d",
+                        "task_attempt_id": "5e6f7a8b-9c0d-4e1f-a2b3-c4d5e6f7a8b9",
+                        "created_at": "2025-10-19T10:00:00Z",
+                        "target_branch_name": "main",
+                        "pr_info": {
+                            "number": 42,
+                            "url": "https://github.com/acme/demo/pull/42",
+                            "status": "merged",
+                            "merged_at": "2025-10-19T11:00:00Z",
+                            "merge_commit_sha": "def456"
+                        }
+                    }
+                ]
+            }]
+        }],
+        "images": [{
+            "id": "1f0b5d0c-6a7b-4e25-8e3f-2b9a3c4d5e6f",
+            "original_name": "screenshot.png",
+            "data": "iVBORw0KGgo="
+        }]
+    });
+
+    let bundle: ProjectBundle = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(bundle.tasks.len(), 1);
+    let task = &bundle.tasks[0];
+    assert_eq!(task.status, TaskStatus::InReview);
+    assert_eq!(task.labels, ["bug"]);
+    let merges = &task.attempts[0].merges;
+    assert!(matches!(merges[0], Merge::Direct(_)));
+    assert!(matches!(&merges[1], Merge::Pr(pr) if pr.pr_info.number == 42));
+    assert_eq!(
+        task.attempts[0].repositories[0].repository_id,
+        bundle.repositories[0].id
+    );
+
+    assert_eq!(serde_json::to_value(&bundle).unwrap(), json);
+}
+
+#[test]
+fn version_one_bundles_still_parse() {
+    let json = serde_json::json!({
+        "version": 1,
+        "exported_at": "2025-10-20T12:00:00Z",
+        "project_name": "demo",
+        "labels": [],
+        "tasks": [{
+            "id": "8d3a3f0e-5a8e-4d4f-9c57-0a7e0f4c1b11",
+            "parent_task_id": null,
+            "title": "Fix login",
+            "description": null,
+            "status": "todo",
+            "due_at": null,
+            "labels": [],
+            "image_ids": [],
+            "attempts": [{
+                "executor": "CLAUDE_CODE",
+                "branch": "vk/fix-login",
+                "target_branch": "main",
+                "created_at": "2025-10-19T08:00:00Z",
+                "merges": []
+            }]
+        }],
+        "images": []
+    });
+
+    let bundle: ProjectBundle = serde_json::from_value(json).unwrap();
+    assert!(bundle.repositories.is_empty());
+    assert!(bundle.setup_script.is_none());
+    assert!(bundle.tasks[0].attempts[0].repositories.is_empty());
+}
+
+fn bundled_repository(name: &str, path: &str, is_primary: bool) -> BundledRepository {
+    BundledRepository {
+        id: Uuid::new_v4(),
+        name: name.to_string(),
+        git_repo_path: path.to_string(),
+        root_path: String::new(),
+        is_primary,
+    }
+}
+
+#[test]
+fn repository_paths_are_remapped_and_checked() {
+    let temp = tempfile::tempdir().unwrap();
+    let web = temp.path().join("web");
+    std::fs::create_dir_all(web.join(".git")).unwrap();
+    let plain = temp.path().join("plain");
+    std::fs::create_dir_all(&plain).unwrap();
+
+    let repositories = vec![
+        bundled_repository("api", "/elsewhere/api", false),
+        bundled_repository("web", "/elsewhere/web", true),
+    ];
+    let mut paths = HashMap::new();
+    paths.insert(repositories[1].id, web.to_string_lossy().into_owned());
+    paths.insert(repositories[0].id, plain.to_string_lossy().into_owned());
+    let Err(ProjectBundleError::RepositoryPaths(problems)) =
+        resolve_repository_paths(&repositories, &paths)
+    else {
+        panic!("a directory without .git is not a repository");
+    };
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].starts_with("api:"));
+
+    std::fs::create_dir_all(plain.join(".git")).unwrap();
+    let resolved = resolve_repository_paths(&repositories, &paths).unwrap();
+    assert_eq!(resolved[0].name, "web");
+    assert!(resolved[0].is_primary);
+    assert_eq!(resolved[0].git_repo_path, web.to_string_lossy());
+    assert_eq!(resolved[1].git_repo_path, plain.to_string_lossy());
+    assert!(!resolved[1].is_primary);
+}
+
+#[test]
+fn bundles_without_repositories_cannot_create_projects() {
+    assert!(matches!(
+        resolve_repository_paths(&[], &HashMap::new()),
+        Err(ProjectBundleError::NoRepositories)
+    ));
+}
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use services::services::project_stats::{ProjectActivityKind, ProjectStatsService};
+use uuid::Uuid;
+
+#[test]
+fn records_counts_and_latencies_per_project() {
+    let stats = ProjectStatsService::new();
+    let busy = Uuid::new_v4();
+    let quiet = Uuid::new_v4();
+
+    stats.record(
+        busy,
+        ProjectActivityKind::ExecutionStarted,
+        Duration::from_millis(30),
+    );
+    stats.record(
+        busy,
+        ProjectActivityKind::ExecutionStarted,
+        Duration::from_millis(10),
+    );
+    drop(stats.start(busy, ProjectActivityKind::SearchRun));
+    stats.record(quiet, ProjectActivityKind::DiffStreamed, Duration::ZERO);
+
+    let busy_stats = stats.for_project(busy);
+    assert_eq!(busy_stats.executions_started.count, 2);
+    assert_eq!(busy_stats.executions_started.max_ms, 30);
+    assert_eq!(busy_stats.executions_started.average_ms(), 20.0);
+    assert_eq!(busy_stats.searches_run.count, 1);
+    assert_eq!(busy_stats.diffs_streamed.count, 0);
+
+    let all = stats.all();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].project_id, busy);
+    assert_eq!(
+        stats.for_project(Uuid::new_v4()).executions_started.count,
+        0
+    );
+}
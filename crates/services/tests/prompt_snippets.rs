@@ -0,0 +1,52 @@
+use chrono::Utc;
+use db::models::prompt_snippet::PromptSnippet;
+use services::services::prompt_snippets::expand;
+use uuid::Uuid;
+
+fn snippet(name: &str, content: &str) -> PromptSnippet {
+    PromptSnippet {
+        id: Uuid::new_v4(),
+        project_id: None,
+        name: name.to_string(),
+        content: content.to_string(),
+        description: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn replaces_known_placeholders() {
+    let snippets = [snippet("coding-standards", "Use rustfmt.\nNo unwraps.\n")];
+    let expanded = expand(
+        "Fix the parser.\n\n{{snippet:coding-standards}}\n{{ snippet:coding-standards }}",
+        &snippets,
+    );
+    assert_eq!(
+        expanded.prompt,
+        "Fix the parser.\n\nUse rustfmt.\nNo unwraps.\nUse rustfmt.\nNo unwraps."
+    );
+    assert!(expanded.missing.is_empty());
+}
+
+#[test]
+fn leaves_unknown_placeholders_and_reports_them() {
+    let expanded = expand("{{snippet:missing}} and {{snippet:missing}}", &[]);
+    assert_eq!(
+        expanded.prompt,
+        "{{snippet:missing}} and {{snippet:missing}}"
+    );
+    assert_eq!(expanded.missing, vec!["missing".to_string()]);
+}
+
+#[test]
+fn snippet_content_is_not_expanded_again() {
+    let snippets = [
+        snippet("outer", "before {{snippet:inner}}"),
+        snippet("inner", "nested"),
+    ];
+    assert_eq!(
+        expand("{{snippet:outer}}", &snippets).prompt,
+        "before {{snippet:inner}}"
+    );
+}
@@ -0,0 +1,96 @@
+use chrono::Utc;
+use db::models::diff_comment::{DiffComment, DiffCommentSide};
+use services::services::review_comments::{
+    ReviewThread, ReviewThreadComment, render_diff_comments_prompt, render_review_threads_prompt,
+};
+use uuid::Uuid;
+
+#[test]
+fn renders_threads_under_their_file_location() {
+    let threads = vec![
+        ReviewThread {
+            path: "src/lib.rs".to_string(),
+            line: Some(42),
+            outdated: false,
+            comments: vec![
+                ReviewThreadComment {
+                    author: Some("alice".to_string()),
+                    body: "Please handle the error here\ninstead of unwrapping".to_string(),
+                    url: None,
+                },
+                ReviewThreadComment {
+                    author: None,
+                    body: "+1".to_string(),
+                    url: None,
+                },
+            ],
+        },
+        ReviewThread {
+            path: "README.md".to_string(),
+            line: None,
+            outdated: true,
+            comments: vec![ReviewThreadComment {
+                author: Some("bob".to_string()),
+                body: "Typo".to_string(),
+                url: None,
+            }],
+        },
+    ];
+
+    let prompt = render_review_threads_prompt("https://github.com/o/r/pull/7", &threads);
+    assert!(prompt.contains("https://github.com/o/r/pull/7"));
+    assert!(prompt.contains(
+        "## src/lib.rs:42\n\nalice:\n> Please handle the error here\n> instead of unwrapping\n"
+    ));
+    assert!(prompt.contains("unknown:\n> +1\n"));
+    assert!(prompt.contains("## README.md (outdated)\n"));
+}
+
+fn diff_comment(
+    file_path: &str,
+    side: DiffCommentSide,
+    lines: (i64, i64),
+    code: Option<&str>,
+    body: &str,
+) -> DiffComment {
+    DiffComment {
+        id: Uuid::new_v4(),
+        task_attempt_id: Uuid::nil(),
+        repository_id: None,
+        file_path: file_path.to_string(),
+        side,
+        start_line: lines.0,
+        end_line: lines.1,
+        code: code.map(str::to_string),
+        body: body.to_string(),
+        sent_at: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[test]
+fn renders_diff_comments_with_their_code() {
+    let comments = vec![
+        diff_comment(
+            "src/main.rs",
+            DiffCommentSide::New,
+            (10, 12),
+            Some("let x = 1;\nlet y = 2;\nlet z = 3;\n"),
+            "Inline these\nplease",
+        ),
+        diff_comment(
+            "src/old.rs",
+            DiffCommentSide::Old,
+            (4, 4),
+            None,
+            "Why remove this?",
+        ),
+    ];
+
+    let prompt = render_diff_comments_prompt(&comments);
+    assert!(prompt.contains(
+        "## src/main.rs:10-12\n\n```\nlet x = 1;\nlet y = 2;\nlet z = 3;\n```\n\n> Inline these\n> please\n"
+    ));
+    assert!(prompt.contains("## src/old.rs:4 (removed lines)\n\n> Why remove this?\n"));
+}
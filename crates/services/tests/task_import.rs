@@ -0,0 +1,71 @@
+use db::models::task::TaskStatus;
+use services::services::task_import::{
+    ImportedTask, TaskImportError, TaskImportFormat, parse_tasks,
+};
+
+#[test]
+fn csv_rows_with_quotes_and_extra_columns() {
+    let csv = "\u{feff}Status,Title,Owner,Labels,Description\r\n\
+               In Progress,Fix login,ana,\"bug, auth\",\"Fails with \"\"401\"\"\nafter reset\"\r\n\
+               \r\n\
+               ,Write docs,,docs;Docs,\n\
+               done,,bob,,no title\n\
+               someday,Refactor,,,\n";
+    let rows = parse_tasks(TaskImportFormat::Csv, csv).unwrap();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(
+        rows[0],
+        Ok(ImportedTask {
+            title: "Fix login".to_string(),
+            description: Some("Fails with \"401\"\nafter reset".to_string()),
+            labels: vec!["bug".to_string(), "auth".to_string()],
+            status: TaskStatus::InProgress,
+        })
+    );
+    assert_eq!(
+        rows[1],
+        Ok(ImportedTask {
+            title: "Write docs".to_string(),
+            description: None,
+            labels: vec!["docs".to_string()],
+            status: TaskStatus::Todo,
+        })
+    );
+    assert_eq!(rows[2], Err("Title is required".to_string()));
+    assert_eq!(rows[3], Err("Unknown status 'someday'".to_string()));
+}
+
+#[test]
+fn csv_needs_a_title_column_and_closed_quotes() {
+    assert!(matches!(
+        parse_tasks(TaskImportFormat::Csv, "name,status\nA,todo\n"),
+        Err(TaskImportError::MissingTitleColumn)
+    ));
+    assert!(matches!(
+        parse_tasks(TaskImportFormat::Csv, "title\n\"unterminated\n"),
+        Err(TaskImportError::Csv(_))
+    ));
+}
+
+#[test]
+fn json_rows_are_checked_one_by_one() {
+    let json = r#"[
+        {"title": "Ship it", "labels": ["release"], "status": "inreview"},
+        {"title": "Joined labels", "labels": "a; b"},
+        {"title": 5},
+        {"description": "missing title"}
+    ]"#;
+    let rows = parse_tasks(TaskImportFormat::Json, json).unwrap();
+    assert_eq!(rows.len(), 4);
+    let first = rows[0].as_ref().unwrap();
+    assert_eq!(first.status, TaskStatus::InReview);
+    assert_eq!(first.labels, ["release"]);
+    assert_eq!(rows[1].as_ref().unwrap().labels, ["a", "b"]);
+    assert!(rows[2].is_err());
+    assert_eq!(rows[3], Err("Title is required".to_string()));
+
+    assert!(matches!(
+        parse_tasks(TaskImportFormat::Json, r#"{"title": "not a list"}"#),
+        Err(TaskImportError::Json(_))
+    ));
+}
@@ -0,0 +1,40 @@
+use services::services::users::{
+    agent_token, generate_session_token, hash_password, hash_session_token, is_agent_token,
+    validate_new_user, verify_password,
+};
+
+#[test]
+fn passwords_are_salted_and_verified() {
+    let hash = hash_password("correct horse");
+    assert!(hash.starts_with("$argon2id$"));
+    assert_ne!(hash, hash_password("correct horse"));
+    assert!(verify_password("correct horse", &hash));
+    assert!(!verify_password("correct horse!", &hash));
+    assert!(!verify_password("correct horse", "not a hash"));
+}
+
+#[test]
+fn session_tokens_are_stored_as_hashes() {
+    let token = generate_session_token();
+    assert_ne!(token, generate_session_token());
+    let hash = hash_session_token(&token);
+    assert_eq!(hash.len(), 64);
+    assert_ne!(hash, token);
+    assert_eq!(hash, hash_session_token(&token));
+}
+
+#[test]
+fn new_users_need_a_plain_username_and_a_long_password() {
+    assert_eq!(validate_new_user("ada.l", "long enough"), None);
+    assert!(validate_new_user(" ", "long enough").is_some());
+    assert!(validate_new_user("ada lovelace", "long enough").is_some());
+    assert!(validate_new_user("ada", "short").is_some());
+}
+
+#[test]
+fn only_the_agent_token_is_recognised_as_one() {
+    assert_eq!(agent_token(), agent_token());
+    assert!(is_agent_token(agent_token()));
+    assert!(!is_agent_token(&generate_session_token()));
+    assert!(!is_agent_token(""));
+}
@@ -0,0 +1,43 @@
+use chrono::{TimeZone, Utc};
+use db::models::webhook::WebhookEvent;
+use services::services::webhooks::{generate_secret, sign_payload, webhook_payload};
+use uuid::Uuid;
+
+#[test]
+fn signatures_are_hmac_sha256_of_the_body() {
+    // RFC 4231, test case 2
+    assert_eq!(
+        sign_payload("Jefe", b"what do ya want for nothing?"),
+        "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+    assert_ne!(
+        sign_payload("Jefe", b"what do ya want for nothing!"),
+        sign_payload("Jefe", b"what do ya want for nothing?")
+    );
+
+    let secret = generate_secret();
+    assert!(secret.starts_with("whsec_"));
+    assert_ne!(secret, generate_secret());
+}
+
+#[test]
+fn payloads_wrap_event_data_in_a_common_envelope() {
+    let delivery_id = Uuid::new_v4();
+    let project_id = Uuid::new_v4();
+    let occurred_at = Utc.with_ymd_and_hms(2025, 10, 31, 9, 0, 0).unwrap();
+    let payload = webhook_payload(
+        delivery_id,
+        WebhookEvent::AttemptFinished,
+        project_id,
+        occurred_at,
+        serde_json::json!({ "exit_code": 1 }),
+    );
+    assert_eq!(payload["id"], delivery_id.to_string());
+    assert_eq!(payload["event"], "attempt_finished");
+    assert_eq!(payload["project_id"], project_id.to_string());
+    assert_eq!(payload["occurred_at"], "2025-10-31T09:00:00Z");
+    assert_eq!(payload["data"]["exit_code"], 1);
+
+    // Stored filters use the same names as the payload
+    assert_eq!(WebhookEvent::MergeCompleted.to_string(), "merge_completed");
+}
@@ -1,10 +1,16 @@
 use uuid::Uuid;
 
-pub fn activity_feed_cache_key(project_id: Uuid, scope: &str, cursor: Option<&str>) -> String {
+/// Read state differs between readers, so each gets their own entries
+pub fn activity_feed_cache_key(
+    project_id: Uuid,
+    reader: &str,
+    scope: &str,
+    cursor: Option<&str>,
+) -> String {
     match cursor {
         Some(cursor) if !cursor.is_empty() => {
-            format!("activity_feed:{project_id}:{scope}:{cursor}")
+            format!("activity_feed:{project_id}:{reader}:{scope}:{cursor}")
         }
-        _ => format!("activity_feed:{project_id}:{scope}:root"),
+        _ => format!("activity_feed:{project_id}:{reader}:{scope}:root"),
     }
 }
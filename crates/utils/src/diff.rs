@@ -1,7 +1,7 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashMap, ops::Range};
 
 use serde::{Deserialize, Serialize};
-use similar::{ChangeTag, TextDiff};
+use similar::{ChangeTag, DiffOp, DiffTag, TextDiff};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -33,6 +33,254 @@ pub struct Diff {
     /// Optional precomputed stats for omitted content
     pub additions: Option<usize>,
     pub deletions: Option<usize>,
+    /// Set when the file is stored in Git LFS; contents are then left out rather than showing
+    /// pointer text
+    pub lfs: Option<LfsChange>,
+    /// Changed words on the lines that were modified; empty when contents are omitted
+    #[serde(default)]
+    pub intraline: Vec<IntralineChange>,
+    /// Set when either side is binary, whose contents are never included
+    #[serde(default)]
+    pub binary: Option<BinaryChange>,
+    /// Matches one of the project's diff ignore patterns, such as a lockfile; shown collapsed and
+    /// with contents omitted
+    #[serde(default)]
+    pub collapsed: bool,
+    /// Blocks of lines moved out of or into this file, so they need not be read as a separate
+    /// deletion and addition
+    #[serde(default)]
+    pub moved_blocks: Vec<MovedBlock>,
+}
+
+/// A run of lines deleted in one place and added unchanged in another, possibly in another file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct MovedBlock {
+    pub from_path: String,
+    /// 1-based line in the old version of `from_path` where the block started
+    pub from_line: usize,
+    pub to_path: String,
+    /// 1-based line in the new version of `to_path` where the block now starts
+    pub to_line: usize,
+    pub lines: usize,
+}
+
+/// Parsed Git LFS pointer file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct LfsPointer {
+    /// Object id including the hash method, e.g. `sha256:<hex>`
+    pub oid: String,
+    #[ts(type = "number")]
+    pub size: u64,
+}
+
+impl LfsPointer {
+    const VERSION_LINE: &'static str = "version https://git-lfs.github.com/spec/v1";
+    /// Pointer files are tiny; anything larger is real content
+    const MAX_POINTER_BYTES: usize = 1024;
+
+    pub fn parse(content: &str) -> Option<Self> {
+        if content.len() > Self::MAX_POINTER_BYTES {
+            return None;
+        }
+        let mut lines = content.lines();
+        if lines.next()?.trim_end() != Self::VERSION_LINE {
+            return None;
+        }
+
+        let mut oid = None;
+        let mut size = None;
+        for line in lines {
+            if let Some(value) = line.strip_prefix("oid ") {
+                oid = Some(value.trim().to_string());
+            } else if let Some(value) = line.strip_prefix("size ") {
+                size = value.trim().parse().ok();
+            }
+        }
+        Some(Self {
+            oid: oid?,
+            size: size?,
+        })
+    }
+}
+
+/// LFS objects on either side of a change. A side is None when it does not exist or is checked
+/// out as real content rather than a pointer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct LfsChange {
+    pub old: Option<LfsPointer>,
+    pub new: Option<LfsPointer>,
+}
+
+/// Dimensions of an image, read from its header
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageInfo {
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImageInfo {
+    /// Read PNG, GIF, JPEG, WebP and BMP headers; other formats are not recognized
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        let be16 = |at: usize| Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?));
+        let le16 = |at: usize| Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?));
+        let le24 = |at: usize| {
+            let b = bytes.get(at..at + 3)?;
+            Some(u32::from(b[0]) | (u32::from(b[1]) << 8) | (u32::from(b[2]) << 16))
+        };
+        let be32 = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+        let le32 = |at: usize| Some(i32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+        let image = |mime_type: &str, width: u32, height: u32| {
+            Some(Self {
+                mime_type: mime_type.to_string(),
+                width,
+                height,
+            })
+        };
+
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            return image("image/png", be32(16)?, be32(20)?);
+        }
+        if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            return image("image/gif", le16(6)?.into(), le16(8)?.into());
+        }
+        if bytes.starts_with(b"BM") {
+            return image(
+                "image/bmp",
+                le32(18)?.unsigned_abs(),
+                le32(22)?.unsigned_abs(),
+            );
+        }
+        if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+            return match bytes.get(12..16)? {
+                b"VP8 " => image(
+                    "image/webp",
+                    (le16(26)? & 0x3fff).into(),
+                    (le16(28)? & 0x3fff).into(),
+                ),
+                b"VP8L" => {
+                    let b = bytes.get(21..25)?;
+                    let (b0, b1, b2, b3) = (
+                        u32::from(b[0]),
+                        u32::from(b[1]),
+                        u32::from(b[2]),
+                        u32::from(b[3]),
+                    );
+                    image(
+                        "image/webp",
+                        1 + (((b1 & 0x3f) << 8) | b0),
+                        1 + (((b3 & 0xf) << 10) | (b2 << 2) | ((b1 & 0xc0) >> 6)),
+                    )
+                }
+                b"VP8X" => image("image/webp", 1 + le24(24)?, 1 + le24(27)?),
+                _ => None,
+            };
+        }
+        if bytes.starts_with(&[0xff, 0xd8]) {
+            // Walk the segments up to the start of frame, which holds the dimensions
+            let mut at = 2;
+            while *bytes.get(at)? == 0xff {
+                let marker = *bytes.get(at + 1)?;
+                match marker {
+                    0xff => at += 1,
+                    0xd0..=0xd9 | 0x01 => at += 2,
+                    0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                        return image("image/jpeg", be16(at + 7)?.into(), be16(at + 5)?.into());
+                    }
+                    _ => at += 2 + usize::from(be16(at + 2)?),
+                }
+            }
+        }
+        None
+    }
+}
+
+/// One side of a binary change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct BinaryFile {
+    #[ts(type = "number")]
+    pub size: u64,
+    pub image: Option<ImageInfo>,
+    /// Where this side's bytes can be fetched for a preview; set on attempt diffs
+    pub content_url: Option<String>,
+}
+
+impl BinaryFile {
+    /// `head` is the start of the file, enough to read an image header from
+    pub fn new(size: u64, head: &[u8]) -> Self {
+        Self {
+            size,
+            image: ImageInfo::sniff(head),
+            content_url: None,
+        }
+    }
+}
+
+/// Binary files on either side of a change. A side is None when it does not exist or is text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct BinaryChange {
+    pub old: Option<BinaryFile>,
+    pub new: Option<BinaryFile>,
+}
+
+impl BinaryChange {
+    /// `None` when neither side is binary
+    pub fn new(old: Option<BinaryFile>, new: Option<BinaryFile>) -> Option<Self> {
+        (old.is_some() || new.is_some()).then_some(Self { old, new })
+    }
+}
+
+/// Which side of a diff to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffSide {
+    Old,
+    New,
+}
+
+/// A changed stretch of a line, in UTF-16 code units so it indexes JavaScript strings directly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct IntralineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Words that changed between a removed line and the added line replacing it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct IntralineChange {
+    /// 1-based line number in the old content
+    pub old_line: usize,
+    /// 1-based line number in the new content
+    pub new_line: usize,
+    pub old_ranges: Vec<IntralineRange>,
+    pub new_ranges: Vec<IntralineRange>,
+}
+
+/// Lines covered by a hunk, as in a unified diff header: 1-based starts and line counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct HunkRange {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+}
+
+impl HunkRange {
+    // whether the 0-based `span` lies within the 1-based `start` and `lines`; an empty span may sit
+    // right before or after them
+    fn covers(start: usize, lines: usize, span: &Range<usize>) -> bool {
+        let first = start.saturating_sub(1);
+        if span.is_empty() {
+            (first..=first + lines).contains(&span.start)
+        } else {
+            span.start >= first && span.end <= first + lines
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -117,6 +365,241 @@ pub fn compute_line_change_counts(old: &str, new: &str) -> (usize, usize) {
     (additions, deletions)
 }
 
+/// Lines longer than this are only highlighted as a whole
+const MAX_INTRALINE_LINE_CHARS: usize = 1000;
+
+/// Word-level changes between the removed and added lines that a line diff of `old` and `new`
+/// pairs up, in order. Pairs that share nothing but whitespace are left out as rewrites.
+pub fn compute_intraline_changes(old: &str, new: &str) -> Vec<IntralineChange> {
+    let old = ensure_newline(old);
+    let new = ensure_newline(new);
+    let diff = TextDiff::from_lines(old.as_ref(), new.as_ref());
+
+    let mut changes = Vec::new();
+    for op in diff.ops() {
+        let DiffOp::Replace {
+            old_index,
+            old_len,
+            new_index,
+            new_len,
+        } = *op
+        else {
+            continue;
+        };
+        for offset in 0..old_len.min(new_len) {
+            let old_line = diff.old_slices()[old_index + offset].trim_end_matches(['\n', '\r']);
+            let new_line = diff.new_slices()[new_index + offset].trim_end_matches(['\n', '\r']);
+            if let Some((old_ranges, new_ranges)) = word_ranges(old_line, new_line) {
+                changes.push(IntralineChange {
+                    old_line: old_index + offset + 1,
+                    new_line: new_index + offset + 1,
+                    old_ranges,
+                    new_ranges,
+                });
+            }
+        }
+    }
+    changes
+}
+
+fn word_ranges(old: &str, new: &str) -> Option<(Vec<IntralineRange>, Vec<IntralineRange>)> {
+    if old.chars().count() > MAX_INTRALINE_LINE_CHARS
+        || new.chars().count() > MAX_INTRALINE_LINE_CHARS
+    {
+        return None;
+    }
+
+    let mut old_ranges = Vec::new();
+    let mut new_ranges = Vec::new();
+    let (mut old_pos, mut new_pos) = (0, 0);
+    let mut shares_words = false;
+    for change in TextDiff::from_words(old, new).iter_all_changes() {
+        let len = change.value().encode_utf16().count();
+        match change.tag() {
+            ChangeTag::Equal => {
+                shares_words |= !change.value().trim().is_empty();
+                old_pos += len;
+                new_pos += len;
+            }
+            ChangeTag::Delete => {
+                push_range(&mut old_ranges, old_pos, old_pos + len);
+                old_pos += len;
+            }
+            ChangeTag::Insert => {
+                push_range(&mut new_ranges, new_pos, new_pos + len);
+                new_pos += len;
+            }
+        }
+    }
+    shares_words.then_some((old_ranges, new_ranges))
+}
+
+// extend the last range when the next one starts where it ends
+fn push_range(ranges: &mut Vec<IntralineRange>, start: usize, end: usize) {
+    match ranges.last_mut() {
+        Some(last) if last.end == start => last.end = end,
+        _ => ranges.push(IntralineRange { start, end }),
+    }
+}
+
+/// `new` with the changes inside `hunk` undone, so those lines read as in `old` again. Changes
+/// are found by diffing the two texts and only those lying entirely within the hunk on both sides
+/// are reverted, which tolerates hunks drawn with a different amount of context. `None` when the
+/// hunk covers no change.
+pub fn revert_hunk(old: &str, new: &str, hunk: &HunkRange) -> Option<String> {
+    let diff = TextDiff::from_lines(old, new);
+    let mut reverted = String::with_capacity(new.len());
+    let mut any = false;
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        let inside = tag != DiffTag::Equal
+            && HunkRange::covers(hunk.old_start, hunk.old_lines, &old_range)
+            && HunkRange::covers(hunk.new_start, hunk.new_lines, &new_range);
+        let lines = if inside {
+            any = true;
+            &diff.old_slices()[old_range]
+        } else {
+            &diff.new_slices()[new_range]
+        };
+        reverted.extend(lines.iter().copied());
+    }
+    any.then_some(reverted)
+}
+
+/// Moves shorter than this are left as plain deletions and additions
+pub const MIN_MOVED_BLOCK_LINES: usize = 4;
+
+// lines deleted or added together in one file
+struct LineRun<'a> {
+    diff_index: usize,
+    path: String,
+    start: usize,
+    lines: Vec<&'a str>,
+}
+
+// the deleted and added runs of one file; `None` when its contents were left out
+fn changed_runs(diff_index: usize, diff: &Diff) -> Option<(Vec<LineRun<'_>>, Vec<LineRun<'_>>)> {
+    let old = match (&diff.old_content, &diff.change) {
+        (Some(old), _) => old.as_str(),
+        (None, DiffChangeKind::Added) => "",
+        (None, _) => return None,
+    };
+    let new = match (&diff.new_content, &diff.change) {
+        (Some(new), _) => new.as_str(),
+        (None, DiffChangeKind::Deleted) => "",
+        (None, _) => return None,
+    };
+    let old_path = diff.old_path.clone().or_else(|| diff.new_path.clone())?;
+    let new_path = diff.new_path.clone().or_else(|| diff.old_path.clone())?;
+    let text_diff = TextDiff::from_lines(old, new);
+    let (old_lines, new_lines) = (text_diff.old_slices(), text_diff.new_slices());
+
+    let (mut deleted, mut added) = (Vec::new(), Vec::new());
+    for op in text_diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+        if !old_range.is_empty() {
+            deleted.push(LineRun {
+                diff_index,
+                path: old_path.clone(),
+                start: old_range.start,
+                lines: old_lines[old_range].to_vec(),
+            });
+        }
+        if !new_range.is_empty() {
+            added.push(LineRun {
+                diff_index,
+                path: new_path.clone(),
+                start: new_range.start,
+                lines: new_lines[new_range].to_vec(),
+            });
+        }
+    }
+    Some((deleted, added))
+}
+
+/// Find blocks of at least [`MIN_MOVED_BLOCK_LINES`] lines that were deleted from one place and
+/// added unchanged elsewhere, within a file or across files, and record them on both files.
+/// Files whose contents were omitted are skipped. Each added line is claimed by one move at most.
+pub fn detect_moved_blocks(diffs: &mut [Diff]) {
+    let (mut deleted, mut added) = (Vec::new(), Vec::new());
+    for (index, diff) in diffs.iter().enumerate() {
+        if let Some((d, a)) = changed_runs(index, diff) {
+            deleted.extend(d);
+            added.extend(a);
+        }
+    }
+
+    // where each added line can be found, keyed by its text without trailing whitespace
+    let mut added_index: HashMap<&str, Vec<(usize, usize)>> = HashMap::new();
+    for (run_index, run) in added.iter().enumerate() {
+        for (offset, line) in run.lines.iter().enumerate() {
+            if !line.trim().is_empty() {
+                added_index
+                    .entry(line.trim_end())
+                    .or_default()
+                    .push((run_index, offset));
+            }
+        }
+    }
+
+    let mut claimed: Vec<Vec<bool>> = added
+        .iter()
+        .map(|run| vec![false; run.lines.len()])
+        .collect();
+    let mut moves: Vec<(usize, usize, MovedBlock)> = Vec::new();
+    for run in &deleted {
+        let mut at = 0;
+        while at < run.lines.len() {
+            let candidates = added_index
+                .get(run.lines[at].trim_end())
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let mut best: Option<(usize, usize, usize)> = None;
+            for &(run_index, offset) in candidates {
+                let target = &added[run_index];
+                let len = run.lines[at..]
+                    .iter()
+                    .zip(&target.lines[offset..])
+                    .zip(&claimed[run_index][offset..])
+                    .take_while(|((old, new), taken)| !**taken && old.trim_end() == new.trim_end())
+                    .count();
+                if best.is_none_or(|(_, _, best_len)| len > best_len) {
+                    best = Some((run_index, offset, len));
+                }
+            }
+            match best {
+                Some((run_index, offset, len)) if len >= MIN_MOVED_BLOCK_LINES => {
+                    claimed[run_index][offset..offset + len].fill(true);
+                    let target = &added[run_index];
+                    moves.push((
+                        run.diff_index,
+                        target.diff_index,
+                        MovedBlock {
+                            from_path: run.path.clone(),
+                            from_line: run.start + at + 1,
+                            to_path: target.path.clone(),
+                            to_line: target.start + offset + 1,
+                            lines: len,
+                        },
+                    ));
+                    at += len;
+                }
+                _ => at += 1,
+            }
+        }
+    }
+
+    for (from_index, to_index, block) in moves {
+        if from_index != to_index {
+            diffs[from_index].moved_blocks.push(block.clone());
+        }
+        diffs[to_index].moved_blocks.push(block);
+    }
+}
+
 // ensure a line ends with a newline character
 fn ensure_newline(line: &str) -> Cow<'_, str> {
     if line.ends_with('\n') {
@@ -266,3 +749,213 @@ pub fn concatenate_diff_hunks(file_path: &str, hunks: &[String]) -> String {
 
     unified_diff
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intraline_ranges_cover_the_changed_words() {
+        let old = "fn main() {\n    let total = price * 2;\n}\n";
+        let new = "fn main() {\n    let total = price * quantity;\n}\n";
+        assert_eq!(
+            compute_intraline_changes(old, new),
+            [IntralineChange {
+                old_line: 2,
+                new_line: 2,
+                old_ranges: vec![IntralineRange { start: 24, end: 26 }],
+                new_ranges: vec![IntralineRange { start: 24, end: 33 }],
+            }]
+        );
+    }
+
+    #[test]
+    fn rewritten_lines_have_no_intraline_ranges() {
+        assert!(compute_intraline_changes("alpha beta\n", "gamma delta\n").is_empty());
+        // Offsets count UTF-16 units, so the emoji takes two
+        let changes = compute_intraline_changes("😀 a b\n", "😀 a c\n");
+        assert_eq!(changes[0].new_ranges, [IntralineRange { start: 5, end: 6 }]);
+    }
+
+    #[test]
+    fn reverts_only_the_changes_inside_the_hunk() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        let new = "a\nB\nc\nd\ne\nf\nG\nh\nI\n";
+        let second = HunkRange {
+            old_start: 5,
+            old_lines: 4,
+            new_start: 5,
+            new_lines: 5,
+        };
+        assert_eq!(
+            revert_hunk(old, new, &second).as_deref(),
+            Some("a\nB\nc\nd\ne\nf\ng\nh\n")
+        );
+
+        let first = HunkRange {
+            old_start: 1,
+            old_lines: 3,
+            new_start: 1,
+            new_lines: 3,
+        };
+        assert_eq!(
+            revert_hunk(old, new, &first).as_deref(),
+            Some("a\nb\nc\nd\ne\nf\nG\nh\nI\n")
+        );
+
+        let unchanged = HunkRange {
+            old_start: 3,
+            old_lines: 2,
+            new_start: 3,
+            new_lines: 2,
+        };
+        assert_eq!(revert_hunk(old, new, &unchanged), None);
+    }
+
+    #[test]
+    fn sniffs_image_dimensions() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+        assert_eq!(
+            ImageInfo::sniff(&png),
+            Some(ImageInfo {
+                mime_type: "image/png".to_string(),
+                width: 640,
+                height: 480,
+            })
+        );
+
+        let gif = b"GIF89a\x20\x00\x10\x00";
+        assert_eq!(
+            ImageInfo::sniff(gif).map(|info| (info.width, info.height)),
+            Some((32, 16))
+        );
+
+        // SOI, an APP0 segment with no payload, then SOF0 for a 300x200 image
+        let jpeg = [
+            0xff, 0xd8, 0xff, 0xe0, 0x00, 0x02, 0xff, 0xc0, 0x00, 0x11, 0x08, 0x00, 0xc8, 0x01,
+            0x2c,
+        ];
+        assert_eq!(
+            ImageInfo::sniff(&jpeg).map(|info| (info.mime_type, info.width, info.height)),
+            Some(("image/jpeg".to_string(), 300, 200))
+        );
+
+        assert_eq!(ImageInfo::sniff(b"\0\x01binary"), None);
+    }
+
+    #[test]
+    fn parses_lfs_pointer() {
+        let pointer = "version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n";
+        assert_eq!(
+            LfsPointer::parse(pointer),
+            Some(LfsPointer {
+                oid: "sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393"
+                    .to_string(),
+                size: 12345,
+            })
+        );
+    }
+
+    #[test]
+    fn ignores_regular_text() {
+        assert_eq!(LfsPointer::parse("size 10\noid sha256:abc\n"), None);
+        assert_eq!(
+            LfsPointer::parse("version https://git-lfs.github.com/spec/v1\nsize 10\n"),
+            None
+        );
+    }
+
+    fn text_diff(
+        change: DiffChangeKind,
+        old_path: Option<&str>,
+        new_path: Option<&str>,
+        old: Option<&str>,
+        new: Option<&str>,
+    ) -> Diff {
+        Diff {
+            repository_id: None,
+            repository_name: None,
+            repository_root: None,
+            change,
+            old_path: old_path.map(str::to_string),
+            new_path: new_path.map(str::to_string),
+            old_content: old.map(str::to_string),
+            new_content: new.map(str::to_string),
+            content_omitted: false,
+            additions: None,
+            deletions: None,
+            lfs: None,
+            intraline: Vec::new(),
+            binary: None,
+            collapsed: false,
+            moved_blocks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn detects_blocks_moved_between_files() {
+        let block = "fn helper() {\n    let a = 1;\n    let b = 2;\n    a + b\n}\n";
+        let mut diffs = vec![
+            text_diff(
+                DiffChangeKind::Modified,
+                Some("src/a.rs"),
+                Some("src/a.rs"),
+                Some(&format!("fn main() {{}}\n\n{block}")),
+                Some("fn main() {}\n"),
+            ),
+            text_diff(
+                DiffChangeKind::Added,
+                None,
+                Some("src/b.rs"),
+                None,
+                Some(&format!("use std::fmt;\n\n{block}")),
+            ),
+            text_diff(
+                DiffChangeKind::Added,
+                None,
+                Some("src/c.rs"),
+                None,
+                Some("fn c() {}\n"),
+            ),
+        ];
+
+        detect_moved_blocks(&mut diffs);
+
+        let expected = MovedBlock {
+            from_path: "src/a.rs".to_string(),
+            from_line: 3,
+            to_path: "src/b.rs".to_string(),
+            to_line: 3,
+            lines: 5,
+        };
+        assert_eq!(diffs[0].moved_blocks, vec![expected.clone()]);
+        assert_eq!(diffs[1].moved_blocks, vec![expected]);
+        assert!(diffs[2].moved_blocks.is_empty());
+    }
+
+    #[test]
+    fn ignores_short_moves_and_omitted_contents() {
+        let mut diffs = vec![
+            text_diff(
+                DiffChangeKind::Modified,
+                Some("a.txt"),
+                Some("a.txt"),
+                Some("one\ntwo\nthree\nfour\nfive\n"),
+                Some("four\nfive\none\ntwo\nthree\n"),
+            ),
+            text_diff(
+                DiffChangeKind::Modified,
+                Some("big.txt"),
+                Some("big.txt"),
+                None,
+                None,
+            ),
+        ];
+
+        detect_moved_blocks(&mut diffs);
+
+        assert!(diffs.iter().all(|diff| diff.moved_blocks.is_empty()));
+    }
+}
@@ -22,6 +22,8 @@ struct StoredMsg {
 struct Inner {
     history: VecDeque<StoredMsg>,
     total_bytes: usize,
+    /// Set once the byte limit has dropped the oldest messages
+    truncated: bool,
 }
 
 pub struct MsgStore {
@@ -42,6 +44,7 @@ impl MsgStore {
             inner: RwLock::new(Inner {
                 history: VecDeque::with_capacity(32),
                 total_bytes: 0,
+                truncated: false,
             }),
             sender,
         }
@@ -55,6 +58,7 @@ impl MsgStore {
         while inner.total_bytes.saturating_add(bytes) > HISTORY_BYTES {
             if let Some(front) = inner.history.pop_front() {
                 inner.total_bytes = inner.total_bytes.saturating_sub(front.bytes);
+                inner.truncated = true;
             } else {
                 break;
             }
@@ -63,6 +67,12 @@ impl MsgStore {
         inner.total_bytes = inner.total_bytes.saturating_add(bytes);
     }
 
+    /// Send `msg` to live listeners only. For state that is replaced often and has no use once the
+    /// process ended, such as resource usage samples.
+    pub fn push_transient(&self, msg: LogMsg) {
+        let _ = self.sender.send(msg);
+    }
+
     // Convenience
     pub fn push_stdout<S: Into<String>>(&self, s: S) {
         self.push(LogMsg::Stdout(s.into()));
@@ -97,6 +107,12 @@ impl MsgStore {
             .collect()
     }
 
+    /// Whether the history still holds every message pushed, none having been dropped to stay
+    /// under the byte limit
+    pub fn is_history_complete(&self) -> bool {
+        !self.inner.read().unwrap().truncated
+    }
+
     /// History then live, as `LogMsg`.
     pub fn history_plus_stream(
         &self,
@@ -1,4 +1,11 @@
-import { Diff } from 'shared/types';
+import {
+  BinaryChange,
+  BinaryFile,
+  Diff,
+  LfsChange,
+  LfsPointer,
+  MovedBlock,
+} from 'shared/types';
 import { DiffModeEnum, DiffView, SplitSide } from '@git-diff-view/react';
 import { generateDiffFile, type DiffFile } from '@git-diff-view/file';
 import { useMemo } from 'react';
@@ -28,6 +35,7 @@ import { CommentWidgetLine } from '@/components/diff/CommentWidgetLine';
 import { ReviewCommentRenderer } from '@/components/diff/ReviewCommentRenderer';
 import { useDiffViewMode } from '@/stores/useDiffViewStore';
 import { useProject } from '@/contexts/project-context';
+import { withBasePath } from '@/lib/basePath';
 
 type Props = {
   diff: Diff;
@@ -48,6 +56,65 @@ function labelAndIcon(diff: Diff) {
   return { label: undefined as string | undefined, Icon: PencilLine };
 }
 
+function formatLfsPointer(pointer: LfsPointer) {
+  const hash = pointer.oid.replace(/^sha256:/, '').slice(0, 12);
+  return `${hash} (${pointer.size.toLocaleString()} bytes)`;
+}
+
+function describeLfsChange(lfs: LfsChange) {
+  if (lfs.old && lfs.new)
+    return `Git LFS object changed: ${formatLfsPointer(lfs.old)} → ${formatLfsPointer(lfs.new)}`;
+  if (lfs.new) return `Git LFS object added: ${formatLfsPointer(lfs.new)}`;
+  if (lfs.old) return `Git LFS object: ${formatLfsPointer(lfs.old)}`;
+  return 'Stored in Git LFS. Open in editor to view.';
+}
+
+function formatBinaryFile(file: BinaryFile) {
+  const size = `${file.size.toLocaleString()} bytes`;
+  return file.image
+    ? `${file.image.width}×${file.image.height} ${file.image.mimeType}, ${size}`
+    : size;
+}
+
+function describeBinaryChange(binary: BinaryChange) {
+  if (binary.old && binary.new)
+    return `Binary file changed: ${formatBinaryFile(binary.old)} → ${formatBinaryFile(binary.new)}`;
+  if (binary.new) return `Binary file added: ${formatBinaryFile(binary.new)}`;
+  if (binary.old) return `Binary file removed: ${formatBinaryFile(binary.old)}`;
+  return 'Binary file. Open in editor to view.';
+}
+
+function describeMovedBlock(block: MovedBlock, filePath: string) {
+  const lines = `${block.lines} line${block.lines === 1 ? '' : 's'}`;
+  if (block.fromPath === block.toPath)
+    return `Moved ${lines} from line ${block.fromLine} to line ${block.toLine}`;
+  if (block.fromPath === filePath)
+    return `Moved ${lines} from line ${block.fromLine} to ${block.toPath}:${block.toLine}`;
+  return `Moved ${lines} from ${block.fromPath}:${block.fromLine} to line ${block.toLine}`;
+}
+
+function BinaryPreview({ binary }: { binary: BinaryChange }) {
+  const sides = [
+    { label: 'Before', file: binary.old },
+    { label: 'After', file: binary.new },
+  ].filter(({ file }) => file?.image && file.contentUrl);
+  if (sides.length === 0) return null;
+  return (
+    <div className="mt-3 flex flex-wrap gap-4">
+      {sides.map(({ label, file }) => (
+        <figure key={label} className="flex flex-col gap-1">
+          <img
+            src={withBasePath(file!.contentUrl!)}
+            alt={label}
+            className="max-h-64 max-w-full border object-contain"
+          />
+          <figcaption>{label}</figcaption>
+        </figure>
+      ))}
+    </div>
+  );
+}
+
 function readPlainLine(
   diffFile: DiffFile | null,
   lineNumber: number,
@@ -92,6 +159,9 @@ export default function DiffCard({
     getHighLightLanguageFromPath(newName || oldName || '') || 'plaintext';
   const { label, Icon } = labelAndIcon(diff);
   const isOmitted = !!diff.contentOmitted;
+  const lfs = diff.lfs;
+  const binary = diff.binary;
+  const movedBlocks = diff.movedBlocks ?? [];
 
   // Build a diff from raw contents so the viewer can expand beyond hunks
   const oldContentSafe = diff.oldContent || '';
@@ -99,7 +169,7 @@ export default function DiffCard({
   const isContentEqual = oldContentSafe === newContentSafe;
 
   const diffFile = useMemo(() => {
-    if (isContentEqual || isOmitted) return null;
+    if (isContentEqual || isOmitted || lfs || binary) return null;
     try {
       const oldFileName = oldName || newName || 'unknown';
       const newFileName = newName || oldName || 'unknown';
@@ -120,6 +190,8 @@ export default function DiffCard({
   }, [
     isContentEqual,
     isOmitted,
+    lfs,
+    binary,
     oldName,
     newName,
     oldLang,
@@ -216,6 +288,21 @@ export default function DiffCard({
           {repositoryName}
         </Badge>
       )}
+      {lfs && (
+        <Badge variant="secondary" className="mr-2 text-[10px]">
+          LFS
+        </Badge>
+      )}
+      {binary && (
+        <Badge variant="secondary" className="mr-2 text-[10px]">
+          Binary
+        </Badge>
+      )}
+      {diff.collapsed && (
+        <Badge variant="secondary" className="mr-2 text-[10px]">
+          Ignored
+        </Badge>
+      )}
       {diff.change === 'renamed' && oldName ? (
         <span className="inline-flex items-center gap-2">
           <span>{oldName}</span>
@@ -291,6 +378,21 @@ export default function DiffCard({
         </Button>
       </div>
 
+      {expanded && movedBlocks.length > 0 && (
+        <ul
+          className="px-4 pb-2 text-xs font-mono"
+          style={{ color: 'hsl(var(--muted-foreground) / 0.9)' }}
+        >
+          {movedBlocks.map((block) => (
+            <li
+              key={`${block.fromPath}:${block.fromLine}->${block.toPath}:${block.toLine}`}
+            >
+              {describeMovedBlock(block, filePath)}
+            </li>
+          ))}
+        </ul>
+      )}
+
       {expanded && diffFile && (
         <div>
           <DiffView
@@ -315,15 +417,28 @@ export default function DiffCard({
           className="px-4 pb-4 text-xs font-mono"
           style={{ color: 'hsl(var(--muted-foreground) / 0.9)' }}
         >
-          {isOmitted
-            ? 'Content omitted due to file size. Open in editor to view.'
-            : isContentEqual
-              ? diff.change === 'renamed'
-                ? 'File renamed with no content changes.'
-                : diff.change === 'permissionChange'
-                  ? 'File permission changed.'
-                  : 'No content changes to display.'
-              : 'Failed to render diff for this file.'}
+          {binary ? (
+            <>
+              {describeBinaryChange(binary)}
+              <BinaryPreview binary={binary} />
+            </>
+          ) : lfs ? (
+            describeLfsChange(lfs)
+          ) : diff.collapsed ? (
+            "Hidden by the project's diff ignore patterns. Open in editor to view."
+          ) : isOmitted ? (
+            'Content omitted due to file size. Open in editor to view.'
+          ) : isContentEqual ? (
+            diff.change === 'renamed' ? (
+              'File renamed with no content changes.'
+            ) : diff.change === 'permissionChange' ? (
+              'File permission changed.'
+            ) : (
+              'No content changes to display.'
+            )
+          ) : (
+            'Failed to render diff for this file.'
+          )}
         </div>
       )}
     </div>
@@ -58,6 +58,11 @@ const baseDiff = {
   contentOmitted: true,
   additions: 3,
   deletions: 1,
+  lfs: null,
+  intraline: [],
+  binary: null,
+  collapsed: false,
+  movedBlocks: [],
 };
 
 describe('DiffCard', () => {
@@ -67,8 +67,10 @@ const stubEvent = (overrides: Partial<ActivityFeedEvent> = {}): ActivityFeedEven
   headline: overrides.headline ?? 'New deployment ready',
   summary: overrides.summary ?? 'Deployment completed successfully',
   cta: overrides.cta ?? { label: 'View', href: '/deployments/1' },
+  action: overrides.action ?? null,
   urgencyScore: overrides.urgencyScore ?? 82,
   actionRequired: overrides.actionRequired ?? true,
+  read: overrides.read ?? false,
   createdAt: overrides.createdAt ?? new Date('2025-09-20T12:00:00Z'),
 });
 
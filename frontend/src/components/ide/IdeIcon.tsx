@@ -1,6 +1,7 @@
 import { Code2 } from 'lucide-react';
 import { EditorType, ThemeMode } from 'shared/types';
 import { useTheme } from '@/components/theme-provider';
+import { withBasePath } from '@/lib/basePath';
 
 type IdeIconProps = {
   editorType?: EditorType | null;
@@ -21,47 +22,61 @@ export function IdeIcon({ editorType, className = 'h-4 w-4' }: IdeIconProps) {
   const resolvedTheme = getResolvedTheme(theme);
 
   if (editorType === EditorType.VS_CODE) {
-    const vscodeIcon =
+    const vscodeIcon = withBasePath(
       resolvedTheme === 'dark'
         ? '/ide/vscode-dark.svg'
-        : '/ide/vscode-light.svg';
+        : '/ide/vscode-light.svg'
+    );
 
     return <img src={vscodeIcon} alt="VS Code" className={className} />;
   }
 
   if (editorType === EditorType.CURSOR) {
-    const cursorIcon =
+    const cursorIcon = withBasePath(
       resolvedTheme === 'dark'
         ? '/ide/cursor-dark.svg' // dark
-        : '/ide/cursor-light.svg'; // light
+        : '/ide/cursor-light.svg' // light
+    );
 
     return <img src={cursorIcon} alt="Cursor" className={className} />;
   }
 
   if (editorType === EditorType.WINDSURF) {
-    const windsurfIcon =
+    const windsurfIcon = withBasePath(
       resolvedTheme === 'dark'
         ? '/ide/windsurf-light.svg'
-        : '/ide/windsurf-dark.svg';
+        : '/ide/windsurf-dark.svg'
+    );
 
     return <img src={windsurfIcon} alt="Windsurf" className={className} />;
   }
 
   if (editorType === EditorType.INTELLI_J) {
     return (
-      <img src="/ide/intellij.svg" alt="IntelliJ IDEA" className={className} />
+      <img
+        src={withBasePath('/ide/intellij.svg')}
+        alt="IntelliJ IDEA"
+        className={className}
+      />
     );
   }
 
   if (editorType === EditorType.ZED) {
-    const zedIcon =
-      resolvedTheme === 'dark' ? '/ide/zed-light.svg' : '/ide/zed-dark.svg';
+    const zedIcon = withBasePath(
+      resolvedTheme === 'dark' ? '/ide/zed-light.svg' : '/ide/zed-dark.svg'
+    );
 
     return <img src={zedIcon} alt="Zed" className={className} />;
   }
 
   if (editorType === EditorType.XCODE) {
-    return <img src="/ide/xcode.svg" alt="Xcode" className={className} />;
+    return (
+      <img
+        src={withBasePath('/ide/xcode.svg')}
+        alt="Xcode"
+        className={className}
+      />
+    );
   }
 
   // Generic fallback for other IDEs or no IDE configured
@@ -1,6 +1,4 @@
-import { useEffect, useState } from 'react';
-import type { ClaudeCodeUsageSnapshot } from 'shared/types';
-import { usageApi } from '@/lib/api';
+import { useUsageStream } from '@/hooks/useUsageStream';
 import {
   CompactProgressBar,
   clampPercent,
@@ -25,22 +23,7 @@ function getBlockResetSeconds(capturedAt: string): number | null {
 }
 
 export function CompactClaudeCodeUsage() {
-  const [usage, setUsage] = useState<ClaudeCodeUsageSnapshot | null>(null);
-
-  const fetchUsage = async () => {
-    try {
-      const result = await usageApi.getClaudeCodeUsage();
-      setUsage(result);
-    } catch (err) {
-      console.error('Failed to fetch Claude Code usage', err);
-    }
-  };
-
-  useEffect(() => {
-    fetchUsage();
-    const interval = setInterval(fetchUsage, 30000);
-    return () => clearInterval(interval);
-  }, []);
+  const { claudeCode: usage } = useUsageStream();
 
   if (!usage) {
     return null;
@@ -1,6 +1,5 @@
-import { useEffect, useMemo, useState } from 'react';
-import type { CodexUsageSnapshot } from 'shared/types';
-import { usageApi } from '@/lib/api';
+import { useMemo } from 'react';
+import { useUsageStream } from '@/hooks/useUsageStream';
 import {
   CompactProgressBar,
   clampPercent,
@@ -8,22 +7,7 @@ import {
 } from './compact-progress-bar';
 
 export function CompactCodexUsage() {
-  const [usage, setUsage] = useState<CodexUsageSnapshot | null>(null);
-
-  const fetchUsage = async () => {
-    try {
-      const result = await usageApi.getCodexUsage();
-      setUsage(result);
-    } catch (err) {
-      console.error('Failed to fetch Codex usage', err);
-    }
-  };
-
-  useEffect(() => {
-    fetchUsage();
-    const interval = setInterval(fetchUsage, 30000);
-    return () => clearInterval(interval);
-  }, []);
+  const { codex: usage } = useUsageStream();
 
   const windows = useMemo(() => {
     if (!usage) return [];
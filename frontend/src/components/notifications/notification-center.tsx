@@ -12,7 +12,11 @@ import { AlertTriangle, ArrowUpRight, CheckCircle2, Loader2, WifiOff, X } from '
 import { Badge } from '@/components/ui/badge';
 import { Button } from '@/components/ui/button';
 import { Alert, AlertDescription } from '@/components/ui/alert';
-import { ActivityFeedEvent, ActivityFeedFilter } from '@/lib/api';
+import {
+  ActivityFeedEvent,
+  ActivityFeedFilter,
+  activityFeedApi,
+} from '@/lib/api';
 import { cn } from '@/lib/utils';
 import { ActivityFilterTabs } from '@/components/home/ActivityFilterTabs';
 import {
@@ -254,6 +258,20 @@ export function NotificationCenter({
     trackEventView(event);
   };
 
+  const handleAction = async (event: ActivityFeedEvent) => {
+    if (!event.action) return;
+    trackAnalyticsEvent('activity_feed.run_action', {
+      eventId: event.id,
+      action: event.action.label,
+    });
+    try {
+      await activityFeedApi.runAction(event.action);
+      markAsHandled(event.id);
+    } catch (error) {
+      console.error('Failed to run activity feed action', error);
+    }
+  };
+
   const handleDismiss = (eventId: string) => {
     markAsHandled(eventId);
     if (onEventDismiss) {
@@ -605,6 +623,31 @@ export function NotificationCenter({
                           </a>
                         </Button>
                       )}
+                      {event.action && (
+                        <Button
+                          variant="outline"
+                          size="sm"
+                          className="h-6 px-2 text-[10px]"
+                          onClick={(actionEvent) => {
+                            actionEvent.stopPropagation();
+                            void handleAction(event);
+                          }}
+                          onPointerDown={(actionEvent) => {
+                            actionEvent.stopPropagation();
+                          }}
+                          onPointerUp={(actionEvent) => {
+                            actionEvent.stopPropagation();
+                          }}
+                          onTouchStart={(actionEvent) => {
+                            actionEvent.stopPropagation();
+                          }}
+                          onTouchEnd={(actionEvent) => {
+                            actionEvent.stopPropagation();
+                          }}
+                        >
+                          {event.action.label}
+                        </Button>
+                      )}
                       {onEventDismiss && urgent && (
                         <Button
                           variant="ghost"
@@ -6,6 +6,7 @@ import { Card, CardContent } from '@/components/ui/card';
 import { Button } from '@/components/ui/button';
 import type { ClaudeCodeUsageSnapshot } from 'shared/types';
 import { usageApi } from '@/lib/api';
+import { useUsageStream } from '@/hooks/useUsageStream';
 
 const numberFormatter = new Intl.NumberFormat();
 
@@ -118,16 +119,17 @@ export function ClaudeCodeUsageCard() {
 
   useEffect(() => {
     fetchUsage();
-
-    // Auto-refresh every 30 seconds to pick up usage from running Claude Code sessions
-    const interval = setInterval(() => {
-      fetchUsage();
-    }, 30000);
-
-    return () => clearInterval(interval);
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, []);
 
+  // The server pushes a new snapshot whenever running Claude Code sessions log usage
+  const { claudeCode: liveUsage } = useUsageStream();
+  useEffect(() => {
+    if (liveUsage !== undefined) {
+      setUsage(liveUsage);
+    }
+  }, [liveUsage]);
+
   const lastUpdated = useMemo(() => {
     if (!usage) return '';
     const date = new Date(usage.captured_at);
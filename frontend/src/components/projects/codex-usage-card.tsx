@@ -6,6 +6,7 @@ import { Card, CardContent } from '@/components/ui/card';
 import { Button } from '@/components/ui/button';
 import type { CodexUsageSnapshot, CodexUsageWindow } from 'shared/types';
 import { usageApi } from '@/lib/api';
+import { useUsageStream } from '@/hooks/useUsageStream';
 
 function clampPercent(value: number | undefined | null) {
   if (value === undefined || value === null || Number.isNaN(value)) {
@@ -153,16 +154,17 @@ export function CodexUsageCard() {
 
   useEffect(() => {
     fetchUsage();
-
-    // Auto-refresh every 30 seconds to pick up usage from running Codex sessions
-    const interval = setInterval(() => {
-      fetchUsage();
-    }, 30000);
-
-    return () => clearInterval(interval);
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, []);
 
+  // The server pushes a new snapshot whenever running Codex sessions log usage
+  const { codex: liveUsage } = useUsageStream();
+  useEffect(() => {
+    if (liveUsage !== undefined) {
+      setUsage(liveUsage);
+    }
+  }, [liveUsage]);
+
   const lastUpdated = useMemo(() => {
     if (!usage) return '';
     const date = new Date(usage.captured_at);
@@ -0,0 +1,212 @@
+import { useCallback, useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Loader2, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import type { BaseCodingAgent, McpServerEntry } from 'shared/types';
+import { mcpServersApi } from '@/lib/api';
+
+type ServerKind = 'stdio' | 'http';
+
+type Props = {
+  executor: BaseCodingAgent;
+  /** Bump to re-read the config file after it was changed elsewhere. */
+  reloadToken: number;
+  onChanged: () => void;
+};
+
+function describeServer(config: McpServerEntry['config']): string {
+  if (!config || typeof config !== 'object' || Array.isArray(config)) {
+    return '';
+  }
+  const server = config as Record<string, unknown>;
+  const url = server.url ?? server.httpUrl;
+  if (typeof url === 'string') return url;
+  if (Array.isArray(server.command)) return server.command.join(' ');
+  const args = Array.isArray(server.args) ? server.args.join(' ') : '';
+  return [server.command, args].filter(Boolean).join(' ');
+}
+
+function McpServerManager({ executor, reloadToken, onChanged }: Props) {
+  const { t } = useTranslation('settings');
+  const [servers, setServers] = useState<McpServerEntry[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [name, setName] = useState('');
+  const [kind, setKind] = useState<ServerKind>('stdio');
+  const [command, setCommand] = useState('');
+  const [args, setArgs] = useState('');
+  const [url, setUrl] = useState('');
+
+  const load = useCallback(async () => {
+    setLoading(true);
+    try {
+      const result = await mcpServersApi.list({ executor });
+      setServers(result.servers);
+      setError(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setLoading(false);
+    }
+  }, [executor]);
+
+  useEffect(() => {
+    load();
+  }, [load, reloadToken]);
+
+  const resetForm = () => {
+    setName('');
+    setCommand('');
+    setArgs('');
+    setUrl('');
+  };
+
+  const handleAdd = async () => {
+    const server =
+      kind === 'http'
+        ? { type: 'http', url: url.trim() }
+        : {
+            command: command.trim(),
+            args: args.split(/\s+/).filter(Boolean),
+          };
+
+    setBusy(true);
+    try {
+      await mcpServersApi.upsertServer({ executor }, name.trim(), { server });
+      resetForm();
+      onChanged();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleRemove = async (serverName: string) => {
+    setBusy(true);
+    try {
+      await mcpServersApi.removeServer({ executor }, serverName);
+      onChanged();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const canAdd =
+    name.trim().length > 0 &&
+    (kind === 'http' ? url.trim().length > 0 : command.trim().length > 0);
+
+  return (
+    <div className="space-y-4">
+      <div className="space-y-2">
+        <Label>{t('settings.mcp.manager.configured')}</Label>
+        {loading ? (
+          <p className="text-sm text-muted-foreground">
+            {t('settings.mcp.loading.configuration')}
+          </p>
+        ) : servers.length === 0 ? (
+          <p className="text-sm text-muted-foreground">
+            {t('settings.mcp.manager.empty')}
+          </p>
+        ) : (
+          <ul className="divide-y rounded-md border">
+            {servers.map((server) => (
+              <li
+                key={server.name}
+                className="flex items-center justify-between gap-3 px-3 py-2"
+              >
+                <div className="min-w-0">
+                  <p className="text-sm font-medium">{server.name}</p>
+                  <p className="truncate font-mono text-xs text-muted-foreground">
+                    {describeServer(server.config)}
+                  </p>
+                </div>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  disabled={busy}
+                  onClick={() => handleRemove(server.name)}
+                  aria-label={t('settings.mcp.manager.remove', {
+                    name: server.name,
+                  })}
+                >
+                  <Trash2 className="h-4 w-4" />
+                </Button>
+              </li>
+            ))}
+          </ul>
+        )}
+      </div>
+
+      <div className="space-y-2">
+        <Label>{t('settings.mcp.manager.add')}</Label>
+        <div className="grid gap-2 sm:grid-cols-[1fr_8rem]">
+          <Input
+            placeholder={t('settings.mcp.manager.namePlaceholder')}
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+          />
+          <Select
+            value={kind}
+            onValueChange={(value) => setKind(value as ServerKind)}
+          >
+            <SelectTrigger>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="stdio">
+                {t('settings.mcp.manager.stdio')}
+              </SelectItem>
+              <SelectItem value="http">
+                {t('settings.mcp.manager.http')}
+              </SelectItem>
+            </SelectContent>
+          </Select>
+        </div>
+        {kind === 'http' ? (
+          <Input
+            placeholder="https://example.com/mcp"
+            value={url}
+            onChange={(e) => setUrl(e.target.value)}
+          />
+        ) : (
+          <div className="grid gap-2 sm:grid-cols-2">
+            <Input
+              placeholder={t('settings.mcp.manager.commandPlaceholder')}
+              value={command}
+              onChange={(e) => setCommand(e.target.value)}
+            />
+            <Input
+              placeholder={t('settings.mcp.manager.argsPlaceholder')}
+              value={args}
+              onChange={(e) => setArgs(e.target.value)}
+            />
+          </div>
+        )}
+        {error && (
+          <p className="text-sm text-destructive dark:text-red-400">{error}</p>
+        )}
+        <div className="flex justify-end">
+          <Button onClick={handleAdd} disabled={!canAdd || busy}>
+            {busy && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            {t('settings.mcp.manager.addButton')}
+          </Button>
+        </div>
+      </div>
+    </div>
+  );
+}
+
+export default McpServerManager;
@@ -1 +1,2 @@
 export { default as ExecutorProfileSelector } from './ExecutorProfileSelector';
+export { default as McpServerManager } from './McpServerManager';
@@ -1,4 +1,7 @@
-import { useDiffEntries } from '@/hooks/useDiffEntries';
+import {
+  useDiffEntries,
+  type DiffRepositoryGroup,
+} from '@/hooks/useDiffEntries';
 import { useMemo, useCallback, useState, useEffect, memo } from 'react';
 import { Loader } from '@/components/ui/loader';
 import { Button } from '@/components/ui/button';
@@ -18,7 +21,7 @@ function DiffTab({ selectedAttempt }: DiffTabProps) {
   const [hasInitialized, setHasInitialized] = useState(false);
   const { selectedRepositoryId, activeRepository } = useProject();
   const repositoryLabel = activeRepository?.name ?? 'Primary repository';
-  const { diffs, error } = useDiffEntries(
+  const { diffs, groups, error } = useDiffEntries(
     selectedAttempt?.id ?? null,
     true,
     selectedRepositoryId
@@ -62,7 +65,7 @@ function DiffTab({ selectedAttempt }: DiffTabProps) {
     ]);
     const initial = new Set(
       diffs
-        .filter((d) => kindsToCollapse.has(d.change))
+        .filter((d) => d.collapsed || kindsToCollapse.has(d.change))
         .map((d, i) => d.newPath || d.oldPath || String(i))
     );
     if (initial.size > 0) setCollapsedIds(initial);
@@ -113,6 +116,7 @@ function DiffTab({ selectedAttempt }: DiffTabProps) {
   return (
     <DiffTabContent
       diffs={diffs}
+      groups={groups}
       fileCount={fileCount}
       added={added}
       deleted={deleted}
@@ -128,6 +132,7 @@ function DiffTab({ selectedAttempt }: DiffTabProps) {
 
 interface DiffTabContentProps {
   diffs: any[];
+  groups: DiffRepositoryGroup[];
   fileCount: number;
   added: number;
   deleted: number;
@@ -141,6 +146,7 @@ interface DiffTabContentProps {
 
 const DiffTabContent = memo(function DiffTabContent({
   diffs,
+  groups,
   fileCount,
   added,
   deleted,
@@ -189,18 +195,35 @@ const DiffTabContent = memo(function DiffTabContent({
         </div>
       )}
       <div className="flex-1 overflow-y-auto px-4">
-        {diffs.map((diff, idx) => {
-          const id = diff.newPath || diff.oldPath || String(idx);
-          return (
-            <DiffCard
-              key={id}
-              diff={diff}
-              expanded={!collapsedIds.has(id)}
-              onToggle={() => toggle(id)}
-              selectedAttempt={selectedAttempt}
-            />
-          );
-        })}
+        {groups.map((group) => (
+          <section key={group.repositoryId ?? 'unassigned'}>
+            {groups.length > 1 && (
+              <h3 className="mt-4 text-xs font-mono text-muted-foreground">
+                {group.repositoryName ?? 'Unassigned files'} ·{' '}
+                {group.diffs.length} file
+                {group.diffs.length === 1 ? '' : 's'}{' '}
+                <span style={{ color: 'hsl(var(--console-success))' }}>
+                  +{group.additions}
+                </span>{' '}
+                <span style={{ color: 'hsl(var(--console-error))' }}>
+                  -{group.deletions}
+                </span>
+              </h3>
+            )}
+            {group.diffs.map((diff, idx) => {
+              const id = diff.newPath || diff.oldPath || String(idx);
+              return (
+                <DiffCard
+                  key={id}
+                  diff={diff}
+                  expanded={!collapsedIds.has(id)}
+                  onToggle={() => toggle(id)}
+                  selectedAttempt={selectedAttempt}
+                />
+              );
+            })}
+          </section>
+        ))}
       </div>
     </div>
   );
@@ -1,15 +1,26 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from 'react';
 import { Virtuoso, VirtuosoHandle } from 'react-virtuoso';
-import { AlertCircle } from 'lucide-react';
+import { Activity, AlertCircle } from 'lucide-react';
 import { useLogStream } from '@/hooks/useLogStream';
 import RawLogText from '@/components/common/RawLogText';
-import type { PatchType } from 'shared/types';
+import type { PatchType, ResourceUsage } from 'shared/types';
 
 type LogEntry = Extract<PatchType, { type: 'STDOUT' } | { type: 'STDERR' }>;
 
 // Add unique ID to log entries for stable React keys
 type LogEntryWithId = LogEntry & { _id: string };
 
+function formatResourceUsage(usage: ResourceUsage): string {
+  const memoryMb = usage.memory_bytes / (1024 * 1024);
+  const memory =
+    memoryMb >= 1024
+      ? `${(memoryMb / 1024).toFixed(1)} GB`
+      : `${Math.round(memoryMb)} MB`;
+  const processes =
+    usage.processes === 1 ? '1 process' : `${usage.processes} processes`;
+  return `CPU ${Math.round(usage.cpu_percent)}% · Memory ${memory} · ${processes}`;
+}
+
 interface ProcessLogsViewerProps {
   processId: string;
 }
@@ -23,7 +34,7 @@ export default function ProcessLogsViewer({
   const [atBottom, setAtBottom] = useState(true);
   const logIdCounterRef = useRef(0);
 
-  const { logs, error } = useLogStream(processId);
+  const { logs, error, resourceUsage } = useLogStream(processId);
 
   // Add stable IDs to logs for React keys (prevents index-based issues)
   const logsWithIds = useMemo<LogEntryWithId[]>(() => {
@@ -95,7 +106,13 @@ export default function ProcessLogsViewer({
   }, []);
 
   return (
-    <div className="h-full">
+    <div className="h-full flex flex-col">
+      {resourceUsage && (
+        <div className="flex items-center gap-2 px-4 py-1 border-b text-xs text-muted-foreground flex-shrink-0">
+          <Activity className="h-3 w-3" />
+          {formatResourceUsage(resourceUsage)}
+        </div>
+      )}
       {logsWithIds.length === 0 && !error ? (
         <div className="p-4 text-center text-muted-foreground text-sm">
           No logs available
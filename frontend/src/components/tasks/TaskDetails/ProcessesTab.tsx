@@ -7,6 +7,8 @@ import {
   Clock,
   Cog,
   ArrowLeft,
+  Download,
+  RotateCcw,
 } from 'lucide-react';
 import { executionProcessesApi } from '@/lib/api.ts';
 import { ProfileVariantBadge } from '@/components/common/ProfileVariantBadge.tsx';
@@ -35,11 +37,43 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
     Record<string, ExecutionProcess>
   >({});
 
+  const [replayedFrom, setReplayedFrom] = useState<Record<string, string>>(
+    {}
+  );
+  const [replayingProcessId, setReplayingProcessId] = useState<string | null>(
+    null
+  );
+  const [replayError, setReplayError] = useState<string | null>(null);
+
   useEffect(() => {
     setLocalProcessDetails({});
     setLoadingProcessId(null);
+    setReplayError(null);
   }, [attemptId]);
 
+  // Reload which processes are replays whenever a process is added
+  useEffect(() => {
+    if (!attemptId) {
+      setReplayedFrom({});
+      return;
+    }
+    let cancelled = false;
+    executionProcessesApi
+      .getReplays(attemptId)
+      .then((replays) => {
+        if (cancelled) return;
+        setReplayedFrom(
+          Object.fromEntries(
+            replays.map((r) => [r.execution_process_id, r.replayed_from_id])
+          )
+        );
+      })
+      .catch((err) => console.error('Failed to load replays:', err));
+    return () => {
+      cancelled = true;
+    };
+  }, [attemptId, executionProcesses.length]);
+
   const getStatusIcon = (status: ExecutionProcessStatus) => {
     switch (status) {
       case 'running':
@@ -128,6 +162,26 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
     ? localProcessDetails[selectedProcessId] ||
       executionProcessesById[selectedProcessId]
     : null;
+  // Cached details keep the status from when they were fetched
+  const selectedProcessRunning =
+    (selectedProcessId &&
+      executionProcessesById[selectedProcessId]?.status === 'running') ||
+    false;
+
+  const handleReplay = async (processId: string) => {
+    setReplayingProcessId(processId);
+    setReplayError(null);
+    try {
+      const replay = await executionProcessesApi.replay(processId);
+      setSelectedProcessId(replay.id);
+    } catch (err) {
+      setReplayError(
+        err instanceof Error ? err.message : 'Failed to replay process'
+      );
+    } finally {
+      setReplayingProcessId(null);
+    }
+  };
   const { isProcessGreyed } = useRetryUi();
 
   if (!attemptId) {
@@ -186,6 +240,11 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
                         <p className="text-sm text-muted-foreground mt-1">
                           Process ID: {process.id}
                         </p>
+                        {replayedFrom[process.id] && (
+                          <p className="text-xs text-muted-foreground mt-1">
+                            Replay of {replayedFrom[process.id]}
+                          </p>
+                        )}
                         {process.dropped && (
                           <span
                             className="inline-block mt-1 text-[10px] px-1.5 py-0.5 rounded-full bg-amber-100 text-amber-700 border border-amber-200"
@@ -247,14 +306,58 @@ function ProcessesTab({ attemptId }: ProcessesTabProps) {
         <div className="flex-1 flex flex-col min-h-0">
           <div className="flex items-center justify-between px-4 py-2 border-b flex-shrink-0">
             <h2 className="text-lg font-semibold">Process Details</h2>
-            <button
-              onClick={() => setSelectedProcessId(null)}
-              className="flex items-center gap-2 px-3 py-2 text-sm font-medium text-muted-foreground hover:text-foreground hover:bg-muted/50 rounded-md border border-border transition-colors"
-            >
-              <ArrowLeft className="h-4 w-4" />
-              Back to list
-            </button>
+            <div className="flex items-center gap-2">
+              {selectedProcess && (
+                <>
+                  <button
+                    onClick={() => handleReplay(selectedProcess.id)}
+                    disabled={
+                      selectedProcessRunning ||
+                      replayingProcessId === selectedProcess.id
+                    }
+                    title="Run this process again with the same prompt and profile"
+                    className="flex items-center gap-2 px-3 py-2 text-sm font-medium text-muted-foreground hover:text-foreground hover:bg-muted/50 rounded-md border border-border transition-colors disabled:opacity-50 disabled:pointer-events-none"
+                  >
+                    <RotateCcw className="h-4 w-4" />
+                    Replay
+                  </button>
+                  <a
+                    href={executionProcessesApi.rawLogsDownloadUrl(
+                      selectedProcess.id
+                    )}
+                    download
+                    className="flex items-center gap-2 px-3 py-2 text-sm font-medium text-muted-foreground hover:text-foreground hover:bg-muted/50 rounded-md border border-border transition-colors"
+                  >
+                    <Download className="h-4 w-4" />
+                    Raw logs
+                  </a>
+                  <a
+                    href={executionProcessesApi.normalizedLogsDownloadUrl(
+                      selectedProcess.id
+                    )}
+                    download
+                    className="flex items-center gap-2 px-3 py-2 text-sm font-medium text-muted-foreground hover:text-foreground hover:bg-muted/50 rounded-md border border-border transition-colors"
+                  >
+                    <Download className="h-4 w-4" />
+                    Conversation
+                  </a>
+                </>
+              )}
+              <button
+                onClick={() => setSelectedProcessId(null)}
+                className="flex items-center gap-2 px-3 py-2 text-sm font-medium text-muted-foreground hover:text-foreground hover:bg-muted/50 rounded-md border border-border transition-colors"
+              >
+                <ArrowLeft className="h-4 w-4" />
+                Back to list
+              </button>
+            </div>
           </div>
+          {replayError && (
+            <div className="px-4 py-2 text-sm text-destructive border-b flex-shrink-0">
+              <AlertCircle className="h-4 w-4 inline mr-2" />
+              {replayError}
+            </div>
+          )}
           <div className="flex-1">
             {selectedProcess ? (
               <ProcessLogsViewer processId={selectedProcess.id} />
@@ -12,8 +12,9 @@ import BranchSelector from '@/components/tasks/BranchSelector.tsx';
 import { ExecutorProfileSelector } from '@/components/settings';
 
 import { showModal } from '@/lib/modals';
-import { projectsApi } from '@/lib/api';
+import { attemptsApi, projectsApi } from '@/lib/api';
 import { Card } from '@/components/ui/card';
+import { Checkbox } from '@/components/ui/checkbox';
 import { Label } from '@/components/ui/label';
 import { openTaskForm } from '@/lib/openTaskForm';
 import { useProject } from '@/contexts/project-context';
@@ -70,6 +71,31 @@ function CreateAttempt({
   const [branchLoading, setBranchLoading] = useState<Record<string, boolean>>({});
   const [selectionError, setSelectionError] = useState<string | null>(null);
   const repositoryLabel = activeRepository?.name ?? 'Primary repository';
+  const previousAttemptId = (selectedAttempt ?? taskAttempts[0])?.id ?? null;
+  const [queuedFollowUpAttemptId, setQueuedFollowUpAttemptId] = useState<
+    string | null
+  >(null);
+  const [carryFollowUp, setCarryFollowUp] = useState(true);
+
+  // Offer to move a follow-up queued on the previous attempt, which would otherwise never run
+  useEffect(() => {
+    setQueuedFollowUpAttemptId(null);
+    if (!previousAttemptId) {
+      return;
+    }
+    let cancelled = false;
+    attemptsApi
+      .getDraft(previousAttemptId, 'follow_up')
+      .then((draft) => {
+        if (!cancelled && draft.queued && draft.prompt.trim().length > 0) {
+          setQueuedFollowUpAttemptId(previousAttemptId);
+        }
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, [previousAttemptId]);
 
   const ensureRepoBranches = useCallback(
     async (repoId: string) => {
@@ -251,6 +277,10 @@ function CreateAttempt({
       await createAttempt({
         profile,
         baseBranch: effectiveBaseBranch,
+        transferFollowUpFrom:
+          carryFollowUp && queuedFollowUpAttemptId
+            ? queuedFollowUpAttemptId
+            : undefined,
         repositories: repositorySelection.selectedIds.map((id) => {
           const override = repositorySelection.baseBranches[id];
           const normalizedBase =
@@ -268,7 +298,14 @@ function CreateAttempt({
         }),
       });
     },
-    [createAttempt, repositorySelection, selectedBranch, createAttemptBranch]
+    [
+      createAttempt,
+      repositorySelection,
+      selectedBranch,
+      createAttemptBranch,
+      carryFollowUp,
+      queuedFollowUpAttemptId,
+    ]
   );
 
   // Handler for Enter key or Start button
@@ -417,6 +454,25 @@ function CreateAttempt({
           </div>
         )}
 
+        {queuedFollowUpAttemptId && (
+          <div className="flex items-start gap-2">
+            <Checkbox
+              id="carry-follow-up"
+              checked={carryFollowUp}
+              onCheckedChange={setCarryFollowUp}
+              disabled={isCreating}
+            />
+            <div className="space-y-0.5">
+              <Label htmlFor="carry-follow-up" className="text-sm font-medium">
+                {t('createAttempt.carryFollowUp')}
+              </Label>
+              <p className="text-xs text-muted-foreground">
+                {t('createAttempt.carryFollowUpHint')}
+              </p>
+            </div>
+          </div>
+        )}
+
         <div className="pt-3 border-t">
           <Button
             onClick={() =>
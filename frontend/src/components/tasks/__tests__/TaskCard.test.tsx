@@ -21,6 +21,8 @@ const baseTask = (): TaskWithAttemptStatus => ({
   last_attempt_failed: false,
   has_running_dev_server: false,
   executor: 'executor',
+  labels: [],
+  due_at: null,
 });
 
 const renderWithDnd = (ui: ReactNode) =>
@@ -27,6 +27,11 @@ describe('useDiffEntries', () => {
       contentOmitted: true,
       additions: 1,
       deletions: 0,
+      lfs: null,
+      intraline: [],
+      binary: null,
+      collapsed: false,
+      movedBlocks: [],
     },
   });
 
@@ -53,4 +58,31 @@ describe('useDiffEntries', () => {
     expect(result.current.diffs).toHaveLength(1);
     expect(result.current.diffs[0].repositoryId).toBe('repo-2');
   });
+
+  it('groups diffs by repository with per-repository totals', () => {
+    mockUseDiffStream.mockImplementationOnce(() => ({
+      data: {
+        entries: {
+          c: sampleDiffEntry('repo-2', 'src/c.ts'),
+          a: sampleDiffEntry('repo-1', 'src/a.ts'),
+          b: sampleDiffEntry('repo-2', 'src/b.ts'),
+        },
+      },
+      isConnected: true,
+      error: null,
+    }));
+    const { result } = renderHook(() => useDiffEntries('attempt-1', true));
+    const { groups, diffs } = result.current;
+    expect(groups.map((g) => g.repositoryId)).toEqual(['repo-1', 'repo-2']);
+    expect(groups[1].diffs.map((d) => d.newPath)).toEqual([
+      'src/b.ts',
+      'src/c.ts',
+    ]);
+    expect(groups[1].additions).toBe(2);
+    expect(diffs.map((d) => d.newPath)).toEqual([
+      'src/a.ts',
+      'src/b.ts',
+      'src/c.ts',
+    ]);
+  });
 });
@@ -27,6 +27,8 @@ describe('useProjectTasks', () => {
     has_merged_attempt: false,
     last_attempt_failed: false,
     executor: 'agent',
+    labels: [],
+    due_at: null,
     id: 'task-id',
     project_id: 'project-1',
     title: 'Task title',
@@ -5,6 +5,7 @@ import type { Operation } from 'rfc6902';
 import useWebSocket from 'react-use-websocket';
 import type { Draft, DraftResponse } from 'shared/types';
 import { useProject } from '@/contexts/project-context';
+import { withBasePath } from '@/lib/basePath';
 
 interface Drafts {
   [attemptId: string]: { follow_up: Draft; retry: DraftResponse | null };
@@ -113,7 +114,7 @@ function useDraftsStreamState(projectId?: string): Drafts | undefined {
 function toWsUrl(endpoint?: string): string | undefined {
   if (!endpoint) return undefined;
   try {
-    const url = new URL(endpoint, window.location.origin);
+    const url = new URL(withBasePath(endpoint), window.location.origin);
     url.protocol = url.protocol.replace('http', 'ws');
     return url.toString();
   } catch {
@@ -6,6 +6,7 @@ import {
   activityFeedApi,
   deserializeActivityFeedItem,
 } from '@/lib/api';
+import { wsUrl } from '@/lib/basePath';
 import {
   useActivityFeedStore,
   useActivityFeedFilter,
@@ -36,10 +37,10 @@ const WS_RETRY_BASE_MS = 1000;
 const WS_RETRY_MAX_MS = 8000;
 
 const buildWsUrl = (projectId: string, scope: 'mine' | 'all') => {
-  const protocol = window.location.protocol === 'https:' ? 'wss' : 'ws';
-  const host = window.location.host;
   const params = new URLSearchParams({ scope });
-  return `${protocol}://${host}/api/projects/${projectId}/activity_feed/ws?${params.toString()}`;
+  return wsUrl(
+    `/api/projects/${projectId}/activity_feed/ws?${params.toString()}`
+  );
 };
 
 const normalizeItem = (
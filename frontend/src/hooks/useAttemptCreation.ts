@@ -15,6 +15,7 @@ export function useAttemptCreation(taskId: string) {
       profile,
       baseBranch,
       repositories,
+      transferFollowUpFrom,
     }: {
       profile: ExecutorProfileId;
       baseBranch: string;
@@ -23,12 +24,15 @@ export function useAttemptCreation(taskId: string) {
         is_primary: boolean;
         base_branch?: string | null;
       }>;
+      /** Previous attempt whose queued follow-up moves to the new attempt. */
+      transferFollowUpFrom?: string;
     }) =>
       attemptsApi.create({
         task_id: taskId,
         executor_profile_id: profile,
         base_branch: baseBranch,
         repositories,
+        transfer_follow_up_from: transferFollowUpFrom,
       }),
     onSuccess: (newAttempt: TaskAttempt) => {
       // Optimistically add to cache to prevent UI flicker
@@ -2,12 +2,54 @@ import { useMemo } from 'react';
 import { useDiffStream } from './useDiffStream';
 import type { Diff, PatchType } from 'shared/types';
 
+export interface DiffRepositoryGroup {
+  repositoryId: string | null;
+  repositoryName: string | null;
+  diffs: Diff[];
+  additions: number;
+  deletions: number;
+}
+
 interface UseDiffEntriesResult {
   diffs: Diff[];
+  groups: DiffRepositoryGroup[];
   isConnected: boolean;
   error: string | null;
 }
 
+const diffPath = (diff: Diff) => diff.newPath || diff.oldPath || '';
+
+// Same order as the server's initial snapshot: the repository at the project
+// root first, then the others by name, files by path within each
+function compareDiffs(a: Diff, b: Diff) {
+  return (
+    Number(!!a.repositoryRoot) - Number(!!b.repositoryRoot) ||
+    (a.repositoryName ?? '').localeCompare(b.repositoryName ?? '') ||
+    diffPath(a).localeCompare(diffPath(b))
+  );
+}
+
+export function groupDiffsByRepository(diffs: Diff[]): DiffRepositoryGroup[] {
+  const groups: DiffRepositoryGroup[] = [];
+  for (const diff of [...diffs].sort(compareDiffs)) {
+    let group = groups[groups.length - 1];
+    if (!group || group.repositoryId !== diff.repositoryId) {
+      group = {
+        repositoryId: diff.repositoryId,
+        repositoryName: diff.repositoryName,
+        diffs: [],
+        additions: 0,
+        deletions: 0,
+      };
+      groups.push(group);
+    }
+    group.diffs.push(diff);
+    group.additions += diff.additions ?? 0;
+    group.deletions += diff.deletions ?? 0;
+  }
+  return groups;
+}
+
 export const useDiffEntries = (
   attemptId: string | null,
   enabled: boolean,
@@ -19,9 +61,9 @@ export const useDiffEntries = (
     repositoryId
   );
 
-  const diffs = useMemo(() => {
+  const groups = useMemo(() => {
     if (!data) return [];
-    return Object.values(data.entries)
+    const diffs = Object.values(data.entries)
       .filter(
         (e): e is Extract<PatchType, { type: 'DIFF' }> => e?.type === 'DIFF'
       )
@@ -29,7 +71,13 @@ export const useDiffEntries = (
       .filter((diff) =>
         repositoryId ? diff.repositoryId === repositoryId : true
       );
+    return groupDiffsByRepository(diffs);
   }, [data, repositoryId]);
 
-  return { diffs, isConnected, error };
+  const diffs = useMemo(
+    () => groups.flatMap((group) => group.diffs),
+    [groups]
+  );
+
+  return { diffs, groups, isConnected, error };
 };
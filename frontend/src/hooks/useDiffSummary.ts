@@ -21,7 +21,12 @@ export function useDiffSummary(
     return diffs.reduce(
       (acc, d) => {
         try {
-          if (d.contentOmitted) {
+          // The server sends line counts with every diff; older payloads
+          // only carried them when contents were omitted
+          if (
+            d.contentOmitted ||
+            (d.additions != null && d.deletions != null)
+          ) {
             acc.added += d.additions ?? 0;
             acc.deleted += d.deletions ?? 0;
             return acc;
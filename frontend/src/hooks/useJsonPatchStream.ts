@@ -1,6 +1,7 @@
 import { useEffect, useState, useRef } from 'react';
 import { applyPatch } from 'rfc6902';
 import type { Operation } from 'rfc6902';
+import { withBasePath } from '@/lib/basePath';
 
 interface UseJsonPatchStreamOptions<T> {
   /**
@@ -81,7 +82,7 @@ export const useJsonPatchStream = <T>(
 
     // Create EventSource if it doesn't exist
     if (!eventSourceRef.current) {
-      const eventSource = new EventSource(endpoint);
+      const eventSource = new EventSource(withBasePath(endpoint));
 
       eventSource.onopen = () => {
         setError(null);
@@ -1,6 +1,7 @@
 import { useEffect, useState, useRef } from 'react';
 import { applyPatch } from 'rfc6902';
 import type { Operation } from 'rfc6902';
+import { withBasePath } from '@/lib/basePath';
 
 type WsJsonPatchMsg = { JsonPatch: Operation[] };
 type WsFinishedMsg = { finished: boolean };
@@ -86,7 +87,7 @@ export const useJsonPatchWsStream = <T>(
     // Create WebSocket if it doesn't exist
     if (!wsRef.current) {
       // Convert HTTP endpoint to WebSocket endpoint
-      const wsEndpoint = endpoint.replace(/^http/, 'ws');
+      const wsEndpoint = withBasePath(endpoint).replace(/^http/, 'ws');
       const ws = new WebSocket(wsEndpoint);
 
       ws.onopen = () => {
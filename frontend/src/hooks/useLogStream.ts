@@ -1,16 +1,22 @@
 import { useEffect, useState, useRef } from 'react';
-import type { PatchType } from 'shared/types';
+import type { PatchType, ResourceUsage } from 'shared/types';
+import { wsUrl } from '@/lib/basePath';
 
 type LogEntry = Extract<PatchType, { type: 'STDOUT' } | { type: 'STDERR' }>;
 
 interface UseLogStreamResult {
   logs: LogEntry[];
   error: string | null;
+  /** Latest CPU and memory sample while the process runs on the host */
+  resourceUsage: ResourceUsage | null;
 }
 
 export const useLogStream = (processId: string): UseLogStreamResult => {
   const [logs, setLogs] = useState<LogEntry[]>([]);
   const [error, setError] = useState<string | null>(null);
+  const [resourceUsage, setResourceUsage] = useState<ResourceUsage | null>(
+    null
+  );
   const wsRef = useRef<WebSocket | null>(null);
   const retryCountRef = useRef<number>(0);
   const retryTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
@@ -24,12 +30,11 @@ export const useLogStream = (processId: string): UseLogStreamResult => {
     // Clear logs when process changes
     setLogs([]);
     setError(null);
+    setResourceUsage(null);
 
     const open = () => {
-      const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
-      const host = window.location.host;
       const ws = new WebSocket(
-        `${protocol}//${host}/api/execution-processes/${processId}/raw-logs/ws`
+        wsUrl(`/api/execution-processes/${processId}/raw-logs/ws`)
       );
       wsRef.current = ws;
       isIntentionallyClosed.current = false;
@@ -93,6 +98,9 @@ export const useLogStream = (processId: string): UseLogStreamResult => {
                 case 'STDERR':
                   addLogEntry({ type: value.type, content: value.content });
                   break;
+                case 'RESOURCE_USAGE':
+                  setResourceUsage(value.content);
+                  break;
                 // Ignore other patch types (NORMALIZED_ENTRY, DIFF, etc.)
                 default:
                   break;
@@ -105,6 +113,7 @@ export const useLogStream = (processId: string): UseLogStreamResult => {
               flushTimerId = null;
             }
             flushLogs();
+            setResourceUsage(null);
             isIntentionallyClosed.current = true;
             ws.close();
           }
@@ -145,5 +154,5 @@ export const useLogStream = (processId: string): UseLogStreamResult => {
     };
   }, [processId]);
 
-  return { logs, error };
+  return { logs, error, resourceUsage };
 };
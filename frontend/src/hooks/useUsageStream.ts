@@ -0,0 +1,82 @@
+import { useEffect, useRef, useState } from 'react';
+import type {
+  ClaudeCodeUsageSnapshot,
+  CodexUsageSnapshot,
+  UsageUpdate,
+} from 'shared/types';
+import { wsUrl } from '@/lib/basePath';
+
+interface UseUsageStreamResult {
+  /** `undefined` until the server sends the first snapshot */
+  codex: CodexUsageSnapshot | null | undefined;
+  claudeCode: ClaudeCodeUsageSnapshot | null | undefined;
+}
+
+/**
+ * Latest Codex and Claude Code usage, pushed by the server whenever either
+ * agent's session logs change
+ */
+export const useUsageStream = (): UseUsageStreamResult => {
+  const [codex, setCodex] = useState<CodexUsageSnapshot | null | undefined>(
+    undefined
+  );
+  const [claudeCode, setClaudeCode] = useState<
+    ClaudeCodeUsageSnapshot | null | undefined
+  >(undefined);
+  const wsRef = useRef<WebSocket | null>(null);
+  const retryCountRef = useRef<number>(0);
+  const retryTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const isIntentionallyClosed = useRef<boolean>(false);
+
+  useEffect(() => {
+    const open = () => {
+      const ws = new WebSocket(wsUrl('/api/usage/stream/ws'));
+      wsRef.current = ws;
+      isIntentionallyClosed.current = false;
+
+      ws.onopen = () => {
+        retryCountRef.current = 0;
+      };
+
+      ws.onmessage = (event) => {
+        try {
+          const update: UsageUpdate = JSON.parse(event.data);
+          if (update.agent === 'codex') {
+            setCodex(update.snapshot);
+          } else {
+            setClaudeCode(update.snapshot);
+          }
+        } catch (e) {
+          console.error('Failed to parse usage update:', e);
+        }
+      };
+
+      ws.onclose = (event) => {
+        if (!isIntentionallyClosed.current && event.code !== 1000) {
+          const next = retryCountRef.current + 1;
+          retryCountRef.current = next;
+          if (next <= 6) {
+            const delay = Math.min(30000, 1000 * 2 ** (next - 1));
+            retryTimerRef.current = setTimeout(() => open(), delay);
+          }
+        }
+      };
+    };
+
+    open();
+
+    return () => {
+      if (wsRef.current) {
+        isIntentionallyClosed.current = true;
+        wsRef.current.close();
+        wsRef.current = null;
+      }
+      if (retryTimerRef.current) {
+        clearTimeout(retryTimerRef.current);
+        retryTimerRef.current = null;
+      }
+    };
+  }, []);
+
+  return { codex, claudeCode };
+};
@@ -1,3 +1,5 @@
+import { withBasePath } from '@/lib/basePath';
+
 export type AnalyticsPayload = Record<string, unknown>;
 
 const EVENT_NAME = 'analytics:event';
@@ -16,7 +18,7 @@ export const trackAnalyticsEvent = (
 
     if (typeof navigator !== 'undefined' && navigator.sendBeacon) {
       const body = JSON.stringify(detail);
-      navigator.sendBeacon('/api/analytics/event', body);
+      navigator.sendBeacon(withBasePath('/api/analytics/event'), body);
     }
   } catch (error) {
     if (import.meta.env.MODE !== 'production') {
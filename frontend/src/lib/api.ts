@@ -3,36 +3,98 @@
 import {
   ApprovalStatus,
   ApiResponse,
+  AuditLogEntry,
+  BranchProtection,
+  BranchPushOutcome,
   BranchStatus,
   CheckTokenResponse,
   Config,
   CommitInfo,
   CreateFollowUpAttempt,
+  HandoffTaskAttemptRequest,
+  PrCheckStatus,
+  LocalAnalyticsSummary,
+  ImportReviewCommentsRequest,
+  ImportReviewCommentsResponse,
+  Diff,
+  DiffComment,
+  CreateDiffComment,
+  UpdateDiffComment,
+  SendDiffCommentsRequest,
+  SendDiffCommentsResponse,
+  DiffSummary,
+  RevertDiffChangeRequest,
   CreateGitHubPrRequest,
+  CreateLabel,
   CreateTask,
+  CreateTaskDependency,
   CreateAndStartTaskRequest,
   CreateTaskAttemptBody,
   CreateTaskTemplate,
   DeviceFlowStartResponse,
   DevicePollStatus,
   DirectoryListResponse,
+  DevServerPort,
   DirectoryEntry,
   EditorType,
   ExecutionProcess,
+  ExecutionProcessReplay,
   GitBranch,
   Project,
+  ProjectWithUnreadActivity,
   CreateProject,
   ProjectRepository,
   CreateProjectRepository,
   UpdateProjectRepository,
   RepositoryInfo,
   SearchResult,
+  Label,
   Task,
   TaskAttempt,
   TaskRelationships,
+  AttemptStack,
   TaskTemplate,
+  PromptSnippet,
+  CreatePromptSnippet,
+  UpdatePromptSnippet,
+  ExpandedPrompt,
+  SetTaskLabels,
+  SetTaskDueDate,
+  OverdueTask,
+  TaskDependencies,
+  TaskDependency,
+  TaskDependencyGraph,
   TaskWithAttemptStatus,
+  TaskSortField,
+  TaskAttemptSortField,
+  SortDirection,
   UpdateProject,
+  UpdateLabel,
+  TaskSchedule,
+  CreateTaskSchedule,
+  UpdateTaskSchedule,
+  ImportTasksRequest,
+  TaskImportResult,
+  ProjectBundle,
+  ProjectBundleImportSummary,
+  ImportProjectBundle,
+  ImportedProjectBundle,
+  IssueSync,
+  LinkIssueSync,
+  TaskIssue,
+  IssueSyncSummary,
+  JiraSync,
+  UpsertJiraSync,
+  TaskJiraIssue,
+  JiraSyncSummary,
+  Webhook,
+  CreateWebhook,
+  UpdateWebhook,
+  User,
+  CreateUser,
+  UpdateUser,
+  LoginRequest,
+  LoginResponse,
   UpdateTask,
   UpdateTaskTemplate,
   UserSystemInfo,
@@ -41,20 +103,37 @@ import {
   McpServerQuery,
   UpdateMcpServersBody,
   GetMcpServerResponse,
+  McpServerEntry,
+  ApprovalPendingInfo,
+  McpServerList,
+  UpsertMcpServerBody,
   ImageResponse,
   DraftResponse,
   UpdateFollowUpDraftRequest,
   GitOperationError,
   ApprovalResponse,
   RebaseTaskAttemptRequest,
+  RestoreSnapshotRequest,
+  WorktreeSnapshot,
+  MergeTaskAttemptRequest,
   ChangeTargetBranchRequest,
   ChangeTargetBranchResponse,
   ActivityFeedItem,
+  ActivityFeedItemAction,
   ActivityFeedResponse,
   CodexUsageSnapshot,
   ClaudeCodeUsageSnapshot,
+  UsageAgent,
+  UsageSample,
+  AgentUsageSummary,
+  UsageAttribution,
+  BaseCodingAgent,
+  CostEstimate,
+  TaskAttemptCost,
+  BudgetStatus,
   GitRemote,
 } from 'shared/types';
+import { withBasePath } from '@/lib/basePath';
 
 // Re-export types for convenience
 export type { RepositoryInfo } from 'shared/types';
@@ -86,7 +165,7 @@ const makeRequest = async (url: string, options: RequestInit = {}) => {
     ...(options.headers || {}),
   };
 
-  return fetch(url, {
+  return fetch(withBasePath(url), {
     ...options,
     headers,
   });
@@ -98,6 +177,26 @@ export interface FollowUpResponse {
   created_new_attempt: boolean;
 }
 
+export interface ListOptions<S> {
+  sort?: S;
+  order?: SortDirection;
+  /** Page size; everything is returned when left out */
+  limit?: number;
+  offset?: number;
+}
+
+const setListParams = <S extends string>(
+  params: URLSearchParams,
+  options: ListOptions<S>
+) => {
+  if (options.sort) params.set('sort', options.sort);
+  if (options.order) params.set('order', options.order);
+  if (options.limit !== undefined) params.set('limit', String(options.limit));
+  if (options.offset !== undefined) {
+    params.set('offset', String(options.offset));
+  }
+};
+
 export type Ok<T> = { success: true; data: T };
 export type Err<E> = { success: false; error: E | undefined; message?: string };
 
@@ -233,9 +332,9 @@ const handleApiResponse = async <T, E = T>(response: Response): Promise<T> => {
 
 // Project Management APIs
 export const projectsApi = {
-  getAll: async (): Promise<Project[]> => {
+  getAll: async (): Promise<ProjectWithUnreadActivity[]> => {
     const response = await makeRequest('/api/projects');
-    return handleApiResponse<Project[]>(response);
+    return handleApiResponse<ProjectWithUnreadActivity[]>(response);
   },
 
   getById: async (id: string): Promise<Project> => {
@@ -266,6 +365,211 @@ export const projectsApi = {
     return handleApiResponse<void>(response);
   },
 
+  getLabels: async (projectId: string): Promise<Label[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/labels`);
+    return handleApiResponse<Label[]>(response);
+  },
+
+  createLabel: async (projectId: string, data: CreateLabel): Promise<Label> => {
+    const response = await makeRequest(`/api/projects/${projectId}/labels`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Label>(response);
+  },
+
+  updateLabel: async (
+    projectId: string,
+    labelId: string,
+    data: UpdateLabel
+  ): Promise<Label> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/labels/${labelId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<Label>(response);
+  },
+
+  deleteLabel: async (projectId: string, labelId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/labels/${labelId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  importTasks: async (
+    projectId: string,
+    data: ImportTasksRequest
+  ): Promise<TaskImportResult> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/import`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<TaskImportResult>(response);
+  },
+
+  // Signed address calendar apps subscribe to; the feed is served as text/calendar
+  calendarFeedUrl: async (projectId: string): Promise<string> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/calendar-url`
+    );
+    const path = await handleApiResponse<string>(response);
+    return `${window.location.origin}${withBasePath(path)}`;
+  },
+
+  exportBundle: async (projectId: string): Promise<ProjectBundle> => {
+    const response = await makeRequest(`/api/projects/${projectId}/export`);
+    if (!response.ok) {
+      const errorData = await response.json();
+      throw new ApiError(
+        errorData.message || 'Failed to export project',
+        response.status,
+        response
+      );
+    }
+    return response.json();
+  },
+
+  importBundle: async (
+    projectId: string,
+    bundle: ProjectBundle
+  ): Promise<ProjectBundleImportSummary> => {
+    const response = await makeRequest(`/api/projects/${projectId}/import`, {
+      method: 'POST',
+      body: JSON.stringify(bundle),
+    });
+    return handleApiResponse<ProjectBundleImportSummary>(response);
+  },
+
+  importBundleAsProject: async (
+    data: ImportProjectBundle
+  ): Promise<ImportedProjectBundle> => {
+    const response = await makeRequest(`/api/projects/import`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ImportedProjectBundle>(response);
+  },
+
+  getIssueSync: async (projectId: string): Promise<IssueSync | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/issues`);
+    return handleApiResponse<IssueSync | null>(response);
+  },
+
+  linkIssues: async (
+    projectId: string,
+    data: LinkIssueSync
+  ): Promise<IssueSync> => {
+    const response = await makeRequest(`/api/projects/${projectId}/issues`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<IssueSync>(response);
+  },
+
+  unlinkIssues: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${projectId}/issues`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  syncIssues: async (projectId: string): Promise<IssueSyncSummary> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/issues/sync`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<IssueSyncSummary>(response);
+  },
+
+  getJiraSync: async (projectId: string): Promise<JiraSync | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/jira`);
+    return handleApiResponse<JiraSync | null>(response);
+  },
+
+  upsertJiraSync: async (
+    projectId: string,
+    data: UpsertJiraSync
+  ): Promise<JiraSync> => {
+    const response = await makeRequest(`/api/projects/${projectId}/jira`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<JiraSync>(response);
+  },
+
+  deleteJiraSync: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${projectId}/jira`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  syncJira: async (projectId: string): Promise<JiraSyncSummary> => {
+    const response = await makeRequest(`/api/projects/${projectId}/jira/sync`, {
+      method: 'POST',
+    });
+    return handleApiResponse<JiraSyncSummary>(response);
+  },
+
+  getSchedules: async (projectId: string): Promise<TaskSchedule[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/schedules`);
+    return handleApiResponse<TaskSchedule[]>(response);
+  },
+
+  createSchedule: async (
+    projectId: string,
+    data: CreateTaskSchedule
+  ): Promise<TaskSchedule> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/schedules`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  updateSchedule: async (
+    projectId: string,
+    scheduleId: string,
+    data: UpdateTaskSchedule
+  ): Promise<TaskSchedule> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/schedules/${scheduleId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<TaskSchedule>(response);
+  },
+
+  deleteSchedule: async (
+    projectId: string,
+    scheduleId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/schedules/${scheduleId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
   openEditor: async (id: string, editorType?: EditorType): Promise<void> => {
     const requestBody: any = {};
     if (editorType) requestBody.editor_type = editorType;
@@ -374,8 +678,15 @@ export const projectsApi = {
 
 // Task Management APIs
 export const tasksApi = {
-  getAll: async (projectId: string): Promise<TaskWithAttemptStatus[]> => {
-    const response = await makeRequest(`/api/tasks?project_id=${projectId}`);
+  getAll: async (
+    projectId: string,
+    labelId?: string,
+    options: ListOptions<TaskSortField> = {}
+  ): Promise<TaskWithAttemptStatus[]> => {
+    const params = new URLSearchParams({ project_id: projectId });
+    if (labelId) params.set('label_id', labelId);
+    setListParams(params, options);
+    const response = await makeRequest(`/api/tasks?${params.toString()}`);
     return handleApiResponse<TaskWithAttemptStatus[]>(response);
   },
 
@@ -416,6 +727,77 @@ export const tasksApi = {
     });
     return handleApiResponse<void>(response);
   },
+
+  setLabels: async (taskId: string, data: SetTaskLabels): Promise<Label[]> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/labels`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Label[]>(response);
+  },
+
+  setDueDate: async (taskId: string, data: SetTaskDueDate): Promise<void> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/due-date`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getOverdue: async (projectId: string): Promise<OverdueTask[]> => {
+    const response = await makeRequest(
+      `/api/tasks/overdue?project_id=${projectId}`
+    );
+    return handleApiResponse<OverdueTask[]>(response);
+  },
+
+  getIssue: async (taskId: string): Promise<TaskIssue | null> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/issue`);
+    return handleApiResponse<TaskIssue | null>(response);
+  },
+
+  getJiraIssue: async (taskId: string): Promise<TaskJiraIssue | null> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/jira-issue`);
+    return handleApiResponse<TaskJiraIssue | null>(response);
+  },
+
+  getDependencyGraph: async (
+    projectId: string
+  ): Promise<TaskDependencyGraph> => {
+    const response = await makeRequest(
+      `/api/tasks/dependencies?project_id=${projectId}`
+    );
+    return handleApiResponse<TaskDependencyGraph>(response);
+  },
+
+  getDependencies: async (taskId: string): Promise<TaskDependencies> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/dependencies`);
+    return handleApiResponse<TaskDependencies>(response);
+  },
+
+  addDependency: async (
+    taskId: string,
+    data: CreateTaskDependency
+  ): Promise<TaskDependency> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/dependencies`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskDependency>(response);
+  },
+
+  removeDependency: async (
+    taskId: string,
+    dependsOnTaskId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/dependencies/${dependsOnTaskId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Task Attempts APIs
@@ -427,8 +809,116 @@ export const attemptsApi = {
     return handleApiResponse<TaskRelationships>(response);
   },
 
-  getAll: async (taskId: string): Promise<TaskAttempt[]> => {
-    const response = await makeRequest(`/api/task-attempts?task_id=${taskId}`);
+  getStack: async (attemptId: string): Promise<AttemptStack> => {
+    const response = await makeRequest(`/api/task-attempts/${attemptId}/stack`);
+    return handleApiResponse<AttemptStack>(response);
+  },
+
+  getPrChecks: async (attemptId: string): Promise<PrCheckStatus | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/pr/checks`
+    );
+    return handleApiResponse<PrCheckStatus | null>(response);
+  },
+
+  getPendingApprovals: async (
+    attemptId: string
+  ): Promise<ApprovalPendingInfo[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/approvals`
+    );
+    return handleApiResponse<ApprovalPendingInfo[]>(response);
+  },
+
+  importReviewComments: async (
+    attemptId: string,
+    data: ImportReviewCommentsRequest
+  ): Promise<ImportReviewCommentsResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/pr/review-comments/import`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ImportReviewCommentsResponse>(response);
+  },
+
+  getDiffComments: async (
+    attemptId: string,
+    includeSent = false
+  ): Promise<DiffComment[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/diff-comments?include_sent=${includeSent}`
+    );
+    return handleApiResponse<DiffComment[]>(response);
+  },
+
+  createDiffComment: async (
+    attemptId: string,
+    data: CreateDiffComment
+  ): Promise<DiffComment> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/diff-comments`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DiffComment>(response);
+  },
+
+  updateDiffComment: async (
+    attemptId: string,
+    commentId: string,
+    data: UpdateDiffComment
+  ): Promise<DiffComment> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/diff-comments/${commentId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DiffComment>(response);
+  },
+
+  deleteDiffComment: async (
+    attemptId: string,
+    commentId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/diff-comments/${commentId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  sendDiffComments: async (
+    attemptId: string,
+    data: SendDiffCommentsRequest
+  ): Promise<SendDiffCommentsResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/diff-comments/send`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<SendDiffCommentsResponse>(response);
+  },
+
+  getAll: async (
+    taskId: string,
+    options: ListOptions<TaskAttemptSortField> = {}
+  ): Promise<TaskAttempt[]> => {
+    const params = new URLSearchParams({ task_id: taskId });
+    setListParams(params, options);
+    const response = await makeRequest(
+      `/api/task-attempts?${params.toString()}`
+    );
     return handleApiResponse<TaskAttempt[]>(response);
   },
 
@@ -460,6 +950,13 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  keep: async (attemptId: string): Promise<void> => {
+    const response = await makeRequest(`/api/task-attempts/${attemptId}/keep`, {
+      method: 'POST',
+    });
+    return handleApiResponse<void>(response);
+  },
+
   replaceProcess: async (
     attemptId: string,
     data: {
@@ -494,6 +991,20 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  handoff: async (
+    attemptId: string,
+    data: HandoffTaskAttemptRequest
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/handoff`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+
   getDraft: async (
     attemptId: string,
     type: 'follow_up' | 'retry'
@@ -535,7 +1046,8 @@ export const attemptsApi = {
     queued: boolean,
     expectedQueued?: boolean,
     expectedVersion?: number,
-    type: 'follow_up' | 'retry' = 'follow_up'
+    type: 'follow_up' | 'retry' = 'follow_up',
+    runAt?: Date
   ): Promise<DraftResponse> => {
     const response = await makeRequest(
       `/api/task-attempts/${attemptId}/draft/queue?type=${encodeURIComponent(type)}`,
@@ -545,6 +1057,7 @@ export const attemptsApi = {
           queued,
           expected_queued: expectedQueued,
           expected_version: expectedVersion,
+          run_at: runAt?.toISOString(),
         }),
       }
     );
@@ -566,6 +1079,31 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  getDiffSummary: async (
+    attemptId: string,
+    repoId?: string
+  ): Promise<DiffSummary> => {
+    const query = repoId ? `?repo_id=${encodeURIComponent(repoId)}` : '';
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/diff/summary${query}`
+    );
+    return handleApiResponse<DiffSummary>(response);
+  },
+
+  revertDiffChange: async (
+    attemptId: string,
+    data: RevertDiffChangeRequest
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/diff/revert`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
   openEditor: async (
     attemptId: string,
     editorType?: EditorType,
@@ -594,16 +1132,29 @@ export const attemptsApi = {
     return handleApiResponse<BranchStatus>(response);
   },
 
-  merge: async (attemptId: string): Promise<void> => {
+  merge: async (
+    attemptId: string,
+    data: Partial<MergeTaskAttemptRequest> = {}
+  ): Promise<void> => {
     const response = await makeRequest(
       `/api/task-attempts/${attemptId}/merge`,
       {
         method: 'POST',
+        body: JSON.stringify(data),
       }
     );
     return handleApiResponse<void>(response);
   },
 
+  getBranchProtection: async (
+    attemptId: string
+  ): Promise<BranchProtection | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge/protection`
+    );
+    return handleApiResponse<BranchProtection | null>(response);
+  },
+
   push: async (attemptId: string): Promise<void> => {
     const response = await makeRequest(`/api/task-attempts/${attemptId}/push`, {
       method: 'POST',
@@ -611,6 +1162,16 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  pushPrUpdates: async (attemptId: string): Promise<BranchPushOutcome> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/pr/push`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<BranchPushOutcome>(response);
+  },
+
   rebase: async (
     attemptId: string,
     data: RebaseTaskAttemptRequest
@@ -649,6 +1210,27 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  getSnapshots: async (attemptId: string): Promise<WorktreeSnapshot[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/snapshots`
+    );
+    return handleApiResponse<WorktreeSnapshot[]>(response);
+  },
+
+  restoreSnapshot: async (
+    attemptId: string,
+    data: RestoreSnapshotRequest
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/snapshots/restore`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
   createPR: async (
     attemptId: string,
     data: CreateGitHubPrRequest
@@ -716,6 +1298,24 @@ export const executionProcessesApi = {
     return handleApiResponse<ExecutionProcess>(response);
   },
 
+  /** Run the process's executor action again as a new process of the same attempt */
+  replay: async (processId: string): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/replay`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+
+  getReplays: async (attemptId: string): Promise<ExecutionProcessReplay[]> => {
+    const response = await makeRequest(
+      `/api/execution-processes/replays?task_attempt_id=${attemptId}`
+    );
+    return handleApiResponse<ExecutionProcessReplay[]>(response);
+  },
+
   stopExecutionProcess: async (processId: string): Promise<void> => {
     const response = await makeRequest(
       `/api/execution-processes/${processId}/stop`,
@@ -725,6 +1325,30 @@ export const executionProcessesApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  getDevServer: async (processId: string): Promise<DevServerPort | null> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/dev-server`
+    );
+    return handleApiResponse<DevServerPort | null>(response);
+  },
+
+  /** Download address of the process's stdout and stderr as a text file */
+  rawLogsDownloadUrl: (processId: string): string =>
+    withBasePath(`/api/execution-processes/${processId}/raw-logs/download`),
+
+  /** Download address of the process's normalized conversation as JSON */
+  normalizedLogsDownloadUrl: (processId: string): string =>
+    withBasePath(
+      `/api/execution-processes/${processId}/normalized-logs/download`
+    ),
+
+  getDiff: async (processId: string, statsOnly = false): Promise<Diff[]> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/diff?stats_only=${statsOnly}`
+    );
+    return handleApiResponse<Diff[]>(response);
+  },
 };
 
 // File System APIs
@@ -798,6 +1422,86 @@ export const usageApi = {
     const response = await makeRequest('/api/usage/claude-code');
     return handleApiResponse<ClaudeCodeUsageSnapshot | null>(response);
   },
+  /** Latest usage of every agent in one normalized shape */
+  getSummary: async (): Promise<AgentUsageSummary[]> => {
+    const response = await makeRequest('/api/usage/summary');
+    return handleApiResponse<AgentUsageSummary[]>(response);
+  },
+  /** Samples between `from` and `to`, by default the last day */
+  getHistory: async (
+    agent: UsageAgent,
+    range: { from?: Date; to?: Date } = {}
+  ): Promise<UsageSample[]> => {
+    const params = new URLSearchParams();
+    if (range.from) params.set('from', range.from.toISOString());
+    if (range.to) params.set('to', range.to.toISOString());
+    const response = await makeRequest(
+      `/api/usage/${agent}/history?${params.toString()}`
+    );
+    return handleApiResponse<UsageSample[]>(response);
+  },
+  /** Tokens per project and task, by default over the last week */
+  getAttribution: async (
+    range: { from?: Date; to?: Date; projectId?: string } = {}
+  ): Promise<UsageAttribution> => {
+    const params = new URLSearchParams();
+    if (range.from) params.set('from', range.from.toISOString());
+    if (range.to) params.set('to', range.to.toISOString());
+    if (range.projectId) params.set('project_id', range.projectId);
+    const response = await makeRequest(
+      `/api/usage/attribution?${params.toString()}`
+    );
+    return handleApiResponse<UsageAttribution>(response);
+  },
+  /** Download address of the recorded usage and its cost as CSV */
+  exportCsvUrl: (
+    range: { from?: Date; to?: Date; agent?: BaseCodingAgent } = {}
+  ): string => {
+    const params = new URLSearchParams();
+    if (range.from) params.set('from', range.from.toISOString());
+    if (range.to) params.set('to', range.to.toISOString());
+    if (range.agent) params.set('agent', range.agent);
+    return withBasePath(`/api/usage/export?${params.toString()}`);
+  },
+};
+
+// Estimated dollar cost of coding agent runs
+export const costsApi = {
+  getExecutionProcess: async (processId: string): Promise<CostEstimate> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/cost`
+    );
+    return handleApiResponse<CostEstimate>(response);
+  },
+  getAttempt: async (attemptId: string): Promise<TaskAttemptCost> => {
+    const response = await makeRequest(`/api/task-attempts/${attemptId}/cost`);
+    return handleApiResponse<TaskAttemptCost>(response);
+  },
+  getTask: async (taskId: string): Promise<CostEstimate> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/cost`);
+    return handleApiResponse<CostEstimate>(response);
+  },
+  getProject: async (projectId: string): Promise<CostEstimate> => {
+    const response = await makeRequest(`/api/projects/${projectId}/cost`);
+    return handleApiResponse<CostEstimate>(response);
+  },
+};
+
+// Monthly usage budgets per coding agent, as they stand this month
+export const budgetsApi = {
+  list: async (): Promise<BudgetStatus[]> => {
+    const response = await makeRequest('/api/budgets');
+    return handleApiResponse<BudgetStatus[]>(response);
+  },
+};
+
+// Local-only analytics (collected while external analytics is disabled)
+export const localAnalyticsApi = {
+  getSummary: async (days?: number): Promise<LocalAnalyticsSummary> => {
+    const query = days ? `?days=${days}` : '';
+    const response = await makeRequest(`/api/analytics/local${query}`);
+    return handleApiResponse<LocalAnalyticsSummary>(response);
+  },
 };
 
 export const activityFeedApi = {
@@ -827,6 +1531,27 @@ export const activityFeedApi = {
       events: payload.events.map(deserializeActivityFeedItem),
     };
   },
+
+  markRead: async (projectId: string, eventId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/activity_feed/${eventId}/read`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  markAllRead: async (projectId: string): Promise<number> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/activity_feed/read-all`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<number>(response);
+  },
+
+  runAction: async (action: ActivityFeedItemAction): Promise<void> => {
+    const response = await makeRequest(action.endpoint, { method: 'POST' });
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Task Templates APIs
@@ -880,6 +1605,169 @@ export const templatesApi = {
   },
 };
 
+// Prompt snippet APIs
+export const snippetsApi = {
+  list: async (): Promise<PromptSnippet[]> => {
+    const response = await makeRequest('/api/snippets');
+    return handleApiResponse<PromptSnippet[]>(response);
+  },
+
+  listGlobal: async (): Promise<PromptSnippet[]> => {
+    const response = await makeRequest('/api/snippets?global=true');
+    return handleApiResponse<PromptSnippet[]>(response);
+  },
+
+  /** Project snippets plus the global ones they do not override. */
+  listForProject: async (projectId: string): Promise<PromptSnippet[]> => {
+    const response = await makeRequest(
+      `/api/snippets?project_id=${projectId}`
+    );
+    return handleApiResponse<PromptSnippet[]>(response);
+  },
+
+  create: async (data: CreatePromptSnippet): Promise<PromptSnippet> => {
+    const response = await makeRequest('/api/snippets', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<PromptSnippet>(response);
+  },
+
+  update: async (
+    snippetId: string,
+    data: UpdatePromptSnippet
+  ): Promise<PromptSnippet> => {
+    const response = await makeRequest(`/api/snippets/${snippetId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<PromptSnippet>(response);
+  },
+
+  delete: async (snippetId: string): Promise<void> => {
+    const response = await makeRequest(`/api/snippets/${snippetId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  expand: async (
+    projectId: string,
+    prompt: string
+  ): Promise<ExpandedPrompt> => {
+    const response = await makeRequest('/api/snippets/expand', {
+      method: 'POST',
+      body: JSON.stringify({ project_id: projectId, prompt }),
+    });
+    return handleApiResponse<ExpandedPrompt>(response);
+  },
+};
+
+// Outbound webhook APIs
+export const webhooksApi = {
+  list: async (): Promise<Webhook[]> => {
+    const response = await makeRequest('/api/webhooks');
+    return handleApiResponse<Webhook[]>(response);
+  },
+
+  create: async (data: CreateWebhook): Promise<Webhook> => {
+    const response = await makeRequest('/api/webhooks', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Webhook>(response);
+  },
+
+  update: async (webhookId: string, data: UpdateWebhook): Promise<Webhook> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Webhook>(response);
+  },
+
+  delete: async (webhookId: string): Promise<void> => {
+    const response = await makeRequest(`/api/webhooks/${webhookId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Users APIs
+export const usersApi = {
+  login: async (data: LoginRequest): Promise<LoginResponse> => {
+    const response = await makeRequest('/api/auth/login', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<LoginResponse>(response);
+  },
+
+  logout: async (): Promise<void> => {
+    const response = await makeRequest('/api/auth/logout', {
+      method: 'POST',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  me: async (): Promise<User | null> => {
+    const response = await makeRequest('/api/users/me');
+    return handleApiResponse<User | null>(response);
+  },
+
+  list: async (): Promise<User[]> => {
+    const response = await makeRequest('/api/users');
+    return handleApiResponse<User[]>(response);
+  },
+
+  create: async (data: CreateUser): Promise<User> => {
+    const response = await makeRequest('/api/users', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<User>(response);
+  },
+
+  update: async (userId: string, data: UpdateUser): Promise<User> => {
+    const response = await makeRequest(`/api/users/${userId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<User>(response);
+  },
+
+  delete: async (userId: string): Promise<void> => {
+    const response = await makeRequest(`/api/users/${userId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+export interface AuditLogQuery {
+  actorUserId?: string;
+  /** Collection name as in API paths, e.g. `task-attempts` */
+  entityType?: string;
+  entityId?: string;
+  limit?: number;
+  offset?: number;
+}
+
+// Audit log APIs
+export const auditLogApi = {
+  list: async (query: AuditLogQuery = {}): Promise<AuditLogEntry[]> => {
+    const params = new URLSearchParams();
+    if (query.actorUserId) params.set('actor_user_id', query.actorUserId);
+    if (query.entityType) params.set('entity_type', query.entityType);
+    if (query.entityId) params.set('entity_id', query.entityId);
+    if (query.limit !== undefined) params.set('limit', String(query.limit));
+    if (query.offset !== undefined) params.set('offset', String(query.offset));
+    const response = await makeRequest(`/api/audit-log?${params.toString()}`);
+    return handleApiResponse<AuditLogEntry[]>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (query: McpServerQuery): Promise<GetMcpServerResponse> => {
@@ -912,6 +1800,36 @@ export const mcpServersApi = {
       );
     }
   },
+  list: async (query: McpServerQuery): Promise<McpServerList> => {
+    const params = new URLSearchParams(query);
+    const response = await makeRequest(
+      `/api/mcp-config/servers?${params.toString()}`
+    );
+    return handleApiResponse<McpServerList>(response);
+  },
+  upsertServer: async (
+    query: McpServerQuery,
+    name: string,
+    data: UpsertMcpServerBody
+  ): Promise<McpServerEntry> => {
+    const params = new URLSearchParams(query);
+    const response = await makeRequest(
+      `/api/mcp-config/servers/${encodeURIComponent(name)}?${params.toString()}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<McpServerEntry>(response);
+  },
+  removeServer: async (query: McpServerQuery, name: string): Promise<void> => {
+    const params = new URLSearchParams(query);
+    const response = await makeRequest(
+      `/api/mcp-config/servers/${encodeURIComponent(name)}?${params.toString()}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Profiles API
@@ -938,7 +1856,7 @@ export const imagesApi = {
     const formData = new FormData();
     formData.append('image', file);
 
-    const response = await fetch('/api/images/upload', {
+    const response = await fetch(withBasePath('/api/images/upload'), {
       method: 'POST',
       body: formData,
       credentials: 'include',
@@ -960,11 +1878,14 @@ export const imagesApi = {
     const formData = new FormData();
     formData.append('image', file);
 
-    const response = await fetch(`/api/images/task/${taskId}/upload`, {
-      method: 'POST',
-      body: formData,
-      credentials: 'include',
-    });
+    const response = await fetch(
+      withBasePath(`/api/images/task/${taskId}/upload`),
+      {
+        method: 'POST',
+        body: formData,
+        credentials: 'include',
+      }
+    );
 
     if (!response.ok) {
       const errorText = await response.text();
@@ -0,0 +1,20 @@
+declare global {
+  interface Window {
+    /** Set by the server when the app is served under a path prefix */
+    __VIBE_BASE_PATH__?: string;
+  }
+}
+
+/** Prefix the app is served under, e.g. `/kanban`; empty at the root */
+export const BASE_PATH =
+  (typeof window !== 'undefined' && window.__VIBE_BASE_PATH__) || '';
+
+/** Prefix an absolute path such as `/api/tasks` with the base path */
+export const withBasePath = (path: string) =>
+  path.startsWith('/') && !path.startsWith('//') ? `${BASE_PATH}${path}` : path;
+
+/** WebSocket URL for an absolute path on this server */
+export const wsUrl = (path: string) => {
+  const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
+  return `${protocol}//${window.location.host}${withBasePath(path)}`;
+};
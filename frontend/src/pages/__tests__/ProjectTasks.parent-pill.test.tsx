@@ -177,6 +177,8 @@ const baseTask = (overrides: Partial<TaskWithAttemptStatus>): TaskWithAttemptSta
   has_merged_attempt: false,
   last_attempt_failed: false,
   executor: 'executor',
+  labels: [],
+  due_at: null,
   ...overrides,
 });
 
@@ -44,6 +44,7 @@ import { useTheme } from '@/components/theme-provider';
 import { useUserSystem } from '@/components/config-provider';
 import { TaskTemplateManager } from '@/components/TaskTemplateManager';
 import NiceModal from '@ebay/nice-modal-react';
+import { withBasePath } from '@/lib/basePath';
 
 const CLAUDE_PLAN_OPTIONS = ['free', 'pro', 'max5x', 'max20x'] as const satisfies ReadonlyArray<ClaudePlan>;
 
@@ -115,7 +116,7 @@ export function GeneralSettings() {
   }, [hasUnsavedChanges]);
 
   const playSound = async (soundFile: SoundFile) => {
-    const audio = new Audio(`/api/sounds/${soundFile}`);
+    const audio = new Audio(withBasePath(`/api/sounds/${soundFile}`));
     try {
       await audio.play();
     } catch (err) {
@@ -29,6 +29,7 @@ import { Loader2 } from 'lucide-react';
 import type { BaseCodingAgent, ExecutorConfig } from 'shared/types';
 import { McpConfig } from 'shared/types';
 import { useUserSystem } from '@/components/config-provider';
+import { McpServerManager } from '@/components/settings';
 import { mcpServersApi } from '@/lib/api';
 import { McpConfigStrategyGeneral } from '@/lib/mcp-strategies';
 
@@ -45,6 +46,7 @@ export function McpSettings() {
   const [mcpApplying, setMcpApplying] = useState(false);
   const [mcpConfigPath, setMcpConfigPath] = useState<string>('');
   const [success, setSuccess] = useState(false);
+  const [reloadToken, setReloadToken] = useState(0);
 
   // Initialize selected profile when config loads
   useEffect(() => {
@@ -106,7 +108,7 @@ export function McpSettings() {
     if (selectedProfile) {
       loadMcpServersForProfile(selectedProfile);
     }
-  }, [selectedProfile]);
+  }, [selectedProfile, reloadToken]);
 
   const handleMcpServersChange = (value: string) => {
     setMcpServers(value);
@@ -168,6 +170,7 @@ export function McpSettings() {
           );
 
           // Show success feedback
+          setReloadToken((token) => token + 1);
           setSuccess(true);
           setTimeout(() => setSuccess(false), 3000);
         } catch (mcpErr) {
@@ -219,6 +222,11 @@ export function McpSettings() {
     Object.entries(preconfigured).filter(([k]) => k !== 'meta')
   ) as Record<string, any>;
   const getMetaFor = (key: string) => meta[key] || {};
+  const selectedProfileKey = selectedProfile
+    ? Object.keys(profiles || {}).find(
+        (key) => profiles![key] === selectedProfile
+      )
+    : undefined;
 
   if (!config) {
     return (
@@ -430,6 +438,25 @@ export function McpSettings() {
         </CardContent>
       </Card>
 
+      {selectedProfileKey &&
+        !(mcpError && mcpError.includes('does not support MCP')) && (
+          <Card>
+            <CardHeader>
+              <CardTitle>{t('settings.mcp.manager.title')}</CardTitle>
+              <CardDescription>
+                {t('settings.mcp.manager.description')}
+              </CardDescription>
+            </CardHeader>
+            <CardContent>
+              <McpServerManager
+                executor={selectedProfileKey as BaseCodingAgent}
+                reloadToken={reloadToken}
+                onChanged={() => setReloadToken((token) => token + 1)}
+              />
+            </CardContent>
+          </Card>
+        )}
+
       {/* Sticky Save Button */}
       <div className="sticky bottom-0 z-10 bg-background/80 backdrop-blur-sm border-t py-4">
         <div className="flex justify-end">
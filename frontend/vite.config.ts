@@ -117,6 +117,8 @@ const backendPort = resolveBackendPort();
 const backendHost = process.env.BACKEND_HOST || "localhost";
 
 export default defineConfig({
+  // Relative asset URLs, so the server can serve the app under any path prefix
+  base: "./",
   plugins: [
     react(),
     sentryVitePlugin({ org: "bloop-ai", project: "vibe-kanban" }),
@@ -24,13 +24,243 @@ export type CreateProjectRepository = { name: string, git_repo_path: string, roo
 
 export type UpdateProjectRepository = { name: string | null, git_repo_path: string | null, root_path: string | null, is_primary: boolean | null, };
 
+export type ProjectWithUnreadActivity = { 
+/**
+ * Events in the project's activity feed the current user has not acknowledged
+ */
+unread_activity_count: number, id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, created_at: Date, updated_at: Date, };
+
+export type AttemptDiffStats = { task_attempt_id: string, task_id: string, files_changed: number, additions: number, deletions: number, 
+/**
+ * Set when the diff could not be computed; the counts are zero in that case
+ */
+error: string | null, };
+
+export type VerifyScriptBody = { 
+/**
+ * Command run after the coding agent; the task only moves to review if it exits 0
+ */
+verify_script: string | null, 
+/**
+ * Follow-ups sent automatically with the failing output when the cleanup or verify script
+ * fails after a coding agent run; 0 turns them off
+ */
+auto_fix_attempts: number, };
+
+/**
+ * Overrides for the worktree diff watcher; `None` fields fall back to the watcher defaults.
+ */
+export type ProjectWatcherSettings = { 
+/**
+ * Gitignore-style patterns whose changes are not streamed; an empty list excludes nothing
+ */
+exclude_patterns: Array<string> | null, 
+/**
+ * Soft cap on filesystem events per second per diff stream; 0 disables throttling
+ */
+max_events_per_second: number | null, 
+/**
+ * Gitignore-style patterns for files such as lockfiles whose diffs start collapsed, with
+ * contents omitted; an empty list collapses nothing
+ */
+diff_ignore_patterns: Array<string> | null, };
+
+/**
+ * How dependency directories from the main checkout end up in a new worktree
+ */
+export type DependencyShareMode = "symlink" | "clone";
+
+/**
+ * How attempt worktrees are prepared for the project
+ */
+export type ProjectWorktreeSettings = { 
+/**
+ * Run `git submodule update --init --recursive` after creating a worktree
+ */
+init_submodules: boolean, 
+/**
+ * Git-ignored directories such as `node_modules`, `target` or `.venv` taken from the main
+ * checkout instead of being installed again in every worktree
+ */
+shared_dirs: Array<string>, share_mode: DependencyShareMode, 
+/**
+ * Worktrees kept ready, with the setup script already run, for new attempts to claim
+ */
+warm_pool_size: number, };
+
+/**
+ * Scheduled `git maintenance` for the project's repositories
+ */
+export type ProjectGitMaintenanceSettings = { enabled: boolean, 
+/**
+ * Hours between runs
+ */
+interval_hours: number, 
+/**
+ * When maintenance last ran; ignored on update
+ */
+last_run_at: Date | null, };
+
+/**
+ * What happens when the last process of a run finishes
+ */
+export type FinalizationRule = { 
+/**
+ * Status the task moves to; `None` leaves it unchanged
+ */
+task_status: TaskStatus | null, notify: boolean, 
+/**
+ * Start the attempt's queued follow-up, if any
+ */
+consume_queued_follow_up: boolean, };
+
+/**
+ * Finalization rules keyed by the run reason of the process that finished last. Dev servers are
+ * never finalized.
+ */
+export type ProjectFinalizationSettings = { coding_agent: FinalizationRule, setup_script: FinalizationRule, cleanup_script: FinalizationRule, 
+/**
+ * Only applied when the verify script passed
+ */
+verify_script: FinalizationRule, };
+
+/**
+ * How pull requests are opened for the project's attempts
+ */
+export type ProjectPrSettings = { 
+/**
+ * Repository-relative PR template; when unset the usual template locations are checked
+ */
+template_path: string | null, 
+/**
+ * Delete the attempt branch on its remote once the PR is merged or the attempt is merged
+ * locally
+ */
+delete_remote_branch_after_merge: boolean, };
+
+export type ContainerRuntime = "docker" | "podman";
+
+/**
+ * Isolation of the project's execution processes in a container. The worktree and the
+ * repository are mounted at their host paths; dev servers keep running on the host so their
+ * ports stay reachable.
+ */
+export type ProjectSandboxSettings = { enabled: boolean, runtime: ContainerRuntime, 
+/**
+ * Image providing the shell and the coding agent CLIs
+ */
+image: string | null, };
+
+/**
+ * CPU and memory caps for an execution process and everything it starts. `None` leaves the
+ * resource unlimited.
+ */
+export type ResourceLimits = { 
+/**
+ * Percent of one CPU core, so 250 allows two and a half cores
+ */
+cpu_percent: number | null, memory_mb: number | null, };
+
+/**
+ * CPU and memory an execution process and everything it started use at one point in time
+ */
+export type ResourceUsage = { 
+/**
+ * Percent of one CPU core since the previous sample, so 250 is two and a half busy cores
+ */
+cpu_percent: number, memory_bytes: number, processes: number, };
+
+/**
+ * Kind of server work attributed to a project
+ */
+export type ProjectActivityKind = "execution_started" | "diff_streamed" | "search_run";
+
+export type ActivityStats = { count: number, total_ms: number, max_ms: number, last_at: string | null, };
+
+/**
+ * Usage counters for one project since the server started
+ */
+export type ProjectUsageStats = { project_id: string, executions_started: ActivityStats, diffs_streamed: ActivityStats, searches_run: ActivityStats, };
+
+/**
+ * Bounds of a release: each side is either a timestamp or a git tag of the primary repository.
+ * A missing start means "from the beginning", a missing end means "now".
+ */
+export type ReleaseNotesRange = { from: Date | null, to: Date | null, from_tag: string | null, to_tag: string | null, };
+
+export type PolishReleaseNotesRequest = { range: ReleaseNotesRange, 
+/**
+ * Defaults to the executor profile from the user config
+ */
+executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Defaults to the branch checked out in the primary repository
+ */
+base_branch: string | null, };
+
+/**
+ * The attempt polishing a set of notes. `markdown` is the agent's reply and stays empty until
+ * the agent has completed; poll `GET /release-notes/polish/{task_attempt_id}` for it. The
+ * attempt's worktree and branch are removed once the reply is available.
+ */
+export type PolishedReleaseNotes = { task_id: string, task_attempt_id: string, 
+/**
+ * Status of the agent run, `None` before it has started
+ */
+status: ExecutionProcessStatus | null, markdown: string | null, };
+
+export type ReleaseNotes = { from: Date, to: Date, tasks: Array<CompletedTask>, 
+/**
+ * Draft notes rendered as markdown
+ */
+markdown: string, };
+
+/**
+ * A task that reached Done, with the details release notes are built from.
+ */
+export type CompletedTask = { id: string, title: string, description: string | null, 
+/**
+ * Latest summary reported by a coding agent for any of the task's attempts
+ */
+summary: string | null, pr_number: bigint | null, pr_url: string | null, completed_at: Date, };
+
+export type DeleteStaleBranchesRequest = { 
+/**
+ * Attempts whose branches the user confirmed for deletion
+ */
+task_attempt_ids: Array<string>, 
+/**
+ * Also delete the branch on its remote
+ */
+delete_remote: boolean, };
+
+export type StaleBranchReason = "merged" | "task_done" | "task_cancelled";
+
+/**
+ * Attempt branch that can be deleted without losing work in progress
+ */
+export type StaleBranch = { task_attempt_id: string, task_id: string, task_title: string, branch: string, target_branch: string, reasons: Array<StaleBranchReason>, 
+/**
+ * The attempt's worktree still exists and is removed along with the branch
+ */
+has_worktree: boolean, 
+/**
+ * Remote holding a copy of the branch, if any
+ */
+remote: string | null, };
+
+/**
+ * Outcome of deleting one stale branch
+ */
+export type StaleBranchDeletion = { task_attempt_id: string, branch: string, deleted_local: boolean, deleted_remote: boolean, error: string | null, };
+
 export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
 
 export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, preconfigured: JsonValue, is_toml_config: boolean, };
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "VerifyScript";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, };
 
@@ -46,11 +276,48 @@ export type CreateTaskTemplate = { project_id: string | null, title: string, des
 
 export type UpdateTaskTemplate = { title: string | null, description: string | null, template_name: string | null, };
 
+/**
+ * Named block of text that prompts pull in with `{{snippet:name}}`
+ */
+export type PromptSnippet = { id: string, project_id: string | null, name: string, content: string, description: string | null, created_at: string, updated_at: string, };
+
+export type CreatePromptSnippet = { project_id: string | null, name: string, content: string, description: string | null, };
+
+export type UpdatePromptSnippet = { name: string | null, content: string | null, description: string | null, };
+
+export type ExpandedPrompt = { prompt: string, 
+/**
+ * Placeholders naming snippets that do not exist; they are left in the prompt as written
+ */
+missing: Array<string>, };
+
+export type ExpandPromptRequest = { project_id: string, prompt: string, };
+
+/**
+ * Creates a task from a template every time its cron expression fires
+ */
+export type TaskSchedule = { id: string, project_id: string, template_id: string, cron_expression: string, enabled: boolean, 
+/**
+ * When set, an attempt is started with this executor right after the task is created
+ */
+executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Base branch of the started attempt; defaults to the branch checked out in the project
+ */
+base_branch: string | null, next_run_at: Date, last_run_at: Date | null, last_task_id: string | null, created_at: Date, updated_at: Date, };
+
+export type CreateTaskSchedule = { template_id: string, cron_expression: string, executor_profile_id: ExecutorProfileId | null, base_branch: string | null, };
+
+/**
+ * Replaces the schedule's settings; the template stays the same
+ */
+export type UpdateTaskSchedule = { cron_expression: string, enabled: boolean, executor_profile_id: ExecutorProfileId | null, base_branch: string | null, };
+
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, parent_task_id: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, has_running_dev_server: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, parent_task_id: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, has_running_dev_server: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, executor: string, labels: Array<Label>, due_at: string | null, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, parent_task_id: string | null, created_at: string, updated_at: string, };
 
 export type TaskRelationships = { parent_task: Task | null, current_attempt: TaskAttempt, children: Array<Task>, subtasks: Array<Task>, };
 
@@ -58,6 +325,295 @@ export type CreateTask = { project_id: string, title: string, description: strin
 
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_task_attempt: string | null, parent_task_id: string | null, image_ids: Array<string> | null, };
 
+export type SetTaskDueDate = { 
+/**
+ * None clears the due date
+ */
+due_at: string | null, };
+
+/**
+ * An open task whose due date has passed
+ */
+export type OverdueTask = { due_at: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, parent_task_id: string | null, created_at: string, updated_at: string, };
+
+/**
+ * What a task list is ordered by; ties are broken by id
+ */
+export type TaskSortField = "created_at" | "updated_at" | "title" | "status" | "due_at";
+
+export type SortDirection = "asc" | "desc";
+
+export type Label = { id: string, project_id: string, name: string, 
+/**
+ * Hex color such as `#3b82f6`
+ */
+color: string, created_at: Date, updated_at: Date, };
+
+export type CreateLabel = { name: string, color: string, };
+
+export type UpdateLabel = { name: string | null, color: string | null, };
+
+export type SetTaskLabels = { label_ids: Array<string>, };
+
+export type TaskImportFormat = "csv" | "json";
+
+export type ImportTasksRequest = { format: TaskImportFormat, content: string, };
+
+export type TaskImportRowError = { 
+/**
+ * 1-based position of the task in the payload, not counting the CSV header or blank lines
+ */
+row: number, message: string, };
+
+export type TaskImportResult = { created: Array<string>, errors: Array<TaskImportRowError>, };
+
+export type ProjectBundle = { version: number, exported_at: Date, project_name: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, 
+/**
+ * Empty in version 1 bundles
+ */
+repositories: Array<BundledRepository>, labels: Array<BundledLabel>, 
+/**
+ * Oldest first, so parent tasks come before their subtasks
+ */
+tasks: Array<BundledTask>, images: Array<BundledImage>, };
+
+export type BundledRepository = { 
+/**
+ * Id on the exporting machine, referenced by attempts
+ */
+id: string, name: string, 
+/**
+ * Path on the exporting machine
+ */
+git_repo_path: string, root_path: string, is_primary: boolean, };
+
+export type BundledLabel = { name: string, color: string, };
+
+export type BundledTask = { 
+/**
+ * Id on the exporting machine; imported tasks get new ids
+ */
+id: string, parent_task_id: string | null, title: string, description: string | null, status: TaskStatus, due_at: string | null, 
+/**
+ * Label names
+ */
+labels: Array<string>, image_ids: Array<string>, 
+/**
+ * Oldest first
+ */
+attempts: Array<BundledAttempt>, };
+
+export type BundledAttempt = { executor: string, branch: string, target_branch: string, created_at: Date, merges: Array<Merge>, 
+/**
+ * Branches per repository; empty in version 1 bundles
+ */
+repositories: Array<BundledAttemptRepository>, };
+
+export type BundledAttemptRepository = { 
+/**
+ * The repository's id in the bundle
+ */
+repository_id: string, is_primary: boolean, branch: string | null, base_branch: string | null, };
+
+export type BundledImage = { id: string, original_name: string, 
+/**
+ * File contents, base64-encoded
+ */
+data: string, };
+
+export type ProjectBundleImportSummary = { tasks: number, attempts: number, merges: number, images: number, 
+/**
+ * Attempts of executors this machine does not know
+ */
+skipped_attempts: number, };
+
+export type ImportProjectBundle = { bundle: ProjectBundle, 
+/**
+ * Name of the new project; the bundle's project name when unset
+ */
+name: string | null, 
+/**
+ * Where bundled repositories are cloned on this machine, keyed by their id in the bundle.
+ * Repositories left out are expected at their original path.
+ */
+repository_paths: Record<string, string>, };
+
+export type ImportedProjectBundle = { project: Project, summary: ProjectBundleImportSummary, };
+
+/**
+ * Where a project's issues are tracked
+ */
+export type IssueTracker = "github" | "gitlab";
+
+/**
+ * The GitHub repository or GitLab project whose issues a project mirrors as tasks
+ */
+export type IssueSync = { project_id: string, tracker: IssueTracker, 
+/**
+ * GitHub owner, or GitLab namespace path such as `group/subgroup`
+ */
+owner: string, repo: string, 
+/**
+ * Issues updated before this were already imported; None until the first sync succeeds
+ */
+last_synced_at: Date | null, 
+/**
+ * Why the latest sync failed; cleared by the next successful one
+ */
+last_error: string | null, created_at: Date, updated_at: Date, };
+
+/**
+ * `repo` is the repository path (`owner/name`, `group/subgroup/project`) or its URL
+ */
+export type LinkIssueSync = { tracker: IssueTracker, repo: string, };
+
+export type IssueState = "open" | "closed";
+
+/**
+ * The issue a task was imported from
+ */
+export type TaskIssue = { task_id: string, project_id: string, 
+/**
+ * GitHub issue number or GitLab issue iid
+ */
+issue_number: number, issue_url: string, 
+/**
+ * Last state seen on the tracker or pushed to it
+ */
+issue_state: IssueState, created_at: Date, updated_at: Date, };
+
+export type IssueSyncSummary = { 
+/**
+ * New tasks created from open issues
+ */
+imported: number, 
+/**
+ * Tasks whose issue was closed or reopened on the tracker
+ */
+updated: number, 
+/**
+ * Issues closed or reopened because their task changed
+ */
+pushed: number, };
+
+/**
+ * The Jira query whose issues a project pulls in as tasks
+ */
+export type JiraSync = { project_id: string, jql: string, 
+/**
+ * Jira status to move the issue to when its task goes to review; None means "In Review"
+ */
+in_review_status: string | null, 
+/**
+ * Jira status to move the issue to when its task is done; None means "Done"
+ */
+done_status: string | null, last_synced_at: Date | null, 
+/**
+ * Why the latest sync failed; cleared by the next successful one
+ */
+last_error: string | null, created_at: Date, updated_at: Date, };
+
+export type UpsertJiraSync = { jql: string, in_review_status: string | null, done_status: string | null, };
+
+/**
+ * The Jira issue a task was imported from
+ */
+export type TaskJiraIssue = { task_id: string, project_id: string, 
+/**
+ * Such as `PROJ-123`
+ */
+issue_key: string, issue_url: string, 
+/**
+ * Task status the issue was last transitioned for
+ */
+pushed_status: TaskStatus | null, created_at: Date, updated_at: Date, };
+
+export type JiraSyncSummary = { 
+/**
+ * New tasks created from issues
+ */
+imported: number, 
+/**
+ * Issues moved to the status matching their task
+ */
+transitioned: number, 
+/**
+ * Issues whose workflow has no transition to the configured status
+ */
+skipped_transitions: number, };
+
+/**
+ * Lifecycle events a webhook can subscribe to
+ */
+export type WebhookEvent = "task_created" | "attempt_started" | "attempt_finished" | "merge_completed";
+
+/**
+ * A URL that lifecycle events are POSTed to
+ */
+export type Webhook = { id: string, 
+/**
+ * Only events from this project are delivered; None delivers events from every project
+ */
+project_id: string | null, url: string, 
+/**
+ * Key for the HMAC-SHA256 signature sent with every delivery
+ */
+secret: string, events: WebhookEvent[], enabled: boolean, last_delivery_at: Date | null, 
+/**
+ * HTTP status of the last delivery; None when no response was received
+ */
+last_response_status: number | null, 
+/**
+ * Why the last delivery failed after all retries; cleared by the next successful one
+ */
+last_error: string | null, created_at: Date, updated_at: Date, };
+
+/**
+ * A secret is generated when none is given
+ */
+export type CreateWebhook = { project_id: string | null, url: string, events: Array<WebhookEvent>, secret: string | null, };
+
+export type UpdateWebhook = { url: string, events: Array<WebhookEvent>, enabled: boolean, };
+
+/**
+ * What a user may do. Each role can do everything the roles after it can.
+ */
+export type UserRole = "admin" | "contributor" | "viewer";
+
+export type User = { id: string, username: string, display_name: string, role: UserRole, created_at: Date, updated_at: Date, };
+
+export type CreateUser = { username: string, 
+/**
+ * Defaults to the username
+ */
+display_name: string | null, password: string, role: UserRole, };
+
+/**
+ * Fields left out are not changed
+ */
+export type UpdateUser = { display_name: string | null, password: string | null, role: UserRole | null, };
+
+export type LoginRequest = { username: string, password: string, };
+
+/**
+ * The token is also set as a cookie; it is returned for clients that are not browsers, such as
+ * the MCP server, to send as a bearer token
+ */
+export type LoginResponse = { user: User, token: string, };
+
+/**
+ * One state-changing API call
+ */
+export type AuditLogEntry = { id: string, 
+/**
+ * `None` when the server runs without user accounts
+ */
+actor_user_id: string | null, actor_name: string | null, method: string, path: string, 
+/**
+ * Collection the call acted on, e.g. `tasks` or `task-attempts`
+ */
+entity_type: string | null, entity_id: string | null, status_code: number, before_summary: JsonValue | null, after_summary: JsonValue | null, created_at: Date, };
+
 export type Image = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export type CreateImage = { file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, };
@@ -78,28 +634,176 @@ export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, }
 
 export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
 
+export type McpServerEntry = { name: string, 
+/**
+ * Server definition as written to the agent's config file.
+ */
+config: JsonValue, };
+
+export type McpServerList = { config_path: string, servers: Array<McpServerEntry>, };
+
+export type UpsertMcpServerBody = { 
+/**
+ * Canonical (Claude-style) definition: `{ command, args?, env? }` for stdio servers or
+ * `{ type: "http", url, headers? }` for HTTP servers. It is converted to the agent's own
+ * format before being written.
+ */
+server: JsonValue, };
+
 export type CreateFollowUpAttempt = { prompt: string, variant: string | null, image_ids: Array<string> | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
 
+export type HandoffTaskAttemptRequest = { 
+/**
+ * Executor that continues the attempt
+ */
+executor_profile_id: ExecutorProfileId, 
+/**
+ * Extra instructions appended after the summary of the previous session
+ */
+prompt: string | null, };
+
 export type CreateTaskAttemptRepositoryBody = { project_repository_id: string, is_primary: boolean, base_branch?: string | null, };
 
-export type DraftResponse = { task_attempt_id: string, draft_type: DraftType, retry_process_id: string | null, prompt: string, queued: boolean, variant: string | null, image_ids: Array<string> | null, version: bigint, };
+export type DraftResponse = { task_attempt_id: string, draft_type: DraftType, retry_process_id: string | null, prompt: string, queued: boolean, variant: string | null, image_ids: Array<string> | null, run_at: string | null, version: bigint, };
 
 export type UpdateFollowUpDraftRequest = { prompt: string | null, variant: string | null | null, image_ids: Array<string> | null, version: bigint | null, };
 
 export type UpdateRetryFollowUpDraftRequest = { retry_process_id: string, prompt: string | null, variant: string | null | null, image_ids: Array<string> | null, version: bigint | null, };
 
+export type ReviewThreadComment = { author: string | null, body: string, url: string | null, };
+
+/**
+ * Unresolved review conversation on a pull request
+ */
+export type ReviewThread = { path: string, 
+/**
+ * Line in the current version of the file; missing when the thread is outdated
+ */
+line: bigint | null, 
+/**
+ * The commented code changed after the comment was made
+ */
+outdated: boolean, comments: Array<ReviewThreadComment>, };
+
+export type ImportReviewCommentsRequest = { 
+/**
+ * Queue the follow-up so it is sent as soon as the agent is idle
+ */
+queue: boolean, };
+
+export type ImportReviewCommentsResponse = { threads: Array<ReviewThread>, draft: DraftResponse, };
+
+/**
+ * Which version of the file a comment's line numbers refer to
+ */
+export type DiffCommentSide = "old" | "new";
+
+/**
+ * Comment on a range of lines in an attempt's diff
+ */
+export type DiffComment = { id: string, task_attempt_id: string, repository_id: string | null, file_path: string, side: DiffCommentSide, 
+/**
+ * 1-based, inclusive
+ */
+start_line: number, 
+/**
+ * 1-based, inclusive
+ */
+end_line: number, 
+/**
+ * The commented lines as they were when the comment was written
+ */
+code: string | null, body: string, 
+/**
+ * When the comment was included in a follow-up; unsent comments are still pending
+ */
+sent_at: Date | null, created_at: Date, updated_at: Date, };
+
+export type CreateDiffComment = { repository_id: string | null, file_path: string, side: DiffCommentSide, start_line: number, 
+/**
+ * Defaults to `start_line` for single-line comments
+ */
+end_line: number | null, code: string | null, body: string, };
+
+export type UpdateDiffComment = { body: string, };
+
+export type SendDiffCommentsRequest = { 
+/**
+ * Queue the follow-up so it is sent as soon as the agent is idle
+ */
+queue: boolean, };
+
+export type SendDiffCommentsResponse = { comments: Array<DiffComment>, draft: DraftResponse, };
+
+export type RevertDiffChangeRequest = { path: string, 
+/**
+ * Where a renamed file used to live; it is restored there
+ */
+old_path: string | null, 
+/**
+ * The repository the diff was streamed for, when it was filtered to one
+ */
+repo_id: string | null, 
+/**
+ * Revert only this hunk instead of the whole file
+ */
+hunk: HunkRange | null, };
+
+export type DiffFileSummary = { path: string, 
+/**
+ * Set for renames and copies
+ */
+old_path: string | null, change: DiffChangeKind, repository_id: string | null, additions: number, deletions: number, };
+
+export type DiffSummary = { files: Array<DiffFileSummary>, additions: number, deletions: number, };
+
 export type ChangeTargetBranchRequest = { new_target_branch: string, };
 
 export type ChangeTargetBranchResponse = { new_target_branch: string, status: [number, number], };
 
-export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, base_branch: string, repositories?: Array<CreateTaskAttemptRepositoryBody> | null, };
+export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, base_branch: string, repositories?: Array<CreateTaskAttemptRepositoryBody> | null, 
+/**
+ * Stack the attempt on another attempt of the same project; that attempt's branch is used
+ * as the base branch instead of `base_branch`
+ */
+parent_attempt_id?: string | null, };
+
+/**
+ * `task_id` is blocked until `depends_on_task_id` is done
+ */
+export type TaskDependency = { task_id: string, depends_on_task_id: string, created_at: Date, };
+
+export type CreateTaskDependency = { depends_on_task_id: string, };
+
+export type TaskDependencies = { 
+/**
+ * Tasks that have to be done before this one can start
+ */
+depends_on: Array<Task>, 
+/**
+ * Tasks waiting on this one
+ */
+dependents: Array<Task>, blocked: boolean, };
+
+/**
+ * Dependency edges of a project's board, plus the tasks they currently block
+ */
+export type TaskDependencyGraph = { dependencies: Array<TaskDependency>, blocked_task_ids: Array<string>, };
 
 export type CreateGitHubPrRequest = { title: string, body: string | null, target_branch: string | null, remote_name: string | null, head_remote_name: string | null, };
 
+export type CreateGitLabMrRequest = { title: string, body: string | null, target_branch: string | null, 
+/**
+ * Remote pointing at the GitLab project; defaults to the branch's remote
+ */
+remote_name: string | null, };
+
 export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export enum GitHubServiceError { TOKEN_INVALID = "TOKEN_INVALID", INSUFFICIENT_PERMISSIONS = "INSUFFICIENT_PERMISSIONS", REPO_NOT_FOUND_OR_NO_ACCESS = "REPO_NOT_FOUND_OR_NO_ACCESS" }
 
+export enum GitLabServiceError { TOKEN_INVALID = "TOKEN_INVALID", INSUFFICIENT_PERMISSIONS = "INSUFFICIENT_PERMISSIONS", REPO_NOT_FOUND_OR_NO_ACCESS = "REPO_NOT_FOUND_OR_NO_ACCESS" }
+
 export type CodexUsageSnapshot = { captured_at: string, rate_limits: CodexUsageRateLimits, token_usage: CodexTokenUsageInfo | null, };
 
 export type CodexUsageRateLimits = { primary: CodexUsageWindow | null, secondary: CodexUsageWindow | null, };
@@ -116,29 +820,357 @@ export type ClaudeCodeSessionInfo = { session_id: string, version: string, git_b
 
 export type ClaudeCodeTokenUsage = { input_tokens: number, cache_creation_input_tokens: number, cache_read_input_tokens: number, output_tokens: number, total_tokens: number, };
 
-export type ActivityFeedItemCta = { label: string, href: string, };
+/**
+ * A coding agent whose usage is sampled, named as in `/usage/{agent}` paths
+ */
+export type UsageAgent = "codex" | "claude-code";
+
+/**
+ * An agent's usage at one point in time
+ */
+export type UsageSample = { id: string, agent: UsageAgent, 
+/**
+ * Share of the current rate limit window used, 0-100
+ */
+used_percent: number | null, 
+/**
+ * Share of Codex's weekly window used
+ */
+secondary_used_percent: number | null, total_tokens: number | null, sampled_at: Date, 
+/**
+ * When the current rate limit window resets, if the agent reports it
+ */
+resets_at: Date | null, };
+
+/**
+ * A rate limit window, in the same shape for every agent
+ */
+export type UsageWindow = { 
+/**
+ * `primary` or `secondary` for Codex, `five_hour` for Claude Code
+ */
+name: string, used_percent: number, window_minutes: number | null, resets_in_seconds: number | null, };
+
+/**
+ * One agent's latest usage, normalized so agents can be shown side by side
+ */
+export type AgentUsageSummary = { agent: UsageAgent, 
+/**
+ * `false` when the agent has no local logs to read usage from
+ */
+available: boolean, captured_at: string | null, windows: Array<UsageWindow>, total_tokens: number | null, 
+/**
+ * Why usage could not be read; the other agents are still summarized
+ */
+error: string | null, };
+
+/**
+ * An agent's latest snapshot, sent over `/usage/stream/ws` when the socket opens and again
+ * whenever the agent's session logs change
+ */
+export type UsageUpdate = { "agent": "codex", "snapshot": CodexUsageSnapshot | null } | { "agent": "claude-code", "snapshot": ClaudeCodeUsageSnapshot | null };
+
+export type TaskTokenUsage = { task_id: string, task_title: string, total_tokens: number, models: Array<ModelTokenUsage>, };
+
+export type ProjectTokenUsage = { project_id: string, project_name: string, total_tokens: number, 
+/**
+ * Most tokens first
+ */
+tasks: Array<TaskTokenUsage>, };
+
+/**
+ * Tokens the coding agents logged between `from` and `to`, per project and task
+ */
+export type UsageAttribution = { from: Date, to: Date, 
+/**
+ * Most tokens first
+ */
+projects: Array<ProjectTokenUsage>, 
+/**
+ * Sessions whose log is not on this machine, so their usage is left out
+ */
+missing_sessions: number, };
+
+/**
+ * Coding agent runs started and finished on one day (UTC)
+ */
+export type DailyRuns = { 
+/**
+ * `YYYY-MM-DD`
+ */
+day: string, started: number, finished: number, succeeded: number, };
+
+export type ExecutorUsage = { executor: string, runs: number, };
+
+export type EventCount = { event_name: string, count: number, };
+
+/**
+ * Aggregates over analytics events kept on this machine. Events are only stored locally while
+ * external analytics is disabled.
+ */
+export type LocalAnalyticsSummary = { days: number, runs_per_day: Array<DailyRuns>, 
+/**
+ * Share of finished coding agent runs that succeeded; null when none finished
+ */
+success_rate: number | null, executor_mix: Array<ExecutorUsage>, event_counts: Array<EventCount>, };
+
+export type ActivityFeedItemCta = { label: string, href: string, };
+
+export type ActivityFeedItemAction = { label: string, 
+/**
+ * Called with POST to perform the action
+ */
+endpoint: string, };
+
+export type ActivityFeedItem = { id: string, headline: string, summary: string | null, cta: ActivityFeedItemCta | null, action: ActivityFeedItemAction | null, urgencyScore: number, actionRequired: boolean, 
+/**
+ * Whether the current user acknowledged the event
+ */
+read: boolean, createdAt: Date, };
+
+export type ActivityFeedResponse = { events: Array<ActivityFeedItem>, nextCursor: string | null, };
+
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, activity_feed: ActivityFeedConfig, claude_plan: ClaudePlan, gitlab: GitLabConfig, gitea: GiteaConfig, commit_signing: CommitSigningConfig, jira: JiraConfig, rate_limit: RateLimitConfig, 
+/**
+ * Prices by model name, or by a prefix of it, used over the built-in ones when estimating
+ * what attempts cost
+ */
+model_pricing: { [key in string]?: ModelPrice }, budgets: Array<UsageBudget>, 
+/**
+ * Share of an agent's rate limit window, 0-100, past which queued follow-ups wait for the
+ * window to reset; `null` starts them regardless
+ */
+followup_usage_threshold: number | null, database_backup: DatabaseBackupConfig, log_archive: LogArchiveConfig, log_snapshots: LogSnapshotConfig, database_maintenance: DatabaseMaintenanceConfig, 
+/**
+ * Seconds a stopped process gets between SIGTERM and SIGKILL to save its session state; 0
+ * kills it right away
+ */
+stop_grace_period_secs: number, audit_log: AuditLogConfig, };
+
+/**
+ * Sound and desktop alerts, plus the chat channels events are posted to
+ */
+export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, 
+/**
+ * Address this app is reached at, e.g. `http://localhost:3000`; chat messages link to the
+ * task when set
+ */
+app_url: string | null, slack: SlackConfig, discord: DiscordConfig, };
+
+export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
+
+export type EditorConfig = { editor_type: EditorType, custom_command: string | null, };
+
+export enum EditorType { VS_CODE = "VS_CODE", CURSOR = "CURSOR", WINDSURF = "WINDSURF", INTELLI_J = "INTELLI_J", ZED = "ZED", XCODE = "XCODE", CUSTOM = "CUSTOM" }
+
+export type GitHubConfig = { pat: string | null, oauth_token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, branch_prefix: string | null, merge_commit_message_suffix: string | null, };
+
+/**
+ * Credentials for GitLab merge requests. `base_url` points at a self-managed instance and
+ * defaults to gitlab.com.
+ */
+export type GitLabConfig = { pat: string | null, base_url: string | null, };
+
+/**
+ * A self-hosted Gitea or Forgejo instance. PRs are only routed to it when both fields are set and
+ * the remote lives on the same host.
+ */
+export type GiteaConfig = { base_url: string | null, token: string | null, };
+
+/**
+ * Jira Cloud or Data Center site to pull issues from. Cloud authenticates with `email` and an API
+ * token; without an email the token is sent as a Data Center personal access token.
+ */
+export type JiraConfig = { base_url: string | null, email: string | null, api_token: string | null, };
+
+/**
+ * Limits how often one client may change things, for instances reachable over a LAN or a
+ * tunnel. Clients are told apart by their signed-in session, or else their IP address.
+ */
+export type RateLimitConfig = { enabled: boolean, 
+/**
+ * Requests other than reads allowed per client per minute, in bursts of up to this many
+ */
+requests_per_minute: number, 
+/**
+ * Leave requests from this machine unlimited. Requests forwarded by a local proxy or tunnel
+ * are limited by the client address it forwards.
+ */
+exempt_local: boolean, };
+
+/**
+ * What a model costs in US dollars per million tokens
+ */
+export type ModelPrice = { input: number, 
+/**
+ * Input read from the prompt cache
+ */
+cached_input: number, 
+/**
+ * Input written to the prompt cache
+ */
+cache_write: number, output: number, };
+
+/**
+ * A monthly allowance for one coding agent, counted from the first of the calendar month (UTC)
+ */
+export type UsageBudget = { agent: BaseCodingAgent, 
+/**
+ * Input, cached input and output tokens together
+ */
+monthly_tokens: number | null, 
+/**
+ * Estimated cost, priced as in the cost reports
+ */
+monthly_cost_usd: number | null, 
+/**
+ * Share of the budget at which to warn
+ */
+warn_percent: number, 
+/**
+ * Refuse to start the agent once the budget is used up
+ */
+hard_stop: boolean, };
+
+/**
+ * Periodic copies of the database, taken while the app runs
+ */
+export type DatabaseBackupConfig = { enabled: boolean, 
+/**
+ * Where backups are written; a `backups` folder next to the database when unset
+ */
+directory: string | null, interval_hours: number, 
+/**
+ * How many backups to keep; older ones are deleted after each new backup
+ */
+keep: number, };
+
+export type DatabaseBackup = { path: string, size_bytes: number, created_at: Date, };
+
+/**
+ * Archives old execution processes, moving their logs out of the table the app reads on every
+ * view
+ */
+export type LogArchiveConfig = { enabled: boolean, 
+/**
+ * Processes that finished this many days ago or earlier are archived
+ */
+after_days: number, };
+
+/**
+ * Compressed copies of finished processes' logs, read instead of normalizing the stored output
+ * again when an old attempt is opened
+ */
+export type LogSnapshotConfig = { enabled: boolean, 
+/**
+ * Snapshots not opened for this many days are deleted
+ */
+retention_days: number, };
+
+/**
+ * How long the record of who changed what is kept
+ */
+export type AuditLogConfig = { 
+/**
+ * Entries older than this many days are deleted; `None` keeps them
+ */
+retention_days: number | null, };
 
-export type ActivityFeedItem = { id: string, headline: string, summary: string | null, cta: ActivityFeedItemCta | null, urgencyScore: number, actionRequired: boolean, createdAt: Date, };
+/**
+ * WAL checkpoint, `ANALYZE` and incremental vacuum, run in the background
+ */
+export type DatabaseMaintenanceConfig = { enabled: boolean, interval_hours: number, };
 
-export type ActivityFeedResponse = { events: Array<ActivityFeedItem>, nextCursor: string | null, };
+export type DatabaseMaintenance = { 
+/**
+ * Whether a reader kept the checkpoint from reaching the end of the WAL
+ */
+checkpoint_busy: boolean, checkpointed_frames: number, 
+/**
+ * Free pages returned to the filesystem
+ */
+freed_pages: number, 
+/**
+ * Whether free pages can be returned without a full `VACUUM`. Databases created before
+ * incremental vacuum was turned on stay without it until they are converted.
+ */
+incremental_vacuum: boolean, 
+/**
+ * Set when this run converted the database, which takes one full `VACUUM`
+ */
+converted_to_incremental: boolean, duration_ms: number, };
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, activity_feed: ActivityFeedConfig, claude_plan: ClaudePlan, };
+export type DatabaseStats = { path: string, file_bytes: number, 
+/**
+ * Write-ahead log not yet checkpointed into the database file
+ */
+wal_bytes: number, 
+/**
+ * Space inside the file that is allocated but unused
+ */
+free_bytes: number, 
+/**
+ * Largest first
+ */
+tables: Array<TableStats>, };
 
-export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
+export type TableStats = { name: string, rows: number, 
+/**
+ * Space used by the table and its indexes; unset when SQLite was built without the `dbstat`
+ * table
+ */
+bytes: number | null, };
 
-export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
+/**
+ * Slack channel that execution and PR events are posted to, through an incoming webhook or a bot
+ * token plus channel. The webhook wins when both are set.
+ */
+export type SlackConfig = { webhook_url: string | null, bot_token: string | null, 
+/**
+ * Channel name or ID the bot posts to
+ */
+channel: string | null, execution_halted: boolean, attempt_failed: boolean, pr_merged: boolean, urgent_activity: boolean, };
 
-export type EditorConfig = { editor_type: EditorType, custom_command: string | null, };
+/**
+ * Discord channel webhook that events are posted to as embeds
+ */
+export type DiscordConfig = { webhook_url: string | null, execution_halted: boolean, attempt_failed: boolean, pr_merged: boolean, urgent_activity: boolean, };
 
-export enum EditorType { VS_CODE = "VS_CODE", CURSOR = "CURSOR", WINDSURF = "WINDSURF", INTELLI_J = "INTELLI_J", ZED = "ZED", XCODE = "XCODE", CUSTOM = "CUSTOM" }
+/**
+ * Value passed to git as `gpg.format`
+ */
+export type CommitSigningFormat = "openpgp" | "ssh";
 
-export type GitHubConfig = { pat: string | null, oauth_token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, branch_prefix: string | null, merge_commit_message_suffix: string | null, };
+/**
+ * Signing for commits made automatically after agent and cleanup runs. Unset `format` and `key`
+ * fall back to the user's git config (`gpg.format` and `user.signingkey`).
+ */
+export type CommitSigningConfig = { enabled: boolean, format: CommitSigningFormat | null, 
+/**
+ * GPG key id or path to an SSH public key
+ */
+key: string | null, };
 
 export enum SoundFile { ABSTRACT_SOUND1 = "ABSTRACT_SOUND1", ABSTRACT_SOUND2 = "ABSTRACT_SOUND2", ABSTRACT_SOUND3 = "ABSTRACT_SOUND3", ABSTRACT_SOUND4 = "ABSTRACT_SOUND4", COW_MOOING = "COW_MOOING", PHONE_VIBRATION = "PHONE_VIBRATION", ROOSTER = "ROOSTER" }
 
 export type UiLanguage = "BROWSER" | "EN" | "JA" | "ES";
 
-export type ActivityFeedConfig = { enabled: boolean, window_days: number, };
+export type ActivityFeedConfig = { enabled: boolean, window_days: number, 
+/**
+ * Events scoring at least this much urgency (0-100) are also sent as notifications; `None`
+ * keeps them in the feed only
+ */
+notify_threshold: number | null, 
+/**
+ * Recorded events older than this many days are deleted; `None` keeps them. Never less than
+ * `window_days`, so pruning does not change what the feed shows.
+ */
+retention_days: number | null, 
+/**
+ * At most this many recorded events are kept, the oldest are deleted first; `None` for no
+ * limit
+ */
+max_events: number | null, };
 
 export type ClaudePlan = "free" | "pro" | "max5x" | "max20x";
 
@@ -152,6 +1184,10 @@ export type GitBranch = { name: string, is_current: boolean, is_remote: boolean,
 
 export type GitRemote = { name: string, url: string | null, };
 
+export type BranchSuggestion = { branch: string, is_remote: boolean, reasons: Array<BranchSuggestionReason>, score: number, last_commit_date: Date, repository_id: string | null, repository_name: string | null, };
+
+export type BranchSuggestionReason = "parent_attempt" | "previous_attempt" | "latest_release" | "default_branch" | "current_branch" | "recent_activity";
+
 export type Diff = { repositoryId: string | null, repositoryName: string | null, repositoryRoot: string | null, change: DiffChangeKind, oldPath: string | null, newPath: string | null, oldContent: string | null, newContent: string | null, 
 /**
  * True when file contents are intentionally omitted (e.g., too large)
@@ -160,12 +1196,125 @@ contentOmitted: boolean,
 /**
  * Optional precomputed stats for omitted content
  */
-additions: number | null, deletions: number | null, };
+additions: number | null, deletions: number | null, 
+/**
+ * Set when the file is stored in Git LFS; contents are then left out rather than showing
+ * pointer text
+ */
+lfs: LfsChange | null, 
+/**
+ * Changed words on the lines that were modified; empty when contents are omitted
+ */
+intraline: Array<IntralineChange>, 
+/**
+ * Set when either side is binary, whose contents are never included
+ */
+binary: BinaryChange | null, 
+/**
+ * Matches one of the project's diff ignore patterns, such as a lockfile; shown collapsed and
+ * with contents omitted
+ */
+collapsed: boolean, 
+/**
+ * Blocks of lines moved out of or into this file, so they need not be read as a separate
+ * deletion and addition
+ */
+movedBlocks: Array<MovedBlock>, };
+
+/**
+ * A changed stretch of a line, in UTF-16 code units so it indexes JavaScript strings directly
+ */
+export type IntralineRange = { start: number, end: number, };
+
+/**
+ * Words that changed between a removed line and the added line replacing it
+ */
+export type IntralineChange = { 
+/**
+ * 1-based line number in the old content
+ */
+oldLine: number, 
+/**
+ * 1-based line number in the new content
+ */
+newLine: number, oldRanges: Array<IntralineRange>, newRanges: Array<IntralineRange>, };
+
+/**
+ * Lines covered by a hunk, as in a unified diff header: 1-based starts and line counts
+ */
+export type HunkRange = { oldStart: number, oldLines: number, newStart: number, newLines: number, };
+
+/**
+ * Dimensions of an image, read from its header
+ */
+export type ImageInfo = { mimeType: string, width: number, height: number, };
+
+/**
+ * One side of a binary change
+ */
+export type BinaryFile = { size: number, image: ImageInfo | null, 
+/**
+ * Where this side's bytes can be fetched for a preview; set on attempt diffs
+ */
+contentUrl: string | null, };
+
+/**
+ * Binary files on either side of a change. A side is None when it does not exist or is text.
+ */
+export type BinaryChange = { old: BinaryFile | null, new: BinaryFile | null, };
+
+/**
+ * A run of lines deleted in one place and added unchanged in another, possibly in another file
+ */
+export type MovedBlock = { fromPath: string, 
+/**
+ * 1-based line in the old version of `from_path` where the block started
+ */
+fromLine: number, toPath: string, 
+/**
+ * 1-based line in the new version of `to_path` where the block now starts
+ */
+toLine: number, lines: number, };
+
+/**
+ * Which side of a diff to read
+ */
+export type DiffSide = "old" | "new";
 
 export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "copied" | "permissionChange";
 
+/**
+ * Parsed Git LFS pointer file
+ */
+export type LfsPointer = { 
+/**
+ * Object id including the hash method, e.g. `sha256:<hex>`
+ */
+oid: string, size: number, };
+
+/**
+ * LFS objects on either side of a change. A side is None when it does not exist or is checked
+ * out as real content rather than a pointer.
+ */
+export type LfsChange = { old: LfsPointer | null, new: LfsPointer | null, };
+
 export type RepositoryInfo = { id: bigint, name: string, full_name: string, owner: string, description: string | null, clone_url: string, ssh_url: string, default_branch: string, private: boolean, };
 
+/**
+ * Protection on a branch as far as the token can see it: classic branch protection and the
+ * repository rulesets that apply to the branch. Required reviews of classic protection are only
+ * visible to admins, so `protected` may be set without any known requirement.
+ */
+export type BranchProtection = { branch: string, protected: boolean, 
+/**
+ * Changes must land through a pull request
+ */
+requires_pull_request: boolean, required_approving_reviews: number, 
+/**
+ * Status check contexts that must pass before merging
+ */
+required_status_checks: Array<string>, };
+
 export type CommandBuilder = { 
 /**
  * Base executable command (e.g., "npx -y @anthropic-ai/claude-code@latest")
@@ -190,12 +1339,44 @@ export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } |
 
 export type BaseAgentCapability = "SESSION_FORK";
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, tool_policy?: ToolPolicy | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+
+export type ToolPolicy = { 
+/**
+ * Tools the agent may call without asking. Claude Code rule syntax such as
+ * `Bash(npm run test:*)` is passed through unchanged.
+ */
+allow?: Array<string>, 
+/**
+ * Tools the agent must never call
+ */
+deny?: Array<string>, 
+/**
+ * Tools whose calls wait for approval on the task attempt
+ */
+ask?: Array<string>, };
 
-export type Gemini = { append_prompt: AppendPrompt, model: GeminiModel, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Gemini = { append_prompt: AppendPrompt, model: GeminiModel, 
+/**
+ * Legacy shorthand for `approval_mode: yolo`
+ */
+yolo?: boolean | null, approval_mode?: GeminiApprovalMode | null, 
+/**
+ * Run tools inside Gemini CLI's sandbox
+ */
+sandbox?: boolean | null, 
+/**
+ * Snapshot the project before file edits so they can be restored
+ */
+checkpointing?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
 
 export type GeminiModel = "default" | "flash";
 
+/**
+ * Tool call approval mode for Gemini CLI
+ */
+export type GeminiApprovalMode = "default" | "auto_edit" | "yolo";
+
 export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
 
 export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
@@ -234,11 +1415,45 @@ export type CreateTaskAttemptBody = { task_id: string,
 /**
  * Executor profile specification
  */
-executor_profile_id: ExecutorProfileId, base_branch: string, repositories?: Array<CreateTaskAttemptRepositoryBody> | null, };
+executor_profile_id: ExecutorProfileId, base_branch: string, repositories?: Array<CreateTaskAttemptRepositoryBody> | null, 
+/**
+ * Stack the attempt on another attempt of the same project; that attempt's branch is used
+ * as the base branch instead of `base_branch`
+ */
+parent_attempt_id?: string | null, 
+/**
+ * Earlier attempt of the same task whose queued follow-up moves to the new attempt; it runs
+ * once the new attempt's first run finishes
+ */
+transfer_follow_up_from?: string | null, };
 
 export type RebaseTaskAttemptRequest = { old_base_branch: string | null, new_base_branch: string | null, };
 
-export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, } | { "type": "rebase_in_progress" };
+export type MergeTaskAttemptRequest = { 
+/**
+ * Defaults to squash
+ */
+strategy: MergeStrategy, 
+/**
+ * Merge even though the target branch requires reviews or status checks on its remote
+ */
+override_branch_protection: boolean, };
+
+export type AttemptStack = { 
+/**
+ * Attempt this one was branched off, if it is stacked
+ */
+parent: TaskAttempt | null, 
+/**
+ * Attempts stacked directly on this one
+ */
+children: Array<TaskAttempt>, };
+
+export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, 
+/**
+ * Files left conflicted in the worktree, with their conflict regions
+ */
+conflicts: Array<ConflictedFile>, } | { "type": "rebase_in_progress" };
 
 export type ReplaceProcessRequest = { 
 /**
@@ -278,10 +1493,109 @@ conflict_op: ConflictOp | null,
  */
 conflicted_files: Array<string>, };
 
+export type CommitManualChangesRequest = { message: string | null, };
+
+export type CommitManualChangesResponse = { 
+/**
+ * False when the worktree had nothing to commit
+ */
+committed: boolean, };
+
+export type ConflictState = { 
+/**
+ * Operation waiting on the conflicts, or None when the worktree is clean
+ */
+op: ConflictOp | null, files: Array<ConflictFileVersions>, };
+
+export type FileConflictResolution = { path: string, resolution: ConflictResolution, };
+
+export type ResolveConflictsRequest = { resolutions: Array<FileConflictResolution>, };
+
+export type RestoreSnapshotRequest = { oid: string, };
+
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
+/**
+ * One `<<<<<<<` … `>>>>>>>` region of a conflicted file. During a rebase `ours` is the branch
+ * being rebased onto and `theirs` is the attempt commit being replayed.
+ */
+export type ConflictHunk = { 
+/**
+ * 1-based line of the opening marker in the worktree file
+ */
+start_line: number, ours: string, 
+/**
+ * Common ancestor text; only present with the `diff3`/`zdiff3` conflict style
+ */
+base: string | null, theirs: string, };
+
+export type ConflictedFile = { path: string, 
+/**
+ * Empty for binary files and conflicts without markers (e.g. modify/delete)
+ */
+hunks: Array<ConflictHunk>, };
+
+/**
+ * Outcome of merging an attempt branch into its target, computed in memory without touching
+ * any worktree or ref.
+ */
+export type MergePreview = { 
+/**
+ * True when the merge produces no conflicts
+ */
+clean: boolean, 
+/**
+ * Commits on the target missing from the attempt branch; merging is refused while non-zero
+ */
+commits_behind: number, 
+/**
+ * Changes the merge would bring into the target. With conflicts these compare the target
+ * against the attempt branch instead.
+ */
+files_changed: number, additions: number, deletions: number, conflicted_files: Array<string>, };
+
+/**
+ * Result of pushing new attempt commits to the branch behind an open pull request
+ */
+export type BranchPushOutcome = { pushed_commits: number, 
+/**
+ * Local commits still missing from the remote branch
+ */
+remote_commits_ahead: number, 
+/**
+ * Remote commits missing locally; nothing is pushed until the branch is brought up to date
+ */
+remote_commits_behind: number, };
+
+/**
+ * Uncommitted worktree changes saved before an operation that could discard them. Snapshots are
+ * commits on top of the HEAD they were taken at, kept alive by a ref in the repository.
+ */
+export type WorktreeSnapshot = { oid: string, 
+/**
+ * Operation the snapshot was taken before
+ */
+reason: string, created_at: string, };
+
+/**
+ * The three index stages of a conflicted path. A missing side means the file was deleted (or
+ * never existed) there; binary content is not inlined.
+ */
+export type ConflictFileVersions = { path: string, base: string | null, ours: string | null, theirs: string | null, is_binary: boolean, };
+
+export type ConflictResolution = { "type": "ours" } | { "type": "theirs" } | { "type": "base" } | { "type": "custom", 
+/**
+ * Hand-merged file content
+ */
+content: string, } | { "type": "delete" };
+
 export type TaskAttempt = { id: string, task_id: string, container_ref: string | null, branch: string, target_branch: string, executor: string, worktree_deleted: boolean, setup_completed_at: string | null, created_at: string, updated_at: string, };
 
+/**
+ * What an attempt list is ordered by; ties are broken by id
+ */
+export type TaskAttemptSortField = "created_at" | "updated_at";
+
 export type ExecutionProcess = { id: string, task_attempt_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, 
 /**
  * Git HEAD commit OID captured before the process starts
@@ -298,24 +1612,144 @@ after_head_commit: string | null, status: ExecutionProcessStatus, exit_code: big
  */
 dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
+/**
+ * Port handed to a dev server process through `VIBE_DEV_SERVER_PORT`
+ */
+export type DevServerPort = { execution_process_id: string, port: number, 
+/**
+ * Where the dev server is expected to listen
+ */
+url: string, created_at: Date, };
+
 export type ExecutionProcessStatus = "running" | "completed" | "failed" | "killed";
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "verifyscript";
+
+/**
+ * Links an execution process to the one whose executor action it re-ran
+ */
+export type ExecutionProcessReplay = { execution_process_id: string, replayed_from_id: string, created_at: Date, };
+
+/**
+ * Tokens used with one model. Input counts exclude the cached input counted separately.
+ */
+export type ModelTokenUsage = { model: string, input_tokens: number, 
+/**
+ * Input read from the prompt cache
+ */
+cached_input_tokens: number, 
+/**
+ * Input written to the prompt cache
+ */
+cache_creation_input_tokens: number, output_tokens: number, };
+
+export type ModelCost = { 
+/**
+ * `null` when the model has no known price
+ */
+cost_usd: number | null, model: string, input_tokens: number, 
+/**
+ * Input read from the prompt cache
+ */
+cached_input_tokens: number, 
+/**
+ * Input written to the prompt cache
+ */
+cache_creation_input_tokens: number, output_tokens: number, };
+
+export type CostEstimate = { models: Array<ModelCost>, 
+/**
+ * Sum over the models with a known price
+ */
+total_usd: number, 
+/**
+ * Some usage could not be priced, so the total is a lower bound
+ */
+incomplete: boolean, };
+
+export type ExecutionProcessCost = { execution_process_id: string, cost: CostEstimate, };
+
+export type TaskAttemptCost = { total: CostEstimate, 
+/**
+ * Processes that reported token usage, oldest first
+ */
+execution_processes: Array<ExecutionProcessCost>, };
+
+/**
+ * How far into its monthly budget an agent is
+ */
+export type BudgetLevel = "warning" | "exceeded";
+
+/**
+ * A budget and how much of it the current month has used
+ */
+export type BudgetStatus = { month_start: string, used_tokens: number, used_cost_usd: number, 
+/**
+ * Some usage could not be priced, so the cost is a lower bound
+ */
+cost_incomplete: boolean, 
+/**
+ * `null` while the usage is below the warning threshold
+ */
+level: BudgetLevel | null, agent: BaseCodingAgent, 
+/**
+ * Input, cached input and output tokens together
+ */
+monthly_tokens: number | null, 
+/**
+ * Estimated cost, priced as in the cost reports
+ */
+monthly_cost_usd: number | null, 
+/**
+ * Share of the budget at which to warn
+ */
+warn_percent: number, 
+/**
+ * Refuse to start the agent once the budget is used up
+ */
+hard_stop: boolean, };
 
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
-export type DirectMerge = { id: string, task_attempt_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
+export type DirectMerge = { id: string, task_attempt_id: string, merge_commit: string, target_branch_name: string, merge_strategy: MergeStrategy, created_at: string, };
 
 export type PrMerge = { id: string, task_attempt_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
 
 export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
+/**
+ * How a direct merge lands the attempt branch on its target
+ */
+export type MergeStrategy = "squash" | "merge_commit" | "rebase_fast_forward";
+
 export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
 
-export type Draft = { id: string, task_attempt_id: string, draft_type: DraftType, retry_process_id: string | null, prompt: string, queued: boolean, sending: boolean, variant: string | null, image_ids: Array<string> | null, created_at: string, updated_at: string, version: bigint, };
+/**
+ * Overall review decision on a pull request
+ */
+export type PrReviewState = "review_required" | "commented" | "approved" | "changes_requested";
+
+export type PrCheckState = "pending" | "success" | "failure" | "neutral";
+
+export type PrCheckRun = { name: string, state: PrCheckState, details_url: string | null, };
+
+/**
+ * Review and CI status of the attempt's open pull request
+ */
+export type PrCheckStatus = { task_attempt_id: string, pr_number: bigint, pr_url: string, review_state: PrReviewState, checks_state: PrCheckState, checks: Array<PrCheckRun>, updated_at: Date, };
+
+export type Draft = { id: string, task_attempt_id: string, draft_type: DraftType, retry_process_id: string | null, prompt: string, queued: boolean, sending: boolean, variant: string | null, image_ids: Array<string> | null, 
+/**
+ * When a queued follow-up should start; `None` starts it as soon as nothing is running
+ */
+run_at: string | null, created_at: string, updated_at: string, version: bigint, };
 
 export type DraftType = "follow_up" | "retry";
 
+export type AttemptManualChanges = { task_attempt_id: string, task_id: string, changed_paths: Array<string>, detected_at: Date, updated_at: Date, };
+
+export type AttemptVerification = { id: string, task_attempt_id: string, execution_process_id: string, passed: boolean, exit_code: bigint | null, created_at: Date, };
+
 export type CommandExitStatus = { "type": "exit_code", code: number, } | { "type": "success", success: boolean, };
 
 export type CommandRunResult = { exit_status: CommandExitStatus | null, output: string | null, };
@@ -348,7 +1782,7 @@ export type ToolResultValueType = { "type": "markdown" } | { "type": "json" };
 
 export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" };
 
-export type PatchType = { "type": "NORMALIZED_ENTRY", "content": NormalizedEntry } | { "type": "STDOUT", "content": string } | { "type": "STDERR", "content": string } | { "type": "DIFF", "content": Diff };
+export type PatchType = { "type": "NORMALIZED_ENTRY", "content": NormalizedEntry } | { "type": "STDOUT", "content": string } | { "type": "STDERR", "content": string } | { "type": "DIFF", "content": Diff } | { "type": "RESOURCE_USAGE", "content": ResourceUsage };
 
 export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
 
@@ -356,4 +1790,6 @@ export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue,
 
 export type ApprovalResponse = { execution_process_id: string, status: ApprovalStatus, };
 
+export type ApprovalPendingInfo = { approval_id: string, execution_process_id: string, tool_name: string, requested_at: string, timeout_at: string, };
+
 export type JsonValue = number | string | boolean | Array<JsonValue> | { [key in string]?: JsonValue } | null;
\ No newline at end of file